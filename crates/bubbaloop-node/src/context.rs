@@ -35,6 +35,9 @@ pub struct NodeContext {
     /// Per-topic input liveness — mirror of [`outputs`](Self::outputs) for
     /// subscribers.
     pub(crate) inputs: Arc<Mutex<BTreeMap<String, Liveness>>>,
+    /// Statuses reported via [`report_diagnostic`](Self::report_diagnostic),
+    /// published alongside the SDK's own status on `diagnostics`.
+    pub(crate) diagnostics: crate::diagnostics::Reported,
 }
 
 /// Strip the `bubbaloop/{global|local}/{machine_id}/` prefix from a fully
@@ -115,6 +118,20 @@ impl NodeContext {
         Some(sfx)
     }
 
+    /// Report the state of one component of this node. Replaces any earlier
+    /// status with the same `name`; it is republished on every diagnostics
+    /// tick until cleared.
+    pub fn report_diagnostic(&self, status: crate::diagnostics::DiagnosticStatus) {
+        let mut guard = self.diagnostics.lock().expect("diagnostics mutex poisoned");
+        guard.insert(status.name.clone(), status);
+    }
+
+    /// Stop reporting the status called `name`.
+    pub fn clear_diagnostic(&self, name: &str) {
+        let mut guard = self.diagnostics.lock().expect("diagnostics mutex poisoned");
+        guard.remove(name);
+    }
+
     /// Snapshot outputs as an ordered list of wire entries.
     pub fn outputs_snapshot(&self) -> Vec<IoEntry> {
         crate::manifest::snapshot_entries(&self.outputs.lock().expect("outputs mutex poisoned"))
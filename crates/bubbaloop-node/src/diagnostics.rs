@@ -0,0 +1,267 @@
+//! Node self-diagnostics, mirroring ROS `diagnostic_msgs`.
+//!
+//! Every node publishes a [`DiagnosticArray`] every 5 seconds on
+//! `bubbaloop/global/{machine_id}/{instance_name}/diagnostics`, as CBOR in
+//! the usual envelope with schema URI [`DIAGNOSTICS_SCHEMA_URI`]. The body
+//! carries the fields of `bubbaloop.diagnostics.v1.DiagnosticArray`; the
+//! envelope header stands in for the protobuf `Header`.
+//!
+//! The SDK always reports a `"{instance_name}: sdk"` status with uptime and
+//! publisher/subscriber counts. Nodes add their own components with
+//! [`NodeContext::report_diagnostic`](crate::NodeContext::report_diagnostic).
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+
+use crate::error::Result;
+use crate::manifest::Liveness;
+use crate::NodeContext;
+
+/// Schema URI stamped on every diagnostics envelope.
+pub const DIAGNOSTICS_SCHEMA_URI: &str = "bubbaloop.diagnostics.v1.DiagnosticArray";
+
+/// How often the diagnostics array is published.
+pub const DIAGNOSTICS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Severity of a [`DiagnosticStatus`]. Serialized as its ROS numeric value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(into = "u8", try_from = "u8")]
+pub enum DiagnosticLevel {
+    Ok = 0,
+    Warn = 1,
+    Error = 2,
+    Stale = 3,
+}
+
+impl From<DiagnosticLevel> for u8 {
+    fn from(level: DiagnosticLevel) -> u8 {
+        level as u8
+    }
+}
+
+impl TryFrom<u8> for DiagnosticLevel {
+    type Error = String;
+
+    fn try_from(value: u8) -> std::result::Result<Self, String> {
+        match value {
+            0 => Ok(DiagnosticLevel::Ok),
+            1 => Ok(DiagnosticLevel::Warn),
+            2 => Ok(DiagnosticLevel::Error),
+            3 => Ok(DiagnosticLevel::Stale),
+            other => Err(format!("unknown diagnostic level {}", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: String,
+}
+
+/// State of one component of a node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiagnosticStatus {
+    pub level: DiagnosticLevel,
+    pub name: String,
+    pub message: String,
+    #[serde(default)]
+    pub hardware_id: String,
+    #[serde(default)]
+    pub values: Vec<KeyValue>,
+}
+
+impl DiagnosticStatus {
+    pub fn new(
+        level: DiagnosticLevel,
+        name: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            level,
+            name: name.into(),
+            message: message.into(),
+            hardware_id: String::new(),
+            values: Vec::new(),
+        }
+    }
+
+    pub fn with_hardware_id(mut self, hardware_id: impl Into<String>) -> Self {
+        self.hardware_id = hardware_id.into();
+        self
+    }
+
+    pub fn with_value(mut self, key: impl Into<String>, value: impl ToString) -> Self {
+        self.values.push(KeyValue {
+            key: key.into(),
+            value: value.to_string(),
+        });
+        self
+    }
+}
+
+/// All statuses a node reports at one instant.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiagnosticArray {
+    pub status: Vec<DiagnosticStatus>,
+}
+
+impl DiagnosticArray {
+    /// The most severe level across all statuses; `Ok` when empty.
+    pub fn worst_level(&self) -> DiagnosticLevel {
+        self.status
+            .iter()
+            .map(|s| s.level)
+            .max()
+            .unwrap_or(DiagnosticLevel::Ok)
+    }
+}
+
+/// Statuses reported by the node, keyed by name.
+pub(crate) type Reported = Arc<Mutex<BTreeMap<String, DiagnosticStatus>>>;
+
+/// The status the SDK reports on behalf of every node.
+pub(crate) fn sdk_status(
+    instance_name: &str,
+    uptime: Duration,
+    outputs: &BTreeMap<String, Liveness>,
+    inputs: &BTreeMap<String, Liveness>,
+) -> DiagnosticStatus {
+    let live = |m: &BTreeMap<String, Liveness>| m.values().filter(|l| l.still_live).count();
+    let idle = |m: &BTreeMap<String, Liveness>| {
+        m.values().filter(|l| l.still_live && !l.ever_fired).count()
+    };
+    DiagnosticStatus::new(
+        DiagnosticLevel::Ok,
+        format!("{}: sdk", instance_name),
+        "running",
+    )
+    .with_value("uptime_secs", uptime.as_secs())
+    .with_value("outputs", live(outputs))
+    .with_value("outputs_idle", idle(outputs))
+    .with_value("inputs", live(inputs))
+    .with_value("inputs_idle", idle(inputs))
+}
+
+/// SDK status first, then node-reported statuses in name order.
+pub(crate) fn collect(
+    sdk: DiagnosticStatus,
+    reported: &BTreeMap<String, DiagnosticStatus>,
+) -> DiagnosticArray {
+    let mut status = Vec::with_capacity(reported.len() + 1);
+    status.push(sdk);
+    status.extend(reported.values().cloned());
+    DiagnosticArray { status }
+}
+
+/// Spawn a background task that publishes the node's diagnostics every
+/// [`DIAGNOSTICS_INTERVAL`]. Stops when the shutdown signal fires.
+pub(crate) async fn spawn_diagnostics(
+    ctx: &NodeContext,
+    mut shutdown_rx: watch::Receiver<()>,
+) -> Result<tokio::task::JoinHandle<()>> {
+    let publisher = ctx
+        .publisher_cbor_with_schema("diagnostics", Some(DIAGNOSTICS_SCHEMA_URI), 1)
+        .await?;
+    log::info!("Diagnostics: {}", ctx.topic("diagnostics"));
+
+    let instance_name = ctx.instance_name.clone();
+    let outputs = ctx.outputs.clone();
+    let inputs = ctx.inputs.clone();
+    let reported = ctx.diagnostics.clone();
+    let started = std::time::Instant::now();
+
+    let handle = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DIAGNOSTICS_INTERVAL);
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown_rx.changed() => {
+                    log::debug!("Diagnostics stopping");
+                    break;
+                }
+                _ = interval.tick() => {
+                    let sdk = sdk_status(
+                        &instance_name,
+                        started.elapsed(),
+                        &outputs.lock().expect("outputs mutex poisoned"),
+                        &inputs.lock().expect("inputs mutex poisoned"),
+                    );
+                    let array = collect(sdk, &reported.lock().expect("diagnostics mutex poisoned"));
+                    if let Err(e) = publisher.put(&array).await {
+                        log::warn!("Diagnostics publish failed: {}", e);
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_serializes_as_ros_number() {
+        let mut buf = Vec::new();
+        ciborium::into_writer(&DiagnosticLevel::Error, &mut buf).unwrap();
+        let n: u8 = ciborium::from_reader(buf.as_slice()).unwrap();
+        assert_eq!(n, 2);
+        assert!(DiagnosticLevel::try_from(4).is_err());
+    }
+
+    #[test]
+    fn sdk_status_counts_live_and_idle_topics() {
+        let mut outputs = BTreeMap::new();
+        let mut fired = Liveness::new(0);
+        fired.ever_fired = true;
+        outputs.insert("cam/raw".to_string(), fired);
+        outputs.insert("cam/diagnostics".to_string(), Liveness::new(0));
+        let mut gone = Liveness::new(0);
+        gone.still_live = false;
+        let inputs = BTreeMap::from([("other/raw".to_string(), gone)]);
+
+        let status = sdk_status("cam", Duration::from_secs(42), &outputs, &inputs);
+        assert_eq!(status.name, "cam: sdk");
+        let get = |k: &str| {
+            status
+                .values
+                .iter()
+                .find(|kv| kv.key == k)
+                .map(|kv| kv.value.as_str())
+        };
+        assert_eq!(get("uptime_secs"), Some("42"));
+        assert_eq!(get("outputs"), Some("2"));
+        assert_eq!(get("outputs_idle"), Some("1"));
+        assert_eq!(get("inputs"), Some("0"));
+    }
+
+    #[test]
+    fn collect_puts_sdk_first_and_reports_worst_level() {
+        let sdk = DiagnosticStatus::new(DiagnosticLevel::Ok, "cam: sdk", "running");
+        let reported = BTreeMap::from([
+            (
+                "cam: rtsp".to_string(),
+                DiagnosticStatus::new(DiagnosticLevel::Error, "cam: rtsp", "refused"),
+            ),
+            (
+                "cam: encoder".to_string(),
+                DiagnosticStatus::new(DiagnosticLevel::Warn, "cam: encoder", "dropping"),
+            ),
+        ]);
+        let array = collect(sdk, &reported);
+        let names: Vec<_> = array.status.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["cam: sdk", "cam: encoder", "cam: rtsp"]);
+        assert_eq!(array.worst_level(), DiagnosticLevel::Error);
+        assert_eq!(
+            DiagnosticArray::default().worst_level(),
+            DiagnosticLevel::Ok
+        );
+    }
+}
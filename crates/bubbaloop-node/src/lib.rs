@@ -1,7 +1,7 @@
 //! Batteries-included SDK for writing bubbaloop nodes.
 //!
-//! Extracts scaffolding (Zenoh session, health heartbeat, config loading,
-//! signal handling, shutdown) into a single crate. Node authors implement
+//! Extracts scaffolding (Zenoh session, health heartbeat, diagnostics, config
+//! loading, signal handling, shutdown) into a single crate. Node authors implement
 //! the `Node` trait and call `run_node::<MyNode>().await`.
 //!
 //! # Example
@@ -27,6 +27,7 @@
 
 mod config;
 mod context;
+pub mod diagnostics;
 pub mod discover;
 pub mod envelope;
 pub mod error;
@@ -39,6 +40,7 @@ pub mod subscriber;
 mod zenoh_session;
 
 pub use context::NodeContext;
+pub use diagnostics::{DiagnosticArray, DiagnosticLevel, DiagnosticStatus, KeyValue};
 pub use discover::{discover_nodes, NodeInfo};
 pub use envelope::{Envelope, Header};
 pub use error::NodeError;
@@ -163,8 +165,11 @@ pub async fn run_node<N: Node>() -> anyhow::Result<()> {
         shutdown_rx: shutdown_tx.subscribe(),
         outputs,
         inputs,
+        diagnostics: Default::default(),
     };
 
+    let _diagnostics_handle = diagnostics::spawn_diagnostics(&ctx, shutdown_tx.subscribe()).await?;
+
     let node = N::init(&ctx, &node_config).await?;
     log::info!("{} node initialized", N::name());
 
@@ -1,4 +1,11 @@
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+use prost::Message;
+use prost_types::field_descriptor_proto::{Label, Type};
+use prost_types::{DescriptorProto, EnumDescriptorProto, FieldDescriptorProto, FileDescriptorSet};
+use serde_json::{json, Map, Value};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let out_dir = PathBuf::from(std::env::var("OUT_DIR")?);
@@ -22,6 +29,12 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     config.file_descriptor_set_path(out_dir.join("descriptor.bin"));
     config.compile_protos(&proto_files, &[protos_dir.to_string_lossy().as_ref()])?;
 
+    // Only the `json-schema` feature includes `json_schemas.rs`.
+    if std::env::var_os("CARGO_FEATURE_JSON_SCHEMA").is_some() {
+        let descriptor_bytes = std::fs::read(out_dir.join("descriptor.bin"))?;
+        write_json_schemas(&out_dir, &descriptor_bytes)?;
+    }
+
     for proto_file in &proto_files {
         println!("cargo:rerun-if-changed={}", proto_file);
     }
@@ -29,3 +42,284 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// A message or enum from the descriptor set, with its doc comments.
+enum TypeDef<'a> {
+    /// The message, its comment, and one comment per field.
+    Message(&'a DescriptorProto, Option<String>, Vec<Option<String>>),
+    Enum(&'a EnumDescriptorProto, Option<String>),
+}
+
+/// Index every message and enum by fully qualified name (no leading dot).
+fn index_types(set: &FileDescriptorSet) -> BTreeMap<String, TypeDef<'_>> {
+    fn add_message<'a>(
+        index: &mut BTreeMap<String, TypeDef<'a>>,
+        comments: &HashMap<Vec<i32>, String>,
+        scope: &str,
+        path: Vec<i32>,
+        message: &'a DescriptorProto,
+    ) {
+        let name = format!("{}.{}", scope, message.name());
+        for (i, nested) in message.nested_type.iter().enumerate() {
+            let nested_path = [path.as_slice(), &[3, i as i32]].concat();
+            add_message(index, comments, &name, nested_path, nested);
+        }
+        for (i, e) in message.enum_type.iter().enumerate() {
+            let enum_path = [path.as_slice(), &[4, i as i32]].concat();
+            let doc = comments.get(&enum_path).cloned();
+            index.insert(format!("{}.{}", name, e.name()), TypeDef::Enum(e, doc));
+        }
+        let field_docs = (0..message.field.len())
+            .map(|i| {
+                let field_path = [path.as_slice(), &[2, i as i32]].concat();
+                comments.get(&field_path).cloned()
+            })
+            .collect();
+        index.insert(
+            name,
+            TypeDef::Message(message, comments.get(&path).cloned(), field_docs),
+        );
+    }
+
+    let mut index = BTreeMap::new();
+    for file in &set.file {
+        let comments = file_comments(file);
+        for (i, message) in file.message_type.iter().enumerate() {
+            add_message(
+                &mut index,
+                &comments,
+                file.package(),
+                vec![4, i as i32],
+                message,
+            );
+        }
+        for (i, e) in file.enum_type.iter().enumerate() {
+            let doc = comments.get(&vec![5, i as i32]).cloned();
+            index.insert(
+                format!("{}.{}", file.package(), e.name()),
+                TypeDef::Enum(e, doc),
+            );
+        }
+    }
+    index
+}
+
+/// Leading or trailing comments keyed by source path.
+fn file_comments(file: &prost_types::FileDescriptorProto) -> HashMap<Vec<i32>, String> {
+    let mut comments = HashMap::new();
+    for location in file.source_code_info.iter().flat_map(|s| &s.location) {
+        let text = location
+            .leading_comments
+            .as_deref()
+            .or(location.trailing_comments.as_deref())
+            .map(|c| c.split_whitespace().collect::<Vec<_>>().join(" "));
+        if let Some(text) = text.filter(|t| !t.is_empty()) {
+            comments.insert(location.path.clone(), text);
+        }
+    }
+    comments
+}
+
+/// Emit a JSON Schema (draft 2020-12) for every message into
+/// `OUT_DIR/json_schema/{type_name}.json`, plus `json_schemas.rs` with a
+/// `(type_name, schema)` table sorted by type name.
+///
+/// The schemas describe the serde JSON form of the generated types: field
+/// names as in the proto, enums as their numeric value, bytes as an array
+/// of integers, unset sub-messages as `null`, and floats as `null` when
+/// they are NaN or infinite (which is how serde_json writes them).
+fn write_json_schemas(out_dir: &Path, descriptor_bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+    let set = FileDescriptorSet::decode(descriptor_bytes)?;
+    let index = index_types(&set);
+    let schema_dir = out_dir.join("json_schema");
+    std::fs::create_dir_all(&schema_dir)?;
+
+    let mut table = String::from(
+        "/// `(type_name, JSON Schema)` for every message, sorted by type name.\n\
+         pub const JSON_SCHEMAS: &[(&str, &str)] = &[\n",
+    );
+    for (name, def) in &index {
+        let TypeDef::Message(message, ..) = def else {
+            continue;
+        };
+        if is_map_entry(message) {
+            continue;
+        }
+        let mut defs = BTreeMap::new();
+        let mut schema = message_schema(name, &index, name, &mut defs)?;
+        let object = schema.as_object_mut().expect("message schema is an object");
+        object.insert(
+            "$schema".into(),
+            json!("https://json-schema.org/draft/2020-12/schema"),
+        );
+        if !defs.is_empty() {
+            object.insert("$defs".into(), Value::Object(defs.into_iter().collect()));
+        }
+
+        let path = schema_dir.join(format!("{}.json", name));
+        std::fs::write(&path, serde_json::to_string_pretty(&schema)?)?;
+        table.push_str(&format!(
+            "    ({:?}, include_str!({:?})),\n",
+            name,
+            path.to_string_lossy()
+        ));
+    }
+    table.push_str("];\n");
+    std::fs::write(out_dir.join("json_schemas.rs"), table)?;
+    Ok(())
+}
+
+fn is_map_entry(message: &DescriptorProto) -> bool {
+    message.options.as_ref().is_some_and(|o| o.map_entry())
+}
+
+fn message_schema(
+    name: &str,
+    index: &BTreeMap<String, TypeDef<'_>>,
+    root: &str,
+    defs: &mut BTreeMap<String, Value>,
+) -> Result<Value, Box<dyn Error>> {
+    let Some(TypeDef::Message(message, doc, field_docs)) = index.get(name) else {
+        return Err(format!("unknown message type {}", name).into());
+    };
+
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for (field, field_doc) in message.field.iter().zip(field_docs) {
+        if field.oneof_index.is_some() && !field.proto3_optional() {
+            return Err(format!(
+                "{}.{}: oneof fields are not supported in JSON Schemas",
+                name,
+                field.name()
+            )
+            .into());
+        }
+        let nullable = field.proto3_optional()
+            || (field.r#type() == Type::Message && field.label() != Label::Repeated);
+        if !nullable {
+            required.push(json!(field.name()));
+        }
+        properties.insert(
+            field.name().to_string(),
+            field_schema(field, nullable, field_doc.as_deref(), index, root, defs)?,
+        );
+    }
+
+    let mut schema = Map::new();
+    schema.insert("title".into(), json!(name));
+    if let Some(doc) = doc {
+        schema.insert("description".into(), json!(doc));
+    }
+    schema.insert("type".into(), json!("object"));
+    schema.insert("properties".into(), Value::Object(properties));
+    schema.insert("required".into(), Value::Array(required));
+    Ok(Value::Object(schema))
+}
+
+fn field_schema(
+    field: &FieldDescriptorProto,
+    nullable: bool,
+    doc: Option<&str>,
+    index: &BTreeMap<String, TypeDef<'_>>,
+    root: &str,
+    defs: &mut BTreeMap<String, Value>,
+) -> Result<Value, Box<dyn Error>> {
+    if field.label() == Label::Repeated && field.r#type() == Type::Message {
+        let entry_name = field.type_name().trim_start_matches('.');
+        if let Some(TypeDef::Message(entry, ..)) = index.get(entry_name) {
+            if is_map_entry(entry) {
+                let value = entry
+                    .field
+                    .iter()
+                    .find(|f| f.number() == 2)
+                    .ok_or_else(|| format!("map entry {} has no value field", entry_name))?;
+                let mut map = json!({
+                    "type": "object",
+                    "additionalProperties": field_schema(value, false, None, index, root, defs)?,
+                });
+                if let Some(doc) = doc {
+                    map["description"] = json!(doc);
+                }
+                return Ok(map);
+            }
+        }
+    }
+
+    let value = match field.r#type() {
+        Type::Double | Type::Float => json!({ "type": ["number", "null"] }),
+        Type::Int32 | Type::Sint32 | Type::Sfixed32 => {
+            json!({ "type": "integer", "minimum": i32::MIN, "maximum": i32::MAX })
+        }
+        Type::Uint32 | Type::Fixed32 => {
+            json!({ "type": "integer", "minimum": 0, "maximum": u32::MAX })
+        }
+        Type::Int64 | Type::Sint64 | Type::Sfixed64 => json!({ "type": "integer" }),
+        Type::Uint64 | Type::Fixed64 => json!({ "type": "integer", "minimum": 0 }),
+        Type::Bool => json!({ "type": "boolean" }),
+        Type::String => json!({ "type": "string" }),
+        Type::Bytes => json!({
+            "type": "array",
+            "items": { "type": "integer", "minimum": 0, "maximum": 255 },
+        }),
+        Type::Enum | Type::Message => {
+            type_ref(field.type_name().trim_start_matches('.'), index, root, defs)?
+        }
+        Type::Group => {
+            return Err(format!("{}: groups are not supported", field.name()).into());
+        }
+    };
+
+    let mut value = if field.label() == Label::Repeated {
+        json!({ "type": "array", "items": value })
+    } else if nullable {
+        json!({ "anyOf": [value, { "type": "null" }] })
+    } else {
+        value
+    };
+    if let Some(doc) = doc {
+        value["description"] = json!(doc);
+    }
+    Ok(value)
+}
+
+/// `$ref` to `name`, adding its definition to `defs` on first use.
+fn type_ref(
+    name: &str,
+    index: &BTreeMap<String, TypeDef<'_>>,
+    root: &str,
+    defs: &mut BTreeMap<String, Value>,
+) -> Result<Value, Box<dyn Error>> {
+    if name == root {
+        return Ok(json!({ "$ref": "#" }));
+    }
+    if !defs.contains_key(name) {
+        // Placeholder first so recursive messages terminate.
+        defs.insert(name.to_string(), Value::Null);
+        let schema = match index.get(name) {
+            Some(TypeDef::Message(..)) => message_schema(name, index, root, defs)?,
+            Some(TypeDef::Enum(e, doc)) => enum_schema(name, e, doc.as_deref()),
+            None => return Err(format!("unknown type {}", name).into()),
+        };
+        defs.insert(name.to_string(), schema);
+    }
+    Ok(json!({ "$ref": format!("#/$defs/{}", name) }))
+}
+
+fn enum_schema(name: &str, e: &EnumDescriptorProto, doc: Option<&str>) -> Value {
+    let values: Vec<_> = e.value.iter().map(|v| v.number()).collect();
+    let names: Vec<_> = e
+        .value
+        .iter()
+        .map(|v| format!("{} = {}", v.name(), v.number()))
+        .collect();
+    let description = match doc {
+        Some(doc) => format!("{} ({})", doc, names.join(", ")),
+        None => names.join(", "),
+    };
+    json!({
+        "title": name,
+        "description": description,
+        "type": "integer",
+        "enum": values,
+    })
+}
@@ -8,6 +8,8 @@
 //!
 //! - `descriptor`: Enables `get_descriptor_for_message` for MCAP schema registration
 //! - `config`: Enables `TopicsConfig` for YAML-based topic configuration
+//! - `json-schema`: Enables `json_schema` for validating JSON payloads
+//! - `ros2`: Enables the `ros2` module, converting to and from ROS 2 CDR
 
 macro_rules! proto_module {
     ($mod_name:ident, $file:literal) => {
@@ -22,19 +24,127 @@ macro_rules! proto_module {
 proto_module!(header, "bubbaloop.header.v1.rs");
 proto_module!(daemon, "bubbaloop.daemon.v1.rs");
 proto_module!(machine, "bubbaloop.machine.v1.rs");
+proto_module!(geometry, "bubbaloop.geometry.v1.rs");
+proto_module!(imu, "bubbaloop.imu.v1.rs");
+proto_module!(gps, "bubbaloop.gps.v1.rs");
+proto_module!(pointcloud, "bubbaloop.pointcloud.v1.rs");
+proto_module!(vision, "bubbaloop.vision.v1.rs");
+proto_module!(audio, "bubbaloop.audio.v1.rs");
+proto_module!(battery, "bubbaloop.battery.v1.rs");
+proto_module!(diagnostics, "bubbaloop.diagnostics.v1.rs");
+proto_module!(tf, "bubbaloop.tf.v1.rs");
+proto_module!(nav, "bubbaloop.nav.v1.rs");
 
 // Re-export commonly used types
+pub use audio::v1::{AudioChunk, AudioEncoding};
+pub use battery::v1::{BatteryState, BatteryStatus};
 pub use daemon::v1::{
     CommandResult, CommandType, HealthStatus, NodeCommand, NodeEvent, NodeList,
     NodeState as DaemonNodeState, NodeStatus,
 };
+pub use diagnostics::v1::{DiagnosticArray, DiagnosticLevel, DiagnosticStatus, KeyValue};
+pub use geometry::v1::{Pose, Quaternion, Vector3};
+pub use gps::v1::{CovarianceType as NavSatCovarianceType, FixStatus, NavSatFix};
 pub use header::v1::Header;
+pub use imu::v1::Imu;
 pub use machine::v1::{MachineHeartbeat, MachineInfo, MachineList};
+pub use nav::v1::OccupancyGrid;
+pub use pointcloud::v1::{NumericType, PackedElementField, PointCloud};
+pub use tf::v1::{TfMessage, TransformStamped};
+pub use vision::v1::{
+    BoundingBox2D, Detection2D, Detection2DArray, ImageReference, ObjectHypothesis,
+};
+
+impl NumericType {
+    /// Size of one value in bytes; 0 for `Unknown`.
+    pub fn size(self) -> usize {
+        match self {
+            Self::Unknown => 0,
+            Self::Uint8 | Self::Int8 => 1,
+            Self::Uint16 | Self::Int16 => 2,
+            Self::Uint32 | Self::Int32 | Self::Float32 => 4,
+            Self::Float64 => 8,
+        }
+    }
+}
+
+impl AudioChunk {
+    /// How long the chunk plays; zero when `sample_rate` is 0.
+    pub fn duration(&self) -> std::time::Duration {
+        match self.sample_rate {
+            0 => std::time::Duration::ZERO,
+            rate => std::time::Duration::from_secs_f64(self.frame_count as f64 / rate as f64),
+        }
+    }
+}
+
+impl DiagnosticArray {
+    /// The most severe level across all statuses; `Ok` when empty.
+    pub fn worst_level(&self) -> DiagnosticLevel {
+        self.status
+            .iter()
+            .map(|s| s.level())
+            .max_by_key(|l| *l as i32)
+            .unwrap_or(DiagnosticLevel::Ok)
+    }
+}
+
+impl BatteryState {
+    /// True when running on battery with less than `min_percent` charge left.
+    /// An unmeasured (NaN) charge never counts as low.
+    pub fn is_low(&self, min_percent: f32) -> bool {
+        self.status() == BatteryStatus::Discharging && self.charge_percent < min_percent
+    }
+}
+
+impl Detection2D {
+    /// The hypothesis with the highest score, if any.
+    pub fn best(&self) -> Option<&ObjectHypothesis> {
+        self.results
+            .iter()
+            .max_by(|a, b| a.score.total_cmp(&b.score))
+    }
+}
+
+impl TfMessage {
+    /// The transform whose child is `child_frame_id`, if present.
+    pub fn find(&self, child_frame_id: &str) -> Option<&TransformStamped> {
+        self.transforms
+            .iter()
+            .find(|t| t.child_frame_id == child_frame_id)
+    }
+}
+
+impl OccupancyGrid {
+    /// Occupancy of cell (`x`, `y`): -1 for unknown, otherwise 0..=100.
+    /// `None` when the cell is outside the grid or missing from `data`.
+    pub fn cell(&self, x: u32, y: u32) -> Option<i8> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let index = y as usize * self.width as usize + x as usize;
+        self.data.get(index).map(|&v| v as i8)
+    }
+}
+
+impl PointCloud {
+    /// Number of points in `data`; 0 when `point_stride` is 0.
+    pub fn point_count(&self) -> usize {
+        match self.point_stride as usize {
+            0 => 0,
+            stride => self.data.len() / stride,
+        }
+    }
+}
 
 // TopicsConfig (behind "config" feature)
 #[cfg(feature = "config")]
 pub mod config;
 
+// ROS 2 CDR conversion (behind "ros2" feature)
+#[cfg(feature = "ros2")]
+pub mod ros2;
+
 // Descriptor utilities (behind "descriptor" feature)
 #[cfg(feature = "descriptor")]
 mod descriptor_utils {
@@ -142,6 +252,33 @@ mod descriptor_utils {
 #[cfg(feature = "descriptor")]
 pub use descriptor_utils::{get_descriptor_for_message, MessageDescriptor, DESCRIPTOR};
 
+// JSON Schemas generated by build.rs (behind "json-schema" feature)
+#[cfg(feature = "json-schema")]
+mod json_schemas {
+    include!(concat!(env!("OUT_DIR"), "/json_schemas.rs"));
+}
+
+/// JSON Schema (draft 2020-12) for a message, by fully qualified type name
+/// (e.g. "bubbaloop.imu.v1.Imu").
+///
+/// The schema describes the serde JSON form of the generated type: enums
+/// as numbers, bytes as arrays of integers, unset sub-messages as `null`,
+/// and NaN floats as `null`. Returns `None` for unknown types.
+#[cfg(feature = "json-schema")]
+pub fn json_schema(type_name: &str) -> Option<&'static str> {
+    let schemas = json_schemas::JSON_SCHEMAS;
+    schemas
+        .binary_search_by(|(name, _)| (*name).cmp(type_name))
+        .ok()
+        .map(|i| schemas[i].1)
+}
+
+/// Type names that have a JSON Schema, in sorted order.
+#[cfg(feature = "json-schema")]
+pub fn json_schema_type_names() -> impl Iterator<Item = &'static str> {
+    json_schemas::JSON_SCHEMAS.iter().map(|(name, _)| *name)
+}
+
 /// Trait for protobuf types to provide their fully-qualified type name.
 /// Used for descriptor lookup and schema registration.
 pub trait MessageTypeName {
@@ -154,6 +291,60 @@ impl MessageTypeName for Header {
     }
 }
 
+impl MessageTypeName for Imu {
+    fn type_name() -> &'static str {
+        "bubbaloop.imu.v1.Imu"
+    }
+}
+
+impl MessageTypeName for NavSatFix {
+    fn type_name() -> &'static str {
+        "bubbaloop.gps.v1.NavSatFix"
+    }
+}
+
+impl MessageTypeName for PointCloud {
+    fn type_name() -> &'static str {
+        "bubbaloop.pointcloud.v1.PointCloud"
+    }
+}
+
+impl MessageTypeName for Detection2DArray {
+    fn type_name() -> &'static str {
+        "bubbaloop.vision.v1.Detection2DArray"
+    }
+}
+
+impl MessageTypeName for AudioChunk {
+    fn type_name() -> &'static str {
+        "bubbaloop.audio.v1.AudioChunk"
+    }
+}
+
+impl MessageTypeName for BatteryState {
+    fn type_name() -> &'static str {
+        "bubbaloop.battery.v1.BatteryState"
+    }
+}
+
+impl MessageTypeName for OccupancyGrid {
+    fn type_name() -> &'static str {
+        "bubbaloop.nav.v1.OccupancyGrid"
+    }
+}
+
+impl MessageTypeName for TfMessage {
+    fn type_name() -> &'static str {
+        "bubbaloop.tf.v1.TfMessage"
+    }
+}
+
+impl MessageTypeName for DiagnosticArray {
+    fn type_name() -> &'static str {
+        "bubbaloop.diagnostics.v1.DiagnosticArray"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -195,4 +386,311 @@ mod tests {
         assert_eq!(decoded.frame_id, "test");
     }
 
+    #[test]
+    fn test_imu_roundtrip() {
+        let imu = Imu {
+            header: Some(Header {
+                frame_id: "imu0".into(),
+                ..Default::default()
+            }),
+            orientation: Some(Quaternion {
+                w: 1.0,
+                ..Default::default()
+            }),
+            orientation_covariance: vec![-1.0],
+            angular_velocity: Some(Vector3 {
+                z: 0.5,
+                ..Default::default()
+            }),
+            linear_acceleration: Some(Vector3 {
+                z: 9.81,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let decoded = Imu::decode(imu.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, imu);
+        assert_eq!(decoded.header.unwrap().frame_id, "imu0");
+        assert_eq!(decoded.linear_acceleration.unwrap().z, 9.81);
+    }
+
+    #[test]
+    fn test_nav_sat_fix_roundtrip() {
+        let fix = NavSatFix {
+            status: FixStatus::GbasFix as i32,
+            services: 1 | 8,
+            latitude: 41.3874,
+            longitude: 2.1686,
+            altitude: 12.5,
+            position_covariance: vec![0.04, 0.0, 0.0, 0.0, 0.04, 0.0, 0.0, 0.0, 0.09],
+            position_covariance_type: NavSatCovarianceType::DiagonalKnown as i32,
+            ..Default::default()
+        };
+        let decoded = NavSatFix::decode(fix.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, fix);
+        assert_eq!(decoded.status(), FixStatus::GbasFix);
+        // A fix from a node that never set a status reads as unknown.
+        assert_eq!(NavSatFix::default().status(), FixStatus::Unknown);
+    }
+
+    #[test]
+    fn test_point_cloud_layout() {
+        let field = |name: &str, offset| PackedElementField {
+            name: name.into(),
+            offset,
+            r#type: NumericType::Float32 as i32,
+        };
+        let points: [[f32; 3]; 2] = [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]];
+        let cloud = PointCloud {
+            point_stride: 3 * NumericType::Float32.size() as u32,
+            fields: vec![field("x", 0), field("y", 4), field("z", 8)],
+            data: points
+                .iter()
+                .flatten()
+                .flat_map(|v| v.to_le_bytes())
+                .collect(),
+            ..Default::default()
+        };
+        let decoded = PointCloud::decode(cloud.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, cloud);
+        assert_eq!(decoded.point_count(), 2);
+        assert_eq!(decoded.fields[2].r#type(), NumericType::Float32);
+        let z = &decoded.data[12 + 8..12 + 12];
+        assert_eq!(f32::from_le_bytes(z.try_into().unwrap()), 6.0);
+        assert_eq!(PointCloud::default().point_count(), 0);
+    }
+
+    #[test]
+    fn test_detection_2d_array_roundtrip() {
+        let hypothesis = |class_id: &str, score| ObjectHypothesis {
+            class_id: class_id.into(),
+            score,
+        };
+        let detections = Detection2DArray {
+            source: Some(ImageReference {
+                topic: "bubbaloop/global/jetson1/camera/front/compressed".into(),
+                sequence: 7,
+                width: 1920,
+                height: 1080,
+                ..Default::default()
+            }),
+            detections: vec![Detection2D {
+                bbox: Some(BoundingBox2D {
+                    center_x: 960.0,
+                    center_y: 540.0,
+                    width: 120.0,
+                    height: 300.0,
+                }),
+                results: vec![hypothesis("dog", 0.2), hypothesis("person", 0.9)],
+                track_id: "7".into(),
+            }],
+            ..Default::default()
+        };
+        let decoded = Detection2DArray::decode(detections.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, detections);
+        assert_eq!(decoded.detections[0].best().unwrap().class_id, "person");
+        assert!(Detection2D::default().best().is_none());
+    }
+
+    #[test]
+    fn test_audio_chunk_roundtrip() {
+        let samples: Vec<i16> = vec![0; 480 * 2];
+        let chunk = AudioChunk {
+            sample_rate: 48_000,
+            channels: 2,
+            encoding: AudioEncoding::PcmS16le as i32,
+            frame_count: 480,
+            data: samples.iter().flat_map(|s| s.to_le_bytes()).collect(),
+            ..Default::default()
+        };
+        let decoded = AudioChunk::decode(chunk.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, chunk);
+        assert_eq!(decoded.encoding(), AudioEncoding::PcmS16le);
+        assert_eq!(decoded.duration(), std::time::Duration::from_millis(10));
+        assert_eq!(AudioChunk::default().duration(), std::time::Duration::ZERO);
+        assert_eq!(
+            AudioEncoding::from_str_name("AUDIO_ENCODING_PCMU"),
+            Some(AudioEncoding::Pcmu)
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_array_roundtrip() {
+        let array = DiagnosticArray {
+            header: None,
+            status: vec![
+                DiagnosticStatus {
+                    level: DiagnosticLevel::Ok as i32,
+                    name: "cam: sdk".into(),
+                    message: "running".into(),
+                    values: vec![KeyValue {
+                        key: "uptime_secs".into(),
+                        value: "12".into(),
+                    }],
+                    ..Default::default()
+                },
+                DiagnosticStatus {
+                    level: DiagnosticLevel::Error as i32,
+                    name: "cam: rtsp".into(),
+                    message: "connection refused".into(),
+                    ..Default::default()
+                },
+            ],
+        };
+        let decoded = DiagnosticArray::decode(array.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, array);
+        assert_eq!(decoded.worst_level(), DiagnosticLevel::Error);
+        assert_eq!(
+            DiagnosticArray::default().worst_level(),
+            DiagnosticLevel::Ok
+        );
+    }
+
+    #[test]
+    fn test_battery_state_roundtrip() {
+        let ups = BatteryState {
+            voltage: 12.1,
+            current: -2.5,
+            charge_percent: 18.0,
+            status: BatteryStatus::Discharging as i32,
+            temperature: f32::NAN,
+            ..Default::default()
+        };
+        let decoded = BatteryState::decode(ups.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded.status(), BatteryStatus::Discharging);
+        assert_eq!(decoded.charge_percent, 18.0);
+        assert!(decoded.temperature.is_nan());
+        assert!(decoded.is_low(20.0));
+        assert!(!decoded.is_low(10.0));
+
+        let charging = BatteryState {
+            status: BatteryStatus::Charging as i32,
+            ..ups.clone()
+        };
+        assert!(!charging.is_low(20.0));
+        let unmeasured = BatteryState {
+            charge_percent: f32::NAN,
+            ..ups
+        };
+        assert!(!unmeasured.is_low(20.0));
+    }
+
+    #[test]
+    fn test_occupancy_grid_roundtrip() {
+        let grid = OccupancyGrid {
+            resolution: 0.05,
+            width: 3,
+            height: 2,
+            origin: Some(Pose {
+                position: Some(Vector3 {
+                    x: -1.0,
+                    y: -0.5,
+                    z: 0.0,
+                }),
+                orientation: None,
+            }),
+            data: [0i8, 100, -1, 50, 0, 0].iter().map(|&v| v as u8).collect(),
+            ..Default::default()
+        };
+        let decoded = OccupancyGrid::decode(grid.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, grid);
+        assert_eq!(decoded.cell(1, 0), Some(100));
+        assert_eq!(decoded.cell(2, 0), Some(-1));
+        assert_eq!(decoded.cell(0, 1), Some(50));
+        assert_eq!(decoded.cell(3, 0), None);
+        assert_eq!(decoded.cell(0, 2), None);
+    }
+
+    #[test]
+    fn test_tf_message_roundtrip() {
+        let mount = TransformStamped {
+            parent_frame_id: "base_link".into(),
+            child_frame_id: "cam_front".into(),
+            translation: Some(Vector3 {
+                x: 0.2,
+                y: 0.0,
+                z: 0.5,
+            }),
+            rotation: Some(Quaternion {
+                w: 1.0,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let msg = TfMessage {
+            transforms: vec![mount.clone()],
+        };
+        let decoded = TfMessage::decode(msg.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, msg);
+        assert_eq!(decoded.find("cam_front"), Some(&mount));
+        assert_eq!(decoded.find("base_link"), None);
+    }
+
+    #[cfg(feature = "json-schema")]
+    #[test]
+    fn test_json_schema_matches_serde_form() {
+        let schema: serde_json::Value =
+            serde_json::from_str(json_schema(Imu::type_name()).unwrap()).unwrap();
+        assert_eq!(schema["title"], "bubbaloop.imu.v1.Imu");
+        assert_eq!(
+            schema["properties"]["orientation"]["anyOf"][0]["$ref"],
+            "#/$defs/bubbaloop.geometry.v1.Quaternion"
+        );
+        assert!(schema["$defs"]["bubbaloop.header.v1.Header"].is_object());
+
+        // Every field serde writes is described, and every required one is written.
+        let value = serde_json::to_value(Imu::default()).unwrap();
+        let written: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+        let described: Vec<_> = schema["properties"]
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        assert_eq!(written, described);
+        for field in schema["required"].as_array().unwrap() {
+            assert!(!value[field.as_str().unwrap()].is_null());
+        }
+
+        let battery: serde_json::Value =
+            serde_json::from_str(json_schema(BatteryState::type_name()).unwrap()).unwrap();
+        assert_eq!(
+            battery["$defs"]["bubbaloop.battery.v1.BatteryStatus"]["enum"],
+            serde_json::json!([0, 1, 2, 3, 4])
+        );
+        assert_eq!(
+            battery["properties"]["voltage"]["type"],
+            serde_json::json!(["number", "null"])
+        );
+    }
+
+    #[cfg(feature = "json-schema")]
+    #[test]
+    fn test_json_schema_covers_every_message_type() {
+        let names: Vec<_> = json_schema_type_names().collect();
+        assert!(names.windows(2).all(|w| w[0] < w[1]));
+        for name in [
+            Header::type_name(),
+            AudioChunk::type_name(),
+            DiagnosticArray::type_name(),
+            OccupancyGrid::type_name(),
+            TfMessage::type_name(),
+            "bubbaloop.daemon.v1.NodeList",
+        ] {
+            assert!(json_schema(name).is_some(), "{}", name);
+        }
+        assert_eq!(json_schema("bubbaloop.nope.v1.Missing"), None);
+    }
+
+    #[cfg(feature = "descriptor")]
+    #[test]
+    fn test_imu_descriptor_includes_dependencies() {
+        let descriptor = get_descriptor_for_message::<Imu>().unwrap();
+        assert_eq!(descriptor.schema_name, "bubbaloop.imu.v1.Imu");
+        let set =
+            prost_types::FileDescriptorSet::decode(descriptor.descriptor_bytes.as_slice()).unwrap();
+        let mut files: Vec<_> = set.file.iter().filter_map(|f| f.name.clone()).collect();
+        files.sort();
+        assert_eq!(files, ["geometry.proto", "header.proto", "imu.proto"]);
+    }
 }
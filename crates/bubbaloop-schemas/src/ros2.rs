@@ -0,0 +1,858 @@
+//! Conversion between bubbaloop messages and ROS 2 CDR.
+//!
+//! zenoh-bridge-ros2dds maps a ROS 2 topic `/imu` to the Zenoh key `imu`
+//! and forwards samples as CDR (XCDR1, with the 4-byte encapsulation
+//! header), which is what [`Ros2Message::to_cdr`] produces and
+//! [`Ros2Message::from_cdr`] accepts. A node can republish its data on such
+//! a key so that ROS 2 tools (`ros2 topic echo`, RViz, Foxglove) read it
+//! directly, or subscribe to a bridged ROS 2 topic and get bubbaloop types.
+//!
+//! | bubbaloop | ROS 2 |
+//! |-----------|-------|
+//! | [`Header`] | `std_msgs/msg/Header` |
+//! | [`Imu`] | `sensor_msgs/msg/Imu` |
+//! | [`NavSatFix`] | `sensor_msgs/msg/NavSatFix` |
+//! | [`BatteryState`] | `sensor_msgs/msg/BatteryState` |
+//! | [`CompressedImage`] | `sensor_msgs/msg/CompressedImage` |
+//! | [`TransformStamped`] | `geometry_msgs/msg/TransformStamped` |
+//! | [`TfMessage`] | `tf2_msgs/msg/TFMessage` |
+//! | [`OccupancyGrid`] | `nav_msgs/msg/OccupancyGrid` |
+//! | [`DiagnosticArray`] | `diagnostic_msgs/msg/DiagnosticArray` |
+//!
+//! ROS headers only carry a stamp and a frame: `acq_time` becomes the
+//! stamp, and `pub_time`, `sequence` and `machine_id` are left at their
+//! defaults when reading. Fields ROS has and bubbaloop doesn't are written
+//! as "unknown" (NaN, 0) and dropped when reading.
+
+use crate::{
+    BatteryState, BatteryStatus, DiagnosticArray, DiagnosticLevel, DiagnosticStatus, FixStatus,
+    Header, Imu, KeyValue, NavSatCovarianceType, NavSatFix, OccupancyGrid, Pose, Quaternion,
+    TfMessage, TransformStamped, Vector3,
+};
+
+/// Encapsulation header for little-endian plain CDR.
+const CDR_LE: [u8; 4] = [0x00, 0x01, 0x00, 0x00];
+
+/// Errors decoding a CDR payload
+#[derive(Debug, thiserror::Error)]
+pub enum CdrError {
+    #[error("Unsupported CDR encapsulation: {0:#06x}")]
+    Encapsulation(u16),
+    #[error("Unexpected end of data at byte {0}")]
+    UnexpectedEnd(usize),
+    #[error("Invalid string at byte {0}")]
+    InvalidString(usize),
+}
+
+/// A message with a ROS 2 counterpart.
+pub trait Ros2Message: Sized {
+    /// ROS 2 type name, e.g. "sensor_msgs/msg/Imu".
+    const ROS_TYPE: &'static str;
+
+    fn write_cdr(&self, w: &mut CdrWriter);
+
+    fn read_cdr(r: &mut CdrReader<'_>) -> Result<Self, CdrError>;
+
+    /// Serialize as a complete CDR payload, encapsulation header included.
+    fn to_cdr(&self) -> Vec<u8> {
+        let mut w = CdrWriter::new();
+        self.write_cdr(&mut w);
+        w.finish()
+    }
+
+    /// Deserialize a complete CDR payload, encapsulation header included.
+    fn from_cdr(bytes: &[u8]) -> Result<Self, CdrError> {
+        Self::read_cdr(&mut CdrReader::new(bytes)?)
+    }
+}
+
+/// Writes little-endian plain CDR. Values are aligned to their size,
+/// counted from the end of the encapsulation header.
+#[derive(Debug)]
+pub struct CdrWriter {
+    buf: Vec<u8>,
+}
+
+impl Default for CdrWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CdrWriter {
+    pub fn new() -> Self {
+        Self {
+            buf: CDR_LE.to_vec(),
+        }
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.buf
+    }
+
+    fn align(&mut self, size: usize) {
+        while !(self.buf.len() - CDR_LE.len()).is_multiple_of(size) {
+            self.buf.push(0);
+        }
+    }
+
+    pub fn write_u8(&mut self, v: u8) {
+        self.buf.push(v);
+    }
+
+    pub fn write_i8(&mut self, v: i8) {
+        self.buf.push(v as u8);
+    }
+
+    pub fn write_bool(&mut self, v: bool) {
+        self.buf.push(v as u8);
+    }
+
+    pub fn write_u16(&mut self, v: u16) {
+        self.align(2);
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, v: u32) {
+        self.align(4);
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    pub fn write_i32(&mut self, v: i32) {
+        self.align(4);
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    pub fn write_f32(&mut self, v: f32) {
+        self.align(4);
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    pub fn write_f64(&mut self, v: f64) {
+        self.align(8);
+        self.buf.extend_from_slice(&v.to_le_bytes());
+    }
+
+    /// Length (including the NUL terminator), bytes, NUL.
+    pub fn write_string(&mut self, v: &str) {
+        self.write_u32(v.len() as u32 + 1);
+        self.buf.extend_from_slice(v.as_bytes());
+        self.buf.push(0);
+    }
+
+    /// Element count of a sequence; write the elements after it.
+    pub fn write_len(&mut self, len: usize) {
+        self.write_u32(len as u32);
+    }
+
+    /// A `uint8[]` or `int8[]` sequence.
+    pub fn write_bytes(&mut self, v: &[u8]) {
+        self.write_len(v.len());
+        self.buf.extend_from_slice(v);
+    }
+}
+
+/// Reads plain CDR in either byte order, as announced by the
+/// encapsulation header.
+#[derive(Debug)]
+pub struct CdrReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    little_endian: bool,
+}
+
+impl<'a> CdrReader<'a> {
+    pub fn new(buf: &'a [u8]) -> Result<Self, CdrError> {
+        let header = buf.get(..4).ok_or(CdrError::UnexpectedEnd(buf.len()))?;
+        let little_endian = match u16::from_be_bytes([header[0], header[1]]) {
+            0x0000 => false,
+            0x0001 => true,
+            other => return Err(CdrError::Encapsulation(other)),
+        };
+        Ok(Self {
+            buf,
+            pos: 4,
+            little_endian,
+        })
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], CdrError> {
+        while !(self.pos - 4).is_multiple_of(N) {
+            self.pos += 1;
+        }
+        let bytes = self
+            .buf
+            .get(self.pos..self.pos + N)
+            .ok_or(CdrError::UnexpectedEnd(self.pos))?;
+        self.pos += N;
+        let mut out = [0; N];
+        out.copy_from_slice(bytes);
+        if !self.little_endian {
+            out.reverse();
+        }
+        Ok(out)
+    }
+
+    fn slice(&mut self, len: usize) -> Result<&'a [u8], CdrError> {
+        let bytes = self
+            .buf
+            .get(self.pos..self.pos.saturating_add(len))
+            .ok_or(CdrError::UnexpectedEnd(self.pos))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, CdrError> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    pub fn read_i8(&mut self) -> Result<i8, CdrError> {
+        Ok(self.read_u8()? as i8)
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool, CdrError> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16, CdrError> {
+        self.take().map(u16::from_le_bytes)
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32, CdrError> {
+        self.take().map(u32::from_le_bytes)
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32, CdrError> {
+        self.take().map(i32::from_le_bytes)
+    }
+
+    pub fn read_f32(&mut self) -> Result<f32, CdrError> {
+        self.take().map(f32::from_le_bytes)
+    }
+
+    pub fn read_f64(&mut self) -> Result<f64, CdrError> {
+        self.take().map(f64::from_le_bytes)
+    }
+
+    pub fn read_string(&mut self) -> Result<String, CdrError> {
+        let len = self.read_u32()? as usize;
+        let start = self.pos;
+        let bytes = self.slice(len)?;
+        let text = bytes.strip_suffix(&[0]).unwrap_or(bytes);
+        String::from_utf8(text.to_vec()).map_err(|_| CdrError::InvalidString(start))
+    }
+
+    /// Element count of a sequence. Fails when the payload is too short to
+    /// hold that many elements of at least one byte each.
+    pub fn read_len(&mut self) -> Result<usize, CdrError> {
+        let len = self.read_u32()? as usize;
+        if len > self.buf.len() - self.pos {
+            return Err(CdrError::UnexpectedEnd(self.buf.len()));
+        }
+        Ok(len)
+    }
+
+    /// A `uint8[]` or `int8[]` sequence.
+    pub fn read_bytes(&mut self) -> Result<Vec<u8>, CdrError> {
+        let len = self.read_len()?;
+        Ok(self.slice(len)?.to_vec())
+    }
+}
+
+/// `sensor_msgs/msg/CompressedImage`. Camera nodes fill it from their
+/// `bubbaloop.camera.v1.CompressedImage` (defined in the camera node).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompressedImage {
+    pub header: Header,
+    /// Image format, e.g. "jpeg" or "h264".
+    pub format: String,
+    pub data: Vec<u8>,
+}
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// `builtin_interfaces/msg/Time` from nanoseconds since the epoch.
+fn write_time(w: &mut CdrWriter, ns: u64) {
+    w.write_i32((ns / NANOS_PER_SEC).min(i32::MAX as u64) as i32);
+    w.write_u32((ns % NANOS_PER_SEC) as u32);
+}
+
+fn read_time(r: &mut CdrReader<'_>) -> Result<u64, CdrError> {
+    let sec = r.read_i32()?.max(0) as u64;
+    let nanosec = r.read_u32()? as u64;
+    Ok(sec * NANOS_PER_SEC + nanosec)
+}
+
+/// `std_msgs/msg/Header` with `frame_id` in place of the header's own.
+fn write_header_with_frame(w: &mut CdrWriter, header: Option<&Header>, frame_id: &str) {
+    write_time(w, header.map_or(0, |h| h.acq_time));
+    w.write_string(frame_id);
+}
+
+fn write_header(w: &mut CdrWriter, header: Option<&Header>) {
+    write_header_with_frame(w, header, header.map_or("", |h| &h.frame_id));
+}
+
+fn read_header(r: &mut CdrReader<'_>) -> Result<Header, CdrError> {
+    Ok(Header {
+        acq_time: read_time(r)?,
+        frame_id: r.read_string()?,
+        ..Default::default()
+    })
+}
+
+fn write_vector3(w: &mut CdrWriter, v: Option<&Vector3>) {
+    let v = v.cloned().unwrap_or_default();
+    for c in [v.x, v.y, v.z] {
+        w.write_f64(c);
+    }
+}
+
+fn read_vector3(r: &mut CdrReader<'_>) -> Result<Vector3, CdrError> {
+    Ok(Vector3 {
+        x: r.read_f64()?,
+        y: r.read_f64()?,
+        z: r.read_f64()?,
+    })
+}
+
+/// An unset rotation is written as the identity.
+fn write_quaternion(w: &mut CdrWriter, q: Option<&Quaternion>) {
+    let q = q.cloned().unwrap_or(Quaternion {
+        w: 1.0,
+        ..Default::default()
+    });
+    for c in [q.x, q.y, q.z, q.w] {
+        w.write_f64(c);
+    }
+}
+
+fn read_quaternion(r: &mut CdrReader<'_>) -> Result<Quaternion, CdrError> {
+    Ok(Quaternion {
+        x: r.read_f64()?,
+        y: r.read_f64()?,
+        z: r.read_f64()?,
+        w: r.read_f64()?,
+    })
+}
+
+fn write_pose(w: &mut CdrWriter, pose: Option<&Pose>) {
+    write_vector3(w, pose.and_then(|p| p.position.as_ref()));
+    write_quaternion(w, pose.and_then(|p| p.orientation.as_ref()));
+}
+
+fn read_pose(r: &mut CdrReader<'_>) -> Result<Pose, CdrError> {
+    Ok(Pose {
+        position: Some(read_vector3(r)?),
+        orientation: Some(read_quaternion(r)?),
+    })
+}
+
+/// `float64[9]`. bubbaloop marks an unknown covariance with an empty list,
+/// ROS with all zeros.
+fn write_covariance(w: &mut CdrWriter, covariance: &[f64]) {
+    for i in 0..9 {
+        w.write_f64(if covariance.len() == 9 {
+            covariance[i]
+        } else {
+            0.0
+        });
+    }
+}
+
+fn read_covariance(r: &mut CdrReader<'_>) -> Result<Vec<f64>, CdrError> {
+    let covariance = (0..9)
+        .map(|_| r.read_f64())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(if covariance.iter().all(|&c| c == 0.0) {
+        Vec::new()
+    } else {
+        covariance
+    })
+}
+
+impl Ros2Message for Header {
+    const ROS_TYPE: &'static str = "std_msgs/msg/Header";
+
+    fn write_cdr(&self, w: &mut CdrWriter) {
+        write_header(w, Some(self));
+    }
+
+    fn read_cdr(r: &mut CdrReader<'_>) -> Result<Self, CdrError> {
+        read_header(r)
+    }
+}
+
+impl Ros2Message for Imu {
+    const ROS_TYPE: &'static str = "sensor_msgs/msg/Imu";
+
+    fn write_cdr(&self, w: &mut CdrWriter) {
+        write_header(w, self.header.as_ref());
+        write_quaternion(w, self.orientation.as_ref());
+        write_covariance(w, &self.orientation_covariance);
+        write_vector3(w, self.angular_velocity.as_ref());
+        write_covariance(w, &self.angular_velocity_covariance);
+        write_vector3(w, self.linear_acceleration.as_ref());
+        write_covariance(w, &self.linear_acceleration_covariance);
+    }
+
+    fn read_cdr(r: &mut CdrReader<'_>) -> Result<Self, CdrError> {
+        Ok(Imu {
+            header: Some(read_header(r)?),
+            orientation: Some(read_quaternion(r)?),
+            orientation_covariance: read_covariance(r)?,
+            angular_velocity: Some(read_vector3(r)?),
+            angular_velocity_covariance: read_covariance(r)?,
+            linear_acceleration: Some(read_vector3(r)?),
+            linear_acceleration_covariance: read_covariance(r)?,
+        })
+    }
+}
+
+impl Ros2Message for NavSatFix {
+    const ROS_TYPE: &'static str = "sensor_msgs/msg/NavSatFix";
+
+    fn write_cdr(&self, w: &mut CdrWriter) {
+        write_header(w, self.header.as_ref());
+        // sensor_msgs/msg/NavSatStatus
+        w.write_i8(match self.status() {
+            FixStatus::Unknown => -2,
+            FixStatus::NoFix => -1,
+            FixStatus::Fix => 0,
+            FixStatus::SbasFix => 1,
+            FixStatus::GbasFix => 2,
+        });
+        w.write_u16(self.services as u16);
+        w.write_f64(self.latitude);
+        w.write_f64(self.longitude);
+        w.write_f64(self.altitude);
+        write_covariance(w, &self.position_covariance);
+        w.write_u8(self.position_covariance_type() as u8);
+    }
+
+    fn read_cdr(r: &mut CdrReader<'_>) -> Result<Self, CdrError> {
+        let header = read_header(r)?;
+        let status = match r.read_i8()? {
+            -1 => FixStatus::NoFix,
+            0 => FixStatus::Fix,
+            1 => FixStatus::SbasFix,
+            2 => FixStatus::GbasFix,
+            _ => FixStatus::Unknown,
+        };
+        let mut fix = NavSatFix {
+            header: Some(header),
+            services: r.read_u16()? as u32,
+            latitude: r.read_f64()?,
+            longitude: r.read_f64()?,
+            altitude: r.read_f64()?,
+            position_covariance: read_covariance(r)?,
+            ..Default::default()
+        };
+        fix.set_status(status);
+        fix.set_position_covariance_type(
+            NavSatCovarianceType::try_from(r.read_u8()? as i32)
+                .unwrap_or(NavSatCovarianceType::Unknown),
+        );
+        Ok(fix)
+    }
+}
+
+impl Ros2Message for BatteryState {
+    const ROS_TYPE: &'static str = "sensor_msgs/msg/BatteryState";
+
+    fn write_cdr(&self, w: &mut CdrWriter) {
+        write_header(w, self.header.as_ref());
+        w.write_f32(self.voltage);
+        w.write_f32(self.temperature);
+        w.write_f32(self.current);
+        w.write_f32(f32::NAN); // charge (Ah)
+        w.write_f32(f32::NAN); // capacity (Ah)
+        w.write_f32(f32::NAN); // design_capacity (Ah)
+        w.write_f32(self.charge_percent / 100.0);
+        // The power_supply_status values match BatteryStatus.
+        w.write_u8(self.status() as u8);
+        w.write_u8(0); // power_supply_health: unknown
+        w.write_u8(0); // power_supply_technology: unknown
+        w.write_bool(true); // present
+        w.write_len(0); // cell_voltage
+        w.write_len(0); // cell_temperature
+        w.write_string(""); // location
+        w.write_string(""); // serial_number
+    }
+
+    fn read_cdr(r: &mut CdrReader<'_>) -> Result<Self, CdrError> {
+        let header = read_header(r)?;
+        let voltage = r.read_f32()?;
+        let temperature = r.read_f32()?;
+        let current = r.read_f32()?;
+        for _ in 0..3 {
+            r.read_f32()?;
+        }
+        let charge_percent = r.read_f32()? * 100.0;
+        let status = BatteryStatus::try_from(r.read_u8()? as i32).unwrap_or(BatteryStatus::Unknown);
+        let mut state = BatteryState {
+            header: Some(header),
+            voltage,
+            current,
+            charge_percent,
+            temperature,
+            ..Default::default()
+        };
+        state.set_status(status);
+        Ok(state)
+    }
+}
+
+impl Ros2Message for CompressedImage {
+    const ROS_TYPE: &'static str = "sensor_msgs/msg/CompressedImage";
+
+    fn write_cdr(&self, w: &mut CdrWriter) {
+        write_header(w, Some(&self.header));
+        w.write_string(&self.format);
+        w.write_bytes(&self.data);
+    }
+
+    fn read_cdr(r: &mut CdrReader<'_>) -> Result<Self, CdrError> {
+        Ok(CompressedImage {
+            header: read_header(r)?,
+            format: r.read_string()?,
+            data: r.read_bytes()?,
+        })
+    }
+}
+
+/// The ROS header's frame is the parent frame.
+impl Ros2Message for TransformStamped {
+    const ROS_TYPE: &'static str = "geometry_msgs/msg/TransformStamped";
+
+    fn write_cdr(&self, w: &mut CdrWriter) {
+        write_header_with_frame(w, self.header.as_ref(), &self.parent_frame_id);
+        w.write_string(&self.child_frame_id);
+        write_vector3(w, self.translation.as_ref());
+        write_quaternion(w, self.rotation.as_ref());
+    }
+
+    fn read_cdr(r: &mut CdrReader<'_>) -> Result<Self, CdrError> {
+        let mut header = read_header(r)?;
+        let parent_frame_id = std::mem::take(&mut header.frame_id);
+        Ok(TransformStamped {
+            header: Some(header),
+            parent_frame_id,
+            child_frame_id: r.read_string()?,
+            translation: Some(read_vector3(r)?),
+            rotation: Some(read_quaternion(r)?),
+        })
+    }
+}
+
+impl Ros2Message for TfMessage {
+    const ROS_TYPE: &'static str = "tf2_msgs/msg/TFMessage";
+
+    fn write_cdr(&self, w: &mut CdrWriter) {
+        w.write_len(self.transforms.len());
+        for t in &self.transforms {
+            t.write_cdr(w);
+        }
+    }
+
+    fn read_cdr(r: &mut CdrReader<'_>) -> Result<Self, CdrError> {
+        let len = r.read_len()?;
+        Ok(TfMessage {
+            transforms: (0..len)
+                .map(|_| TransformStamped::read_cdr(r))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// `info.map_load_time` is the header stamp.
+impl Ros2Message for OccupancyGrid {
+    const ROS_TYPE: &'static str = "nav_msgs/msg/OccupancyGrid";
+
+    fn write_cdr(&self, w: &mut CdrWriter) {
+        write_header(w, self.header.as_ref());
+        // nav_msgs/msg/MapMetaData
+        write_time(w, self.header.as_ref().map_or(0, |h| h.acq_time));
+        w.write_f32(self.resolution);
+        w.write_u32(self.width);
+        w.write_u32(self.height);
+        write_pose(w, self.origin.as_ref());
+        w.write_bytes(&self.data);
+    }
+
+    fn read_cdr(r: &mut CdrReader<'_>) -> Result<Self, CdrError> {
+        let header = read_header(r)?;
+        read_time(r)?;
+        Ok(OccupancyGrid {
+            header: Some(header),
+            resolution: r.read_f32()?,
+            width: r.read_u32()?,
+            height: r.read_u32()?,
+            origin: Some(read_pose(r)?),
+            data: r.read_bytes()?,
+        })
+    }
+}
+
+impl Ros2Message for DiagnosticArray {
+    const ROS_TYPE: &'static str = "diagnostic_msgs/msg/DiagnosticArray";
+
+    fn write_cdr(&self, w: &mut CdrWriter) {
+        write_header(w, self.header.as_ref());
+        w.write_len(self.status.len());
+        for status in &self.status {
+            // The levels match diagnostic_msgs.
+            w.write_u8(status.level() as u8);
+            w.write_string(&status.name);
+            w.write_string(&status.message);
+            w.write_string(&status.hardware_id);
+            w.write_len(status.values.len());
+            for kv in &status.values {
+                w.write_string(&kv.key);
+                w.write_string(&kv.value);
+            }
+        }
+    }
+
+    fn read_cdr(r: &mut CdrReader<'_>) -> Result<Self, CdrError> {
+        let header = read_header(r)?;
+        let len = r.read_len()?;
+        let mut status = Vec::with_capacity(len);
+        for _ in 0..len {
+            let level =
+                DiagnosticLevel::try_from(r.read_u8()? as i32).unwrap_or(DiagnosticLevel::Stale);
+            let mut s = DiagnosticStatus {
+                name: r.read_string()?,
+                message: r.read_string()?,
+                hardware_id: r.read_string()?,
+                ..Default::default()
+            };
+            s.set_level(level);
+            let values = r.read_len()?;
+            for _ in 0..values {
+                s.values.push(KeyValue {
+                    key: r.read_string()?,
+                    value: r.read_string()?,
+                });
+            }
+            status.push(s);
+        }
+        Ok(DiagnosticArray {
+            header: Some(header),
+            status,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header() -> Header {
+        Header {
+            acq_time: 1_700_000_000_123_456_789,
+            pub_time: 1_700_000_000_200_000_000,
+            sequence: 7,
+            frame_id: "imu_link".to_string(),
+            machine_id: "orin".to_string(),
+        }
+    }
+
+    #[test]
+    fn header_matches_ros_wire_format() {
+        let bytes = header().to_cdr();
+        let mut expected = vec![0x00, 0x01, 0x00, 0x00];
+        expected.extend_from_slice(&1_700_000_000i32.to_le_bytes());
+        expected.extend_from_slice(&123_456_789u32.to_le_bytes());
+        expected.extend_from_slice(&9u32.to_le_bytes());
+        expected.extend_from_slice(b"imu_link\0");
+        assert_eq!(bytes, expected);
+
+        let decoded = Header::from_cdr(&bytes).unwrap();
+        assert_eq!(decoded.acq_time, header().acq_time);
+        assert_eq!(decoded.frame_id, "imu_link");
+        assert_eq!(decoded.sequence, 0);
+    }
+
+    #[test]
+    fn doubles_are_aligned_after_the_header() {
+        let imu = Imu {
+            header: Some(header()),
+            orientation: Some(Quaternion {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+                w: 1.0,
+            }),
+            orientation_covariance: vec![-1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+            angular_velocity: Some(Vector3 {
+                x: 0.1,
+                y: 0.2,
+                z: 0.3,
+            }),
+            angular_velocity_covariance: Vec::new(),
+            linear_acceleration: Some(Vector3 {
+                x: 0.0,
+                y: 0.0,
+                z: 9.81,
+            }),
+            linear_acceleration_covariance: vec![0.01; 9],
+        };
+        let bytes = imu.to_cdr();
+        // 8 bytes of stamp + 4 + "imu_link\0" = 21, padded to 24.
+        let first_double = 4 + 24;
+        assert_eq!(&bytes[4 + 21..first_double], &[0, 0, 0]);
+        assert_eq!(bytes.len(), first_double + (4 + 9 + 3 + 9 + 3 + 9) * 8);
+
+        let decoded = Imu::from_cdr(&bytes).unwrap();
+        assert_eq!(decoded.orientation, imu.orientation);
+        assert_eq!(decoded.orientation_covariance, imu.orientation_covariance);
+        assert!(decoded.angular_velocity_covariance.is_empty());
+        assert_eq!(decoded.linear_acceleration, imu.linear_acceleration);
+        assert_eq!(
+            decoded.linear_acceleration_covariance,
+            imu.linear_acceleration_covariance
+        );
+    }
+
+    #[test]
+    fn nav_sat_fix_maps_status_values() {
+        let mut fix = NavSatFix {
+            header: Some(header()),
+            services: 1 | 8,
+            latitude: 41.39,
+            longitude: 2.17,
+            altitude: 12.0,
+            ..Default::default()
+        };
+        fix.set_status(FixStatus::NoFix);
+        fix.set_position_covariance_type(NavSatCovarianceType::DiagonalKnown);
+        let bytes = fix.to_cdr();
+        // int8 status needs no padding after "imu_link\0".
+        assert_eq!(bytes[4 + 21], (-1i8) as u8);
+
+        let decoded = NavSatFix::from_cdr(&bytes).unwrap();
+        assert_eq!(decoded.status(), FixStatus::NoFix);
+        assert_eq!(decoded.services, 9);
+        assert_eq!(decoded.latitude, 41.39);
+        assert_eq!(
+            decoded.position_covariance_type(),
+            NavSatCovarianceType::DiagonalKnown
+        );
+    }
+
+    #[test]
+    fn battery_percentage_is_a_fraction_in_ros() {
+        let mut state = BatteryState {
+            header: Some(header()),
+            voltage: 12.4,
+            current: -1.5,
+            charge_percent: 80.0,
+            temperature: f32::NAN,
+            ..Default::default()
+        };
+        state.set_status(BatteryStatus::Discharging);
+        let decoded = BatteryState::from_cdr(&state.to_cdr()).unwrap();
+        assert_eq!(decoded.voltage, 12.4);
+        assert_eq!(decoded.current, -1.5);
+        assert!((decoded.charge_percent - 80.0).abs() < 1e-4);
+        assert!(decoded.temperature.is_nan());
+        assert_eq!(decoded.status(), BatteryStatus::Discharging);
+    }
+
+    #[test]
+    fn tf_message_uses_parent_frame_as_header_frame() {
+        let tf = TfMessage {
+            transforms: vec![TransformStamped {
+                header: Some(header()),
+                parent_frame_id: "base_link".to_string(),
+                child_frame_id: "cam_front".to_string(),
+                translation: Some(Vector3 {
+                    x: 0.2,
+                    y: 0.0,
+                    z: 0.5,
+                }),
+                rotation: None,
+            }],
+        };
+        let bytes = tf.to_cdr();
+        let decoded = TfMessage::from_cdr(&bytes).unwrap();
+        let t = decoded.find("cam_front").unwrap();
+        assert_eq!(t.parent_frame_id, "base_link");
+        assert_eq!(t.header.as_ref().unwrap().frame_id, "");
+        assert_eq!(t.rotation.as_ref().unwrap().w, 1.0);
+        assert_eq!(t.translation, tf.transforms[0].translation);
+    }
+
+    #[test]
+    fn occupancy_grid_and_diagnostics_roundtrip() {
+        let grid = OccupancyGrid {
+            header: Some(header()),
+            resolution: 0.05,
+            width: 2,
+            height: 2,
+            origin: Some(Pose {
+                position: Some(Vector3 {
+                    x: -1.0,
+                    y: -1.0,
+                    z: 0.0,
+                }),
+                orientation: Some(Quaternion {
+                    w: 1.0,
+                    ..Default::default()
+                }),
+            }),
+            data: vec![0, 100, 0xFF, 50],
+        };
+        let decoded = OccupancyGrid::from_cdr(&grid.to_cdr()).unwrap();
+        assert_eq!(decoded.cell(0, 1), Some(-1));
+        assert_eq!(decoded.origin, grid.origin);
+        assert_eq!(decoded.resolution, 0.05);
+
+        let mut status = DiagnosticStatus {
+            name: "cam: rtsp".to_string(),
+            message: "refused".to_string(),
+            values: vec![KeyValue {
+                key: "retries".to_string(),
+                value: "3".to_string(),
+            }],
+            ..Default::default()
+        };
+        status.set_level(DiagnosticLevel::Error);
+        let array = DiagnosticArray {
+            header: Some(header()),
+            status: vec![status],
+        };
+        let decoded = DiagnosticArray::from_cdr(&array.to_cdr()).unwrap();
+        assert_eq!(decoded.status, array.status);
+        assert_eq!(decoded.worst_level(), DiagnosticLevel::Error);
+    }
+
+    #[test]
+    fn reads_big_endian_and_rejects_truncated_payloads() {
+        let mut bytes = vec![0x00, 0x00, 0x00, 0x00];
+        bytes.extend_from_slice(&5i32.to_be_bytes());
+        bytes.extend_from_slice(&6u32.to_be_bytes());
+        bytes.extend_from_slice(&4u32.to_be_bytes());
+        bytes.extend_from_slice(b"map\0");
+        let header = Header::from_cdr(&bytes).unwrap();
+        assert_eq!(header.acq_time, 5 * NANOS_PER_SEC + 6);
+        assert_eq!(header.frame_id, "map");
+
+        let image = CompressedImage {
+            header: header.clone(),
+            format: "jpeg".to_string(),
+            data: vec![0xFF, 0xD8, 0xFF],
+        };
+        let le = image.to_cdr();
+        assert_eq!(CompressedImage::from_cdr(&le).unwrap(), image);
+        assert!(matches!(
+            CompressedImage::from_cdr(&le[..le.len() - 1]),
+            Err(CdrError::UnexpectedEnd(_))
+        ));
+        assert!(matches!(
+            Header::from_cdr(&[0x00, 0x07, 0x00, 0x00]),
+            Err(CdrError::Encapsulation(7))
+        ));
+    }
+}
@@ -67,6 +67,11 @@ impl<P: PlatformOperations> Dispatcher<P> {
         self
     }
 
+    /// The platform tools run against, shared with rule actions.
+    pub fn platform(&self) -> &Arc<P> {
+        &self.platform
+    }
+
     /// Return the telemetry prompt summary, or `None` if telemetry is not attached.
     pub async fn telemetry_prompt_summary(&self) -> Option<String> {
         if let Some(ref telem) = self.telemetry {
@@ -448,7 +453,7 @@ impl<P: PlatformOperations> Dispatcher<P> {
             ToolDefinition {
                 name: "register_alert".to_string(),
                 description: "Register a reactive alert rule. When world state matches the \
-                    predicate, the agent's arousal spikes, shortening the heartbeat interval \
+                    predicate, or a cron schedule comes due, the agent's arousal spikes, shortening the heartbeat interval \
                     and making the agent react faster. Combine with configure_context to wire \
                     a sensor topic into world state first, then write a predicate over that key."
                     .to_string(),
@@ -461,7 +466,7 @@ impl<P: PlatformOperations> Dispatcher<P> {
                         },
                         "predicate": {
                             "type": "string",
-                            "description": "World state predicate expression (e.g. \"motion.level > 0.05\" or \"toddler.near_stairs = 'true'\")"
+                            "description": "World state predicate expression (e.g. \"motion.level > 0.05\" or \"cpu_temp > 80 AND (fan_rpm < 500 OR NOT fan.ok = true)\"); combine clauses with AND, OR, NOT and parentheses"
                         },
                         "description": {
                             "type": "string",
@@ -471,12 +476,50 @@ impl<P: PlatformOperations> Dispatcher<P> {
                             "type": "integer",
                             "description": "Minimum seconds between consecutive firings (default: 60)"
                         },
+                        "hold_secs": {
+                            "type": "integer",
+                            "description": "Seconds the predicate must hold continuously before firing (default: 0)"
+                        },
+                        "schedule": {
+                            "type": "string",
+                            "description": "Cron expression in UTC (e.g. \"0 22 * * *\") that triggers the alert; the predicate may then be omitted, otherwise it is checked when the schedule comes due"
+                        },
+                        "clear_predicate": {
+                            "type": "string",
+                            "description": "Predicate that must hold before the alert can fire again (hysteresis), e.g. \"cpu_temp < 75\" for the predicate \"cpu_temp > 85\": the alert fires once when the value goes above 85 and re-arms only after it dropped below 75"
+                        },
+                        "priority": {
+                            "type": "integer",
+                            "description": "When alerts that fire together give one node different commands, only the command of the highest-priority alert runs; the others are logged as suppressed (default: 0, range -100 to 100)"
+                        },
+                        "dry_run": {
+                            "type": "boolean",
+                            "description": "Evaluate the alert and log \"would have executed\" for each action instead of running it, without boosting arousal, to validate it against live data before arming it (default: false)"
+                        },
+                        "max_triggers_per_hour": {
+                            "type": "integer",
+                            "description": "Most firings within an hour that run the alert's actions and boost arousal; further firings are recorded as throttled, so a flapping sensor cannot hammer a node (default: unlimited, range 1 to 3600)"
+                        },
                         "arousal_boost": {
                             "type": "number",
                             "description": "Arousal boost when rule fires (default: 2.0)"
+                        },
+                        "actions": {
+                            "type": "array",
+                            "description": "Actions run when the rule fires. An http action POSTs the rule, trigger and predicate field values as JSON: {\"type\": \"http\", \"url\": \"https://...\", \"headers\": {}, \"secret\": \"env:HOOK_TOKEN\", \"timeout_secs\": 10, \"retries\": 2}; secret is sent as a bearer token. A notify action sends a message to a channel in ~/.bubbaloop/notifications.yaml (ntfy, slack or email): {\"type\": \"notify\", \"channel\": \"phone\", \"message\": \"CPU at {fields.cpu_temp}C\"}. A node action starts, stops or restarts a node: {\"type\": \"node\", \"node\": \"camera\", \"command\": \"restart\"}. A workflow action runs steps in order, stopping at a failed step unless its on_failure is \"continue\"; steps are http, notify and node actions plus {\"type\": \"wait\", \"secs\": 30}, {\"type\": \"check\", \"node\": \"camera\", \"status\": \"running\", \"health\": \"healthy\"} and {\"type\": \"publish\", \"topic\": \"bubbaloop/...\"}: {\"type\": \"workflow\", \"steps\": [...]}",
+                            "items": {"type": "object"}
+                        },
+                        "escalation": {
+                            "type": "array",
+                            "description": "Escalation policy: steps of further actions for when the predicate still holds after_secs after the rule fired despite its actions, e.g. [{\"after_secs\": 600, \"actions\": [{\"type\": \"notify\", \"channel\": \"phone\"}]}, {\"after_secs\": 1800, \"actions\": [{\"type\": \"node\", \"node\": \"camera\", \"command\": \"stop\"}]}]. after_secs must increase from step to step (at most 4 steps); the escalation ends once the predicate stops holding",
+                            "items": {"type": "object"}
+                        },
+                        "scope": {
+                            "type": "object",
+                            "description": "Machines and nodes the alert is addressed to, as names or globs with *: {\"machines\": [\"jetson-*\"], \"nodes\": [\"camera-*\"]}. Only the agent on a matching machine that has a matching node evaluates it, and its node actions may only target matching nodes (default: everywhere)"
                         }
                     },
-                    "required": ["mission_id", "predicate", "description"]
+                    "required": ["mission_id", "description"]
                 }),
             },
             ToolDefinition {
@@ -1490,8 +1533,13 @@ impl<P: PlatformOperations> Dispatcher<P> {
                 return ToolResult::error("Missing required parameter: mission_id".to_string());
             }
         };
+        let schedule = input
+            .get("schedule")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
         let predicate = match input.get("predicate").and_then(|v| v.as_str()) {
             Some(s) => s.to_string(),
+            None if schedule.is_some() => String::new(),
             None => {
                 return ToolResult::error("Missing required parameter: predicate".to_string());
             }
@@ -1502,14 +1550,56 @@ impl<P: PlatformOperations> Dispatcher<P> {
                 return ToolResult::error("Missing required parameter: description".to_string());
             }
         };
+        let actions = match input.get("actions") {
+            None | Some(Value::Null) => None,
+            Some(v) => match serde_json::from_value(v.clone()) {
+                Ok(actions) => Some(actions),
+                Err(e) => return ToolResult::error(format!("Invalid actions: {}", e)),
+            },
+        };
+        let escalation = match input.get("escalation") {
+            None | Some(Value::Null) => None,
+            Some(v) => match serde_json::from_value(v.clone()) {
+                Ok(escalation) => Some(escalation),
+                Err(e) => return ToolResult::error(format!("Invalid escalation: {}", e)),
+            },
+        };
+        let scope = match input.get("scope") {
+            None | Some(Value::Null) => None,
+            Some(v) => match serde_json::from_value(v.clone()) {
+                Ok(scope) => Some(scope),
+                Err(e) => return ToolResult::error(format!("Invalid scope: {}", e)),
+            },
+        };
         let params = RegisterAlertParams {
             mission_id,
             predicate,
             description,
+            actions,
+            escalation,
+            scope,
             debounce_secs: input
                 .get("debounce_secs")
                 .and_then(|v| v.as_u64())
                 .and_then(|n| u32::try_from(n).ok()),
+            hold_secs: input
+                .get("hold_secs")
+                .and_then(|v| v.as_u64())
+                .and_then(|n| u32::try_from(n).ok()),
+            schedule,
+            clear_predicate: input
+                .get("clear_predicate")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            priority: input
+                .get("priority")
+                .and_then(|v| v.as_i64())
+                .and_then(|n| i32::try_from(n).ok()),
+            dry_run: input.get("dry_run").and_then(|v| v.as_bool()),
+            max_triggers_per_hour: input
+                .get("max_triggers_per_hour")
+                .and_then(|v| v.as_u64())
+                .and_then(|n| u32::try_from(n).ok()),
             arousal_boost: input.get("arousal_boost").and_then(|v| v.as_f64()),
         };
         match self.platform.register_alert(params).await {
@@ -1552,7 +1642,7 @@ impl<P: PlatformOperations> Dispatcher<P> {
 
         let mut top_processes: Vec<&crate::daemon::telemetry::types::ProcessSnapshot> =
             snapshot.processes.iter().collect();
-        top_processes.sort_by(|a, b| b.rss_bytes.cmp(&a.rss_bytes));
+        top_processes.sort_by_key(|p| std::cmp::Reverse(p.rss_bytes));
         top_processes.truncate(10);
 
         let processes_json: Vec<Value> = top_processes
@@ -3,7 +3,13 @@
 //! The Gateway is a convention (topic pair + JSON schema), not a process.
 //! Messages flow through Zenoh pub/sub between CLI clients and agent runtimes.
 
+use crate::daemon::overrides::{Override, OverrideAudit};
+use crate::daemon::reactive::RuleScope;
+use crate::daemon::rule_actions::{EscalationStep, RuleAction};
+use crate::daemon::rule_events::RuleEvent;
+use crate::daemon::rule_templates::TemplateInfo;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 // ── Inbox (CLI → Daemon) ─────────────────────────────────────────
 
@@ -148,6 +154,271 @@ pub struct AgentManifest {
     pub machine_id: String,
 }
 
+// ── Rules (queryable) ────────────────────────────────────────────
+
+/// An operation on an agent's reactive rules, sent as a query payload.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RulesAction {
+    /// List rules, optionally for one mission.
+    List {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mission_id: Option<String>,
+    },
+    /// Register a rule (same defaults as the `register_alert` MCP tool).
+    Add {
+        mission_id: String,
+        #[serde(default)]
+        predicate: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        debounce_secs: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        hold_secs: Option<u32>,
+        /// Cron expression (UTC); the predicate may then be empty.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        schedule: Option<String>,
+        /// Predicate that re-arms the rule after it fired.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        clear_predicate: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        priority: Option<i32>,
+        /// Log the actions instead of running them (see `Arm`).
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        dry_run: Option<bool>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_triggers_per_hour: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        arousal_boost: Option<f64>,
+        #[serde(default)]
+        description: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        actions: Vec<RuleAction>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        escalation: Vec<EscalationStep>,
+        #[serde(default, skip_serializing_if = "RuleScope::is_empty")]
+        scope: RuleScope,
+    },
+    Remove {
+        id: String,
+    },
+    Enable {
+        id: String,
+    },
+    Disable {
+        id: String,
+    },
+    /// Take a rule out of dry-run mode, so its actions run.
+    Arm {
+        id: String,
+    },
+    /// Evaluate a stored rule (`id`) or a `predicate` against `state`, or
+    /// against the agent's current world state when `state` is omitted.
+    /// Nothing fires.
+    Test {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        predicate: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        state: Option<HashMap<String, String>>,
+    },
+    /// List the rule templates of the built-in and installed rule packs.
+    Templates,
+    /// Register a rule from a template (`name` or `pack/name`).
+    Instantiate {
+        template: String,
+        mission_id: String,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        params: HashMap<String, String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        dry_run: Option<bool>,
+    },
+    /// Install a rule pack (its YAML text) on the agent's machine.
+    InstallPack {
+        pack: String,
+    },
+    /// Read the rule event history, newest first: every rule firing or
+    /// those of rule `id`, between `since` and `until` (epoch seconds).
+    Events {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        since: Option<i64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        until: Option<i64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limit: Option<u32>,
+    },
+    /// How much of its trigger budget each rule used in the last hour,
+    /// and how far each escalating rule got.
+    Status,
+    /// Pause automation: rule `id` of this agent, or every rule of every
+    /// agent on the machine when `id` is omitted, until `Resume` or, when
+    /// set, `until` (epoch seconds). Paused rules fire and are recorded,
+    /// but run no actions.
+    Pause {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        /// Who pauses, for the audit trail.
+        actor: String,
+        #[serde(default, skip_serializing_if = "String::is_empty")]
+        reason: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        until: Option<i64>,
+    },
+    /// Lift the pause of rule `id`, or the global pause.
+    Resume {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        actor: String,
+    },
+    /// The overrides in force and the latest `limit` entries of their
+    /// audit trail, newest first.
+    Overrides {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        limit: Option<u32>,
+    },
+}
+
+impl RulesAction {
+    /// Whether the action changes the rule store (and needs the auth token).
+    pub fn is_mutation(&self) -> bool {
+        matches!(
+            self,
+            Self::Add { .. }
+                | Self::Remove { .. }
+                | Self::Enable { .. }
+                | Self::Disable { .. }
+                | Self::Arm { .. }
+                | Self::Instantiate { .. }
+                | Self::InstallPack { .. }
+                | Self::Pause { .. }
+                | Self::Resume { .. }
+        )
+    }
+}
+
+/// Query payload for the rules queryable.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RulesRequest {
+    #[serde(flatten)]
+    pub action: RulesAction,
+    /// Bearer token (from `~/.bubbaloop/mcp-token`), required to change rules.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_token: Option<String>,
+}
+
+/// A reactive rule as reported by the rules queryable.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RuleInfo {
+    pub id: String,
+    pub mission_id: String,
+    pub predicate: String,
+    pub debounce_secs: u32,
+    #[serde(default)]
+    pub hold_secs: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clear_predicate: Option<String>,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_triggers_per_hour: Option<u32>,
+    pub arousal_boost: f64,
+    pub description: String,
+    /// Actions with literal secrets redacted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<RuleAction>,
+    /// Escalation steps, redacted like `actions`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub escalation: Vec<EscalationStep>,
+    /// Machines and nodes the rule is addressed to.
+    #[serde(default, skip_serializing_if = "RuleScope::is_empty")]
+    pub scope: RuleScope,
+    pub enabled: bool,
+    /// Predicate fields no context provider writes (see `list_alerts`).
+    #[serde(default)]
+    pub dangling_fields: Vec<String>,
+}
+
+/// Trigger budget use of one rule over the last hour, from the rule event
+/// history, and its escalation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RuleStatus {
+    pub id: String,
+    pub mission_id: String,
+    /// `None` when the rule has no budget.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_triggers_per_hour: Option<u32>,
+    /// Firings that ran (or, in dry-run mode, logged) their actions.
+    pub triggers_last_hour: u32,
+    /// Firings over the budget, whose actions were skipped.
+    pub throttled_last_hour: u32,
+    /// Latest firing over the budget (epoch seconds), at any time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_throttled_at: Option<i64>,
+    /// Escalation steps the rule has.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub escalation_steps: u32,
+    /// When the firing that started the current escalation happened
+    /// (epoch seconds); `None` while the rule is not escalating.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escalating_since: Option<i64>,
+    /// Escalation steps run since then.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub escalation_level: u32,
+    /// When the latest of them ran (epoch seconds).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escalated_at: Option<i64>,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+/// Result of a `test` action.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RuleTestResult {
+    pub predicate: String,
+    pub matched: bool,
+    /// Each field the predicate reads, with its value (None when absent).
+    pub fields: Vec<(String, Option<String>)>,
+}
+
+/// Reply of the rules queryable.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RulesReply {
+    pub ok: bool,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<RuleInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test: Option<RuleTestResult>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<RuleEvent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub templates: Vec<TemplateInfo>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub statuses: Vec<RuleStatus>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<Override>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub audit: Vec<OverrideAudit>,
+}
+
+impl RulesReply {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+}
+
 // ── Topic builders ───────────────────────────────────────────────
 
 /// Build the shared agent inbox topic.
@@ -174,6 +445,13 @@ pub fn manifest_topic(machine_id: &str, agent_id: &str) -> String {
     )
 }
 
+/// Build a per-agent rules topic (queryable).
+///
+/// Format: `bubbaloop/global/{machine}/agent/{agent_id}/rules`
+pub fn rules_topic(machine_id: &str, agent_id: &str) -> String {
+    format!("bubbaloop/global/{}/agent/{}/rules", machine_id, agent_id)
+}
+
 /// Build a wildcard pattern for discovering all agent manifests.
 ///
 /// Format: `bubbaloop/global/{machine}/agent/*/manifest`
@@ -316,6 +594,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rules_topic_format() {
+        assert_eq!(
+            rules_topic("jetson01", "camera-expert"),
+            "bubbaloop/global/jetson01/agent/camera-expert/rules"
+        );
+    }
+
+    #[test]
+    fn rules_request_wire_format() {
+        let json = r#"{"action":"disable","id":"alert-1","auth_token":"bb_x"}"#;
+        let req: RulesRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            req.action,
+            RulesAction::Disable {
+                id: "alert-1".to_string()
+            }
+        );
+        assert!(req.action.is_mutation());
+        assert_eq!(serde_json::to_string(&req).unwrap(), json);
+
+        let req: RulesRequest = serde_json::from_str(r#"{"action":"list"}"#).unwrap();
+        assert_eq!(req.action, RulesAction::List { mission_id: None });
+        assert!(!req.action.is_mutation());
+        assert_eq!(req.auth_token, None);
+    }
+
     #[test]
     fn manifest_wildcard_format() {
         assert_eq!(
@@ -8,6 +8,7 @@
 //! - `dispatch` — Internal MCP tool dispatch
 //! - `prompt` — System prompt builder
 //! - `scheduler` — Job poller integrated with heartbeat
+//! - `rules` — Rules queryable for managing reactive rules from the CLI
 
 pub mod dispatch;
 pub(crate) mod dispatch_security;
@@ -16,6 +17,7 @@ pub mod heartbeat;
 pub mod memory;
 pub mod prompt;
 pub mod provider;
+pub mod rules;
 pub mod runtime;
 pub mod scheduler;
 pub mod soul;
@@ -461,19 +461,16 @@ impl SseParser {
                             }
                         }
                     }
-                    "content_block_stop" => {
-                        if self.in_tool_block {
-                            let input: serde_json::Value =
-                                serde_json::from_str(&self.tool_input_json)
-                                    .unwrap_or_else(|_| serde_json::json!({}));
-                            events.push(StreamEvent::ToolUse {
-                                id: self.tool_id.clone(),
-                                name: self.tool_name.clone(),
-                                input,
-                            });
-                            self.in_tool_block = false;
-                            self.tool_input_json.clear();
-                        }
+                    "content_block_stop" if self.in_tool_block => {
+                        let input: serde_json::Value = serde_json::from_str(&self.tool_input_json)
+                            .unwrap_or_else(|_| serde_json::json!({}));
+                        events.push(StreamEvent::ToolUse {
+                            id: self.tool_id.clone(),
+                            name: self.tool_name.clone(),
+                            input,
+                        });
+                        self.in_tool_block = false;
+                        self.tool_input_json.clear();
                     }
                     "message_delta" => {
                         if let Some(d) = parsed.get("delta") {
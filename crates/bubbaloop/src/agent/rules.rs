@@ -0,0 +1,804 @@
+//! Rules queryable — manage an agent's reactive rules over Zenoh.
+//!
+//! Gives operators without an LLM client the same control as the MCP alert
+//! tools (`register_alert`, `unregister_alert`, `list_alerts`), plus enable,
+//! disable, a dry-run `test`, rule templates, the rule event history and
+//! the trigger budget use and escalation of each rule. Requests and replies are JSON on
+//! `bubbaloop/global/{machine}/agent/{agent_id}/rules`. Listing and testing
+//! are open like the manifest; changes need the MCP token like the inbox.
+//! The agent loop picks changes up on its next rule reload.
+//!
+//! Pausing and resuming automation ([`crate::daemon::overrides`]) also
+//! goes through here, and only through here: the agent loop reads the
+//! overrides whenever a rule fires.
+
+use crate::agent::gateway::{
+    RuleInfo, RuleStatus, RuleTestResult, RulesAction, RulesReply, RulesRequest,
+};
+use crate::daemon::context_provider::{load_provider_templates, parse_filter};
+use crate::daemon::overrides::{self, Override, OverrideStore};
+use crate::daemon::reactive::{
+    eval_predicate, extract_predicate_fields, field_value, ReactiveRuleStore,
+};
+use crate::daemon::rule_events::{RuleEventQuery, RuleEventStore};
+use crate::daemon::rule_templates;
+use crate::mcp::platform::{AlertInfo, RegisterAlertParams};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Whether answering `action` needs a snapshot of the agent's world state.
+pub fn needs_world_state(action: &RulesAction) -> bool {
+    matches!(action, RulesAction::Test { state: None, .. })
+}
+
+/// Answer one rules request for the agent whose files live in `agent_dir`.
+///
+/// `world_state` is only read by `test` without an explicit state.
+pub fn handle_request(
+    agent_dir: &Path,
+    request: RulesRequest,
+    expected_token: &str,
+    world_state: &HashMap<String, String>,
+) -> RulesReply {
+    if request.action.is_mutation() {
+        let authorized = request
+            .auth_token
+            .as_deref()
+            .is_some_and(|t| crate::mcp::auth::validate_token(t, expected_token));
+        if !authorized {
+            return RulesReply::error("missing or invalid auth token");
+        }
+    }
+    match handle_action(agent_dir, request.action, world_state) {
+        Ok(reply) => reply,
+        Err(e) => RulesReply::error(e.to_string()),
+    }
+}
+
+fn handle_action(
+    agent_dir: &Path,
+    action: RulesAction,
+    world_state: &HashMap<String, String>,
+) -> anyhow::Result<RulesReply> {
+    let store = ReactiveRuleStore::open(&agent_dir.join("alerts.db"))?;
+    let ok = |message: String| RulesReply {
+        ok: true,
+        message,
+        ..Default::default()
+    };
+    match action {
+        RulesAction::List { mission_id } => {
+            let rules = match mission_id.as_deref() {
+                Some(mission_id) => store.rules_for_mission(mission_id)?,
+                None => store.list_rules()?,
+            };
+            let disabled = store.disabled_rule_ids()?;
+            let templates = load_provider_templates(&agent_dir.join("providers.db"))?;
+            let rules: Vec<RuleInfo> = rules
+                .into_iter()
+                .map(|rule| {
+                    let enabled = !disabled.contains(&rule.id);
+                    let info = AlertInfo::from_rule(rule, &templates);
+                    RuleInfo {
+                        id: info.id,
+                        mission_id: info.mission_id,
+                        predicate: info.predicate,
+                        debounce_secs: info.debounce_secs,
+                        hold_secs: info.hold_secs,
+                        schedule: info.schedule,
+                        clear_predicate: info.clear_predicate,
+                        priority: info.priority,
+                        dry_run: info.dry_run,
+                        max_triggers_per_hour: info.max_triggers_per_hour,
+                        arousal_boost: info.arousal_boost,
+                        description: info.description,
+                        actions: info.actions,
+                        escalation: info.escalation,
+                        scope: info.scope,
+                        enabled,
+                        dangling_fields: info.dangling_fields,
+                    }
+                })
+                .collect();
+            Ok(RulesReply {
+                rules,
+                ..ok(String::new())
+            })
+        }
+        RulesAction::Add {
+            mission_id,
+            predicate,
+            debounce_secs,
+            hold_secs,
+            schedule,
+            clear_predicate,
+            priority,
+            dry_run,
+            max_triggers_per_hour,
+            arousal_boost,
+            description,
+            actions,
+            escalation,
+            scope,
+        } => {
+            let id = format!("alert-{}", uuid::Uuid::new_v4());
+            let rule = RegisterAlertParams {
+                mission_id,
+                predicate,
+                debounce_secs,
+                hold_secs,
+                schedule,
+                clear_predicate,
+                priority,
+                dry_run,
+                max_triggers_per_hour,
+                arousal_boost,
+                description,
+                actions: Some(actions),
+                escalation: Some(escalation),
+                scope: Some(scope),
+            }
+            .into_config(id.clone());
+            store.save_rule(&rule)?;
+            Ok(ok(format!("Rule '{}' registered", id)))
+        }
+        RulesAction::Remove { id } => {
+            if !store.list_rules()?.iter().any(|r| r.id == id) {
+                anyhow::bail!("rule '{}' not found", id);
+            }
+            store.delete_rule(&id)?;
+            Ok(ok(format!("Rule '{}' removed", id)))
+        }
+        RulesAction::Enable { id } => set_enabled(&store, &id, true).map(ok),
+        RulesAction::Disable { id } => set_enabled(&store, &id, false).map(ok),
+        RulesAction::Arm { id } => {
+            if !store.set_dry_run(&id, false)? {
+                anyhow::bail!("rule '{}' not found", id);
+            }
+            Ok(ok(format!("Rule '{}' armed", id)))
+        }
+        RulesAction::Test {
+            id,
+            predicate,
+            state,
+        } => {
+            let predicate = match (id, predicate) {
+                (Some(id), None) => store
+                    .list_rules()?
+                    .into_iter()
+                    .find(|r| r.id == id)
+                    .map(|r| r.predicate)
+                    .ok_or_else(|| anyhow::anyhow!("rule '{}' not found", id))?,
+                (None, Some(predicate)) => predicate,
+                _ => anyhow::bail!("test needs either a rule id or a predicate"),
+            };
+            if let Err(e) = parse_filter(&predicate) {
+                anyhow::bail!("predicate does not parse: {}", e);
+            }
+            let fields = extract_predicate_fields(&predicate);
+            if fields.is_empty() {
+                anyhow::bail!("predicate has no comparison (e.g. \"motion.level > 0.5\")");
+            }
+            let state = state.as_ref().unwrap_or(world_state);
+            let map: HashMap<&str, &str> = state
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect();
+            let matched = eval_predicate(&predicate, &map);
+            let fields = fields
+                .into_iter()
+                .map(|f| {
+                    let value = field_value(&map, &f);
+                    (f, value)
+                })
+                .collect();
+            Ok(RulesReply {
+                test: Some(RuleTestResult {
+                    predicate,
+                    matched,
+                    fields,
+                }),
+                ..ok(if matched { "matches" } else { "does not match" }.to_string())
+            })
+        }
+        RulesAction::Templates => {
+            let templates = rule_templates::list_templates(&rule_templates::load_packs(
+                &rule_templates::packs_dir(agent_dir),
+            ));
+            Ok(RulesReply {
+                message: format!("{} template(s)", templates.len()),
+                templates,
+                ..ok(String::new())
+            })
+        }
+        RulesAction::Instantiate {
+            template,
+            mission_id,
+            params,
+            dry_run,
+        } => {
+            let packs = rule_templates::load_packs(&rule_templates::packs_dir(agent_dir));
+            let mut rule = rule_templates::find_template(&packs, &template)?
+                .instantiate(&mission_id, &params)?;
+            rule.dry_run = dry_run.or(rule.dry_run);
+            let id = format!("alert-{}", uuid::Uuid::new_v4());
+            store.save_rule(&rule.into_config(id.clone()))?;
+            Ok(ok(format!("Rule '{}' registered from {}", id, template)))
+        }
+        RulesAction::InstallPack { pack } => {
+            let pack = rule_templates::install_pack(&rule_templates::packs_dir(agent_dir), &pack)?;
+            Ok(ok(format!(
+                "Rule pack '{}' {} installed ({} template(s))",
+                pack.name,
+                pack.version,
+                pack.templates.len()
+            )))
+        }
+        RulesAction::Events {
+            id,
+            since,
+            until,
+            limit,
+        } => {
+            let events =
+                RuleEventStore::open(&agent_dir.join("alerts.db"))?.query(&RuleEventQuery {
+                    rule_id: id,
+                    since,
+                    until,
+                    limit,
+                })?;
+            Ok(RulesReply {
+                message: format!("{} event(s)", events.len()),
+                events,
+                ..ok(String::new())
+            })
+        }
+        RulesAction::Status => {
+            let hour_ago = crate::agent::memory::now_epoch_secs() as i64 - 3600;
+            let history = RuleEventStore::open(&agent_dir.join("alerts.db"))?;
+            let recent = history.usage(hour_ago)?;
+            let ever = history.usage(0)?;
+            let escalations = history.escalations()?;
+            let statuses: Vec<RuleStatus> = store
+                .list_rules()?
+                .into_iter()
+                .map(|rule| {
+                    let usage = recent.get(&rule.id).copied().unwrap_or_default();
+                    // A rule whose steps are gone no longer escalates.
+                    let escalation = escalations
+                        .get(&rule.id)
+                        .filter(|_| !rule.escalation.is_empty());
+                    RuleStatus {
+                        last_throttled_at: ever.get(&rule.id).and_then(|u| u.last_throttled_at),
+                        escalation_steps: rule.escalation.len() as u32,
+                        escalating_since: escalation.map(|e| e.since),
+                        escalation_level: escalation.map_or(0, |e| e.level),
+                        escalated_at: escalation.and_then(|e| e.escalated_at),
+                        id: rule.id,
+                        mission_id: rule.mission_id,
+                        max_triggers_per_hour: rule.max_triggers_per_hour,
+                        triggers_last_hour: usage.triggers,
+                        throttled_last_hour: usage.throttled,
+                    }
+                })
+                .collect();
+            let over = statuses
+                .iter()
+                .filter(|s| s.throttled_last_hour > 0)
+                .count();
+            let escalating = statuses
+                .iter()
+                .filter(|s| s.escalating_since.is_some())
+                .count();
+            Ok(RulesReply {
+                message: format!(
+                    "{} rule(s) over budget in the last hour, {} escalating",
+                    over, escalating
+                ),
+                statuses,
+                ..ok(String::new())
+            })
+        }
+        RulesAction::Pause {
+            id,
+            actor,
+            reason,
+            until,
+        } => {
+            if let Some(id) = &id {
+                if !store.list_rules()?.iter().any(|r| &r.id == id) {
+                    anyhow::bail!("rule '{}' not found", id);
+                }
+            }
+            let now = crate::agent::memory::now_epoch_secs() as i64;
+            let agent = id.is_some().then(|| agent_id(agent_dir));
+            OverrideStore::open(&overrides::path(agent_dir))?.set(&Override {
+                agent,
+                rule: id.clone(),
+                set_by: actor,
+                reason,
+                set_at: now,
+                expires_at: until,
+            })?;
+            let target = match &id {
+                Some(id) => format!("Rule '{}'", id),
+                None => "All automation on this machine".to_string(),
+            };
+            Ok(ok(match until {
+                Some(until) => format!("{} paused for {}s", target, until - now),
+                None => format!("{} paused", target),
+            }))
+        }
+        RulesAction::Resume { id, actor } => {
+            let now = crate::agent::memory::now_epoch_secs() as i64;
+            let store = OverrideStore::open(&overrides::path(agent_dir))?;
+            let agent = id.as_ref().map(|_| agent_id(agent_dir));
+            if !store.clear(agent.as_deref(), id.as_deref(), &actor, now)? {
+                match &id {
+                    Some(id) => anyhow::bail!("rule '{}' is not paused", id),
+                    None => anyhow::bail!("automation is not paused"),
+                }
+            }
+            let global = store.active(now)?.into_iter().find(|o| o.agent.is_none());
+            Ok(ok(match (id, global) {
+                (Some(id), Some(global)) => format!(
+                    "Rule '{}' resumed; all automation is still paused by {}",
+                    id,
+                    global.by()
+                ),
+                (Some(id), None) => format!("Rule '{}' resumed", id),
+                (None, _) => "Automation resumed".to_string(),
+            }))
+        }
+        RulesAction::Overrides { limit } => {
+            let store = OverrideStore::open(&overrides::path(agent_dir))?;
+            let now = crate::agent::memory::now_epoch_secs() as i64;
+            let overrides = store.active(now)?;
+            Ok(RulesReply {
+                message: format!("{} override(s) in force", overrides.len()),
+                overrides,
+                audit: store.audit(limit)?,
+                ..ok(String::new())
+            })
+        }
+    }
+}
+
+/// The agent whose files live in `agent_dir`.
+fn agent_id(agent_dir: &Path) -> String {
+    agent_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn set_enabled(store: &ReactiveRuleStore, id: &str, enabled: bool) -> anyhow::Result<String> {
+    if !store.set_enabled(id, enabled)? {
+        anyhow::bail!("rule '{}' not found", id);
+    }
+    let state = if enabled { "enabled" } else { "disabled" };
+    Ok(format!("Rule '{}' {}", id, state))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::rule_actions::{EscalationStep, HttpAction, NotifyAction, RuleAction};
+
+    const TOKEN: &str = "bb_test-token";
+
+    fn request(action: RulesAction, auth_token: Option<&str>) -> RulesRequest {
+        RulesRequest {
+            action,
+            auth_token: auth_token.map(str::to_string),
+        }
+    }
+
+    fn list(dir: &Path) -> Vec<RuleInfo> {
+        let reply = handle_request(
+            dir,
+            request(RulesAction::List { mission_id: None }, None),
+            TOKEN,
+            &HashMap::new(),
+        );
+        assert!(reply.ok, "{}", reply.message);
+        reply.rules
+    }
+
+    #[test]
+    fn rules_lifecycle_over_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let add = RulesAction::Add {
+            mission_id: "stairs".to_string(),
+            predicate: "dog.near_stairs = true".to_string(),
+            debounce_secs: None,
+            hold_secs: Some(5),
+            schedule: None,
+            clear_predicate: None,
+            priority: Some(2),
+            dry_run: Some(true),
+            max_triggers_per_hour: None,
+            escalation: Vec::new(),
+            scope: Default::default(),
+            arousal_boost: Some(3.0),
+            description: "Dog near stairs".to_string(),
+            actions: vec![RuleAction::Http(HttpAction {
+                secret: Some("s3cret".to_string()),
+                ..HttpAction::new("https://example.com/hook")
+            })],
+        };
+
+        let reply = handle_request(
+            dir.path(),
+            request(add.clone(), None),
+            TOKEN,
+            &HashMap::new(),
+        );
+        assert!(!reply.ok);
+        assert!(list(dir.path()).is_empty());
+
+        let reply = handle_request(
+            dir.path(),
+            request(add, Some(TOKEN)),
+            TOKEN,
+            &HashMap::new(),
+        );
+        assert!(reply.ok, "{}", reply.message);
+        let rules = list(dir.path());
+        assert_eq!(rules.len(), 1);
+        let rule = &rules[0];
+        assert!(rule.enabled);
+        assert_eq!(
+            rule.debounce_secs,
+            crate::daemon::reactive::DEFAULT_DEBOUNCE_SECS
+        );
+        assert_eq!(rule.hold_secs, 5);
+        let [RuleAction::Http(http)] = rule.actions.as_slice() else {
+            panic!("expected one http action");
+        };
+        assert_eq!(http.secret.as_deref(), Some("***"));
+        assert_eq!(rule.dangling_fields, vec!["dog.near_stairs".to_string()]);
+
+        let disable = RulesAction::Disable {
+            id: rule.id.clone(),
+        };
+        let reply = handle_request(
+            dir.path(),
+            request(disable, Some(TOKEN)),
+            TOKEN,
+            &HashMap::new(),
+        );
+        assert!(reply.ok, "{}", reply.message);
+        assert!(!list(dir.path())[0].enabled);
+
+        assert!(rule.dry_run);
+        let arm = || RulesAction::Arm {
+            id: rule.id.clone(),
+        };
+        assert!(!handle_request(dir.path(), request(arm(), None), TOKEN, &HashMap::new()).ok);
+        let reply = handle_request(
+            dir.path(),
+            request(arm(), Some(TOKEN)),
+            TOKEN,
+            &HashMap::new(),
+        );
+        assert!(reply.ok, "{}", reply.message);
+        assert!(!list(dir.path())[0].dry_run);
+
+        let remove = |id: &str| RulesAction::Remove { id: id.to_string() };
+        let reply = handle_request(
+            dir.path(),
+            request(remove("alert-missing"), Some(TOKEN)),
+            TOKEN,
+            &HashMap::new(),
+        );
+        assert!(!reply.ok);
+        let reply = handle_request(
+            dir.path(),
+            request(remove(&rule.id), Some(TOKEN)),
+            TOKEN,
+            &HashMap::new(),
+        );
+        assert!(reply.ok, "{}", reply.message);
+        assert!(list(dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_evaluates_against_given_or_current_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let test = |state: Option<&[(&str, &str)]>| RulesAction::Test {
+            id: None,
+            predicate: Some("motion.level > 0.5 AND zone = porch".to_string()),
+            state: state.map(|s| {
+                s.iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect()
+            }),
+        };
+        let world: HashMap<String, String> = [("motion.level".to_string(), "0.9".to_string())]
+            .into_iter()
+            .collect();
+
+        assert!(needs_world_state(&test(None)));
+        let reply = handle_request(dir.path(), request(test(None), None), TOKEN, &world);
+        let result = reply.test.unwrap();
+        assert!(!result.matched);
+        assert_eq!(
+            result.fields,
+            vec![
+                ("motion.level".to_string(), Some("0.9".to_string())),
+                ("zone".to_string(), None),
+            ]
+        );
+
+        let given = test(Some(&[("motion.level", "0.7"), ("zone", "porch")]));
+        assert!(!needs_world_state(&given));
+        let reply = handle_request(dir.path(), request(given, None), TOKEN, &world);
+        assert!(reply.test.unwrap().matched);
+
+        let compound = |armed: &str| RulesAction::Test {
+            id: None,
+            predicate: Some(
+                "motion.level > 0.5 AND (zone = porch OR NOT armed = true)".to_string(),
+            ),
+            state: Some(
+                [("motion.level", "0.7"), ("zone", "yard"), ("armed", armed)]
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ),
+        };
+        let reply = handle_request(dir.path(), request(compound("true"), None), TOKEN, &world);
+        let result = reply.test.unwrap();
+        assert!(!result.matched);
+        assert_eq!(result.fields.len(), 3);
+        let reply = handle_request(dir.path(), request(compound("false"), None), TOKEN, &world);
+        assert!(reply.test.unwrap().matched);
+
+        let unbalanced = RulesAction::Test {
+            id: None,
+            predicate: Some("(motion.level > 0.5".to_string()),
+            state: None,
+        };
+        let reply = handle_request(dir.path(), request(unbalanced, None), TOKEN, &world);
+        assert!(
+            reply.message.contains("does not parse"),
+            "{}",
+            reply.message
+        );
+
+        let missing = RulesAction::Test {
+            id: Some("alert-missing".to_string()),
+            predicate: None,
+            state: None,
+        };
+        assert!(!handle_request(dir.path(), request(missing, None), TOKEN, &world).ok);
+    }
+
+    #[test]
+    fn events_read_the_rule_history_without_a_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = RuleEventStore::open(&dir.path().join("alerts.db")).unwrap();
+        let payload = serde_json::json!({"fields": {"cpu_temp": "91"}});
+        for (rule, at) in [("alert-a", 100), ("alert-b", 200), ("alert-a", 300)] {
+            history
+                .record(rule, "rack", at, &payload, &[], false)
+                .unwrap();
+        }
+        let events = |id: Option<&str>, since: Option<i64>| RulesAction::Events {
+            id: id.map(str::to_string),
+            since,
+            until: None,
+            limit: None,
+        };
+
+        let reply = handle_request(
+            dir.path(),
+            request(events(None, None), None),
+            TOKEN,
+            &HashMap::new(),
+        );
+        assert!(reply.ok, "{}", reply.message);
+        assert_eq!(reply.events.len(), 3);
+        assert_eq!(reply.events[0].fired_at, 300);
+
+        let reply = handle_request(
+            dir.path(),
+            request(events(Some("alert-a"), Some(200)), None),
+            TOKEN,
+            &HashMap::new(),
+        );
+        assert_eq!(reply.events.len(), 1);
+        assert_eq!(reply.events[0].payload["fields"]["cpu_temp"], "91");
+    }
+
+    #[test]
+    fn status_reports_trigger_budget_use_and_escalation() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ReactiveRuleStore::open(&dir.path().join("alerts.db")).unwrap();
+        let rule = RegisterAlertParams {
+            mission_id: "rack".to_string(),
+            predicate: "cpu_temp > 90".to_string(),
+            debounce_secs: None,
+            hold_secs: None,
+            schedule: None,
+            clear_predicate: None,
+            priority: None,
+            dry_run: None,
+            max_triggers_per_hour: Some(2),
+            arousal_boost: None,
+            description: String::new(),
+            actions: None,
+            escalation: Some(vec![EscalationStep {
+                after_secs: 600,
+                actions: vec![RuleAction::Notify(NotifyAction::new("phone"))],
+            }]),
+            scope: None,
+        };
+        store
+            .save_rule(&rule.into_config("alert-hot".into()))
+            .unwrap();
+
+        let history = RuleEventStore::open(&dir.path().join("alerts.db")).unwrap();
+        let now = crate::agent::memory::now_epoch_secs() as i64;
+        let payload = serde_json::json!({});
+        for (at, throttled) in [(now - 7200, true), (now - 60, false), (now - 30, false)] {
+            history
+                .record("alert-hot", "rack", at, &payload, &[], throttled)
+                .unwrap();
+        }
+        history
+            .record("alert-hot", "rack", now - 10, &payload, &[], true)
+            .unwrap();
+        history
+            .record_escalation("alert-hot", "rack", now - 5, &payload, &[], 1)
+            .unwrap();
+        history
+            .set_escalation("alert-hot", now - 700, 1, Some(now - 5))
+            .unwrap();
+
+        let reply = handle_request(
+            dir.path(),
+            request(RulesAction::Status, None),
+            TOKEN,
+            &HashMap::new(),
+        );
+        assert!(reply.ok, "{}", reply.message);
+        assert_eq!(
+            reply.statuses,
+            [RuleStatus {
+                id: "alert-hot".to_string(),
+                mission_id: "rack".to_string(),
+                max_triggers_per_hour: Some(2),
+                triggers_last_hour: 2,
+                throttled_last_hour: 1,
+                last_throttled_at: Some(now - 10),
+                escalation_steps: 1,
+                escalating_since: Some(now - 700),
+                escalation_level: 1,
+                escalated_at: Some(now - 5),
+            }]
+        );
+        assert_eq!(
+            reply.message,
+            "1 rule(s) over budget in the last hour, 1 escalating"
+        );
+    }
+
+    #[test]
+    fn templates_install_and_instantiate() {
+        let dir = tempfile::tempdir().unwrap();
+        let send = |action: RulesAction| {
+            handle_request(
+                dir.path(),
+                request(action, Some(TOKEN)),
+                TOKEN,
+                &HashMap::new(),
+            )
+        };
+        let pack = "name: rack\nversion: '1'\ntemplates:\n  - name: fan-check\n    \
+                    params: { rpm: { default: 500 } }\n    rule: { predicate: \"fan_rpm < ${rpm}\" }\n";
+        let reply = send(RulesAction::InstallPack {
+            pack: pack.to_string(),
+        });
+        assert!(reply.ok, "{}", reply.message);
+        assert!(dir.path().join("rule-packs/rack.yaml").exists());
+
+        let reply = send(RulesAction::Templates);
+        let names: Vec<_> = reply.templates.iter().map(|t| t.name.as_str()).collect();
+        assert!(names.contains(&"high-temperature-shutdown"));
+        assert!(names.contains(&"fan-check"));
+
+        let instantiate = |template: &str, params: &[(&str, &str)]| RulesAction::Instantiate {
+            template: template.to_string(),
+            mission_id: "rack".to_string(),
+            params: params
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            dry_run: Some(true),
+        };
+        let reply = send(instantiate("fan-check", &[("rpm", "300")]));
+        assert!(reply.ok, "{}", reply.message);
+        let reply = send(instantiate("high-temperature-shutdown", &[]));
+        assert!(!reply.ok);
+        assert!(reply.message.contains("'node' is required"));
+
+        let rules = list(dir.path());
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].predicate, "fan_rpm < 300");
+        assert!(rules[0].dry_run);
+    }
+
+    #[test]
+    fn pause_and_resume_are_shared_and_audited() {
+        let home = tempfile::tempdir().unwrap();
+        let rack = home.path().join("agents/rack");
+        let garden = home.path().join("agents/garden");
+        let store = ReactiveRuleStore::open(&rack.join("alerts.db")).unwrap();
+        let rule = RegisterAlertParams {
+            mission_id: "rack".to_string(),
+            predicate: "cpu_temp > 80".to_string(),
+            debounce_secs: None,
+            hold_secs: None,
+            schedule: None,
+            clear_predicate: None,
+            priority: None,
+            dry_run: None,
+            max_triggers_per_hour: None,
+            arousal_boost: None,
+            description: String::new(),
+            actions: None,
+            escalation: None,
+            scope: None,
+        }
+        .into_config("alert-hot".to_string());
+        store.save_rule(&rule).unwrap();
+        let send = |dir: &Path, action: RulesAction, token: Option<&str>| {
+            handle_request(dir, request(action, token), TOKEN, &HashMap::new())
+        };
+        let pause = |id: Option<&str>| RulesAction::Pause {
+            id: id.map(str::to_string),
+            actor: "alice".to_string(),
+            reason: "maintenance".to_string(),
+            until: None,
+        };
+        let resume = |id: Option<&str>| RulesAction::Resume {
+            id: id.map(str::to_string),
+            actor: "bob".to_string(),
+        };
+
+        assert!(
+            !send(&rack, pause(None), None).ok,
+            "pausing needs the token"
+        );
+        let reply = send(&rack, pause(Some("alert-hot")), Some(TOKEN));
+        assert!(reply.ok, "{}", reply.message);
+        assert!(!send(&rack, pause(Some("alert-nope")), Some(TOKEN)).ok);
+        assert!(send(&rack, pause(None), Some(TOKEN)).ok);
+
+        // The global pause shows up for every agent of the machine.
+        let reply = send(&garden, RulesAction::Overrides { limit: None }, None);
+        assert!(reply.ok, "{}", reply.message);
+        assert_eq!(reply.overrides.len(), 2);
+        assert!(overrides::paused_by(&reply.overrides, "garden", "alert-cold").is_some());
+        assert_eq!(
+            overrides::paused_by(&reply.overrides, "rack", "alert-hot").map(Override::by),
+            Some("alice (maintenance)".to_string())
+        );
+
+        let reply = send(&rack, resume(Some("alert-hot")), Some(TOKEN));
+        assert_eq!(
+            reply.message,
+            "Rule 'alert-hot' resumed; all automation is still paused by alice (maintenance)"
+        );
+        assert!(!send(&rack, resume(Some("alert-hot")), Some(TOKEN)).ok);
+        assert!(send(&garden, resume(None), Some(TOKEN)).ok);
+
+        let reply = send(&rack, RulesAction::Overrides { limit: None }, None);
+        assert!(reply.overrides.is_empty());
+        let actors: Vec<_> = reply.audit.iter().map(|a| a.actor.as_str()).collect();
+        assert_eq!(actors, ["bob", "bob", "alice", "alice"]);
+        assert_eq!(reply.audit[1].agent.as_deref(), Some("rack"));
+    }
+}
@@ -15,16 +15,23 @@ use crate::agent::{run_agent_turn, AgentTurnInput, EventSink};
 use crate::daemon::belief_updater::spawn_belief_decay_task;
 use crate::daemon::context_provider::{spawn_provider, ProviderStore};
 use crate::daemon::mission::{watch_missions_dir, Mission, MissionStatus, MissionStore};
+use crate::daemon::overrides::{self, Override, OverrideStore};
 use crate::daemon::reactive::{
-    evaluate_rules_fired, merge_rule_state, total_boost, FiredRule, ReactiveCircuitBreaker,
-    ReactiveRule, ReactiveRuleStore, REACTIVE_BREAKER_COOL_OFF, REACTIVE_BREAKER_THRESHOLD,
+    evaluate_escalations, evaluate_rules_fired, merge_rule_state, total_boost, EscalationEvent,
+    FiredRule, ReactiveCircuitBreaker, ReactiveRule, ReactiveRuleConfig, ReactiveRuleStore,
+    REACTIVE_BREAKER_COOL_OFF, REACTIVE_BREAKER_THRESHOLD,
 };
 use crate::daemon::registry::get_bubbaloop_home;
+use crate::daemon::rule_actions::{self, ActionRunner, RuleAction};
+use crate::daemon::rule_events::{ActionResult, RuleEventStore};
+use crate::daemon::rule_windows::SampleWindows;
+use crate::daemon::rules_file;
 use crate::daemon::world_state_sweeper::spawn_world_state_sweeper;
 use crate::mcp::platform::DaemonPlatform;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::{mpsc, Notify, RwLock};
 
@@ -261,6 +268,7 @@ impl AgentRuntime {
         ));
 
         let mut handles = HashMap::new();
+        let mut rules_file_agents = BTreeMap::new();
 
         for (agent_id, entry) in &config.agents {
             if !entry.enabled {
@@ -464,6 +472,59 @@ impl AgentRuntime {
                 }
             });
 
+            // Register rules queryable so operators can manage reactive rules
+            // from the CLI (`bubbaloop agent rules`) without an LLM client.
+            let rules_topic = gateway::rules_topic(&machine_id, agent_id);
+            let rules_session = session.clone();
+            let rules_backend = memory.backend.clone();
+            let rules_dir = agent_dir.clone();
+            let rules_token = expected_token.clone();
+            tokio::spawn(async move {
+                let queryable = match rules_session.declare_queryable(&rules_topic).await {
+                    Ok(queryable) => queryable,
+                    Err(e) => {
+                        log::warn!(
+                            "[Runtime] Failed to register rules queryable {}: {}",
+                            rules_topic,
+                            e
+                        );
+                        return;
+                    }
+                };
+                while let Ok(query) = queryable.recv_async().await {
+                    let request = query
+                        .payload()
+                        .map(|p| p.to_bytes().to_vec())
+                        .unwrap_or_default();
+                    let reply = match serde_json::from_slice::<gateway::RulesRequest>(&request) {
+                        Ok(request) => {
+                            let world_state =
+                                if crate::agent::rules::needs_world_state(&request.action) {
+                                    let backend = rules_backend.lock().await;
+                                    backend
+                                        .semantic
+                                        .world_state_snapshot_fresh()
+                                        .unwrap_or_default()
+                                        .into_iter()
+                                        .map(|e| (e.key, e.value))
+                                        .collect()
+                                } else {
+                                    HashMap::new()
+                                };
+                            crate::agent::rules::handle_request(
+                                &rules_dir,
+                                request,
+                                &rules_token,
+                                &world_state,
+                            )
+                        }
+                        Err(e) => gateway::RulesReply::error(format!("invalid request: {}", e)),
+                    };
+                    let payload = serde_json::to_vec(&reply).unwrap_or_default();
+                    let _ = query.reply(&rules_topic, payload).await;
+                }
+            });
+
             // Subscribe to this agent's inbox topic so other agents can send messages.
             // Messages are appended to episodic memory and surface in the next prompt turn.
             let inbox_topic = format!("bubbaloop/global/agent/{}/inbox", agent_id);
@@ -601,6 +662,12 @@ impl AgentRuntime {
             let onboarding_marker = agent_dir.join(".needs-onboarding");
             let identity_path = soul_dir.join("identity.md");
 
+            let rules_changed = Arc::new(AtomicBool::new(false));
+            rules_file_agents.insert(
+                agent_id.clone(),
+                (agent_dir.join("alerts.db"), rules_changed.clone()),
+            );
+
             tokio::spawn(agent_loop(
                 agent_id_clone,
                 provider,
@@ -613,6 +680,7 @@ impl AgentRuntime {
                 job_notify,
                 identity_path,
                 onboarding_marker,
+                rules_changed,
             ));
 
             log::info!(
@@ -627,6 +695,14 @@ impl AgentRuntime {
             return Ok(());
         }
 
+        tokio::spawn(rules_file_watcher(
+            session.clone(),
+            machine_id.clone(),
+            rules_file_agents,
+            config.default_agent().map(str::to_string),
+            shutdown_rx.clone(),
+        ));
+
         let runtime = AgentRuntime { handles };
 
         // Subscribe to shared inbox
@@ -716,6 +792,114 @@ impl AgentRuntime {
     }
 }
 
+/// Apply `~/.bubbaloop/rules.yaml` to the agents' rule stores at startup and
+/// after every edit. `agents` maps each agent to its `alerts.db` and a flag
+/// that makes it reload its rules on the next heartbeat tick. A file that
+/// fails to apply changes nothing and is reported as an error event on
+/// every agent's outbox.
+async fn rules_file_watcher(
+    session: Arc<zenoh::Session>,
+    machine_id: String,
+    agents: BTreeMap<String, (PathBuf, Arc<AtomicBool>)>,
+    default_agent: Option<String>,
+    mut shutdown: tokio::sync::watch::Receiver<()>,
+) {
+    use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+    // Editors save in several steps (truncate + write, or write a temp
+    // file and rename it), so wait for them to settle before reading.
+    const SETTLE: std::time::Duration = std::time::Duration::from_millis(250);
+
+    let path = rules_file::path();
+    let stores: BTreeMap<String, PathBuf> = agents
+        .iter()
+        .map(|(id, (db, _))| (id.clone(), db.clone()))
+        .collect();
+    let mut sinks = Vec::with_capacity(agents.len());
+    for id in agents.keys() {
+        match ZenohSink::new(&session, &gateway::outbox_topic(&machine_id, id)).await {
+            Ok(sink) => sinks.push(sink),
+            Err(e) => log::warn!("[RulesFile] No outbox for agent '{}': {}", id, e),
+        }
+    }
+
+    let apply = || async {
+        let result = rules_file::load_from(&path)
+            .and_then(|file| rules_file::apply(&file, &stores, default_agent.as_deref()));
+        match result {
+            Ok((sync, changed)) => {
+                if sync.changed() {
+                    log::info!(
+                        "[RulesFile] Applied {}: {} added, {} updated, {} removed",
+                        path.display(),
+                        sync.added,
+                        sync.updated,
+                        sync.removed
+                    );
+                }
+                for id in changed {
+                    agents[&id].1.store(true, Ordering::Relaxed);
+                }
+            }
+            Err(e) => {
+                log::warn!("[RulesFile] Not applied, keeping the current rules: {}", e);
+                let event = AgentEvent::error("rules-file", &e.to_string());
+                for sink in &sinks {
+                    sink.emit(event.clone()).await;
+                }
+            }
+        }
+    };
+    apply().await;
+
+    let Some(dir) = path.parent().filter(|d| d.exists()) else {
+        let _ = shutdown.changed().await;
+        return;
+    };
+    let (tx, mut rx) = mpsc::channel::<()>(1);
+    let file_name = path.file_name().map(|n| n.to_os_string());
+    let mut watcher = match notify::recommended_watcher(move |res: notify::Result<Event>| {
+        if let Ok(event) = res {
+            let ours = event
+                .paths
+                .iter()
+                .any(|p| p.file_name().map(|n| n.to_os_string()) == file_name);
+            if ours
+                && matches!(
+                    event.kind,
+                    EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_)
+                )
+            {
+                let _ = tx.try_send(());
+            }
+        }
+    }) {
+        Ok(w) => w,
+        Err(e) => {
+            log::warn!("[RulesFile] Failed to create watcher: {}", e);
+            let _ = shutdown.changed().await;
+            return;
+        }
+    };
+    if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+        log::warn!("[RulesFile] Failed to watch {}: {}", dir.display(), e);
+        let _ = shutdown.changed().await;
+        return;
+    }
+    log::info!("[RulesFile] Watching {}", path.display());
+
+    loop {
+        tokio::select! {
+            Some(()) = rx.recv() => {
+                tokio::time::sleep(SETTLE).await;
+                while rx.try_recv().is_ok() {}
+                apply().await;
+            }
+            _ = shutdown.changed() => break,
+        }
+    }
+}
+
 /// Per-agent event loop: processes inbox messages and heartbeat ticks.
 /// Warn once at startup for each reactive rule that references a
 /// world-state field not produced by any registered context provider.
@@ -749,6 +933,195 @@ fn warn_on_dangling_reactive_refs(agent_id: &str, rules: &[ReactiveRule]) {
     }
 }
 
+/// Keep the rules addressed to this machine (see
+/// [`RuleScope`](crate::daemon::reactive::RuleScope)). Nodes are
+/// only listed when a rule is scoped to some; if that fails, those rules
+/// stay off until the next reload.
+async fn rules_in_scope(
+    agent_id: &str,
+    configs: Vec<ReactiveRuleConfig>,
+    machine_id: &str,
+    platform: &Arc<DaemonPlatform>,
+) -> Vec<ReactiveRuleConfig> {
+    use crate::mcp::platform::PlatformOperations;
+
+    let local_nodes: Vec<String> = if configs.iter().any(|c| !c.scope.nodes.is_empty()) {
+        match platform.list_nodes().await {
+            Ok(nodes) => nodes.into_iter().map(|n| n.name).collect(),
+            Err(e) => {
+                log::warn!(
+                    "[Agent:{}] Failed to list nodes for scoped rules: {}",
+                    agent_id,
+                    e
+                );
+                Vec::new()
+            }
+        }
+    } else {
+        Vec::new()
+    };
+    configs
+        .into_iter()
+        .filter(|c| c.scope.applies_to(machine_id, &local_nodes))
+        .collect()
+}
+
+/// The overrides in force now. When they cannot be read, automation goes
+/// on as if there were none: a broken `overrides.db` is logged, it does
+/// not stop every rule of the machine.
+fn active_overrides(agent_id: &str, path: &std::path::Path) -> Vec<Override> {
+    let now = crate::agent::memory::now_epoch_secs() as i64;
+    OverrideStore::open(path)
+        .and_then(|store| store.active(now))
+        .unwrap_or_else(|e| {
+            log::warn!("[Agent:{}] Failed to read rule overrides: {}", agent_id, e);
+            Vec::new()
+        })
+}
+
+/// Pick up the escalations that were under way when the agent stopped,
+/// and forget those of rules that no longer escalate. Best effort like
+/// the rest of the rule history.
+fn restore_escalations(agent_id: &str, db_path: &std::path::Path, rules: &[ReactiveRule]) {
+    let restore = || -> anyhow::Result<()> {
+        let store = RuleEventStore::open(db_path)?;
+        for (id, escalation) in store.escalations()? {
+            match rules
+                .iter()
+                .find(|r| r.id == id && !r.escalation.is_empty())
+            {
+                Some(rule) => {
+                    rule.escalating_since
+                        .store(escalation.since, std::sync::atomic::Ordering::Relaxed);
+                    rule.escalation_level
+                        .store(escalation.level, std::sync::atomic::Ordering::Relaxed);
+                }
+                None => store.clear_escalation(&id)?,
+            }
+        }
+        Ok(())
+    };
+    if let Err(e) = restore() {
+        log::warn!(
+            "[Agent:{}] Failed to restore rule escalations: {}",
+            agent_id,
+            e
+        );
+    }
+}
+
+/// Record escalation changes and run the actions of the steps that came
+/// due, in the background like those of firings. Steps of paused rules
+/// are recorded, not run.
+fn handle_escalations(
+    agent_id: &str,
+    events: Vec<EscalationEvent>,
+    overrides: &[Override],
+    world_state: &HashMap<&str, &str>,
+    db_path: &std::path::Path,
+    runner: &Arc<ActionRunner>,
+    platform: &Arc<DaemonPlatform>,
+) {
+    let store = match RuleEventStore::open(db_path) {
+        Ok(store) => Some(store),
+        Err(e) => {
+            log::warn!(
+                "[Agent:{}] Failed to open rule event history: {}",
+                agent_id,
+                e
+            );
+            None
+        }
+    };
+    let now = crate::agent::memory::now_epoch_secs() as i64;
+    for event in events {
+        let result = match event {
+            EscalationEvent::Started { id, since } => store
+                .as_ref()
+                .map(|s| s.set_escalation(&id, since, 0, None)),
+            EscalationEvent::Resolved { id, level } => {
+                log::info!(
+                    "[Agent:{}] Rule {} stopped matching after {} escalation step(s)",
+                    agent_id,
+                    id,
+                    level
+                );
+                store.as_ref().map(|s| s.clear_escalation(&id))
+            }
+            EscalationEvent::Escalated { mut rule, since } => {
+                rule.paused_by =
+                    overrides::paused_by(overrides, agent_id, &rule.id).map(Override::by);
+                log::warn!(
+                    "[Agent:{}] Rule {} still matches {}s after it fired, escalating (step {})",
+                    agent_id,
+                    rule.id,
+                    now - since,
+                    rule.escalation
+                );
+                let mut payload = rule_actions::payload(&rule, world_state);
+                payload["escalation"] = serde_json::json!({
+                    "step": rule.escalation,
+                    "since": since,
+                });
+                let skipped = if rule.paused_by.is_some() {
+                    rule_actions::paused(&rule)
+                } else if rule.dry_run {
+                    rule_actions::dry_run(&rule)
+                } else {
+                    Vec::new()
+                };
+                let event_id = store.as_ref().and_then(|s| {
+                    s.set_escalation(&rule.id, since, rule.escalation, Some(now))
+                        .and_then(|()| {
+                            s.record_escalation(
+                                &rule.id,
+                                &rule.mission_id,
+                                now,
+                                &payload,
+                                &skipped,
+                                rule.escalation,
+                            )
+                        })
+                        .map_err(|e| {
+                            log::warn!(
+                                "[Agent:{}] Failed to record escalation of rule {}: {}",
+                                agent_id,
+                                rule.id,
+                                e
+                            )
+                        })
+                        .ok()
+                });
+                if !rule.dry_run && rule.paused_by.is_none() {
+                    let (runner, platform) = (runner.clone(), platform.clone());
+                    let db_path = db_path.to_path_buf();
+                    tokio::spawn(async move {
+                        let results = runner.run(&rule, &payload, &*platform).await;
+                        let Some(id) = event_id else { return };
+                        if let Err(e) = RuleEventStore::open(&db_path)
+                            .and_then(|store| store.set_results(id, &results))
+                        {
+                            log::warn!(
+                                "[Rules] Failed to record escalation results of rule {}: {}",
+                                rule.id,
+                                e
+                            );
+                        }
+                    });
+                }
+                None
+            }
+        };
+        if let Some(Err(e)) = result {
+            log::warn!(
+                "[Agent:{}] Failed to record rule escalation: {}",
+                agent_id,
+                e
+            );
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn agent_loop(
     agent_id: String,
@@ -762,16 +1135,24 @@ async fn agent_loop(
     job_notify: Arc<Notify>,
     identity_path: std::path::PathBuf,
     onboarding_marker: std::path::PathBuf,
+    rules_changed: Arc<AtomicBool>,
 ) {
     let initial_caps = soul.read().await.capabilities.clone();
     let mut arousal = ArousalState::new(&initial_caps);
 
     // Phase 3: load reactive rules for arousal integration.
     let alerts_db_path = agent_directory(&agent_id).join("alerts.db");
-    let mut reactive_rules: Vec<ReactiveRule> = ReactiveRuleStore::open(&alerts_db_path)
-        .and_then(|s| s.list_rules())
-        .map(|configs| configs.into_iter().map(Into::into).collect())
+    let overrides_path = overrides::path(&agent_directory(&agent_id));
+    let machine_id = crate::daemon::util::get_machine_id();
+    let configs = ReactiveRuleStore::open(&alerts_db_path)
+        .and_then(|s| s.list_enabled_rules())
         .unwrap_or_default();
+    let mut reactive_rules: Vec<ReactiveRule> =
+        rules_in_scope(&agent_id, configs, &machine_id, dispatcher.platform())
+            .await
+            .into_iter()
+            .map(Into::into)
+            .collect();
     let mut tick_count: u64 = 0;
     if !reactive_rules.is_empty() {
         log::info!(
@@ -781,6 +1162,10 @@ async fn agent_loop(
         );
         warn_on_dangling_reactive_refs(&agent_id, &reactive_rules);
     }
+    restore_escalations(&agent_id, &alerts_db_path, &reactive_rules);
+    let action_runner = Arc::new(ActionRunner::new());
+    // Recent values of the keys rules aggregate, e.g. `avg(cpu_load, 5m)`.
+    let mut sample_windows = SampleWindows::default();
 
     // Rate limiting: minimum 2 seconds between LLM turns to prevent abuse.
     const MIN_TURN_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
@@ -808,7 +1193,8 @@ async fn agent_loop(
     // How often to hot-reload reactive rules from disk (in heartbeat ticks).
     // Trade-off: shorter = faster visibility for newly-registered rules but
     // more DB traffic; longer = cheaper but laggier. 10 ticks ≈ up to 10×
-    // current heartbeat interval (~5s–60s depending on arousal).
+    // current heartbeat interval (~5s–60s depending on arousal). Edits to
+    // `rules.yaml` set `rules_changed` and reload on the next tick instead.
     const REACTIVE_RULE_RELOAD_INTERVAL: u64 = 10;
 
     // Cache onboarding state in memory — avoids a syscall on every inbox message.
@@ -921,39 +1307,38 @@ async fn agent_loop(
 
             // Phase 3: evaluate reactive rules against world state.
             tick_count += 1;
-            if tick_count.is_multiple_of(REACTIVE_RULE_RELOAD_INTERVAL) {
-                match ReactiveRuleStore::open(&alerts_db_path) {
-                    Ok(store) => match store.list_rules() {
-                        Ok(configs) => {
-                            // Reload without wiping debounce state: `From<ReactiveRuleConfig>`
-                            // zero-inits every rule's `last_fired_at`, so a naive
-                            // `configs.into_iter().map(Into::into).collect()` would cause
-                            // every previously-fired rule to refire on the next matching
-                            // tick — a bug that, in the 2026-04-10 incident, turned a
-                            // 1-alert-per-hour rule into a continuous firestorm every
-                            // reload cycle. `merge_rule_state` copies `last_fired_at`
-                            // forward for rules whose id survived the reload; new rules
-                            // stay at 0 (correct), deleted rules vanish (correct).
-                            let freshly_loaded: Vec<ReactiveRule> =
-                                configs.into_iter().map(Into::into).collect();
-                            reactive_rules = merge_rule_state(&reactive_rules, freshly_loaded);
-                        }
-                        Err(e) => {
-                            // Don't wipe the in-memory rule set on a transient read
-                            // error — keep the last known-good set and surface the
-                            // failure so the operator sees why new rules aren't
-                            // being picked up.
-                            log::warn!(
-                                "[Agent:{}] Reactive rule reload failed (list): {} — keeping {} cached rule(s)",
-                                agent_id,
-                                e,
-                                reactive_rules.len()
-                            );
-                        }
-                    },
+            if rules_changed.swap(false, Ordering::Relaxed)
+                || tick_count.is_multiple_of(REACTIVE_RULE_RELOAD_INTERVAL)
+            {
+                // Opened and read in one go: the store must not be held
+                // across the await below.
+                let loaded =
+                    ReactiveRuleStore::open(&alerts_db_path).and_then(|s| s.list_enabled_rules());
+                match loaded {
+                    Ok(configs) => {
+                        let configs =
+                            rules_in_scope(&agent_id, configs, &machine_id, dispatcher.platform())
+                                .await;
+                        // Reload without wiping debounce state: `From<ReactiveRuleConfig>`
+                        // zero-inits every rule's `last_fired_at`, so a naive
+                        // `configs.into_iter().map(Into::into).collect()` would cause
+                        // every previously-fired rule to refire on the next matching
+                        // tick — a bug that, in the 2026-04-10 incident, turned a
+                        // 1-alert-per-hour rule into a continuous firestorm every
+                        // reload cycle. `merge_rule_state` copies `last_fired_at`
+                        // forward for rules whose id survived the reload; new rules
+                        // stay at 0 (correct), deleted rules vanish (correct).
+                        let freshly_loaded: Vec<ReactiveRule> =
+                            configs.into_iter().map(Into::into).collect();
+                        reactive_rules = merge_rule_state(&reactive_rules, freshly_loaded);
+                    }
                     Err(e) => {
+                        // Don't wipe the in-memory rule set on a transient read
+                        // error — keep the last known-good set and surface the
+                        // failure so the operator sees why new rules aren't
+                        // being picked up.
                         log::warn!(
-                            "[Agent:{}] Reactive rule store open failed: {} — keeping {} cached rule(s)",
+                            "[Agent:{}] Reactive rule reload failed: {} — keeping {} cached rule(s)",
                             agent_id,
                             e,
                             reactive_rules.len()
@@ -980,11 +1365,37 @@ async fn agent_loop(
                         .world_state_snapshot_fresh()
                         .unwrap_or_default()
                 };
+                sample_windows.track(&reactive_rules);
+                sample_windows.record(
+                    ws_entries
+                        .iter()
+                        .map(|e| (e.key.as_str(), e.value.as_str(), e.last_seen_at)),
+                );
+                let aggregates = sample_windows.values(
+                    &reactive_rules,
+                    crate::agent::memory::now_epoch_secs() as i64,
+                );
                 let ws_map: HashMap<&str, &str> = ws_entries
                     .iter()
                     .map(|e| (e.key.as_str(), e.value.as_str()))
+                    .chain(aggregates.iter().map(|(k, v)| (k.as_str(), v.as_str())))
                     .collect();
                 fired_this_tick = evaluate_rules_fired(&reactive_rules, &ws_map);
+                // Rules a human paused fire and are recorded, nothing more.
+                let escalating = reactive_rules
+                    .iter()
+                    .any(|r| r.escalating_since.load(Ordering::Relaxed) != 0);
+                let overrides = if fired_this_tick.is_empty() && !escalating {
+                    Vec::new()
+                } else {
+                    active_overrides(&agent_id, &overrides_path)
+                };
+                for rule in &mut fired_this_tick {
+                    if let Some(o) = overrides::paused_by(&overrides, &agent_id, &rule.id) {
+                        rule.paused_by = Some(o.by());
+                        rule.boost = 0.0;
+                    }
+                }
                 let boost = total_boost(&fired_this_tick);
                 if boost > 0.0 {
                     arousal.add_external_boost(boost);
@@ -995,6 +1406,112 @@ async fn agent_loop(
                         fired_this_tick.len()
                     );
                 }
+                // Rules that fire together must not fight over a node.
+                let suppressed = rule_actions::resolve_conflicts(&mut fired_this_tick);
+                for s in &suppressed {
+                    log::warn!(
+                        "[Agent:{}] Rule {} action {} suppressed: rule {} has priority",
+                        agent_id,
+                        s.rule,
+                        RuleAction::Node(s.action.clone()).summary(),
+                        s.winner
+                    );
+                }
+                // Every firing goes into the rule event history; the outcomes
+                // of its actions are filled in once they have run.
+                let events = (!fired_this_tick.is_empty())
+                    .then(|| RuleEventStore::open(&alerts_db_path))
+                    .transpose()
+                    .unwrap_or_else(|e| {
+                        log::warn!(
+                            "[Agent:{}] Failed to open rule event history: {}",
+                            agent_id,
+                            e
+                        );
+                        None
+                    });
+                let fired_at = crate::agent::memory::now_epoch_secs() as i64;
+                for rule in &fired_this_tick {
+                    let payload = rule_actions::payload(rule, &ws_map);
+                    let mut skipped: Vec<ActionResult> = suppressed
+                        .iter()
+                        .filter(|s| s.rule == rule.id)
+                        .map(|s| ActionResult {
+                            action: RuleAction::Node(s.action.clone()).summary(),
+                            ok: false,
+                            outcome: format!("suppressed: rule {} has priority", s.winner),
+                        })
+                        .collect();
+                    if rule.paused_by.is_some() {
+                        skipped.extend(rule_actions::paused(rule));
+                    } else if rule.throttled {
+                        skipped.extend(rule_actions::over_budget(rule));
+                    } else if rule.dry_run {
+                        skipped.extend(rule_actions::dry_run(rule));
+                    }
+                    let event_id = events.as_ref().and_then(|store| {
+                        store
+                            .record(
+                                &rule.id,
+                                &rule.mission_id,
+                                fired_at,
+                                &payload,
+                                &skipped,
+                                rule.throttled,
+                            )
+                            .map_err(|e| {
+                                log::warn!(
+                                    "[Agent:{}] Failed to record firing of rule {}: {}",
+                                    agent_id,
+                                    rule.id,
+                                    e
+                                )
+                            })
+                            .ok()
+                    });
+                    if rule.dry_run
+                        || rule.throttled
+                        || rule.paused_by.is_some()
+                        || rule.actions.is_empty()
+                    {
+                        continue;
+                    }
+                    // Rule actions run in the background: a slow or
+                    // unreachable webhook must never hold up the heartbeat.
+                    let (runner, rule) = (action_runner.clone(), rule.clone());
+                    let platform = dispatcher.platform().clone();
+                    let db_path = alerts_db_path.clone();
+                    tokio::spawn(async move {
+                        let mut results = skipped;
+                        results.extend(runner.run(&rule, &payload, &*platform).await);
+                        let Some(id) = event_id else { return };
+                        if let Err(e) = RuleEventStore::open(&db_path)
+                            .and_then(|store| store.set_results(id, &results))
+                        {
+                            log::warn!(
+                                "[Rules] Failed to record action results of rule {}: {}",
+                                rule.id,
+                                e
+                            );
+                        }
+                    });
+                }
+                // A condition that outlasts the actions of its rule escalates.
+                let escalations = evaluate_escalations(&reactive_rules, &fired_this_tick, &ws_map);
+                if !escalations.is_empty() {
+                    handle_escalations(
+                        &agent_id,
+                        escalations,
+                        &overrides,
+                        &ws_map,
+                        &alerts_db_path,
+                        &action_runner,
+                        dispatcher.platform(),
+                    );
+                }
+                // Rules in dry-run mode, over their trigger budget or paused
+                // are logged and recorded, nothing more.
+                fired_this_tick.retain(|r| !r.dry_run && !r.throttled && r.paused_by.is_none());
             }
 
             // If rules fired and the reactive-turn debounce allows it, wake the
@@ -1221,9 +1738,16 @@ fn build_reactive_prompt(fired: &[FiredRule]) -> String {
         } else {
             r.description.clone()
         };
+        let trigger = match (&r.schedule, r.predicate.trim()) {
+            (Some(schedule), "") => format!("schedule=`{}`", schedule),
+            (Some(schedule), predicate) => {
+                format!("schedule=`{}` predicate=`{}`", schedule, predicate)
+            }
+            (None, predicate) => format!("predicate=`{}`", predicate),
+        };
         out.push_str(&format!(
-            "- {} [mission={}] predicate=`{}` — {}\n",
-            r.id, r.mission_id, r.predicate, desc
+            "- {} [mission={}] {} — {}\n",
+            r.id, r.mission_id, trigger, desc
         ));
     }
     out
@@ -1380,15 +1904,43 @@ provider = "ollama"
                 id: "r1".to_string(),
                 mission_id: "patrol".to_string(),
                 predicate: "motion.level > 0.05".to_string(),
+                schedule: None,
                 description: "Motion detected on terrace".to_string(),
+                priority: 0,
+                dry_run: false,
+                throttled: false,
+                escalation: 0,
+                paused_by: None,
                 boost: 3.0,
+                actions: Vec::new(),
             },
             FiredRule {
                 id: "r2".to_string(),
                 mission_id: "patrol".to_string(),
                 predicate: "dog.near_stairs = 'true'".to_string(),
+                schedule: None,
                 description: String::new(),
+                priority: 0,
+                dry_run: false,
+                throttled: false,
+                escalation: 0,
+                paused_by: None,
                 boost: 2.5,
+                actions: Vec::new(),
+            },
+            FiredRule {
+                id: "r3".to_string(),
+                mission_id: "patrol".to_string(),
+                predicate: String::new(),
+                schedule: Some("0 22 * * *".to_string()),
+                description: "Stop the cameras for the night".to_string(),
+                priority: 0,
+                dry_run: false,
+                throttled: false,
+                escalation: 0,
+                paused_by: None,
+                boost: 2.0,
+                actions: Vec::new(),
             },
         ];
         let prompt = build_reactive_prompt(&fired);
@@ -1399,7 +1951,8 @@ provider = "ollama"
         assert!(prompt.contains("Motion detected on terrace"));
         assert!(prompt.contains("r2"));
         assert!(prompt.contains("(no description)"));
-        assert!(prompt.contains("motion.level > 0.05"));
+        assert!(prompt.contains("predicate=`motion.level > 0.05`"));
+        assert!(prompt.contains("r3 [mission=patrol] schedule=`0 22 * * *` —"));
         // Boost value is implementation detail — kept out of the prompt to
         // avoid confusing the LLM with internal tuning parameters.
         assert!(!prompt.contains("boost="));
@@ -1420,8 +1973,15 @@ provider = "ollama"
             id: "r1".to_string(),
             mission_id: "m1".to_string(),
             predicate: "p".to_string(),
+            schedule: None,
             description: long_desc,
+            priority: 0,
+            dry_run: false,
+            throttled: false,
+            escalation: 0,
+            paused_by: None,
             boost: 1.0,
+            actions: Vec::new(),
         }];
         let prompt = build_reactive_prompt(&fired);
         assert!(prompt.contains("… (truncated)"));
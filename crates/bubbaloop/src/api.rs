@@ -207,6 +207,7 @@ fn parse_command(s: &str) -> Option<NodeCommand> {
         "clean" => Some(NodeCommand::Clean),
         "enable" => Some(NodeCommand::EnableAutostart),
         "disable" => Some(NodeCommand::DisableAutostart),
+        "update" => Some(NodeCommand::Update),
         _ => None,
     }
 }
@@ -242,6 +243,7 @@ mod tests {
             parse_command("disable"),
             Some(NodeCommand::DisableAutostart)
         ));
+        assert!(matches!(parse_command("update"), Some(NodeCommand::Update)));
     }
 
     #[test]
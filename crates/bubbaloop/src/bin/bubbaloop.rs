@@ -1,7 +1,9 @@
 //! Bubbaloop CLI - Unified command-line interface
 //!
 //! Usage:
-//!   bubbaloop status [-f format]       # Show services status
+//!   bubbaloop setup                    # First-run setup: zenohd, daemon, starter nodes
+//!   bubbaloop status [-f format]       # Show services status (--json for JSON)
+//!   bubbaloop status --watch           # Live status, re-rendered on daemon events
 //!   bubbaloop doctor                   # Run all system diagnostics
 //!   bubbaloop doctor -c zenoh          # Check Zenoh connectivity only
 //!   bubbaloop doctor -c daemon         # Check daemon health only
@@ -12,16 +14,27 @@
 //!   bubbaloop node start <name>        # Start a node
 //!   bubbaloop node stop <name>         # Stop a node
 //!   bubbaloop node logs <name>         # View node logs
+//!   bubbaloop skill add <file.yaml>   # Validate and add a sensor skill
+//!   bubbaloop topic echo <key>         # Print decoded messages with rate/size
+//!   bubbaloop topic pub <key> --json   # Publish a test message
+//!   bubbaloop topic hz <key>           # Measure rate and jitter
+//!   bubbaloop topic bw <key>           # Measure bandwidth and payload sizes
+//!   bubbaloop record start --topics <k> # Start the mcap-recorder
+//!   bubbaloop play <file> --rate 2x    # Replay a recording
+//!   bubbaloop completions <shell>      # Print a bash/zsh/fish completion script
 //!   bubbaloop debug topics             # List active Zenoh topics
 //!   bubbaloop debug subscribe <key>    # Subscribe to Zenoh topic
 //!   bubbaloop debug query <key>        # Query Zenoh endpoint
 //!   bubbaloop debug info               # Show Zenoh connection info
+//!   bubbaloop --machine <id> node list # Address another machine's daemon
 
 use argh::FromArgs;
+use bubbaloop::cli::exit;
 use bubbaloop::cli::launch::LaunchCommand;
 use bubbaloop::cli::{
-    AgentCommand, DaemonCommand, DataflowCommand, DebugCommand, LoginCommand, LogoutCommand,
-    MarketplaceCommand, NodeCommand, UpCommand,
+    AgentCommand, BenchCommand, CompletionsCommand, DaemonCommand, DataflowCommand, DebugCommand,
+    LoginCommand, LogoutCommand, MarketplaceCommand, NodeCommand, PlayCommand, RecordCommand,
+    SchemaCommand, SelfUpdateCommand, SetupCommand, SkillCommand, TopicCommand, UpCommand,
 };
 
 /// Bubbaloop - AI-native orchestration for Physical AI
@@ -31,16 +44,26 @@ struct Args {
     #[argh(switch, short = 'V')]
     version: bool,
 
+    /// machine whose daemon and nodes to address (default: this machine)
+    #[argh(option)]
+    machine: Option<String>,
+
+    /// key space for node key expressions: global or local (default: global)
+    #[argh(option)]
+    scope: Option<String>,
+
     #[argh(subcommand)]
     command: Option<Command>,
 }
 
+#[allow(clippy::large_enum_variant)]
 #[derive(FromArgs)]
 #[argh(subcommand)]
 enum Command {
     Agent(AgentCommand),
     Login(LoginCommand),
     Logout(LogoutCommand),
+    Setup(SetupCommand),
     Status(StatusArgs),
     Doctor(DoctorArgs),
     Daemon(DaemonCommand),
@@ -48,10 +71,18 @@ enum Command {
     Node(NodeCommand),
     Launch(LaunchCommand),
     Marketplace(MarketplaceCommand),
+    Topic(TopicCommand),
+    Record(RecordCommand),
+    Play(PlayCommand),
+    Bench(BenchCommand),
+    Schema(SchemaCommand),
     Debug(DebugCommand),
     Up(UpCommand),
+    Skill(SkillCommand),
     Dataflow(DataflowCommand),
     InitTls(InitTlsArgs),
+    SelfUpdate(SelfUpdateCommand),
+    Completions(CompletionsCommand),
 }
 
 /// Show services status (non-interactive)
@@ -61,6 +92,18 @@ struct StatusArgs {
     /// output format: table, json, yaml (default: table)
     #[argh(option, short = 'f', default = "String::from(\"table\")")]
     format: String,
+
+    /// output as JSON (same as -f json)
+    #[argh(switch)]
+    json: bool,
+
+    /// keep running and re-render on daemon events and node heartbeats
+    #[argh(switch, short = 'w')]
+    watch: bool,
+
+    /// refresh interval in seconds for --watch (default: 2)
+    #[argh(option, short = 'n', default = "2")]
+    interval: u64,
 }
 
 /// Print TLS/mTLS certificate generation guide
@@ -190,27 +233,95 @@ async fn try_zenoh_session(endpoint: Option<&str>) -> Option<std::sync::Arc<zeno
 }
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+async fn main() {
     // Initialize logging — warn level by default; subcommands may override via init_logger().
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn"))
         .target(env_logger::Target::Stderr)
         .init();
 
-    let args: Args = argh::from_env();
+    if let Err(e) = run(parse_args()).await {
+        eprintln!("Error: {}", e);
+        std::process::exit(exit::code_for(e.as_ref()));
+    }
+}
+
+/// Like `argh::from_env`, but with the usage exit code for bad arguments.
+fn parse_args() -> Args {
+    let strings: Vec<String> = std::env::args().collect();
+    let cmd = std::path::Path::new(&strings[0])
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or(&strings[0]);
+    let mut rest: Vec<&str> = strings[1..].iter().map(String::as_str).collect();
+    rewrite_legacy_launch(&mut rest);
+    match Args::from_args(&[cmd], &rest) {
+        Ok(args) => args,
+        Err(early) => {
+            let code = match early.status {
+                Ok(()) => {
+                    println!("{}", early.output);
+                    exit::SUCCESS
+                }
+                Err(()) => {
+                    eprintln!("{}\nRun {} --help for more information.", early.output, cmd);
+                    exit::USAGE
+                }
+            };
+            std::process::exit(code);
+        }
+    }
+}
+
+/// Turn the deprecated `launch <node> <file>` into `launch instance
+/// <node> <file>`, with a warning.
+fn rewrite_legacy_launch(args: &mut Vec<&str>) {
+    let mut i = 0;
+    while i < args.len() {
+        match args[i] {
+            "--machine" | "--scope" => i += 2,
+            flag if flag.starts_with('-') => i += 1,
+            _ => break,
+        }
+    }
+    if args.get(i) != Some(&"launch") {
+        return;
+    }
+    if let Some(launch_args) = bubbaloop::cli::launch::legacy_instance_args(&args[i + 1..]) {
+        eprintln!(
+            "Warning: 'bubbaloop launch <node> <file>' is deprecated, use 'bubbaloop launch instance <node> <file>'"
+        );
+        args.truncate(i + 1);
+        args.extend(launch_args);
+    }
+}
 
+async fn run(args: Args) -> Result<(), Box<dyn std::error::Error>> {
     // Handle --version flag
     if args.version {
         println!("bubbaloop {}", env!("CARGO_PKG_VERSION"));
         return Ok(());
     }
 
+    if args.machine.is_some() || args.scope.is_some() {
+        match bubbaloop::cli::target::Target::new(
+            args.machine,
+            args.scope.as_deref().unwrap_or("global"),
+        ) {
+            Ok(target) => bubbaloop::cli::target::set(target),
+            Err(e) => return Err(Box::new(e)),
+        }
+    }
+
     match args.command {
         // No subcommand = show help
         None => {
             eprintln!("Bubbaloop - AI-native orchestration for Physical AI\n");
-            eprintln!("Usage: bubbaloop <command>\n");
+            eprintln!("Usage: bubbaloop [--machine <id>] [--scope global|local] <command>\n");
             eprintln!("Commands:");
+            eprintln!("  setup     First-run setup: zenohd and daemon services, starter nodes");
+            eprintln!("              -y, --yes: Accept defaults; --machine-id <id>; --no-nodes");
             eprintln!("  status    Show services status (non-interactive)");
+            eprintln!("              --json: Output as JSON; -w, --watch: Live view");
             eprintln!("  doctor    Run system diagnostics and health checks");
             eprintln!("              --json: Output as JSON");
             eprintln!("              -c, --check <type>: all|zenoh|daemon (default: all)");
@@ -231,7 +342,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             eprintln!("              install, uninstall, start, stop, restart");
             eprintln!("              logs, build");
             eprintln!("  launch    Launch node instances from a YAML file");
-            eprintln!("              (default: ~/.bubbaloop/launch.yaml)");
+            eprintln!(
+                "              start <file> [--arg name:=value]: Run a multi-node launch file"
+            );
+            eprintln!("              plan <file> [-f text|dot|mermaid]: Show the start order");
+            eprintln!("              validate <file>: Check a launch file for problems");
+            eprintln!("              instance <node> <file>: Register a single instance");
+            eprintln!("              list [--all], status <name>: Inspect running launches");
+            eprintln!("              reload <name>: Apply launch file changes to a running launch");
+            eprintln!("              stop <name>: Stop a running launch");
             eprintln!("  marketplace  Manage marketplace sources:");
             eprintln!("              list, add, remove, enable, disable");
             eprintln!("  login     Authenticate with Anthropic API:");
@@ -244,9 +363,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             eprintln!("  up        Load skills and ensure sensor nodes are running:");
             eprintln!("              -s, --skills-dir <path>: Skills directory");
             eprintln!("              --dry-run: Show what would be done");
+            eprintln!("  skill     Manage sensor skills (YAML driver configs):");
+            eprintln!("              add <file> [--apply], list, drivers");
+            eprintln!("              apply [name] [--dry-run], remove <name>");
+            eprintln!("  topic     Inspect data on Zenoh topics:");
+            eprintln!(
+                "              echo <key> [--type T] [-n N] [--json]: Print decoded messages"
+            );
+            eprintln!("              pub <key> --json <msg> [--proto T] [--rate HZ]: Publish test messages");
+            eprintln!("              hz <key> [-w SECS]: Measure rate and jitter");
+            eprintln!("              bw <key> [-w SECS]: Measure bandwidth and payload sizes");
+            eprintln!("  record    Control the mcap-recorder node:");
+            eprintln!("              start [--topics K]... [-d 10m] [-o name], stop, status");
+            eprintln!("  play      Replay a recording: play <file> [--rate 2x] [--loop]");
             eprintln!("  debug     Debug Zenoh connectivity:");
             eprintln!("              info, topics, query, subscribe");
             eprintln!("  init-tls  Print TLS/mTLS certificate generation guide");
+            eprintln!("  completions  Print a shell completion script: bash|zsh|fish");
             eprintln!("\nRun 'bubbaloop <command> --help' for more information.");
             return Ok(());
         }
@@ -260,8 +393,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 eprintln!("Error: {}", e);
             }
         }
+        Some(Command::Setup(cmd)) => {
+            init_logger("warn,zenoh=warn");
+            cmd.run()
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        }
+        Some(Command::Status(status_args)) if status_args.watch => {
+            init_logger("warn,zenoh=warn");
+            let interval = std::time::Duration::from_secs(status_args.interval.max(1));
+            bubbaloop::cli::status::watch(interval).await?;
+        }
         Some(Command::Status(status_args)) => {
-            bubbaloop::cli::status::run(&status_args.format).await?;
+            let format = if status_args.json {
+                "json"
+            } else {
+                &status_args.format
+            };
+            bubbaloop::cli::status::run(format).await?;
         }
         Some(Command::Doctor(args)) => {
             bubbaloop::cli::doctor::run(args.fix, args.json, &args.check).await?;
@@ -283,7 +432,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     init_logger("warn,zenoh=warn");
                     let Some(session) = try_zenoh_session(cmd.zenoh_endpoint.as_deref()).await
                     else {
-                        return Ok(());
+                        std::process::exit(exit::DAEMON_UNREACHABLE);
                     };
                     bubbaloop::cli::daemon_client::run_daemon_stop(session).await?;
                 }
@@ -300,7 +449,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     init_logger("warn,zenoh=warn");
                     let Some(session) = try_zenoh_session(cmd.zenoh_endpoint.as_deref()).await
                     else {
-                        return Ok(());
+                        std::process::exit(exit::DAEMON_UNREACHABLE);
                     };
                     bubbaloop::cli::daemon_client::run_daemon_status(session).await?;
                 }
@@ -311,7 +460,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     init_logger("warn,zenoh=warn");
                     let Some(session) = try_zenoh_session(cmd.zenoh_endpoint.as_deref()).await
                     else {
-                        return Ok(());
+                        std::process::exit(exit::DAEMON_UNREACHABLE);
                     };
                     bubbaloop::cli::daemon_client::run_daemon_fix(session).await?;
                 }
@@ -335,6 +484,47 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .await
                 .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
         }
+        Some(Command::Topic(cmd)) => {
+            init_logger("warn,zenoh=warn");
+            cmd.run()
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        }
+        Some(Command::Record(cmd)) => {
+            init_logger("warn,zenoh=warn");
+            cmd.run()
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        }
+        Some(Command::Play(cmd)) => {
+            init_logger("warn,zenoh=warn");
+            cmd.run()
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        }
+        Some(Command::Bench(cmd)) => {
+            init_logger("warn,zenoh=warn");
+            cmd.run()
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        }
+        Some(Command::Schema(cmd)) => {
+            init_logger("warn,zenoh=warn");
+            cmd.run()
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        }
+        Some(Command::SelfUpdate(cmd)) => {
+            cmd.run()
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        }
+        Some(Command::Completions(cmd)) => {
+            init_logger("off");
+            cmd.run()
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        }
         Some(Command::Debug(cmd)) => {
             cmd.run()
                 .await
@@ -386,7 +576,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
             // Create Zenoh client session for all agent subcommands
             let Some(session) = try_zenoh_session(cmd.zenoh_endpoint.as_deref()).await else {
-                return Ok(());
+                std::process::exit(exit::DAEMON_UNREACHABLE);
             };
 
             let local_machine_id = bubbaloop::daemon::util::get_machine_id();
@@ -395,7 +585,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             if !bubbaloop::cli::agent_client::is_daemon_running(&session, &local_machine_id).await {
                 eprintln!("Error: Daemon is not running.");
                 eprintln!("  Start it with: bubbaloop daemon start");
-                return Ok(());
+                std::process::exit(exit::DAEMON_UNREACHABLE);
             }
 
             match cmd.subcommand {
@@ -428,6 +618,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                         eprintln!("Error: {}", e);
                     }
                 }
+                bubbaloop::cli::agent::AgentSubcommand::Rules(rules_cmd) => {
+                    let (agent, target_machine) =
+                        parse_agent_target(rules_cmd.agent.as_deref(), &local_machine_id);
+                    let (agent, target_machine) =
+                        (agent.map(str::to_string), target_machine.to_string());
+                    if let Err(e) = bubbaloop::cli::agent_rules::run_agent_rules(
+                        session,
+                        &target_machine,
+                        agent.as_deref(),
+                        rules_cmd,
+                    )
+                    .await
+                    {
+                        eprintln!("Error: {}", e);
+                        std::process::exit(exit::code_for(&e));
+                    }
+                }
                 bubbaloop::cli::agent::AgentSubcommand::Setup(_) => unreachable!(),
             }
         }
@@ -436,6 +643,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 .await
                 .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
         }
+        Some(Command::Skill(cmd)) => {
+            init_logger("warn,zenoh=warn");
+            cmd.run()
+                .await
+                .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        }
         Some(Command::Dataflow(cmd)) => {
             init_logger("warn,zenoh=warn");
             cmd.run().await?;
@@ -516,6 +729,37 @@ mod tests {
         assert_eq!(machine, "local_host");
     }
 
+    #[test]
+    fn legacy_launch_gets_instance_inserted() {
+        let mut args = vec![
+            "--machine",
+            "jetson",
+            "launch",
+            "rtsp-camera",
+            "entrance.yaml",
+        ];
+        rewrite_legacy_launch(&mut args);
+        assert_eq!(
+            args,
+            [
+                "--machine",
+                "jetson",
+                "launch",
+                "instance",
+                "rtsp-camera",
+                "entrance.yaml"
+            ]
+        );
+
+        let mut args = vec!["launch", "start", "garage.launch.yaml"];
+        rewrite_legacy_launch(&mut args);
+        assert_eq!(args, ["launch", "start", "garage.launch.yaml"]);
+
+        let mut args = vec!["node", "launch", "x"];
+        rewrite_legacy_launch(&mut args);
+        assert_eq!(args, ["node", "launch", "x"]);
+    }
+
     #[test]
     fn parse_agent_target_none() {
         let (agent, machine) = parse_agent_target(None, "local_host");
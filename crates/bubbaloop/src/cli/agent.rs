@@ -12,11 +12,14 @@ pub struct AgentCommand {
     pub subcommand: AgentSubcommand,
 }
 
+// Parsed once per invocation; argh cannot box subcommands.
+#[allow(clippy::large_enum_variant)]
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand)]
 pub enum AgentSubcommand {
     Chat(ChatCommand),
     List(ListCommand),
+    Rules(RulesCommand),
     Setup(SetupCommand),
 }
 
@@ -54,3 +57,323 @@ pub struct ListCommand {
     #[argh(switch)]
     pub all: bool,
 }
+
+/// Manage an agent's reactive rules (same as the MCP alert tools)
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "rules")]
+pub struct RulesCommand {
+    /// target agent ID, optionally as agent@machine (default: the default agent)
+    #[argh(option, short = 'a')]
+    pub agent: Option<String>,
+
+    #[argh(subcommand)]
+    pub action: RulesAction,
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand)]
+pub enum RulesAction {
+    List(RulesListArgs),
+    Add(RulesAddArgs),
+    Remove(RulesRemoveArgs),
+    Enable(RulesEnableArgs),
+    Disable(RulesDisableArgs),
+    Arm(RulesArmArgs),
+    Test(RulesTestArgs),
+    Templates(RulesTemplatesArgs),
+    Instantiate(RulesInstantiateArgs),
+    InstallPack(RulesInstallPackArgs),
+    Events(RulesEventsArgs),
+    Status(RulesStatusArgs),
+    Pause(RulesPauseArgs),
+    Resume(RulesResumeArgs),
+    Overrides(RulesOverridesArgs),
+}
+
+/// List rules with their mission, predicate and whether they are enabled
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "list")]
+pub struct RulesListArgs {
+    /// only list the rules of this mission
+    #[argh(option, short = 'm')]
+    pub mission: Option<String>,
+
+    /// output as JSON
+    #[argh(switch)]
+    pub json: bool,
+}
+
+/// Register a rule that raises the agent's arousal when its predicate matches
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "add")]
+pub struct RulesAddArgs {
+    /// mission the rule belongs to
+    #[argh(option, short = 'm')]
+    pub mission: String,
+
+    /// predicate over world state, e.g. "motion.level > 0.5 AND NOT zone = yard"
+    /// (optional with --schedule)
+    #[argh(positional, default = "String::new()")]
+    pub predicate: String,
+
+    /// minimum seconds between two firings (default: 60)
+    #[argh(option)]
+    pub debounce: Option<u32>,
+
+    /// seconds the predicate must keep matching before the rule fires (default: 0)
+    #[argh(option)]
+    pub hold: Option<u32>,
+
+    /// cron expression in UTC that triggers the rule, e.g. "0 22 * * *"
+    #[argh(option)]
+    pub schedule: Option<String>,
+
+    /// predicate that must match before the rule can fire again, e.g. "cpu_temp < 75"
+    #[argh(option)]
+    pub clear: Option<String>,
+
+    /// node command to run when the rule fires, as "command:node", e.g. "restart:camera" (repeatable)
+    #[argh(option)]
+    pub node_command: Vec<String>,
+
+    /// wins over lower-priority rules that give the same node a different command (default: 0)
+    #[argh(option)]
+    pub priority: Option<i32>,
+
+    /// log the actions the rule would run instead of running them, until `rules arm`
+    #[argh(switch)]
+    pub dry_run: bool,
+
+    /// most firings an hour that run the rule's actions; further ones are skipped and logged
+    #[argh(option)]
+    pub max_per_hour: Option<u32>,
+
+    /// URL to POST the firing to as JSON
+    #[argh(option)]
+    pub webhook: Option<String>,
+
+    /// extra webhook header as "Name: value" (repeatable)
+    #[argh(option)]
+    pub webhook_header: Vec<String>,
+
+    /// bearer token for the webhook, or env:NAME to read it from the agent's environment
+    #[argh(option)]
+    pub webhook_secret: Option<String>,
+
+    /// notification channel from ~/.bubbaloop/notifications.yaml (repeatable)
+    #[argh(option)]
+    pub notify: Vec<String>,
+
+    /// notification message template, e.g. "CPU at {fields.cpu_temp}C" (default: the description)
+    #[argh(option)]
+    pub message: Option<String>,
+
+    /// YAML or JSON file with the steps of a workflow to run when the rule fires (repeatable)
+    #[argh(option)]
+    pub workflow: Vec<String>,
+
+    /// action to run when the predicate still holds a while after the rule fired, as
+    /// "after=action" with action notify:channel, webhook:url or command:node,
+    /// e.g. "10m=notify:phone" (repeatable)
+    #[argh(option)]
+    pub escalate: Vec<String>,
+
+    /// only evaluate the rule on machines whose ID matches, e.g. "jetson-*" (repeatable)
+    #[argh(option)]
+    pub machine: Vec<String>,
+
+    /// only evaluate the rule on machines with a matching node, and only let its node
+    /// actions target matching nodes, e.g. "camera-*" (repeatable)
+    #[argh(option)]
+    pub node: Vec<String>,
+
+    /// arousal added when the rule fires (default: 2.0)
+    #[argh(option)]
+    pub boost: Option<f64>,
+
+    /// human-readable description
+    #[argh(option, short = 'd', default = "String::new()")]
+    pub description: String,
+}
+
+/// Delete a rule
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "remove")]
+pub struct RulesRemoveArgs {
+    /// rule ID (see `agent rules list`)
+    #[argh(positional)]
+    pub id: String,
+}
+
+/// Enable a disabled rule
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "enable")]
+pub struct RulesEnableArgs {
+    /// rule ID (see `agent rules list`)
+    #[argh(positional)]
+    pub id: String,
+}
+
+/// Stop evaluating a rule without deleting it
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "disable")]
+pub struct RulesDisableArgs {
+    /// rule ID (see `agent rules list`)
+    #[argh(positional)]
+    pub id: String,
+}
+
+/// Run the actions of a rule added with --dry-run
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "arm")]
+pub struct RulesArmArgs {
+    /// rule ID (see `agent rules list`)
+    #[argh(positional)]
+    pub id: String,
+}
+
+/// Evaluate a rule or predicate without firing it
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "test")]
+pub struct RulesTestArgs {
+    /// rule ID to test (or use --predicate)
+    #[argh(positional)]
+    pub id: Option<String>,
+
+    /// predicate to test instead of a stored rule
+    #[argh(option, short = 'p')]
+    pub predicate: Option<String>,
+
+    /// world state entry as key=value, repeatable (default: the agent's current world state)
+    #[argh(option, short = 's')]
+    pub state: Vec<String>,
+
+    /// output as JSON
+    #[argh(switch)]
+    pub json: bool,
+}
+
+/// List the rule templates of the built-in and installed rule packs
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "templates")]
+pub struct RulesTemplatesArgs {
+    /// output as JSON
+    #[argh(switch)]
+    pub json: bool,
+}
+
+/// Register a rule from a template
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "instantiate")]
+pub struct RulesInstantiateArgs {
+    /// template name, or pack/template (see `agent rules templates`)
+    #[argh(positional)]
+    pub template: String,
+
+    /// mission the rule belongs to
+    #[argh(option, short = 'm')]
+    pub mission: String,
+
+    /// template parameter as name=value, repeatable
+    #[argh(option, short = 'p')]
+    pub param: Vec<String>,
+
+    /// log the actions the rule would run instead of running them, until `rules arm`
+    #[argh(switch)]
+    pub dry_run: bool,
+}
+
+/// Install a rule pack on the agent's machine
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "install-pack")]
+pub struct RulesInstallPackArgs {
+    /// rule pack YAML file, https:// URL, or name in the marketplace registry
+    #[argh(positional)]
+    pub source: String,
+
+    /// use the cached marketplace registry without refreshing it
+    #[argh(switch)]
+    pub offline: bool,
+}
+
+/// Show the rule event history: when rules fired and what their actions did
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "events")]
+pub struct RulesEventsArgs {
+    /// only show the firings of this rule
+    #[argh(positional)]
+    pub id: Option<String>,
+
+    /// only show firings since this long ago (90s, 10m, 1h, 2d) or RFC 3339 time
+    #[argh(option)]
+    pub since: Option<String>,
+
+    /// only show firings up to this long ago (90s, 10m, 1h, 2d) or RFC 3339 time
+    #[argh(option)]
+    pub until: Option<String>,
+
+    /// most events to show, newest first (default: 100)
+    #[argh(option, short = 'n')]
+    pub limit: Option<u32>,
+
+    /// output as JSON
+    #[argh(switch)]
+    pub json: bool,
+}
+
+/// Show how much of its trigger budget each rule used in the last hour
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "status")]
+pub struct RulesStatusArgs {
+    /// output as JSON
+    #[argh(switch)]
+    pub json: bool,
+}
+
+/// Pause a rule, or all rule automation on the machine, until `rules resume`
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "pause")]
+pub struct RulesPauseArgs {
+    /// rule ID to pause (default: every rule of every agent on the machine)
+    #[argh(positional)]
+    pub id: Option<String>,
+
+    /// why, for the audit trail
+    #[argh(option, short = 'r')]
+    pub reason: Option<String>,
+
+    /// resume by itself after this long (90s, 10m, 1h, 2d)
+    #[argh(option, long = "for")]
+    pub duration: Option<String>,
+
+    /// who pauses, for the audit trail (default: $USER)
+    #[argh(option)]
+    pub by: Option<String>,
+}
+
+/// Lift a pause set with `rules pause`
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "resume")]
+pub struct RulesResumeArgs {
+    /// rule ID to resume (default: lift the machine-wide pause)
+    #[argh(positional)]
+    pub id: Option<String>,
+
+    /// who resumes, for the audit trail (default: $USER)
+    #[argh(option)]
+    pub by: Option<String>,
+}
+
+/// Show the pauses in force and who set and cleared pauses
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "overrides")]
+pub struct RulesOverridesArgs {
+    /// most audit entries to show, newest first (default: 100)
+    #[argh(option, short = 'n')]
+    pub limit: Option<u32>,
+
+    /// output as JSON
+    #[argh(switch)]
+    pub json: bool,
+}
@@ -185,12 +185,14 @@ use futures::StreamExt;
 use ratatui::{
     backend::CrosstermBackend,
     layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
     Terminal,
 };
 
+use crate::cli::tui_config::{Action, Theme, TuiConfig};
+
 /// Output line kinds — drive colour rendering.
 #[derive(Clone)]
 enum OutputLine {
@@ -220,12 +222,16 @@ impl Drop for TerminalGuard {
 ///
 /// Top panel: scrolling output.
 /// Bottom panel (3 rows): input prompt, always visible.
+/// Colours and scroll keys come from `~/.bubbaloop/tui.yaml`.
 async fn run_tui_repl(
     session: &Arc<Session>,
     machine_id: &str,
     agent: Option<&str>,
     verbose: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    // Read before raw mode so a broken file is reported on a normal terminal
+    let TuiConfig { theme, keys } = crate::cli::tui_config::load()?;
+
     // ── Terminal setup ────────────────────────────────────────────────────────
     enable_raw_mode()?;
     let mut stdout = std::io::stdout();
@@ -280,7 +286,7 @@ async fn run_tui_repl(
             // Build coloured output lines
             let items: Vec<ListItem> = output
                 .iter()
-                .flat_map(|line| render_output_line(line))
+                .flat_map(|line| render_output_line(line, &theme))
                 .collect();
 
             // Auto-scroll: 0 means "follow bottom"; otherwise clamp to max
@@ -299,11 +305,11 @@ async fn run_tui_repl(
             let output_widget = List::new(visible).block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::DarkGray))
+                    .border_style(Style::default().fg(theme.border))
                     .title(Span::styled(
                         " output ",
                         Style::default()
-                            .fg(Color::Cyan)
+                            .fg(theme.agent_name)
                             .add_modifier(Modifier::BOLD),
                     )),
             );
@@ -312,28 +318,28 @@ async fn run_tui_repl(
             // Input prompt
             let prompt_prefix = if waiting_for_agent { "⏳ " } else { "▸ " };
             let prompt_style = if waiting_for_agent {
-                Style::default().fg(Color::DarkGray)
+                Style::default().fg(theme.border)
             } else {
                 Style::default()
-                    .fg(Color::Green)
+                    .fg(theme.prompt)
                     .add_modifier(Modifier::BOLD)
             };
             let input_paragraph = Paragraph::new(Line::from(vec![
                 Span::styled(prompt_prefix, prompt_style),
-                Span::styled(input.as_str(), Style::default().fg(Color::White)),
+                Span::styled(input.as_str(), Style::default().fg(theme.user)),
             ]))
             .wrap(Wrap { trim: false })
             .block(
                 Block::default()
                     .borders(Borders::ALL)
                     .border_style(if waiting_for_agent {
-                        Style::default().fg(Color::DarkGray)
+                        Style::default().fg(theme.border)
                     } else {
-                        Style::default().fg(Color::Green)
+                        Style::default().fg(theme.prompt)
                     })
                     .title(Span::styled(
                         " type a message ",
-                        Style::default().fg(Color::DarkGray),
+                        Style::default().fg(theme.border),
                     )),
             );
             frame.render_widget(input_paragraph, input_area);
@@ -345,6 +351,15 @@ async fn run_tui_repl(
             maybe_event = event_stream.next() => {
                 match maybe_event {
                     Some(Ok(Event::Key(key))) => {
+                        if let Some(action) = keys.action(key.code, key.modifiers) {
+                            scroll_offset = match action {
+                                Action::ScrollUp => scroll_offset.saturating_add(1),
+                                Action::ScrollDown => scroll_offset.saturating_sub(1),
+                                Action::PageUp => scroll_offset.saturating_add(10),
+                                Action::PageDown => scroll_offset.saturating_sub(10),
+                            };
+                            continue;
+                        }
                         match (key.code, key.modifiers) {
                             (KeyCode::Char('c'), KeyModifiers::CONTROL) |
                             (KeyCode::Char('q'), KeyModifiers::NONE) if input.is_empty() => {
@@ -385,18 +400,6 @@ async fn run_tui_repl(
                             (KeyCode::Char(c), _) => {
                                 input.push(c);
                             }
-                            (KeyCode::Up, _) => {
-                                scroll_offset = scroll_offset.saturating_add(1);
-                            }
-                            (KeyCode::Down, _) => {
-                                scroll_offset = scroll_offset.saturating_sub(1);
-                            }
-                            (KeyCode::PageUp, _) => {
-                                scroll_offset = scroll_offset.saturating_add(10);
-                            }
-                            (KeyCode::PageDown, _) => {
-                                scroll_offset = scroll_offset.saturating_sub(10);
-                            }
                             _ => {}
                         }
                     }
@@ -567,54 +570,59 @@ fn styled_item(text: String, style: Style) -> ListItem<'static> {
 }
 
 /// Convert an `OutputLine` into one or more ratatui `ListItem`s with colour styling.
-fn render_output_line(line: &OutputLine) -> Vec<ListItem<'static>> {
+fn render_output_line(line: &OutputLine, theme: &Theme) -> Vec<ListItem<'static>> {
     match line {
         OutputLine::UserMessage(text) => {
-            let style = Style::default()
-                .fg(Color::White)
-                .add_modifier(Modifier::BOLD);
+            let style = Style::default().fg(theme.user).add_modifier(Modifier::BOLD);
             vec![styled_item(format!("You  {}", text), style)]
         }
         OutputLine::AgentHeader(id) => {
             let style = Style::default()
-                .fg(Color::Cyan)
+                .fg(theme.agent_name)
                 .add_modifier(Modifier::BOLD);
             vec![styled_item(format!("╾── {} ──╼", id), style)]
         }
         OutputLine::AgentDelta(text) => {
-            let style = Style::default().fg(Color::Green);
+            let style = Style::default().fg(theme.agent);
             text.split('\n')
                 .map(|chunk| styled_item(chunk.to_string(), style))
                 .collect()
         }
         OutputLine::ToolCall(label) => {
             let style = Style::default()
-                .fg(Color::Yellow)
+                .fg(theme.tool_call)
                 .add_modifier(Modifier::ITALIC);
             vec![styled_item(label.clone(), style)]
         }
         OutputLine::ToolResult(text) => {
-            vec![styled_item(text.clone(), Style::default().fg(Color::Gray))]
+            vec![styled_item(
+                text.clone(),
+                Style::default().fg(theme.tool_result),
+            )]
         }
         OutputLine::ErrorLine(text) => {
-            let style = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+            let style = Style::default()
+                .fg(theme.error)
+                .add_modifier(Modifier::BOLD);
             vec![styled_item(text.clone(), style)]
         }
         OutputLine::Separator => {
             vec![styled_item(
                 "─".repeat(60),
-                Style::default().fg(Color::DarkGray),
+                Style::default().fg(theme.border),
             )]
         }
         OutputLine::Info(text) => {
             let style = Style::default()
-                .fg(Color::Blue)
+                .fg(theme.info)
                 .add_modifier(Modifier::ITALIC);
             vec![styled_item(text.clone(), style)]
         }
         OutputLine::SystemInfo(text) => {
             // Dim blue-gray — visible but not distracting
-            let style = Style::default().fg(Color::Cyan).add_modifier(Modifier::DIM);
+            let style = Style::default()
+                .fg(theme.system)
+                .add_modifier(Modifier::DIM);
             vec![styled_item(text.clone(), style)]
         }
     }
@@ -0,0 +1,860 @@
+//! `bubbaloop agent rules` — manage an agent's reactive rules from the terminal.
+//!
+//! Talks to the agent's rules queryable (see [`crate::agent::rules`]), so
+//! operators and scripts get the same control as the MCP alert tools without
+//! an LLM client. Changes carry the token from `~/.bubbaloop/mcp-token`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use thiserror::Error;
+use zenoh::query::QueryTarget;
+use zenoh::Session;
+
+use crate::agent::gateway::{self, AgentManifest, RuleInfo, RulesAction, RulesReply, RulesRequest};
+use crate::cli::agent::{RulesAction as RulesArgs, RulesCommand};
+use crate::daemon::overrides::OverrideChange;
+use crate::daemon::reactive::RuleScope;
+use crate::daemon::rule_actions::{
+    EscalationStep, HttpAction, NodeAction, NotifyAction, RuleAction, WorkflowAction, WorkflowStep,
+};
+use crate::daemon::rule_events::RuleEvent;
+use crate::daemon::rule_templates::TemplateInfo;
+use crate::registry;
+
+/// How long to wait for the agent to answer a rules query.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Error)]
+pub enum AgentRulesError {
+    #[error("Zenoh error: {0}")]
+    Zenoh(String),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("No reply from {0} - is the agent running?")]
+    NoReply(String),
+    #[error("{0}")]
+    Rejected(String),
+}
+
+pub type Result<T> = std::result::Result<T, AgentRulesError>;
+
+/// Run a `rules` subcommand against `agent` on `machine_id` (the default
+/// agent of that machine when `agent` is `None`).
+pub async fn run_agent_rules(
+    session: Arc<Session>,
+    machine_id: &str,
+    agent: Option<&str>,
+    cmd: RulesCommand,
+) -> Result<()> {
+    let agent_id = match agent {
+        Some(agent) => agent.to_string(),
+        None => default_agent(&session, machine_id).await?,
+    };
+    let key = gateway::rules_topic(machine_id, &agent_id);
+
+    match cmd.action {
+        RulesArgs::List(args) => {
+            let action = RulesAction::List {
+                mission_id: args.mission,
+            };
+            let reply = send(&session, &key, action).await?;
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&reply.rules)?);
+            } else {
+                print_rules(&reply);
+            }
+        }
+        RulesArgs::Add(args) => {
+            let mut actions =
+                webhook_actions(args.webhook, &args.webhook_header, args.webhook_secret)?;
+            actions.extend(notify_actions(args.notify, args.message)?);
+            actions.extend(node_actions(&args.node_command)?);
+            actions.extend(workflow_actions(&args.workflow)?);
+            let action = RulesAction::Add {
+                mission_id: args.mission,
+                predicate: args.predicate,
+                debounce_secs: args.debounce,
+                hold_secs: args.hold,
+                schedule: args.schedule,
+                clear_predicate: args.clear,
+                priority: args.priority,
+                dry_run: args.dry_run.then_some(true),
+                max_triggers_per_hour: args.max_per_hour,
+                arousal_boost: args.boost,
+                description: args.description,
+                actions,
+                escalation: escalation_steps(&args.escalate)?,
+                scope: RuleScope {
+                    machines: args.machine,
+                    nodes: args.node,
+                },
+            };
+            println!("{}", send(&session, &key, action).await?.message);
+        }
+        RulesArgs::Remove(args) => {
+            let action = RulesAction::Remove { id: args.id };
+            println!("{}", send(&session, &key, action).await?.message);
+        }
+        RulesArgs::Enable(args) => {
+            let action = RulesAction::Enable { id: args.id };
+            println!("{}", send(&session, &key, action).await?.message);
+        }
+        RulesArgs::Disable(args) => {
+            let action = RulesAction::Disable { id: args.id };
+            println!("{}", send(&session, &key, action).await?.message);
+        }
+        RulesArgs::Arm(args) => {
+            let action = RulesAction::Arm { id: args.id };
+            println!("{}", send(&session, &key, action).await?.message);
+        }
+        RulesArgs::Test(args) => {
+            let action = test_action(args.id, args.predicate, &args.state)?;
+            let reply = send(&session, &key, action).await?;
+            let Some(result) = reply.test else {
+                return Err(AgentRulesError::Rejected(
+                    "agent sent no test result".to_string(),
+                ));
+            };
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&result)?);
+            } else {
+                println!("{}: {}", result.predicate, reply.message);
+                for (field, value) in &result.fields {
+                    println!("  {:<30} {}", field, value.as_deref().unwrap_or("(absent)"));
+                }
+            }
+        }
+        RulesArgs::Templates(args) => {
+            let reply = send(&session, &key, RulesAction::Templates).await?;
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&reply.templates)?);
+            } else {
+                print_templates(&reply.templates);
+            }
+        }
+        RulesArgs::Instantiate(args) => {
+            let action = RulesAction::Instantiate {
+                template: args.template,
+                mission_id: args.mission,
+                params: parse_pairs("parameter", &args.param)?,
+                dry_run: args.dry_run.then_some(true),
+            };
+            println!("{}", send(&session, &key, action).await?.message);
+        }
+        RulesArgs::InstallPack(args) => {
+            let pack = read_pack(&args.source, args.offline)?;
+            let action = RulesAction::InstallPack { pack };
+            println!("{}", send(&session, &key, action).await?.message);
+        }
+        RulesArgs::Events(args) => {
+            let now = chrono::Utc::now().timestamp();
+            let action = RulesAction::Events {
+                id: args.id,
+                since: args
+                    .since
+                    .map(|t| parse_time("--since", &t, now))
+                    .transpose()?,
+                until: args
+                    .until
+                    .map(|t| parse_time("--until", &t, now))
+                    .transpose()?,
+                limit: args.limit,
+            };
+            let reply = send(&session, &key, action).await?;
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&reply.events)?);
+            } else {
+                print_events(&reply.events);
+            }
+        }
+        RulesArgs::Status(args) => {
+            let reply = send(&session, &key, RulesAction::Status).await?;
+            if args.json {
+                println!("{}", serde_json::to_string_pretty(&reply.statuses)?);
+            } else {
+                print_statuses(&reply);
+            }
+        }
+        RulesArgs::Pause(args) => {
+            let now = chrono::Utc::now().timestamp();
+            let until = args
+                .duration
+                .map(|d| {
+                    parse_duration(&d).map(|secs| now + secs).ok_or_else(|| {
+                        AgentRulesError::InvalidArgument(format!(
+                            "invalid --for '{}' (expected e.g. 10m, 1h or 2d)",
+                            d
+                        ))
+                    })
+                })
+                .transpose()?;
+            let action = RulesAction::Pause {
+                id: args.id,
+                actor: actor(args.by),
+                reason: args.reason.unwrap_or_default(),
+                until,
+            };
+            println!("{}", send(&session, &key, action).await?.message);
+        }
+        RulesArgs::Resume(args) => {
+            let action = RulesAction::Resume {
+                id: args.id,
+                actor: actor(args.by),
+            };
+            println!("{}", send(&session, &key, action).await?.message);
+        }
+        RulesArgs::Overrides(args) => {
+            let action = RulesAction::Overrides { limit: args.limit };
+            let reply = send(&session, &key, action).await?;
+            if args.json {
+                let json = serde_json::json!({
+                    "overrides": reply.overrides,
+                    "audit": reply.audit,
+                });
+                println!("{}", serde_json::to_string_pretty(&json)?);
+            } else {
+                print_overrides(&reply);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Who the audit trail names: `--by`, or the logged-in user.
+fn actor(by: Option<String>) -> String {
+    by.or_else(|| std::env::var("USER").ok())
+        .filter(|by| !by.trim().is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Build a `test` action from the CLI arguments.
+fn test_action(
+    id: Option<String>,
+    predicate: Option<String>,
+    state: &[String],
+) -> Result<RulesAction> {
+    if id.is_some() == predicate.is_some() {
+        return Err(AgentRulesError::InvalidArgument(
+            "give either a rule ID or --predicate".to_string(),
+        ));
+    }
+    let state = if state.is_empty() {
+        None
+    } else {
+        Some(parse_state(state)?)
+    };
+    Ok(RulesAction::Test {
+        id,
+        predicate,
+        state,
+    })
+}
+
+/// Parse `key=value` world state entries.
+fn parse_state(entries: &[String]) -> Result<HashMap<String, String>> {
+    parse_pairs("state entry", entries)
+}
+
+/// Parse `key=value` entries; `what` names them in errors.
+fn parse_pairs(what: &str, entries: &[String]) -> Result<HashMap<String, String>> {
+    entries
+        .iter()
+        .map(|entry| match entry.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.trim().to_string()))
+            }
+            _ => Err(AgentRulesError::InvalidArgument(format!(
+                "{} '{}' is not key=value",
+                what, entry
+            ))),
+        })
+        .collect()
+}
+
+/// The YAML of a rule pack from a file, a URL or the marketplace registry.
+fn read_pack(source: &str, offline: bool) -> Result<String> {
+    let path = std::path::Path::new(source);
+    if path.is_file() {
+        return std::fs::read_to_string(path)
+            .map_err(|e| AgentRulesError::InvalidArgument(format!("{}: {}", source, e)));
+    }
+    let url = if source.starts_with("https://") || source.starts_with("http://") {
+        source.to_string()
+    } else {
+        let loaded = registry::load_registry(offline || registry::offline_from_env());
+        if let Some(warning) = loaded.warning() {
+            eprintln!("warning: {}", warning);
+        }
+        let pack = registry::load_cached_rule_packs()
+            .into_iter()
+            .find(|p| p.name == source)
+            .ok_or_else(|| {
+                AgentRulesError::InvalidArgument(format!(
+                    "'{}' is neither a file nor a rule pack in the marketplace registry",
+                    source
+                ))
+            })?;
+        registry::rule_pack_url(&pack).map_err(AgentRulesError::InvalidArgument)?
+    };
+    crate::marketplace::download_text(&url)
+        .map_err(|e| AgentRulesError::InvalidArgument(format!("{}: {}", url, e)))
+}
+
+/// `90s`, `10m`, `1h`, `2d` ago, or an RFC 3339 time, to seconds since epoch.
+fn parse_time(flag: &str, time: &str, now: i64) -> Result<i64> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(time) {
+        return Ok(time.timestamp());
+    }
+    parse_duration(time).map(|secs| now - secs).ok_or_else(|| {
+        AgentRulesError::InvalidArgument(format!(
+            "invalid {} '{}' (expected e.g. 10m, 1h, 2d or 2026-01-01T08:00:00Z)",
+            flag, time
+        ))
+    })
+}
+
+/// Seconds in a positive duration such as "90s", "10m", "1h" or "2d".
+fn parse_duration(text: &str) -> Option<i64> {
+    let (number, unit) = text.split_at(text.len().saturating_sub(1));
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        _ => return None,
+    };
+    number
+        .parse::<i64>()
+        .ok()
+        .filter(|n| *n > 0)
+        .and_then(|n| n.checked_mul(unit_secs))
+}
+
+/// Find the default agent of `machine_id` from the agent manifests.
+async fn default_agent(session: &Session, machine_id: &str) -> Result<String> {
+    let pattern = gateway::manifest_wildcard(machine_id);
+    let replies = session
+        .get(&pattern)
+        .timeout(Duration::from_secs(3))
+        .await
+        .map_err(|e| AgentRulesError::Zenoh(e.to_string()))?;
+    while let Ok(reply) = replies.recv_async().await {
+        if let Ok(sample) = reply.into_result() {
+            let bytes = sample.payload().to_bytes();
+            if let Ok(manifest) = serde_json::from_slice::<AgentManifest>(&bytes) {
+                if manifest.is_default {
+                    return Ok(manifest.agent_id);
+                }
+            }
+        }
+    }
+    Err(AgentRulesError::NoReply(pattern))
+}
+
+async fn send(session: &Session, key: &str, action: RulesAction) -> Result<RulesReply> {
+    let auth_token = if action.is_mutation() {
+        crate::mcp::auth::load_or_generate_token().ok()
+    } else {
+        None
+    };
+    let payload = serde_json::to_vec(&RulesRequest { action, auth_token })?;
+    let replies = session
+        .get(key)
+        .payload(payload)
+        .target(QueryTarget::BestMatching)
+        .timeout(QUERY_TIMEOUT)
+        .await
+        .map_err(|e| AgentRulesError::Zenoh(e.to_string()))?;
+    let reply = replies
+        .recv_async()
+        .await
+        .map_err(|_| AgentRulesError::NoReply(key.to_string()))?;
+    let sample = reply.into_result().map_err(|e| {
+        AgentRulesError::Rejected(String::from_utf8_lossy(&e.payload().to_bytes()).into_owned())
+    })?;
+    let reply: RulesReply = serde_json::from_slice(&sample.payload().to_bytes())?;
+    if !reply.ok {
+        return Err(AgentRulesError::Rejected(reply.message));
+    }
+    Ok(reply)
+}
+
+/// The `http` action given by `--webhook` and its options, if any.
+fn webhook_actions(
+    url: Option<String>,
+    headers: &[String],
+    secret: Option<String>,
+) -> Result<Vec<RuleAction>> {
+    let Some(url) = url else {
+        if !headers.is_empty() || secret.is_some() {
+            return Err(AgentRulesError::InvalidArgument(
+                "--webhook-header and --webhook-secret need --webhook".to_string(),
+            ));
+        }
+        return Ok(Vec::new());
+    };
+    let mut http = HttpAction::new(url);
+    for header in headers {
+        let Some((name, value)) = header.split_once(':') else {
+            return Err(AgentRulesError::InvalidArgument(format!(
+                "webhook header '{}' is not 'Name: value'",
+                header
+            )));
+        };
+        http.headers
+            .insert(name.trim().to_string(), value.trim().to_string());
+    }
+    http.secret = secret;
+    Ok(vec![RuleAction::Http(http)])
+}
+
+/// One `notify` action per `--notify` channel, all with `--message`.
+fn notify_actions(channels: Vec<String>, message: Option<String>) -> Result<Vec<RuleAction>> {
+    if channels.is_empty() && message.is_some() {
+        return Err(AgentRulesError::InvalidArgument(
+            "--message needs --notify".to_string(),
+        ));
+    }
+    Ok(channels
+        .into_iter()
+        .map(|channel| {
+            RuleAction::Notify(NotifyAction {
+                message: message.clone(),
+                ..NotifyAction::new(channel)
+            })
+        })
+        .collect())
+}
+
+/// One `node` action per `--node-command command:node`.
+fn node_actions(commands: &[String]) -> Result<Vec<RuleAction>> {
+    commands
+        .iter()
+        .map(|entry| {
+            let (command, node) = entry.split_once(':').ok_or_else(|| {
+                AgentRulesError::InvalidArgument(format!(
+                    "node command '{}' is not 'command:node'",
+                    entry
+                ))
+            })?;
+            let command = command
+                .trim()
+                .parse()
+                .map_err(AgentRulesError::InvalidArgument)?;
+            Ok(RuleAction::Node(NodeAction::new(node.trim(), command)))
+        })
+        .collect()
+}
+
+/// One `workflow` action per `--workflow` file, each a YAML or JSON list
+/// of steps.
+fn workflow_actions(paths: &[String]) -> Result<Vec<RuleAction>> {
+    paths
+        .iter()
+        .map(|path| {
+            let text = std::fs::read_to_string(path).map_err(|e| {
+                AgentRulesError::InvalidArgument(format!("cannot read {}: {}", path, e))
+            })?;
+            let steps: Vec<WorkflowStep> = serde_yaml::from_str(&text).map_err(|e| {
+                AgentRulesError::InvalidArgument(format!("{} is not a list of steps: {}", path, e))
+            })?;
+            Ok(RuleAction::Workflow(WorkflowAction { steps }))
+        })
+        .collect()
+}
+
+/// Escalation steps from `--escalate after=action` entries. Entries with
+/// the same delay make up one step, and steps are ordered by delay.
+fn escalation_steps(entries: &[String]) -> Result<Vec<EscalationStep>> {
+    let invalid = |entry: &str, why: &str| {
+        AgentRulesError::InvalidArgument(format!("escalation '{}' {}", entry, why))
+    };
+    let mut steps: Vec<EscalationStep> = Vec::new();
+    for entry in entries {
+        let (after, action) = entry
+            .split_once('=')
+            .ok_or_else(|| invalid(entry, "is not 'after=action'"))?;
+        let after_secs = parse_duration(after.trim())
+            .and_then(|secs| u32::try_from(secs).ok())
+            .ok_or_else(|| invalid(entry, "needs a delay such as 10m or 1h"))?;
+        let (kind, target) = action
+            .split_once(':')
+            .ok_or_else(|| invalid(entry, "needs notify:channel, webhook:url or command:node"))?;
+        let (kind, target) = (kind.trim(), target.trim().to_string());
+        let action = match kind {
+            "notify" => RuleAction::Notify(NotifyAction::new(target)),
+            "webhook" => RuleAction::Http(HttpAction::new(target)),
+            command => {
+                let command = command.parse().map_err(AgentRulesError::InvalidArgument)?;
+                RuleAction::Node(NodeAction::new(target, command))
+            }
+        };
+        match steps.iter_mut().find(|s| s.after_secs == after_secs) {
+            Some(step) => step.actions.push(action),
+            None => steps.push(EscalationStep {
+                after_secs,
+                actions: vec![action],
+            }),
+        }
+    }
+    steps.sort_by_key(|s| s.after_secs);
+    Ok(steps)
+}
+
+/// The predicate, prefixed with the schedule of a scheduled rule and
+/// followed by the clear predicate, if any.
+fn trigger(rule: &RuleInfo) -> String {
+    let trigger = match (&rule.schedule, rule.predicate.trim()) {
+        (Some(schedule), "") => format!("cron \"{}\"", schedule),
+        (Some(schedule), predicate) => format!("cron \"{}\" if {}", schedule, predicate),
+        (None, predicate) => predicate.to_string(),
+    };
+    match &rule.clear_predicate {
+        Some(clear) => format!("{} (clears when {})", trigger, clear),
+        None => trigger,
+    }
+}
+
+fn print_rules(reply: &RulesReply) {
+    if reply.rules.is_empty() {
+        println!("No rules registered.");
+        return;
+    }
+    println!(
+        "{:<44} {:<16} {:<8} {:<9} {:<6} {:<6} {:<5} TRIGGER",
+        "ID", "MISSION", "ENABLED", "DEBOUNCE", "HOLD", "BOOST", "PRIO",
+    );
+    println!("{}", "-".repeat(123));
+    for rule in &reply.rules {
+        println!(
+            "{:<44} {:<16} {:<8} {:<9} {:<6} {:<6} {:<5} {}",
+            rule.id,
+            rule.mission_id,
+            if rule.enabled { "yes" } else { "no" },
+            format!("{}s", rule.debounce_secs),
+            format!("{}s", rule.hold_secs),
+            rule.arousal_boost,
+            rule.priority,
+            trigger(rule),
+        );
+        for action in &rule.actions {
+            println!("{:<44} then {}", "", action.summary());
+        }
+        if rule.dry_run {
+            println!("{:<44} dry run: actions are logged, not run", "");
+        }
+        for step in &rule.escalation {
+            let actions: Vec<String> = step.actions.iter().map(RuleAction::summary).collect();
+            println!(
+                "{:<44} still matching after {}s: {}",
+                "",
+                step.after_secs,
+                actions.join(", ")
+            );
+        }
+        if let Some(max) = rule.max_triggers_per_hour {
+            println!("{:<44} budget: {} trigger(s) per hour", "", max);
+        }
+        if !rule.scope.machines.is_empty() {
+            println!("{:<44} machines: {}", "", rule.scope.machines.join(", "));
+        }
+        if !rule.scope.nodes.is_empty() {
+            println!("{:<44} nodes: {}", "", rule.scope.nodes.join(", "));
+        }
+        if !rule.dangling_fields.is_empty() {
+            println!(
+                "{:<44} warning: no context provider writes {}",
+                "",
+                rule.dangling_fields.join(", ")
+            );
+        }
+    }
+}
+
+fn print_statuses(reply: &RulesReply) {
+    if reply.statuses.is_empty() {
+        println!("No rules registered.");
+        return;
+    }
+    let time = |at: Option<i64>| {
+        at.and_then(|at| chrono::DateTime::from_timestamp(at, 0))
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "-".to_string())
+    };
+    println!(
+        "{:<44} {:<16} {:<8} {:<10} {:<10} {:<20} ESCALATION",
+        "ID", "MISSION", "BUDGET", "LAST HOUR", "THROTTLED", "LAST THROTTLED"
+    );
+    println!("{}", "-".repeat(130));
+    for status in &reply.statuses {
+        let max = status
+            .max_triggers_per_hour
+            .map(|max| max.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let escalation = match status.escalating_since {
+            Some(since) => format!(
+                "step {}/{} since {}",
+                status.escalation_level,
+                status.escalation_steps,
+                time(Some(since))
+            ),
+            None if status.escalation_steps > 0 => "idle".to_string(),
+            None => "-".to_string(),
+        };
+        println!(
+            "{:<44} {:<16} {:<8} {:<10} {:<10} {:<20} {}",
+            status.id,
+            status.mission_id,
+            max,
+            status.triggers_last_hour,
+            status.throttled_last_hour,
+            time(status.last_throttled_at),
+            escalation
+        );
+    }
+    println!("\n{}", reply.message);
+}
+
+fn print_overrides(reply: &RulesReply) {
+    let time = |at: i64| {
+        chrono::DateTime::from_timestamp(at, 0)
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| at.to_string())
+    };
+    let target = |agent: Option<&str>, rule: Option<&str>| match (agent, rule) {
+        (Some(agent), Some(rule)) => format!("{}/{}", agent, rule),
+        (Some(agent), None) => format!("{}/*", agent),
+        (None, Some(rule)) => rule.to_string(),
+        (None, None) => "all automation".to_string(),
+    };
+    if reply.overrides.is_empty() {
+        println!("Automation is not paused.");
+    }
+    for o in &reply.overrides {
+        let until = match o.expires_at {
+            Some(at) => format!(" until {}", time(at)),
+            None => String::new(),
+        };
+        println!(
+            "{} paused by {} since {}{}",
+            target(o.agent.as_deref(), o.rule.as_deref()),
+            o.by(),
+            time(o.set_at),
+            until
+        );
+    }
+    if reply.audit.is_empty() {
+        return;
+    }
+    println!(
+        "\n{:<21} {:<8} {:<16} {:<44} REASON",
+        "AT", "CHANGE", "BY", "TARGET"
+    );
+    println!("{}", "-".repeat(100));
+    for entry in &reply.audit {
+        let change = match entry.change {
+            OverrideChange::Set => "pause",
+            OverrideChange::Clear => "resume",
+            OverrideChange::Expire => "expired",
+        };
+        println!(
+            "{:<21} {:<8} {:<16} {:<44} {}",
+            time(entry.at),
+            change,
+            entry.actor,
+            target(entry.agent.as_deref(), entry.rule.as_deref()),
+            entry.reason
+        );
+    }
+}
+
+fn print_templates(templates: &[TemplateInfo]) {
+    if templates.is_empty() {
+        println!("No rule templates.");
+        return;
+    }
+    for template in templates {
+        println!(
+            "{}/{}  {}",
+            template.pack, template.name, template.description
+        );
+        for param in &template.params {
+            let default = match &param.default {
+                Some(default) => format!(" (default: {})", default),
+                None => " (required)".to_string(),
+            };
+            println!("    {:<16} {}{}", param.name, param.description, default);
+        }
+    }
+}
+
+fn print_events(events: &[RuleEvent]) {
+    if events.is_empty() {
+        println!("No rule events.");
+        return;
+    }
+    println!("{:<21} {:<44} {:<16} FIELDS", "FIRED AT", "RULE", "MISSION");
+    println!("{}", "-".repeat(100));
+    for event in events {
+        let fired_at = chrono::DateTime::from_timestamp(event.fired_at, 0)
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| event.fired_at.to_string());
+        let fields = event.payload["fields"]
+            .as_object()
+            .map(|fields| {
+                fields
+                    .iter()
+                    .map(|(k, v)| format!("{}={}", k, v.as_str().unwrap_or("(absent)")))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .unwrap_or_default();
+        println!(
+            "{:<21} {:<44} {:<16} {}",
+            fired_at, event.rule_id, event.mission_id, fields
+        );
+        if event.throttled {
+            println!("{:<21} throttled: rule over its trigger budget", "");
+        }
+        if event.escalation > 0 {
+            println!("{:<21} escalation step {}", "", event.escalation);
+        }
+        for result in &event.results {
+            let status = if result.ok { "ok" } else { "failed" };
+            println!(
+                "{:<21} {} {}: {}",
+                "", status, result.action, result.outcome
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_needs_exactly_one_target() {
+        assert!(test_action(None, None, &[]).is_err());
+        assert!(test_action(Some("a".into()), Some("x = 1".into()), &[]).is_err());
+
+        let action = test_action(None, Some("x = 1".into()), &["x = 1".into()]).unwrap();
+        let RulesAction::Test { state, .. } = action else {
+            panic!("expected a test action");
+        };
+        assert_eq!(state.unwrap().get("x").map(String::as_str), Some("1"));
+    }
+
+    #[test]
+    fn webhook_options_build_an_http_action() {
+        assert!(webhook_actions(None, &[], None).unwrap().is_empty());
+        assert!(webhook_actions(None, &[], Some("t".into())).is_err());
+        assert!(webhook_actions(Some("http://h".into()), &["bad".into()], None).is_err());
+
+        let actions = webhook_actions(
+            Some("https://example.com/hook".into()),
+            &["X-Source: rack".into()],
+            Some("env:HOOK_TOKEN".into()),
+        )
+        .unwrap();
+        let [RuleAction::Http(http)] = actions.as_slice() else {
+            panic!("expected one http action");
+        };
+        assert_eq!(
+            http.headers.get("X-Source").map(String::as_str),
+            Some("rack")
+        );
+        assert_eq!(http.secret.as_deref(), Some("env:HOOK_TOKEN"));
+    }
+
+    #[test]
+    fn notify_options_build_one_action_per_channel() {
+        assert!(notify_actions(Vec::new(), None).unwrap().is_empty());
+        assert!(notify_actions(Vec::new(), Some("hi".into())).is_err());
+
+        let actions =
+            notify_actions(vec!["phone".into(), "ops".into()], Some("hi".into())).unwrap();
+        assert_eq!(actions.len(), 2);
+        let RuleAction::Notify(notify) = &actions[1] else {
+            panic!("expected a notify action");
+        };
+        assert_eq!(notify.channel, "ops");
+        assert_eq!(notify.message.as_deref(), Some("hi"));
+    }
+
+    #[test]
+    fn node_commands_build_node_actions() {
+        let actions = node_actions(&["restart:camera".into(), "stop: fan".into()]).unwrap();
+        let summaries: Vec<_> = actions.iter().map(RuleAction::summary).collect();
+        assert_eq!(summaries, ["restart camera", "stop fan"]);
+        assert!(node_actions(&["camera".into()]).is_err());
+        assert!(node_actions(&["reboot:camera".into()]).is_err());
+    }
+
+    #[test]
+    fn workflow_files_build_workflow_actions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recover.yaml");
+        std::fs::write(
+            &path,
+            "- { type: node, node: camera, command: restart }\n\
+             - { type: wait, secs: 30 }\n\
+             - { type: check, node: camera, status: running, on_failure: continue }\n",
+        )
+        .unwrap();
+        let actions = workflow_actions(&[path.display().to_string()]).unwrap();
+        assert_eq!(
+            actions[0].summary(),
+            "workflow: restart camera -> wait 30s -> check camera running"
+        );
+
+        std::fs::write(&path, "- { type: reboot }\n").unwrap();
+        assert!(workflow_actions(&[path.display().to_string()]).is_err());
+        assert!(workflow_actions(&["/nonexistent/steps.yaml".into()]).is_err());
+    }
+
+    #[test]
+    fn parse_state_rejects_entries_without_key() {
+        assert!(parse_state(&["motion".into()]).is_err());
+        assert!(parse_state(&["=1".into()]).is_err());
+        let state = parse_state(&["zone=porch".into(), "level=0.5".into()]).unwrap();
+        assert_eq!(state.len(), 2);
+    }
+
+    #[test]
+    fn parse_time_accepts_durations_and_rfc3339() {
+        let now = 1_000_000;
+        assert_eq!(parse_time("--since", "90s", now).unwrap(), now - 90);
+        assert_eq!(parse_time("--since", "2d", now).unwrap(), now - 172_800);
+        assert_eq!(
+            parse_time("--until", "2026-01-01T00:00:00Z", now).unwrap(),
+            1_767_225_600
+        );
+        let err = parse_time("--until", "yesterday", now).unwrap_err();
+        assert!(err.to_string().contains("--until"));
+        assert!(parse_time("--since", "0m", now).is_err());
+    }
+
+    #[test]
+    fn escalate_options_group_actions_by_delay() {
+        let steps = escalation_steps(&[
+            "30m=stop:camera".to_string(),
+            "10m=notify:phone".to_string(),
+            "10m=webhook:https://example.com/page".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(steps.len(), 2);
+        assert_eq!(steps[0].after_secs, 600);
+        assert_eq!(steps[0].actions.len(), 2);
+        assert_eq!(steps[1].after_secs, 1800);
+        assert_eq!(steps[1].actions[0].summary(), "stop camera");
+
+        assert!(escalation_steps(&["notify:phone".to_string()]).is_err());
+        assert!(escalation_steps(&["soon=notify:phone".to_string()]).is_err());
+        assert!(escalation_steps(&["10m=reboot:camera".to_string()]).is_err());
+    }
+}
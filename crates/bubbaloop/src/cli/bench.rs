@@ -0,0 +1,615 @@
+//! Zenoh throughput and latency benchmark
+//!
+//! Usage:
+//!   bubbaloop bench run                                  # in-process responder
+//!   bubbaloop bench run --sizes 1k,64k,1m -n 2000 --congestion drop
+//!   bubbaloop bench respond                              # on the remote machine
+//!   bubbaloop bench run -m jetson_orin --rate 30 --json  # across machines
+//!
+//! `run` publishes numbered pings on `{prefix}/bench/{run}/{size}/ping` and
+//! a responder echoes each one back on `.../pong` with the same QoS. Every
+//! message travels the network twice, so latencies are round-trip times and
+//! loss counts messages dropped in either direction. Without `--machine` the
+//! responder runs on a second Zenoh session in this process, which still
+//! goes through the router like any node would.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use argh::FromArgs;
+use serde::Serialize;
+use thiserror::Error;
+use zenoh::qos::{CongestionControl, Priority};
+use zenoh::Session;
+
+use crate::cli::target::Scope;
+use crate::cli::topic::stats::{format_size, Summary};
+
+/// Sequence number of the pings sent until the responder answers.
+const PROBE_SEQ: u64 = u64::MAX;
+/// How long to wait for a responder before giving up.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Sequence number and QoS byte at the start of every payload.
+const HEADER_LEN: usize = 9;
+/// Responder publishers kept before the cache is cleared.
+const MAX_PUBLISHERS: usize = 64;
+
+/// Priorities in the order they are encoded in the QoS byte.
+const PRIORITIES: [(&str, Priority); 7] = [
+    ("realtime", Priority::RealTime),
+    ("interactive-high", Priority::InteractiveHigh),
+    ("interactive-low", Priority::InteractiveLow),
+    ("data-high", Priority::DataHigh),
+    ("data", Priority::Data),
+    ("data-low", Priority::DataLow),
+    ("background", Priority::Background),
+];
+
+#[derive(Debug, Error)]
+pub enum BenchError {
+    #[error("Zenoh error: {0}")]
+    Zenoh(String),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("No responder on {0} - is `bubbaloop bench respond` running there?")]
+    NoResponder(String),
+}
+
+pub type Result<T> = std::result::Result<T, BenchError>;
+
+/// Measure Zenoh throughput, latency and loss
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "bench")]
+pub struct BenchCommand {
+    #[argh(subcommand)]
+    action: BenchAction,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand)]
+enum BenchAction {
+    Run(RunArgs),
+    Respond(RespondArgs),
+}
+
+/// Send pings of each payload size and report throughput, round-trip latency and loss
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "run")]
+struct RunArgs {
+    /// comma-separated payload sizes, e.g. 64,1k,64k,1m (default: 64,1k,64k,1m)
+    #[argh(option, default = "String::from(\"64,1k,64k,1m\")")]
+    sizes: String,
+
+    /// messages sent per payload size (default: 1000)
+    #[argh(option, short = 'n', default = "1000")]
+    count: usize,
+
+    /// messages per second; 0 sends as fast as possible (default: 0)
+    #[argh(option, short = 'r', default = "0.0")]
+    rate: f64,
+
+    /// congestion control: block or drop (default: block)
+    #[argh(option, default = "String::from(\"block\")")]
+    congestion: String,
+
+    /// priority: realtime, interactive-high, interactive-low, data-high, data, data-low, background (default: data)
+    #[argh(option, default = "String::from(\"data\")")]
+    priority: String,
+
+    /// send without batching
+    #[argh(switch)]
+    express: bool,
+
+    /// seconds to wait for late echoes after the last ping (default: 2)
+    #[argh(option, default = "2")]
+    drain: u64,
+
+    /// machine running `bench respond` (default: the --machine target, or a responder in this process)
+    #[argh(option, short = 'm')]
+    machine: Option<String>,
+
+    /// output the report as JSON
+    #[argh(switch)]
+    json: bool,
+}
+
+/// Echo pings from `bench run` on other machines until Ctrl-C
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "respond")]
+struct RespondArgs {}
+
+/// QoS of a benchmark, mirrored by the responder.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Qos {
+    drop: bool,
+    express: bool,
+    /// Index into [`PRIORITIES`].
+    priority: usize,
+}
+
+impl Qos {
+    fn parse(congestion: &str, priority: &str, express: bool) -> Result<Self> {
+        let drop = match congestion {
+            "block" => false,
+            "drop" => true,
+            other => {
+                return Err(BenchError::InvalidArgument(format!(
+                    "--congestion must be block or drop, got '{}'",
+                    other
+                )))
+            }
+        };
+        let priority = PRIORITIES
+            .iter()
+            .position(|(name, _)| *name == priority)
+            .ok_or_else(|| {
+                BenchError::InvalidArgument(format!("unknown priority '{}'", priority))
+            })?;
+        Ok(Self {
+            drop,
+            express,
+            priority,
+        })
+    }
+
+    fn to_byte(self) -> u8 {
+        (self.drop as u8) | (self.express as u8) << 1 | (self.priority as u8) << 2
+    }
+
+    fn from_byte(byte: u8) -> Self {
+        Self {
+            drop: byte & 1 != 0,
+            express: byte & 2 != 0,
+            priority: ((byte >> 2) as usize).min(PRIORITIES.len() - 1),
+        }
+    }
+
+    async fn declare_publisher(
+        self,
+        session: &Session,
+        key: String,
+    ) -> Result<zenoh::pubsub::Publisher<'static>> {
+        session
+            .declare_publisher(key)
+            .congestion_control(if self.drop {
+                CongestionControl::Drop
+            } else {
+                CongestionControl::Block
+            })
+            .priority(PRIORITIES[self.priority].1)
+            .express(self.express)
+            .await
+            .map_err(|e| BenchError::Zenoh(e.to_string()))
+    }
+}
+
+/// Everything `run` reports.
+#[derive(Debug, Serialize)]
+struct BenchReport {
+    machine: String,
+    congestion: String,
+    priority: String,
+    express: bool,
+    rate: f64,
+    rounds: Vec<Round>,
+}
+
+/// Results for one payload size.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Round {
+    payload_bytes: usize,
+    sent: usize,
+    received: usize,
+    loss_pct: f64,
+    duration_s: f64,
+    messages_per_s: f64,
+    /// Payload bytes echoed back per second.
+    throughput_bps: f64,
+    /// Round-trip times in milliseconds.
+    rtt_ms: Option<Summary>,
+}
+
+impl BenchCommand {
+    pub async fn run(self) -> Result<()> {
+        match self.action {
+            BenchAction::Run(args) => run(args).await,
+            BenchAction::Respond(_) => respond().await,
+        }
+    }
+}
+
+async fn run(args: RunArgs) -> Result<()> {
+    let sizes = parse_sizes(&args.sizes)?;
+    let qos = Qos::parse(&args.congestion, &args.priority, args.express)?;
+    if args.count == 0 {
+        return Err(BenchError::InvalidArgument(
+            "--count must be at least 1".to_string(),
+        ));
+    }
+    if !(args.rate >= 0.0 && args.rate.is_finite()) {
+        return Err(BenchError::InvalidArgument(format!(
+            "--rate must be zero or a positive number, got {}",
+            args.rate
+        )));
+    }
+    let target = crate::cli::target::get();
+    let remote = args.machine.clone().or_else(|| target.machine.clone());
+    let machine = remote
+        .clone()
+        .unwrap_or_else(crate::daemon::util::get_machine_id);
+    let prefix = bench_prefix(target.scope, &machine);
+
+    let session = crate::cli::zenoh_session::create_zenoh_session(None)
+        .await
+        .map_err(|e| BenchError::Zenoh(e.to_string()))?;
+    let responder = match remote {
+        Some(_) => None,
+        None => {
+            let responder_session = crate::cli::zenoh_session::create_zenoh_session(None)
+                .await
+                .map_err(|e| BenchError::Zenoh(e.to_string()))?;
+            let prefix = prefix.clone();
+            Some(tokio::spawn(async move {
+                if let Err(e) = serve(&responder_session, &prefix).await {
+                    log::warn!("[bench] responder stopped: {}", e);
+                }
+            }))
+        }
+    };
+
+    let run_id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+    let interval = (args.rate > 0.0).then(|| Duration::from_secs_f64(1.0 / args.rate));
+    let mut rounds = Vec::new();
+    for size in sizes {
+        if !args.json {
+            eprintln!(
+                "Sending {} x {} to {}...",
+                args.count,
+                format_size(size as f64),
+                machine
+            );
+        }
+        let base = format!("{}/{}/{}", prefix, run_id, size);
+        let round = run_round(
+            &session,
+            &base,
+            size,
+            args.count,
+            interval,
+            qos,
+            Duration::from_secs(args.drain),
+        )
+        .await;
+        match round {
+            Ok(round) => rounds.push(round),
+            Err(e) => {
+                if let Some(responder) = &responder {
+                    responder.abort();
+                }
+                return Err(e);
+            }
+        }
+    }
+    if let Some(responder) = responder {
+        responder.abort();
+    }
+
+    let report = BenchReport {
+        machine,
+        congestion: args.congestion,
+        priority: args.priority,
+        express: args.express,
+        rate: args.rate,
+        rounds,
+    };
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print_report(&report);
+    }
+    Ok(())
+}
+
+async fn respond() -> Result<()> {
+    let target = crate::cli::target::get();
+    if target.machine.is_some() {
+        return Err(BenchError::InvalidArgument(
+            "bench respond serves this machine; run it on the other machine instead".to_string(),
+        ));
+    }
+    let prefix = bench_prefix(target.scope, &target.machine_id());
+    let session = crate::cli::zenoh_session::create_zenoh_session(None)
+        .await
+        .map_err(|e| BenchError::Zenoh(e.to_string()))?;
+    eprintln!("Responding to bench runs on {}/** (Ctrl-C to stop)", prefix);
+    tokio::select! {
+        result = serve(&session, &prefix) => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+    }
+}
+
+/// `bubbaloop/{scope}/{machine}/bench`
+fn bench_prefix(scope: Scope, machine: &str) -> String {
+    format!("bubbaloop/{}/{}/bench", scope.as_str(), machine)
+}
+
+/// Echo every ping under `prefix` back on its pong key.
+async fn serve(session: &Session, prefix: &str) -> Result<()> {
+    let subscriber = session
+        .declare_subscriber(format!("{}/**/ping", prefix))
+        .await
+        .map_err(|e| BenchError::Zenoh(e.to_string()))?;
+    let mut publishers = HashMap::new();
+    while let Ok(sample) = subscriber.recv_async().await {
+        let payload = sample.payload().to_bytes();
+        let Some((_, qos)) = parse_header(&payload) else {
+            continue;
+        };
+        let Some(base) = sample.key_expr().as_str().strip_suffix("/ping") else {
+            continue;
+        };
+        let pong = format!("{}/pong", base);
+        if publishers.len() >= MAX_PUBLISHERS {
+            publishers.clear();
+        }
+        let publisher = match publishers.entry((pong.clone(), qos.to_byte())) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(qos.declare_publisher(session, pong).await?)
+            }
+        };
+        publisher
+            .put(payload.into_owned())
+            .await
+            .map_err(|e| BenchError::Zenoh(e.to_string()))?;
+    }
+    Ok(())
+}
+
+/// Send `count` pings of `size` bytes on `{base}/ping` and time their echoes.
+async fn run_round(
+    session: &Session,
+    base: &str,
+    size: usize,
+    count: usize,
+    interval: Option<Duration>,
+    qos: Qos,
+    drain: Duration,
+) -> Result<Round> {
+    let arrivals: Arc<Mutex<Vec<Option<Instant>>>> = Arc::new(Mutex::new(vec![None; count]));
+    let received = Arc::new(AtomicUsize::new(0));
+    let ready = Arc::new(AtomicBool::new(false));
+    let _subscriber = {
+        let (arrivals, received, ready) = (arrivals.clone(), received.clone(), ready.clone());
+        session
+            .declare_subscriber(format!("{}/pong", base))
+            .callback(move |sample| {
+                let now = Instant::now();
+                let Some((seq, _)) = parse_header(&sample.payload().to_bytes()) else {
+                    return;
+                };
+                if seq == PROBE_SEQ {
+                    ready.store(true, Ordering::Relaxed);
+                    return;
+                }
+                let mut arrivals = arrivals.lock().unwrap();
+                if let Some(slot @ None) = usize::try_from(seq)
+                    .ok()
+                    .and_then(|seq| arrivals.get_mut(seq))
+                {
+                    *slot = Some(now);
+                    received.fetch_add(1, Ordering::Relaxed);
+                }
+            })
+            .await
+            .map_err(|e| BenchError::Zenoh(e.to_string()))?
+    };
+    let publisher = qos
+        .declare_publisher(session, format!("{}/ping", base))
+        .await?;
+
+    // Subscriptions take a moment to propagate; probe until the responder echoes.
+    let probe_deadline = Instant::now() + PROBE_TIMEOUT;
+    while !ready.load(Ordering::Relaxed) {
+        if Instant::now() > probe_deadline {
+            return Err(BenchError::NoResponder(base.to_string()));
+        }
+        publisher
+            .put(encode(PROBE_SEQ, qos, size))
+            .await
+            .map_err(|e| BenchError::Zenoh(e.to_string()))?;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    let mut ticker = interval.map(tokio::time::interval);
+    let mut sent_at = Vec::with_capacity(count);
+    for seq in 0..count {
+        if let Some(ticker) = ticker.as_mut() {
+            ticker.tick().await;
+        }
+        let payload = encode(seq as u64, qos, size);
+        sent_at.push(Instant::now());
+        publisher
+            .put(payload)
+            .await
+            .map_err(|e| BenchError::Zenoh(e.to_string()))?;
+    }
+    let drain_deadline = Instant::now() + drain;
+    while received.load(Ordering::Relaxed) < count && Instant::now() < drain_deadline {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+
+    let arrivals = arrivals.lock().unwrap().clone();
+    Ok(summarize(size, &sent_at, &arrivals))
+}
+
+/// Turn send and echo times into a [`Round`].
+fn summarize(size: usize, sent_at: &[Instant], arrivals: &[Option<Instant>]) -> Round {
+    let rtts: Vec<f64> = sent_at
+        .iter()
+        .zip(arrivals)
+        .filter_map(|(sent, arrival)| Some((*arrival)?.duration_since(*sent).as_secs_f64() * 1e3))
+        .collect();
+    let received = rtts.len();
+    let sent = sent_at.len();
+    let duration = match (sent_at.first(), arrivals.iter().flatten().max()) {
+        (Some(first), Some(last)) => last.duration_since(*first),
+        _ => Duration::ZERO,
+    };
+    let per_second = |n: f64| {
+        if duration.is_zero() {
+            0.0
+        } else {
+            n / duration.as_secs_f64()
+        }
+    };
+    Round {
+        payload_bytes: size,
+        sent,
+        received,
+        loss_pct: if sent == 0 {
+            0.0
+        } else {
+            (sent - received) as f64 * 100.0 / sent as f64
+        },
+        duration_s: duration.as_secs_f64(),
+        messages_per_s: per_second(received as f64),
+        throughput_bps: per_second((received * size) as f64),
+        rtt_ms: Summary::of(rtts),
+    }
+}
+
+/// A ping of `size` bytes: sequence number, QoS byte, zero padding.
+fn encode(seq: u64, qos: Qos, size: usize) -> Vec<u8> {
+    let mut payload = vec![0u8; size.max(HEADER_LEN)];
+    payload[..8].copy_from_slice(&seq.to_le_bytes());
+    payload[8] = qos.to_byte();
+    payload
+}
+
+fn parse_header(payload: &[u8]) -> Option<(u64, Qos)> {
+    let seq = u64::from_le_bytes(payload.get(..8)?.try_into().ok()?);
+    Some((seq, Qos::from_byte(*payload.get(8)?)))
+}
+
+/// `64,1k,64k,1m` to byte counts (powers of 1024).
+fn parse_sizes(sizes: &str) -> Result<Vec<usize>> {
+    sizes
+        .split(',')
+        .map(|size| {
+            let size = size.trim().to_lowercase();
+            let (digits, unit) = match size.char_indices().find(|(_, c)| c.is_alphabetic()) {
+                Some((i, _)) => size.split_at(i),
+                None => (size.as_str(), ""),
+            };
+            let multiplier = match unit {
+                "" | "b" => 1,
+                "k" | "kb" | "kib" => 1024,
+                "m" | "mb" | "mib" => 1024 * 1024,
+                _ => 0,
+            };
+            match digits.parse::<usize>() {
+                Ok(n) if multiplier > 0 && n * multiplier >= HEADER_LEN => Ok(n * multiplier),
+                _ => Err(BenchError::InvalidArgument(format!(
+                    "invalid payload size '{}' (use e.g. 64, 1k, 1m; at least {} bytes)",
+                    size, HEADER_LEN
+                ))),
+            }
+        })
+        .collect()
+}
+
+fn print_report(report: &BenchReport) {
+    println!(
+        "Machine: {}  congestion: {}  priority: {}  express: {}  rate: {}",
+        report.machine,
+        report.congestion,
+        report.priority,
+        report.express,
+        if report.rate > 0.0 {
+            format!("{} msg/s", report.rate)
+        } else {
+            "max".to_string()
+        }
+    );
+    println!(
+        "{:<10} {:>7} {:>7} {:>7} {:>10} {:>12} {:>10} {:>10} {:>10}",
+        "SIZE", "SENT", "RECV", "LOSS", "MSG/S", "THROUGHPUT", "RTT P50", "RTT P99", "RTT MAX"
+    );
+    println!("{}", "-".repeat(95));
+    for round in &report.rounds {
+        let rtt = |pick: fn(&Summary) -> f64| {
+            round
+                .rtt_ms
+                .as_ref()
+                .map(|s| format!("{:.2} ms", pick(s)))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        println!(
+            "{:<10} {:>7} {:>7} {:>6.1}% {:>10.0} {:>10}/s {:>10} {:>10} {:>10}",
+            format_size(round.payload_bytes as f64),
+            round.sent,
+            round.received,
+            round.loss_pct,
+            round.messages_per_s,
+            format_size(round.throughput_bps),
+            rtt(|s| s.p50),
+            rtt(|s| s.p99),
+            rtt(|s| s.max),
+        );
+    }
+    println!("Latencies are round trips; loss counts pings or echoes that never arrived.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_accept_units() {
+        assert_eq!(
+            parse_sizes("64, 1k,64KiB,1m").unwrap(),
+            vec![64, 1024, 65536, 1024 * 1024]
+        );
+        assert!(parse_sizes("8").is_err());
+        assert!(parse_sizes("1g").is_err());
+        assert!(parse_sizes("").is_err());
+    }
+
+    #[test]
+    fn header_round_trips_seq_and_qos() {
+        let qos = Qos::parse("drop", "interactive-high", true).unwrap();
+        let payload = encode(42, qos, 1024);
+        assert_eq!(payload.len(), 1024);
+        assert_eq!(parse_header(&payload), Some((42, qos)));
+        assert_eq!(parse_header(&payload[..4]), None);
+        assert!(Qos::parse("fast", "data", false).is_err());
+        assert!(Qos::parse("block", "urgent", false).is_err());
+    }
+
+    #[test]
+    fn summary_counts_loss_and_throughput() {
+        let start = Instant::now();
+        let sent_at: Vec<Instant> = (0..4)
+            .map(|i| start + Duration::from_millis(i * 10))
+            .collect();
+        // Echoes arrive 5 ms after each ping; the third one is lost.
+        let arrivals: Vec<Option<Instant>> = sent_at
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (i != 2).then(|| *t + Duration::from_millis(5)))
+            .collect();
+        let round = summarize(1000, &sent_at, &arrivals);
+
+        assert_eq!((round.sent, round.received), (4, 3));
+        assert!((round.loss_pct - 25.0).abs() < 1e-9);
+        assert!((round.duration_s - 0.035).abs() < 1e-9);
+        assert!((round.throughput_bps - 3000.0 / 0.035).abs() < 1e-6);
+        let rtt = round.rtt_ms.unwrap();
+        assert!((rtt.p50 - 5.0).abs() < 1e-6);
+    }
+}
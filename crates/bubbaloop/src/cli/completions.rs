@@ -0,0 +1,391 @@
+//! Shell completion scripts
+//!
+//! Usage:
+//!   bubbaloop completions bash > ~/.local/share/bash-completion/completions/bubbaloop
+//!   bubbaloop completions zsh > "${fpath[1]}/_bubbaloop"
+//!   bubbaloop completions fish > ~/.config/fish/completions/bubbaloop.fish
+//!
+//! argh cannot generate completions, so the command tree lives in
+//! [`COMMANDS`] and the scripts are rendered from it. Node names are
+//! completed dynamically: the scripts call `bubbaloop completions --nodes`,
+//! which asks the daemon for the registered nodes.
+//!
+//! Every script keys its positional completions on the same string,
+//! `{index}:{command}[ {subcommand}]`, where `index` is the position of the
+//! word being completed (the binary being 0).
+
+use argh::FromArgs;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum CompletionsError {
+    #[error("Unsupported shell '{0}' (expected bash, zsh or fish)")]
+    UnsupportedShell(String),
+}
+
+pub type Result<T> = std::result::Result<T, CompletionsError>;
+
+/// Top-level commands and their subcommands (for `completions`, the
+/// shells it accepts).
+pub const COMMANDS: &[(&str, &[&str])] = &[
+    ("agent", &["chat", "list", "rules", "setup"]),
+    ("bench", &["run", "respond"]),
+    ("completions", &["bash", "zsh", "fish"]),
+    (
+        "daemon",
+        &["run", "start", "stop", "restart", "status", "logs", "fix"],
+    ),
+    ("dataflow", &[]),
+    (
+        "debug",
+        &["topics", "subscribe", "query", "info", "liveliness"],
+    ),
+    ("doctor", &[]),
+    ("init-tls", &[]),
+    (
+        "launch",
+        &[
+            "start", "plan", "validate", "instance", "list", "status", "reload", "stop",
+        ],
+    ),
+    ("login", &[]),
+    ("logout", &[]),
+    (
+        "marketplace",
+        &["list", "add", "remove", "enable", "disable"],
+    ),
+    ("mcp", &[]),
+    (
+        "node",
+        &[
+            "init",
+            "validate",
+            "list",
+            "add",
+            "remove",
+            "instance",
+            "config",
+            "install",
+            "uninstall",
+            "start",
+            "stop",
+            "restart",
+            "logs",
+            "history",
+            "build",
+            "update",
+            "diff",
+            "clean",
+            "enable",
+            "disable",
+            "search",
+            "discover",
+            "prune",
+        ],
+    ),
+    ("play", &[]),
+    ("record", &["start", "stop", "status"]),
+    ("schema", &["list", "dump", "registry"]),
+    ("self-update", &[]),
+    ("setup", &[]),
+    ("skill", &["add", "list", "drivers", "apply", "remove"]),
+    ("status", &[]),
+    ("topic", &["echo", "pub", "hz", "bw"]),
+    ("up", &[]),
+];
+
+/// Positions completed with registered node names.
+const NODE_POSITIONS: &[&str] = &[
+    "3:node remove",
+    "3:node instance",
+    "4:node config",
+    "3:node uninstall",
+    "3:node start",
+    "3:node stop",
+    "3:node restart",
+    "3:node logs",
+    "3:node history",
+    "3:node build",
+    "3:node update",
+    "3:node diff",
+    "3:node clean",
+    "3:node enable",
+    "3:node disable",
+    "3:launch instance",
+    "3:schema dump",
+];
+
+/// Positions completed with file paths.
+const FILE_POSITIONS: &[&str] = &[
+    "3:node add",
+    "3:node validate",
+    "3:skill add",
+    "4:launch instance",
+    "3:launch start",
+    "3:launch plan",
+    "3:launch validate",
+    "2:play",
+];
+
+/// Print a shell completion script
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "completions")]
+pub struct CompletionsCommand {
+    /// shell to generate completions for: bash, zsh, fish
+    #[argh(positional)]
+    shell: Option<String>,
+
+    /// print registered node names, one per line (used by the scripts)
+    #[argh(switch)]
+    nodes: bool,
+}
+
+impl CompletionsCommand {
+    pub async fn run(self) -> Result<()> {
+        if self.nodes {
+            for name in node_names().await {
+                println!("{}", name);
+            }
+            return Ok(());
+        }
+        let shell = self.shell.unwrap_or_default();
+        print!("{}", script(&shell)?);
+        Ok(())
+    }
+}
+
+/// Registered node names, or nothing when the daemon is unreachable:
+/// completion must never print errors into the user's prompt.
+async fn node_names() -> Vec<String> {
+    let Ok(client) = crate::cli::daemon_client::DaemonClient::connect().await else {
+        return Vec::new();
+    };
+    let Ok(json) = client.list_nodes().await else {
+        return Vec::new();
+    };
+    serde_json::from_str::<Vec<crate::mcp::platform::NodeInfo>>(&json)
+        .map(|nodes| nodes.into_iter().map(|node| node.name).collect())
+        .unwrap_or_default()
+}
+
+/// The completion script for `shell`.
+pub fn script(shell: &str) -> Result<String> {
+    match shell {
+        "bash" => Ok(bash()),
+        "zsh" => Ok(zsh()),
+        "fish" => Ok(fish()),
+        other => Err(CompletionsError::UnsupportedShell(other.to_string())),
+    }
+}
+
+fn top_level() -> String {
+    COMMANDS
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `"3:node start"|"3:node stop"` for a shell `case` arm.
+fn case_patterns(positions: &[&str]) -> String {
+    positions
+        .iter()
+        .map(|p| format!("\"{}\"", p))
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+fn bash() -> String {
+    let mut out = String::from(
+        "# bash completion for bubbaloop\n\
+         _bubbaloop() {\n\
+         \x20   local cur=\"${COMP_WORDS[COMP_CWORD]}\"\n\
+         \x20   if (( COMP_CWORD == 1 )); then\n",
+    );
+    out += &format!(
+        "        COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))\n        return\n    fi\n",
+        top_level()
+    );
+    out += "    local key=\"${COMP_CWORD}:${COMP_WORDS[1]}\"\n";
+    out += "    (( COMP_CWORD >= 3 )) && key=\"$key ${COMP_WORDS[2]}\"\n";
+    out += "    case \"$key\" in\n";
+    for (name, subcommands) in COMMANDS.iter().filter(|(_, s)| !s.is_empty()) {
+        out += &format!(
+            "        \"2:{}\") COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")) ;;\n",
+            name,
+            subcommands.join(" ")
+        );
+    }
+    out += &format!(
+        "        {}) COMPREPLY=($(compgen -W \"$(bubbaloop completions --nodes 2>/dev/null)\" -- \"$cur\")) ;;\n",
+        case_patterns(NODE_POSITIONS)
+    );
+    out += &format!(
+        "        {}) COMPREPLY=($(compgen -f -- \"$cur\")) ;;\n",
+        case_patterns(FILE_POSITIONS)
+    );
+    out += "    esac\n}\ncomplete -F _bubbaloop bubbaloop\n";
+    out
+}
+
+fn zsh() -> String {
+    let mut out = String::from("#compdef bubbaloop\n\n_bubbaloop() {\n");
+    out += &format!(
+        "    if (( CURRENT == 2 )); then\n        compadd -- {}\n        return\n    fi\n",
+        top_level()
+    );
+    out += "    local key=\"$(( CURRENT - 1 )):${words[2]}\"\n";
+    out += "    (( CURRENT >= 4 )) && key=\"$key ${words[3]}\"\n";
+    out += "    case \"$key\" in\n";
+    for (name, subcommands) in COMMANDS.iter().filter(|(_, s)| !s.is_empty()) {
+        out += &format!(
+            "        \"2:{}\") compadd -- {} ;;\n",
+            name,
+            subcommands.join(" ")
+        );
+    }
+    out += &format!(
+        "        {}) compadd -- ${{(f)\"$(bubbaloop completions --nodes 2>/dev/null)\"}} ;;\n",
+        case_patterns(NODE_POSITIONS)
+    );
+    out += &format!("        {}) _files ;;\n", case_patterns(FILE_POSITIONS));
+    out += "    esac\n}\n\n_bubbaloop \"$@\"\n";
+    out
+}
+
+fn fish() -> String {
+    let mut out = String::from(
+        "# fish completion for bubbaloop\n\
+         function __bubbaloop_at\n\
+         \x20   set -l words (commandline -opc)\n\
+         \x20   set -l key (count $words):$words[2]\n\
+         \x20   if test (count $words) -ge 3\n\
+         \x20       set key \"$key $words[3]\"\n\
+         \x20   end\n\
+         \x20   contains -- $key $argv\n\
+         end\n\n\
+         complete -c bubbaloop -f\n",
+    );
+    out += &format!(
+        "complete -c bubbaloop -n \"__bubbaloop_at 1:\" -a \"{}\"\n",
+        top_level()
+    );
+    for (name, subcommands) in COMMANDS.iter().filter(|(_, s)| !s.is_empty()) {
+        out += &format!(
+            "complete -c bubbaloop -n \"__bubbaloop_at '2:{}'\" -a \"{}\"\n",
+            name,
+            subcommands.join(" ")
+        );
+    }
+    let quoted = |positions: &[&str]| {
+        positions
+            .iter()
+            .map(|p| format!("'{}'", p))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    out += &format!(
+        "complete -c bubbaloop -n \"__bubbaloop_at {}\" -a \"(bubbaloop completions --nodes 2>/dev/null)\"\n",
+        quoted(NODE_POSITIONS)
+    );
+    out += &format!(
+        "complete -c bubbaloop -n \"__bubbaloop_at {}\" -F\n",
+        quoted(FILE_POSITIONS)
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::launch::LaunchCommand;
+    use crate::cli::record::RecordCommand;
+    use crate::cli::schema::SchemaCommand;
+    use crate::cli::topic::TopicCommand;
+    use crate::cli::{
+        AgentCommand, BenchCommand, DaemonCommand, DebugCommand, MarketplaceCommand, NodeCommand,
+    };
+
+    /// Subcommand names listed under `Commands:` in argh's help output.
+    fn listed<T: FromArgs>(name: &str) -> Vec<String> {
+        let Err(help) = T::from_args(&[name], &["--help"]) else {
+            panic!("--help should exit early");
+        };
+        let help = help.output;
+        let mut names: Vec<String> = help
+            .lines()
+            .skip_while(|line| !line.starts_with("Commands:"))
+            .skip(1)
+            .take_while(|line| !line.trim().is_empty())
+            // Wrapped descriptions are indented further than the names.
+            .filter(|line| !line[2..].starts_with(' '))
+            .filter_map(|line| line.split_whitespace().next().map(str::to_string))
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn tree(name: &str) -> Vec<String> {
+        let (_, subcommands) = COMMANDS.iter().find(|(n, _)| *n == name).unwrap();
+        let mut names: Vec<String> = subcommands.iter().map(|s| s.to_string()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn tree_matches_argh_subcommands() {
+        assert_eq!(tree("agent"), listed::<AgentCommand>("agent"));
+        assert_eq!(tree("bench"), listed::<BenchCommand>("bench"));
+        assert_eq!(tree("daemon"), listed::<DaemonCommand>("daemon"));
+        assert_eq!(tree("debug"), listed::<DebugCommand>("debug"));
+        assert_eq!(tree("launch"), listed::<LaunchCommand>("launch"));
+        assert_eq!(
+            tree("marketplace"),
+            listed::<MarketplaceCommand>("marketplace")
+        );
+        assert_eq!(tree("node"), listed::<NodeCommand>("node"));
+        assert_eq!(tree("record"), listed::<RecordCommand>("record"));
+        assert_eq!(tree("schema"), listed::<SchemaCommand>("schema"));
+        assert_eq!(tree("topic"), listed::<TopicCommand>("topic"));
+    }
+
+    #[test]
+    fn positions_name_real_subcommands() {
+        for position in NODE_POSITIONS.iter().chain(FILE_POSITIONS) {
+            let (index, path) = position.split_once(':').unwrap();
+            let mut words = path.split(' ');
+            let command = words.next().unwrap();
+            let (_, subcommands) = COMMANDS
+                .iter()
+                .find(|(name, _)| *name == command)
+                .unwrap_or_else(|| panic!("unknown command in {}", position));
+            match words.next() {
+                Some(sub) => {
+                    assert!(subcommands.contains(&sub), "{}", position);
+                    assert!(index.parse::<usize>().unwrap() >= 3, "{}", position);
+                }
+                None => assert_eq!(index, "2", "{}", position),
+            }
+        }
+    }
+
+    #[test]
+    fn scripts_render_for_each_shell() {
+        let bash = script("bash").unwrap();
+        assert!(bash.contains("\"2:topic\") COMPREPLY=($(compgen -W \"echo pub hz bw\""));
+        assert!(bash.ends_with("complete -F _bubbaloop bubbaloop\n"));
+
+        let zsh = script("zsh").unwrap();
+        assert!(zsh.starts_with("#compdef bubbaloop\n"));
+        assert!(zsh.contains("\"3:node start\"|\"3:node stop\""));
+
+        let fish = script("fish").unwrap();
+        assert!(fish.contains("'2:play'\" -F\n"));
+        assert!(fish.contains("(bubbaloop completions --nodes 2>/dev/null)"));
+
+        assert!(matches!(
+            script("powershell"),
+            Err(CompletionsError::UnsupportedShell(_))
+        ));
+    }
+}
@@ -52,13 +52,28 @@ impl DaemonClient {
         }
     }
 
+    /// Create a client for the daemon on another machine.
+    ///
+    /// Commands are authenticated with the local MCP token, so the remote
+    /// daemon must share it (copy `~/.bubbaloop/mcp-token`).
+    pub fn for_machine(session: Arc<Session>, machine_id: &str) -> Self {
+        Self {
+            machine_id: machine_id.to_string(),
+            ..Self::new(session)
+        }
+    }
+
     /// Create a daemon client by connecting to Zenoh automatically.
-    /// Convenience for CLI commands that don't already have a session.
+    /// Convenience for CLI commands that don't already have a session;
+    /// addresses the machine selected with the global `--machine` option.
     pub async fn connect() -> Result<Self> {
         let session = crate::agent::create_agent_session(None)
             .await
             .map_err(|e| DaemonClientError::Request(format!("Zenoh connect failed: {}", e)))?;
-        Ok(Self::new(session))
+        Ok(Self::for_machine(
+            session,
+            &crate::cli::target::get().machine_id(),
+        ))
     }
 
     /// Check if the daemon is running by querying its manifest.
@@ -258,6 +273,110 @@ impl DaemonClient {
         Err(DaemonClientError::NotReachable)
     }
 
+    /// Query the full node states (paths, config files) from the nodes queryable.
+    /// Uses 1s timeout with 3 retries per Zenoh query convention.
+    pub async fn node_states(&self) -> Result<gateway::NodeListJson> {
+        let pattern = gateway::nodes_topic(&self.machine_id);
+        for _ in 0..3 {
+            match self
+                .session
+                .get(&pattern)
+                .target(zenoh::query::QueryTarget::BestMatching)
+                .timeout(Duration::from_secs(1))
+                .await
+            {
+                Ok(replies) => match replies.recv_async().await {
+                    Ok(reply) => {
+                        if let Ok(sample) = reply.into_result() {
+                            let bytes = sample.payload().to_bytes();
+                            return gateway::from_cbor::<gateway::NodeListJson>(&bytes).map_err(
+                                |e| DaemonClientError::Request(format!("Invalid node list: {}", e)),
+                            );
+                        }
+                    }
+                    Err(_) => continue,
+                },
+                Err(_) => continue,
+            }
+        }
+        Err(DaemonClientError::NotReachable)
+    }
+
+    /// Read node output from the daemon's logs queryable.
+    pub async fn logs(&self, name: &str, query: &gateway::LogQuery) -> Result<gateway::LogChunk> {
+        let payload = gateway::to_cbor(query)
+            .map_err(|e| DaemonClientError::Request(format!("Invalid logs query: {}", e)))?;
+        let replies = self
+            .session
+            .get(gateway::logs_topic(&self.machine_id, name))
+            .payload(payload)
+            .target(zenoh::query::QueryTarget::BestMatching)
+            .timeout(RESPONSE_TIMEOUT)
+            .await
+            .map_err(|e| DaemonClientError::Request(e.to_string()))?;
+        let reply = replies
+            .recv_async()
+            .await
+            .map_err(|_| DaemonClientError::NotReachable)?;
+        match reply.into_result() {
+            Ok(sample) => gateway::from_cbor(&sample.payload().to_bytes())
+                .map_err(|e| DaemonClientError::Request(format!("Invalid logs reply: {}", e))),
+            Err(err) => Err(DaemonClientError::DaemonError(
+                String::from_utf8_lossy(&err.payload().to_bytes()).into_owned(),
+            )),
+        }
+    }
+
+    /// Status and health transitions the daemon recorded for a node.
+    pub async fn health_history(&self, name: &str) -> Result<gateway::HealthHistory> {
+        let replies = self
+            .session
+            .get(gateway::history_topic(&self.machine_id, name))
+            .target(zenoh::query::QueryTarget::BestMatching)
+            .timeout(RESPONSE_TIMEOUT)
+            .await
+            .map_err(|e| DaemonClientError::Request(e.to_string()))?;
+        let reply = replies
+            .recv_async()
+            .await
+            .map_err(|_| DaemonClientError::NotReachable)?;
+        match reply.into_result() {
+            Ok(sample) => gateway::from_cbor(&sample.payload().to_bytes())
+                .map_err(|e| DaemonClientError::Request(format!("Invalid history reply: {}", e))),
+            Err(err) => Err(DaemonClientError::DaemonError(
+                String::from_utf8_lossy(&err.payload().to_bytes()).into_owned(),
+            )),
+        }
+    }
+
+    /// Message types in the daemon's fleet-wide schema registry.
+    pub async fn schema_registry(
+        &self,
+        query: &gateway::SchemaQuery,
+    ) -> Result<Vec<gateway::RegisteredSchema>> {
+        let payload = gateway::to_cbor(query)
+            .map_err(|e| DaemonClientError::Request(format!("Invalid schema query: {}", e)))?;
+        let replies = self
+            .session
+            .get(gateway::schemas_topic(&self.machine_id))
+            .payload(payload)
+            .target(zenoh::query::QueryTarget::BestMatching)
+            .timeout(RESPONSE_TIMEOUT)
+            .await
+            .map_err(|e| DaemonClientError::Request(e.to_string()))?;
+        let reply = replies
+            .recv_async()
+            .await
+            .map_err(|_| DaemonClientError::NotReachable)?;
+        match reply.into_result() {
+            Ok(sample) => gateway::from_cbor(&sample.payload().to_bytes())
+                .map_err(|e| DaemonClientError::Request(format!("Invalid schema reply: {}", e))),
+            Err(err) => Err(DaemonClientError::DaemonError(
+                String::from_utf8_lossy(&err.payload().to_bytes()).into_owned(),
+            )),
+        }
+    }
+
     /// Send a node command (start, stop, restart, etc.) and return the result message.
     pub async fn send_node_command(&self, name: &str, command: &str) -> Result<String> {
         let cmd_type = match command {
@@ -291,6 +410,9 @@ impl DaemonClient {
             "disable_autostart" => DaemonCommandType::DisableAutostart {
                 name: name.to_string(),
             },
+            "update" => DaemonCommandType::UpdateNode {
+                name: name.to_string(),
+            },
             _ => {
                 return Err(DaemonClientError::Request(format!(
                     "Unknown command: {}",
@@ -316,6 +438,21 @@ impl DaemonClient {
         .await
     }
 
+    /// Register an instance of an installed node with an inline config.
+    pub async fn add_instance(
+        &self,
+        node: &str,
+        name: &str,
+        config_yaml: Option<&str>,
+    ) -> Result<String> {
+        self.send(DaemonCommandType::AddInstance {
+            node: node.to_string(),
+            name: name.to_string(),
+            config_yaml: config_yaml.map(|s| s.to_string()),
+        })
+        .await
+    }
+
     /// List nodes via the daemon gateway.
     pub async fn list_nodes(&self) -> Result<String> {
         self.send(DaemonCommandType::ListNodes).await
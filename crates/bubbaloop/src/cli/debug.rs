@@ -205,13 +205,17 @@ async fn list_topics(args: TopicsArgs) -> Result<()> {
     // Query the admin space for topic information
     // Note: This requires Zenoh admin API which may not be available in all deployments
     // Fallback to scanning known bubbaloop topics
-    let known_topics = vec!["bubbaloop/**"];
+    // Narrowed to one machine / key space by the global --machine and --scope
+    let known_topics = match crate::cli::target::explicit() {
+        Some(target) => vec![format!("{}/**", target.key_prefix())],
+        None => vec!["bubbaloop/**".to_string()],
+    };
 
     let mut found_topics = std::collections::HashSet::new();
 
     for pattern in &known_topics {
         let replies: Vec<_> = session
-            .get(*pattern)
+            .get(pattern)
             .target(QueryTarget::All)
             .timeout(Duration::from_secs(args.timeout))
             .await
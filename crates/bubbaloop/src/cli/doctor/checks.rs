@@ -758,6 +758,404 @@ fn check_node_names(bubbaloop_dir: &std::path::Path) -> Vec<DiagnosticResult> {
     results
 }
 
+/// Zenoh major version this CLI speaks; routers of another major version
+/// cannot talk to it.
+const ZENOH_MAJOR: u64 = 1;
+
+/// Ports bubbaloop services listen on, with the process names expected
+/// there (prefixes, as `ss` shows truncated command names).
+const SERVICE_PORTS: [(u16, &str, &[&str]); 3] = [
+    (7447, "Zenoh router", &["zenohd", "zenoh-bridge"]),
+    (crate::mcp::MCP_PORT, "MCP server", &["bubbaloop"]),
+    (10001, "dashboard bridge", &["zenoh-bridge", "zenohd"]),
+];
+
+/// Below this much free space under ~/.bubbaloop, builds and recordings fail.
+const MIN_FREE_DISK_MB: u64 = 1024;
+
+/// Clock difference between machines above which timestamps (recordings,
+/// health checks, HLC ordering) stop lining up.
+const MAX_CLOCK_SKEW_MS: i64 = 2000;
+
+/// Check the Zenoh router answers and report its version.
+pub async fn check_zenoh_router() -> Vec<DiagnosticResult> {
+    let session = match crate::cli::zenoh_session::create_zenoh_session(None).await {
+        Ok(s) => s,
+        Err(e) => {
+            return vec![DiagnosticResult::fail(
+                "Zenoh router",
+                &format!("not reachable: {}", e),
+                "Run: zenohd &",
+            )];
+        }
+    };
+
+    let mut version = None;
+    if let Ok(replies) = session
+        .get("@/*/router")
+        .timeout(std::time::Duration::from_secs(2))
+        .await
+    {
+        while let Ok(reply) = replies.recv_async().await {
+            if let Ok(sample) = reply.into_result() {
+                let data: serde_json::Value =
+                    serde_json::from_slice(&sample.payload().to_bytes()).unwrap_or_default();
+                version = router_version(&data);
+                break;
+            }
+        }
+    }
+
+    let result = match version {
+        None => DiagnosticResult::pass(
+            "Zenoh router",
+            "reachable (version unknown: the admin space did not answer)",
+        ),
+        Some(v) if major_version(&v) == Some(ZENOH_MAJOR) => {
+            DiagnosticResult::pass("Zenoh router", &format!("reachable ({})", v))
+        }
+        Some(v) => DiagnosticResult::fail(
+            "Zenoh router",
+            &format!(
+                "version {} is not compatible with this CLI (Zenoh {}.x)",
+                v, ZENOH_MAJOR
+            ),
+            &format!("Install zenohd {}.x", ZENOH_MAJOR),
+        ),
+    };
+    let _ = session.close().await;
+    vec![result]
+}
+
+/// `v1.9.0` from the router admin data (`"version": "v1.9.0 built with ..."`).
+fn router_version(data: &serde_json::Value) -> Option<String> {
+    let long = data.get("version")?.as_str()?;
+    long.split_whitespace().next().map(str::to_string)
+}
+
+fn major_version(version: &str) -> Option<u64> {
+    version
+        .trim_start_matches('v')
+        .split('.')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Check the user's systemd services keep running without a login session.
+pub async fn check_lingering() -> Vec<DiagnosticResult> {
+    let Ok(user) = std::env::var("USER") else {
+        return Vec::new();
+    };
+    let output = tokio::process::Command::new("loginctl")
+        .args(["show-user", &user, "-p", "Linger", "--value"])
+        .output()
+        .await;
+    // No logind (containers, non-systemd hosts): nothing to check.
+    let Some(output) = output.ok().filter(|o| o.status.success()) else {
+        return Vec::new();
+    };
+    if String::from_utf8_lossy(&output.stdout).trim() == "yes" {
+        vec![DiagnosticResult::pass("systemd lingering", "enabled")]
+    } else {
+        vec![DiagnosticResult::fail_with_action(
+            "systemd lingering",
+            "disabled: the daemon and nodes stop at logout and do not start at boot",
+            &format!("Run: loginctl enable-linger {}", user),
+            FixAction::EnableLinger,
+        )]
+    }
+}
+
+/// Check no other process holds the ports bubbaloop services use.
+pub async fn check_port_conflicts() -> Vec<DiagnosticResult> {
+    let output = tokio::process::Command::new("ss")
+        .args(["-Hltnp"])
+        .output()
+        .await;
+    let Some(output) = output.ok().filter(|o| o.status.success()) else {
+        return Vec::new();
+    };
+    let listeners = parse_listeners(&String::from_utf8_lossy(&output.stdout));
+
+    let mut results = Vec::new();
+    for (port, service, expected) in SERVICE_PORTS {
+        for (_, process) in listeners.iter().filter(|(p, _)| *p == port) {
+            // Processes of other users are not shown; give them the benefit of the doubt.
+            let Some(process) = process else { continue };
+            if !expected.iter().any(|e| process.starts_with(e)) {
+                results.push(DiagnosticResult::fail(
+                    &format!("Port {}", port),
+                    &format!(
+                        "used by '{}', but the {} ({}) needs it",
+                        process,
+                        service,
+                        expected.join(" or ")
+                    ),
+                    &format!("Stop '{}' or move it to another port", process),
+                ));
+            }
+        }
+    }
+    if results.is_empty() {
+        let ports: Vec<String> = SERVICE_PORTS
+            .iter()
+            .map(|(p, _, _)| p.to_string())
+            .collect();
+        results.push(DiagnosticResult::pass(
+            "Ports",
+            &format!("no conflicts on {}", ports.join(", ")),
+        ));
+    }
+    results
+}
+
+/// `(port, process)` of each listening socket in `ss -Hltnp` output.
+fn parse_listeners(ss_output: &str) -> Vec<(u16, Option<String>)> {
+    ss_output
+        .lines()
+        .filter_map(|line| {
+            let local = line.split_whitespace().nth(3)?;
+            let port = local.rsplit(':').next()?.parse().ok()?;
+            let process = line
+                .split_once("((\"")
+                .and_then(|(_, rest)| rest.split_once('"'))
+                .map(|(name, _)| name.to_string());
+            Some((port, process))
+        })
+        .collect()
+}
+
+/// Check free space on the filesystem holding ~/.bubbaloop.
+pub async fn check_disk_space() -> Vec<DiagnosticResult> {
+    let home = crate::daemon::registry::get_bubbaloop_home();
+    let dir = if home.exists() {
+        home.clone()
+    } else {
+        dirs::home_dir().unwrap_or_else(|| std::path::PathBuf::from("/"))
+    };
+    let output = tokio::process::Command::new("df")
+        .arg("-Pk")
+        .arg(&dir)
+        .output()
+        .await;
+    let Some(free_mb) = output
+        .ok()
+        .and_then(|o| parse_df_available_kb(&String::from_utf8_lossy(&o.stdout)))
+        .map(|kb| kb / 1024)
+    else {
+        return Vec::new();
+    };
+
+    if free_mb >= MIN_FREE_DISK_MB {
+        vec![DiagnosticResult::pass(
+            "Disk space",
+            &format!(
+                "{:.1} GB free under {}",
+                free_mb as f64 / 1024.0,
+                home.display()
+            ),
+        )]
+    } else {
+        vec![DiagnosticResult::fail(
+            "Disk space",
+            &format!("only {} MB free under {}", free_mb, home.display()),
+            "Free up space: old logs and recordings under ~/.bubbaloop, build artifacts with 'bubbaloop node clean <name>'",
+        )]
+    }
+}
+
+/// Available kilobytes from `df -Pk` output.
+fn parse_df_available_kb(df_output: &str) -> Option<u64> {
+    df_output
+        .lines()
+        .nth(1)?
+        .split_whitespace()
+        .nth(3)?
+        .parse()
+        .ok()
+}
+
+/// Check every registered node directory still exists.
+pub async fn check_stale_registry() -> Vec<DiagnosticResult> {
+    let Ok(registry) = crate::daemon::registry::load_registry() else {
+        return vec![DiagnosticResult::fail(
+            "Node registry",
+            "~/.bubbaloop/nodes.json is unreadable",
+            "Fix or remove ~/.bubbaloop/nodes.json and re-add the nodes",
+        )];
+    };
+    let stale = stale_entries(&registry);
+    if stale.is_empty() {
+        return vec![DiagnosticResult::pass(
+            "Node registry",
+            &format!(
+                "{} entries, all node directories present",
+                registry.nodes.len()
+            ),
+        )];
+    }
+    let paths: Vec<String> = stale.iter().map(|e| e.path.clone()).collect();
+    vec![DiagnosticResult::fail_with_action(
+        "Node registry",
+        &format!(
+            "{} stale entr{} (node.yaml missing): {}",
+            stale.len(),
+            if stale.len() == 1 { "y" } else { "ies" },
+            paths.join(", ")
+        ),
+        "Run: bubbaloop node remove <name>",
+        FixAction::RemoveStaleNodes(paths),
+    )]
+}
+
+/// Registry entries whose directory (or its node.yaml) is gone.
+fn stale_entries(
+    registry: &crate::daemon::registry::NodesRegistry,
+) -> Vec<&crate::daemon::registry::NodeEntry> {
+    registry
+        .nodes
+        .iter()
+        .filter(|e| !std::path::Path::new(&e.path).join("node.yaml").is_file())
+        .collect()
+}
+
+/// Check for daemons running twice, locally or under one machine id on the
+/// network, and for clocks drifting between machines.
+pub async fn check_daemon_fleet() -> Vec<DiagnosticResult> {
+    let mut results = Vec::new();
+
+    let ps = tokio::process::Command::new("ps")
+        .args(["-eo", "pid=,args="])
+        .output()
+        .await;
+    if let Ok(ps) = ps {
+        let pids = daemon_pids(&String::from_utf8_lossy(&ps.stdout), std::process::id());
+        if pids.len() > 1 {
+            let pids: Vec<String> = pids.iter().map(u32::to_string).collect();
+            results.push(DiagnosticResult::fail(
+                "Duplicate daemons",
+                &format!("{} daemon processes on this machine (pids {})", pids.len(), pids.join(", ")),
+                "Keep the systemd one (systemctl --user status bubbaloop-daemon) and kill the others",
+            ));
+        }
+    }
+
+    let Ok(session) = crate::cli::zenoh_session::create_zenoh_session(None).await else {
+        return results;
+    };
+    let sent_ms = crate::daemon::util::now_ms();
+    let mut manifests = Vec::new();
+    if let Ok(replies) = session
+        .get(crate::daemon::gateway::manifest_wildcard())
+        .target(zenoh::query::QueryTarget::All)
+        .timeout(std::time::Duration::from_secs(2))
+        .await
+    {
+        while let Ok(reply) = replies.recv_async().await {
+            let received_ms = crate::daemon::util::now_ms();
+            if let Ok(sample) = reply.into_result() {
+                if let Ok(manifest) = crate::daemon::gateway::from_cbor::<
+                    crate::daemon::gateway::DaemonManifest,
+                >(&sample.payload().to_bytes())
+                {
+                    manifests.push((manifest, received_ms));
+                }
+            }
+        }
+    }
+    let _ = session.close().await;
+
+    let mut machines: Vec<&str> = manifests
+        .iter()
+        .map(|(m, _)| m.machine_id.as_str())
+        .collect();
+    machines.sort_unstable();
+    let duplicated: Vec<&str> = machines
+        .windows(2)
+        .filter(|w| w[0] == w[1])
+        .map(|w| w[0])
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .collect();
+    if duplicated.is_empty() {
+        if results.is_empty() {
+            results.push(DiagnosticResult::pass(
+                "Duplicate daemons",
+                &format!("none ({} daemon(s) on the network)", manifests.len()),
+            ));
+        }
+    } else {
+        results.push(DiagnosticResult::fail(
+            "Duplicate daemons",
+            &format!(
+                "several daemons answer as machine {}",
+                duplicated.join(", ")
+            ),
+            "Give each machine a unique id: set BUBBALOOP_MACHINE_ID in the daemon's environment",
+        ));
+    }
+
+    let skewed: Vec<String> = manifests
+        .iter()
+        .filter(|(m, _)| m.timestamp_ms > 0)
+        .filter_map(|(m, received_ms)| {
+            let skew = clock_skew_ms(m.timestamp_ms, sent_ms, *received_ms);
+            (skew.abs() > MAX_CLOCK_SKEW_MS).then(|| {
+                format!(
+                    "{} is {:.1}s {}",
+                    m.machine_id,
+                    skew.abs() as f64 / 1000.0,
+                    if skew > 0 { "ahead" } else { "behind" }
+                )
+            })
+        })
+        .collect();
+    if !skewed.is_empty() {
+        results.push(DiagnosticResult::fail(
+            "Clock skew",
+            &skewed.join(", "),
+            "Enable time sync on every machine: sudo timedatectl set-ntp true",
+        ));
+    } else if manifests.iter().any(|(m, _)| m.timestamp_ms > 0) {
+        results.push(DiagnosticResult::pass(
+            "Clock skew",
+            &format!(
+                "daemon clocks within {}s of this machine",
+                MAX_CLOCK_SKEW_MS / 1000
+            ),
+        ));
+    }
+
+    results
+}
+
+/// Pids of `bubbaloop daemon [run]` processes other than `own_pid`, from
+/// `ps -eo pid=,args=` output.
+fn daemon_pids(ps_output: &str, own_pid: u32) -> Vec<u32> {
+    ps_output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid: u32 = fields.next()?.parse().ok()?;
+            let program = std::path::Path::new(fields.next()?).file_name()?.to_str()?;
+            let is_daemon = program == "bubbaloop"
+                && fields.next() == Some("daemon")
+                && matches!(
+                    fields.next(),
+                    None | Some("run") | Some("-z" | "--zenoh-endpoint")
+                );
+            (is_daemon && pid != own_pid).then_some(pid)
+        })
+        .collect()
+}
+
+/// How far a remote clock is ahead of ours, assuming the reply was stamped
+/// halfway through the round trip.
+fn clock_skew_ms(remote_ms: i64, sent_ms: i64, received_ms: i64) -> i64 {
+    remote_ms - (sent_ms + received_ms) / 2
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -901,4 +1299,75 @@ mod tests {
         assert!(results[0].passed);
         assert!(!results[1].passed);
     }
+
+    #[test]
+    fn test_router_version() {
+        let data = serde_json::json!({"zid": "a1", "version": "v1.9.0 built with rustc 1.85.0"});
+        let version = router_version(&data).unwrap();
+        assert_eq!(version, "v1.9.0");
+        assert_eq!(major_version(&version), Some(1));
+        assert_eq!(router_version(&serde_json::json!({})), None);
+    }
+
+    #[test]
+    fn test_parse_listeners() {
+        let ss = "LISTEN 0 4096 127.0.0.1:7447 0.0.0.0:* users:((\"zenohd\",pid=812,fd=9))\n\
+                  LISTEN 0 128 [::]:8088 [::]:*\n\
+                  LISTEN 0 511 0.0.0.0:10001 0.0.0.0:* users:((\"node\",pid=90,fd=20))\n";
+        assert_eq!(
+            parse_listeners(ss),
+            vec![
+                (7447, Some("zenohd".to_string())),
+                (8088, None),
+                (10001, Some("node".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_df_available_kb() {
+        let df = "Filesystem 1024-blocks Used Available Capacity Mounted on\n\
+                  /dev/nvme0n1p2 61255492 48118744 10000000 83% /\n";
+        assert_eq!(parse_df_available_kb(df), Some(10_000_000));
+        assert_eq!(parse_df_available_kb(""), None);
+    }
+
+    #[test]
+    fn test_stale_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("node.yaml"), "name: cam\n").unwrap();
+        let entry = |path: &str| crate::daemon::registry::NodeEntry {
+            path: path.to_string(),
+            added_at: String::new(),
+            name_override: None,
+            config_override: None,
+        };
+        let registry = crate::daemon::registry::NodesRegistry {
+            nodes: vec![
+                entry(dir.path().to_str().unwrap()),
+                entry("/nonexistent/bubbaloop/node"),
+            ],
+        };
+        let stale = stale_entries(&registry);
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].path, "/nonexistent/bubbaloop/node");
+    }
+
+    #[test]
+    fn test_daemon_pids() {
+        let ps = "  101 /home/u/.bubbaloop/bin/bubbaloop daemon run\n\
+                  \x20 202 bubbaloop daemon\n\
+                  \x20 303 bubbaloop daemon status\n\
+                  \x20 404 bubbaloop doctor\n\
+                  \x20 505 bubbaloop daemon -z tcp/10.0.0.2:7447\n";
+        assert_eq!(daemon_pids(ps, 404), vec![101, 202, 505]);
+        assert_eq!(daemon_pids(ps, 202), vec![101, 505]);
+    }
+
+    #[test]
+    fn test_clock_skew_ms() {
+        assert_eq!(clock_skew_ms(1_000, 900, 1_100), 0);
+        assert_eq!(clock_skew_ms(5_000, 900, 1_100), 4_000);
+        assert_eq!(clock_skew_ms(0, 900, 1_100), -1_000);
+    }
 }
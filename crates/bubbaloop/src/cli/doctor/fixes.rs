@@ -19,6 +19,9 @@ pub enum FixAction {
     StartBridgeService,
     CreateZenohConfig,
     CreateMarketplaceSources,
+    EnableLinger,
+    /// Registry entries (node paths) whose directory is gone.
+    RemoveStaleNodes(Vec<String>),
 }
 
 impl FixAction {
@@ -33,6 +36,8 @@ impl FixAction {
             FixAction::CreateMarketplaceSources => {
                 "Create marketplace sources with official registry"
             }
+            FixAction::EnableLinger => "Enable systemd lingering for this user",
+            FixAction::RemoveStaleNodes(_) => "Remove stale node registry entries",
         }
     }
 
@@ -177,6 +182,32 @@ impl FixAction {
                     sources_path.display()
                 ))
             }
+            FixAction::EnableLinger => {
+                let status = tokio::process::Command::new("loginctl")
+                    .arg("enable-linger")
+                    .status()
+                    .await?;
+                if !status.success() {
+                    return Err(anyhow!("loginctl enable-linger failed ({})", status));
+                }
+                Ok("Lingering enabled: services now start at boot".to_string())
+            }
+            FixAction::RemoveStaleNodes(paths) => {
+                // The daemon re-reads the registry on its next refresh.
+                let mut registry = crate::daemon::registry::load_registry()?;
+                let before = registry.nodes.len();
+                registry.nodes.retain(|e| !paths.contains(&e.path));
+                crate::daemon::registry::save_registry(&registry)?;
+                Ok(format!(
+                    "Removed {} stale entr{} from the node registry",
+                    before - registry.nodes.len(),
+                    if before - registry.nodes.len() == 1 {
+                        "y"
+                    } else {
+                        "ies"
+                    }
+                ))
+            }
         }
     }
 }
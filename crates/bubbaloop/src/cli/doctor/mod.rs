@@ -1,12 +1,16 @@
 //! Doctor command for system diagnostics
 //!
 //! Performs comprehensive health checks on all bubbaloop components:
-//! - System services (zenohd, daemon, bridge)
-//! - Daemon HTTP connectivity and health (via REST API)
+//! - Configuration, node registry (stale entries) and disk space
+//! - System services (zenohd, daemon, bridge), systemd lingering, port conflicts
+//! - Zenoh router reachability and version, daemon connectivity
+//! - Daemon health, duplicate daemons and clock skew between machines
 //! - Zenoh data plane availability (port check for node streaming)
 //! - Security posture
 //!
-//! Provides actionable fixes for each issue found.
+//! Provides actionable fixes for each issue found; `--fix` applies the safe
+//! ones (starting services, writing default configs, enabling lingering,
+//! dropping registry entries of deleted nodes).
 
 pub mod checks;
 pub mod fixes;
@@ -104,6 +108,8 @@ pub async fn run(fix: bool, json: bool, check: &str) -> Result<()> {
             println!("[1/6] Checking configuration...");
         }
         results.extend(checks::check_configuration().await);
+        results.extend(checks::check_stale_registry().await);
+        results.extend(checks::check_disk_space().await);
 
         if fix && !json {
             fixes_applied += apply_fixes(&mut results).await;
@@ -119,6 +125,8 @@ pub async fn run(fix: bool, json: bool, check: &str) -> Result<()> {
             println!("[2/6] Checking system services...");
         }
         results.extend(checks::check_system_services().await);
+        results.extend(checks::check_lingering().await);
+        results.extend(checks::check_port_conflicts().await);
 
         if fix && !json {
             fixes_applied += apply_fixes(&mut results).await;
@@ -133,6 +141,7 @@ pub async fn run(fix: bool, json: bool, check: &str) -> Result<()> {
         if !json {
             println!("[3/6] Checking daemon connectivity...");
         }
+        results.extend(checks::check_zenoh_router().await);
         results.extend(checks::check_daemon_connectivity().await);
 
         if !json {
@@ -146,6 +155,7 @@ pub async fn run(fix: bool, json: bool, check: &str) -> Result<()> {
             println!("[4/6] Checking daemon health...");
         }
         results.extend(checks::check_daemon_health().await);
+        results.extend(checks::check_daemon_fleet().await);
 
         if !json {
             println!();
@@ -0,0 +1,186 @@
+//! Process exit codes of the `bubbaloop` binary.
+//!
+//! Scripts can tell why a command failed without parsing its message:
+//!
+//! | Code | Meaning |
+//! |------|---------|
+//! | 0 | Success |
+//! | 1 | Any other failure |
+//! | 2 | Invalid arguments |
+//! | 3 | Node (or recorder/player) not found |
+//! | 4 | Daemon or Zenoh unreachable |
+//! | 5 | The daemon or node ran the command and it failed |
+
+use std::error::Error;
+
+use super::agent_rules::AgentRulesError;
+use super::bench::BenchError;
+use super::completions::CompletionsError;
+use super::daemon_client::DaemonClientError;
+use super::node::NodeError;
+use super::record::RecordError;
+use super::schema::SchemaError;
+use super::self_update::SelfUpdateError;
+use super::setup::SetupError;
+use super::skill::SkillCommandError;
+use super::target::TargetError;
+use super::topic::TopicError;
+use super::up::UpError;
+use crate::skills::SkillError;
+
+pub const SUCCESS: i32 = 0;
+pub const FAILURE: i32 = 1;
+pub const USAGE: i32 = 2;
+pub const NOT_FOUND: i32 = 3;
+pub const DAEMON_UNREACHABLE: i32 = 4;
+pub const COMMAND_FAILED: i32 = 5;
+
+/// Exit code for `err`, from the first error in its source chain that
+/// has a specific one.
+pub fn code_for(err: &(dyn Error + 'static)) -> i32 {
+    let mut current = Some(err);
+    while let Some(err) = current {
+        if let Some(code) = specific_code(err) {
+            return code;
+        }
+        current = err.source();
+    }
+    FAILURE
+}
+
+fn specific_code(err: &(dyn Error + 'static)) -> Option<i32> {
+    if let Some(err) = err.downcast_ref::<DaemonClientError>() {
+        return Some(match err {
+            DaemonClientError::NotReachable
+            | DaemonClientError::Timeout
+            | DaemonClientError::Request(_) => DAEMON_UNREACHABLE,
+            // The daemon only sends text back; node lookups fail with
+            // "Node not found: <name>".
+            DaemonClientError::DaemonError(msg) if msg.to_lowercase().contains("not found") => {
+                NOT_FOUND
+            }
+            DaemonClientError::DaemonError(_) => COMMAND_FAILED,
+        });
+    }
+    if let Some(err) = err.downcast_ref::<NodeError>() {
+        return match err {
+            NodeError::NotFound(_) => Some(NOT_FOUND),
+            NodeError::CommandFailed(_) => Some(COMMAND_FAILED),
+            NodeError::InvalidArgs(_) | NodeError::InvalidUrl(_) => Some(USAGE),
+            _ => None,
+        };
+    }
+    if let Some(err) = err.downcast_ref::<RecordError>() {
+        return match err {
+            RecordError::InvalidArgument(_) => Some(USAGE),
+            RecordError::NoReply(_) => Some(NOT_FOUND),
+            RecordError::Rejected(_) => Some(COMMAND_FAILED),
+            RecordError::Zenoh(_) => Some(DAEMON_UNREACHABLE),
+            RecordError::Cbor(_) => None,
+        };
+    }
+    if let Some(err) = err.downcast_ref::<AgentRulesError>() {
+        return match err {
+            AgentRulesError::InvalidArgument(_) => Some(USAGE),
+            AgentRulesError::NoReply(_) | AgentRulesError::Zenoh(_) => Some(DAEMON_UNREACHABLE),
+            AgentRulesError::Rejected(msg) if msg.contains("not found") => Some(NOT_FOUND),
+            AgentRulesError::Rejected(_) => Some(COMMAND_FAILED),
+            AgentRulesError::Json(_) => None,
+        };
+    }
+    if let Some(err) = err.downcast_ref::<BenchError>() {
+        return match err {
+            BenchError::InvalidArgument(_) => Some(USAGE),
+            BenchError::NoResponder(_) => Some(NOT_FOUND),
+            BenchError::Zenoh(_) => Some(DAEMON_UNREACHABLE),
+            BenchError::Json(_) => None,
+        };
+    }
+    if let Some(err) = err.downcast_ref::<SelfUpdateError>() {
+        return match err {
+            SelfUpdateError::InvalidArgument(_) => Some(USAGE),
+            SelfUpdateError::NoBackup(_) => Some(NOT_FOUND),
+            _ => None,
+        };
+    }
+    if let Some(err) = err.downcast_ref::<SchemaError>() {
+        return match err {
+            SchemaError::InvalidArgument(_) => Some(USAGE),
+            SchemaError::NotFound(_) => Some(NOT_FOUND),
+            SchemaError::Zenoh(_) => Some(DAEMON_UNREACHABLE),
+            SchemaError::Json(_) | SchemaError::Daemon(_) => None,
+        };
+    }
+    if let Some(err) = err.downcast_ref::<UpError>() {
+        return match err {
+            UpError::Profile(_) => Some(USAGE),
+            UpError::Daemon(_) => Some(DAEMON_UNREACHABLE),
+            UpError::Unhealthy(_) => Some(COMMAND_FAILED),
+            _ => None,
+        };
+    }
+    if let Some(TopicError::InvalidArgument(_)) = err.downcast_ref::<TopicError>() {
+        return Some(USAGE);
+    }
+    if let Some(SetupError::InvalidMachineId(_)) = err.downcast_ref::<SetupError>() {
+        return Some(USAGE);
+    }
+    if let Some(SkillCommandError::NotFound(_)) = err.downcast_ref::<SkillCommandError>() {
+        return Some(NOT_FOUND);
+    }
+    if let Some(err) = err.downcast_ref::<SkillError>() {
+        return match err {
+            SkillError::InvalidName(_)
+            | SkillError::UnknownDriver(_)
+            | SkillError::ParseError { .. } => Some(USAGE),
+            SkillError::Io(_) => None,
+        };
+    }
+    if err.is::<TargetError>() || err.is::<CompletionsError>() {
+        return Some(USAGE);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn code(err: impl Error + 'static) -> i32 {
+        let boxed: Box<dyn Error> = Box::new(err);
+        code_for(boxed.as_ref())
+    }
+
+    #[test]
+    fn codes_follow_the_source_chain() {
+        assert_eq!(code(NodeError::NotFound("cam".into())), NOT_FOUND);
+        assert_eq!(code(NodeError::InvalidArgs("x".into())), USAGE);
+        assert_eq!(code(NodeError::CommandFailed("x".into())), COMMAND_FAILED);
+        assert_eq!(
+            code(NodeError::Daemon(DaemonClientError::NotReachable)),
+            DAEMON_UNREACHABLE
+        );
+        assert_eq!(
+            code(NodeError::Daemon(DaemonClientError::DaemonError(
+                "Node not found: cam".into()
+            ))),
+            NOT_FOUND
+        );
+        assert_eq!(
+            code(NodeError::Daemon(DaemonClientError::DaemonError(
+                "Build command must start with cargo".into()
+            ))),
+            COMMAND_FAILED
+        );
+        // Through another command's error type.
+        assert_eq!(
+            code(crate::cli::launch::LaunchError::Node(NodeError::NotFound(
+                "cam".into()
+            ))),
+            NOT_FOUND
+        );
+        assert_eq!(code(RecordError::NoReply("k".into())), NOT_FOUND);
+        assert_eq!(code(TargetError::InvalidScope("prod".into())), USAGE);
+        assert_eq!(code(std::io::Error::other("disk")), FAILURE);
+    }
+}
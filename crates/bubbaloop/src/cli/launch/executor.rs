@@ -0,0 +1,1459 @@
+//! Launch executor: runs the nodes of a [`LaunchPlan`] as child processes.
+//!
+//! Each planned node is resolved to its installed base node under
+//! `~/.bubbaloop/nodes/`, its inline config is written to
+//! `~/.bubbaloop/configs/{instance}.yaml`, and the node binary is spawned
+//! with `-c <config>` and the node's `env:`. A node's `namespace:` prefixes
+//! the config `name`, and so its topics. Child stdout and stderr are
+//! forwarded line by line with a `[name]` prefix, optionally teed to
+//! per-node log files (see [`super::output`]).
+//!
+//! Startup is event driven: a node is spawned once every node it
+//! `depends_on` is ready — spawned, and past its `ready:` probe if it has
+//! one. Nodes with `autostart: false` wait for a `start:` action. Every
+//! [`ProcessEvent`] fires the node's lifecycle handlers: `on_start` after a
+//! spawn, `on_exit` after a clean exit, `on_crash` otherwise. The executor
+//! runs until all children have exited, a `shutdown` action fires,
+//! `bubbaloop launch stop` is run, or Ctrl-C, and tears every remaining
+//! child down in reverse start order (SIGTERM, then SIGKILL after the
+//! node's `stop_timeout`). While it runs, per-node state is served on the
+//! launch status queryable (see [`super::status`]).
+//!
+//! Nodes with a `machine:` of another host, and every node when the
+//! executor is [`daemon_managed`](LaunchExecutor::daemon_managed), are not
+//! spawned here but handed to a daemon (see [`super::remote`]).
+//!
+//! A node with a `start_delay` is spawned that long after it becomes due.
+//! A node that is not ready `start_timeout` after it was spawned is marked
+//! failed, stopped, and handled like a crash.
+//!
+//! An executor with a [`reloader`](LaunchExecutor::reloader) re-reads its
+//! launch file on SIGHUP or `bubbaloop launch reload`, and applies only the
+//! difference: removed nodes are stopped, added autostart nodes started,
+//! and running nodes whose settings changed are restarted.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::process::{Child, Command};
+use tokio::sync::{mpsc, oneshot};
+
+use super::output;
+use super::plan::{LaunchPlan, PlanDiff, PlannedNode};
+use super::python;
+use super::readiness::{self, ProbeContext};
+use super::status::{self, ControlReply, StatusBoard};
+use super::write_config;
+use super::{remote, LaunchAction, LaunchError, Result};
+use crate::daemon::registry::{find_installed_node, NodeManifest};
+
+/// How long a child gets to exit after SIGTERM before it is killed, unless
+/// the node sets `stop_timeout`.
+const STOP_GRACE: Duration = Duration::from_secs(3);
+
+/// Lifecycle events reported by the executor and the tasks that own each
+/// child process.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProcessEvent {
+    /// A child was spawned.
+    Started { name: String, pid: u32 },
+    /// A child passed its readiness probe.
+    Ready { name: String },
+    /// A child's readiness probe failed or timed out.
+    NotReady { name: String, reason: String },
+    /// A child exited on its own. `code` is `None` when killed by a signal.
+    Exited { name: String, code: Option<i32> },
+    /// A remote node could not be dispatched to its machine.
+    Failed { name: String, reason: String },
+    /// A node's `start_delay` elapsed.
+    Due { name: String },
+    /// `start_timeout` expired for start number `attempt` of a node.
+    StartTimedOut { name: String, attempt: u64 },
+}
+
+/// A node started by the executor: a local child, or an instance run by
+/// another machine's daemon (`pid` 0).
+struct RunningNode {
+    name: String,
+    pid: u32,
+    /// `(machine, instance)` for remote nodes.
+    remote: Option<(String, String)>,
+    /// Executor-wide start counter, matched against `StartTimedOut`.
+    attempt: u64,
+    stop_timeout: Duration,
+}
+
+/// Spawns and supervises the child processes of a launch plan.
+pub struct LaunchExecutor {
+    machine_id: String,
+    nodes_dir: PathBuf,
+    configs_dir: PathBuf,
+    running: Vec<RunningNode>,
+    /// Autostart nodes still waiting for their dependencies, in plan order.
+    pending: Vec<String>,
+    /// Nodes that are up and, if probed, have passed their probe.
+    ready: HashSet<String>,
+    /// Nodes stopped by a `stop:` action or a start timeout; their exit
+    /// runs no handlers.
+    stopping: HashSet<String>,
+    /// Nodes waiting out their `start_delay`.
+    delayed: HashSet<String>,
+    /// Nodes stopped by a reload, started again once they have exited.
+    restarting: HashSet<String>,
+    /// Number of spawns so far.
+    attempts: u64,
+    /// Run local nodes under this machine's daemon instead of as children.
+    daemon_managed: bool,
+    /// Directory for per-node log files (`None`: terminal only).
+    log_dir: Option<PathBuf>,
+    /// Color output prefixes.
+    color: bool,
+    /// Width node names are padded to in output prefixes.
+    prefix_width: usize,
+    /// Shared session for readiness probes and the status queryable.
+    session: Option<Arc<zenoh::Session>>,
+    /// Per-node state served by the status queryable.
+    status: Option<Arc<Mutex<StatusBoard>>>,
+    /// Daemon instances this launch registered, removed at shutdown.
+    registered: remote::Registered,
+    /// Re-resolves the launch file on reload.
+    reloader: Option<Box<dyn Fn() -> Result<LaunchPlan> + Send>>,
+}
+
+impl LaunchExecutor {
+    pub fn new(nodes_dir: PathBuf, configs_dir: PathBuf) -> Self {
+        Self {
+            machine_id: crate::daemon::util::get_machine_id(),
+            nodes_dir,
+            configs_dir,
+            running: Vec::new(),
+            pending: Vec::new(),
+            ready: HashSet::new(),
+            stopping: HashSet::new(),
+            delayed: HashSet::new(),
+            restarting: HashSet::new(),
+            attempts: 0,
+            daemon_managed: false,
+            log_dir: None,
+            color: output::use_color(),
+            prefix_width: 0,
+            session: None,
+            status: None,
+            registered: remote::Registered::default(),
+            reloader: None,
+        }
+    }
+
+    /// Register every local node with this machine's daemon (add, install,
+    /// start) instead of spawning it, so it gets the daemon's health
+    /// monitoring and shows up in `bubbaloop node list`. Nodes are stopped
+    /// through the daemon when the launch ends.
+    pub fn daemon_managed(mut self, enabled: bool) -> Self {
+        self.daemon_managed = enabled;
+        self
+    }
+
+    /// Also append each local node's output to `{dir}/{node}.log`.
+    pub fn log_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.log_dir = dir;
+        self
+    }
+
+    /// Accept reloads (SIGHUP, `bubbaloop launch reload`), resolving the
+    /// new plan with `reloader`.
+    pub fn reloader(mut self, reloader: impl Fn() -> Result<LaunchPlan> + Send + 'static) -> Self {
+        self.reloader = Some(Box::new(reloader));
+        self
+    }
+
+    fn update_status(&self, f: impl FnOnce(&mut StatusBoard)) {
+        if let Some(board) = &self.status {
+            f(&mut board.lock().unwrap_or_else(|e| e.into_inner()));
+        }
+    }
+
+    /// Start every autostart node of `plan`, then block until all children
+    /// have exited, a `shutdown` action fires, or Ctrl-C is pressed.
+    pub async fn run(mut self, plan: &LaunchPlan) -> Result<()> {
+        let mut plan = plan.clone();
+        self.prefix_width = plan.nodes.iter().map(|n| n.name.len()).max().unwrap_or(0);
+        let needs_zenoh =
+            readiness::needs_zenoh(plan.nodes.iter().filter_map(|n| n.ready.as_ref()))
+                || plan.nodes.iter().any(|n| self.daemon_machine(n).is_some());
+        if let Some(node) = plan.nodes.iter().find(|n| {
+            self.daemon_machine(n).is_some() && n.ready.as_ref().is_some_and(|r| r.stdout.is_some())
+        }) {
+            return Err(LaunchError::Instance(format!(
+                "'{}' runs under a daemon, where stdout readiness probes do not work",
+                node.name
+            )));
+        }
+        match crate::cli::zenoh_session::create_zenoh_session(None).await {
+            Ok(session) => self.session = Some(session),
+            Err(e) if needs_zenoh => {
+                return Err(LaunchError::Instance(format!(
+                    "readiness probes and remote nodes need Zenoh: {}",
+                    e
+                )));
+            }
+            Err(e) => log::warn!("[launch] no Zenoh session, status unavailable: {}", e),
+        }
+        let board = Arc::new(Mutex::new(StatusBoard::new(&plan, &self.machine_id)));
+        if let Some(session) = &self.session {
+            status::serve(session.clone(), board.clone());
+        }
+        self.status = Some(board);
+
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let (reload_tx, mut reload_rx) = mpsc::unbounded_channel::<ControlReply>();
+        if self.reloader.is_some() {
+            if let Some(session) = &self.session {
+                let key = status::reload_topic(&self.machine_id, &plan.name);
+                status::serve_control(session.clone(), key, reload_tx.clone());
+            }
+            tokio::spawn(forward_sighup(reload_tx));
+        }
+        let (stop_tx, mut stop_rx) = mpsc::unbounded_channel::<ControlReply>();
+        if let Some(session) = &self.session {
+            let key = status::stop_topic(&self.machine_id, &plan.name);
+            status::serve_control(session.clone(), key, stop_tx);
+        }
+        self.pending = plan
+            .nodes
+            .iter()
+            .filter(|n| n.autostart)
+            .map(|n| n.name.clone())
+            .collect();
+        if let Err(e) = self.start_unblocked(&plan, &event_tx) {
+            self.shutdown().await;
+            return Err(e);
+        }
+
+        if self.running.is_empty() && self.delayed.is_empty() {
+            println!("Launch '{}' has no nodes to start.", plan.name);
+            return Ok(());
+        }
+        println!(
+            "Launching {} node(s) from '{}'. Press Ctrl-C to stop.",
+            plan.nodes.iter().filter(|n| n.autostart).count(),
+            plan.name
+        );
+
+        let mut result = Ok(());
+        let mut stop_reply = None;
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {
+                    println!("\nStopping launch '{}'...", plan.name);
+                    break;
+                }
+                Some(reply) = stop_rx.recv() => {
+                    println!("Stop requested, stopping launch '{}'...", plan.name);
+                    stop_reply = Some(reply);
+                    break;
+                }
+                Some(reply) = reload_rx.recv() => {
+                    let outcome = self.reload(&mut plan, &event_tx).await;
+                    match &outcome {
+                        Ok(diff) => println!("Reloaded '{}': {}", plan.name, diff),
+                        Err(e) => log::error!("[launch] reload of '{}' failed: {}", plan.name, e),
+                    }
+                    let _ = reply.send(outcome.map(|d| d.to_string()).map_err(|e| e.to_string()));
+                }
+                Some(event) = event_rx.recv() => match self.handle_event(&plan, event, &event_tx) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        println!("Shutdown requested, stopping launch '{}'...", plan.name);
+                        break;
+                    }
+                    Err(e) => {
+                        result = Err(e);
+                        break;
+                    }
+                },
+            }
+            if self.running.is_empty() && self.delayed.is_empty() {
+                if !self.pending.is_empty() {
+                    log::warn!(
+                        "[launch] never started (dependencies not ready): {}",
+                        self.pending.join(", ")
+                    );
+                }
+                println!("All nodes of '{}' have exited.", plan.name);
+                break;
+            }
+        }
+
+        let stopped = self.running.len();
+        self.shutdown().await;
+        if let Some(reply) = stop_reply {
+            let _ = reply.send(Ok(format!("stopped {} node(s)", stopped)));
+        }
+        result
+    }
+
+    /// Apply an event and run the node's lifecycle actions.
+    ///
+    /// Returns `Ok(false)` when an action asked for the launch to shut down,
+    /// and an error when a node fails its readiness probe or a dependent
+    /// cannot be started.
+    fn handle_event(
+        &mut self,
+        plan: &LaunchPlan,
+        event: ProcessEvent,
+        event_tx: &mpsc::UnboundedSender<ProcessEvent>,
+    ) -> Result<bool> {
+        let (name, actions, code) = match &event {
+            ProcessEvent::Started { name, pid } => {
+                let node = plan_node(plan, name);
+                let probed = node.is_some_and(|n| n.ready.is_some());
+                self.update_status(|b| b.started(name, (*pid != 0).then_some(*pid), probed));
+                if node.is_some_and(|n| n.ready.is_none()) {
+                    self.ready.insert(name.clone());
+                }
+                (name, node.map(|n| n.on_start.as_slice()), None)
+            }
+            ProcessEvent::Ready { name } | ProcessEvent::NotReady { name, .. }
+                if self.stopping.contains(name) =>
+            {
+                (name, None, None)
+            }
+            ProcessEvent::Ready { name } => {
+                println!("[{}] ready", name);
+                self.update_status(|b| b.ready(name));
+                self.ready.insert(name.clone());
+                (name, None, None)
+            }
+            ProcessEvent::NotReady { name, reason } => {
+                return Err(LaunchError::Instance(format!(
+                    "'{}' failed its readiness check: {}",
+                    name, reason
+                )));
+            }
+            ProcessEvent::Failed { name, reason } => {
+                self.running.retain(|r| &r.name != name);
+                self.update_status(|b| b.failed(name));
+                return Err(LaunchError::Instance(format!(
+                    "failed to start '{}': {}",
+                    name, reason
+                )));
+            }
+            ProcessEvent::Due { name } => {
+                self.delayed.remove(name);
+                if let Some(node) = plan_node(plan, name) {
+                    self.spawn(node, event_tx).inspect_err(|e| {
+                        log::error!("[launch] failed to start {}: {}", name, e);
+                    })?;
+                }
+                (name, None, None)
+            }
+            ProcessEvent::StartTimedOut { name, attempt } => {
+                let current = self
+                    .running
+                    .iter()
+                    .any(|r| &r.name == name && r.attempt == *attempt);
+                if !current || self.ready.contains(name) || self.stopping.contains(name) {
+                    (name, None, None)
+                } else {
+                    let node = plan_node(plan, name);
+                    let timeout = node.and_then(|n| n.start_timeout).unwrap_or_default();
+                    println!("[{}] failed: not ready after {:?}", name, timeout);
+                    self.stop_node(name);
+                    self.update_status(|b| b.failed(name));
+                    (name, node.map(|n| n.on_crash.as_slice()), None)
+                }
+            }
+            ProcessEvent::Exited { name, code } if self.stopping.remove(name) => {
+                self.running.retain(|r| &r.name != name);
+                self.update_status(|b| b.exited(name, *code));
+                if self.restarting.remove(name) {
+                    self.pending.push(name.clone());
+                }
+                (name, None, *code)
+            }
+            ProcessEvent::Exited { name, code } => {
+                match code {
+                    Some(0) => println!("[{}] exited cleanly", name),
+                    Some(c) => println!("[{}] exited with code {}", name, c),
+                    None => println!("[{}] terminated by signal", name),
+                }
+                self.running.retain(|r| &r.name != name);
+                self.ready.remove(name);
+                self.update_status(|b| b.exited(name, *code));
+                let node = plan_node(plan, name);
+                let actions = match code {
+                    Some(0) => node.map(|n| n.on_exit.as_slice()),
+                    _ => node.map(|n| n.on_crash.as_slice()),
+                };
+                (name, actions, *code)
+            }
+        };
+        if !self.run_actions(plan, name, actions.unwrap_or_default(), code, event_tx) {
+            return Ok(false);
+        }
+        self.start_unblocked(plan, event_tx)?;
+        Ok(true)
+    }
+
+    /// Spawn every pending node whose dependencies are all ready.
+    fn start_unblocked(
+        &mut self,
+        plan: &LaunchPlan,
+        event_tx: &mpsc::UnboundedSender<ProcessEvent>,
+    ) -> Result<()> {
+        let mut i = 0;
+        while i < self.pending.len() {
+            let Some(node) = plan_node(plan, &self.pending[i]) else {
+                self.pending.remove(i);
+                continue;
+            };
+            if !node.depends_on.iter().all(|d| self.ready.contains(d)) {
+                i += 1;
+                continue;
+            }
+            self.pending.remove(i);
+            self.start(node, event_tx).inspect_err(|e| {
+                log::error!("[launch] failed to start {}: {}", node.name, e);
+            })?;
+        }
+        Ok(())
+    }
+
+    fn run_actions(
+        &mut self,
+        plan: &LaunchPlan,
+        source: &str,
+        actions: &[LaunchAction],
+        code: Option<i32>,
+        event_tx: &mpsc::UnboundedSender<ProcessEvent>,
+    ) -> bool {
+        for action in actions {
+            match action {
+                LaunchAction::Exec(cmd) => spawn_hook(source, cmd, code),
+                LaunchAction::Start(target) => {
+                    for node in plan.targets(target) {
+                        if self.running.iter().any(|r| r.name == node.name)
+                            || self.delayed.contains(&node.name)
+                        {
+                            log::info!("[launch] {} is already running", node.name);
+                            continue;
+                        }
+                        if let Err(e) = self.start(node, event_tx) {
+                            log::error!("[launch] failed to start {}: {}", node.name, e);
+                        }
+                    }
+                }
+                LaunchAction::Stop(target) => {
+                    let names: Vec<String> = plan.targets(target).map(|n| n.name.clone()).collect();
+                    for name in names {
+                        self.stop_node(&name);
+                    }
+                }
+                LaunchAction::Shutdown => return false,
+            }
+        }
+        true
+    }
+
+    /// Stop a running node for a `stop:` action. Local nodes stay in
+    /// `running` until their exit is reported; remote ones are dropped
+    /// right away since their exit is only noticed once heartbeats stop.
+    /// Returns the task stopping a remote node.
+    fn stop_node(&mut self, name: &str) -> Option<tokio::task::JoinHandle<()>> {
+        let i = self.running.iter().position(|r| r.name == name)?;
+        self.stopping.insert(name.to_string());
+        self.ready.remove(name);
+        match self.running[i].remote.clone() {
+            Some((machine, instance)) => {
+                self.running.remove(i);
+                self.update_status(|b| b.exited(name, None));
+                let session = self.session.clone()?;
+                Some(tokio::spawn(async move {
+                    remote::stop(session, &machine, &instance).await
+                }))
+            }
+            None => {
+                let node = &self.running[i];
+                let (name, pid, grace) = (node.name.clone(), node.pid, node.stop_timeout);
+                tokio::spawn(async move { stop_process(&name, pid, grace).await });
+                None
+            }
+        }
+    }
+
+    /// Re-resolve the launch file and apply the difference to `plan`.
+    ///
+    /// Local nodes being restarted are queued again once their exit is
+    /// reported; remote ones once their daemon has stopped them.
+    async fn reload(
+        &mut self,
+        plan: &mut LaunchPlan,
+        event_tx: &mpsc::UnboundedSender<ProcessEvent>,
+    ) -> Result<PlanDiff> {
+        let reloader = self
+            .reloader
+            .as_ref()
+            .ok_or_else(|| LaunchError::Instance("this launch cannot be reloaded".into()))?;
+        let new = reloader()?;
+        if new.name != plan.name {
+            return Err(LaunchError::Instance(format!(
+                "launch name changed from '{}' to '{}'; restart the launch instead",
+                plan.name, new.name
+            )));
+        }
+        let diff = plan.diff(&new);
+
+        let mut remote_stops = Vec::new();
+        for name in &diff.removed {
+            self.pending.retain(|p| p != name);
+            self.delayed.remove(name);
+            self.restarting.remove(name);
+            remote_stops.extend(self.stop_node(name));
+        }
+        for name in &diff.changed {
+            if !self.running.iter().any(|r| &r.name == name) {
+                continue;
+            }
+            println!("[{}] restarting with new settings", name);
+            match self.stop_node(name) {
+                Some(stop) => {
+                    remote_stops.push(stop);
+                    self.pending.push(name.clone());
+                }
+                None => {
+                    self.restarting.insert(name.clone());
+                }
+            }
+        }
+        for stop in remote_stops {
+            let _ = stop.await;
+        }
+
+        *plan = new;
+        self.update_status(|b| b.replan(plan));
+        self.prefix_width = plan.nodes.iter().map(|n| n.name.len()).max().unwrap_or(0);
+        self.pending.extend(
+            plan.nodes
+                .iter()
+                .filter(|n| n.autostart && diff.added.contains(&n.name))
+                .map(|n| n.name.clone()),
+        );
+        self.start_unblocked(plan, event_tx)?;
+        Ok(diff)
+    }
+
+    /// Spawn `node` now, or after its `start_delay`.
+    fn start(
+        &mut self,
+        node: &PlannedNode,
+        event_tx: &mpsc::UnboundedSender<ProcessEvent>,
+    ) -> Result<()> {
+        if node.start_delay.is_zero() {
+            return self.spawn(node, event_tx);
+        }
+        if self.delayed.insert(node.name.clone()) {
+            println!("[{}] starting in {:?}", node.name, node.start_delay);
+            let (name, delay, tx) = (node.name.clone(), node.start_delay, event_tx.clone());
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                let _ = tx.send(ProcessEvent::Due { name });
+            });
+        }
+        Ok(())
+    }
+
+    /// Number a new start of `node` and arm its `start_timeout`.
+    fn next_attempt(
+        &mut self,
+        node: &PlannedNode,
+        event_tx: &mpsc::UnboundedSender<ProcessEvent>,
+    ) -> u64 {
+        self.attempts += 1;
+        let attempt = self.attempts;
+        if let Some(timeout) = node.start_timeout {
+            let (name, tx) = (node.name.clone(), event_tx.clone());
+            tokio::spawn(async move {
+                tokio::time::sleep(timeout).await;
+                let _ = tx.send(ProcessEvent::StartTimedOut { name, attempt });
+            });
+        }
+        attempt
+    }
+
+    /// Machine whose daemon runs `node`, or `None` to spawn it as a child.
+    fn daemon_machine(&self, node: &PlannedNode) -> Option<String> {
+        match node.machine.as_deref() {
+            Some(machine) if machine != self.machine_id => Some(machine.to_string()),
+            _ if self.daemon_managed => Some(self.machine_id.clone()),
+            _ => None,
+        }
+    }
+
+    fn spawn(
+        &mut self,
+        node: &PlannedNode,
+        event_tx: &mpsc::UnboundedSender<ProcessEvent>,
+    ) -> Result<()> {
+        if let Some(machine) = self.daemon_machine(node) {
+            return self.spawn_remote(node, &machine, event_tx);
+        }
+        let node_dir = find_installed_node(&self.nodes_dir, &node.node).ok_or_else(|| {
+            LaunchError::Instance(format!(
+                "base node '{}' for '{}' not found under {}",
+                node.node,
+                node.name,
+                self.nodes_dir.display()
+            ))
+        })?;
+        let manifest = read_manifest(&node_dir)?;
+        let (program, args) = node_command(&node_dir, &manifest)?;
+        let (program, mut args, python_env) =
+            python::command(&node_dir, &manifest, node, program, args);
+
+        let instance = node.instance_name();
+        let mut topic_name = instance.clone();
+        if let Some(config) = &node.config {
+            let config = with_instance_name(config, &node.name, node.namespace.as_deref());
+            if let Some(name) = config.get("name").and_then(|v| v.as_str()) {
+                topic_name = name.to_string();
+            }
+            let path = write_config(&self.configs_dir, &instance, &config)?;
+            args.push("-c".to_string());
+            args.push(path.to_string_lossy().to_string());
+        } else if node.namespace.is_some() {
+            log::warn!(
+                "[launch] {} has a namespace but no config; its topics are not namespaced",
+                node.name
+            );
+        }
+
+        let machine_id = self.machine_id.clone();
+        let stdout_pattern = node.ready.as_ref().and_then(|r| r.stdout.as_deref());
+        let mut command = Command::new(&program);
+        command
+            .args(&args)
+            .current_dir(&node_dir)
+            .env("BUBBALOOP_MACHINE_ID", &machine_id)
+            .envs(python_env)
+            .envs(&node.env);
+        command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        let mut child = command.kill_on_drop(true).spawn().map_err(|e| {
+            LaunchError::Instance(format!(
+                "failed to spawn '{}' ({}): {}",
+                node.name, program, e
+            ))
+        })?;
+        let pid = child
+            .id()
+            .ok_or_else(|| LaunchError::Instance(format!("no PID for '{}'", node.name)))?;
+
+        println!("[{}] started (pid {})", node.name, pid);
+        let attempt = self.next_attempt(node, event_tx);
+        self.running.push(RunningNode {
+            name: node.name.clone(),
+            pid,
+            remote: None,
+            attempt,
+            stop_timeout: node.stop_timeout.unwrap_or(STOP_GRACE),
+        });
+        // Queued before the watcher exists, so Started always precedes Exited.
+        let _ = event_tx.send(ProcessEvent::Started {
+            name: node.name.clone(),
+            pid,
+        });
+
+        let (matcher, stdout_match) = match stdout_pattern {
+            Some(pattern) => {
+                let regex = regex::Regex::new(pattern)
+                    .map_err(|e| LaunchError::Instance(format!("invalid stdout regex: {}", e)))?;
+                let (tx, rx) = oneshot::channel();
+                (Some((regex, tx)), Some(rx))
+            }
+            None => (None, None),
+        };
+        let prefix = output::prefix(&node.name, self.prefix_width, self.color);
+        let log_file = self
+            .log_dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.log", node.name)));
+        if let Some(stdout) = child.stdout.take() {
+            tokio::spawn(output::forward(
+                stdout,
+                output::Stream::Stdout,
+                prefix.clone(),
+                log_file.clone(),
+                matcher,
+            ));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            tokio::spawn(output::forward(
+                stderr,
+                output::Stream::Stderr,
+                prefix,
+                log_file,
+                None,
+            ));
+        }
+
+        if let Some(check) = node.ready.clone() {
+            let ctx = ProbeContext {
+                session: self.session.clone(),
+                health_key: readiness::health_topic(&machine_id, &topic_name),
+                stdout_match,
+            };
+            let name = node.name.clone();
+            let tx = event_tx.clone();
+            tokio::spawn(async move {
+                let event = match readiness::wait_ready(&check, ctx).await {
+                    Ok(()) => ProcessEvent::Ready { name },
+                    Err(reason) => ProcessEvent::NotReady { name, reason },
+                };
+                let _ = tx.send(event);
+            });
+        }
+
+        tokio::spawn(watch_child(node.name.clone(), child, event_tx.clone()));
+        Ok(())
+    }
+
+    /// Hand a node to the daemon on `machine`; events arrive from the
+    /// dispatch task once it is registered and started.
+    fn spawn_remote(
+        &mut self,
+        node: &PlannedNode,
+        machine: &str,
+        event_tx: &mpsc::UnboundedSender<ProcessEvent>,
+    ) -> Result<()> {
+        let session = self.session.clone().ok_or_else(|| {
+            LaunchError::Instance(format!(
+                "'{}' runs on {} but there is no Zenoh session",
+                node.name, machine
+            ))
+        })?;
+        let instance = node.instance_name();
+        let mut topic_name = instance.clone();
+        let config_yaml = match &node.config {
+            Some(config) => {
+                let config = with_instance_name(config, &node.name, node.namespace.as_deref());
+                if let Some(name) = config.get("name").and_then(|v| v.as_str()) {
+                    topic_name = name.to_string();
+                }
+                Some(serde_yaml::to_string(&config)?)
+            }
+            None => None,
+        };
+        if !node.env.is_empty() {
+            log::warn!(
+                "[launch] env: of {} is ignored, the daemon on {} sets the environment",
+                node.name,
+                machine
+            );
+        }
+        if node.interpreter.is_some() || node.venv.is_some() {
+            log::warn!(
+                "[launch] interpreter/venv of {} is ignored, the daemon on {} picks it",
+                node.name,
+                machine
+            );
+        }
+
+        println!("[{}] dispatching to {}", node.name, machine);
+        let attempt = self.next_attempt(node, event_tx);
+        self.running.push(RunningNode {
+            name: node.name.clone(),
+            pid: 0,
+            remote: Some((machine.to_string(), instance.clone())),
+            attempt,
+            stop_timeout: node.stop_timeout.unwrap_or(STOP_GRACE),
+        });
+        let remote = remote::RemoteNode {
+            name: node.name.clone(),
+            node: node.node.clone(),
+            instance,
+            machine: machine.to_string(),
+            config_yaml,
+            topic_name,
+            ready: node.ready.clone(),
+        };
+        tokio::spawn(remote::run(
+            session,
+            remote,
+            self.registered.clone(),
+            event_tx.clone(),
+        ));
+        Ok(())
+    }
+
+    /// Stop remaining nodes in reverse start order, and unregister the
+    /// daemon instances this launch registered.
+    async fn shutdown(&mut self) {
+        while let Some(node) = self.running.pop() {
+            match (&node.remote, &self.session) {
+                (Some((machine, instance)), Some(session)) => {
+                    let created = self
+                        .registered
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .remove(&(machine.clone(), instance.clone()));
+                    if created {
+                        remote::remove(session.clone(), machine, instance).await;
+                    } else {
+                        remote::stop(session.clone(), machine, instance).await;
+                    }
+                }
+                (Some((machine, _)), None) => {
+                    log::warn!("[launch] cannot stop {} on {}", node.name, machine);
+                }
+                (None, _) => stop_process(&node.name, node.pid, node.stop_timeout).await,
+            }
+        }
+        // Instances stopped earlier by a `stop:` action or a reload.
+        let leftover: Vec<_> = self
+            .registered
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .drain()
+            .collect();
+        if let Some(session) = &self.session {
+            for (machine, instance) in leftover {
+                remote::remove(session.clone(), &machine, &instance).await;
+            }
+        }
+    }
+}
+
+/// Turn every SIGHUP into a reload request.
+async fn forward_sighup(requests: mpsc::UnboundedSender<ControlReply>) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = match signal(SignalKind::hangup()) {
+        Ok(s) => s,
+        Err(e) => {
+            log::warn!(
+                "[launch] cannot handle SIGHUP, reload only via Zenoh: {}",
+                e
+            );
+            return;
+        }
+    };
+    while hangup.recv().await.is_some() {
+        // The outcome is printed by the executor; nobody waits for it here.
+        let (tx, _) = oneshot::channel();
+        if requests.send(tx).is_err() {
+            return;
+        }
+    }
+}
+
+/// Reap a child and report its exit to the executor.
+async fn watch_child(
+    name: String,
+    mut child: Child,
+    event_tx: mpsc::UnboundedSender<ProcessEvent>,
+) {
+    let code = match child.wait().await {
+        Ok(status) => status.code(),
+        Err(e) => {
+            log::warn!("[launch] wait failed for {}: {}", name, e);
+            None
+        }
+    };
+    let _ = event_tx.send(ProcessEvent::Exited { name, code });
+}
+
+fn plan_node<'a>(plan: &'a LaunchPlan, name: &str) -> Option<&'a PlannedNode> {
+    plan.nodes.iter().find(|n| n.name == name)
+}
+
+/// Run an `exec:` hook in the background.
+///
+/// The hook sees the node that fired it in `BUBBALOOP_LAUNCH_NODE` and, for
+/// exit handlers, its exit code in `BUBBALOOP_EXIT_CODE` (empty when the
+/// node was killed by a signal).
+fn spawn_hook(source: &str, cmd: &str, code: Option<i32>) {
+    let mut command = Command::new("sh");
+    command
+        .args(["-c", cmd])
+        .env("BUBBALOOP_LAUNCH_NODE", source)
+        .env(
+            "BUBBALOOP_EXIT_CODE",
+            code.map(|c| c.to_string()).unwrap_or_default(),
+        );
+    let source = source.to_string();
+    let cmd = cmd.to_string();
+    tokio::spawn(async move {
+        match command.status().await {
+            Ok(status) if status.success() => {}
+            Ok(status) => log::warn!("[launch] {} hook '{}' failed: {}", source, cmd, status),
+            Err(e) => log::warn!("[launch] {} hook '{}' not run: {}", source, cmd, e),
+        }
+    });
+}
+
+/// Send SIGTERM, wait up to `grace`, then SIGKILL.
+async fn stop_process(name: &str, pid: u32, grace: Duration) {
+    if pid <= 1 {
+        return;
+    }
+    if !send_signal(pid, "-TERM").await {
+        return; // already gone
+    }
+    let deadline = tokio::time::Instant::now() + grace;
+    while tokio::time::Instant::now() < deadline {
+        if !is_pid_alive(pid) {
+            println!("[{}] stopped", name);
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    log::warn!("[launch] {} did not exit after SIGTERM, killing", name);
+    send_signal(pid, "-KILL").await;
+}
+
+async fn send_signal(pid: u32, signal: &str) -> bool {
+    let kill_bin = if Path::new("/bin/kill").exists() {
+        "/bin/kill"
+    } else {
+        "/usr/bin/kill"
+    };
+    Command::new(kill_bin)
+        .args([signal, &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .await
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+fn is_pid_alive(pid: u32) -> bool {
+    // A reaped child disappears from /proc; zombies are reaped by watch_child.
+    Path::new(&format!("/proc/{pid}")).exists()
+}
+
+pub(super) fn read_manifest(node_dir: &Path) -> Result<NodeManifest> {
+    let content = std::fs::read_to_string(node_dir.join("node.yaml"))?;
+    Ok(serde_yaml::from_str(&content)?)
+}
+
+/// Resolve the program and arguments used to run a base node.
+///
+/// Mirrors the native supervisor: an explicit `command:` in node.yaml wins,
+/// otherwise Rust nodes run their release (or debug) binary and Python
+/// nodes run `main.py` from their venv.
+pub(super) fn node_command(
+    node_dir: &Path,
+    manifest: &NodeManifest,
+) -> Result<(String, Vec<String>)> {
+    let command = match &manifest.command {
+        Some(cmd) => cmd.clone(),
+        None => crate::daemon::native_supervisor::default_command(
+            &node_dir.to_string_lossy(),
+            &manifest.name,
+            &manifest.node_type,
+        ),
+    };
+    let mut parts = command.split_whitespace().map(str::to_string);
+    let program = parts.next().ok_or_else(|| {
+        LaunchError::Instance(format!("node '{}' has an empty command", manifest.name))
+    })?;
+    Ok((program, parts.collect()))
+}
+
+/// Default the config's `name` to the launch instance name, prefixed with
+/// `namespace` when one is set.
+///
+/// The node SDK derives its topic prefix from `name`, so without this two
+/// instances of the same base node would publish on the same topics. An
+/// explicit `name` is kept but still namespaced.
+fn with_instance_name(
+    config: &serde_yaml::Value,
+    instance: &str,
+    namespace: Option<&str>,
+) -> serde_yaml::Value {
+    let mut config = config.clone();
+    if let serde_yaml::Value::Mapping(map) = &mut config {
+        let key = serde_yaml::Value::String("name".to_string());
+        let name = map
+            .get(&key)
+            .and_then(|v| v.as_str())
+            .unwrap_or(instance)
+            .to_string();
+        let name = match namespace {
+            Some(ns) => format!("{}_{}", ns, name),
+            None => name,
+        };
+        map.insert(key, serde_yaml::Value::String(name));
+    }
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(yaml: &str) -> NodeManifest {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn explicit_command_is_split() {
+        let m = manifest("name: cam\nversion: 0.1.0\ntype: python\ncommand: pixi run main\n");
+        let (program, args) = node_command(Path::new("/nodes/cam"), &m).unwrap();
+        assert_eq!(program, "pixi");
+        assert_eq!(args, vec!["run", "main"]);
+    }
+
+    #[test]
+    fn rust_node_defaults_to_target_binary() {
+        let m = manifest("name: cam\nversion: 0.1.0\ntype: rust\n");
+        let (program, args) = node_command(Path::new("/nodes/cam"), &m).unwrap();
+        assert!(program.ends_with("target/debug/cam") || program.ends_with("target/release/cam"));
+        assert!(args.is_empty());
+    }
+
+    #[test]
+    fn instance_name_injected_only_when_missing() {
+        let config: serde_yaml::Value = serde_yaml::from_str("url: x\n").unwrap();
+        let out = with_instance_name(&config, "entrance", None);
+        assert_eq!(out["name"].as_str(), Some("entrance"));
+
+        let config: serde_yaml::Value = serde_yaml::from_str("name: custom\n").unwrap();
+        let out = with_instance_name(&config, "entrance", None);
+        assert_eq!(out["name"].as_str(), Some("custom"));
+    }
+
+    #[test]
+    fn namespace_prefixes_instance_name() {
+        let config: serde_yaml::Value = serde_yaml::from_str("url: x\n").unwrap();
+        let out = with_instance_name(&config, "entrance", Some("garage"));
+        assert_eq!(out["name"].as_str(), Some("garage_entrance"));
+
+        let config: serde_yaml::Value = serde_yaml::from_str("name: custom\n").unwrap();
+        let out = with_instance_name(&config, "entrance", Some("garage"));
+        assert_eq!(out["name"].as_str(), Some("garage_custom"));
+    }
+
+    #[tokio::test]
+    async fn spawn_reports_exit_event() {
+        let nodes = tempfile::tempdir().unwrap();
+        let configs = tempfile::tempdir().unwrap();
+        let node_dir = nodes.path().join("echo-node");
+        std::fs::create_dir_all(&node_dir).unwrap();
+        std::fs::write(
+            node_dir.join("node.yaml"),
+            "name: echo-node\nversion: 0.1.0\ntype: rust\ncommand: /bin/true\n",
+        )
+        .unwrap();
+
+        let mut executor =
+            LaunchExecutor::new(nodes.path().to_path_buf(), configs.path().to_path_buf());
+        let planned = PlannedNode {
+            name: "echo".to_string(),
+            node: "echo-node".to_string(),
+            config: Some(serde_yaml::from_str("k: v\n").unwrap()),
+            ..Default::default()
+        };
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        executor.spawn(&planned, &tx).unwrap();
+        assert!(configs.path().join("echo.yaml").exists());
+
+        let started = rx.recv().await.unwrap();
+        assert!(matches!(started, ProcessEvent::Started { ref name, .. } if name == "echo"));
+        let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            event,
+            ProcessEvent::Exited {
+                name: "echo".to_string(),
+                code: Some(0)
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn namespaced_config_written_under_instance_name() {
+        let nodes = tempfile::tempdir().unwrap();
+        let configs = tempfile::tempdir().unwrap();
+        let node_dir = nodes.path().join("echo-node");
+        std::fs::create_dir_all(&node_dir).unwrap();
+        std::fs::write(
+            node_dir.join("node.yaml"),
+            "name: echo-node\nversion: 0.1.0\ntype: rust\ncommand: /bin/true\n",
+        )
+        .unwrap();
+
+        let mut executor =
+            LaunchExecutor::new(nodes.path().to_path_buf(), configs.path().to_path_buf());
+        let planned = PlannedNode {
+            name: "echo".to_string(),
+            node: "echo-node".to_string(),
+            config: Some(serde_yaml::from_str("k: v\n").unwrap()),
+            namespace: Some("garage".to_string()),
+            env: [("RUST_LOG".to_string(), "debug".to_string())].into(),
+            ..Default::default()
+        };
+        let (tx, _rx) = mpsc::unbounded_channel();
+        executor.spawn(&planned, &tx).unwrap();
+
+        let written = std::fs::read_to_string(configs.path().join("garage_echo.yaml")).unwrap();
+        assert!(written.contains("name: garage_echo"), "{written}");
+    }
+
+    #[tokio::test]
+    async fn crash_handler_starts_fallback_and_shutdown_stops() {
+        let nodes = tempfile::tempdir().unwrap();
+        let node_dir = nodes.path().join("echo-node");
+        std::fs::create_dir_all(&node_dir).unwrap();
+        std::fs::write(
+            node_dir.join("node.yaml"),
+            "name: echo-node\nversion: 0.1.0\ntype: rust\ncommand: /bin/true\n",
+        )
+        .unwrap();
+        let plan = LaunchPlan {
+            name: "l".to_string(),
+            nodes: vec![
+                PlannedNode {
+                    name: "main".to_string(),
+                    node: "echo-node".to_string(),
+                    autostart: true,
+                    on_crash: vec![LaunchAction::Start("backup".to_string())],
+                    on_exit: vec![LaunchAction::Shutdown],
+                    ..Default::default()
+                },
+                PlannedNode {
+                    name: "backup".to_string(),
+                    node: "echo-node".to_string(),
+                    ..Default::default()
+                },
+            ],
+            skipped: vec![],
+        };
+        let mut executor =
+            LaunchExecutor::new(nodes.path().to_path_buf(), nodes.path().to_path_buf());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let crashed = ProcessEvent::Exited {
+            name: "main".to_string(),
+            code: Some(1),
+        };
+        assert!(executor.handle_event(&plan, crashed, &tx).unwrap());
+        assert!(matches!(
+            rx.recv().await.unwrap(),
+            ProcessEvent::Started { ref name, .. } if name == "backup"
+        ));
+
+        let clean = ProcessEvent::Exited {
+            name: "main".to_string(),
+            code: Some(0),
+        };
+        assert!(!executor.handle_event(&plan, clean, &tx).unwrap());
+    }
+
+    fn install_node(nodes: &Path, name: &str, command: &str) {
+        let node_dir = nodes.join(name);
+        std::fs::create_dir_all(&node_dir).unwrap();
+        std::fs::write(
+            node_dir.join("node.yaml"),
+            format!("name: {name}\nversion: 0.1.0\ntype: rust\ncommand: {command}\n"),
+        )
+        .unwrap();
+    }
+
+    fn ready_check(yaml: &str) -> Option<super::super::ReadyCheck> {
+        Some(serde_yaml::from_str(yaml).unwrap())
+    }
+
+    #[tokio::test]
+    async fn dependents_wait_for_readiness() {
+        let nodes = tempfile::tempdir().unwrap();
+        install_node(nodes.path(), "sleeper", "/bin/sleep 5");
+        let plan = LaunchPlan {
+            name: "l".to_string(),
+            nodes: vec![
+                PlannedNode {
+                    name: "server".to_string(),
+                    node: "sleeper".to_string(),
+                    autostart: true,
+                    ready: ready_check("tcp: \"127.0.0.1:1\"\ntimeout: 60\n"),
+                    ..Default::default()
+                },
+                PlannedNode {
+                    name: "client".to_string(),
+                    node: "sleeper".to_string(),
+                    autostart: true,
+                    depends_on: vec!["server".to_string()],
+                    ..Default::default()
+                },
+            ],
+            skipped: vec![],
+        };
+        let mut executor =
+            LaunchExecutor::new(nodes.path().to_path_buf(), nodes.path().to_path_buf());
+        let (tx, _rx) = mpsc::unbounded_channel();
+        executor.pending = vec!["server".to_string(), "client".to_string()];
+
+        executor.start_unblocked(&plan, &tx).unwrap();
+        let started: Vec<&str> = executor.running.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(started, vec!["server"]);
+
+        // Spawned but not yet ready: the dependent stays pending.
+        let spawned = ProcessEvent::Started {
+            name: "server".to_string(),
+            pid: executor.running[0].pid,
+        };
+        assert!(executor.handle_event(&plan, spawned, &tx).unwrap());
+        assert_eq!(executor.running.len(), 1);
+
+        let ready = ProcessEvent::Ready {
+            name: "server".to_string(),
+        };
+        assert!(executor.handle_event(&plan, ready, &tx).unwrap());
+        assert_eq!(executor.running.len(), 2);
+        assert!(executor.pending.is_empty());
+
+        let failed = ProcessEvent::NotReady {
+            name: "server".to_string(),
+            reason: "timeout".to_string(),
+        };
+        assert!(executor.handle_event(&plan, failed, &tx).is_err());
+        executor.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn group_actions_start_and_stop_members() {
+        let nodes = tempfile::tempdir().unwrap();
+        install_node(nodes.path(), "sleeper", "/bin/sleep 30");
+        let member = |name: &str| PlannedNode {
+            name: name.to_string(),
+            node: "sleeper".to_string(),
+            group: Some("cams".to_string()),
+            ..Default::default()
+        };
+        let plan = LaunchPlan {
+            name: "l".to_string(),
+            nodes: vec![
+                PlannedNode {
+                    name: "main".to_string(),
+                    node: "sleeper".to_string(),
+                    on_start: vec![LaunchAction::Start("cams".to_string())],
+                    on_exit: vec![LaunchAction::Stop("cams".to_string())],
+                    ..Default::default()
+                },
+                member("front"),
+                member("back"),
+            ],
+            skipped: vec![],
+        };
+        let mut executor =
+            LaunchExecutor::new(nodes.path().to_path_buf(), nodes.path().to_path_buf());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        let started = ProcessEvent::Started {
+            name: "main".to_string(),
+            pid: 0,
+        };
+        assert!(executor.handle_event(&plan, started, &tx).unwrap());
+        let names: Vec<&str> = executor.running.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["front", "back"]);
+
+        let exited = ProcessEvent::Exited {
+            name: "main".to_string(),
+            code: Some(0),
+        };
+        assert!(executor.handle_event(&plan, exited, &tx).unwrap());
+        let mut stopped = 0;
+        while stopped < 2 {
+            let event = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            if let ProcessEvent::Exited { .. } = event {
+                assert!(executor.handle_event(&plan, event, &tx).unwrap());
+                stopped += 1;
+            }
+        }
+        assert!(executor.running.is_empty());
+        assert!(executor.stopping.is_empty());
+    }
+
+    #[tokio::test]
+    async fn start_delay_and_start_timeout() {
+        let nodes = tempfile::tempdir().unwrap();
+        install_node(nodes.path(), "sleeper", "/bin/sleep 30");
+        let plan = LaunchPlan {
+            name: "l".to_string(),
+            nodes: vec![
+                PlannedNode {
+                    name: "slow".to_string(),
+                    node: "sleeper".to_string(),
+                    start_delay: Duration::from_millis(50),
+                    start_timeout: Some(Duration::from_millis(100)),
+                    stop_timeout: Some(Duration::from_millis(200)),
+                    ready: ready_check("tcp: \"127.0.0.1:1\"\ntimeout: 60\n"),
+                    on_crash: vec![LaunchAction::Start("fallback".to_string())],
+                    ..Default::default()
+                },
+                PlannedNode {
+                    name: "fallback".to_string(),
+                    node: "sleeper".to_string(),
+                    ..Default::default()
+                },
+            ],
+            skipped: vec![],
+        };
+        let mut executor =
+            LaunchExecutor::new(nodes.path().to_path_buf(), nodes.path().to_path_buf());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        executor.start(&plan.nodes[0], &tx).unwrap();
+        assert!(executor.running.is_empty());
+        assert!(executor.delayed.contains("slow"));
+
+        let mut timed_out = false;
+        while !timed_out {
+            let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            timed_out = matches!(event, ProcessEvent::StartTimedOut { .. });
+            assert!(executor.handle_event(&plan, event, &tx).unwrap());
+        }
+        assert!(executor.delayed.is_empty());
+        assert!(executor.stopping.contains("slow"));
+        assert!(!executor.ready.contains("slow"));
+        assert!(executor.running.iter().any(|r| r.name == "fallback"));
+        executor.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn reload_applies_only_the_difference() {
+        let nodes = tempfile::tempdir().unwrap();
+        install_node(nodes.path(), "sleeper", "/bin/sleep 30");
+        let node = |name: &str, fps: u32| PlannedNode {
+            name: name.to_string(),
+            node: "sleeper".to_string(),
+            env: [("FPS".to_string(), fps.to_string())].into(),
+            autostart: true,
+            stop_timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+        let mut plan = LaunchPlan {
+            name: "l".to_string(),
+            nodes: vec![node("kept", 15), node("changed", 15), node("removed", 15)],
+            skipped: vec![],
+        };
+        let reloaded = LaunchPlan {
+            name: "l".to_string(),
+            nodes: vec![node("kept", 15), node("changed", 30), node("added", 15)],
+            skipped: vec![],
+        };
+        let mut executor =
+            LaunchExecutor::new(nodes.path().to_path_buf(), nodes.path().to_path_buf())
+                .reloader(move || Ok(reloaded.clone()));
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        executor.pending = plan.nodes.iter().map(|n| n.name.clone()).collect();
+        executor.start_unblocked(&plan, &tx).unwrap();
+        let kept_pid = executor.running[0].pid;
+
+        let diff = executor.reload(&mut plan, &tx).await.unwrap();
+        assert_eq!(
+            diff.to_string(),
+            "added added; removed removed; changed changed"
+        );
+        assert!(executor.running.iter().any(|r| r.name == "added"));
+        assert!(executor.restarting.contains("changed"));
+
+        let mut exited = 0;
+        while exited < 2 {
+            let event = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            if let ProcessEvent::Exited { .. } = event {
+                assert!(executor.handle_event(&plan, event, &tx).unwrap());
+                exited += 1;
+            }
+        }
+        let names: Vec<&str> = executor.running.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["kept", "added", "changed"]);
+        assert_eq!(executor.running[0].pid, kept_pid);
+        assert!(executor.restarting.is_empty());
+        executor.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn stdout_probe_reports_ready() {
+        let nodes = tempfile::tempdir().unwrap();
+        install_node(nodes.path(), "talker", "/bin/echo listening on 1234");
+        let planned = PlannedNode {
+            name: "talker".to_string(),
+            node: "talker".to_string(),
+            ready: ready_check("stdout: \"listening on \\\\d+\"\ntimeout: 5\n"),
+            ..Default::default()
+        };
+        let mut executor =
+            LaunchExecutor::new(nodes.path().to_path_buf(), nodes.path().to_path_buf());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        executor.spawn(&planned, &tx).unwrap();
+
+        let mut saw_ready = false;
+        while let Ok(Some(event)) = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await {
+            match event {
+                ProcessEvent::Ready { .. } => saw_ready = true,
+                ProcessEvent::NotReady { reason, .. } => panic!("not ready: {reason}"),
+                ProcessEvent::Exited { .. } if saw_ready => break,
+                _ => {}
+            }
+        }
+        assert!(saw_ready);
+    }
+
+    #[test]
+    fn spawn_fails_for_unknown_base_node() {
+        let nodes = tempfile::tempdir().unwrap();
+        let mut executor =
+            LaunchExecutor::new(nodes.path().to_path_buf(), nodes.path().to_path_buf());
+        let planned = PlannedNode {
+            name: "x".to_string(),
+            node: "missing".to_string(),
+            config: None,
+            ..Default::default()
+        };
+        let (tx, _rx) = mpsc::unbounded_channel();
+        assert!(executor.spawn(&planned, &tx).is_err());
+    }
+
+    #[test]
+    fn remote_node_needs_session() {
+        let nodes = tempfile::tempdir().unwrap();
+        let mut executor =
+            LaunchExecutor::new(nodes.path().to_path_buf(), nodes.path().to_path_buf());
+        let mut planned = PlannedNode {
+            name: "det".to_string(),
+            node: "detector".to_string(),
+            machine: Some(executor.machine_id.clone()),
+            ..Default::default()
+        };
+        assert_eq!(executor.daemon_machine(&planned), None);
+
+        planned.machine = Some("jetson_orin".to_string());
+        assert_eq!(
+            executor.daemon_machine(&planned).as_deref(),
+            Some("jetson_orin")
+        );
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let err = executor.spawn(&planned, &tx).unwrap_err();
+        assert!(err.to_string().contains("no Zenoh session"), "{err}");
+        assert!(executor.running.is_empty());
+
+        let local = executor.machine_id.clone();
+        let executor = executor.daemon_managed(true);
+        planned.machine = None;
+        assert_eq!(executor.daemon_machine(&planned), Some(local));
+    }
+}
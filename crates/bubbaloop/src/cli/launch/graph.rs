@@ -0,0 +1,224 @@
+//! Graph renderings of a launch plan for `bubbaloop launch plan`.
+//!
+//! Solid edges point from a dependency to its dependent, so the graph reads
+//! in start order. Dashed edges are lifecycle `start:` / `stop:` actions,
+//! labelled with the handler that fires them. Groups become clusters
+//! (Graphviz) or subgraphs (Mermaid), and nodes skipped by their condition
+//! are drawn dotted.
+
+use std::fmt::Write;
+
+use super::plan::{LaunchPlan, PlannedNode};
+use super::LaunchAction;
+
+/// Graphviz DOT source for `plan`.
+pub(super) fn dot(plan: &LaunchPlan) -> String {
+    let mut out = format!("digraph {:?} {{\n", plan.name);
+    out.push_str("  rankdir=LR;\n  node [shape=box];\n");
+    for (group, members) in groups(plan) {
+        let _ = writeln!(out, "  subgraph {:?} {{", format!("cluster_{}", group));
+        let _ = writeln!(out, "    label={:?};", group);
+        for i in members {
+            let _ = writeln!(out, "    {}", dot_node(plan, i));
+        }
+        out.push_str("  }\n");
+    }
+    for (i, node) in plan.nodes.iter().enumerate() {
+        if node.group.is_none() {
+            let _ = writeln!(out, "  {}", dot_node(plan, i));
+        }
+    }
+    for name in &plan.skipped {
+        let _ = writeln!(
+            out,
+            "  {:?} [label={:?}, style=dotted];",
+            name,
+            format!("{}\n(skipped)", name)
+        );
+    }
+    for node in &plan.nodes {
+        for dep in &node.depends_on {
+            let _ = writeln!(out, "  {:?} -> {:?};", dep, node.name);
+        }
+        for (hook, target) in action_edges(plan, node) {
+            let _ = writeln!(
+                out,
+                "  {:?} -> {:?} [style=dashed, label={:?}];",
+                node.name, target, hook
+            );
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Mermaid flowchart source for `plan`.
+pub(super) fn mermaid(plan: &LaunchPlan) -> String {
+    // Mermaid ids are positional: node names may contain `-`, which the
+    // edge syntax does not tolerate.
+    let id = |name: &str| match plan.nodes.iter().position(|n| n.name == name) {
+        Some(i) => format!("n{}", i),
+        None => format!(
+            "s{}",
+            plan.skipped.iter().position(|s| s == name).unwrap_or(0)
+        ),
+    };
+
+    let mut out = String::from("flowchart LR\n");
+    for (g, (group, members)) in groups(plan).into_iter().enumerate() {
+        let _ = writeln!(out, "  subgraph g{}[\"{}\"]", g, group);
+        for i in members {
+            let _ = writeln!(out, "    n{}[\"{}\"]", i, label(plan, i, "<br/>"));
+        }
+        out.push_str("  end\n");
+    }
+    for (i, node) in plan.nodes.iter().enumerate() {
+        if node.group.is_none() {
+            let _ = writeln!(out, "  n{}[\"{}\"]", i, label(plan, i, "<br/>"));
+        }
+    }
+    for (i, name) in plan.skipped.iter().enumerate() {
+        let _ = writeln!(out, "  s{}[\"{}<br/>(skipped)\"]", i, name);
+        let _ = writeln!(out, "  style s{} stroke-dasharray: 2 2", i);
+    }
+    for node in &plan.nodes {
+        for dep in &node.depends_on {
+            let _ = writeln!(out, "  {} --> {}", id(dep), id(&node.name));
+        }
+        for (hook, target) in action_edges(plan, node) {
+            let _ = writeln!(out, "  {} -. {} .-> {}", id(&node.name), hook, id(&target));
+        }
+    }
+    out
+}
+
+fn dot_node(plan: &LaunchPlan, i: usize) -> String {
+    let node = &plan.nodes[i];
+    let style = if node.autostart { "" } else { ", style=dashed" };
+    format!(
+        "{:?} [label={:?}{}];",
+        node.name,
+        label(plan, i, "\n"),
+        style
+    )
+}
+
+/// `{order}. {instance}` over the base node (and remote machine).
+fn label(plan: &LaunchPlan, i: usize, newline: &str) -> String {
+    let node = &plan.nodes[i];
+    let mut label = format!(
+        "{}. {}{}{}",
+        i + 1,
+        node.instance_name(),
+        newline,
+        node.node
+    );
+    if let Some(machine) = &node.machine {
+        let _ = write!(label, "@{}", machine);
+    }
+    label
+}
+
+/// Groups in first-appearance order with the indices of their members.
+fn groups(plan: &LaunchPlan) -> Vec<(&str, Vec<usize>)> {
+    let mut groups: Vec<(&str, Vec<usize>)> = Vec::new();
+    for (i, node) in plan.nodes.iter().enumerate() {
+        let Some(group) = node.group.as_deref() else {
+            continue;
+        };
+        match groups.iter_mut().find(|(g, _)| *g == group) {
+            Some((_, members)) => members.push(i),
+            None => groups.push((group, vec![i])),
+        }
+    }
+    groups
+}
+
+/// `(handler, target node)` for every `start:` / `stop:` action of `node`,
+/// with group targets expanded to their members.
+fn action_edges(plan: &LaunchPlan, node: &PlannedNode) -> Vec<(String, String)> {
+    let hooks = [
+        ("on_start", &node.on_start),
+        ("on_exit", &node.on_exit),
+        ("on_crash", &node.on_crash),
+    ];
+    let mut edges = Vec::new();
+    for (hook, actions) in hooks {
+        for action in actions {
+            let (verb, target) = match action {
+                LaunchAction::Start(target) => ("start", target),
+                LaunchAction::Stop(target) => ("stop", target),
+                _ => continue,
+            };
+            for member in plan.targets(target) {
+                edges.push((format!("{} {}", hook, verb), member.name.clone()));
+            }
+        }
+    }
+    edges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> LaunchPlan {
+        LaunchPlan {
+            name: "garage".to_string(),
+            nodes: vec![
+                PlannedNode {
+                    name: "rtsp-proxy".to_string(),
+                    node: "mediamtx".to_string(),
+                    autostart: true,
+                    ..Default::default()
+                },
+                PlannedNode {
+                    name: "entrance".to_string(),
+                    node: "rtsp-camera".to_string(),
+                    group: Some("cams".to_string()),
+                    depends_on: vec!["rtsp-proxy".to_string()],
+                    on_crash: vec![LaunchAction::Start("fallback".to_string())],
+                    autostart: true,
+                    ..Default::default()
+                },
+                PlannedNode {
+                    name: "fallback".to_string(),
+                    node: "rtsp-camera".to_string(),
+                    machine: Some("jetson".to_string()),
+                    ..Default::default()
+                },
+            ],
+            skipped: vec!["mic".to_string()],
+        }
+    }
+
+    #[test]
+    fn dot_has_clusters_dependencies_and_actions() {
+        let dot = dot(&sample());
+        assert!(dot.starts_with("digraph \"garage\" {"), "{dot}");
+        assert!(dot.contains("subgraph \"cluster_cams\""), "{dot}");
+        assert!(dot.contains("\"rtsp-proxy\" -> \"entrance\";"), "{dot}");
+        assert!(
+            dot.contains("\"entrance\" -> \"fallback\" [style=dashed, label=\"on_crash start\"]"),
+            "{dot}"
+        );
+        assert!(dot.contains("3. fallback\\nrtsp-camera@jetson"), "{dot}");
+        assert!(
+            dot.contains("\"mic\" [label=\"mic\\n(skipped)\", style=dotted]"),
+            "{dot}"
+        );
+    }
+
+    #[test]
+    fn mermaid_uses_positional_ids() {
+        let mermaid = mermaid(&sample());
+        assert!(mermaid.starts_with("flowchart LR\n"), "{mermaid}");
+        assert!(mermaid.contains("subgraph g0[\"cams\"]"), "{mermaid}");
+        assert!(mermaid.contains("  n0 --> n1\n"), "{mermaid}");
+        assert!(
+            mermaid.contains("  n1 -. on_crash start .-> n2\n"),
+            "{mermaid}"
+        );
+        assert!(mermaid.contains("s0[\"mic<br/>(skipped)\"]"), "{mermaid}");
+    }
+}
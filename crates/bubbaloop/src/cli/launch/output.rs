@@ -0,0 +1,131 @@
+//! Output of launched nodes.
+//!
+//! The executor pipes every child's stdout and stderr and forwards them
+//! line by line with a `[name]` prefix, so output of several nodes stays
+//! readable when interleaved. Prefixes are padded to the longest node name
+//! and colored per node when stdout is a terminal (and `NO_COLOR` is not
+//! set). With `launch start --log-files` each line is also appended,
+//! without prefix, to `~/.bubbaloop/logs/<launch>/<node>.log`.
+
+use std::io::IsTerminal;
+use std::path::PathBuf;
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWriteExt, BufReader};
+use tokio::sync::oneshot;
+
+/// ANSI foreground colors cycled through by node name.
+const COLORS: [&str; 6] = ["36", "32", "33", "35", "34", "31"];
+
+/// Which stream of the child a forwarder reads.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Whether prefixes should be colored.
+pub(super) fn use_color() -> bool {
+    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// Line prefix of `name`, padded to `width` characters inside the brackets.
+pub(super) fn prefix(name: &str, width: usize, color: bool) -> String {
+    let padded = format!("[{:<width$}]", name, width = width);
+    if !color {
+        return format!("{} ", padded);
+    }
+    let hash = name
+        .bytes()
+        .fold(0usize, |h, b| h.wrapping_mul(31).wrapping_add(b as usize));
+    format!("\x1b[{}m{}\x1b[0m ", COLORS[hash % COLORS.len()], padded)
+}
+
+/// Copy `reader` line by line to the terminal with `prefix`, and to
+/// `log_file` when set.
+///
+/// When `matcher` is set its sender fires on the first line matching the
+/// regex; this is how `stdout` readiness probes observe the node.
+pub(super) async fn forward<R: AsyncRead + Unpin>(
+    reader: R,
+    stream: Stream,
+    prefix: String,
+    log_file: Option<PathBuf>,
+    mut matcher: Option<(regex::Regex, oneshot::Sender<()>)>,
+) {
+    let mut file = match &log_file {
+        Some(path) => match tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+        {
+            Ok(file) => Some(file),
+            Err(e) => {
+                log::warn!("[launch] cannot write {}: {}", path.display(), e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        match stream {
+            Stream::Stdout => println!("{}{}", prefix, line),
+            Stream::Stderr => eprintln!("{}{}", prefix, line),
+        }
+        if let Some(f) = file.as_mut() {
+            if f.write_all(format!("{}\n", line).as_bytes()).await.is_err() {
+                file = None;
+            }
+        }
+        if matcher
+            .as_ref()
+            .is_some_and(|(regex, _)| regex.is_match(&line))
+        {
+            if let Some((_, tx)) = matcher.take() {
+                let _ = tx.send(());
+            }
+        }
+    }
+    // tokio writes files on a blocking thread; wait for the last line to land.
+    if let Some(f) = file.as_mut() {
+        let _ = f.flush().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefix_is_padded_and_optionally_colored() {
+        assert_eq!(prefix("cam", 6, false), "[cam   ] ");
+        let colored = prefix("cam", 3, true);
+        assert!(colored.starts_with("\x1b["), "{colored:?}");
+        assert!(colored.ends_with("[cam]\x1b[0m "), "{colored:?}");
+        assert_eq!(colored, prefix("cam", 3, true));
+    }
+
+    #[tokio::test]
+    async fn forward_tees_to_file_and_signals_match() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cam.log");
+        let input: &[u8] = b"booting\nlistening on 8554\nbye\n";
+        let (tx, rx) = oneshot::channel();
+        let regex = regex::Regex::new(r"listening on \d+").unwrap();
+
+        forward(
+            input,
+            Stream::Stdout,
+            "[cam] ".to_string(),
+            Some(path.clone()),
+            Some((regex, tx)),
+        )
+        .await;
+
+        assert!(rx.await.is_ok());
+        let logged = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(logged, "booting\nlistening on 8554\nbye\n");
+    }
+}
@@ -0,0 +1,1185 @@
+//! Launch plan: the resolved, ordered set of nodes a launch file will start.
+//!
+//! Building a plan evaluates `if:` / `unless:` conditions, expands `$(...)`
+//! substitutions in node configs, merges `include:`d launch files,
+//! applies `groups:` settings to their members, validates node names and
+//! dependencies, and orders nodes so that every node starts after its
+//! `depends_on`.
+//! Nothing is executed here — the plan is pure data consumed by the
+//! executor (and printed as-is by `--dry-run`).
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::substitution::{scalar_to_string, SubstitutionContext};
+use super::{
+    read_launch_file, LaunchAction, LaunchError, LaunchFile, LaunchNode, ReadyCheck, Result,
+};
+
+/// A node that survived condition evaluation, ready to be started.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlannedNode {
+    /// Instance name (used for the config file and the node's topics).
+    pub name: String,
+    /// Registered base node providing the binary (e.g. `rtsp-camera`).
+    pub node: String,
+    /// Inline config with substitutions already expanded.
+    pub config: Option<serde_yaml::Value>,
+    /// Instances that must be started before this one.
+    pub depends_on: Vec<String>,
+    /// Group the node belongs to (prefixed like node names in includes).
+    pub group: Option<String>,
+    /// Namespace prepended to the instance name on topics and config files.
+    pub namespace: Option<String>,
+    /// Extra environment variables for the child process.
+    pub env: BTreeMap<String, String>,
+    /// Started with the launch (otherwise only by a `start:` action).
+    pub autostart: bool,
+    /// Actions run after the process is spawned.
+    pub on_start: Vec<LaunchAction>,
+    /// Actions run after a clean (code 0) exit.
+    pub on_exit: Vec<LaunchAction>,
+    /// Actions run after a non-zero exit or a signal.
+    pub on_crash: Vec<LaunchAction>,
+    /// Readiness probe gating dependents (`None`: ready once spawned).
+    pub ready: Option<ReadyCheck>,
+    /// Remote machine whose daemon runs the node (`None`: run locally).
+    pub machine: Option<String>,
+    /// Wait between the node becoming due and spawning it.
+    pub start_delay: Duration,
+    /// Time from start to ready after which the node is marked failed.
+    pub start_timeout: Option<Duration>,
+    /// Time between SIGTERM and SIGKILL (`None`: the executor default).
+    pub stop_timeout: Option<Duration>,
+    /// Interpreter for a Python base node (`None`: auto-detected).
+    pub interpreter: Option<String>,
+    /// Absolute virtualenv path for a Python base node.
+    pub venv: Option<PathBuf>,
+}
+
+impl PlannedNode {
+    /// Name the running instance is known by: `{namespace}_{name}`, or just
+    /// `name` without a namespace.
+    pub fn instance_name(&self) -> String {
+        match &self.namespace {
+            Some(ns) => format!("{}_{}", ns, self.name),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Ordered result of resolving a multi-node launch file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchPlan {
+    /// Launch name (top-level `name:` of the file).
+    pub name: String,
+    /// Nodes in start order (dependencies first).
+    pub nodes: Vec<PlannedNode>,
+    /// Nodes excluded by their `if:` / `unless:` condition.
+    pub skipped: Vec<String>,
+}
+
+/// Node-level difference between two plans of the same launch, as applied
+/// by a reload.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlanDiff {
+    /// Nodes only in the old plan.
+    pub removed: Vec<String>,
+    /// Nodes only in the new plan.
+    pub added: Vec<String>,
+    /// Nodes in both plans whose resolved settings differ.
+    pub changed: Vec<String>,
+}
+
+impl PlanDiff {
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for PlanDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no changes");
+        }
+        let parts: Vec<String> = [
+            ("added", &self.added),
+            ("removed", &self.removed),
+            ("changed", &self.changed),
+        ]
+        .into_iter()
+        .filter(|(_, names)| !names.is_empty())
+        .map(|(what, names)| format!("{} {}", what, names.join(", ")))
+        .collect();
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
+/// Maximum nesting of `include:` directives.
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+impl LaunchPlan {
+    /// Resolve `launch` against `ctx` into a start-ordered plan.
+    ///
+    /// `base_dir` is the directory of the launch file; `include:` paths are
+    /// resolved relative to it. Included nodes are merged into this plan
+    /// (names prefixed with the include's `prefix`), so dependencies may
+    /// cross file boundaries.
+    pub fn build(launch: &LaunchFile, ctx: &SubstitutionContext, base_dir: &Path) -> Result<Self> {
+        let mut collected = Collected::default();
+        collect(launch, ctx, base_dir, "", &mut Vec::new(), &mut collected)?;
+        let nodes = order_by_dependencies(collected.nodes, &collected.skipped, &collected.groups)?;
+        Ok(Self {
+            name: launch.name.clone(),
+            nodes,
+            skipped: collected.skipped,
+        })
+    }
+
+    /// Compare this plan with `new`. A node counts as changed when any of
+    /// its resolved settings differ, which covers edited configs as well as
+    /// arg overrides that feed into them.
+    pub fn diff(&self, new: &LaunchPlan) -> PlanDiff {
+        let mut diff = PlanDiff::default();
+        for old in &self.nodes {
+            match new.nodes.iter().find(|n| n.name == old.name) {
+                None => diff.removed.push(old.name.clone()),
+                Some(n) if n != old => diff.changed.push(old.name.clone()),
+                Some(_) => {}
+            }
+        }
+        for node in &new.nodes {
+            if !self.nodes.iter().any(|n| n.name == node.name) {
+                diff.added.push(node.name.clone());
+            }
+        }
+        diff
+    }
+
+    /// Nodes addressed by a `start:` / `stop:` target: the node with that
+    /// name, or every member of the group with that name.
+    pub fn targets<'a>(&'a self, target: &'a str) -> impl Iterator<Item = &'a PlannedNode> {
+        self.nodes
+            .iter()
+            .filter(move |n| n.name == target || n.group.as_deref() == Some(target))
+    }
+
+    /// Restrict the plan to the members of `groups` plus everything they
+    /// need: their dependencies and the targets of their `start:` actions.
+    pub fn select_groups(&mut self, groups: &[String]) -> Result<()> {
+        let mut wanted: HashSet<String> = HashSet::new();
+        let mut queue: Vec<String> = Vec::new();
+        for group in groups {
+            let members: Vec<String> = self
+                .nodes
+                .iter()
+                .filter(|n| n.group.as_ref() == Some(group))
+                .map(|n| n.name.clone())
+                .collect();
+            if members.is_empty() {
+                return Err(LaunchError::Instance(format!(
+                    "launch group '{}' has no enabled nodes",
+                    group
+                )));
+            }
+            queue.extend(members);
+        }
+
+        while let Some(name) = queue.pop() {
+            if !wanted.insert(name.clone()) {
+                continue;
+            }
+            let Some(node) = self.nodes.iter().find(|n| n.name == name) else {
+                continue;
+            };
+            queue.extend(node.depends_on.iter().cloned());
+            for action in node
+                .on_start
+                .iter()
+                .chain(&node.on_exit)
+                .chain(&node.on_crash)
+            {
+                if let LaunchAction::Start(target) = action {
+                    queue.extend(self.targets(target).map(|n| n.name.clone()));
+                }
+            }
+        }
+        self.nodes.retain(|n| wanted.contains(&n.name));
+        Ok(())
+    }
+}
+
+/// Nodes gathered from a launch file and everything it includes.
+#[derive(Default)]
+struct Collected {
+    seen: HashSet<String>,
+    groups: HashSet<String>,
+    nodes: Vec<PlannedNode>,
+    skipped: Vec<String>,
+}
+
+/// Add the nodes of `launch` (and, recursively, its includes) to `out`.
+///
+/// `stack` holds the canonical paths of the files currently being
+/// included so that include cycles are reported instead of recursing
+/// forever.
+fn collect(
+    launch: &LaunchFile,
+    ctx: &SubstitutionContext,
+    base_dir: &Path,
+    prefix: &str,
+    stack: &mut Vec<PathBuf>,
+    out: &mut Collected,
+) -> Result<()> {
+    for group in launch.groups.keys() {
+        let name = format!("{}{}", prefix, group);
+        crate::validation::validate_node_name(&name)
+            .map_err(|e| LaunchError::Instance(format!("launch group '{}': {}", name, e)))?;
+        if out.seen.contains(&name) || !out.groups.insert(name.clone()) {
+            return Err(LaunchError::Instance(format!(
+                "launch group '{}' clashes with another group or node",
+                name
+            )));
+        }
+    }
+
+    for entry in &launch.nodes {
+        let name = format!("{}{}", prefix, entry.name);
+        crate::validation::validate_node_name(&name)
+            .map_err(|e| LaunchError::Instance(format!("launch node '{}': {}", name, e)))?;
+        if out.groups.contains(&name) || !out.seen.insert(name.clone()) {
+            return Err(LaunchError::Instance(format!(
+                "duplicate launch node name '{}'",
+                name
+            )));
+        }
+
+        if !is_enabled(entry, ctx)? {
+            out.skipped.push(name);
+            continue;
+        }
+
+        let config = entry
+            .config
+            .as_ref()
+            .map(|c| ctx.substitute_value(c))
+            .transpose()?;
+        let group = match &entry.group {
+            Some(g) => Some((
+                format!("{}{}", prefix, g),
+                launch.groups.get(g).ok_or_else(|| {
+                    LaunchError::Instance(format!(
+                        "launch node '{}' is in group '{}', which is not declared under groups:",
+                        name, g
+                    ))
+                })?,
+            )),
+            None => None,
+        };
+        let namespace = entry
+            .namespace
+            .as_ref()
+            .or_else(|| group.as_ref().and_then(|(_, g)| g.namespace.as_ref()))
+            .map(|ns| ctx.substitute(ns))
+            .transpose()?
+            .filter(|ns| !ns.is_empty());
+        if let Some(ns) = &namespace {
+            crate::validation::validate_node_name(ns).map_err(|e| {
+                LaunchError::Instance(format!("launch node '{}' namespace: {}", name, e))
+            })?;
+        }
+        let env = group
+            .iter()
+            .flat_map(|(_, g)| &g.env)
+            .chain(&entry.env)
+            .map(|(key, value)| {
+                validate_env_key(key)
+                    .map_err(|e| LaunchError::Instance(format!("launch node '{}': {}", name, e)))?;
+                Ok((key.clone(), ctx.substitute(value)?))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()?;
+        let ready = entry
+            .ready
+            .as_ref()
+            .map(|check| resolve_ready(check, ctx))
+            .transpose()
+            .map_err(|e| LaunchError::Instance(format!("launch node '{}': {}", name, e)))?;
+        let machine = entry
+            .machine
+            .as_ref()
+            .map(|m| ctx.substitute(m))
+            .transpose()?
+            .filter(|m| !m.is_empty());
+        if let Some(machine) = &machine {
+            crate::validation::validate_node_name(machine).map_err(|e| {
+                LaunchError::Instance(format!("launch node '{}' machine: {}", name, e))
+            })?;
+            if ready.as_ref().is_some_and(|r| r.stdout.is_some()) {
+                return Err(LaunchError::Instance(format!(
+                    "launch node '{}': stdout readiness probes only work for local nodes",
+                    name
+                )));
+            }
+        }
+        let start_delay = seconds(&name, "start_delay", entry.start_delay)?.unwrap_or_default();
+        let start_timeout = seconds(&name, "start_timeout", entry.start_timeout)?;
+        let stop_timeout = seconds(&name, "stop_timeout", entry.stop_timeout)?;
+        let interpreter = entry
+            .interpreter
+            .as_ref()
+            .map(|i| ctx.substitute(i))
+            .transpose()?
+            .filter(|i| !i.is_empty());
+        let venv = entry
+            .venv
+            .as_ref()
+            .map(|v| ctx.substitute(v))
+            .transpose()?
+            .filter(|v| !v.is_empty())
+            .map(|v| match (v.strip_prefix("~/"), dirs::home_dir()) {
+                (Some(rest), Some(home)) => home.join(rest),
+                _ => base_dir.join(v),
+            });
+        if interpreter.is_some() && venv.is_some() {
+            return Err(LaunchError::Instance(format!(
+                "launch node '{}': set either interpreter or venv, not both",
+                name
+            )));
+        }
+        out.nodes.push(PlannedNode {
+            name,
+            node: ctx.substitute(&entry.node)?,
+            config,
+            depends_on: entry
+                .depends_on
+                .iter()
+                .map(|d| format!("{}{}", prefix, d))
+                .collect(),
+            autostart: entry.autostart && group.as_ref().is_none_or(|(_, g)| g.autostart),
+            group: group.map(|(name, _)| name),
+            namespace,
+            env,
+            on_start: resolve_actions(&entry.on_start, ctx, prefix)?,
+            on_exit: resolve_actions(&entry.on_exit, ctx, prefix)?,
+            on_crash: resolve_actions(&entry.on_crash, ctx, prefix)?,
+            ready,
+            machine,
+            start_delay,
+            start_timeout,
+            stop_timeout,
+            interpreter,
+            venv,
+        });
+    }
+
+    for include in &launch.include {
+        if stack.len() >= MAX_INCLUDE_DEPTH {
+            return Err(LaunchError::Instance(format!(
+                "launch includes nested deeper than {} levels",
+                MAX_INCLUDE_DEPTH
+            )));
+        }
+        let path = base_dir.join(ctx.substitute(&include.file)?);
+        let canonical = path
+            .canonicalize()
+            .map_err(|_| LaunchError::FileNotFound(path.display().to_string()))?;
+        if stack.contains(&canonical) {
+            return Err(LaunchError::Instance(format!(
+                "include cycle: {} includes itself",
+                canonical.display()
+            )));
+        }
+
+        let child = read_launch_file(&path.to_string_lossy())?;
+        let overrides = include
+            .args
+            .iter()
+            .map(|(name, value)| {
+                Ok(format!(
+                    "{}:={}",
+                    name,
+                    ctx.substitute(&scalar_to_string(value)?)?
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let child_prefix = format!("{}{}", prefix, ctx.substitute(&include.prefix)?);
+        let child_dir = canonical
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let child_ctx = ctx
+            .for_include(&child, &child_dir, &overrides)
+            .map_err(|e| LaunchError::Substitution(format!("include '{}': {}", include.file, e)))?;
+
+        stack.push(canonical);
+        collect(&child, &child_ctx, &child_dir, &child_prefix, stack, out)?;
+        stack.pop();
+    }
+    Ok(())
+}
+
+/// Convert a `*_delay` / `*_timeout` field in seconds to a duration.
+fn seconds(node: &str, field: &str, value: Option<f64>) -> Result<Option<Duration>> {
+    value
+        .map(|secs| {
+            Duration::try_from_secs_f64(secs).map_err(|_| {
+                LaunchError::Instance(format!(
+                    "launch node '{}': {} must be a non-negative number of seconds, got {}",
+                    node, field, secs
+                ))
+            })
+        })
+        .transpose()
+}
+
+/// Expand substitutions in lifecycle actions and prefix `start:` targets
+/// the same way as node names.
+fn resolve_actions(
+    actions: &[LaunchAction],
+    ctx: &SubstitutionContext,
+    prefix: &str,
+) -> Result<Vec<LaunchAction>> {
+    actions
+        .iter()
+        .map(|action| {
+            Ok(match action {
+                LaunchAction::Exec(cmd) => LaunchAction::Exec(ctx.substitute(cmd)?),
+                LaunchAction::Start(target) => {
+                    LaunchAction::Start(format!("{}{}", prefix, ctx.substitute(target)?))
+                }
+                LaunchAction::Stop(target) => {
+                    LaunchAction::Stop(format!("{}{}", prefix, ctx.substitute(target)?))
+                }
+                LaunchAction::Shutdown => LaunchAction::Shutdown,
+            })
+        })
+        .collect()
+}
+
+/// Expand substitutions in a readiness probe and check that it is usable.
+fn resolve_ready(check: &ReadyCheck, ctx: &SubstitutionContext) -> Result<ReadyCheck> {
+    let sub = |v: &Option<String>| v.as_ref().map(|s| ctx.substitute(s)).transpose();
+    let resolved = ReadyCheck {
+        tcp: sub(&check.tcp)?,
+        zenoh: sub(&check.zenoh)?,
+        health: check.health,
+        stdout: sub(&check.stdout)?,
+        timeout: check.timeout,
+    };
+
+    let set = [
+        resolved.tcp.is_some(),
+        resolved.zenoh.is_some(),
+        resolved.health,
+        resolved.stdout.is_some(),
+    ]
+    .iter()
+    .filter(|&&b| b)
+    .count();
+    if set != 1 {
+        return Err(LaunchError::Instance(
+            "ready: needs exactly one of tcp, zenoh, health, stdout".to_string(),
+        ));
+    }
+    if resolved.timeout == 0 {
+        return Err(LaunchError::Instance(
+            "ready: timeout must be at least 1 second".to_string(),
+        ));
+    }
+    if let Some(pattern) = &resolved.stdout {
+        regex::Regex::new(pattern).map_err(|e| {
+            LaunchError::Instance(format!("ready: invalid stdout regex '{}': {}", pattern, e))
+        })?;
+    }
+    Ok(resolved)
+}
+
+/// Reject environment variable names the OS would refuse or misparse.
+fn validate_env_key(key: &str) -> std::result::Result<(), String> {
+    let valid = key
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "invalid env var name '{}' (use letters, digits and '_')",
+            key
+        ))
+    }
+}
+
+/// Evaluate a node's `if:` and `unless:` conditions.
+///
+/// Both may be present; the node is included only when `if` is true and
+/// `unless` is false.
+fn is_enabled(entry: &LaunchNode, ctx: &SubstitutionContext) -> Result<bool> {
+    let eval = |cond: &serde_yaml::Value| -> Result<bool> {
+        ctx.eval_bool(&scalar_to_string(cond)?)
+            .map_err(|e| LaunchError::Substitution(format!("launch node '{}': {}", entry.name, e)))
+    };
+    if let Some(cond) = &entry.if_condition {
+        if !eval(cond)? {
+            return Ok(false);
+        }
+    }
+    if let Some(cond) = &entry.unless_condition {
+        if eval(cond)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// Topologically sort nodes so dependencies start first.
+///
+/// Ties keep file order, so a launch file without `depends_on` starts its
+/// nodes exactly in the order they are written.
+fn order_by_dependencies(
+    nodes: Vec<PlannedNode>,
+    skipped: &[String],
+    groups: &HashSet<String>,
+) -> Result<Vec<PlannedNode>> {
+    let index: HashMap<&str, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (n.name.as_str(), i))
+        .collect();
+
+    let missing_reason = |name: &String| {
+        if skipped.contains(name) {
+            "which is disabled by its if/unless condition"
+        } else {
+            "which is not defined in this launch file"
+        }
+    };
+    for node in &nodes {
+        for dep in &node.depends_on {
+            match index.get(dep.as_str()) {
+                Some(&i) if node.autostart && !nodes[i].autostart => {
+                    return Err(LaunchError::Instance(format!(
+                        "launch node '{}' depends on '{}', which has autostart: false",
+                        node.name, dep
+                    )));
+                }
+                Some(_) => {}
+                None => {
+                    return Err(LaunchError::Instance(format!(
+                        "launch node '{}' depends on '{}', {}",
+                        node.name,
+                        dep,
+                        missing_reason(dep)
+                    )));
+                }
+            }
+        }
+        let actions = node
+            .on_start
+            .iter()
+            .chain(&node.on_exit)
+            .chain(&node.on_crash);
+        for action in actions {
+            let (kind, target) = match action {
+                LaunchAction::Start(target) => ("start", target),
+                LaunchAction::Stop(target) => ("stop", target),
+                _ => continue,
+            };
+            if !index.contains_key(target.as_str()) && !groups.contains(target) {
+                return Err(LaunchError::Instance(format!(
+                    "launch node '{}' has a {} action for '{}', {}",
+                    node.name,
+                    kind,
+                    target,
+                    missing_reason(target)
+                )));
+            }
+        }
+    }
+
+    let mut started = vec![false; nodes.len()];
+    let mut order = Vec::with_capacity(nodes.len());
+
+    while order.len() < nodes.len() {
+        let ready = |i: usize| {
+            !started[i]
+                && nodes[i]
+                    .depends_on
+                    .iter()
+                    .all(|d| started[index[d.as_str()]])
+        };
+        let Some(i) = (0..nodes.len()).find(|&i| ready(i)) else {
+            let cycle: Vec<&str> = (0..nodes.len())
+                .filter(|&i| !started[i])
+                .map(|i| nodes[i].name.as_str())
+                .collect();
+            return Err(LaunchError::Instance(format!(
+                "dependency cycle between launch nodes: {}",
+                cycle.join(", ")
+            )));
+        };
+        started[i] = true;
+        order.push(i);
+    }
+
+    let mut slots: Vec<Option<PlannedNode>> = nodes.into_iter().map(Some).collect();
+    Ok(order.into_iter().filter_map(|i| slots[i].take()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan(yaml: &str, overrides: &[&str]) -> Result<LaunchPlan> {
+        let launch = super::super::parse_launch_file(yaml)?;
+        let overrides: Vec<String> = overrides.iter().map(|s| s.to_string()).collect();
+        let ctx = SubstitutionContext::new(&launch.args, &overrides)?;
+        LaunchPlan::build(&launch, &ctx, Path::new("."))
+    }
+
+    fn names(plan: &LaunchPlan) -> Vec<&str> {
+        plan.nodes.iter().map(|n| n.name.as_str()).collect()
+    }
+
+    const CONDITIONAL: &str = r#"
+name: garage
+args:
+  enable_camera:
+    default: true
+  simulate:
+    default: false
+nodes:
+  - name: telemetry
+    node: system-telemetry
+  - name: entrance
+    node: rtsp-camera
+    if: "$(arg enable_camera)"
+    unless: "$(arg simulate)"
+    config:
+      url: "rtsp://cam/stream"
+"#;
+
+    #[test]
+    fn if_unless_include_by_default() {
+        let plan = plan(CONDITIONAL, &[]).unwrap();
+        assert_eq!(names(&plan), vec!["telemetry", "entrance"]);
+        assert!(plan.skipped.is_empty());
+    }
+
+    #[test]
+    fn if_false_skips_node() {
+        let plan = plan(CONDITIONAL, &["enable_camera:=false"]).unwrap();
+        assert_eq!(names(&plan), vec!["telemetry"]);
+        assert_eq!(plan.skipped, vec!["entrance"]);
+    }
+
+    #[test]
+    fn unless_true_skips_node() {
+        let plan = plan(CONDITIONAL, &["simulate:=true"]).unwrap();
+        assert_eq!(plan.skipped, vec!["entrance"]);
+    }
+
+    #[test]
+    fn literal_condition_accepted() {
+        let yaml = "name: l\nnodes:\n  - name: a\n    node: x\n    if: false\n";
+        assert_eq!(plan(yaml, &[]).unwrap().skipped, vec!["a"]);
+    }
+
+    #[test]
+    fn non_boolean_condition_rejected() {
+        let yaml = "name: l\nargs:\n  m:\n    default: fast\nnodes:\n  - name: a\n    node: x\n    if: \"$(arg m)\"\n";
+        let err = plan(yaml, &[]).unwrap_err().to_string();
+        assert!(err.contains("'a'"), "{err}");
+    }
+
+    #[test]
+    fn config_substitutions_expanded() {
+        let yaml = r#"
+name: l
+args:
+  host:
+    default: 10.0.0.5
+nodes:
+  - name: cam
+    node: rtsp-camera
+    config:
+      url: "rtsp://$(arg host)/s1"
+"#;
+        let plan = plan(yaml, &["host:=10.0.0.9"]).unwrap();
+        let config = plan.nodes[0].config.as_ref().unwrap();
+        assert_eq!(config["url"].as_str(), Some("rtsp://10.0.0.9/s1"));
+    }
+
+    #[test]
+    fn dependencies_start_first() {
+        let yaml = r#"
+name: l
+nodes:
+  - name: detector
+    node: yolo
+    depends_on: [camera]
+  - name: recorder
+    node: mcap
+    depends_on: [camera, detector]
+  - name: camera
+    node: rtsp-camera
+"#;
+        let plan = plan(yaml, &[]).unwrap();
+        assert_eq!(names(&plan), vec!["camera", "detector", "recorder"]);
+    }
+
+    #[test]
+    fn dependency_on_skipped_node_rejected() {
+        let yaml = r#"
+name: l
+args:
+  cam:
+    default: false
+nodes:
+  - name: camera
+    node: rtsp-camera
+    if: "$(arg cam)"
+  - name: detector
+    node: yolo
+    depends_on: [camera]
+"#;
+        let err = plan(yaml, &[]).unwrap_err().to_string();
+        assert!(err.contains("disabled"), "{err}");
+    }
+
+    #[test]
+    fn unknown_dependency_rejected() {
+        let yaml = "name: l\nnodes:\n  - name: a\n    node: x\n    depends_on: [ghost]\n";
+        let err = plan(yaml, &[]).unwrap_err().to_string();
+        assert!(err.contains("not defined"), "{err}");
+    }
+
+    #[test]
+    fn dependency_cycle_rejected() {
+        let yaml = r#"
+name: l
+nodes:
+  - name: a
+    node: x
+    depends_on: [b]
+  - name: b
+    node: x
+    depends_on: [a]
+"#;
+        let err = plan(yaml, &[]).unwrap_err().to_string();
+        assert!(err.contains("cycle"), "{err}");
+    }
+
+    #[test]
+    fn duplicate_and_invalid_names_rejected() {
+        let dup = "name: l\nnodes:\n  - name: a\n    node: x\n  - name: a\n    node: y\n";
+        assert!(plan(dup, &[]).is_err());
+        let bad = "name: l\nnodes:\n  - name: \"a b\"\n    node: x\n";
+        assert!(plan(bad, &[]).is_err());
+    }
+
+    #[test]
+    fn lifecycle_actions_resolved_and_validated() {
+        let yaml = r#"
+name: l
+args:
+  hook:
+    default: "logger crashed"
+nodes:
+  - name: cam
+    node: x
+    on_crash:
+      - exec: "$(arg hook)"
+      - start: backup
+  - name: backup
+    node: x
+    autostart: false
+"#;
+        let resolved = plan(yaml, &[]).unwrap();
+        assert_eq!(
+            resolved.nodes[0].on_crash,
+            vec![
+                LaunchAction::Exec("logger crashed".to_string()),
+                LaunchAction::Start("backup".to_string())
+            ]
+        );
+        assert!(!resolved.nodes[1].autostart);
+
+        let unknown =
+            "name: l\nnodes:\n  - name: a\n    node: x\n    on_exit:\n      - start: nope\n";
+        let err = plan(unknown, &[]).unwrap_err().to_string();
+        assert!(err.contains("nope"), "{err}");
+
+        let dep = "name: l\nnodes:\n  - name: a\n    node: x\n    autostart: false\n  - name: b\n    node: x\n    depends_on: [a]\n";
+        let err = plan(dep, &[]).unwrap_err().to_string();
+        assert!(err.contains("autostart"), "{err}");
+    }
+
+    #[test]
+    fn ready_check_resolved_and_validated() {
+        let yaml = r#"
+name: l
+args:
+  port:
+    default: 8554
+nodes:
+  - name: proxy
+    node: x
+    ready:
+      tcp: "127.0.0.1:$(arg port)"
+      timeout: 5
+"#;
+        let resolved = plan(yaml, &[]).unwrap();
+        let ready = resolved.nodes[0].ready.as_ref().unwrap();
+        assert_eq!(ready.tcp.as_deref(), Some("127.0.0.1:8554"));
+        assert_eq!(ready.timeout, 5);
+
+        let none = "name: l\nnodes:\n  - name: a\n    node: x\n    ready:\n      timeout: 3\n";
+        assert!(plan(none, &[]).is_err());
+        let two = "name: l\nnodes:\n  - name: a\n    node: x\n    ready:\n      health: true\n      tcp: \"h:1\"\n";
+        assert!(plan(two, &[]).is_err());
+        let bad_regex =
+            "name: l\nnodes:\n  - name: a\n    node: x\n    ready:\n      stdout: \"(\"\n";
+        let err = plan(bad_regex, &[]).unwrap_err().to_string();
+        assert!(err.contains("regex"), "{err}");
+    }
+
+    #[test]
+    fn machine_substituted_and_validated() {
+        let yaml = r#"
+name: l
+args:
+  board:
+    default: jetson_orin
+nodes:
+  - name: det
+    node: x
+    machine: "$(arg board)"
+"#;
+        let resolved = plan(yaml, &[]).unwrap();
+        assert_eq!(resolved.nodes[0].machine.as_deref(), Some("jetson_orin"));
+
+        let bad = "name: l\nnodes:\n  - name: a\n    node: x\n    machine: \"a/b\"\n";
+        assert!(plan(bad, &[]).is_err());
+        let stdout = "name: l\nnodes:\n  - name: a\n    node: x\n    machine: pi\n    ready:\n      stdout: up\n";
+        let err = plan(stdout, &[]).unwrap_err().to_string();
+        assert!(err.contains("local"), "{err}");
+    }
+
+    #[test]
+    fn timings_parsed_and_validated() {
+        let yaml = "name: l\nnodes:\n  - name: a\n    node: x\n    start_delay: 0.5\n    start_timeout: 10\n    stop_timeout: 1\n";
+        let resolved = plan(yaml, &[]).unwrap();
+        assert_eq!(resolved.nodes[0].start_delay, Duration::from_millis(500));
+        assert_eq!(
+            resolved.nodes[0].start_timeout,
+            Some(Duration::from_secs(10))
+        );
+        assert_eq!(resolved.nodes[0].stop_timeout, Some(Duration::from_secs(1)));
+
+        let negative = "name: l\nnodes:\n  - name: a\n    node: x\n    stop_timeout: -1\n";
+        let err = plan(negative, &[]).unwrap_err().to_string();
+        assert!(err.contains("stop_timeout"), "{err}");
+    }
+
+    #[test]
+    fn python_venv_resolved_against_launch_dir() {
+        let yaml = "name: l\nnodes:\n  - name: a\n    node: x\n    venv: envs/a\n  - name: b\n    node: x\n    interpreter: python3.11\n";
+        let resolved = plan(yaml, &[]).unwrap();
+        assert_eq!(resolved.nodes[0].venv, Some(PathBuf::from("./envs/a")));
+        assert_eq!(resolved.nodes[1].interpreter.as_deref(), Some("python3.11"));
+
+        let both =
+            "name: l\nnodes:\n  - name: a\n    node: x\n    venv: e\n    interpreter: python3\n";
+        let err = plan(both, &[]).unwrap_err().to_string();
+        assert!(err.contains("either interpreter or venv"), "{err}");
+    }
+
+    #[test]
+    fn namespace_and_env_substituted() {
+        let yaml = r#"
+name: l
+args:
+  site:
+    default: garage
+nodes:
+  - name: cam
+    node: rtsp-camera
+    namespace: "$(arg site)"
+    env:
+      RUST_LOG: debug
+      SITE: "$(arg site)"
+  - name: plain
+    node: rtsp-camera
+    namespace: ""
+"#;
+        let plan = plan(yaml, &["site:=porch"]).unwrap();
+        let cam = &plan.nodes[0];
+        assert_eq!(cam.namespace.as_deref(), Some("porch"));
+        assert_eq!(cam.instance_name(), "porch_cam");
+        assert_eq!(cam.env["RUST_LOG"], "debug");
+        assert_eq!(cam.env["SITE"], "porch");
+        assert_eq!(plan.nodes[1].namespace, None);
+        assert_eq!(plan.nodes[1].instance_name(), "plain");
+    }
+
+    #[test]
+    fn invalid_namespace_and_env_key_rejected() {
+        let ns = "name: l\nnodes:\n  - name: a\n    node: x\n    namespace: \"a/b\"\n";
+        assert!(plan(ns, &[]).is_err());
+        let env = "name: l\nnodes:\n  - name: a\n    node: x\n    env:\n      \"BAD=KEY\": v\n";
+        let err = plan(env, &[]).unwrap_err().to_string();
+        assert!(err.contains("BAD=KEY"), "{err}");
+    }
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn plan_file(path: &Path, overrides: &[&str]) -> Result<LaunchPlan> {
+        let launch = read_launch_file(&path.to_string_lossy())?;
+        let overrides: Vec<String> = overrides.iter().map(|s| s.to_string()).collect();
+        let ctx = SubstitutionContext::for_launch(&launch, path.parent().unwrap(), &overrides)?;
+        LaunchPlan::build(&launch, &ctx, path.parent().unwrap())
+    }
+
+    const CAMERAS: &str = r#"
+name: cameras
+args:
+  host:
+    default: 10.0.0.1
+  audio:
+    default: false
+nodes:
+  - name: camera
+    node: rtsp-camera
+    config:
+      url: "rtsp://$(arg host)/s1"
+  - name: mic
+    node: audio
+    if: "$(arg audio)"
+  - name: detector
+    node: yolo
+    depends_on: [camera]
+"#;
+
+    #[test]
+    fn include_merges_prefixed_nodes_with_arg_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "cameras.yaml", CAMERAS);
+        let root = write(
+            dir.path(),
+            "site.yaml",
+            r#"
+name: site
+args:
+  front_host:
+    default: 10.0.0.7
+include:
+  - file: cameras.yaml
+    prefix: front_
+    args:
+      host: "$(arg front_host)"
+nodes:
+  - name: recorder
+    node: mcap
+    depends_on: [front_detector]
+"#,
+        );
+
+        let plan = plan_file(&root, &[]).unwrap();
+        assert_eq!(
+            names(&plan),
+            vec!["front_camera", "front_detector", "recorder"]
+        );
+        assert_eq!(plan.skipped, vec!["front_mic"]);
+        assert_eq!(plan.nodes[1].depends_on, vec!["front_camera"]);
+        let config = plan.nodes[0].config.as_ref().unwrap();
+        assert_eq!(config["url"].as_str(), Some("rtsp://10.0.0.7/s1"));
+    }
+
+    #[test]
+    fn same_file_included_twice_with_different_prefixes() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "cameras.yaml", CAMERAS);
+        let root = write(
+            dir.path(),
+            "site.yaml",
+            "name: site\ninclude:\n  - file: cameras.yaml\n    prefix: a_\n  - file: cameras.yaml\n    prefix: b_\n    args:\n      audio: true\n",
+        );
+        let plan = plan_file(&root, &[]).unwrap();
+        assert_eq!(plan.nodes.len(), 5);
+        assert_eq!(plan.skipped, vec!["a_mic"]);
+    }
+
+    #[test]
+    fn include_without_prefix_conflicts_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "cameras.yaml", CAMERAS);
+        let root = write(
+            dir.path(),
+            "site.yaml",
+            "name: site\ninclude:\n  - file: cameras.yaml\nnodes:\n  - name: camera\n    node: other\n",
+        );
+        let err = plan_file(&root, &[]).unwrap_err().to_string();
+        assert!(err.contains("duplicate"), "{err}");
+    }
+
+    #[test]
+    fn include_unknown_arg_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "cameras.yaml", CAMERAS);
+        let root = write(
+            dir.path(),
+            "site.yaml",
+            "name: site\ninclude:\n  - file: cameras.yaml\n    args:\n      hots: x\n",
+        );
+        let err = plan_file(&root, &[]).unwrap_err().to_string();
+        assert!(err.contains("hots"), "{err}");
+    }
+
+    #[test]
+    fn include_cycle_and_missing_file_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "a.yaml",
+            "name: a\ninclude:\n  - file: b.yaml\n",
+        );
+        let b = write(
+            dir.path(),
+            "b.yaml",
+            "name: b\ninclude:\n  - file: a.yaml\n",
+        );
+        let err = plan_file(&b, &[]).unwrap_err().to_string();
+        assert!(err.contains("cycle"), "{err}");
+
+        let missing = write(
+            dir.path(),
+            "m.yaml",
+            "name: m\ninclude:\n  - file: nope.yaml\n",
+        );
+        assert!(matches!(
+            plan_file(&missing, &[]),
+            Err(LaunchError::FileNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn groups_share_settings_and_can_be_selected() {
+        let launch = r#"
+name: l
+groups:
+  core:
+    namespace: garage
+    env:
+      RUST_LOG: info
+      ZONE: a
+  spare:
+    autostart: false
+nodes:
+  - name: telemetry
+    node: system-telemetry
+  - name: camera
+    node: rtsp-camera
+    group: core
+    depends_on: [telemetry]
+    env:
+      ZONE: b
+    on_crash:
+      - start: spare
+  - name: backup
+    node: rtsp-camera
+    group: spare
+  - name: recorder
+    node: mcap-recorder
+"#;
+        let mut resolved = plan(launch, &[]).unwrap();
+        let camera = resolved.nodes.iter().find(|n| n.name == "camera").unwrap();
+        assert_eq!(camera.instance_name(), "garage_camera");
+        assert_eq!(camera.env["RUST_LOG"], "info");
+        assert_eq!(camera.env["ZONE"], "b");
+        assert!(
+            !resolved
+                .nodes
+                .iter()
+                .find(|n| n.name == "backup")
+                .unwrap()
+                .autostart
+        );
+
+        resolved.select_groups(&["core".to_string()]).unwrap();
+        assert_eq!(names(&resolved), vec!["telemetry", "camera", "backup"]);
+        assert!(resolved.select_groups(&["nope".to_string()]).is_err());
+    }
+
+    #[test]
+    fn undeclared_or_clashing_group_rejected() {
+        let undeclared = "name: l\nnodes:\n  - name: a\n    node: x\n    group: core\n";
+        let err = plan(undeclared, &[]).unwrap_err().to_string();
+        assert!(err.contains("not declared"), "{err}");
+
+        let clash = "name: l\ngroups:\n  a: {}\nnodes:\n  - name: a\n    node: x\n";
+        assert!(plan(clash, &[]).is_err());
+
+        let bad_stop = "name: l\nnodes:\n  - name: a\n    node: x\n    on_exit:\n      - stop: b\n";
+        let err = plan(bad_stop, &[]).unwrap_err().to_string();
+        assert!(err.contains("stop action"), "{err}");
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed() {
+        let old = plan(
+            "name: l\nnodes:\n  - name: a\n    node: x\n  - name: b\n    node: x\n    config:\n      fps: 15\n  - name: c\n    node: x\n",
+            &[],
+        )
+        .unwrap();
+        let new = plan(
+            "name: l\nnodes:\n  - name: b\n    node: x\n    config:\n      fps: 30\n  - name: c\n    node: x\n  - name: d\n    node: x\n",
+            &[],
+        )
+        .unwrap();
+        let diff = old.diff(&new);
+        assert_eq!(diff.removed, vec!["a"]);
+        assert_eq!(diff.added, vec!["d"]);
+        assert_eq!(diff.changed, vec!["b"]);
+        assert_eq!(diff.to_string(), "added d; removed a; changed b");
+        assert!(new.diff(&new).is_empty());
+    }
+
+    #[test]
+    fn include_sees_parent_env_file() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "site.env", "BUBBALOOP_TEST_CAM_HOST=10.0.0.7\n");
+        write(dir.path(), "cameras.yaml", CAMERAS);
+        let top = write(
+            dir.path(),
+            "top.yaml",
+            r#"
+name: site
+env_file: site.env
+include:
+  - file: cameras.yaml
+    args:
+      host: "$(env BUBBALOOP_TEST_CAM_HOST)"
+"#,
+        );
+        let plan = plan_file(&top, &[]).unwrap();
+        let camera = plan.nodes.iter().find(|n| n.name == "camera").unwrap();
+        assert_eq!(
+            camera.config.as_ref().unwrap()["url"].as_str(),
+            Some("rtsp://10.0.0.7/s1")
+        );
+
+        let missing = write(dir.path(), "bad.yaml", "name: bad\nenv_file: nope.env\n");
+        assert!(matches!(
+            plan_file(&missing, &[]),
+            Err(LaunchError::FileNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,199 @@
+//! Interpreter selection for Python base nodes.
+//!
+//! A base node of `type: python` runs `main.py` (or its `command:` when
+//! that starts with `python` / `python3`) with the first interpreter found:
+//!
+//! 1. the launch node's `interpreter:`
+//! 2. the launch node's `venv:` (`{venv}/bin/python`)
+//! 3. a virtualenv in the node directory: `.venv/` (uv's default), then
+//!    `venv/` (what `bubbaloop node build` creates)
+//! 4. `uv run`, when the node directory has a `uv.lock` and `uv` is on
+//!    `PATH`; uv creates the environment on first use
+//! 5. `python3` on `PATH`
+//!
+//! Virtualenv runs export `VIRTUAL_ENV` and put the env's `bin/` first on
+//! `PATH`, so subprocesses of the node find the same tools. Every Python
+//! node gets `PYTHONUNBUFFERED=1`, otherwise its output would reach the
+//! launch in bursts.
+
+use std::path::{Path, PathBuf};
+
+use super::plan::PlannedNode;
+use crate::daemon::registry::NodeManifest;
+
+/// Program, arguments and extra environment to run a base node with.
+pub(super) type Command = (String, Vec<String>, Vec<(String, String)>);
+
+/// Where a Python node's interpreter comes from.
+#[derive(Debug, Clone, PartialEq)]
+enum Interpreter {
+    Explicit(String),
+    Venv(PathBuf),
+    Uv,
+    System,
+}
+
+/// Rewrite the base `program` and `args` of `node` for its interpreter.
+/// Nodes that are not Python, or whose `command:` runs something other
+/// than Python, pass through unchanged.
+pub(super) fn command(
+    node_dir: &Path,
+    manifest: &NodeManifest,
+    node: &PlannedNode,
+    program: String,
+    args: Vec<String>,
+) -> Command {
+    if manifest.node_type != "python" {
+        return (program, args, Vec::new());
+    }
+    let mut env = vec![("PYTHONUNBUFFERED".to_string(), "1".to_string())];
+    if manifest.command.is_some() && !is_python(&program) {
+        return (program, args, env);
+    }
+    let (program, args) = match select(node_dir, node) {
+        Interpreter::Explicit(interpreter) => (interpreter, args),
+        Interpreter::Venv(venv) => {
+            let bin = venv.join("bin");
+            let path = std::env::var_os("PATH").unwrap_or_default();
+            let path = std::env::join_paths(
+                std::iter::once(bin.clone()).chain(std::env::split_paths(&path)),
+            )
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_else(|_| bin.to_string_lossy().into_owned());
+            env.push((
+                "VIRTUAL_ENV".to_string(),
+                venv.to_string_lossy().into_owned(),
+            ));
+            env.push(("PATH".to_string(), path));
+            (bin.join("python").to_string_lossy().into_owned(), args)
+        }
+        Interpreter::Uv => {
+            let uv_args = ["run", "python"].into_iter().map(str::to_string);
+            ("uv".to_string(), uv_args.chain(args).collect())
+        }
+        Interpreter::System => ("python3".to_string(), args),
+    };
+    (program, args, env)
+}
+
+fn select(node_dir: &Path, node: &PlannedNode) -> Interpreter {
+    if let Some(interpreter) = &node.interpreter {
+        return Interpreter::Explicit(interpreter.clone());
+    }
+    if let Some(venv) = &node.venv {
+        return Interpreter::Venv(venv.clone());
+    }
+    for dir in [".venv", "venv"] {
+        let venv = node_dir.join(dir);
+        if venv.join("bin/python").exists() {
+            return Interpreter::Venv(venv);
+        }
+    }
+    if node_dir.join("uv.lock").exists() && on_path("uv") {
+        return Interpreter::Uv;
+    }
+    Interpreter::System
+}
+
+fn is_python(program: &str) -> bool {
+    matches!(
+        Path::new(program).file_name().and_then(|n| n.to_str()),
+        Some("python" | "python3")
+    )
+}
+
+/// Whether a bare program name resolves through `PATH`.
+pub(super) fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(yaml: &str) -> NodeManifest {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn default_python(node_dir: &Path) -> (String, Vec<String>) {
+        (
+            node_dir
+                .join("venv/bin/python")
+                .to_string_lossy()
+                .into_owned(),
+            vec!["main.py".to_string()],
+        )
+    }
+
+    #[test]
+    fn interpreter_selection_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let m = manifest("name: det\nversion: 0.1.0\ntype: python\n");
+        let node = PlannedNode::default();
+        let run = |node: &PlannedNode| {
+            let (program, args) = default_python(dir.path());
+            command(dir.path(), &m, node, program, args)
+        };
+
+        // No environment at all: the system interpreter.
+        let (program, args, env) = run(&node);
+        assert_eq!(program, "python3");
+        assert_eq!(args, vec!["main.py"]);
+        assert_eq!(env, vec![("PYTHONUNBUFFERED".into(), "1".into())]);
+
+        // A uv-style .venv in the node directory.
+        let venv = dir.path().join(".venv");
+        std::fs::create_dir_all(venv.join("bin")).unwrap();
+        std::fs::write(venv.join("bin/python"), "").unwrap();
+        let (program, _, env) = run(&node);
+        assert_eq!(PathBuf::from(program), venv.join("bin/python"));
+        assert!(env.contains(&("VIRTUAL_ENV".into(), venv.to_string_lossy().into_owned())));
+
+        // The launch node's venv: and interpreter: win, in that order.
+        let with_venv = PlannedNode {
+            venv: Some(PathBuf::from("/opt/envs/det")),
+            ..Default::default()
+        };
+        assert_eq!(run(&with_venv).0, "/opt/envs/det/bin/python");
+        let with_interpreter = PlannedNode {
+            interpreter: Some("/usr/bin/python3.11".to_string()),
+            ..with_venv
+        };
+        assert_eq!(run(&with_interpreter).0, "/usr/bin/python3.11");
+    }
+
+    #[test]
+    fn non_python_commands_pass_through() {
+        let dir = tempfile::tempdir().unwrap();
+        let node = PlannedNode {
+            interpreter: Some("python3.11".to_string()),
+            ..Default::default()
+        };
+        let m = manifest("name: det\nversion: 0.1.0\ntype: python\ncommand: pixi run main\n");
+        let (program, args, _) = command(
+            dir.path(),
+            &m,
+            &node,
+            "pixi".to_string(),
+            vec!["run".to_string(), "main".to_string()],
+        );
+        assert_eq!((program.as_str(), args.len()), ("pixi", 2));
+
+        let m = manifest("name: det\nversion: 0.1.0\ntype: python\ncommand: python3 -m det\n");
+        let (program, args, _) = command(
+            dir.path(),
+            &m,
+            &node,
+            "python3".to_string(),
+            vec!["-m".to_string(), "det".to_string()],
+        );
+        assert_eq!(program, "python3.11");
+        assert_eq!(args, vec!["-m", "det"]);
+
+        let m = manifest("name: cam\nversion: 0.1.0\ntype: rust\n");
+        let (_, _, env) = command(dir.path(), &m, &node, "cam".to_string(), Vec::new());
+        assert!(env.is_empty());
+    }
+}
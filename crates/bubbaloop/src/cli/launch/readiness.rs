@@ -0,0 +1,156 @@
+//! Readiness probes for launch nodes.
+//!
+//! A node with a `ready:` check is only considered up once the check
+//! passes; until then the executor holds back every node that
+//! `depends_on` it. Checks are polled until they succeed or their
+//! `timeout` expires:
+//!
+//! - `tcp`: a TCP connection to `host:port` succeeds.
+//! - `zenoh`: at least one sample arrives on the key expression.
+//! - `health`: the node's heartbeat on `.../{instance}/health` reports `ok`.
+//! - `stdout`: a line printed by the node matches the regex (the executor
+//!   pipes stdout and signals the match through a oneshot channel).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::oneshot;
+
+use super::ReadyCheck;
+
+/// How often a TCP probe retries the connection.
+const TCP_RETRY: Duration = Duration::from_millis(200);
+
+/// Everything a probe may need besides the check itself.
+pub(super) struct ProbeContext {
+    /// Zenoh session, present when the plan has `zenoh` or `health` checks.
+    pub session: Option<Arc<zenoh::Session>>,
+    /// Health topic of the node being probed.
+    pub health_key: String,
+    /// Fires when a stdout line matches the `stdout` regex.
+    pub stdout_match: Option<oneshot::Receiver<()>>,
+}
+
+/// Health heartbeat topic of a node instance.
+///
+/// Format: `bubbaloop/global/{machine}/{name}/health`
+pub(super) fn health_topic(machine_id: &str, name: &str) -> String {
+    format!("bubbaloop/global/{}/{}/health", machine_id, name)
+}
+
+/// Whether any check in `checks` needs a Zenoh session.
+pub(super) fn needs_zenoh<'a>(mut checks: impl Iterator<Item = &'a ReadyCheck>) -> bool {
+    checks.any(|c| c.zenoh.is_some() || c.health)
+}
+
+/// Wait until `check` passes or its timeout expires.
+pub(super) async fn wait_ready(check: &ReadyCheck, ctx: ProbeContext) -> Result<(), String> {
+    let timeout = Duration::from_secs(check.timeout);
+    match tokio::time::timeout(timeout, probe(check, ctx)).await {
+        Ok(result) => result,
+        Err(_) => Err(format!("not ready after {}s", check.timeout)),
+    }
+}
+
+async fn probe(check: &ReadyCheck, ctx: ProbeContext) -> Result<(), String> {
+    if let Some(addr) = &check.tcp {
+        return wait_tcp(addr).await;
+    }
+    if let Some(key) = &check.zenoh {
+        return wait_zenoh(ctx.session.as_deref(), key, |_| true).await;
+    }
+    if check.health {
+        return wait_zenoh(ctx.session.as_deref(), &ctx.health_key, |payload| {
+            payload == b"ok"
+        })
+        .await;
+    }
+    if let Some(rx) = ctx.stdout_match {
+        return rx
+            .await
+            .map_err(|_| "stdout closed before the pattern matched".to_string());
+    }
+    Ok(())
+}
+
+async fn wait_tcp(addr: &str) -> Result<(), String> {
+    loop {
+        if tokio::net::TcpStream::connect(addr).await.is_ok() {
+            return Ok(());
+        }
+        tokio::time::sleep(TCP_RETRY).await;
+    }
+}
+
+async fn wait_zenoh(
+    session: Option<&zenoh::Session>,
+    key: &str,
+    accept: impl Fn(&[u8]) -> bool,
+) -> Result<(), String> {
+    let session = session.ok_or_else(|| "no Zenoh session for readiness probe".to_string())?;
+    let subscriber = session
+        .declare_subscriber(key)
+        .await
+        .map_err(|e| format!("failed to subscribe to '{}': {}", key, e))?;
+    loop {
+        let sample = subscriber
+            .recv_async()
+            .await
+            .map_err(|e| format!("subscription to '{}' closed: {}", key, e))?;
+        if accept(&sample.payload().to_bytes()) {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(yaml: &str) -> ReadyCheck {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    fn ctx() -> ProbeContext {
+        ProbeContext {
+            session: None,
+            health_key: String::new(),
+            stdout_match: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn tcp_probe_passes_when_port_is_open() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let check = check(&format!("tcp: \"{}\"\ntimeout: 2\n", addr));
+        assert!(wait_ready(&check, ctx()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn tcp_probe_times_out_when_port_is_closed() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let check = check(&format!("tcp: \"{}\"\ntimeout: 1\n", addr));
+        let err = wait_ready(&check, ctx()).await.unwrap_err();
+        assert!(err.contains("not ready"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn stdout_probe_waits_for_match_signal() {
+        let (tx, rx) = oneshot::channel();
+        let check = check("stdout: listening\ntimeout: 2\n");
+        let mut probe_ctx = ctx();
+        probe_ctx.stdout_match = Some(rx);
+        tx.send(()).unwrap();
+        assert!(wait_ready(&check, probe_ctx).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn zenoh_probe_without_session_fails() {
+        let check = check("health: true\ntimeout: 1\n");
+        assert!(wait_ready(&check, ctx()).await.is_err());
+        assert!(needs_zenoh(std::iter::once(&check)));
+    }
+}
@@ -0,0 +1,327 @@
+//! Daemon-run launch nodes: nodes with a `machine:` run under that
+//! machine's daemon instead of as local children, and with
+//! `launch start --daemon` so does every local node (under this machine's
+//! daemon).
+//!
+//! The executor registers the instance on the daemon over Zenoh
+//! (`AddInstance` with the inline config), installs and starts it, and then
+//! follows its health heartbeat. A heartbeat that stops for
+//! [`HEALTH_TIMEOUT`] is reported as an exit, so `on_crash` handlers and
+//! `launch status` behave the same as for local nodes.
+//!
+//! When the launch ends, its instances are stopped, and those it
+//! registered are removed from the daemon again; instances that were
+//! registered before the launch are left registered.
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use super::readiness::{self, ProbeContext};
+use super::{ProcessEvent, ReadyCheck};
+use crate::cli::daemon_client::DaemonClient;
+use crate::daemon::INSTANCE_UPDATED;
+
+/// Silence on the health topic after which a remote node counts as exited.
+/// Nodes publish a heartbeat every 5 seconds.
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// `(machine, instance)` pairs a launch registered with a daemon.
+pub(super) type Registered = Arc<Mutex<HashSet<(String, String)>>>;
+
+/// The daemon requests a launch makes for its nodes.
+pub(super) trait Daemon {
+    async fn add_instance(
+        &self,
+        node: &str,
+        name: &str,
+        config_yaml: Option<&str>,
+    ) -> Result<String, String>;
+    async fn node_command(&self, name: &str, command: &str) -> Result<String, String>;
+    async fn remove(&self, name: &str) -> Result<String, String>;
+}
+
+impl Daemon for DaemonClient {
+    async fn add_instance(
+        &self,
+        node: &str,
+        name: &str,
+        config_yaml: Option<&str>,
+    ) -> Result<String, String> {
+        DaemonClient::add_instance(self, node, name, config_yaml)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn node_command(&self, name: &str, command: &str) -> Result<String, String> {
+        self.send_node_command(name, command)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn remove(&self, name: &str) -> Result<String, String> {
+        self.remove_node(name).await.map_err(|e| e.to_string())
+    }
+}
+
+/// Everything needed to bring up one node on another machine.
+pub(super) struct RemoteNode {
+    /// Launch node name.
+    pub name: String,
+    /// Base node installed on the remote machine.
+    pub node: String,
+    /// Instance name registered with the remote daemon.
+    pub instance: String,
+    pub machine: String,
+    pub config_yaml: Option<String>,
+    /// Name the node publishes its topics under.
+    pub topic_name: String,
+    pub ready: Option<ReadyCheck>,
+}
+
+/// Dispatch `remote` and report its lifecycle as [`ProcessEvent`]s.
+/// Instances the launch registers are added to `registered`.
+pub(super) async fn run(
+    session: Arc<zenoh::Session>,
+    remote: RemoteNode,
+    registered: Registered,
+    event_tx: mpsc::UnboundedSender<ProcessEvent>,
+) {
+    let client = DaemonClient::for_machine(session.clone(), &remote.machine);
+    match dispatch(&client, &remote).await {
+        Ok(true) => {
+            registered
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert((remote.machine.clone(), remote.instance.clone()));
+        }
+        Ok(false) => {}
+        Err(reason) => {
+            let _ = event_tx.send(ProcessEvent::Failed {
+                name: remote.name,
+                reason,
+            });
+            return;
+        }
+    }
+    let _ = event_tx.send(ProcessEvent::Started {
+        name: remote.name.clone(),
+        pid: 0,
+    });
+
+    let health_key = readiness::health_topic(&remote.machine, &remote.topic_name);
+    if let Some(check) = &remote.ready {
+        let ctx = ProbeContext {
+            session: Some(session.clone()),
+            health_key: health_key.clone(),
+            stdout_match: None,
+        };
+        let event = match readiness::wait_ready(check, ctx).await {
+            Ok(()) => ProcessEvent::Ready {
+                name: remote.name.clone(),
+            },
+            Err(reason) => ProcessEvent::NotReady {
+                name: remote.name.clone(),
+                reason,
+            },
+        };
+        let failed = matches!(event, ProcessEvent::NotReady { .. });
+        let _ = event_tx.send(event);
+        if failed {
+            return;
+        }
+    }
+
+    monitor_health(&session, &health_key, &remote.name, &event_tx).await;
+}
+
+/// Register, install and start `remote`. Returns whether the instance is
+/// new, i.e. was not registered before.
+async fn dispatch<D: Daemon>(client: &D, remote: &RemoteNode) -> Result<bool, String> {
+    let on = |e: String| format!("{} on {}: {}", remote.instance, remote.machine, e);
+    let reply = client
+        .add_instance(
+            &remote.node,
+            &remote.instance,
+            remote.config_yaml.as_deref(),
+        )
+        .await
+        .map_err(on)?;
+    let created = !reply.starts_with(INSTANCE_UPDATED);
+    if let Err(e) = client.node_command(&remote.instance, "install").await {
+        log::warn!(
+            "[launch] install of {} on {} failed, starting anyway: {}",
+            remote.instance,
+            remote.machine,
+            e
+        );
+    }
+    client
+        .node_command(&remote.instance, "start")
+        .await
+        .map_err(on)?;
+    Ok(created)
+}
+
+/// Wait for heartbeats until they stop or the executor goes away.
+async fn monitor_health(
+    session: &zenoh::Session,
+    key: &str,
+    name: &str,
+    event_tx: &mpsc::UnboundedSender<ProcessEvent>,
+) {
+    let subscriber = match session.declare_subscriber(key).await {
+        Ok(s) => s,
+        Err(e) => {
+            log::warn!("[launch] cannot follow health of {}: {}", name, e);
+            return;
+        }
+    };
+    while !event_tx.is_closed() {
+        match tokio::time::timeout(HEALTH_TIMEOUT, subscriber.recv_async()).await {
+            Ok(Ok(_)) => continue,
+            Ok(Err(_)) => return,
+            Err(_) => {
+                log::warn!(
+                    "[launch] no heartbeat from {} for {}s",
+                    name,
+                    HEALTH_TIMEOUT.as_secs()
+                );
+                let _ = event_tx.send(ProcessEvent::Exited {
+                    name: name.to_string(),
+                    code: None,
+                });
+                return;
+            }
+        }
+    }
+}
+
+/// Ask the remote daemon to stop an instance started by this launch.
+pub(super) async fn stop(session: Arc<zenoh::Session>, machine: &str, instance: &str) {
+    let client = DaemonClient::for_machine(session, machine);
+    teardown(&client, machine, instance, false).await;
+}
+
+/// Stop an instance when the launch ends, and remove it if the launch
+/// registered it.
+pub(super) async fn remove(session: Arc<zenoh::Session>, machine: &str, instance: &str) {
+    let client = DaemonClient::for_machine(session, machine);
+    teardown(&client, machine, instance, true).await;
+}
+
+async fn teardown<D: Daemon>(client: &D, machine: &str, instance: &str, unregister: bool) {
+    match client.node_command(instance, "stop").await {
+        Ok(_) => println!("[{}] stopped on {}", instance, machine),
+        Err(e) => log::warn!("[launch] failed to stop {} on {}: {}", instance, machine, e),
+    }
+    if !unregister {
+        return;
+    }
+    match client.remove(instance).await {
+        Ok(_) => println!("[{}] removed from {}", instance, machine),
+        Err(e) => log::warn!(
+            "[launch] failed to remove {} from {}: {}",
+            instance,
+            machine,
+            e
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records every request; `existing` instances are already registered.
+    #[derive(Default)]
+    struct FakeDaemon {
+        existing: HashSet<String>,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl FakeDaemon {
+        fn record(&self, call: String) {
+            self.calls.lock().unwrap().push(call);
+        }
+
+        fn calls(&self) -> Vec<String> {
+            std::mem::take(&mut *self.calls.lock().unwrap())
+        }
+    }
+
+    impl Daemon for FakeDaemon {
+        async fn add_instance(
+            &self,
+            _node: &str,
+            name: &str,
+            _config_yaml: Option<&str>,
+        ) -> Result<String, String> {
+            self.record(format!("add {}", name));
+            if self.existing.contains(name) {
+                Ok(format!("{}: {}", INSTANCE_UPDATED, name))
+            } else {
+                Ok(format!("Added {}", name))
+            }
+        }
+
+        async fn node_command(&self, name: &str, command: &str) -> Result<String, String> {
+            self.record(format!("{} {}", command, name));
+            Ok(String::new())
+        }
+
+        async fn remove(&self, name: &str) -> Result<String, String> {
+            self.record(format!("remove {}", name));
+            Ok(String::new())
+        }
+    }
+
+    fn node(instance: &str) -> RemoteNode {
+        RemoteNode {
+            name: instance.to_string(),
+            node: "rtsp-camera".to_string(),
+            instance: instance.to_string(),
+            machine: "jetson".to_string(),
+            config_yaml: None,
+            topic_name: instance.to_string(),
+            ready: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn launch_removes_only_instances_it_registered() {
+        let daemon = FakeDaemon {
+            existing: HashSet::from(["garage_lobby".to_string()]),
+            ..Default::default()
+        };
+
+        let created = dispatch(&daemon, &node("garage_entrance")).await.unwrap();
+        assert!(created);
+        teardown(&daemon, "jetson", "garage_entrance", created).await;
+        assert_eq!(
+            daemon.calls(),
+            [
+                "add garage_entrance",
+                "install garage_entrance",
+                "start garage_entrance",
+                "stop garage_entrance",
+                "remove garage_entrance",
+            ]
+        );
+
+        let created = dispatch(&daemon, &node("garage_lobby")).await.unwrap();
+        assert!(!created);
+        teardown(&daemon, "jetson", "garage_lobby", created).await;
+        assert_eq!(
+            daemon.calls(),
+            [
+                "add garage_lobby",
+                "install garage_lobby",
+                "start garage_lobby",
+                "stop garage_lobby",
+            ]
+        );
+    }
+}
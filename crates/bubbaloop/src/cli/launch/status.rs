@@ -0,0 +1,556 @@
+//! Launch status: what a running launch reports about its nodes.
+//!
+//! The executor keeps a [`StatusBoard`] up to date as [`ProcessEvent`]s
+//! arrive and serves snapshots of it as JSON from a Zenoh queryable at
+//! `bubbaloop/global/{machine}/launch/{name}/status`. `bubbaloop launch
+//! list` and `bubbaloop launch status` query that key from any terminal.
+//! A launch that can be reloaded also answers on `.../{name}/reload`, which
+//! `bubbaloop launch reload` queries, and every launch stops when
+//! `bubbaloop launch stop` queries `.../{name}/stop`.
+//!
+//! [`ProcessEvent`]: super::ProcessEvent
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+
+use super::plan::{LaunchPlan, PlannedNode};
+use super::{LaunchError, Result};
+
+/// How long `list` / `status` wait for replies.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long `reload` and `stop` wait; stopping nodes can take a while.
+const CONTROL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Answer to a reload or stop request: a summary of what was done, or why
+/// it failed.
+pub(super) type ControlReply = oneshot::Sender<std::result::Result<String, String>>;
+
+/// Lifecycle state of one launch node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeState {
+    /// Not started yet (waiting on dependencies or a `start:` action).
+    Waiting,
+    /// Spawned, readiness probe still pending.
+    Starting,
+    /// Spawned and ready.
+    Running,
+    /// Exited; see `exit_code`.
+    Exited,
+    /// Did not start: dispatch failed or `start_timeout` expired.
+    Failed,
+}
+
+/// Status of one node in a [`LaunchStatus`] snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NodeStatus {
+    pub name: String,
+    pub node: String,
+    /// Machine running the node when it is not the launch's own.
+    #[serde(default)]
+    pub machine: Option<String>,
+    pub state: NodeState,
+    pub pid: Option<u32>,
+    /// Seconds since the current process was spawned.
+    pub uptime_secs: Option<u64>,
+    /// Times the node was started again after exiting.
+    pub restarts: u32,
+    pub exit_code: Option<i32>,
+}
+
+/// Snapshot served by the launch status queryable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaunchStatus {
+    pub name: String,
+    pub machine_id: String,
+    /// PID of the `bubbaloop launch start` process.
+    pub pid: u32,
+    pub uptime_secs: u64,
+    pub nodes: Vec<NodeStatus>,
+}
+
+struct NodeEntry {
+    name: String,
+    node: String,
+    machine: Option<String>,
+    state: NodeState,
+    pid: Option<u32>,
+    started: Option<Instant>,
+    starts: u32,
+    exit_code: Option<i32>,
+}
+
+impl NodeEntry {
+    fn waiting(node: &PlannedNode, machine_id: &str) -> Self {
+        Self {
+            name: node.name.clone(),
+            node: node.node.clone(),
+            machine: node.machine.clone().filter(|m| m != machine_id),
+            state: NodeState::Waiting,
+            pid: None,
+            started: None,
+            starts: 0,
+            exit_code: None,
+        }
+    }
+}
+
+/// Live per-node state of a launch, updated by the executor.
+pub struct StatusBoard {
+    name: String,
+    machine_id: String,
+    started: Instant,
+    nodes: Vec<NodeEntry>,
+}
+
+impl StatusBoard {
+    pub fn new(plan: &LaunchPlan, machine_id: &str) -> Self {
+        Self {
+            name: plan.name.clone(),
+            machine_id: machine_id.to_string(),
+            started: Instant::now(),
+            nodes: plan
+                .nodes
+                .iter()
+                .map(|n| NodeEntry::waiting(n, machine_id))
+                .collect(),
+        }
+    }
+
+    /// Switch to a reloaded plan, keeping the state of nodes in both.
+    pub fn replan(&mut self, plan: &LaunchPlan) {
+        let mut old = std::mem::take(&mut self.nodes);
+        self.nodes = plan
+            .nodes
+            .iter()
+            .map(|n| match old.iter().position(|e| e.name == n.name) {
+                Some(i) => old.swap_remove(i),
+                None => NodeEntry::waiting(n, &self.machine_id),
+            })
+            .collect();
+    }
+
+    fn entry(&mut self, name: &str) -> Option<&mut NodeEntry> {
+        self.nodes.iter_mut().find(|n| n.name == name)
+    }
+
+    /// A node was spawned; `probed` nodes stay `Starting` until ready.
+    pub fn started(&mut self, name: &str, pid: Option<u32>, probed: bool) {
+        if let Some(entry) = self.entry(name) {
+            entry.state = if probed {
+                NodeState::Starting
+            } else {
+                NodeState::Running
+            };
+            entry.pid = pid;
+            entry.started = Some(Instant::now());
+            entry.starts += 1;
+            entry.exit_code = None;
+        }
+    }
+
+    pub fn ready(&mut self, name: &str) {
+        if let Some(entry) = self.entry(name) {
+            entry.state = NodeState::Running;
+        }
+    }
+
+    pub fn exited(&mut self, name: &str, code: Option<i32>) {
+        if let Some(entry) = self.entry(name) {
+            entry.state = NodeState::Exited;
+            entry.pid = None;
+            entry.started = None;
+            entry.exit_code = code;
+        }
+    }
+
+    pub fn failed(&mut self, name: &str) {
+        if let Some(entry) = self.entry(name) {
+            entry.state = NodeState::Failed;
+            entry.pid = None;
+            entry.started = None;
+        }
+    }
+
+    pub fn snapshot(&self) -> LaunchStatus {
+        LaunchStatus {
+            name: self.name.clone(),
+            machine_id: self.machine_id.clone(),
+            pid: std::process::id(),
+            uptime_secs: self.started.elapsed().as_secs(),
+            nodes: self
+                .nodes
+                .iter()
+                .map(|n| NodeStatus {
+                    name: n.name.clone(),
+                    node: n.node.clone(),
+                    machine: n.machine.clone(),
+                    state: n.state,
+                    pid: n.pid,
+                    uptime_secs: n.started.map(|s| s.elapsed().as_secs()),
+                    restarts: n.starts.saturating_sub(1),
+                    exit_code: n.exit_code,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Status key of one launch.
+///
+/// Format: `bubbaloop/global/{machine}/launch/{name}/status`
+pub fn status_topic(machine_id: &str, launch: &str) -> String {
+    format!("bubbaloop/global/{}/launch/{}/status", machine_id, launch)
+}
+
+/// Serve snapshots of `board` until the session closes.
+pub(super) fn serve(session: Arc<zenoh::Session>, board: Arc<Mutex<StatusBoard>>) {
+    let key = {
+        let board = board.lock().unwrap_or_else(|e| e.into_inner());
+        status_topic(&board.machine_id, &board.name)
+    };
+    tokio::spawn(async move {
+        let queryable = match session.declare_queryable(&key).await {
+            Ok(q) => q,
+            Err(e) => {
+                log::warn!("[launch] failed to register status queryable: {}", e);
+                return;
+            }
+        };
+        while let Ok(query) = queryable.recv_async().await {
+            let snapshot = board.lock().unwrap_or_else(|e| e.into_inner()).snapshot();
+            if let Ok(json) = serde_json::to_vec(&snapshot) {
+                let _ = query
+                    .reply(&key, json)
+                    .encoding(zenoh::bytes::Encoding::APPLICATION_JSON)
+                    .await;
+            }
+        }
+    });
+}
+
+/// Reload key of one launch.
+///
+/// Format: `bubbaloop/global/{machine}/launch/{name}/reload`
+pub fn reload_topic(machine_id: &str, launch: &str) -> String {
+    format!("bubbaloop/global/{}/launch/{}/reload", machine_id, launch)
+}
+
+/// Stop key of one launch.
+///
+/// Format: `bubbaloop/global/{machine}/launch/{name}/stop`
+pub fn stop_topic(machine_id: &str, launch: &str) -> String {
+    format!("bubbaloop/global/{}/launch/{}/stop", machine_id, launch)
+}
+
+/// Forward queries on a reload or stop key to the executor as `requests`
+/// and reply with its answer.
+pub(super) fn serve_control(
+    session: Arc<zenoh::Session>,
+    key: String,
+    requests: mpsc::UnboundedSender<ControlReply>,
+) {
+    tokio::spawn(async move {
+        let queryable = match session.declare_queryable(&key).await {
+            Ok(q) => q,
+            Err(e) => {
+                log::warn!("[launch] failed to register queryable {}: {}", key, e);
+                return;
+            }
+        };
+        while let Ok(query) = queryable.recv_async().await {
+            let (tx, rx) = oneshot::channel();
+            if requests.send(tx).is_err() {
+                return;
+            }
+            let _ = match rx.await {
+                Ok(Ok(summary)) => query.reply(&key, summary).await,
+                Ok(Err(reason)) => query.reply_err(reason).await,
+                Err(_) => continue,
+            };
+        }
+    });
+}
+
+/// Query every launch status matching `pattern`.
+async fn query(pattern: &str) -> Result<Vec<LaunchStatus>> {
+    let session = crate::cli::zenoh_session::create_zenoh_session(None)
+        .await
+        .map_err(|e| LaunchError::Instance(format!("Zenoh connect failed: {}", e)))?;
+    let replies = session
+        .get(pattern)
+        .timeout(QUERY_TIMEOUT)
+        .await
+        .map_err(|e| LaunchError::Instance(format!("query '{}' failed: {}", pattern, e)))?;
+
+    let mut statuses = Vec::new();
+    while let Ok(reply) = replies.recv_async().await {
+        let Ok(sample) = reply.result() else {
+            continue;
+        };
+        match serde_json::from_slice::<LaunchStatus>(&sample.payload().to_bytes()) {
+            Ok(status) => statuses.push(status),
+            Err(e) => log::warn!("[launch] bad status from {}: {}", sample.key_expr(), e),
+        }
+    }
+    statuses.sort_by(|a, b| (&a.machine_id, &a.name).cmp(&(&b.machine_id, &b.name)));
+    Ok(statuses)
+}
+
+/// `bubbaloop launch list`
+pub(super) async fn list(all: bool, json: bool) -> Result<()> {
+    let machine = if all {
+        "*".to_string()
+    } else {
+        crate::daemon::util::get_machine_id()
+    };
+    let statuses = query(&status_topic(&machine, "*")).await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&statuses)?);
+        return Ok(());
+    }
+    if statuses.is_empty() {
+        println!("No running launches found.");
+        return Ok(());
+    }
+    println!(
+        "{:<24} {:<20} {:>8} {:>8} {:>10}",
+        "LAUNCH", "MACHINE", "PID", "NODES", "UPTIME"
+    );
+    for status in &statuses {
+        let up = status
+            .nodes
+            .iter()
+            .filter(|n| matches!(n.state, NodeState::Starting | NodeState::Running))
+            .count();
+        println!(
+            "{:<24} {:<20} {:>8} {:>8} {:>10}",
+            status.name,
+            status.machine_id,
+            status.pid,
+            format!("{}/{}", up, status.nodes.len()),
+            format_uptime(status.uptime_secs)
+        );
+    }
+    Ok(())
+}
+
+/// `bubbaloop launch status <name>`
+pub(super) async fn show(name: &str, machine: Option<&str>, json: bool) -> Result<()> {
+    let machine = machine
+        .map(str::to_string)
+        .unwrap_or_else(crate::daemon::util::get_machine_id);
+    let statuses = query(&status_topic(&machine, name)).await?;
+    let Some(status) = statuses.first() else {
+        return Err(LaunchError::Instance(format!(
+            "no running launch '{}' on {}",
+            name, machine
+        )));
+    };
+    if json {
+        println!("{}", serde_json::to_string_pretty(status)?);
+    } else {
+        print!("{}", format_status(status));
+    }
+    Ok(())
+}
+
+/// `bubbaloop launch reload <name>`
+pub(super) async fn reload(name: &str, machine: Option<&str>) -> Result<()> {
+    let summary = control(name, machine, reload_topic, "reload").await?;
+    println!("Reloaded '{}': {}", name, summary);
+    Ok(())
+}
+
+/// `bubbaloop launch stop <name>`
+pub(super) async fn stop(name: &str, machine: Option<&str>) -> Result<()> {
+    let summary = control(name, machine, stop_topic, "stop").await?;
+    println!("Stopped '{}': {}", name, summary);
+    Ok(())
+}
+
+/// Query the `topic` key of a running launch and return its summary.
+async fn control(
+    name: &str,
+    machine: Option<&str>,
+    topic: fn(&str, &str) -> String,
+    action: &str,
+) -> Result<String> {
+    let machine = machine
+        .map(str::to_string)
+        .unwrap_or_else(crate::daemon::util::get_machine_id);
+    let key = topic(&machine, name);
+    let session = crate::cli::zenoh_session::create_zenoh_session(None)
+        .await
+        .map_err(|e| LaunchError::Instance(format!("Zenoh connect failed: {}", e)))?;
+    let replies = session
+        .get(&key)
+        .timeout(CONTROL_TIMEOUT)
+        .await
+        .map_err(|e| LaunchError::Instance(format!("query '{}' failed: {}", key, e)))?;
+
+    let Ok(reply) = replies.recv_async().await else {
+        return Err(LaunchError::Instance(format!(
+            "no running launch '{}' on {}",
+            name, machine
+        )));
+    };
+    match reply.result() {
+        Ok(sample) => Ok(String::from_utf8_lossy(&sample.payload().to_bytes()).into_owned()),
+        Err(e) => Err(LaunchError::Instance(format!(
+            "{} of '{}' failed: {}",
+            action,
+            name,
+            String::from_utf8_lossy(&e.payload().to_bytes())
+        ))),
+    }
+}
+
+fn format_status(status: &LaunchStatus) -> String {
+    let mut out = format!(
+        "Launch: {} on {} (pid {}, up {})\n",
+        status.name,
+        status.machine_id,
+        status.pid,
+        format_uptime(status.uptime_secs)
+    );
+    out.push_str(&format!(
+        "{:<24} {:<20} {:<9} {:>8} {:>10} {:>8}\n",
+        "NODE", "BASE", "STATE", "PID", "UPTIME", "RESTARTS"
+    ));
+    for node in &status.nodes {
+        let state = match (node.state, node.exit_code) {
+            (NodeState::Exited, Some(code)) => format!("exited({})", code),
+            (state, _) => serde_json::to_value(state)
+                .ok()
+                .and_then(|v| v.as_str().map(str::to_string))
+                .unwrap_or_default(),
+        };
+        let base = match &node.machine {
+            Some(machine) => format!("{}@{}", node.node, machine),
+            None => node.node.clone(),
+        };
+        out.push_str(&format!(
+            "{:<24} {:<20} {:<9} {:>8} {:>10} {:>8}\n",
+            node.name,
+            base,
+            state,
+            node.pid
+                .map(|p| p.to_string())
+                .unwrap_or_else(|| "-".into()),
+            node.uptime_secs
+                .map(format_uptime)
+                .unwrap_or_else(|| "-".into()),
+            node.restarts
+        ));
+    }
+    out
+}
+
+pub(crate) fn format_uptime(secs: u64) -> String {
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m{}s", s / 60, s % 60),
+        s => format!("{}h{}m", s / 3600, (s % 3600) / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board() -> StatusBoard {
+        let plan = LaunchPlan {
+            name: "garage".to_string(),
+            nodes: vec![
+                PlannedNode {
+                    name: "cam".to_string(),
+                    node: "rtsp-camera".to_string(),
+                    ..Default::default()
+                },
+                PlannedNode {
+                    name: "det".to_string(),
+                    node: "detector".to_string(),
+                    ..Default::default()
+                },
+            ],
+            skipped: vec![],
+        };
+        StatusBoard::new(&plan, "jetson1")
+    }
+
+    #[test]
+    fn board_tracks_lifecycle_and_restarts() {
+        let mut board = board();
+        board.started("cam", Some(100), true);
+        assert_eq!(board.snapshot().nodes[0].state, NodeState::Starting);
+        board.ready("cam");
+        board.exited("cam", Some(1));
+        board.started("cam", Some(101), false);
+
+        let snap = board.snapshot();
+        assert_eq!(snap.name, "garage");
+        assert_eq!(snap.nodes[0].state, NodeState::Running);
+        assert_eq!(snap.nodes[0].pid, Some(101));
+        assert_eq!(snap.nodes[0].restarts, 1);
+        assert_eq!(snap.nodes[1].state, NodeState::Waiting);
+        assert_eq!(snap.nodes[1].uptime_secs, None);
+    }
+
+    #[test]
+    fn replan_keeps_existing_entries() {
+        let mut board = board();
+        board.started("cam", Some(100), false);
+        let plan = LaunchPlan {
+            name: "garage".to_string(),
+            nodes: vec![
+                PlannedNode {
+                    name: "mic".to_string(),
+                    node: "audio".to_string(),
+                    ..Default::default()
+                },
+                PlannedNode {
+                    name: "cam".to_string(),
+                    node: "rtsp-camera".to_string(),
+                    ..Default::default()
+                },
+            ],
+            skipped: vec![],
+        };
+        board.replan(&plan);
+        let snap = board.snapshot();
+        assert_eq!(snap.nodes.len(), 2);
+        assert_eq!(snap.nodes[0].state, NodeState::Waiting);
+        assert_eq!(snap.nodes[1].pid, Some(100));
+    }
+
+    #[test]
+    fn status_roundtrips_as_json() {
+        let mut board = board();
+        board.started("det", None, false);
+        board.exited("det", Some(3));
+        let snap = board.snapshot();
+        let json = serde_json::to_string(&snap).unwrap();
+        assert!(json.contains("\"state\":\"exited\""), "{json}");
+        let back: LaunchStatus = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, snap);
+        assert!(format_status(&back).contains("exited(3)"));
+    }
+
+    #[test]
+    fn status_topic_format() {
+        assert_eq!(
+            status_topic("jetson1", "garage"),
+            "bubbaloop/global/jetson1/launch/garage/status"
+        );
+        assert_eq!(
+            stop_topic("jetson1", "garage"),
+            "bubbaloop/global/jetson1/launch/garage/stop"
+        );
+        assert_eq!(format_uptime(3725), "1h2m");
+    }
+}
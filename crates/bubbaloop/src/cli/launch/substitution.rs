@@ -0,0 +1,510 @@
+//! `$(...)` substitutions for launch files.
+//!
+//! Launch files reference their declared arguments with `$(arg name)` and
+//! environment variables with `$(env NAME)` or `$(env NAME default)`.
+//! Substitutions are resolved against a [`SubstitutionContext`] built from
+//! the file's `args:` defaults merged with `--arg name:=value` overrides
+//! from the command line. The same context evaluates `if:` / `unless:`
+//! conditions on launch nodes.
+//!
+//! `$(env ...)` reads the process environment first and then the
+//! variables of the launch file's `env_file:`, so a deployment can keep its
+//! hosts and credentials in a `.env` file next to the launch file and still
+//! override them from the shell. Arg defaults may use `$(env ...)` too.
+//!
+//! Resolved arg values are checked against their declared `type:`,
+//! `choices:` and `required:` when the context is built, so a bad value
+//! fails the launch before any node starts.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::{ArgType, LaunchArg, LaunchError, LaunchFile, Result};
+
+/// Resolved launch arguments used to expand `$(...)` substitutions.
+#[derive(Debug, Clone, Default)]
+pub struct SubstitutionContext {
+    args: BTreeMap<String, String>,
+    /// Variables loaded from `env_file:`, below the process environment.
+    env_file: BTreeMap<String, String>,
+}
+
+impl SubstitutionContext {
+    /// Build a context from declared args and `name:=value` overrides.
+    ///
+    /// Overrides for args that the launch file does not declare are
+    /// rejected — a typo on the command line should fail loudly instead of
+    /// silently falling back to the default.
+    pub fn new(declared: &BTreeMap<String, LaunchArg>, overrides: &[String]) -> Result<Self> {
+        Self::with_env_file(declared, overrides, BTreeMap::new())
+    }
+
+    /// Build the context of `launch`, loading its `env_file:` (relative to
+    /// `base_dir`) for `$(env ...)` lookups.
+    pub fn for_launch(launch: &LaunchFile, base_dir: &Path, overrides: &[String]) -> Result<Self> {
+        let env_file = match &launch.env_file {
+            Some(file) => read_env_file(&base_dir.join(file))?,
+            None => BTreeMap::new(),
+        };
+        Self::with_env_file(&launch.args, overrides, env_file)
+    }
+
+    /// Build the context of an included launch file. The include sees the
+    /// env file variables of its parent, overlaid with its own `env_file:`.
+    pub fn for_include(
+        &self,
+        launch: &LaunchFile,
+        base_dir: &Path,
+        overrides: &[String],
+    ) -> Result<Self> {
+        let mut env_file = self.env_file.clone();
+        if let Some(file) = &launch.env_file {
+            env_file.extend(read_env_file(&base_dir.join(file))?);
+        }
+        Self::with_env_file(&launch.args, overrides, env_file)
+    }
+
+    fn with_env_file(
+        declared: &BTreeMap<String, LaunchArg>,
+        overrides: &[String],
+        env_file: BTreeMap<String, String>,
+    ) -> Result<Self> {
+        let mut ctx = Self {
+            args: BTreeMap::new(),
+            env_file,
+        };
+        let mut args = BTreeMap::new();
+        for (name, arg) in declared {
+            if arg.required && arg.default.is_some() {
+                return Err(LaunchError::Substitution(format!(
+                    "launch arg '{}' is required and cannot have a default",
+                    name
+                )));
+            }
+            if let Some(default) = &arg.default {
+                let default = ctx.substitute(&scalar_to_string(default)?).map_err(|e| {
+                    LaunchError::Substitution(format!("default of arg '{}': {}", name, e))
+                })?;
+                args.insert(name.clone(), default);
+            }
+        }
+
+        for raw in overrides {
+            let (name, value) = parse_override(raw)?;
+            if !declared.contains_key(name) {
+                return Err(LaunchError::Substitution(format!(
+                    "unknown launch arg '{}' (declared: {})",
+                    name,
+                    declared.keys().cloned().collect::<Vec<_>>().join(", ")
+                )));
+            }
+            args.insert(name.to_string(), value.to_string());
+        }
+
+        for (name, arg) in declared {
+            match args.remove(name) {
+                Some(value) => {
+                    args.insert(name.clone(), check_arg(name, arg, value)?);
+                }
+                None if arg.required => {
+                    return Err(LaunchError::Substitution(format!(
+                        "launch arg '{}' is required (pass --arg {}:=<value>)",
+                        name, name
+                    )));
+                }
+                None => {}
+            }
+        }
+
+        ctx.args = args;
+        Ok(ctx)
+    }
+
+    /// Value of a resolved arg, if it has one.
+    pub fn arg(&self, name: &str) -> Option<&str> {
+        self.args.get(name).map(String::as_str)
+    }
+
+    /// Value of an environment variable: the process environment wins over
+    /// the env file.
+    pub fn env(&self, name: &str) -> Option<String> {
+        std::env::var(name)
+            .ok()
+            .or_else(|| self.env_file.get(name).cloned())
+    }
+
+    /// Expand every `$(...)` substitution in `input`.
+    pub fn substitute(&self, input: &str) -> Result<String> {
+        let mut out = String::with_capacity(input.len());
+        let mut rest = input;
+        while let Some(start) = rest.find("$(") {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let end = after.find(')').ok_or_else(|| {
+                LaunchError::Substitution(format!("unterminated substitution in '{}'", input))
+            })?;
+            out.push_str(&self.resolve(after[..end].trim())?);
+            rest = &after[end + 1..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    /// Expand substitutions in every string scalar of a YAML value.
+    pub fn substitute_value(&self, value: &serde_yaml::Value) -> Result<serde_yaml::Value> {
+        Ok(match value {
+            serde_yaml::Value::String(s) => serde_yaml::Value::String(self.substitute(s)?),
+            serde_yaml::Value::Sequence(seq) => serde_yaml::Value::Sequence(
+                seq.iter()
+                    .map(|v| self.substitute_value(v))
+                    .collect::<Result<_>>()?,
+            ),
+            serde_yaml::Value::Mapping(map) => {
+                let mut out = serde_yaml::Mapping::with_capacity(map.len());
+                for (k, v) in map {
+                    out.insert(k.clone(), self.substitute_value(v)?);
+                }
+                serde_yaml::Value::Mapping(out)
+            }
+            other => other.clone(),
+        })
+    }
+
+    /// Evaluate an `if:` / `unless:` expression to a boolean.
+    ///
+    /// The expression is substituted first, so both `"$(arg use_gpu)"` and
+    /// literal `true` / `false` work. Accepted spellings are `true`/`false`,
+    /// `yes`/`no`, `on`/`off` and `1`/`0` (case-insensitive); anything else is
+    /// an error rather than being coerced.
+    pub fn eval_bool(&self, expr: &str) -> Result<bool> {
+        let value = self.substitute(expr)?;
+        parse_bool(&value).ok_or_else(|| {
+            LaunchError::Substitution(format!(
+                "condition '{}' evaluated to '{}', expected a boolean",
+                expr,
+                value.trim()
+            ))
+        })
+    }
+
+    fn resolve(&self, body: &str) -> Result<String> {
+        let (kind, rest) = body.split_once(char::is_whitespace).unwrap_or((body, ""));
+        let rest = rest.trim();
+        match kind {
+            "arg" if !rest.is_empty() && !rest.contains(char::is_whitespace) => {
+                self.arg(rest).map(str::to_string).ok_or_else(|| {
+                    LaunchError::Substitution(format!(
+                        "launch arg '{}' is not declared or has no value (pass --arg {}:=<value>)",
+                        rest, rest
+                    ))
+                })
+            }
+            // The default is everything after the name, spaces included.
+            "env" if !rest.is_empty() => {
+                let (name, default) = match rest.split_once(char::is_whitespace) {
+                    Some((name, default)) => (name, Some(default.trim())),
+                    None => (rest, None),
+                };
+                self.env(name)
+                    .or_else(|| default.map(str::to_string))
+                    .ok_or_else(|| {
+                        LaunchError::Substitution(format!(
+                            "environment variable '{}' is not set (set it, add it to env_file: \
+                             or use $(env {} <default>))",
+                            name, name
+                        ))
+                    })
+            }
+            _ => Err(LaunchError::Substitution(format!(
+                "unsupported substitution '$({})' (expected $(arg <name>) or $(env <name> [default]))",
+                body
+            ))),
+        }
+    }
+}
+
+/// Read a `KEY=VALUE` env file.
+///
+/// Blank lines and `#` comments are skipped, an `export ` prefix is
+/// accepted, and values wrapped in single or double quotes are unquoted.
+pub(super) fn read_env_file(path: &Path) -> Result<BTreeMap<String, String>> {
+    if !path.exists() {
+        return Err(LaunchError::FileNotFound(path.display().to_string()));
+    }
+    parse_env_file(&std::fs::read_to_string(path)?)
+        .map_err(|e| LaunchError::Substitution(format!("{}: {}", path.display(), e)))
+}
+
+fn parse_env_file(content: &str) -> std::result::Result<BTreeMap<String, String>, String> {
+    let mut vars = BTreeMap::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected KEY=VALUE", i + 1))?;
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!("line {}: invalid variable name '{}'", i + 1, key));
+        }
+        let value = value.trim();
+        let value = [('"', '"'), ('\'', '\'')]
+            .iter()
+            .find_map(|(open, close)| {
+                value
+                    .strip_prefix(*open)
+                    .and_then(|v| v.strip_suffix(*close))
+            })
+            .unwrap_or(value);
+        vars.insert(key.to_string(), value.to_string());
+    }
+    Ok(vars)
+}
+
+/// Parse the boolean spellings accepted by conditions and `type: bool` args.
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Check a resolved arg value against its declared type and choices,
+/// returning the normalised value.
+fn check_arg(name: &str, arg: &LaunchArg, value: String) -> Result<String> {
+    let invalid = |expected: &str| {
+        LaunchError::Substitution(format!(
+            "launch arg '{}' = '{}' is not {}",
+            name, value, expected
+        ))
+    };
+    let value = match arg.arg_type {
+        ArgType::String => value.clone(),
+        ArgType::Int => value
+            .trim()
+            .parse::<i64>()
+            .map_err(|_| invalid("an integer"))?
+            .to_string(),
+        ArgType::Bool => parse_bool(&value)
+            .ok_or_else(|| invalid("a boolean"))?
+            .to_string(),
+        ArgType::Path => {
+            if value.trim().is_empty() {
+                return Err(invalid("a path"));
+            }
+            match (value.strip_prefix("~/"), dirs::home_dir()) {
+                (Some(rest), Some(home)) => home.join(rest).to_string_lossy().into_owned(),
+                _ => value.clone(),
+            }
+        }
+    };
+
+    if arg.choices.is_empty() {
+        return Ok(value);
+    }
+    let choices = arg
+        .choices
+        .iter()
+        .map(|c| {
+            let choice = scalar_to_string(c)?;
+            Ok(match arg.arg_type {
+                ArgType::Int => choice
+                    .trim()
+                    .parse::<i64>()
+                    .map_or(choice, |n| n.to_string()),
+                ArgType::Bool => parse_bool(&choice).map_or(choice, |b| b.to_string()),
+                _ => choice,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if !choices.contains(&value) {
+        return Err(LaunchError::Substitution(format!(
+            "launch arg '{}' = '{}' is not one of: {}",
+            name,
+            value,
+            choices.join(", ")
+        )));
+    }
+    Ok(value)
+}
+
+/// Split a `name:=value` override into its parts.
+fn parse_override(raw: &str) -> Result<(&str, &str)> {
+    let (name, value) = raw.split_once(":=").ok_or_else(|| {
+        LaunchError::Substitution(format!("invalid --arg '{}' (expected name:=value)", raw))
+    })?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(LaunchError::Substitution(format!(
+            "invalid --arg '{}' (empty name)",
+            raw
+        )));
+    }
+    Ok((name, value))
+}
+
+/// Render a YAML scalar default (`true`, `8080`, `"cam"`) as a string.
+pub(super) fn scalar_to_string(value: &serde_yaml::Value) -> Result<String> {
+    match value {
+        serde_yaml::Value::String(s) => Ok(s.clone()),
+        serde_yaml::Value::Bool(b) => Ok(b.to_string()),
+        serde_yaml::Value::Number(n) => Ok(n.to_string()),
+        other => Err(LaunchError::Substitution(format!(
+            "launch arg defaults must be scalars, got {:?}",
+            other
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn declared(yaml: &str) -> BTreeMap<String, LaunchArg> {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn defaults_and_overrides_resolve() {
+        let args = declared("camera:\n  default: true\nfps:\n  default: 15\n");
+        let ctx = SubstitutionContext::new(&args, &["fps:=30".to_string()]).unwrap();
+        assert_eq!(ctx.arg("camera"), Some("true"));
+        assert_eq!(ctx.arg("fps"), Some("30"));
+    }
+
+    #[test]
+    fn unknown_override_rejected() {
+        let args = declared("camera:\n  default: true\n");
+        let err = SubstitutionContext::new(&args, &["camrea:=false".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("camrea"));
+    }
+
+    #[test]
+    fn malformed_override_rejected() {
+        let args = declared("camera:\n  default: true\n");
+        assert!(SubstitutionContext::new(&args, &["camera=false".to_string()]).is_err());
+        assert!(SubstitutionContext::new(&args, &[":=false".to_string()]).is_err());
+    }
+
+    #[test]
+    fn substitute_expands_inline() {
+        let args = declared("cam:\n  default: entrance\n");
+        let ctx = SubstitutionContext::new(&args, &[]).unwrap();
+        assert_eq!(
+            ctx.substitute("camera/$(arg cam)/compressed").unwrap(),
+            "camera/entrance/compressed"
+        );
+        assert_eq!(
+            ctx.substitute("no substitutions").unwrap(),
+            "no substitutions"
+        );
+    }
+
+    #[test]
+    fn substitute_errors_on_undefined_or_unterminated() {
+        let ctx = SubstitutionContext::default();
+        assert!(ctx.substitute("$(arg missing)").is_err());
+        assert!(ctx.substitute("$(arg cam").is_err());
+        assert!(ctx.substitute("$(find pkg)").is_err());
+    }
+
+    #[test]
+    fn substitute_value_walks_nested_yaml() {
+        let args = declared("host:\n  default: 10.0.0.2\n");
+        let ctx = SubstitutionContext::new(&args, &[]).unwrap();
+        let value: serde_yaml::Value =
+            serde_yaml::from_str("url: rtsp://$(arg host)/s1\nlist: [\"$(arg host)\", 3]\n")
+                .unwrap();
+        let out = ctx.substitute_value(&value).unwrap();
+        assert_eq!(out["url"].as_str(), Some("rtsp://10.0.0.2/s1"));
+        assert_eq!(out["list"][0].as_str(), Some("10.0.0.2"));
+        assert_eq!(out["list"][1].as_u64(), Some(3));
+    }
+
+    #[test]
+    fn eval_bool_accepts_common_spellings() {
+        let args = declared("a:\n  default: \"yes\"\nb:\n  default: 0\n");
+        let ctx = SubstitutionContext::new(&args, &[]).unwrap();
+        assert!(ctx.eval_bool("$(arg a)").unwrap());
+        assert!(!ctx.eval_bool("$(arg b)").unwrap());
+        assert!(ctx.eval_bool("TRUE").unwrap());
+        assert!(!ctx.eval_bool(" off ").unwrap());
+    }
+
+    #[test]
+    fn env_substitution_prefers_process_env_over_file() {
+        let env_file =
+            parse_env_file("# camera\nexport CAM_HOST=\"10.0.0.9\"\nPATH=from-file\n").unwrap();
+        let args = declared("host:\n  default: $(env CAM_HOST)\n");
+        let ctx = SubstitutionContext::with_env_file(&args, &[], env_file).unwrap();
+        assert_eq!(ctx.arg("host"), Some("10.0.0.9"));
+        assert_eq!(
+            ctx.substitute("$(env PATH)").unwrap(),
+            std::env::var("PATH").unwrap()
+        );
+        assert_eq!(
+            ctx.substitute("$(env BUBBALOOP_TEST_UNSET rtsp://cam 1)")
+                .unwrap(),
+            "rtsp://cam 1"
+        );
+        assert!(ctx.substitute("$(env BUBBALOOP_TEST_UNSET)").is_err());
+    }
+
+    #[test]
+    fn env_file_rejects_malformed_lines() {
+        assert!(parse_env_file("NO_EQUALS\n").is_err());
+        assert!(parse_env_file("BAD KEY=1\n").is_err());
+        let vars = parse_env_file("\nA=\nB='x y'\n").unwrap();
+        assert_eq!(vars["A"], "");
+        assert_eq!(vars["B"], "x y");
+    }
+
+    #[test]
+    fn typed_args_validated_and_normalised() {
+        let args = declared(
+            "fps:\n  type: int\n  default: \" 30\"\ngpu:\n  type: bool\n  default: \"yes\"\n\
+             out:\n  type: path\n  default: ~/rec\n",
+        );
+        let ctx = SubstitutionContext::new(&args, &[]).unwrap();
+        assert_eq!(ctx.arg("fps"), Some("30"));
+        assert_eq!(ctx.arg("gpu"), Some("true"));
+        assert!(!ctx.arg("out").unwrap().starts_with('~'));
+
+        let err = SubstitutionContext::new(&args, &["fps:=fast".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("not an integer"), "{err}");
+        assert!(SubstitutionContext::new(&args, &["gpu:=maybe".to_string()]).is_err());
+        assert!(SubstitutionContext::new(&args, &["out:=".to_string()]).is_err());
+    }
+
+    #[test]
+    fn choices_and_required_enforced() {
+        let args = declared(
+            "fps:\n  type: int\n  choices: [15, 30]\n  required: true\n\
+             mode:\n  choices: [fast, accurate]\n  default: fast\n",
+        );
+        let err = SubstitutionContext::new(&args, &[]).unwrap_err();
+        assert!(err.to_string().contains("required"), "{err}");
+        let ctx = SubstitutionContext::new(&args, &["fps:=30".to_string()]).unwrap();
+        assert_eq!(ctx.arg("mode"), Some("fast"));
+
+        let err = SubstitutionContext::new(&args, &["fps:=25".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("one of: 15, 30"), "{err}");
+        let bad =
+            SubstitutionContext::new(&args, &["fps:=15".to_string(), "mode:=slow".to_string()]);
+        assert!(bad.is_err());
+
+        let conflicting = declared("fps:\n  required: true\n  default: 15\n");
+        assert!(SubstitutionContext::new(&conflicting, &[]).is_err());
+    }
+
+    #[test]
+    fn eval_bool_rejects_non_boolean() {
+        let args = declared("mode:\n  default: fast\n");
+        let ctx = SubstitutionContext::new(&args, &[]).unwrap();
+        assert!(ctx.eval_bool("$(arg mode)").is_err());
+    }
+}
@@ -0,0 +1,427 @@
+//! Static checks of a launch file for `bubbaloop launch validate`.
+//!
+//! Everything `launch start` would trip over later is checked up front and
+//! reported together, with line numbers where the file gives one: YAML
+//! syntax and field types, misspelled fields (which serde would otherwise
+//! ignore), `$(arg ...)` references to undeclared args, includes, the
+//! resolved plan (dependencies, cycles, groups, actions) and, for nodes run
+//! on this machine, whether the base node is installed and its program
+//! exists. Nodes run by another machine's daemon are not checked for
+//! installation.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::executor::{node_command, read_manifest};
+use super::plan::LaunchPlan;
+use super::python;
+use super::{resolve_plan, LaunchFile};
+use crate::daemon::registry::find_installed_node;
+
+const FILE_FIELDS: &[&str] = &[
+    "name", "config", "args", "env_file", "nodes", "include", "groups",
+];
+const NODE_FIELDS: &[&str] = &[
+    "name",
+    "node",
+    "config",
+    "depends_on",
+    "if",
+    "unless",
+    "group",
+    "namespace",
+    "env",
+    "autostart",
+    "on_start",
+    "on_exit",
+    "on_crash",
+    "ready",
+    "machine",
+    "start_delay",
+    "start_timeout",
+    "stop_timeout",
+    "interpreter",
+    "venv",
+];
+const ARG_FIELDS: &[&str] = &["default", "description", "type", "choices", "required"];
+const GROUP_FIELDS: &[&str] = &["description", "namespace", "env", "autostart"];
+const INCLUDE_FIELDS: &[&str] = &["file", "prefix", "args"];
+
+/// One problem found in a launch file.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Problem {
+    pub file: PathBuf,
+    /// 1-based line, when the problem can be pinned to one.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.file.display(), line, self.message),
+            None => write!(f, "{}: {}", self.file.display(), self.message),
+        }
+    }
+}
+
+/// Check `file` (and the files it includes) with `overrides` applied.
+///
+/// Returns the resolved plan when the file is usable, and every problem
+/// found either way.
+pub(super) fn validate(
+    file: &str,
+    overrides: &[String],
+    nodes_dir: &Path,
+) -> (Option<LaunchPlan>, Vec<Problem>) {
+    let mut problems = Vec::new();
+    let mut visited = HashSet::new();
+    let Some(launch) = check_file(Path::new(file), &mut visited, &mut problems) else {
+        return (None, problems);
+    };
+    if !launch.is_multi_node() {
+        // Single-instance files have no plan; their config is the node's.
+        return (None, problems);
+    }
+
+    let plan = match resolve_plan(file, overrides, &[]) {
+        Ok((_, _, plan)) => plan,
+        Err(e) => {
+            let message = e.to_string();
+            let content = std::fs::read_to_string(file).unwrap_or_default();
+            let line = quoted_names(&message)
+                .find_map(|name| find_line(&content, 1, |l| is_entry(l, "name", name)));
+            problems.push(Problem {
+                file: PathBuf::from(file),
+                line,
+                message,
+            });
+            return (None, problems);
+        }
+    };
+    check_installed(file, &plan, nodes_dir, &mut problems);
+    (Some(plan), problems)
+}
+
+/// Syntax, types, unknown fields and undeclared args of one file, then of
+/// its includes. Returns the parsed file when it deserializes.
+fn check_file(
+    path: &Path,
+    visited: &mut HashSet<PathBuf>,
+    problems: &mut Vec<Problem>,
+) -> Option<LaunchFile> {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    if !visited.insert(canonical) {
+        return None; // include cycles are reported by the planner
+    }
+    let mut report = |line: Option<usize>, message: String| {
+        problems.push(Problem {
+            file: path.to_path_buf(),
+            line,
+            message,
+        })
+    };
+    let content = match std::fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => {
+            report(None, format!("cannot read: {}", e));
+            return None;
+        }
+    };
+    let value: serde_yaml::Value = match serde_yaml::from_str(&content) {
+        Ok(v) => v,
+        Err(e) => {
+            report(e.location().map(|l| l.line()), e.to_string());
+            return None;
+        }
+    };
+    for (line, message) in unknown_fields(&content, &value) {
+        report(line, message);
+    }
+    for (line, message) in undeclared_args(&content, &value) {
+        report(line, message);
+    }
+    let launch: LaunchFile = match serde_yaml::from_str(&content) {
+        Ok(l) => l,
+        Err(e) => {
+            report(e.location().map(|l| l.line()), e.to_string());
+            return None;
+        }
+    };
+    if launch.name.is_empty() {
+        report(
+            find_line(&content, 1, |l| is_key(l, "name")),
+            "'name' must not be empty".to_string(),
+        );
+    }
+
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    for include in &launch.include {
+        // Substituted paths are only known once the plan is resolved.
+        if !include.file.contains("$(") {
+            check_file(&base_dir.join(&include.file), visited, problems);
+        }
+    }
+    Some(launch)
+}
+
+/// Fields serde would silently ignore, with the line they are on.
+fn unknown_fields(content: &str, value: &serde_yaml::Value) -> Vec<(Option<usize>, String)> {
+    let mut found = Vec::new();
+    let mut check = |map: &serde_yaml::Value, allowed: &[&str], what: &str, from: usize| {
+        let Some(map) = map.as_mapping() else {
+            return;
+        };
+        for key in map.keys().filter_map(|k| k.as_str()) {
+            if !allowed.contains(&key) {
+                found.push((
+                    find_line(content, from, |l| is_key(l, key)),
+                    format!("unknown field '{}' in {}", key, what),
+                ));
+            }
+        }
+    };
+
+    check(value, FILE_FIELDS, "launch file", 1);
+    let section = |key: &str| find_line(content, 1, |l| is_key(l, key)).unwrap_or(1);
+    if let Some(nodes) = value.get("nodes").and_then(|v| v.as_sequence()) {
+        let start = section("nodes");
+        for node in nodes {
+            let name = node.get("name").and_then(|v| v.as_str()).unwrap_or("?");
+            let from = find_line(content, start, |l| is_entry(l, "name", name)).unwrap_or(start);
+            check(node, NODE_FIELDS, &format!("node '{}'", name), from);
+        }
+    }
+    for (key, fields, what) in [
+        ("args", ARG_FIELDS, "arg"),
+        ("groups", GROUP_FIELDS, "group"),
+    ] {
+        if let Some(entries) = value.get(key).and_then(|v| v.as_mapping()) {
+            let start = section(key);
+            for (name, entry) in entries {
+                let name = name.as_str().unwrap_or("?");
+                let from = find_line(content, start, |l| is_key(l, name)).unwrap_or(start);
+                check(entry, fields, &format!("{} '{}'", what, name), from);
+            }
+        }
+    }
+    if let Some(includes) = value.get("include").and_then(|v| v.as_sequence()) {
+        let start = section("include");
+        for include in includes {
+            let file = include.get("file").and_then(|v| v.as_str()).unwrap_or("?");
+            let from = find_line(content, start, |l| is_entry(l, "file", file)).unwrap_or(start);
+            check(
+                include,
+                INCLUDE_FIELDS,
+                &format!("include '{}'", file),
+                from,
+            );
+        }
+    }
+    found
+}
+
+/// `$(arg name)` references to args the file does not declare.
+fn undeclared_args(content: &str, value: &serde_yaml::Value) -> Vec<(Option<usize>, String)> {
+    let declared: HashSet<&str> = value
+        .get("args")
+        .and_then(|v| v.as_mapping())
+        .map(|m| m.keys().filter_map(|k| k.as_str()).collect())
+        .unwrap_or_default();
+    let mut strings = Vec::new();
+    collect_strings(value, &mut strings);
+
+    let mut reported = HashSet::new();
+    let mut found = Vec::new();
+    for s in strings {
+        let mut rest = s;
+        while let Some(start) = rest.find("$(") {
+            let after = &rest[start + 2..];
+            let body = after.split(')').next().unwrap_or_default().trim();
+            if let Some(("arg", name)) = body.split_once(char::is_whitespace) {
+                let name = name.trim();
+                if !declared.contains(name) && reported.insert(name) {
+                    let needle = format!("$(arg {}", name);
+                    found.push((
+                        find_line(content, 1, |l| l.contains(&needle)),
+                        format!(
+                            "'$(arg {})' refers to an arg not declared under args:",
+                            name
+                        ),
+                    ));
+                }
+            }
+            rest = after;
+        }
+    }
+    found
+}
+
+fn collect_strings<'a>(value: &'a serde_yaml::Value, out: &mut Vec<&'a str>) {
+    match value {
+        serde_yaml::Value::String(s) => out.push(s),
+        serde_yaml::Value::Sequence(seq) => seq.iter().for_each(|v| collect_strings(v, out)),
+        serde_yaml::Value::Mapping(map) => map.values().for_each(|v| collect_strings(v, out)),
+        serde_yaml::Value::Tagged(tagged) => collect_strings(&tagged.value, out),
+        _ => {}
+    }
+}
+
+/// Base nodes and programs of the nodes this machine would spawn.
+fn check_installed(file: &str, plan: &LaunchPlan, nodes_dir: &Path, problems: &mut Vec<Problem>) {
+    let machine_id = crate::daemon::util::get_machine_id();
+    let content = std::fs::read_to_string(file).unwrap_or_default();
+    for node in &plan.nodes {
+        if node.machine.as_deref().is_some_and(|m| m != machine_id) {
+            continue;
+        }
+        let message = match find_installed_node(nodes_dir, &node.node) {
+            None => Some(format!(
+                "launch node '{}': base node '{}' is not installed under {}",
+                node.name,
+                node.node,
+                nodes_dir.display()
+            )),
+            Some(node_dir) => match read_manifest(&node_dir).and_then(|manifest| {
+                let (program, args) = node_command(&node_dir, &manifest)?;
+                Ok(python::command(&node_dir, &manifest, node, program, args))
+            }) {
+                Err(e) => Some(format!("launch node '{}': {}", node.name, e)),
+                Ok((program, _, _)) if !program_exists(&node_dir, &program) => Some(format!(
+                    "launch node '{}': program '{}' of base node '{}' not found (is it built?)",
+                    node.name, program, node.node
+                )),
+                Ok(_) => None,
+            },
+        };
+        if let Some(message) = message {
+            problems.push(Problem {
+                file: PathBuf::from(file),
+                line: find_line(&content, 1, |l| is_entry(l, "name", &node.name)),
+                message,
+            });
+        }
+    }
+}
+
+/// Whether `program` resolves like a spawn from `node_dir` would: paths
+/// relative to the node directory, bare names through `PATH`.
+fn program_exists(node_dir: &Path, program: &str) -> bool {
+    if program.contains('/') {
+        return node_dir.join(program).exists();
+    }
+    python::on_path(program)
+}
+
+/// 1-based number of the first line at or after line `from` that matches
+/// `pred`, looked at without indentation and list dash.
+fn find_line(content: &str, from: usize, pred: impl Fn(&str) -> bool) -> Option<usize> {
+    content
+        .lines()
+        .enumerate()
+        .skip(from.saturating_sub(1))
+        .find(|(_, line)| pred(line.trim_start().trim_start_matches("- ").trim_start()))
+        .map(|(i, _)| i + 1)
+}
+
+fn is_key(line: &str, key: &str) -> bool {
+    line.strip_prefix(key)
+        .is_some_and(|rest| rest.trim_start().starts_with(':'))
+}
+
+fn is_entry(line: &str, key: &str, value: &str) -> bool {
+    line.strip_prefix(key)
+        .and_then(|rest| rest.trim_start().strip_prefix(':'))
+        .map(|v| {
+            v.split(" #")
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .trim_matches(['"', '\''])
+        })
+        .is_some_and(|v| v == value)
+}
+
+/// Names quoted as `'name'` in an error message, in order.
+fn quoted_names(message: &str) -> impl Iterator<Item = &str> {
+    message.split('\'').skip(1).step_by(2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) -> String {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn reports_unknown_fields_and_undeclared_args_with_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = write(
+            dir.path(),
+            "l.launch.yaml",
+            "name: l\n\
+             args:\n  fps:\n    default: 30\n\
+             nodes:\n\
+             \x20 - name: cam\n    node: rtsp-camera\n    depend_on: [proxy]\n\
+             \x20   config:\n      fps: \"$(arg fps)\"\n      url: \"$(arg host)\"\n",
+        );
+        let (_, problems) = validate(&file, &[], dir.path());
+        let messages: Vec<String> = problems
+            .iter()
+            .map(|p| format!("{}: {}", p.line.unwrap_or(0), p.message))
+            .collect();
+        assert_eq!(messages[0], "8: unknown field 'depend_on' in node 'cam'");
+        assert_eq!(
+            messages[1],
+            "11: '$(arg host)' refers to an arg not declared under args:"
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors_and_cycles() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = write(dir.path(), "bad.yaml", "name: l\nnodes:\n  - name: [\n");
+        let (_, problems) = validate(&file, &[], dir.path());
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].line.is_some(), "{}", problems[0]);
+
+        let file = write(
+            dir.path(),
+            "cycle.yaml",
+            "name: l\nnodes:\n  - name: a\n    node: x\n    depends_on: [b]\n  \
+             - name: b\n    node: x\n    depends_on: [a]\n",
+        );
+        let (plan, problems) = validate(&file, &[], dir.path());
+        assert!(plan.is_none());
+        assert!(problems[0].message.contains("cycle"), "{}", problems[0]);
+    }
+
+    #[test]
+    fn reports_missing_base_nodes_and_programs() {
+        let dir = tempfile::tempdir().unwrap();
+        let node_dir = dir.path().join("nodes").join("cam");
+        std::fs::create_dir_all(&node_dir).unwrap();
+        std::fs::write(
+            node_dir.join("node.yaml"),
+            "name: cam\nversion: 0.1.0\ntype: rust\ncommand: ./missing-binary\n",
+        )
+        .unwrap();
+        let file = write(
+            dir.path(),
+            "l.yaml",
+            "name: l\nnodes:\n  - name: front\n    node: cam\n  - name: mic\n    node: audio\n",
+        );
+        let (plan, problems) = validate(&file, &[], &dir.path().join("nodes"));
+        assert!(plan.is_some());
+        assert_eq!(problems.len(), 2, "{problems:?}");
+        assert_eq!(problems[0].line, Some(3));
+        assert!(problems[0].message.contains("'./missing-binary'"));
+        assert_eq!(problems[1].line, Some(5));
+        assert!(problems[1].message.contains("'audio' is not installed"));
+    }
+}
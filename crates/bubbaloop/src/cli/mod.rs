@@ -2,26 +2,47 @@
 
 pub mod agent;
 pub mod agent_client;
+pub mod agent_rules;
 pub mod agent_setup;
+pub mod bench;
+pub mod completions;
 pub mod daemon;
 pub mod daemon_client;
 pub mod dataflow;
 pub mod debug;
 pub mod doctor;
+pub mod exit;
 pub mod launch;
 pub mod login;
 pub mod marketplace;
 pub mod node;
+pub mod record;
+pub mod schema;
+pub mod self_update;
+pub mod setup;
+pub mod skill;
 pub mod status;
 pub mod system_utils;
+pub mod target;
+pub mod topic;
+pub mod tui_config;
 pub mod up;
+pub mod up_profile;
 pub mod zenoh_session;
 
 pub use agent::AgentCommand;
+pub use bench::BenchCommand;
+pub use completions::CompletionsCommand;
 pub use daemon::DaemonCommand;
 pub use dataflow::DataflowCommand;
 pub use debug::{DebugCommand, DebugError};
 pub use login::{LoginCommand, LogoutCommand};
 pub use marketplace::MarketplaceCommand;
 pub use node::{NodeCommand, NodeError};
+pub use record::{PlayCommand, RecordCommand};
+pub use schema::SchemaCommand;
+pub use self_update::SelfUpdateCommand;
+pub use setup::SetupCommand;
+pub use skill::SkillCommand;
+pub use topic::TopicCommand;
 pub use up::UpCommand;
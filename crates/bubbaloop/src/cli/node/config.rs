@@ -0,0 +1,422 @@
+//! Node config commands: edit an instance's config file in place.
+//!
+//! The daemon knows which file each instance runs with (`config_path`,
+//! relative to the node directory or absolute). `edit` opens a copy of it
+//! in `$VISUAL` / `$EDITOR`, checks the result is valid YAML and, when the
+//! node ships a `config.schema.json`, that it matches the schema. Only
+//! then is the original replaced, via a rename so the node never reads a
+//! half-written file.
+
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use argh::FromArgs;
+use serde_json::Value as Json;
+
+use super::{NodeError, Result};
+use crate::daemon::gateway::NodeStateJson;
+use crate::schemas::NodeStatus;
+
+/// Schema file looked up in the node directory.
+const SCHEMA_FILE: &str = "config.schema.json";
+
+/// Manage a node instance's config file
+#[derive(FromArgs)]
+#[argh(subcommand, name = "config")]
+pub(crate) struct ConfigArgs {
+    #[argh(subcommand)]
+    action: ConfigAction,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum ConfigAction {
+    Edit(EditArgs),
+}
+
+/// Edit a node's config in $EDITOR, validate it, and optionally restart
+#[derive(FromArgs)]
+#[argh(subcommand, name = "edit")]
+struct EditArgs {
+    /// node name
+    #[argh(positional)]
+    name: String,
+
+    /// restart the node after saving without asking
+    #[argh(switch)]
+    restart: bool,
+
+    /// never restart the node after saving
+    #[argh(switch)]
+    no_restart: bool,
+}
+
+pub(crate) async fn run(args: ConfigArgs) -> Result<()> {
+    match args.action {
+        ConfigAction::Edit(args) => edit(args).await,
+    }
+}
+
+async fn edit(args: EditArgs) -> Result<()> {
+    crate::validation::validate_node_name(&args.name).map_err(NodeError::InvalidArgs)?;
+    if crate::cli::target::is_remote() {
+        return Err(NodeError::InvalidArgs(
+            "config edit works on this machine's files and cannot be used with --machine".into(),
+        ));
+    }
+    if args.restart && args.no_restart {
+        return Err(NodeError::InvalidArgs(
+            "Cannot use --restart and --no-restart together".into(),
+        ));
+    }
+
+    let client = crate::cli::daemon_client::DaemonClient::connect().await?;
+    let node = client
+        .node_states()
+        .await?
+        .nodes
+        .into_iter()
+        .find(|n| n.name == args.name)
+        .ok_or_else(|| NodeError::NotFound(args.name.clone()))?;
+    let config_path = config_file(&node)?;
+    let original = std::fs::read_to_string(&config_path)?;
+    let schema = load_schema(Path::new(&node.path))?;
+
+    let scratch = std::env::temp_dir().join(format!(
+        "bubbaloop-{}-{}.yaml",
+        node.name,
+        std::process::id()
+    ));
+    std::fs::write(&scratch, &original)?;
+    let edited = edit_until_valid(&scratch, schema.as_ref());
+    let _ = std::fs::remove_file(&scratch);
+    let Some(edited) = edited? else {
+        println!("Discarded changes to {}", config_path.display());
+        return Ok(());
+    };
+
+    if edited == original {
+        println!("No changes to {}", config_path.display());
+        return Ok(());
+    }
+    write_atomically(&config_path, &edited)?;
+    println!("Saved {}", config_path.display());
+
+    if node.status != NodeStatus::Running as i32 || args.no_restart {
+        return Ok(());
+    }
+    let restart = args.restart
+        || (io::stdin().is_terminal()
+            && confirm(&format!("Restart {} now to apply it? [Y/n] ", node.name))?);
+    if restart {
+        super::lifecycle::restart_node(&node.name).await
+    } else {
+        println!(
+            "Run 'bubbaloop node restart {}' to apply the new config.",
+            node.name
+        );
+        Ok(())
+    }
+}
+
+/// The file an instance runs with, falling back to `config.yaml` in the
+/// node directory for nodes registered without an explicit config.
+fn config_file(node: &NodeStateJson) -> Result<PathBuf> {
+    let dir = Path::new(&node.path);
+    if !node.config_path.is_empty() {
+        // `join` keeps absolute config paths as they are.
+        return Ok(dir.join(&node.config_path));
+    }
+    let default = dir.join("config.yaml");
+    if default.is_file() {
+        return Ok(default);
+    }
+    Err(NodeError::CommandFailed(format!(
+        "Node '{}' has no config file (register it with --config to give it one)",
+        node.name
+    )))
+}
+
+fn load_schema(node_dir: &Path) -> Result<Option<Json>> {
+    let path = node_dir.join(SCHEMA_FILE);
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)?;
+    Ok(Some(serde_json::from_str(&content)?))
+}
+
+/// Open the editor until the file validates. Returns `None` when the user
+/// gives up on an invalid file.
+fn edit_until_valid(path: &Path, schema: Option<&Json>) -> Result<Option<String>> {
+    loop {
+        open_editor(path)?;
+        let content = std::fs::read_to_string(path)?;
+        let problems = validate(&content, schema);
+        if problems.is_empty() {
+            return Ok(Some(content));
+        }
+        eprintln!("The config is not valid:");
+        for problem in &problems {
+            eprintln!("  - {}", problem);
+        }
+        if !io::stdin().is_terminal() || !confirm("Edit again? [Y/n] ")? {
+            return Ok(None);
+        }
+    }
+}
+
+fn open_editor(path: &Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // Through the shell so editors configured with flags ("code --wait") work.
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(path)
+        .status()?;
+    if !status.success() {
+        return Err(NodeError::CommandFailed(format!(
+            "Editor '{}' exited with {}",
+            editor, status
+        )));
+    }
+    Ok(())
+}
+
+fn confirm(question: &str) -> Result<bool> {
+    print!("{}", question);
+    io::stdout().flush()?;
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+    let answer = line.trim();
+    Ok(answer.is_empty() || answer.eq_ignore_ascii_case("y"))
+}
+
+/// Write to a sibling file, then rename over the original.
+fn write_atomically(path: &Path, content: &str) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| NodeError::CommandFailed(format!("Not a file: {}", path.display())))?;
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(file_name);
+    tmp_name.push(".tmp");
+    let tmp = path.with_file_name(tmp_name);
+    std::fs::write(&tmp, content)?;
+    if let Ok(metadata) = std::fs::metadata(path) {
+        std::fs::set_permissions(&tmp, metadata.permissions())?;
+    }
+    std::fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })?;
+    Ok(())
+}
+
+/// Problems with an edited config, empty when it is valid.
+fn validate(content: &str, schema: Option<&Json>) -> Vec<String> {
+    let value: Json = match serde_yaml::from_str(content) {
+        Ok(value) => value,
+        Err(e) => return vec![format!("invalid YAML: {}", e)],
+    };
+    let mut problems = Vec::new();
+    match schema {
+        Some(schema) => check_schema(&value, schema, "config", &mut problems),
+        None if !value.is_object() => problems.push("config must be a YAML mapping".into()),
+        None => {}
+    }
+    problems
+}
+
+/// The subset of JSON Schema node configs need: `type`, `enum`,
+/// `minimum` / `maximum`, `required`, `properties`,
+/// `additionalProperties: false` and `items`.
+fn check_schema(value: &Json, schema: &Json, at: &str, problems: &mut Vec<String>) {
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Json::String(t) => vec![t.as_str()],
+            Json::Array(ts) => ts.iter().filter_map(Json::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| has_type(value, t)) {
+            problems.push(format!(
+                "{}: expected {}, got {}",
+                at,
+                types.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Json::as_array) {
+        if !allowed.contains(value) {
+            let allowed: Vec<String> = allowed.iter().map(Json::to_string).collect();
+            problems.push(format!("{}: must be one of {}", at, allowed.join(", ")));
+        }
+    }
+    if let Some(n) = value.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(Json::as_f64) {
+            if n < min {
+                problems.push(format!("{}: {} is below the minimum {}", at, n, min));
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(Json::as_f64) {
+            if n > max {
+                problems.push(format!("{}: {} is above the maximum {}", at, n, max));
+            }
+        }
+    }
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(Json::as_object);
+        for required in schema
+            .get("required")
+            .and_then(Json::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Json::as_str)
+        {
+            if !object.contains_key(required) {
+                problems.push(format!("{}: missing required field '{}'", at, required));
+            }
+        }
+        let closed = schema.get("additionalProperties") == Some(&Json::Bool(false));
+        for (key, field) in object {
+            let path = format!("{}.{}", at, key);
+            match properties.and_then(|p| p.get(key)) {
+                Some(field_schema) => check_schema(field, field_schema, &path, problems),
+                None if closed => problems.push(format!("{}: unknown field", path)),
+                None => {}
+            }
+        }
+    }
+    if let (Some(items), Some(item_schema)) = (value.as_array(), schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            check_schema(item, item_schema, &format!("{}[{}]", at, i), problems);
+        }
+    }
+}
+
+fn has_type(value: &Json, name: &str) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Json) -> &'static str {
+    match value {
+        Json::Null => "null",
+        Json::Bool(_) => "boolean",
+        Json::Number(n) if n.is_f64() => "number",
+        Json::Number(_) => "integer",
+        Json::String(_) => "string",
+        Json::Array(_) => "array",
+        Json::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn node(path: &str, config_path: &str) -> NodeStateJson {
+        NodeStateJson {
+            name: "rtsp-camera-terrace".into(),
+            path: path.into(),
+            status: NodeStatus::Running as i32,
+            installed: true,
+            autostart_enabled: false,
+            version: "0.1.0".into(),
+            description: String::new(),
+            node_type: "rust".into(),
+            is_built: true,
+            last_updated_ms: 0,
+            build_output: Vec::new(),
+            health_status: 0,
+            last_health_check_ms: 0,
+            machine_id: String::new(),
+            machine_hostname: String::new(),
+            machine_ips: Vec::new(),
+            base_node: "rtsp-camera".into(),
+            config_path: config_path.into(),
+            config: String::new(),
+        }
+    }
+
+    #[test]
+    fn config_file_resolves_relative_absolute_and_default_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+
+        assert_eq!(
+            config_file(&node(path, "configs/terrace.yaml")).unwrap(),
+            dir.path().join("configs/terrace.yaml")
+        );
+        assert_eq!(
+            config_file(&node(path, "/etc/terrace.yaml")).unwrap(),
+            PathBuf::from("/etc/terrace.yaml")
+        );
+        assert!(config_file(&node(path, "")).is_err());
+        std::fs::write(dir.path().join("config.yaml"), "a: 1\n").unwrap();
+        assert_eq!(
+            config_file(&node(path, "")).unwrap(),
+            dir.path().join("config.yaml")
+        );
+    }
+
+    #[test]
+    fn validate_reports_yaml_and_schema_problems() {
+        assert!(validate("url: rtsp://cam\nfps: 30\n", None).is_empty());
+        assert!(validate("url: [unclosed\n", None)[0].starts_with("invalid YAML"));
+        assert_eq!(
+            validate("- a\n- b\n", None),
+            vec!["config must be a YAML mapping"]
+        );
+
+        let schema = json!({
+            "type": "object",
+            "required": ["url"],
+            "additionalProperties": false,
+            "properties": {
+                "url": {"type": "string"},
+                "fps": {"type": "integer", "minimum": 1, "maximum": 60},
+                "codec": {"enum": ["h264", "h265"]},
+                "topics": {"type": "array", "items": {"type": "string"}}
+            }
+        });
+        assert!(validate("url: rtsp://cam\nfps: 30\ntopics: [a]\n", Some(&schema)).is_empty());
+        assert_eq!(
+            validate(
+                "fps: 90\ncodec: vp9\ntopics: [a, 2]\nextra: true\n",
+                Some(&schema)
+            ),
+            vec![
+                "config: missing required field 'url'",
+                "config.codec: must be one of \"h264\", \"h265\"",
+                "config.extra: unknown field",
+                "config.fps: 90 is above the maximum 60",
+                "config.topics[1]: expected string, got integer",
+            ]
+        );
+    }
+
+    #[test]
+    fn write_atomically_replaces_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        std::fs::write(&path, "old: 1\n").unwrap();
+        write_atomically(&path, "new: 2\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new: 2\n");
+        let entries = std::fs::read_dir(dir.path()).unwrap().count();
+        assert_eq!(entries, 1, "no temporary file left behind");
+    }
+}
@@ -0,0 +1,238 @@
+//! Node diff command: compare an installed node with the marketplace.
+//!
+//! The version comes from the daemon's node state and the latest one from
+//! the marketplace registry. When the node's git checkout is on this
+//! machine, its upstream is fetched and the commits not yet pulled are
+//! listed as the changelog.
+
+use std::path::Path;
+use std::process::Command;
+
+use serde::Serialize;
+
+use super::{NodeError, Result};
+use crate::cli::daemon_client::DaemonClient;
+use crate::daemon::gateway::NodeStateJson;
+use crate::registry::{self, RegistryNode};
+
+/// Most commits listed in the changelog.
+const MAX_COMMITS: usize = 20;
+
+#[derive(Debug, Serialize)]
+struct NodeDiff {
+    name: String,
+    installed_version: String,
+    installed_commit: Option<String>,
+    marketplace_version: Option<String>,
+    repo: Option<String>,
+    upstream_commit: Option<String>,
+    /// Commits of the node's directory between HEAD and its upstream.
+    commits: Vec<String>,
+    update_available: bool,
+}
+
+pub(crate) async fn diff_node(name: &str, fetch: bool, json: bool) -> Result<()> {
+    crate::validation::validate_node_name(name).map_err(NodeError::InvalidArgs)?;
+    let client = DaemonClient::connect().await?;
+    let node = client
+        .node_states()
+        .await?
+        .nodes
+        .into_iter()
+        .find(|n| n.name == name)
+        .ok_or_else(|| NodeError::NotFound(name.to_string()))?;
+
+    if let Err(e) = registry::refresh_cache() {
+        log::warn!("registry refresh failed: {}", e);
+        eprintln!("Warning: could not refresh registry (using cache): {}", e);
+    }
+    let entry = marketplace_entry(&registry::load_cached_registry(), &node);
+
+    // The checkout is only reachable when the daemon runs on this machine.
+    let checkout = Path::new(&node.path);
+    let git = !crate::cli::target::is_remote()
+        && git_output(checkout, &["rev-parse", "--git-dir"]).is_some();
+    let installed_commit = git
+        .then(|| git_output(checkout, &["rev-parse", "--short", "HEAD"]))
+        .flatten();
+    if git && fetch {
+        if let Err(e) = git_fetch(checkout) {
+            eprintln!("Warning: could not fetch {}: {}", node.path, e);
+        }
+    }
+    let upstream_commit = git
+        .then(|| git_output(checkout, &["rev-parse", "--short", "@{u}"]))
+        .flatten();
+    let commits = match upstream_commit {
+        Some(_) => git_output(
+            checkout,
+            &[
+                "log",
+                "--oneline",
+                &format!("--max-count={}", MAX_COMMITS),
+                "HEAD..@{u}",
+                "--",
+                ".",
+            ],
+        )
+        .map(|log| log.lines().map(str::to_string).collect())
+        .unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    let diff = NodeDiff {
+        update_available: !commits.is_empty()
+            || entry
+                .as_ref()
+                .is_some_and(|e| is_newer(&e.version, &node.version)),
+        name: node.name,
+        installed_version: node.version,
+        installed_commit,
+        marketplace_version: entry.as_ref().map(|e| e.version.clone()),
+        repo: entry.map(|e| e.repo),
+        upstream_commit,
+        commits,
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&diff)?);
+    } else {
+        print_diff(&diff);
+    }
+    Ok(())
+}
+
+fn print_diff(diff: &NodeDiff) {
+    let commit = |c: &Option<String>| c.as_ref().map(|c| format!(" ({})", c)).unwrap_or_default();
+    println!("{}", diff.name);
+    println!(
+        "  installed:    v{}{}",
+        diff.installed_version,
+        commit(&diff.installed_commit)
+    );
+    match (&diff.marketplace_version, &diff.repo) {
+        (Some(version), Some(repo)) => println!("  marketplace:  v{} ({})", version, repo),
+        _ => println!("  marketplace:  not listed"),
+    }
+    if let Some(upstream) = &diff.upstream_commit {
+        println!(
+            "  upstream:     {}, {} new commit(s)",
+            upstream,
+            diff.commits.len()
+        );
+        for line in &diff.commits {
+            println!("    {}", line);
+        }
+    }
+    println!();
+    if diff.update_available {
+        println!("Update available: bubbaloop node update {}", diff.name);
+    } else {
+        println!("Up to date.");
+    }
+}
+
+/// The marketplace entry of `node`; instances use their base node's entry.
+pub(super) fn marketplace_entry(
+    registry: &[RegistryNode],
+    node: &NodeStateJson,
+) -> Option<RegistryNode> {
+    let name = if node.base_node.is_empty() {
+        &node.name
+    } else {
+        &node.base_node
+    };
+    registry::find_by_name(registry, name)
+}
+
+/// Whether `latest` is a newer version than `installed`, comparing the
+/// numeric parts of dotted versions (`1.10.0` > `1.9.2`). Versions that do
+/// not parse only count as newer when they differ.
+pub(crate) fn is_newer(latest: &str, installed: &str) -> bool {
+    fn parts(version: &str) -> Option<Vec<u64>> {
+        let version = version.trim().trim_start_matches('v');
+        let release = version.split(['-', '+']).next()?;
+        release.split('.').map(|p| p.parse().ok()).collect()
+    }
+    match (parts(latest), parts(installed)) {
+        (Some(latest), Some(installed)) => latest > installed,
+        _ => !latest.is_empty() && latest.trim() != installed.trim(),
+    }
+}
+
+fn git_output(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn git_fetch(dir: &Path) -> std::result::Result<(), String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["fetch", "--quiet"])
+        .output()
+        .map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_compare_numerically() {
+        assert!(is_newer("0.2.0", "0.1.9"));
+        assert!(is_newer("1.10.0", "1.9.2"));
+        assert!(is_newer("v1.1", "1.0.5"));
+        assert!(!is_newer("1.0.0", "1.0.0"));
+        assert!(!is_newer("1.0.0-rc1", "1.0.0"));
+        assert!(!is_newer("0.9.0", "1.0.0"));
+        assert!(is_newer("nightly-2", "nightly-1"));
+        assert!(!is_newer("", "0.1.0"));
+    }
+
+    #[test]
+    fn instances_use_their_base_node_entry() {
+        let registry = registry::parse_nodes_yaml(
+            "nodes:\n  - name: rtsp-camera\n    version: 0.2.0\n    type: rust\n    repo: kornia/bubbaloop-nodes-official\n    subdir: rtsp-camera\n",
+        );
+        let state = |name: &str, base_node: &str| NodeStateJson {
+            name: name.into(),
+            path: "/home/u/.bubbaloop/nodes/bubbaloop-nodes-official/rtsp-camera".into(),
+            status: 0,
+            installed: true,
+            autostart_enabled: false,
+            version: "0.1.0".into(),
+            description: String::new(),
+            node_type: "rust".into(),
+            is_built: true,
+            last_updated_ms: 0,
+            build_output: Vec::new(),
+            health_status: 0,
+            last_health_check_ms: 0,
+            machine_id: String::new(),
+            machine_hostname: String::new(),
+            machine_ips: Vec::new(),
+            base_node: base_node.into(),
+            config_path: String::new(),
+            config: String::new(),
+        };
+
+        let entry = marketplace_entry(&registry, &state("rtsp-camera-terrace", "rtsp-camera"));
+        assert_eq!(entry.map(|e| e.version).as_deref(), Some("0.2.0"));
+        assert!(marketplace_entry(&registry, &state("rtsp-camera", "")).is_some());
+        assert!(marketplace_entry(&registry, &state("my-sensor", "")).is_none());
+    }
+}
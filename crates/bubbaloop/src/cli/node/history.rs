@@ -0,0 +1,247 @@
+//! `node history` — a node's status and health transitions as a timeline.
+//!
+//! The daemon records every change in memory, so the history only reaches
+//! back to the last daemon start.
+
+use super::lifecycle::parse_since;
+use super::{HistoryArgs, NodeError, Result};
+use crate::cli::daemon_client::DaemonClient;
+use crate::daemon::gateway::{HealthHistory, HealthTransition};
+use crate::schemas::daemon::v1::{HealthStatus, NodeStatus};
+
+/// Width of the timeline strip in characters.
+const TIMELINE_WIDTH: usize = 60;
+
+/// One timeline cell per kind of state, from best to worst. A cell shows
+/// the worst state the node was in during that slice of time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Cell {
+    NoData,
+    Healthy,
+    Running,
+    Stopped,
+    Building,
+    Failed,
+}
+
+impl Cell {
+    fn of(t: &HealthTransition) -> Self {
+        let health = HealthStatus::try_from(t.health_status).unwrap_or_default();
+        match NodeStatus::try_from(t.status).unwrap_or_default() {
+            NodeStatus::Failed => Cell::Failed,
+            _ if health == HealthStatus::Unhealthy => Cell::Failed,
+            NodeStatus::Building | NodeStatus::Installing => Cell::Building,
+            NodeStatus::Running if health == HealthStatus::Healthy => Cell::Healthy,
+            NodeStatus::Running => Cell::Running,
+            _ => Cell::Stopped,
+        }
+    }
+
+    fn glyph(self) -> char {
+        match self {
+            Cell::NoData => ' ',
+            Cell::Healthy => '#',
+            Cell::Running => '=',
+            Cell::Stopped => '.',
+            Cell::Building => '~',
+            Cell::Failed => 'x',
+        }
+    }
+}
+
+/// `NODE_STATUS_NOT_INSTALLED` -> `not-installed`
+fn status_name(status: i32) -> String {
+    let status = NodeStatus::try_from(status).unwrap_or_default();
+    enum_name(status.as_str_name(), "NODE_STATUS_")
+}
+
+fn health_name(health: i32) -> String {
+    let health = HealthStatus::try_from(health).unwrap_or_default();
+    enum_name(health.as_str_name(), "HEALTH_STATUS_")
+}
+
+fn enum_name(name: &str, prefix: &str) -> String {
+    name.trim_start_matches(prefix)
+        .to_lowercase()
+        .replace('_', "-")
+}
+
+/// Timeline cells covering `from_ms..now_ms`.
+fn timeline(history: &HealthHistory, from_ms: i64, now_ms: i64, width: usize) -> Vec<Cell> {
+    let span = (now_ms - from_ms).max(1) as f64;
+    let at = |i: usize| from_ms + (span * i as f64 / width as f64) as i64;
+    (0..width)
+        .map(|i| {
+            let (start, end) = (at(i), at(i + 1));
+            // State at the start of the slice, then every change within it.
+            let before = history
+                .transitions
+                .iter()
+                .take_while(|t| t.timestamp_ms <= start)
+                .last();
+            let within = history
+                .transitions
+                .iter()
+                .filter(|t| t.timestamp_ms > start && t.timestamp_ms < end);
+            before
+                .into_iter()
+                .chain(within)
+                .map(Cell::of)
+                .max()
+                .unwrap_or(Cell::NoData)
+        })
+        .collect()
+}
+
+/// Times the node went into a failed or unhealthy state after `from_ms`.
+fn failures(history: &HealthHistory, from_ms: i64) -> usize {
+    let mut previous = Cell::NoData;
+    let mut count = 0;
+    for t in &history.transitions {
+        let cell = Cell::of(t);
+        if cell == Cell::Failed && previous != Cell::Failed && t.timestamp_ms > from_ms {
+            count += 1;
+        }
+        previous = cell;
+    }
+    count
+}
+
+fn local_time(ms: i64, format: &str) -> String {
+    chrono::DateTime::from_timestamp_millis(ms)
+        .map(|t| t.with_timezone(&chrono::Local).format(format).to_string())
+        .unwrap_or_default()
+}
+
+#[derive(serde::Serialize)]
+struct HistoryEntry {
+    timestamp_ms: i64,
+    status: String,
+    health: String,
+}
+
+/// `node history --json` output.
+#[derive(serde::Serialize)]
+struct HistoryResponse<'a> {
+    name: &'a str,
+    /// When the daemon started recording.
+    recorded_since_ms: i64,
+    transitions: Vec<HistoryEntry>,
+}
+
+pub(crate) async fn show_history(args: HistoryArgs) -> Result<()> {
+    crate::validation::validate_node_name(&args.name).map_err(NodeError::InvalidArgs)?;
+    let now_ms = crate::daemon::util::now_ms();
+    let from_ms = parse_since(&args.since, now_ms)?;
+
+    let client = DaemonClient::connect().await?;
+    let history = client.health_history(&args.name).await?;
+    let in_window: Vec<_> = history
+        .transitions
+        .iter()
+        .filter(|t| t.timestamp_ms > from_ms)
+        .collect();
+
+    if args.json {
+        let response = HistoryResponse {
+            name: &args.name,
+            recorded_since_ms: history.since_ms,
+            transitions: in_window
+                .iter()
+                .map(|t| HistoryEntry {
+                    timestamp_ms: t.timestamp_ms,
+                    status: status_name(t.status),
+                    health: health_name(t.health_status),
+                })
+                .collect(),
+        };
+        println!("{}", serde_json::to_string_pretty(&response)?);
+        return Ok(());
+    }
+
+    let cells = timeline(&history, from_ms, now_ms, TIMELINE_WIDTH);
+    let strip: String = cells.iter().map(|c| c.glyph()).collect();
+    let failures = failures(&history, from_ms);
+
+    println!(
+        "{} since {} (recorded since {})",
+        args.name,
+        local_time(from_ms, "%Y-%m-%d %H:%M"),
+        local_time(history.since_ms, "%Y-%m-%d %H:%M")
+    );
+    println!();
+    println!("  |{}|", strip);
+    println!(
+        "  {:<width$}now",
+        local_time(from_ms, "%H:%M"),
+        width = TIMELINE_WIDTH - 1
+    );
+    println!("  # healthy  = running  . stopped  ~ building  x failed/unhealthy");
+    println!();
+    println!("{} transition(s), {} failure(s)", in_window.len(), failures);
+    if in_window.is_empty() {
+        return Ok(());
+    }
+    println!();
+    println!("{:<21}{:<15}HEALTH", "TIME", "STATUS");
+    for t in in_window {
+        println!(
+            "{:<21}{:<15}{}",
+            local_time(t.timestamp_ms, "%Y-%m-%d %H:%M:%S"),
+            status_name(t.status),
+            health_name(t.health_status)
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transition(at: i64, status: NodeStatus, health: HealthStatus) -> HealthTransition {
+        HealthTransition {
+            timestamp_ms: at,
+            status: status as i32,
+            health_status: health as i32,
+        }
+    }
+
+    #[test]
+    fn names_match_node_list() {
+        assert_eq!(
+            status_name(NodeStatus::NotInstalled as i32),
+            "not-installed"
+        );
+        assert_eq!(status_name(NodeStatus::Running as i32), "running");
+        assert_eq!(health_name(HealthStatus::Unhealthy as i32), "unhealthy");
+    }
+
+    #[test]
+    fn timeline_shows_worst_state_per_cell() {
+        let history = HealthHistory {
+            since_ms: 100,
+            transitions: vec![
+                transition(100, NodeStatus::Running, HealthStatus::Healthy),
+                transition(450, NodeStatus::Failed, HealthStatus::Healthy),
+                transition(460, NodeStatus::Running, HealthStatus::Healthy),
+                transition(800, NodeStatus::Stopped, HealthStatus::Unknown),
+            ],
+        };
+        let strip: String = timeline(&history, 0, 1_000, 10)
+            .iter()
+            .map(|c| c.glyph())
+            .collect();
+        assert_eq!(strip, " ###x###..");
+        assert_eq!(failures(&history, 0), 1);
+        assert_eq!(failures(&history, 450), 0);
+    }
+
+    #[test]
+    fn unhealthy_counts_as_failed() {
+        let t = transition(0, NodeStatus::Running, HealthStatus::Unhealthy);
+        assert_eq!(Cell::of(&t), Cell::Failed);
+        let t = transition(0, NodeStatus::Installing, HealthStatus::Unknown);
+        assert_eq!(Cell::of(&t), Cell::Building);
+    }
+}
@@ -91,14 +91,9 @@ pub(crate) fn clone_from_github(url: &str, output: Option<&str>, branch: &str) -
         .ok_or_else(|| NodeError::InvalidUrl(url.to_string()))?;
 
     // Determine target directory
-    let target_dir = if let Some(out) = output {
-        PathBuf::from(out)
-    } else {
-        let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
-        PathBuf::from(home)
-            .join(".bubbaloop")
-            .join("nodes")
-            .join(repo_name)
+    let target_dir = match output {
+        Some(out) => PathBuf::from(out),
+        None => default_clone_dir(repo_name),
     };
 
     if target_dir.exists() {
@@ -148,6 +143,32 @@ pub(crate) fn clone_from_github(url: &str, output: Option<&str>, branch: &str) -
     Ok(target_dir.to_string_lossy().to_string())
 }
 
+/// Where repositories are cloned by default: `~/.bubbaloop/nodes/<repo-name>`.
+fn default_clone_dir(repo_name: &str) -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| "/tmp".to_string());
+    PathBuf::from(home)
+        .join(".bubbaloop")
+        .join("nodes")
+        .join(repo_name)
+}
+
+/// The earlier clone (or precompiled download) of a registry node's repo,
+/// for installing without network access.
+fn offline_source(entry: &registry::RegistryNode) -> Result<String> {
+    let repo_name = entry.repo.rsplit('/').next().unwrap_or(&entry.repo);
+    let dir = default_clone_dir(repo_name);
+    if !dir.join(&entry.subdir).exists() {
+        return Err(NodeError::CommandFailed(format!(
+            "'{}' is not available offline: {} has not been cloned to {}. Install it once while online.",
+            entry.name,
+            entry.repo,
+            dir.display()
+        )));
+    }
+    println!("Offline: using existing clone at {}", dir.display());
+    Ok(dir.to_string_lossy().to_string())
+}
+
 /// Attempt to download a precompiled binary for a registry node.
 ///
 /// Returns the node directory path on success. On failure, the caller should
@@ -198,11 +219,12 @@ pub(crate) async fn handle_install(args: InstallArgs) -> Result<()> {
         args.name
     );
 
-    if let Err(e) = registry::refresh_cache() {
-        log::warn!("registry refresh failed: {}", e);
-        eprintln!("Warning: could not refresh registry (using cache): {}", e);
+    let offline = args.offline || registry::offline_from_env();
+    let loaded = registry::load_registry(offline);
+    if let Some(warning) = loaded.warning() {
+        eprintln!("Warning: {}", warning);
     }
-    let nodes = registry::load_cached_registry();
+    let nodes = loaded.nodes;
 
     let entry = match registry::find_by_name(&nodes, &args.name) {
         Some(entry) => entry,
@@ -233,6 +255,28 @@ pub(crate) async fn handle_install(args: InstallArgs) -> Result<()> {
     registry::validate_repo(&entry.repo)
         .map_err(|e| NodeError::InvalidUrl(format!("Invalid registry repo: {}", e)))?;
 
+    // Offline: register the earlier clone or download as-is.
+    if offline {
+        let base_path = offline_source(&entry)?;
+        let node_path = super::resolve_node_path(&base_path, Some(&entry.subdir))?;
+        copy_canonical_header_proto(Path::new(&node_path));
+        client.add_node(&node_path, None, None).await?;
+        println!("Registered node: {}", args.name);
+        if args.build {
+            println!("Building {}...", args.name);
+            send_command(&args.name, "build").await?;
+        }
+        println!("Installing {} as systemd service...", args.name);
+        send_command(&args.name, "install").await?;
+        log::info!(
+            "node install: completed offline install of '{}' from {}",
+            args.name,
+            base_path
+        );
+        println!("\nInstalled '{}' from {} (offline)", args.name, base_path);
+        return Ok(());
+    }
+
     // Try precompiled binary first (fast path)
     match try_download_precompiled(&entry) {
         Ok(node_path) => {
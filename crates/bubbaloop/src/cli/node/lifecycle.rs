@@ -1,8 +1,13 @@
 //! Node lifecycle commands: start, stop, restart, logs.
 
-use std::process::Command;
+use std::time::Duration;
 
-use super::{send_command, LogsArgs, NodeError, Result};
+use super::{send_command, LogsArgs, LogsResponse, NodeError, Result};
+use crate::cli::daemon_client::{DaemonClient, DaemonClientError};
+use crate::daemon::gateway::{LogChunk, LogLevel, LogLine, LogQuery};
+
+/// How often `node logs --follow` asks the daemon for new lines.
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(1);
 
 pub(crate) async fn start_node(name: &str) -> Result<()> {
     crate::validation::validate_node_name(name).map_err(NodeError::InvalidArgs)?;
@@ -21,29 +26,168 @@ pub(crate) async fn restart_node(name: &str) -> Result<()> {
 
 pub(crate) async fn view_logs(args: LogsArgs) -> Result<()> {
     crate::validation::validate_node_name(&args.name).map_err(NodeError::InvalidArgs)?;
-    if args.follow {
-        // Follow mode is only available for the systemd backend.
-        let service = format!("bubbaloop-{}.service", args.name);
-        let status = Command::new("journalctl")
-            .args(["--user", "-u", &service, "-f", "--no-pager"])
-            .status()?;
-
-        if !status.success() {
-            return Err(NodeError::CommandFailed(format!(
-                "journalctl failed for service {}. This command only works when the node is installed as a systemd service.",
-                service
-            )));
-        }
+    if args.follow && args.json {
+        return Err(NodeError::InvalidArgs(
+            "Cannot use --follow and --json together".into(),
+        ));
+    }
+    let mut query = log_query(&args, crate::daemon::util::now_ms())?;
+
+    // The daemon serves the logs, so this works for --machine as well.
+    let client = DaemonClient::connect().await?;
+    let chunk = client.logs(&args.name, &query).await?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&logs_response(&chunk))?);
         return Ok(());
     }
+    print_lines(&chunk.lines);
+    if !args.follow {
+        return Ok(());
+    }
+
+    let mut cursor = chunk.cursor;
+    loop {
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => return Ok(()),
+            _ = tokio::time::sleep(FOLLOW_INTERVAL) => {}
+        }
+        query.after = (!cursor.is_empty()).then(|| cursor.clone());
+        match client.logs(&args.name, &query).await {
+            Ok(chunk) => {
+                print_lines(&chunk.lines);
+                if !chunk.cursor.is_empty() {
+                    cursor = chunk.cursor;
+                }
+            }
+            // Keep following across daemon restarts and network hiccups.
+            Err(DaemonClientError::NotReachable | DaemonClientError::Timeout) => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// The daemon query for the filters of `args`, with `--since` resolved
+/// against `now_ms`.
+fn log_query(args: &LogsArgs, now_ms: i64) -> Result<LogQuery> {
+    let level = args
+        .level
+        .as_deref()
+        .map(str::parse::<LogLevel>)
+        .transpose()
+        .map_err(NodeError::InvalidArgs)?;
+    if let Some(grep) = &args.grep {
+        regex::Regex::new(grep)
+            .map_err(|e| NodeError::InvalidArgs(format!("invalid --grep regex: {}", e)))?;
+    }
+    let since_ms = args
+        .since
+        .as_deref()
+        .map(|since| parse_since(since, now_ms))
+        .transpose()?;
+    Ok(LogQuery {
+        lines: args.lines,
+        since_ms,
+        level,
+        grep: args.grep.clone(),
+        after: None,
+    })
+}
+
+/// `90s`, `10m`, `1h`, `2d` ago, or an RFC 3339 time, to milliseconds since epoch.
+pub(super) fn parse_since(since: &str, now_ms: i64) -> Result<i64> {
+    if let Ok(time) = chrono::DateTime::parse_from_rfc3339(since) {
+        return Ok(time.timestamp_millis());
+    }
+    let (number, unit) = since.split_at(since.len().saturating_sub(1));
+    let unit_ms = match unit {
+        "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        "d" => 86_400_000,
+        _ => 0,
+    };
+    match number.parse::<i64>() {
+        Ok(n) if n > 0 && unit_ms > 0 => Ok(now_ms - n * unit_ms),
+        _ => Err(NodeError::InvalidArgs(format!(
+            "invalid --since '{}' (expected e.g. 10m, 1h, 2d or 2026-01-01T08:00:00Z)",
+            since
+        ))),
+    }
+}
+
+fn print_lines(lines: &[LogLine]) {
+    for line in lines {
+        println!("{}", line.text);
+    }
+}
 
-    // Use REST API for non-follow mode
-    super::send_command(&args.name, "logs").await
+fn logs_response(chunk: &LogChunk) -> LogsResponse {
+    LogsResponse {
+        lines: chunk.lines.iter().map(|l| l.text.clone()).collect(),
+        success: true,
+        error: None,
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::super::LogsResponse;
+    use super::super::{LogsArgs, LogsResponse};
+    use super::*;
+
+    #[test]
+    fn logs_response_lists_the_line_texts() {
+        let chunk = LogChunk {
+            lines: ["line1", "line2"]
+                .into_iter()
+                .map(|text| LogLine {
+                    timestamp_ms: 0,
+                    level: LogLevel::Info,
+                    text: text.to_string(),
+                })
+                .collect(),
+            cursor: "native:12:0".into(),
+        };
+        let response = logs_response(&chunk);
+        assert_eq!(response.lines, ["line1", "line2"]);
+        assert!(logs_response(&LogChunk::default()).lines.is_empty());
+        let json = serde_json::to_string(&response).unwrap();
+        assert_eq!(json, r#"{"lines":["line1","line2"],"success":true}"#);
+    }
+
+    #[test]
+    fn log_query_resolves_filters() {
+        let args = LogsArgs {
+            name: "camera".into(),
+            lines: 20,
+            follow: false,
+            level: Some("warn".into()),
+            since: Some("10m".into()),
+            grep: Some("dropped".into()),
+            json: false,
+        };
+        let query = log_query(&args, 1_000_000_000).unwrap();
+        assert_eq!(query.lines, 20);
+        assert_eq!(query.level, Some(LogLevel::Warn));
+        assert_eq!(query.since_ms, Some(1_000_000_000 - 600_000));
+        assert_eq!(query.grep.as_deref(), Some("dropped"));
+
+        assert_eq!(
+            parse_since("2026-01-01T00:00:00Z", 0).unwrap(),
+            1_767_225_600_000
+        );
+        assert_eq!(parse_since("2d", 200_000_000).unwrap(), 27_200_000);
+        assert!(parse_since("10", 0).is_err());
+        assert!(parse_since("-5m", 0).is_err());
+
+        let bad_regex = LogsArgs {
+            grep: Some("(".into()),
+            ..args
+        };
+        assert!(matches!(
+            log_query(&bad_regex, 0),
+            Err(NodeError::InvalidArgs(_))
+        ));
+    }
 
     #[test]
     fn test_logs_response_deserialization() {
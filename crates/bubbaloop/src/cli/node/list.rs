@@ -4,33 +4,125 @@ use super::{truncate, Result};
 use crate::registry;
 use std::path::Path;
 
-pub(crate) async fn list_nodes(format: &str, _base: bool, _instances: bool) -> Result<()> {
+/// Statuses accepted by `node list --status`, as the daemon reports them.
+const STATUSES: &[&str] = &[
+    "running",
+    "stopped",
+    "failed",
+    "installing",
+    "building",
+    "not-installed",
+    "unknown",
+];
+
+/// `node list` pattern and `--status` filter.
+#[derive(Debug, Default)]
+pub(crate) struct NodeFilter {
+    pattern: Option<String>,
+    status: Option<String>,
+}
+
+impl NodeFilter {
+    pub(crate) fn new(pattern: Option<String>, status: Option<String>) -> Result<Self> {
+        let status = match status {
+            Some(s) => {
+                let s = s.to_lowercase().replace('_', "-");
+                if !STATUSES.contains(&s.as_str()) {
+                    return Err(super::NodeError::InvalidArgs(format!(
+                        "unknown status '{}' (expected one of: {})",
+                        s,
+                        STATUSES.join(", ")
+                    )));
+                }
+                Some(s)
+            }
+            None => None,
+        };
+        Ok(Self { pattern, status })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pattern.is_none() && self.status.is_none()
+    }
+
+    fn matches(&self, node: &crate::mcp::platform::NodeInfo) -> bool {
+        // Statuses arrive as `NotInstalled`; compare without separators.
+        let status_ok = self
+            .status
+            .as_ref()
+            .is_none_or(|s| s.replace('-', "") == node.status.to_lowercase());
+        let pattern_ok = self
+            .pattern
+            .as_ref()
+            .is_none_or(|p| fuzzy_match(p, &node.name) || fuzzy_match(p, &node.node_type));
+        status_ok && pattern_ok
+    }
+}
+
+/// Case-insensitive subsequence match: `rtcam` matches `rtsp-camera`.
+fn fuzzy_match(pattern: &str, text: &str) -> bool {
+    let mut chars = text.chars().flat_map(char::to_lowercase);
+    pattern
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|p| chars.any(|c| c == p))
+}
+
+pub(crate) async fn list_nodes(
+    format: &str,
+    _base: bool,
+    _instances: bool,
+    filter: &NodeFilter,
+) -> Result<()> {
     let client = crate::cli::daemon_client::DaemonClient::connect().await?;
     let result = client.list_nodes().await?;
 
-    if format == "json" {
+    if format == "json" && filter.is_empty() {
         println!("{}", result);
     } else if result.contains("[]") || result.is_empty() {
-        println!("No nodes registered. Use 'bubbaloop node add <path>' to add one.");
+        if format == "json" {
+            println!("[]");
+        } else {
+            println!("No nodes registered. Use 'bubbaloop node add <path>' to add one.");
+        }
     } else {
         // Parse the JSON node list from the gateway response
-        let nodes: Vec<crate::mcp::platform::NodeInfo> =
-            serde_json::from_str(&result).map_err(|e| {
+        let mut nodes: Vec<crate::mcp::platform::NodeInfo> = serde_json::from_str(&result)
+            .map_err(|e| {
                 super::NodeError::CommandFailed(format!("Invalid daemon response: {}", e))
             })?;
-        if nodes.is_empty() {
+        let registered = nodes.len();
+        nodes.retain(|node| filter.matches(node));
+        if format == "json" {
+            println!("{}", serde_json::to_string(&nodes)?);
+        } else if registered == 0 {
             println!("No nodes registered. Use 'bubbaloop node add <path>' to add one.");
+        } else if nodes.is_empty() {
+            println!("No nodes match the filter ({} registered).", registered);
         } else {
+            // Versions come from the node states; the registry cache is
+            // not refreshed here so listing stays fast and offline.
+            let states = client
+                .node_states()
+                .await
+                .map(|l| l.nodes)
+                .unwrap_or_default();
+            let marketplace = registry::load_cached_registry();
             println!(
-                "{:<20} {:<10} {:<12} {:<8} HEALTH",
-                "NAME", "STATUS", "TYPE", "BUILT"
+                "{:<20} {:<10} {:<12} {:<8} {:<10} UPDATE",
+                "NAME", "STATUS", "TYPE", "BUILT", "HEALTH"
             );
-            println!("{}", "-".repeat(70));
+            println!("{}", "-".repeat(80));
             for node in &nodes {
                 let built = if node.is_built { "yes" } else { "no" };
+                let update = states
+                    .iter()
+                    .find(|s| s.name == node.name)
+                    .and_then(|state| available_update(&marketplace, state))
+                    .map_or_else(|| "-".to_string(), |v| format!("v{}", v));
                 println!(
-                    "{:<20} {:<10} {:<12} {:<8} {}",
-                    node.name, node.status, node.node_type, built, node.health,
+                    "{:<20} {:<10} {:<12} {:<8} {:<10} {}",
+                    node.name, node.status, node.node_type, built, node.health, update,
                 );
             }
         }
@@ -39,19 +131,43 @@ pub(crate) async fn list_nodes(format: &str, _base: bool, _instances: bool) -> R
     Ok(())
 }
 
-pub(crate) fn search_nodes(query: &str, category: Option<&str>, tag: Option<&str>) -> Result<()> {
+/// Marketplace version newer than the installed one, if any.
+fn available_update(
+    marketplace: &[registry::RegistryNode],
+    state: &crate::daemon::gateway::NodeStateJson,
+) -> Option<String> {
+    super::diff::marketplace_entry(marketplace, state)
+        .filter(|entry| super::diff::is_newer(&entry.version, &state.version))
+        .map(|entry| entry.version)
+}
+
+pub(crate) fn search_nodes(
+    query: &str,
+    category: Option<&str>,
+    tag: Option<&str>,
+    json: bool,
+    offline: bool,
+) -> Result<()> {
     log::info!(
         "node search: query={:?} category={:?} tag={:?}",
         query,
         category,
         tag
     );
-    println!("Refreshing marketplace registry...");
-    if let Err(e) = registry::refresh_cache() {
-        log::warn!("registry refresh failed: {}", e);
-        eprintln!("Warning: could not refresh registry (using cache): {}", e);
+    if !json && !offline {
+        println!("Refreshing marketplace registry...");
+    }
+    let loaded = registry::load_registry(offline);
+    if let Some(warning) = loaded.warning() {
+        eprintln!("Warning: {}", warning);
+    }
+    let all_nodes = loaded.nodes;
+
+    if json {
+        let results = registry::search_registry(&all_nodes, query, category, tag);
+        println!("{}", serde_json::to_string_pretty(&results)?);
+        return Ok(());
     }
-    let all_nodes = registry::load_cached_registry();
 
     if all_nodes.is_empty() {
         println!("No nodes found in marketplace registry.");
@@ -91,13 +207,13 @@ pub(crate) fn search_nodes(query: &str, category: Option<&str>, tag: Option<&str
     Ok(())
 }
 
-pub(crate) async fn discover_nodes(format: &str) -> Result<()> {
-    // Refresh marketplace cache
-    if let Err(e) = registry::refresh_cache() {
-        log::warn!("registry refresh failed: {}", e);
-        eprintln!("Warning: could not refresh registry (using cache): {}", e);
+pub(crate) async fn discover_nodes(format: &str, offline: bool) -> Result<()> {
+    // Refresh marketplace cache unless offline
+    let loaded = registry::load_registry(offline);
+    if let Some(warning) = loaded.warning() {
+        eprintln!("Warning: {}", warning);
     }
-    let all_marketplace = registry::load_cached_registry();
+    let all_marketplace = loaded.nodes;
 
     // Query daemon for registered nodes via Zenoh gateway
     let registered: Vec<crate::mcp::platform::NodeInfo> =
@@ -112,7 +228,7 @@ pub(crate) async fn discover_nodes(format: &str) -> Result<()> {
             Err(_) => vec![],
         };
 
-    if all_marketplace.is_empty() {
+    if all_marketplace.is_empty() && format != "json" {
         println!(
             "No nodes found in marketplace. The registry cache may not have been fetched yet."
         );
@@ -230,6 +346,39 @@ pub(crate) fn discover_nodes_in_subdirs(base_path: &Path) -> Vec<(String, String
 mod tests {
     use super::*;
 
+    fn node(name: &str, status: &str, node_type: &str) -> crate::mcp::platform::NodeInfo {
+        crate::mcp::platform::NodeInfo {
+            name: name.to_string(),
+            status: status.to_string(),
+            health: "Healthy".to_string(),
+            node_type: node_type.to_string(),
+            installed: true,
+            is_built: true,
+        }
+    }
+
+    #[test]
+    fn test_node_filter() {
+        assert!(fuzzy_match("rtcam", "rtsp-camera-terrace"));
+        assert!(fuzzy_match("TERR", "rtsp-camera-terrace"));
+        assert!(!fuzzy_match("camrt", "rtsp-camera"));
+
+        let camera = node("rtsp-camera-terrace", "Running", "rust");
+        let weather = node("openmeteo", "NotInstalled", "python");
+        let filter = NodeFilter::new(Some("cam".into()), None).unwrap();
+        assert!(filter.matches(&camera));
+        assert!(!filter.matches(&weather));
+
+        let filter = NodeFilter::new(None, Some("NOT_INSTALLED".into())).unwrap();
+        assert!(filter.matches(&weather));
+        assert!(!filter.matches(&camera));
+
+        let filter = NodeFilter::new(Some("py".into()), Some("running".into())).unwrap();
+        assert!(!filter.matches(&weather));
+        assert!(NodeFilter::new(None, Some("crashed".into())).is_err());
+        assert!(NodeFilter::default().is_empty());
+    }
+
     #[test]
     fn test_discover_nodes_in_subdirs() {
         let dir = tempfile::tempdir().unwrap();
@@ -4,6 +4,7 @@ use std::path::{Path, PathBuf};
 
 use super::install;
 use super::list::discover_nodes_in_subdirs;
+use super::prune;
 use super::{send_command, NodeError, Result};
 
 #[allow(clippy::too_many_arguments)]
@@ -62,12 +63,35 @@ pub(crate) async fn add_node(
 }
 
 pub(crate) async fn remove_node(name: &str, delete_files: bool) -> Result<()> {
+    // Look the files up before the daemon forgets the node.
+    let files = if delete_files {
+        prune::ensure_local("node remove --delete-files")?;
+        let mut nodes = prune::registered_nodes()?;
+        let Some(index) = nodes.iter().position(|n| n.name == name) else {
+            return Err(NodeError::NotFound(name.to_string()));
+        };
+        let node = nodes.remove(index);
+        Some(prune::removable_files(
+            &prune::Layout::current(),
+            &node,
+            &nodes,
+        ))
+    } else {
+        None
+    };
+
     let client = crate::cli::daemon_client::DaemonClient::connect().await?;
     client.remove_node(name).await?;
     println!("Removed node: {}", name);
 
-    if delete_files {
-        eprintln!("Note: File deletion not implemented yet. Remove files manually.");
+    if let Some((files, kept)) = files {
+        for path in &files {
+            prune::delete_path(path)?;
+            println!("Deleted {}", path.display());
+        }
+        for reason in &kept {
+            println!("Kept {}", reason);
+        }
     }
 
     Ok(())
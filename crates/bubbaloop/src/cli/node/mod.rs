@@ -4,10 +4,15 @@
 //! These interact with the daemon via HTTP REST API to manage systemd services.
 
 pub mod build;
+mod config;
+pub(crate) mod diff;
+mod history;
 pub mod install;
 pub mod lifecycle;
 mod list;
 mod manage;
+mod prune;
+mod update;
 
 // Re-export for use by sibling modules (e.g., install.rs uses super::resolve_node_path)
 pub(crate) use manage::resolve_node_path;
@@ -16,6 +21,7 @@ use argh::FromArgs;
 use std::path::PathBuf;
 use thiserror::Error;
 
+use crate::registry;
 use crate::templates;
 
 #[derive(Debug, Error)]
@@ -57,18 +63,23 @@ enum NodeAction {
     Add(AddArgs),
     Remove(RemoveArgs),
     Instance(InstanceArgs),
+    Config(config::ConfigArgs),
     Install(InstallArgs),
     Uninstall(UninstallArgs),
     Start(StartArgs),
     Stop(StopArgs),
     Restart(RestartArgs),
     Logs(LogsArgs),
+    History(HistoryArgs),
     Build(BuildArgs),
+    Update(UpdateArgs),
+    Diff(DiffArgs),
     Clean(CleanArgs),
     Enable(EnableArgs),
     Disable(DisableArgs),
     Search(SearchArgs),
     Discover(DiscoverArgs),
+    Prune(PruneArgs),
 }
 
 /// Initialize a new node from template
@@ -120,6 +131,15 @@ struct ListArgs {
     /// show only instances (excludes base nodes)
     #[argh(switch)]
     instances: bool,
+
+    /// only show nodes whose name or type fuzzy-matches this pattern
+    #[argh(positional)]
+    pattern: Option<String>,
+
+    /// only show nodes with this status: running, stopped, failed,
+    /// installing, building, not-installed, unknown
+    #[argh(option, short = 's')]
+    status: Option<String>,
 }
 
 /// Add a node from local path or GitHub URL
@@ -167,7 +187,8 @@ struct RemoveArgs {
     #[argh(positional)]
     name: String,
 
-    /// also delete files
+    /// also delete the node's clone under ~/.bubbaloop/nodes (unless other
+    /// nodes use it) and its instance config
     #[argh(switch)]
     delete_files: bool,
 }
@@ -225,6 +246,10 @@ pub(crate) struct InstallArgs {
     /// also build the node (marketplace install only)
     #[argh(switch)]
     pub(crate) build: bool,
+
+    /// use the cached registry and an earlier clone instead of the network
+    #[argh(switch)]
+    pub(crate) offline: bool,
 }
 
 /// Uninstall a node's systemd service
@@ -272,13 +297,45 @@ pub(crate) struct LogsArgs {
     pub(crate) name: String,
 
     /// number of lines to show (default: 50)
-    #[allow(dead_code)]
     #[argh(option, short = 'n', default = "50")]
     pub(crate) lines: usize,
 
     /// follow log output
     #[argh(switch, short = 'f')]
     pub(crate) follow: bool,
+
+    /// only lines at this level or above: debug, info, warn, error
+    #[argh(option)]
+    pub(crate) level: Option<String>,
+
+    /// only lines newer than this: 90s, 10m, 1h, 2d or an RFC 3339 time
+    #[argh(option)]
+    pub(crate) since: Option<String>,
+
+    /// only lines matching this regex
+    #[argh(option)]
+    pub(crate) grep: Option<String>,
+
+    /// output the log lines as JSON
+    #[argh(switch)]
+    pub(crate) json: bool,
+}
+
+/// Show a node's status and health transitions as a timeline
+#[derive(FromArgs)]
+#[argh(subcommand, name = "history")]
+pub(crate) struct HistoryArgs {
+    /// node name
+    #[argh(positional)]
+    pub(crate) name: String,
+
+    /// how far back to show: 90s, 10m, 1h, 2d or an RFC 3339 time (default: 24h)
+    #[argh(option, default = "String::from(\"24h\")")]
+    pub(crate) since: String,
+
+    /// output the transitions as JSON
+    #[argh(switch)]
+    pub(crate) json: bool,
 }
 
 /// Build a node
@@ -290,6 +347,36 @@ struct BuildArgs {
     name: String,
 }
 
+/// Pull, rebuild and restart a node from its git checkout
+#[derive(FromArgs)]
+#[argh(subcommand, name = "update")]
+struct UpdateArgs {
+    /// node name
+    #[argh(positional)]
+    name: Option<String>,
+
+    /// update every node (instances are restarted with their base node)
+    #[argh(switch)]
+    all: bool,
+}
+
+/// Compare an installed node with its marketplace entry
+#[derive(FromArgs)]
+#[argh(subcommand, name = "diff")]
+struct DiffArgs {
+    /// node name
+    #[argh(positional)]
+    name: String,
+
+    /// do not fetch the node's git upstream
+    #[argh(switch)]
+    no_fetch: bool,
+
+    /// output as JSON
+    #[argh(switch)]
+    json: bool,
+}
+
 /// Clean a node's build artifacts
 #[derive(FromArgs)]
 #[argh(subcommand, name = "clean")]
@@ -332,6 +419,14 @@ struct SearchArgs {
     /// filter by tag
     #[argh(option, short = 't')]
     tag: Option<String>,
+
+    /// output results as JSON
+    #[argh(switch)]
+    json: bool,
+
+    /// search the cached registry without refreshing it
+    #[argh(switch)]
+    offline: bool,
 }
 
 /// Discover available nodes from marketplace sources (with status from daemon)
@@ -341,6 +436,31 @@ struct DiscoverArgs {
     /// output format: table, json (default: table)
     #[argh(option, short = 'f', default = "String::from(\"table\")")]
     format: String,
+
+    /// output as JSON (same as -f json)
+    #[argh(switch)]
+    json: bool,
+
+    /// use the cached registry without refreshing it
+    #[argh(switch)]
+    offline: bool,
+}
+
+/// Remove clones, configs and systemd units no registered node uses
+#[derive(FromArgs)]
+#[argh(subcommand, name = "prune")]
+struct PruneArgs {
+    /// remove without asking
+    #[argh(switch, short = 'y')]
+    yes: bool,
+
+    /// only list what would be removed
+    #[argh(switch)]
+    dry_run: bool,
+
+    /// list what would be removed as JSON (implies --dry-run)
+    #[argh(switch)]
+    json: bool,
 }
 
 /// Legacy response types kept for tests (no longer used at runtime).
@@ -375,12 +495,12 @@ pub(crate) struct NodeListResponse {
     pub(crate) nodes: Vec<NodeState>,
 }
 
-#[cfg(test)]
-#[derive(serde::Deserialize)]
+/// `node logs --json` output.
+#[derive(serde::Serialize, serde::Deserialize)]
 pub(crate) struct LogsResponse {
     pub(crate) lines: Vec<String>,
     pub(crate) success: bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub(crate) error: Option<String>,
 }
 
@@ -399,7 +519,8 @@ impl NodeCommand {
                         "Cannot use --base and --instances together".into(),
                     ));
                 }
-                list::list_nodes(&args.format, args.base, args.instances).await
+                let filter = list::NodeFilter::new(args.pattern, args.status)?;
+                list::list_nodes(&args.format, args.base, args.instances, &filter).await
             }
             Some(NodeAction::Add(args)) => {
                 manage::add_node(
@@ -428,20 +549,36 @@ impl NodeCommand {
                 )
                 .await
             }
+            Some(NodeAction::Config(args)) => config::run(args).await,
             Some(NodeAction::Install(args)) => install::handle_install(args).await,
             Some(NodeAction::Uninstall(args)) => send_command(&args.name, "uninstall").await,
             Some(NodeAction::Start(args)) => lifecycle::start_node(&args.name).await,
             Some(NodeAction::Stop(args)) => lifecycle::stop_node(&args.name).await,
             Some(NodeAction::Restart(args)) => lifecycle::restart_node(&args.name).await,
             Some(NodeAction::Logs(args)) => lifecycle::view_logs(args).await,
+            Some(NodeAction::History(args)) => history::show_history(args).await,
             Some(NodeAction::Build(args)) => build::build_node(&args.name).await,
+            Some(NodeAction::Update(args)) => {
+                update::update_nodes(args.name.as_deref(), args.all).await
+            }
+            Some(NodeAction::Diff(args)) => {
+                diff::diff_node(&args.name, !args.no_fetch, args.json).await
+            }
             Some(NodeAction::Clean(args)) => send_command(&args.name, "clean").await,
             Some(NodeAction::Enable(args)) => send_command(&args.name, "enable_autostart").await,
             Some(NodeAction::Disable(args)) => send_command(&args.name, "disable_autostart").await,
-            Some(NodeAction::Search(args)) => {
-                list::search_nodes(&args.query, args.category.as_deref(), args.tag.as_deref())
+            Some(NodeAction::Search(args)) => list::search_nodes(
+                &args.query,
+                args.category.as_deref(),
+                args.tag.as_deref(),
+                args.json,
+                args.offline || registry::offline_from_env(),
+            ),
+            Some(NodeAction::Discover(args)) => {
+                let format = if args.json { "json" } else { &args.format };
+                list::discover_nodes(format, args.offline || registry::offline_from_env()).await
             }
-            Some(NodeAction::Discover(args)) => list::discover_nodes(&args.format).await,
+            Some(NodeAction::Prune(args)) => prune::prune(args.yes, args.dry_run, args.json).await,
         }
     }
 
@@ -458,6 +595,7 @@ impl NodeCommand {
         eprintln!(
             "              Example: bubbaloop node instance rtsp-camera terrace -c config.yaml"
         );
+        eprintln!("  config      Edit a node's config file (config edit <name>)");
         eprintln!("  search      Search the node marketplace");
         eprintln!("  discover    Discover available nodes with status");
         eprintln!("  install     Install a node (or from marketplace by name)");
@@ -466,11 +604,15 @@ impl NodeCommand {
         eprintln!("  stop        Stop a node service");
         eprintln!("  restart     Restart a node service");
         eprintln!("  logs        View logs for a node");
+        eprintln!("  history     Show a node's health timeline");
         eprintln!("  build       Build a node");
+        eprintln!("  update      Pull, rebuild and restart a node (or --all)");
+        eprintln!("  diff        Compare an installed node with the marketplace");
         eprintln!("  clean       Clean a node's build artifacts");
         eprintln!("  enable      Enable autostart for a node");
         eprintln!("  disable     Disable autostart for a node");
-        eprintln!("  (See also: bubbaloop launch  -- launch multi-instance YAML)");
+        eprintln!("  prune       Remove files no registered node uses");
+        eprintln!("  (See also: bubbaloop launch start|instance -- launch multi-instance YAML)");
         eprintln!("\nRun 'bubbaloop node <command> --help' for more information.");
     }
 }
@@ -0,0 +1,468 @@
+//! File cleanup for `node remove --delete-files` and `node prune`.
+//!
+//! Only files bubbaloop created are touched: clones under
+//! `~/.bubbaloop/nodes`, instance configs under `~/.bubbaloop/configs` and
+//! `~/.bubbaloop/skills-config`, and `bubbaloop-*.service` units written for
+//! nodes. Something is orphaned when no entry of the node registry
+//! (`~/.bubbaloop/nodes.json`) uses it.
+
+use std::collections::HashSet;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::{NodeError, Result};
+use crate::daemon::registry::{self, get_bubbaloop_home, NodeEntry, NodeManifest};
+use crate::daemon::systemd::get_systemd_user_dir;
+
+/// First line of the `[Unit]` description of node units.
+const NODE_UNIT_MARKER: &str = "Description=Bubbaloop Node: ";
+
+/// Files nothing in the registry refers to.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub(crate) struct Orphans {
+    /// Clone directories under `~/.bubbaloop/nodes`.
+    pub clones: Vec<PathBuf>,
+    /// Config files and skill config directories.
+    pub configs: Vec<PathBuf>,
+    /// Node names with a leftover systemd unit.
+    pub units: Vec<String>,
+}
+
+impl Orphans {
+    fn is_empty(&self) -> bool {
+        self.clones.is_empty() && self.configs.is_empty() && self.units.is_empty()
+    }
+}
+
+/// Where bubbaloop keeps node files; the home directory in production, a
+/// temporary one in tests.
+pub(crate) struct Layout {
+    pub home: PathBuf,
+    pub systemd_dir: PathBuf,
+}
+
+impl Layout {
+    pub fn current() -> Self {
+        Self {
+            home: get_bubbaloop_home(),
+            systemd_dir: get_systemd_user_dir(),
+        }
+    }
+
+    fn nodes_dir(&self) -> PathBuf {
+        canonical(&self.home.join("nodes"))
+    }
+}
+
+/// A registered node, reduced to what the cleanup needs.
+#[derive(Debug, Clone)]
+pub(crate) struct Registered {
+    pub name: String,
+    pub path: PathBuf,
+    pub config: Option<PathBuf>,
+}
+
+impl Registered {
+    fn from_entry(entry: &NodeEntry, manifest: Option<&NodeManifest>) -> Self {
+        let path = PathBuf::from(&entry.path);
+        let name = match manifest {
+            Some(manifest) => registry::effective_name(entry, manifest),
+            None => entry.name_override.clone().unwrap_or_else(|| {
+                path.file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default()
+            }),
+        };
+        Self {
+            name,
+            path: canonical(&path),
+            config: entry
+                .config_override
+                .as_deref()
+                .map(|c| canonical(Path::new(c))),
+        }
+    }
+}
+
+/// Every node in the local registry.
+pub(crate) fn registered_nodes() -> Result<Vec<Registered>> {
+    let nodes = registry::list_nodes().map_err(|e| NodeError::CommandFailed(e.to_string()))?;
+    Ok(nodes
+        .iter()
+        .map(|(entry, manifest)| Registered::from_entry(entry, manifest.as_ref()))
+        .collect())
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// What `node remove --delete-files` may delete for `node`, once it is
+/// unregistered: its clone (the whole repository when no remaining node
+/// uses it) and its config, when they live under the bubbaloop home.
+pub(crate) fn removable_files(
+    layout: &Layout,
+    node: &Registered,
+    remaining: &[Registered],
+) -> (Vec<PathBuf>, Vec<String>) {
+    let mut files = Vec::new();
+    let mut kept = Vec::new();
+    let nodes_dir = layout.nodes_dir();
+
+    match node.path.strip_prefix(&nodes_dir) {
+        Ok(relative) => match relative.components().next() {
+            Some(repo) => {
+                let repo_dir = nodes_dir.join(repo);
+                if remaining.iter().any(|r| r.path == node.path) {
+                    kept.push(format!("{} (used by other instances)", node.path.display()));
+                } else if remaining.iter().any(|r| r.path.starts_with(&node.path)) {
+                    kept.push(format!(
+                        "{} (contains other registered nodes)",
+                        node.path.display()
+                    ));
+                } else if remaining.iter().any(|r| r.path.starts_with(&repo_dir)) {
+                    files.push(node.path.clone());
+                } else {
+                    files.push(repo_dir);
+                }
+            }
+            None => kept.push(format!("{} (the nodes directory)", node.path.display())),
+        },
+        Err(_) => kept.push(format!(
+            "{} (outside {})",
+            node.path.display(),
+            nodes_dir.display()
+        )),
+    }
+
+    if let Some(config) = &node.config {
+        let owned = [
+            canonical(&layout.home.join("configs")),
+            canonical(&layout.home.join("skills-config")),
+        ]
+        .iter()
+        .any(|dir| config.starts_with(dir));
+        let shared = remaining.iter().any(|r| r.config.as_ref() == Some(config));
+        if owned && !shared {
+            files.push(config.clone());
+        } else if !shared {
+            kept.push(format!(
+                "{} (outside {})",
+                config.display(),
+                layout.home.display()
+            ));
+        }
+    }
+    (files, kept)
+}
+
+/// Find the clones, configs and units no registered node uses.
+pub(crate) fn find_orphans(layout: &Layout, nodes: &[Registered]) -> Orphans {
+    let mut orphans = Orphans::default();
+    let names: HashSet<&str> = nodes.iter().map(|n| n.name.as_str()).collect();
+
+    for repo_dir in subdirs(&layout.nodes_dir()) {
+        if !nodes.iter().any(|n| n.path.starts_with(&repo_dir)) {
+            orphans.clones.push(repo_dir);
+        }
+    }
+
+    let configs: HashSet<&PathBuf> = nodes.iter().filter_map(|n| n.config.as_ref()).collect();
+    if let Ok(entries) = std::fs::read_dir(layout.home.join("configs")) {
+        for entry in entries.flatten() {
+            let path = canonical(&entry.path());
+            let is_yaml = matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("yaml") | Some("yml")
+            );
+            let stem = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default();
+            if path.is_file() && is_yaml && !configs.contains(&path) && !names.contains(stem) {
+                orphans.configs.push(path);
+            }
+        }
+    }
+    for dir in subdirs(&layout.home.join("skills-config")) {
+        let name = dir.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+        if !names.contains(name) && !configs.iter().any(|c| c.starts_with(&dir)) {
+            orphans.configs.push(dir);
+        }
+    }
+
+    if let Ok(entries) = std::fs::read_dir(&layout.systemd_dir) {
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let Some(name) = file_name
+                .strip_prefix("bubbaloop-")
+                .and_then(|n| n.strip_suffix(".service"))
+            else {
+                continue;
+            };
+            let is_node_unit = std::fs::read_to_string(entry.path())
+                .map(|content| content.contains(NODE_UNIT_MARKER))
+                .unwrap_or(false);
+            if is_node_unit && !names.contains(name) {
+                orphans.units.push(name.to_string());
+            }
+        }
+    }
+
+    orphans.clones.sort();
+    orphans.configs.sort();
+    orphans.units.sort();
+    orphans
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|e| canonical(&e.path()))
+        .filter(|p| p.is_dir())
+        .collect()
+}
+
+/// Delete a file or directory.
+pub(crate) fn delete_path(path: &Path) -> io::Result<()> {
+    if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
+
+/// `bubbaloop node prune`
+pub(crate) async fn prune(yes: bool, dry_run: bool, json: bool) -> Result<()> {
+    ensure_local("node prune")?;
+    let orphans = find_orphans(&Layout::current(), &registered_nodes()?);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&orphans)?);
+        return Ok(());
+    }
+    if orphans.is_empty() {
+        println!("Nothing to prune.");
+        return Ok(());
+    }
+    print_orphans(&orphans);
+    if dry_run {
+        return Ok(());
+    }
+    if !yes {
+        if !io::stdin().is_terminal() {
+            println!("\nRun with --yes to remove them.");
+            return Ok(());
+        }
+        print!("\nRemove them? [y/N]: ");
+        io::stdout().flush()?;
+        let mut line = String::new();
+        io::stdin().read_line(&mut line)?;
+        if !matches!(line.trim().to_lowercase().as_str(), "y" | "yes") {
+            println!("Nothing removed.");
+            return Ok(());
+        }
+    }
+
+    let mut failures = 0;
+    for path in orphans.clones.iter().chain(&orphans.configs) {
+        match delete_path(path) {
+            Ok(()) => println!("Removed {}", path.display()),
+            Err(e) => {
+                failures += 1;
+                eprintln!("Failed to remove {}: {}", path.display(), e);
+            }
+        }
+    }
+    for name in &orphans.units {
+        match crate::daemon::systemd::uninstall_service(name).await {
+            Ok(()) => println!(
+                "Removed unit {}",
+                crate::daemon::systemd::get_service_name(name)
+            ),
+            Err(e) => {
+                failures += 1;
+                eprintln!("Failed to remove unit of {}: {}", name, e);
+            }
+        }
+    }
+    if failures > 0 {
+        return Err(NodeError::CommandFailed(format!(
+            "{} item(s) could not be removed",
+            failures
+        )));
+    }
+    Ok(())
+}
+
+fn print_orphans(orphans: &Orphans) {
+    if !orphans.clones.is_empty() {
+        println!("Clones no registered node uses:");
+        for path in &orphans.clones {
+            println!("  {}", path.display());
+        }
+    }
+    if !orphans.configs.is_empty() {
+        println!("Configs of nodes that are not registered:");
+        for path in &orphans.configs {
+            println!("  {}", path.display());
+        }
+    }
+    if !orphans.units.is_empty() {
+        println!("Systemd units of nodes that are not registered:");
+        for name in &orphans.units {
+            println!("  {}", crate::daemon::systemd::get_service_name(name));
+        }
+    }
+}
+
+/// File cleanup reads this machine's registry and home directory, so it
+/// cannot target another machine.
+pub(crate) fn ensure_local(what: &str) -> Result<()> {
+    let target = crate::cli::target::get();
+    match &target.machine {
+        Some(machine) if *machine != crate::daemon::util::get_machine_id() => {
+            Err(NodeError::InvalidArgs(format!(
+                "{} only works on this machine, not on '{}'",
+                what, machine
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(dir: &Path) -> Layout {
+        let layout = Layout {
+            home: dir.join(".bubbaloop"),
+            systemd_dir: dir.join("systemd"),
+        };
+        std::fs::create_dir_all(layout.home.join("nodes")).unwrap();
+        std::fs::create_dir_all(layout.home.join("configs")).unwrap();
+        std::fs::create_dir_all(&layout.systemd_dir).unwrap();
+        layout
+    }
+
+    fn node(layout: &Layout, name: &str, rel: &str, config: Option<&str>) -> Registered {
+        let path = layout.home.join("nodes").join(rel);
+        std::fs::create_dir_all(&path).unwrap();
+        Registered {
+            name: name.to_string(),
+            path: canonical(&path),
+            config: config.map(|c| {
+                let path = layout.home.join("configs").join(c);
+                std::fs::write(&path, "").unwrap();
+                canonical(&path)
+            }),
+        }
+    }
+
+    #[test]
+    fn finds_orphaned_clones_configs_and_units() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = layout(dir.path());
+        let camera = node(
+            &layout,
+            "cam-terrace",
+            "official/rtsp-camera",
+            Some("terrace.yaml"),
+        );
+        node(&layout, "gone", "old-repo/sensor", None);
+        std::fs::write(layout.home.join("configs/cam-front.yaml"), "").unwrap();
+        std::fs::write(layout.home.join("configs/notes.txt"), "").unwrap();
+        std::fs::write(
+            layout.systemd_dir.join("bubbaloop-gone.service"),
+            format!("[Unit]\n{}gone\n", NODE_UNIT_MARKER),
+        )
+        .unwrap();
+        std::fs::write(
+            layout.systemd_dir.join("bubbaloop-cam-terrace.service"),
+            format!("[Unit]\n{}cam-terrace\n", NODE_UNIT_MARKER),
+        )
+        .unwrap();
+        std::fs::write(
+            layout.systemd_dir.join("bubbaloop-daemon.service"),
+            "[Unit]\nDescription=Bubbaloop Daemon\n",
+        )
+        .unwrap();
+
+        let orphans = find_orphans(&layout, &[camera]);
+        assert_eq!(orphans.clones, vec![layout.nodes_dir().join("old-repo")]);
+        assert_eq!(
+            orphans.configs,
+            vec![canonical(&layout.home.join("configs/cam-front.yaml"))]
+        );
+        assert_eq!(orphans.units, vec!["gone"]);
+    }
+
+    #[test]
+    fn remove_deletes_repo_only_when_unused() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = layout(dir.path());
+        let camera = node(&layout, "rtsp-camera", "official/rtsp-camera", None);
+        let instance = Registered {
+            name: "rtsp-camera-terrace".to_string(),
+            ..node(&layout, "x", "official/rtsp-camera", Some("terrace.yaml"))
+        };
+        let telemetry = node(&layout, "telemetry", "official/telemetry", None);
+
+        // An instance shares the base node's directory.
+        let (files, kept) =
+            removable_files(&layout, &instance, &[camera.clone(), telemetry.clone()]);
+        assert_eq!(files, vec![instance.config.clone().unwrap()]);
+        assert_eq!(kept.len(), 1);
+
+        // Another node still lives in the repository.
+        let (files, _) = removable_files(&layout, &camera, std::slice::from_ref(&telemetry));
+        assert_eq!(files, vec![camera.path.clone()]);
+
+        // The last node of the repository takes the whole clone with it.
+        let (files, _) = removable_files(&layout, &telemetry, &[]);
+        assert_eq!(files, vec![layout.nodes_dir().join("official")]);
+    }
+
+    #[test]
+    fn remove_keeps_repo_root_node_with_registered_sub_nodes() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = layout(dir.path());
+        let root = node(&layout, "gateway", "my-repo", None);
+        let sub = node(&layout, "sensor", "my-repo/sensor", None);
+
+        let (files, kept) = removable_files(&layout, &root, std::slice::from_ref(&sub));
+        assert!(files.is_empty(), "{files:?}");
+        assert_eq!(
+            kept,
+            vec![format!(
+                "{} (contains other registered nodes)",
+                root.path.display()
+            )]
+        );
+
+        // The sub-node can still go on its own.
+        let (files, _) = removable_files(&layout, &sub, std::slice::from_ref(&root));
+        assert_eq!(files, vec![sub.path.clone()]);
+    }
+
+    #[test]
+    fn remove_keeps_files_outside_bubbaloop_home() {
+        let dir = tempfile::tempdir().unwrap();
+        let layout = layout(dir.path());
+        let local = dir.path().join("src/my-node");
+        std::fs::create_dir_all(&local).unwrap();
+        let node = Registered {
+            name: "my-node".to_string(),
+            path: canonical(&local),
+            config: Some(canonical(&local.join("config.yaml"))),
+        };
+        let (files, kept) = removable_files(&layout, &node, &[]);
+        assert!(files.is_empty());
+        assert_eq!(kept.len(), 2);
+    }
+}
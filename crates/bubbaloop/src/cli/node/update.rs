@@ -0,0 +1,199 @@
+//! Node update command: pull, rebuild and restart from the node's git checkout.
+//!
+//! The daemon does the work in the background (see `NodeManager::update_node`);
+//! this side follows the node's build output through the nodes queryable
+//! until the update finishes, then reports the version change.
+
+use std::time::{Duration, Instant};
+
+use super::{NodeError, Result};
+use crate::cli::daemon_client::DaemonClient;
+use crate::daemon::gateway::NodeStateJson;
+use crate::schemas::NodeStatus;
+
+/// How often the build output is polled.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Give up following an update after the daemon's git and build timeouts.
+const FOLLOW_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// Last build output line of a successful update.
+const SUCCESS_MARKER: &str = "--- Update completed successfully ---";
+
+pub(crate) async fn update_nodes(name: Option<&str>, all: bool) -> Result<()> {
+    let client = DaemonClient::connect().await?;
+    let nodes = client.node_states().await?.nodes;
+    let targets = select_targets(&nodes, name, all)?;
+
+    let mut failed = Vec::new();
+    for node in &targets {
+        if let Err(e) = update_one(&client, node).await {
+            eprintln!("{}: {}", node.name, e);
+            failed.push(node.name.clone());
+        }
+        if targets.len() > 1 {
+            println!();
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(NodeError::CommandFailed(format!(
+            "{} of {} updates failed: {}",
+            failed.len(),
+            targets.len(),
+            failed.join(", ")
+        )))
+    }
+}
+
+/// The named node, or with `--all` every base node (instances share their
+/// base node's checkout and are restarted with it).
+fn select_targets(
+    nodes: &[NodeStateJson],
+    name: Option<&str>,
+    all: bool,
+) -> Result<Vec<NodeStateJson>> {
+    match (name, all) {
+        (Some(_), true) => Err(NodeError::InvalidArgs(
+            "Cannot use a node name and --all together".into(),
+        )),
+        (None, false) => Err(NodeError::InvalidArgs(
+            "Specify a node name or --all".into(),
+        )),
+        (Some(name), false) => {
+            crate::validation::validate_node_name(name).map_err(NodeError::InvalidArgs)?;
+            nodes
+                .iter()
+                .find(|n| n.name == name)
+                .cloned()
+                .map(|n| vec![n])
+                .ok_or_else(|| NodeError::NotFound(name.to_string()))
+        }
+        (None, true) => {
+            let mut targets: Vec<NodeStateJson> = Vec::new();
+            for node in nodes.iter().filter(|n| n.base_node.is_empty()) {
+                if !targets.iter().any(|t| t.path == node.path) {
+                    targets.push(node.clone());
+                }
+            }
+            Ok(targets)
+        }
+    }
+}
+
+async fn update_one(client: &DaemonClient, node: &NodeStateJson) -> Result<()> {
+    println!("Updating {} (v{})", node.name, node.version);
+    client.send_node_command(&node.name, "update").await?;
+
+    let started = Instant::now();
+    let mut seen: Vec<String> = Vec::new();
+    let after = loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        let Some(state) = client
+            .node_states()
+            .await?
+            .nodes
+            .into_iter()
+            .find(|n| n.name == node.name)
+        else {
+            return Err(NodeError::NotFound(node.name.clone()));
+        };
+        for line in new_lines(&seen, &state.build_output) {
+            println!("  {}", line);
+        }
+        seen = state.build_output.clone();
+        if state.status != NodeStatus::Building as i32 {
+            break state;
+        }
+        if started.elapsed() > FOLLOW_TIMEOUT {
+            return Err(NodeError::CommandFailed(
+                "Timed out following the update (it may still be running)".into(),
+            ));
+        }
+    };
+
+    if after.build_output.last().map(String::as_str) != Some(SUCCESS_MARKER) {
+        return Err(NodeError::CommandFailed("update failed".into()));
+    }
+    if after.version == node.version {
+        println!("{}: v{} (version unchanged)", node.name, after.version);
+    } else {
+        println!("{}: v{} -> v{}", node.name, node.version, after.version);
+    }
+    Ok(())
+}
+
+/// Lines of `current` not already in `seen`. The daemon keeps only the
+/// last lines of output, so `current` may have dropped lines from the
+/// front of `seen`: match the longest tail of `seen` against its start.
+fn new_lines<'a>(seen: &[String], current: &'a [String]) -> &'a [String] {
+    let overlap = (0..=seen.len().min(current.len()))
+        .rev()
+        .find(|&k| seen[seen.len() - k..] == current[..k])
+        .unwrap_or(0);
+    &current[overlap..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn node(name: &str, path: &str, base_node: &str) -> NodeStateJson {
+        NodeStateJson {
+            name: name.into(),
+            path: path.into(),
+            status: NodeStatus::Running as i32,
+            installed: true,
+            autostart_enabled: false,
+            version: "0.1.0".into(),
+            description: String::new(),
+            node_type: "rust".into(),
+            is_built: true,
+            last_updated_ms: 0,
+            build_output: Vec::new(),
+            health_status: 0,
+            last_health_check_ms: 0,
+            machine_id: String::new(),
+            machine_hostname: String::new(),
+            machine_ips: Vec::new(),
+            base_node: base_node.into(),
+            config_path: String::new(),
+            config: String::new(),
+        }
+    }
+
+    #[test]
+    fn new_lines_follows_a_truncated_buffer() {
+        let seen = lines(&["a", "b", "c"]);
+        assert_eq!(new_lines(&[], &seen), seen.as_slice());
+        assert_eq!(new_lines(&seen, &lines(&["a", "b", "c", "d"])), ["d"]);
+        // The front was dropped while two lines arrived.
+        assert_eq!(new_lines(&seen, &lines(&["c", "d", "e"])), ["d", "e"]);
+        assert!(new_lines(&seen, &seen).is_empty());
+        assert_eq!(new_lines(&seen, &lines(&["x"])), ["x"]);
+    }
+
+    #[test]
+    fn all_selects_one_node_per_checkout() {
+        let nodes = vec![
+            node("rtsp-camera", "/nodes/rtsp-camera", ""),
+            node("rtsp-camera-terrace", "/nodes/rtsp-camera", "rtsp-camera"),
+            node("openmeteo", "/nodes/openmeteo", ""),
+        ];
+        let all = select_targets(&nodes, None, true).unwrap();
+        let names: Vec<&str> = all.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, ["rtsp-camera", "openmeteo"]);
+
+        let one = select_targets(&nodes, Some("rtsp-camera-terrace"), false).unwrap();
+        assert_eq!(one[0].name, "rtsp-camera-terrace");
+        assert!(select_targets(&nodes, Some("missing"), false).is_err());
+        assert!(select_targets(&nodes, None, false).is_err());
+        assert!(select_targets(&nodes, Some("openmeteo"), true).is_err());
+    }
+}
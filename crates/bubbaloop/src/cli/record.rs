@@ -0,0 +1,371 @@
+//! Record and play commands
+//!
+//! Usage:
+//!   bubbaloop record start --topics 'camera/**' --duration 10m
+//!   bubbaloop record start --topics 'camera/**' --topics 'telemetry/**' -o garage
+//!   bubbaloop record stop
+//!   bubbaloop record status
+//!   bubbaloop play garage.mcap --rate 2x
+//!
+//! These commands drive the `mcap-recorder` node (and `mcap-player` for
+//! playback) over Zenoh, so capturing data does not require editing node
+//! configs. Each command is a CBOR [`RecorderRequest`] sent as a query to
+//! `bubbaloop/global/{machine}/{node}/command`; the node answers with a
+//! CBOR [`RecorderReply`]. Topic patterns are passed through verbatim and
+//! resolved by the node.
+
+use std::time::Duration;
+
+use argh::FromArgs;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use zenoh::query::QueryTarget;
+
+use crate::daemon::gateway;
+
+/// How long to wait for the recorder or player to answer.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Error)]
+pub enum RecordError {
+    #[error("Zenoh error: {0}")]
+    Zenoh(String),
+    #[error("CBOR error: {0}")]
+    Cbor(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("No reply from {0} - is the node running?")]
+    NoReply(String),
+    #[error("{0}")]
+    Rejected(String),
+}
+
+pub type Result<T> = std::result::Result<T, RecordError>;
+
+/// A command for a recorder or player node.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecorderRequest {
+    /// `start`, `stop` or `status` for the recorder; `play` for the player.
+    pub command: String,
+    /// Key expressions to record or replay; empty means the node's default.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<String>,
+    /// `start`: stop recording after this many seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_s: Option<u64>,
+    /// `start`: output file name; `play`: file to replay.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// `play`: playback speed, 1.0 being real time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate: Option<f64>,
+    /// `play`: start over when the file ends.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub looped: bool,
+}
+
+/// A recorder or player node's answer to a [`RecorderRequest`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecorderReply {
+    pub success: bool,
+    #[serde(default)]
+    pub message: String,
+    /// The file being recorded or played, if any.
+    #[serde(default)]
+    pub file: Option<String>,
+}
+
+/// Record Zenoh topics with the mcap-recorder node
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "record")]
+pub struct RecordCommand {
+    #[argh(subcommand)]
+    action: RecordAction,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand)]
+enum RecordAction {
+    Start(StartArgs),
+    Stop(StopArgs),
+    Status(StatusArgs),
+}
+
+/// Start recording
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "start")]
+struct StartArgs {
+    /// key expression to record (repeatable; default: the recorder's config)
+    #[argh(option)]
+    topics: Vec<String>,
+
+    /// stop after this long, e.g. 90s, 10m, 1h (default: until `record stop`)
+    #[argh(option, short = 'd')]
+    duration: Option<String>,
+
+    /// output file name (default: chosen by the recorder)
+    #[argh(option, short = 'o')]
+    output: Option<String>,
+
+    /// recorder node instance (default: mcap-recorder)
+    #[argh(option, default = "String::from(\"mcap-recorder\")")]
+    node: String,
+
+    /// machine running the recorder (default: the --machine target)
+    #[argh(option, short = 'm')]
+    machine: Option<String>,
+}
+
+/// Stop recording
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "stop")]
+struct StopArgs {
+    /// recorder node instance (default: mcap-recorder)
+    #[argh(option, default = "String::from(\"mcap-recorder\")")]
+    node: String,
+
+    /// machine running the recorder (default: the --machine target)
+    #[argh(option, short = 'm')]
+    machine: Option<String>,
+}
+
+/// Show what the recorder is doing
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "status")]
+struct StatusArgs {
+    /// recorder node instance (default: mcap-recorder)
+    #[argh(option, default = "String::from(\"mcap-recorder\")")]
+    node: String,
+
+    /// machine running the recorder (default: the --machine target)
+    #[argh(option, short = 'm')]
+    machine: Option<String>,
+}
+
+/// Replay a recording with the mcap-player node
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "play")]
+pub struct PlayCommand {
+    /// recording to replay, as seen by the player node
+    #[argh(positional)]
+    file: String,
+
+    /// playback speed, e.g. 2x or 0.5 (default: 1x)
+    #[argh(option, short = 'r', default = "String::from(\"1x\")")]
+    rate: String,
+
+    /// replay only these key expressions (repeatable)
+    #[argh(option)]
+    topics: Vec<String>,
+
+    /// start over when the recording ends
+    #[argh(switch, short = 'l', long = "loop")]
+    looped: bool,
+
+    /// player node instance (default: mcap-player)
+    #[argh(option, default = "String::from(\"mcap-player\")")]
+    node: String,
+
+    /// machine running the player (default: the --machine target)
+    #[argh(option, short = 'm')]
+    machine: Option<String>,
+}
+
+impl RecordCommand {
+    pub async fn run(self) -> Result<()> {
+        let (node, machine, request) = match self.action {
+            RecordAction::Start(args) => {
+                let duration_s = args.duration.as_deref().map(parse_duration).transpose()?;
+                let request = RecorderRequest {
+                    command: "start".to_string(),
+                    topics: args.topics,
+                    duration_s,
+                    file: args.output,
+                    ..Default::default()
+                };
+                (args.node, args.machine, request)
+            }
+            RecordAction::Stop(args) => (args.node, args.machine, request("stop")),
+            RecordAction::Status(args) => (args.node, args.machine, request("status")),
+        };
+        let reply = send(&node, machine, &request).await?;
+        print_reply(&reply);
+        Ok(())
+    }
+}
+
+impl PlayCommand {
+    pub async fn run(self) -> Result<()> {
+        let request = RecorderRequest {
+            command: "play".to_string(),
+            topics: self.topics,
+            file: Some(self.file),
+            rate: Some(parse_rate(&self.rate)?),
+            looped: self.looped,
+            ..Default::default()
+        };
+        let reply = send(&self.node, self.machine, &request).await?;
+        print_reply(&reply);
+        Ok(())
+    }
+}
+
+fn request(command: &str) -> RecorderRequest {
+    RecorderRequest {
+        command: command.to_string(),
+        ..Default::default()
+    }
+}
+
+/// `bubbaloop/global/{machine}/{node}/command`
+pub fn command_topic(machine_id: &str, node: &str) -> String {
+    format!("bubbaloop/global/{}/{}/command", machine_id, node)
+}
+
+async fn send(
+    node: &str,
+    machine: Option<String>,
+    request: &RecorderRequest,
+) -> Result<RecorderReply> {
+    let machine = machine.unwrap_or_else(|| crate::cli::target::get().machine_id());
+    let key = command_topic(&machine, node);
+    let payload = gateway::to_cbor(request).map_err(|e| RecordError::Cbor(e.to_string()))?;
+    let session = crate::cli::zenoh_session::create_zenoh_session(None)
+        .await
+        .map_err(|e| RecordError::Zenoh(e.to_string()))?;
+    let replies = session
+        .get(&key)
+        .payload(payload)
+        .encoding(zenoh::bytes::Encoding::APPLICATION_CBOR)
+        .target(QueryTarget::BestMatching)
+        .timeout(COMMAND_TIMEOUT)
+        .await
+        .map_err(|e| RecordError::Zenoh(e.to_string()))?;
+    let reply = replies
+        .recv_async()
+        .await
+        .map_err(|_| RecordError::NoReply(key))?;
+    let sample = reply.into_result().map_err(|e| {
+        RecordError::Rejected(String::from_utf8_lossy(&e.payload().to_bytes()).into_owned())
+    })?;
+    let reply: RecorderReply = gateway::from_cbor(&sample.payload().to_bytes())
+        .map_err(|e| RecordError::Cbor(e.to_string()))?;
+    if !reply.success {
+        return Err(RecordError::Rejected(reply.message));
+    }
+    Ok(reply)
+}
+
+fn print_reply(reply: &RecorderReply) {
+    if !reply.message.is_empty() {
+        println!("{}", reply.message);
+    }
+    if let Some(file) = &reply.file {
+        println!("File: {}", file);
+    }
+}
+
+/// `90`, `90s`, `10m`, `1h` to seconds.
+fn parse_duration(s: &str) -> Result<u64> {
+    let s = s.trim();
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(at) => s.split_at(at),
+        None => (s, "s"),
+    };
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        _ => 0,
+    };
+    match number.parse::<u64>() {
+        Ok(n) if n > 0 && multiplier > 0 => Ok(n * multiplier),
+        _ => Err(RecordError::InvalidArgument(format!(
+            "invalid duration '{}' (expected e.g. 90s, 10m, 1h)",
+            s
+        ))),
+    }
+}
+
+/// `2x`, `0.5x`, `2` to a playback speed.
+fn parse_rate(s: &str) -> Result<f64> {
+    let number = s.trim().strip_suffix('x').unwrap_or(s.trim());
+    match number.parse::<f64>() {
+        Ok(rate) if rate > 0.0 && rate.is_finite() => Ok(rate),
+        _ => Err(RecordError::InvalidArgument(format!(
+            "invalid rate '{}' (expected e.g. 2x or 0.5)",
+            s
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_and_rates_parse() {
+        assert_eq!(parse_duration("90").unwrap(), 90);
+        assert_eq!(parse_duration("90s").unwrap(), 90);
+        assert_eq!(parse_duration("10m").unwrap(), 600);
+        assert_eq!(parse_duration("1h").unwrap(), 3600);
+        assert!(parse_duration("0m").is_err());
+        assert!(parse_duration("10d").is_err());
+        assert!(parse_duration("m").is_err());
+
+        assert_eq!(parse_rate("2x").unwrap(), 2.0);
+        assert_eq!(parse_rate("0.5").unwrap(), 0.5);
+        assert!(parse_rate("0x").is_err());
+        assert!(parse_rate("fast").is_err());
+    }
+
+    #[test]
+    fn request_round_trips_and_omits_unset_fields() {
+        let request = RecorderRequest {
+            command: "start".to_string(),
+            topics: vec!["camera/**".to_string()],
+            duration_s: Some(600),
+            ..Default::default()
+        };
+        let bytes = gateway::to_cbor(&request).unwrap();
+        let value: ciborium::Value = gateway::from_cbor(&bytes).unwrap();
+        let keys: Vec<String> = value
+            .as_map()
+            .unwrap()
+            .iter()
+            .filter_map(|(k, _)| k.as_text().map(str::to_string))
+            .collect();
+        assert_eq!(keys, vec!["command", "topics", "duration_s"]);
+        assert_eq!(
+            gateway::from_cbor::<RecorderRequest>(&bytes).unwrap(),
+            request
+        );
+    }
+
+    #[test]
+    fn test_cli_subcommands_parse() {
+        let cmd = RecordCommand::from_args(
+            &["record"],
+            &["start", "--topics", "a/**", "--topics", "b/**", "-d", "10m"],
+        )
+        .unwrap();
+        let RecordAction::Start(args) = cmd.action else {
+            panic!("expected start");
+        };
+        assert_eq!(args.topics, vec!["a/**", "b/**"]);
+        assert_eq!(args.node, "mcap-recorder");
+
+        let cmd = RecordCommand::from_args(&["record"], &["stop", "-m", "orin"]).unwrap();
+        assert!(
+            matches!(cmd.action, RecordAction::Stop(ref a) if a.machine.as_deref() == Some("orin"))
+        );
+
+        let cmd = PlayCommand::from_args(&["play"], &["g.mcap", "--rate", "2x", "--loop"]).unwrap();
+        assert_eq!((cmd.file.as_str(), cmd.rate.as_str()), ("g.mcap", "2x"));
+        assert!(cmd.looped);
+        assert_eq!(
+            command_topic("orin", &cmd.node),
+            "bubbaloop/global/orin/mcap-player/command"
+        );
+    }
+}
@@ -0,0 +1,869 @@
+//! `bubbaloop schema` — inspect the protobuf schemas nodes publish with.
+//!
+//! Usage:
+//!   bubbaloop schema list
+//!   bubbaloop schema list --all --json
+//!   bubbaloop schema dump tapo_terrace
+//!   bubbaloop schema dump tapo_terrace --message camera.v1.CompressedImage
+//!   bubbaloop schema dump tapo_terrace -m jetson_orin --format json
+//!   bubbaloop schema registry
+//!   bubbaloop schema registry camera.v1.CompressedImage --version 2
+//!
+//! Every node serves the FileDescriptorSet of its messages on
+//! `bubbaloop/{scope}/{machine}/{node}/schema`. `dump` renders it back into
+//! `.proto` source, so consumers can be written without the node's repo.
+//! `--message` prints one type plus the types it references. `registry`
+//! reads the daemon's fleet-wide registry, which keeps every definition of
+//! every message type seen on any machine.
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use argh::FromArgs;
+use prost_reflect::{
+    Cardinality, DescriptorPool, EnumDescriptor, FieldDescriptor, FileDescriptor, Kind,
+    MessageDescriptor, Syntax,
+};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::cli::daemon_client::{DaemonClient, DaemonClientError};
+use crate::daemon::decode::fetch_pool;
+use crate::daemon::gateway::{RegisteredSchema, SchemaQuery};
+
+/// How long `list` collects replies from schema queryables.
+const LIST_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Error)]
+pub enum SchemaError {
+    #[error("Zenoh error: {0}")]
+    Zenoh(String),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
+    #[error("Daemon error: {0}")]
+    Daemon(#[from] DaemonClientError),
+}
+
+pub type Result<T> = std::result::Result<T, SchemaError>;
+
+/// Inspect the protobuf schemas served by nodes
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "schema")]
+pub struct SchemaCommand {
+    #[argh(subcommand)]
+    action: SchemaSubcommand,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand)]
+enum SchemaSubcommand {
+    List(ListArgs),
+    Dump(DumpArgs),
+    Registry(RegistryArgs),
+}
+
+/// List nodes serving a schema and their message types
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "list")]
+struct ListArgs {
+    /// include nodes on every machine, not just the target one
+    #[argh(switch)]
+    all: bool,
+
+    /// output as JSON
+    #[argh(switch)]
+    json: bool,
+}
+
+/// Print a node's schema as .proto source
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "dump")]
+struct DumpArgs {
+    /// node name (e.g. "tapo_terrace")
+    #[argh(positional)]
+    node: String,
+
+    /// machine the node runs on (default: the --machine target)
+    #[argh(option, short = 'm')]
+    machine: Option<String>,
+
+    /// print only this message type (full or short name) and the types it uses
+    #[argh(option)]
+    message: Option<String>,
+
+    /// output format: proto, json (default: proto)
+    #[argh(option, default = "String::from(\"proto\")")]
+    format: String,
+}
+
+/// Show message types from the daemon's fleet-wide schema registry
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "registry")]
+struct RegistryArgs {
+    /// message type (full name); omit to list every registered type
+    #[argh(positional)]
+    type_name: Option<String>,
+
+    /// print this version's .proto source (default: the latest)
+    #[argh(option)]
+    version: Option<u32>,
+
+    /// output as JSON
+    #[argh(switch)]
+    json: bool,
+}
+
+/// One node's schema, as shown by `list`.
+#[derive(Debug, Serialize)]
+struct NodeSchema {
+    node: String,
+    machine: String,
+    messages: Vec<String>,
+}
+
+/// A message or enum type, as printed by `dump --format json`.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum TypeInfo {
+    Message {
+        name: String,
+        file: String,
+        fields: Vec<FieldInfo>,
+    },
+    Enum {
+        name: String,
+        file: String,
+        values: Vec<EnumValueInfo>,
+    },
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct FieldInfo {
+    name: String,
+    number: u32,
+    #[serde(rename = "type")]
+    type_name: String,
+    label: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oneof: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct EnumValueInfo {
+    name: String,
+    number: i32,
+}
+
+impl SchemaCommand {
+    pub async fn run(self) -> Result<()> {
+        match self.action {
+            SchemaSubcommand::List(args) => list(args).await,
+            SchemaSubcommand::Dump(args) => dump(args).await,
+            SchemaSubcommand::Registry(args) => registry(args).await,
+        }
+    }
+}
+
+async fn list(args: ListArgs) -> Result<()> {
+    let target = crate::cli::target::get();
+    let pattern = if args.all {
+        format!("bubbaloop/{}/*/*/schema", target.scope.as_str())
+    } else {
+        format!("{}/*/schema", target.key_prefix())
+    };
+    let session = crate::cli::zenoh_session::create_zenoh_session(None)
+        .await
+        .map_err(|e| SchemaError::Zenoh(e.to_string()))?;
+    let replies = session
+        .get(&pattern)
+        .timeout(LIST_TIMEOUT)
+        .await
+        .map_err(|e| SchemaError::Zenoh(e.to_string()))?;
+
+    let mut schemas = Vec::new();
+    while let Ok(reply) = replies.recv_async().await {
+        let Ok(sample) = reply.into_result() else {
+            continue;
+        };
+        let key = sample.key_expr().as_str();
+        let segments: Vec<&str> = key.split('/').collect();
+        if segments.len() != 5 {
+            continue;
+        }
+        match DescriptorPool::decode(sample.payload().to_bytes().as_ref()) {
+            Ok(pool) => schemas.push(NodeSchema {
+                node: segments[3].to_string(),
+                machine: segments[2].to_string(),
+                messages: message_names(&pool),
+            }),
+            Err(e) => log::warn!("Invalid schema on {}: {}", key, e),
+        }
+    }
+    schemas.sort_by(|a, b| (&a.machine, &a.node).cmp(&(&b.machine, &b.node)));
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&schemas)?);
+        return Ok(());
+    }
+    if schemas.is_empty() {
+        println!("No node served a schema on {}", pattern);
+        return Ok(());
+    }
+    println!("{:<24} {:<20} MESSAGES", "NODE", "MACHINE");
+    println!("{}", "-".repeat(80));
+    for schema in &schemas {
+        println!(
+            "{:<24} {:<20} {}",
+            schema.node,
+            schema.machine,
+            schema.messages.join(", ")
+        );
+    }
+    Ok(())
+}
+
+async fn dump(args: DumpArgs) -> Result<()> {
+    if args.format != "proto" && args.format != "json" {
+        return Err(SchemaError::InvalidArgument(format!(
+            "unknown format '{}' (expected proto or json)",
+            args.format
+        )));
+    }
+    let target = crate::cli::target::get();
+    let machine = args.machine.unwrap_or_else(|| target.machine_id());
+    let key = format!(
+        "bubbaloop/{}/{}/{}/schema",
+        target.scope.as_str(),
+        machine,
+        args.node
+    );
+    let session = crate::cli::zenoh_session::create_zenoh_session(None)
+        .await
+        .map_err(|e| SchemaError::Zenoh(e.to_string()))?;
+    let pool = fetch_pool(&session, &key)
+        .await
+        .ok_or_else(|| SchemaError::NotFound(format!("no schema on {}", key)))?;
+
+    match (&args.message, args.format.as_str()) {
+        (Some(name), "json") => {
+            let types = referenced_types(&find_message(&pool, name)?);
+            println!("{}", serde_json::to_string_pretty(&types_info(&types))?);
+        }
+        (Some(name), _) => print!("{}", render_message_tree(&find_message(&pool, name)?)),
+        (None, "json") => {
+            let types: Vec<TypeRef> = user_files(&pool)
+                .flat_map(|file| {
+                    file.enums()
+                        .map(TypeRef::Enum)
+                        .chain(file.messages().map(TypeRef::Message))
+                        .collect::<Vec<_>>()
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&types_info(&types))?);
+        }
+        (None, _) => {
+            for (i, file) in user_files(&pool).enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                print!("{}", render_file(&file));
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn registry(args: RegistryArgs) -> Result<()> {
+    if args.version.is_some() && args.type_name.is_none() {
+        return Err(SchemaError::InvalidArgument(
+            "--version needs a message type".to_string(),
+        ));
+    }
+    let client = DaemonClient::connect().await?;
+    let schemas = client
+        .schema_registry(&SchemaQuery {
+            type_name: args.type_name.clone(),
+        })
+        .await?;
+
+    let Some(type_name) = args.type_name else {
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&schemas)?);
+        } else if schemas.is_empty() {
+            println!("The schema registry is empty");
+        } else {
+            print!(
+                "{}",
+                render_registry(&schemas, crate::daemon::util::now_ms())
+            );
+        }
+        return Ok(());
+    };
+
+    let schema = schemas
+        .into_iter()
+        .next()
+        .ok_or_else(|| SchemaError::NotFound(format!("'{}' in the schema registry", type_name)))?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&schema)?);
+        return Ok(());
+    }
+    let version = match args.version {
+        Some(n) => schema
+            .versions
+            .iter()
+            .find(|v| v.version == n)
+            .ok_or_else(|| SchemaError::NotFound(format!("version {} of {}", n, type_name)))?,
+        None => schema
+            .versions
+            .last()
+            .ok_or_else(|| SchemaError::NotFound(format!("versions of {}", type_name)))?,
+    };
+    let now_ms = crate::daemon::util::now_ms();
+    for v in &schema.versions {
+        println!(
+            "// v{} {} last seen {}s ago on {}",
+            v.version,
+            v.fingerprint,
+            (now_ms - v.last_seen_ms).max(0) / 1000,
+            v.sources.join(", ")
+        );
+    }
+    println!();
+    let pool = DescriptorPool::decode(version.descriptor.as_slice()).map_err(|e| {
+        SchemaError::InvalidArgument(format!("registry descriptor is invalid: {}", e))
+    })?;
+    print!("{}", render_message_tree(&find_message(&pool, &type_name)?));
+    Ok(())
+}
+
+/// Table of registered types with their latest version.
+fn render_registry(schemas: &[RegisteredSchema], now_ms: i64) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:<48} {:>7} {:>7}  LAST SEEN",
+        "TYPE", "VERSION", "SOURCES"
+    );
+    let _ = writeln!(out, "{}", "-".repeat(80));
+    for schema in schemas {
+        let Some(latest) = schema.versions.last() else {
+            continue;
+        };
+        let _ = writeln!(
+            out,
+            "{:<48} {:>7} {:>7}  {}s ago",
+            schema.type_name,
+            format!("v{}", latest.version),
+            latest.sources.len(),
+            (now_ms - latest.last_seen_ms).max(0) / 1000
+        );
+    }
+    out
+}
+
+/// Files of a pool, minus the well-known `google/protobuf` ones.
+fn user_files(pool: &DescriptorPool) -> impl Iterator<Item = FileDescriptor> + '_ {
+    pool.files()
+        .filter(|file| !file.name().starts_with("google/protobuf/"))
+}
+
+/// Full names of the top-level messages in a pool.
+fn message_names(pool: &DescriptorPool) -> Vec<String> {
+    let mut names: Vec<String> = user_files(pool)
+        .flat_map(|file| {
+            file.messages()
+                .map(|m| m.full_name().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+/// Look up a message by full name, or by short name when that is unique.
+fn find_message(pool: &DescriptorPool, name: &str) -> Result<MessageDescriptor> {
+    if let Some(message) = pool.get_message_by_name(name) {
+        return Ok(message);
+    }
+    let matches: Vec<MessageDescriptor> = pool
+        .all_messages()
+        .filter(|m| m.name() == name && !m.is_map_entry())
+        .collect();
+    match matches.as_slice() {
+        [message] => Ok(message.clone()),
+        [] => Err(SchemaError::NotFound(format!("message type '{}'", name))),
+        _ => Err(SchemaError::InvalidArgument(format!(
+            "'{}' is ambiguous: {}",
+            name,
+            matches
+                .iter()
+                .map(|m| m.full_name())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))),
+    }
+}
+
+/// A top-level type to print.
+#[derive(Debug, Clone, PartialEq)]
+enum TypeRef {
+    Message(MessageDescriptor),
+    Enum(EnumDescriptor),
+}
+
+impl TypeRef {
+    fn full_name(&self) -> &str {
+        match self {
+            TypeRef::Message(m) => m.full_name(),
+            TypeRef::Enum(e) => e.full_name(),
+        }
+    }
+}
+
+/// `message` followed by every type it uses, directly or through other
+/// types. Nested types are represented by their outermost message, which
+/// prints them in place.
+fn referenced_types(message: &MessageDescriptor) -> Vec<TypeRef> {
+    let mut seen = BTreeSet::new();
+    let mut types = Vec::new();
+    let mut pending = vec![TypeRef::Message(outermost(message.clone()))];
+    while let Some(ty) = pending.pop() {
+        if !seen.insert(ty.full_name().to_string()) {
+            continue;
+        }
+        if let TypeRef::Message(m) = &ty {
+            let mut inner = vec![m.clone()];
+            while let Some(m) = inner.pop() {
+                for field in m.fields() {
+                    match field.kind() {
+                        Kind::Message(used) if used.is_map_entry() => inner.push(used),
+                        Kind::Message(used) => {
+                            pending.push(TypeRef::Message(outermost(used)));
+                        }
+                        Kind::Enum(used) => pending.push(match used.parent_message() {
+                            Some(parent) => TypeRef::Message(outermost(parent)),
+                            None => TypeRef::Enum(used),
+                        }),
+                        _ => {}
+                    }
+                }
+                inner.extend(m.child_messages());
+            }
+        }
+        types.push(ty);
+    }
+    types
+}
+
+fn outermost(mut message: MessageDescriptor) -> MessageDescriptor {
+    while let Some(parent) = message.parent_message() {
+        message = parent;
+    }
+    message
+}
+
+fn render_message_tree(message: &MessageDescriptor) -> String {
+    let mut out = String::new();
+    for (i, ty) in referenced_types(message).iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        match ty {
+            TypeRef::Message(m) => {
+                let _ = writeln!(out, "// {} ({})", m.full_name(), m.parent_file().name());
+                render_message(&mut out, m, 0);
+            }
+            TypeRef::Enum(e) => {
+                let _ = writeln!(out, "// {} ({})", e.full_name(), e.parent_file().name());
+                render_enum(&mut out, e, 0);
+            }
+        }
+    }
+    out
+}
+
+/// `.proto` source for one file of a descriptor pool.
+fn render_file(file: &FileDescriptor) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "// {}", file.name());
+    let syntax = match file.syntax() {
+        Syntax::Proto2 => "proto2",
+        Syntax::Proto3 => "proto3",
+    };
+    let _ = writeln!(out, "syntax = \"{}\";", syntax);
+    if !file.package_name().is_empty() {
+        let _ = writeln!(out, "\npackage {};", file.package_name());
+    }
+    let imports: Vec<FileDescriptor> = file.dependencies().collect();
+    if !imports.is_empty() {
+        out.push('\n');
+        for import in imports {
+            let _ = writeln!(out, "import \"{}\";", import.name());
+        }
+    }
+    for e in file.enums() {
+        out.push('\n');
+        render_enum(&mut out, &e, 0);
+    }
+    for m in file.messages() {
+        out.push('\n');
+        render_message(&mut out, &m, 0);
+    }
+    for service in file.services() {
+        out.push('\n');
+        let _ = writeln!(out, "service {} {{", service.name());
+        for method in service.methods() {
+            let _ = writeln!(
+                out,
+                "  rpc {}({}{}) returns ({}{});",
+                method.name(),
+                if method.is_client_streaming() {
+                    "stream "
+                } else {
+                    ""
+                },
+                method.input().full_name(),
+                if method.is_server_streaming() {
+                    "stream "
+                } else {
+                    ""
+                },
+                method.output().full_name()
+            );
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+fn render_message(out: &mut String, message: &MessageDescriptor, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let _ = writeln!(out, "{}message {} {{", indent, message.name());
+    for e in message.child_enums() {
+        render_enum(out, &e, depth + 1);
+    }
+    for m in message.child_messages().filter(|m| !m.is_map_entry()) {
+        render_message(out, &m, depth + 1);
+    }
+
+    // Fields in declaration order; a real oneof is printed as a block where
+    // its first member appears.
+    let mut oneofs_done = BTreeSet::new();
+    for field in message.fields() {
+        match field.containing_oneof().filter(|o| !o.is_synthetic()) {
+            Some(oneof) => {
+                if !oneofs_done.insert(oneof.name().to_string()) {
+                    continue;
+                }
+                let _ = writeln!(out, "{}  oneof {} {{", indent, oneof.name());
+                for member in oneof.fields() {
+                    let _ = writeln!(out, "{}    {}", indent, field_line(&member));
+                }
+                let _ = writeln!(out, "{}  }}", indent);
+            }
+            None => {
+                let _ = writeln!(out, "{}  {}", indent, field_line(&field));
+            }
+        }
+    }
+    let _ = writeln!(out, "{}}}", indent);
+}
+
+fn render_enum(out: &mut String, e: &EnumDescriptor, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let _ = writeln!(out, "{}enum {} {{", indent, e.name());
+    for value in e.values() {
+        let _ = writeln!(out, "{}  {} = {};", indent, value.name(), value.number());
+    }
+    let _ = writeln!(out, "{}}}", indent);
+}
+
+/// `repeated string tags = 3;`
+fn field_line(field: &FieldDescriptor) -> String {
+    let label = field_label(field);
+    let label = if label.is_empty() {
+        String::new()
+    } else {
+        format!("{} ", label)
+    };
+    format!(
+        "{}{} {} = {};",
+        label,
+        type_name(field),
+        field.name(),
+        field.number()
+    )
+}
+
+/// The label a field is declared with; empty for plain proto3 fields,
+/// oneof members and maps.
+fn field_label(field: &FieldDescriptor) -> &'static str {
+    if field.is_map() {
+        return "";
+    }
+    let in_oneof = field.containing_oneof();
+    match field.cardinality() {
+        Cardinality::Repeated => "repeated",
+        Cardinality::Required => "required",
+        Cardinality::Optional => match (field.parent_file().syntax(), in_oneof) {
+            (Syntax::Proto3, Some(oneof)) if oneof.is_synthetic() => "optional",
+            (Syntax::Proto2, None) => "optional",
+            _ => "",
+        },
+    }
+}
+
+fn type_name(field: &FieldDescriptor) -> String {
+    if field.is_map() {
+        if let Kind::Message(entry) = field.kind() {
+            return format!(
+                "map<{}, {}>",
+                type_name(&entry.map_entry_key_field()),
+                type_name(&entry.map_entry_value_field())
+            );
+        }
+    }
+    match field.kind() {
+        Kind::Double => "double".to_string(),
+        Kind::Float => "float".to_string(),
+        Kind::Int32 => "int32".to_string(),
+        Kind::Int64 => "int64".to_string(),
+        Kind::Uint32 => "uint32".to_string(),
+        Kind::Uint64 => "uint64".to_string(),
+        Kind::Sint32 => "sint32".to_string(),
+        Kind::Sint64 => "sint64".to_string(),
+        Kind::Fixed32 => "fixed32".to_string(),
+        Kind::Fixed64 => "fixed64".to_string(),
+        Kind::Sfixed32 => "sfixed32".to_string(),
+        Kind::Sfixed64 => "sfixed64".to_string(),
+        Kind::Bool => "bool".to_string(),
+        Kind::String => "string".to_string(),
+        Kind::Bytes => "bytes".to_string(),
+        Kind::Message(m) => m.full_name().to_string(),
+        Kind::Enum(e) => e.full_name().to_string(),
+    }
+}
+
+/// JSON view of `types` and everything nested in them, flattened.
+fn types_info(types: &[TypeRef]) -> Vec<TypeInfo> {
+    let mut out = Vec::new();
+    for ty in types {
+        match ty {
+            TypeRef::Message(m) => message_info(m, &mut out),
+            TypeRef::Enum(e) => out.push(enum_info(e)),
+        }
+    }
+    out
+}
+
+fn message_info(message: &MessageDescriptor, out: &mut Vec<TypeInfo>) {
+    out.push(TypeInfo::Message {
+        name: message.full_name().to_string(),
+        file: message.parent_file().name().to_string(),
+        fields: message
+            .fields()
+            .map(|field| FieldInfo {
+                name: field.name().to_string(),
+                number: field.number(),
+                type_name: type_name(&field),
+                label: field_label(&field),
+                oneof: field
+                    .containing_oneof()
+                    .filter(|o| !o.is_synthetic())
+                    .map(|o| o.name().to_string()),
+            })
+            .collect(),
+    });
+    for e in message.child_enums() {
+        out.push(enum_info(&e));
+    }
+    for m in message.child_messages().filter(|m| !m.is_map_entry()) {
+        message_info(&m, out);
+    }
+}
+
+fn enum_info(e: &EnumDescriptor) -> TypeInfo {
+    TypeInfo::Enum {
+        name: e.full_name().to_string(),
+        file: e.parent_file().name().to_string(),
+        values: e
+            .values()
+            .map(|v| EnumValueInfo {
+                name: v.name().to_string(),
+                number: v.number(),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_types::field_descriptor_proto::{Label, Type};
+    use prost_types::{
+        DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+        MessageOptions, OneofDescriptorProto,
+    };
+
+    fn field(name: &str, number: i32, ty: Type, label: Label) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_string()),
+            number: Some(number),
+            r#type: Some(ty as i32),
+            label: Some(label as i32),
+            json_name: Some(name.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// A proto3 file with a map, a oneof and a proto3 `optional` field.
+    fn sample_pool() -> DescriptorPool {
+        let mut labels = field("labels", 1, Type::Message, Label::Repeated);
+        labels.type_name = Some(".test.v1.Detection.LabelsEntry".to_string());
+        let mut by_id = field("by_id", 2, Type::Uint32, Label::Optional);
+        by_id.oneof_index = Some(0);
+        let mut by_name = field("by_name", 3, Type::String, Label::Optional);
+        by_name.oneof_index = Some(0);
+        let mut score = field("score", 4, Type::Float, Label::Optional);
+        score.oneof_index = Some(1);
+        score.proto3_optional = Some(true);
+
+        let file = FileDescriptorProto {
+            name: Some("test/detection.proto".to_string()),
+            package: Some("test.v1".to_string()),
+            syntax: Some("proto3".to_string()),
+            message_type: vec![DescriptorProto {
+                name: Some("Detection".to_string()),
+                field: vec![labels, by_id, by_name, score],
+                nested_type: vec![DescriptorProto {
+                    name: Some("LabelsEntry".to_string()),
+                    field: vec![
+                        field("key", 1, Type::String, Label::Optional),
+                        field("value", 2, Type::Int32, Label::Optional),
+                    ],
+                    options: Some(MessageOptions {
+                        map_entry: Some(true),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }],
+                oneof_decl: vec![
+                    OneofDescriptorProto {
+                        name: Some("target".to_string()),
+                        ..Default::default()
+                    },
+                    OneofDescriptorProto {
+                        name: Some("_score".to_string()),
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        DescriptorPool::from_file_descriptor_set(FileDescriptorSet { file: vec![file] }).unwrap()
+    }
+
+    #[test]
+    fn renders_builtin_header() {
+        let pool = crate::get_descriptor_pool();
+        let file = pool
+            .files()
+            .find(|f| f.package_name() == "bubbaloop.header.v1")
+            .unwrap();
+        let text = render_file(&file);
+        assert!(text.contains("syntax = \"proto3\";"));
+        assert!(text.contains("package bubbaloop.header.v1;"));
+        assert!(text.contains("message Header {\n  uint64 acq_time = 1;\n"));
+        assert!(text.contains("  string machine_id = 5;\n}"));
+    }
+
+    #[test]
+    fn renders_maps_oneofs_and_optional() {
+        let pool = sample_pool();
+        let text = render_file(&pool.files().next().unwrap());
+        let expected = "message Detection {\n\
+                        \x20 map<string, int32> labels = 1;\n\
+                        \x20 oneof target {\n\
+                        \x20   uint32 by_id = 2;\n\
+                        \x20   string by_name = 3;\n\
+                        \x20 }\n\
+                        \x20 optional float score = 4;\n\
+                        }\n";
+        assert!(text.ends_with(expected), "{}", text);
+        assert!(!text.contains("LabelsEntry"));
+    }
+
+    #[test]
+    fn message_tree_includes_referenced_types() {
+        let pool = crate::get_descriptor_pool();
+        let list = find_message(pool, "NodeList").unwrap();
+        let names: Vec<String> = referenced_types(&list)
+            .iter()
+            .map(|t| t.full_name().to_string())
+            .collect();
+        assert_eq!(names[0], "bubbaloop.daemon.v1.NodeList");
+        assert!(names.contains(&"bubbaloop.daemon.v1.NodeState".to_string()));
+        assert!(names.contains(&"bubbaloop.daemon.v1.NodeStatus".to_string()));
+
+        let text = render_message_tree(&list);
+        assert!(text.contains("repeated bubbaloop.daemon.v1.NodeState nodes = 1;"));
+        assert!(text.contains("enum NodeStatus {\n  NODE_STATUS_UNKNOWN = 0;"));
+    }
+
+    #[test]
+    fn find_message_by_short_or_full_name() {
+        let pool = sample_pool();
+        assert!(find_message(&pool, "Detection").is_ok());
+        assert!(find_message(&pool, "test.v1.Detection").is_ok());
+        assert!(matches!(
+            find_message(&pool, "Missing"),
+            Err(SchemaError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn json_lists_fields_with_labels() {
+        let pool = sample_pool();
+        let detection = find_message(&pool, "Detection").unwrap();
+        let info = types_info(&[TypeRef::Message(detection)]);
+        let TypeInfo::Message { fields, .. } = &info[0] else {
+            panic!("expected a message");
+        };
+        assert_eq!(fields[0].type_name, "map<string, int32>");
+        assert_eq!(fields[1].oneof.as_deref(), Some("target"));
+        assert_eq!(fields[3].label, "optional");
+        assert_eq!(fields[3].oneof, None);
+    }
+
+    #[test]
+    fn registry_table_shows_latest_version() {
+        use crate::daemon::gateway::SchemaVersion;
+        let version = |version, last_seen_ms, sources: &[&str]| SchemaVersion {
+            version,
+            fingerprint: format!("{:016x}", version),
+            first_seen_ms: 0,
+            last_seen_ms,
+            sources: sources.iter().map(|s| s.to_string()).collect(),
+            descriptor: Vec::new(),
+        };
+        let schemas = vec![RegisteredSchema {
+            type_name: "camera.v1.CompressedImage".to_string(),
+            versions: vec![
+                version(1, 1_000, &["orin/cam"]),
+                version(2, 9_000, &["orin/cam", "pi/cam"]),
+            ],
+        }];
+        let text = render_registry(&schemas, 14_000);
+        let row = text.lines().nth(2).unwrap();
+        assert!(row.starts_with("camera.v1.CompressedImage"));
+        assert!(row.contains("v2"));
+        assert!(row.ends_with("2  5s ago"));
+    }
+}
@@ -0,0 +1,326 @@
+//! `bubbaloop self-update` — replace this binary with a newer release.
+//!
+//! Usage:
+//!   bubbaloop self-update --check
+//!   bubbaloop self-update
+//!   bubbaloop self-update --version v0.0.12
+//!   bubbaloop self-update --rollback
+//!
+//! Downloads `bubbaloop-linux-{amd64,arm64}` from the GitHub release and
+//! verifies it against the `.sha256` published next to it. The new binary
+//! is written beside the current one, must run `--version` successfully,
+//! and is then renamed over it in one step, so the executable path always
+//! holds a working binary. The previous binary is kept as `<exe>.bak` for
+//! `--rollback`; a failed swap leaves an existing `.bak` untouched.
+
+use std::path::{Path, PathBuf};
+
+use argh::FromArgs;
+use thiserror::Error;
+
+use crate::marketplace::{self, MarketplaceError};
+
+/// GitHub repository the releases come from.
+const RELEASE_REPO: &str = "kornia/bubbaloop";
+
+#[derive(Debug, Error)]
+pub enum SelfUpdateError {
+    #[error("{0}")]
+    Download(#[from] MarketplaceError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid release metadata: {0}")]
+    Release(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+    #[error("Downloaded binary does not run: {0}")]
+    BrokenBinary(String),
+    #[error("No previous binary at {0}")]
+    NoBackup(String),
+}
+
+pub type Result<T> = std::result::Result<T, SelfUpdateError>;
+
+/// Update bubbaloop to the latest release
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "self-update")]
+pub struct SelfUpdateCommand {
+    /// only report whether an update is available
+    #[argh(switch)]
+    check: bool,
+
+    /// install this release tag instead of the latest (e.g. v0.0.12)
+    #[argh(option)]
+    version: Option<String>,
+
+    /// reinstall even when already up to date
+    #[argh(switch, short = 'f')]
+    force: bool,
+
+    /// restore the binary replaced by the last update
+    #[argh(switch)]
+    rollback: bool,
+}
+
+impl SelfUpdateCommand {
+    pub async fn run(self) -> Result<()> {
+        let exe = std::env::current_exe()?.canonicalize()?;
+        if self.rollback {
+            return rollback(&exe);
+        }
+        let current = env!("CARGO_PKG_VERSION");
+        let pinned = self.version.is_some();
+        let tag = match self.version {
+            Some(tag) => {
+                validate_tag(&tag)?;
+                tag
+            }
+            None => tokio::task::spawn_blocking(latest_tag)
+                .await
+                .map_err(|e| SelfUpdateError::Release(e.to_string()))??,
+        };
+        let newer = crate::cli::node::diff::is_newer(&tag, current);
+
+        if self.check {
+            if newer {
+                println!("Update available: {} -> {}", current, tag);
+                println!("Run: bubbaloop self-update");
+            } else {
+                println!("bubbaloop {} is up to date (latest: {})", current, tag);
+            }
+            return Ok(());
+        }
+        let downgrade = pinned && is_other_release(&tag, current);
+        if !(newer || downgrade || self.force) {
+            println!("bubbaloop {} is up to date (latest: {})", current, tag);
+            return Ok(());
+        }
+
+        let arch = marketplace::detect_arch()?;
+        println!("Downloading bubbaloop {} for linux-{}...", tag, arch);
+        let exe_clone = exe.clone();
+        let tag_clone = tag.clone();
+        tokio::task::spawn_blocking(move || install(&exe_clone, &tag_clone, arch))
+            .await
+            .map_err(|e| SelfUpdateError::Release(e.to_string()))??;
+        println!(
+            "Updated bubbaloop {} -> {} ({})",
+            current,
+            tag,
+            exe.display()
+        );
+        println!("Previous binary kept at {}", backup_path(&exe).display());
+        println!("Restart the daemon to use it: bubbaloop daemon restart");
+        Ok(())
+    }
+}
+
+/// Whether `--version` names a release other than the running one, which
+/// is installed even when it is older (a deliberate downgrade).
+fn is_other_release(tag: &str, current: &str) -> bool {
+    tag.trim_start_matches('v') != current
+}
+
+/// Tag of the latest GitHub release.
+fn latest_tag() -> Result<String> {
+    let url = format!(
+        "https://api.github.com/repos/{}/releases/latest",
+        RELEASE_REPO
+    );
+    let body = marketplace::download_text(&url)?;
+    parse_tag(&body)
+}
+
+fn parse_tag(release_json: &str) -> Result<String> {
+    let release: serde_json::Value =
+        serde_json::from_str(release_json).map_err(|e| SelfUpdateError::Release(e.to_string()))?;
+    let tag = release
+        .get("tag_name")
+        .and_then(|t| t.as_str())
+        .ok_or_else(|| SelfUpdateError::Release("no tag_name".to_string()))?;
+    validate_tag(tag)?;
+    Ok(tag.to_string())
+}
+
+/// Tags end up in URLs; allow only version-like characters.
+fn validate_tag(tag: &str) -> Result<()> {
+    let valid = !tag.is_empty()
+        && !tag.starts_with('-')
+        && tag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | '+'));
+    if valid {
+        Ok(())
+    } else {
+        Err(SelfUpdateError::InvalidArgument(format!(
+            "invalid release tag '{}'",
+            tag
+        )))
+    }
+}
+
+fn asset_url(tag: &str, arch: &str) -> String {
+    format!(
+        "https://github.com/{}/releases/download/{}/bubbaloop-linux-{}",
+        RELEASE_REPO, tag, arch
+    )
+}
+
+/// `<exe>.bak`
+fn backup_path(exe: &Path) -> PathBuf {
+    let mut name = exe.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    exe.with_file_name(name)
+}
+
+/// Download, verify and swap in the release binary.
+fn install(exe: &Path, tag: &str, arch: &str) -> Result<()> {
+    let url = asset_url(tag, arch);
+    let expected = marketplace::download_text(&format!("{}.sha256", url))?;
+
+    // Same directory as the executable, so the final rename is atomic.
+    let mut name = exe.file_name().unwrap_or_default().to_os_string();
+    name.push(".new");
+    let new = exe.with_file_name(name);
+    let result = marketplace::download_file(&url, &new)
+        .and_then(|()| marketplace::verify_sha256(&new, &expected))
+        .and_then(|()| marketplace::set_executable(&new))
+        .map_err(SelfUpdateError::from)
+        .and_then(|()| smoke_test(&new))
+        .and_then(|()| swap(exe, &new));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&new);
+    }
+    result
+}
+
+/// Run `<binary> --version` to catch wrong-arch or truncated downloads.
+fn smoke_test(binary: &Path) -> Result<()> {
+    let output = std::process::Command::new(binary)
+        .arg("--version")
+        .output()
+        .map_err(|e| SelfUpdateError::BrokenBinary(e.to_string()))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if output.status.success() && stdout.starts_with("bubbaloop ") {
+        Ok(())
+    } else {
+        Err(SelfUpdateError::BrokenBinary(format!(
+            "`--version` exited with {}",
+            output.status
+        )))
+    }
+}
+
+/// Rename `new` over `exe`, keeping the previous binary as its backup.
+///
+/// The backup is first linked (or copied) to a temporary name, so `exe`
+/// is never missing, and only replaces `<exe>.bak` once `new` is in
+/// place.
+fn swap(exe: &Path, new: &Path) -> Result<()> {
+    let backup = backup_path(exe);
+    let mut name = backup.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    let pending = backup.with_file_name(name);
+    let _ = std::fs::remove_file(&pending);
+    if std::fs::hard_link(exe, &pending).is_err() {
+        std::fs::copy(exe, &pending)?;
+    }
+    if let Err(e) = std::fs::rename(new, exe) {
+        log::warn!(
+            "self-update: install failed ({}), keeping previous binary",
+            e
+        );
+        let _ = std::fs::remove_file(&pending);
+        return Err(e.into());
+    }
+    std::fs::rename(&pending, &backup)?;
+    Ok(())
+}
+
+fn rollback(exe: &Path) -> Result<()> {
+    let backup = backup_path(exe);
+    if !backup.exists() {
+        return Err(SelfUpdateError::NoBackup(backup.display().to_string()));
+    }
+    smoke_test(&backup)?;
+    std::fs::rename(&backup, exe)?;
+    println!("Restored previous binary to {}", exe.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_release_tag() {
+        let json = r#"{"tag_name": "v0.0.13", "name": "v0.0.13"}"#;
+        assert_eq!(parse_tag(json).unwrap(), "v0.0.13");
+        assert!(parse_tag(r#"{"message": "Not Found"}"#).is_err());
+        assert!(parse_tag(r#"{"tag_name": "v1/../../x"}"#).is_err());
+        assert!(validate_tag("--help").is_err());
+    }
+
+    #[test]
+    fn asset_urls_and_backup_names() {
+        assert_eq!(
+            asset_url("v0.0.13", "arm64"),
+            "https://github.com/kornia/bubbaloop/releases/download/v0.0.13/bubbaloop-linux-arm64"
+        );
+        assert_eq!(
+            backup_path(Path::new("/home/u/.bubbaloop/bin/bubbaloop")),
+            Path::new("/home/u/.bubbaloop/bin/bubbaloop.bak")
+        );
+        assert!(is_other_release("v0.0.11", "0.0.12-dev"));
+        assert!(!is_other_release("v0.0.12", "0.0.12"));
+    }
+
+    #[test]
+    fn swap_keeps_backup_and_a_failed_swap_keeps_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("bubbaloop");
+        let new = dir.path().join("bubbaloop.new");
+        std::fs::write(&exe, "old").unwrap();
+        std::fs::write(&new, "new").unwrap();
+
+        swap(&exe, &new).unwrap();
+        assert_eq!(std::fs::read_to_string(&exe).unwrap(), "new");
+        assert_eq!(std::fs::read_to_string(backup_path(&exe)).unwrap(), "old");
+        assert!(!new.exists());
+
+        // A failed install leaves the current binary and the last backup
+        // in place.
+        assert!(swap(&exe, &dir.path().join("missing")).is_err());
+        assert_eq!(std::fs::read_to_string(&exe).unwrap(), "new");
+        assert_eq!(std::fs::read_to_string(backup_path(&exe)).unwrap(), "old");
+        let names: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(names.len(), 2, "{names:?}");
+    }
+
+    #[test]
+    fn swap_never_leaves_exe_missing() {
+        use std::os::unix::fs::MetadataExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let exe = dir.path().join("bubbaloop");
+        std::fs::write(&exe, "v1").unwrap();
+        for version in ["v2", "v3"] {
+            let new = dir.path().join("bubbaloop.new");
+            std::fs::write(&new, version).unwrap();
+            let (old_ino, new_ino) = (
+                std::fs::metadata(&exe).unwrap().ino(),
+                std::fs::metadata(&new).unwrap().ino(),
+            );
+            swap(&exe, &new).unwrap();
+            // The old binary was never moved away from `exe`: the backup
+            // is a link to it, and `new` itself was renamed over `exe`.
+            assert_eq!(std::fs::metadata(&exe).unwrap().ino(), new_ino);
+            assert_eq!(std::fs::metadata(backup_path(&exe)).unwrap().ino(), old_ino);
+            assert_eq!(std::fs::read_to_string(&exe).unwrap(), version);
+        }
+        assert_eq!(std::fs::read_to_string(backup_path(&exe)).unwrap(), "v2");
+    }
+}
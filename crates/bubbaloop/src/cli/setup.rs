@@ -0,0 +1,338 @@
+//! `bubbaloop setup` — interactive first-run setup.
+//!
+//! Walks a fresh machine to a working installation: picks the machine id,
+//! installs zenohd and the daemon as systemd user services, enables
+//! lingering so they survive logout and reboots, optionally installs
+//! starter nodes, and finally checks the daemon answers over Zenoh.
+//! Every step is safe to re-run; existing units are rewritten in place.
+
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use argh::FromArgs;
+use thiserror::Error;
+
+use crate::cli::doctor::fixes::FixAction;
+use crate::daemon::registry::get_bubbaloop_home;
+use crate::daemon::systemd::SystemdClient;
+
+/// Nodes offered at the end of the setup.
+const STARTER_NODES: &[(&str, &str)] = &[(
+    "system-telemetry",
+    "CPU, memory, disk and temperature of this machine",
+)];
+
+/// How long to wait for the freshly started daemon to answer.
+pub(crate) const DAEMON_READY_TIMEOUT: Duration = Duration::from_secs(20);
+
+pub(crate) const ZENOHD_UNIT: &str = "bubbaloop-zenohd.service";
+pub(crate) const DAEMON_UNIT: &str = "bubbaloop-daemon.service";
+
+#[derive(Debug, Error)]
+pub enum SetupError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("systemd error: {0}")]
+    Systemd(String),
+    #[error("{0}")]
+    Failed(String),
+    #[error("Invalid machine id '{0}' (use letters, digits and underscores)")]
+    InvalidMachineId(String),
+}
+
+pub type Result<T> = std::result::Result<T, SetupError>;
+
+/// Set up zenohd, the daemon and starter nodes on this machine
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "setup")]
+pub struct SetupCommand {
+    /// accept all defaults without asking
+    #[argh(switch, short = 'y')]
+    pub yes: bool,
+
+    /// machine id for this machine (default: hostname)
+    #[argh(option)]
+    pub machine_id: Option<String>,
+
+    /// do not offer starter nodes
+    #[argh(switch)]
+    pub no_nodes: bool,
+}
+
+impl SetupCommand {
+    pub async fn run(&self) -> Result<()> {
+        let prompt = Prompt {
+            interactive: !self.yes && io::stdin().is_terminal(),
+        };
+        println!("\n  Bubbaloop setup\n");
+
+        // Machine id
+        let default_id = crate::daemon::util::get_machine_id();
+        let machine_id = match &self.machine_id {
+            Some(id) => id.clone(),
+            None => prompt.ask("Machine id", &default_id)?,
+        };
+        let machine_id = normalize_machine_id(&machine_id)?;
+        let custom_id = (machine_id != default_id).then_some(machine_id.as_str());
+        println!("  Machine id: {}\n", machine_id);
+
+        // Zenoh router
+        println!("[1/4] Zenoh router");
+        let zenohd = find_zenohd().ok_or_else(|| {
+            SetupError::Failed(
+                "zenohd not found in ~/.bubbaloop/bin or PATH. Install it with scripts/install.sh or from https://zenoh.io".into(),
+            )
+        })?;
+        let config = get_bubbaloop_home().join("zenoh/zenohd.json5");
+        if !config.exists() {
+            let msg = FixAction::CreateZenohConfig
+                .execute()
+                .await
+                .map_err(|e| SetupError::Failed(e.to_string()))?;
+            println!("  {}", msg);
+        }
+        let systemd = SystemdClient::new().await.map_err(|e| {
+            SetupError::Systemd(format!(
+                "cannot reach the systemd user session ({}). Is this a login session?",
+                e
+            ))
+        })?;
+        write_unit(ZENOHD_UNIT, &zenohd_unit(&zenohd, &config))?;
+
+        // Daemon
+        println!("[2/4] Daemon");
+        let exe = std::env::current_exe()?;
+        write_unit(DAEMON_UNIT, &daemon_unit(&exe, custom_id))?;
+        if let Err(e) = systemd.daemon_reload().await {
+            eprintln!("  Warning: systemd daemon-reload failed: {}", e);
+        }
+        for unit in [ZENOHD_UNIT, DAEMON_UNIT] {
+            enable_and_restart(&systemd, unit).await?;
+        }
+
+        if prompt.confirm(
+            "Keep services running after logout and start them at boot (systemd lingering)?",
+            true,
+        )? {
+            match FixAction::EnableLinger.execute().await {
+                Ok(msg) => println!("  {}", msg),
+                Err(e) => eprintln!("  Warning: {} (run: loginctl enable-linger)", e),
+            }
+        }
+
+        // Connectivity
+        println!("[3/4] Checking the daemon over Zenoh");
+        let client = wait_for_daemon().await?;
+
+        // Starter nodes
+        println!("[4/4] Starter nodes");
+        if !self.no_nodes {
+            for (name, description) in STARTER_NODES {
+                if !prompt.confirm(&format!("Install {} ({})?", name, description), true)? {
+                    continue;
+                }
+                let args = crate::cli::node::InstallArgs {
+                    name: name.to_string(),
+                    branch: "main".to_string(),
+                    build: true,
+                    offline: false,
+                };
+                if let Err(e) = crate::cli::node::install::handle_install(args).await {
+                    eprintln!("  Could not install {}: {}", name, e);
+                }
+            }
+        }
+        let nodes = client.node_states().await.map(|l| l.nodes.len()).ok();
+
+        println!("\n  Setup complete.");
+        if let Some(nodes) = nodes {
+            println!("  {} node(s) registered.", nodes);
+        }
+        if custom_id.is_some() {
+            println!(
+                "\n  The daemon runs as '{}'. Add this to your shell profile so the CLI\n  addresses it too:\n\n    export BUBBALOOP_MACHINE_ID={}",
+                machine_id, machine_id
+            );
+        }
+        println!("\n  Next: bubbaloop status, bubbaloop node list, bubbaloop doctor\n");
+        Ok(())
+    }
+}
+
+/// Answers questions, or takes the defaults when not interactive.
+struct Prompt {
+    interactive: bool,
+}
+
+impl Prompt {
+    fn ask(&self, question: &str, default: &str) -> Result<String> {
+        if !self.interactive {
+            return Ok(default.to_string());
+        }
+        print!("  {} [{}]: ", question, default);
+        io::stdout().flush()?;
+        let mut line = String::new();
+        io::stdin().read_line(&mut line)?;
+        let answer = line.trim();
+        Ok(if answer.is_empty() { default } else { answer }.to_string())
+    }
+
+    fn confirm(&self, question: &str, default: bool) -> Result<bool> {
+        if !self.interactive {
+            return Ok(default);
+        }
+        let hint = if default { "Y/n" } else { "y/N" };
+        let answer = self.ask(question, hint)?;
+        Ok(match answer.to_lowercase().as_str() {
+            "y" | "yes" => true,
+            "n" | "no" => false,
+            _ => default,
+        })
+    }
+}
+
+/// Machine ids use underscores (see `get_machine_id`).
+fn normalize_machine_id(id: &str) -> Result<String> {
+    let id = id.trim().replace('-', "_");
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(SetupError::InvalidMachineId(id));
+    }
+    Ok(id)
+}
+
+/// zenohd from the install script's bin directory, then from PATH.
+fn find_zenohd() -> Option<PathBuf> {
+    let bundled = get_bubbaloop_home().join("bin/zenohd");
+    if bundled.is_file() {
+        return Some(bundled);
+    }
+    std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .map(|dir| dir.join("zenohd"))
+            .find(|p| p.is_file())
+    })
+}
+
+fn zenohd_unit(zenohd: &Path, config: &Path) -> String {
+    format!(
+        "[Unit]\n\
+         Description=Zenoh Router for Bubbaloop\n\
+         After=network.target\n\
+         \n\
+         [Service]\n\
+         Type=simple\n\
+         ExecStart={} -c {}\n\
+         Restart=on-failure\n\
+         RestartSec=5\n\
+         \n\
+         [Install]\n\
+         WantedBy=default.target\n",
+        zenohd.display(),
+        config.display()
+    )
+}
+
+fn daemon_unit(exe: &Path, machine_id: Option<&str>) -> String {
+    let machine_env = machine_id
+        .map(|id| format!("Environment=BUBBALOOP_MACHINE_ID={}\n", id))
+        .unwrap_or_default();
+    format!(
+        "[Unit]\n\
+         Description=Bubbaloop Daemon\n\
+         After={zenohd}\n\
+         Requires={zenohd}\n\
+         \n\
+         [Service]\n\
+         ExecStart={exe} daemon run\n\
+         Restart=on-failure\n\
+         RestartSec=5\n\
+         Environment=RUST_LOG=info\n\
+         {machine_env}\
+         \n\
+         [Install]\n\
+         WantedBy=default.target\n",
+        zenohd = ZENOHD_UNIT,
+        exe = exe.display(),
+        machine_env = machine_env,
+    )
+}
+
+fn write_unit(name: &str, content: &str) -> Result<()> {
+    let home = dirs::home_dir().ok_or_else(|| SetupError::Failed("HOME not set".into()))?;
+    let dir = home.join(".config/systemd/user");
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(name);
+    std::fs::write(&path, content)?;
+    println!("  Wrote {}", path.display());
+    Ok(())
+}
+
+async fn enable_and_restart(systemd: &SystemdClient, unit: &str) -> Result<()> {
+    systemd
+        .enable_unit(unit)
+        .await
+        .map_err(|e| SetupError::Systemd(format!("enable {}: {}", unit, e)))?;
+    // Restart rather than start so re-running setup applies the new unit.
+    systemd
+        .restart_unit(unit)
+        .await
+        .map_err(|e| SetupError::Systemd(format!("start {}: {}", unit, e)))?;
+    println!("  {} enabled and running", unit);
+    Ok(())
+}
+
+async fn wait_for_daemon() -> Result<crate::cli::daemon_client::DaemonClient> {
+    let started = Instant::now();
+    loop {
+        if let Ok(client) = crate::cli::daemon_client::DaemonClient::connect().await {
+            if let Ok(manifest) = client.health().await {
+                println!(
+                    "  Daemon v{} answering as {}",
+                    manifest.version, manifest.machine_id
+                );
+                return Ok(client);
+            }
+        }
+        if started.elapsed() > DAEMON_READY_TIMEOUT {
+            return Err(SetupError::Failed(
+                "the daemon did not answer over Zenoh. Check: journalctl --user -u bubbaloop-daemon, then run: bubbaloop doctor".into(),
+            ));
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn units_reference_binaries_and_machine_id() {
+        let zenohd = zenohd_unit(
+            Path::new("/opt/bin/zenohd"),
+            Path::new("/home/u/.bubbaloop/zenoh/zenohd.json5"),
+        );
+        assert!(
+            zenohd.contains("ExecStart=/opt/bin/zenohd -c /home/u/.bubbaloop/zenoh/zenohd.json5\n")
+        );
+
+        let daemon = daemon_unit(Path::new("/opt/bin/bubbaloop"), None);
+        assert!(daemon.contains("ExecStart=/opt/bin/bubbaloop daemon run\n"));
+        assert!(daemon.contains("Requires=bubbaloop-zenohd.service\n"));
+        assert!(!daemon.contains("BUBBALOOP_MACHINE_ID"));
+
+        let daemon = daemon_unit(Path::new("/opt/bin/bubbaloop"), Some("lab_orin"));
+        assert!(daemon.contains("Environment=BUBBALOOP_MACHINE_ID=lab_orin\n\n[Install]"));
+    }
+
+    #[test]
+    fn machine_ids_are_normalized() {
+        assert_eq!(
+            normalize_machine_id(" jetson-orin01 ").unwrap(),
+            "jetson_orin01"
+        );
+        assert!(normalize_machine_id("").is_err());
+        assert!(normalize_machine_id("lab/orin").is_err());
+    }
+}
@@ -0,0 +1,395 @@
+//! `bubbaloop skill` — manage sensor skills from the command line.
+//!
+//! Skills are YAML files in `~/.bubbaloop/skills/` that map a driver to a
+//! marketplace node (see [`crate::skills`]). `add` validates a file and
+//! copies it there, `apply` installs and starts the driver nodes the same
+//! way `bubbaloop up` does, and `remove` deletes a skill together with its
+//! node instance.
+
+use std::path::{Path, PathBuf};
+
+use argh::FromArgs;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::cli::daemon_client::{DaemonClient, DaemonClientError};
+use crate::cli::up::{self, SkillOutcome, UpError};
+use crate::daemon::registry::get_bubbaloop_home;
+use crate::registry;
+use crate::skills::{self, SkillConfig, SkillError};
+
+#[derive(Debug, Error)]
+pub enum SkillCommandError {
+    #[error("{0}")]
+    Skill(#[from] SkillError),
+    #[error("{0}")]
+    Up(#[from] UpError),
+    #[error("Daemon error: {0}")]
+    Daemon(#[from] DaemonClientError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Skill not found: {0}")]
+    NotFound(String),
+    #[error("Skill '{0}' already exists at {1}; use --force to replace it")]
+    Exists(String, String),
+}
+
+pub type Result<T> = std::result::Result<T, SkillCommandError>;
+
+/// Manage sensor skills (YAML driver configs)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "skill")]
+pub struct SkillCommand {
+    #[argh(subcommand)]
+    action: SkillAction,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum SkillAction {
+    Add(AddArgs),
+    List(ListArgs),
+    Drivers(DriversArgs),
+    Apply(ApplyArgs),
+    Remove(RemoveArgs),
+}
+
+/// Validate a skill file and add it to ~/.bubbaloop/skills
+#[derive(FromArgs)]
+#[argh(subcommand, name = "add")]
+struct AddArgs {
+    /// path to the skill YAML file
+    #[argh(positional)]
+    file: String,
+
+    /// replace an existing skill with the same name
+    #[argh(switch)]
+    force: bool,
+
+    /// install and start the skill's node right away
+    #[argh(switch)]
+    apply: bool,
+}
+
+/// List skills and whether they are valid
+#[derive(FromArgs)]
+#[argh(subcommand, name = "list")]
+struct ListArgs {
+    /// output as JSON
+    #[argh(switch)]
+    json: bool,
+}
+
+/// List the drivers skills can use
+#[derive(FromArgs)]
+#[argh(subcommand, name = "drivers")]
+struct DriversArgs {
+    /// output as JSON
+    #[argh(switch)]
+    json: bool,
+}
+
+/// Install, configure and start the node of a skill (or of all enabled skills)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "apply")]
+struct ApplyArgs {
+    /// skill name (default: all enabled skills)
+    #[argh(positional)]
+    name: Option<String>,
+
+    /// show what would be done without doing it
+    #[argh(switch)]
+    dry_run: bool,
+}
+
+/// Remove a skill and its node instance
+#[derive(FromArgs)]
+#[argh(subcommand, name = "remove")]
+struct RemoveArgs {
+    /// skill name
+    #[argh(positional)]
+    name: String,
+
+    /// keep the node instance registered with the daemon
+    #[argh(switch)]
+    keep_node: bool,
+}
+
+/// One row of `skill list`.
+#[derive(Debug, Serialize)]
+struct SkillEntry {
+    file: String,
+    name: Option<String>,
+    driver: Option<String>,
+    node: Option<String>,
+    enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl SkillCommand {
+    pub async fn run(self) -> Result<()> {
+        let dir = skills_dir();
+        match self.action {
+            SkillAction::Add(args) => add_skill(&dir, args).await,
+            SkillAction::List(args) => list_skills(&dir, args.json),
+            SkillAction::Drivers(args) => list_drivers(args.json),
+            SkillAction::Apply(args) => {
+                apply_skills(&dir, args.name.as_deref(), args.dry_run).await
+            }
+            SkillAction::Remove(args) => remove_skill(&dir, &args.name, args.keep_node).await,
+        }
+    }
+}
+
+fn skills_dir() -> PathBuf {
+    get_bubbaloop_home().join("skills")
+}
+
+async fn add_skill(dir: &Path, args: AddArgs) -> Result<()> {
+    let skill = skills::load_skill_file(Path::new(&args.file))?;
+    if let Some((existing, _)) = find_skill(dir, &skill.name)? {
+        if !args.force {
+            return Err(SkillCommandError::Exists(
+                skill.name,
+                existing.display().to_string(),
+            ));
+        }
+        std::fs::remove_file(&existing)?;
+    }
+    std::fs::create_dir_all(dir)?;
+    let dest = dir.join(format!("{}.yaml", skill.name));
+    std::fs::copy(&args.file, &dest)?;
+    println!("Added skill '{}' ({})", skill.name, dest.display());
+
+    if args.apply {
+        apply_skills(dir, Some(&skill.name), false).await
+    } else {
+        println!("Start it with: bubbaloop skill apply {}", skill.name);
+        Ok(())
+    }
+}
+
+fn list_skills(dir: &Path, json: bool) -> Result<()> {
+    let entries: Vec<SkillEntry> = skills::skill_files(dir)?
+        .iter()
+        .map(|path| skill_entry(path, skills::load_skill_file(path)))
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+    if entries.is_empty() {
+        println!("No skills in {}", dir.display());
+        println!("Add one with: bubbaloop skill add <file.yaml>");
+        return Ok(());
+    }
+
+    println!(
+        "{:<20} {:<10} {:<18} {:<8} FILE",
+        "NAME", "DRIVER", "NODE", "ENABLED"
+    );
+    println!("{}", "-".repeat(80));
+    for entry in &entries {
+        let file = Path::new(&entry.file)
+            .file_name()
+            .map(|f| f.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        match &entry.error {
+            Some(error) => println!("{:<20} invalid: {}", file, error),
+            None => println!(
+                "{:<20} {:<10} {:<18} {:<8} {}",
+                entry.name.as_deref().unwrap_or("-"),
+                entry.driver.as_deref().unwrap_or("-"),
+                entry.node.as_deref().unwrap_or("-"),
+                if entry.enabled { "yes" } else { "no" },
+                file
+            ),
+        }
+    }
+    Ok(())
+}
+
+fn skill_entry(path: &Path, skill: skills::Result<SkillConfig>) -> SkillEntry {
+    match skill {
+        Ok(skill) => SkillEntry {
+            file: path.display().to_string(),
+            node: skills::resolve_driver(&skill.driver).map(|d| d.marketplace_node.to_string()),
+            name: Some(skill.name),
+            driver: Some(skill.driver),
+            enabled: skill.enabled,
+            error: None,
+        },
+        Err(e) => SkillEntry {
+            file: path.display().to_string(),
+            name: None,
+            driver: None,
+            node: None,
+            enabled: false,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+fn list_drivers(json: bool) -> Result<()> {
+    // The cached registry is enough to tell which drivers can be installed.
+    let marketplace = registry::load_cached_registry();
+    let available = |node: &str| registry::find_by_name(&marketplace, node).is_some();
+
+    if json {
+        let drivers: Vec<serde_json::Value> = skills::DRIVER_CATALOG
+            .iter()
+            .map(|d| {
+                serde_json::json!({
+                    "driver": d.driver_name,
+                    "node": d.marketplace_node,
+                    "description": d.description,
+                    "in_marketplace": available(d.marketplace_node),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&drivers)?);
+        return Ok(());
+    }
+
+    println!(
+        "{:<10} {:<18} {:<12} DESCRIPTION",
+        "DRIVER", "NODE", "MARKETPLACE"
+    );
+    println!("{}", "-".repeat(70));
+    for d in skills::DRIVER_CATALOG {
+        let listed = if available(d.marketplace_node) {
+            "yes"
+        } else {
+            "-"
+        };
+        println!(
+            "{:<10} {:<18} {:<12} {}",
+            d.driver_name, d.marketplace_node, listed, d.description
+        );
+    }
+    Ok(())
+}
+
+async fn apply_skills(dir: &Path, name: Option<&str>, dry_run: bool) -> Result<()> {
+    let targets: Vec<SkillConfig> = match name {
+        Some(name) => {
+            let (_, skill) =
+                find_skill(dir, name)?.ok_or_else(|| SkillCommandError::NotFound(name.into()))?;
+            vec![skill]
+        }
+        None => skills::load_skills(dir)?
+            .into_iter()
+            .filter(|s| s.enabled)
+            .collect(),
+    };
+    if targets.is_empty() {
+        println!("No enabled skills in {}", dir.display());
+        return Ok(());
+    }
+
+    let mut marketplace = registry::load_cached_registry();
+    if marketplace.is_empty() {
+        registry::refresh_cache().map_err(UpError::Registry)?;
+        marketplace = registry::load_cached_registry();
+    }
+    let client = DaemonClient::connect().await?;
+
+    let mut skipped = Vec::new();
+    for skill in &targets {
+        if up::apply_skill(&client, &marketplace, skill, dry_run).await? == SkillOutcome::Skipped {
+            skipped.push(skill.name.clone());
+        }
+    }
+    println!();
+    if skipped.is_empty() {
+        println!("Applied {} skill(s)", targets.len());
+    } else {
+        println!(
+            "Applied {} of {} skill(s); skipped: {}",
+            targets.len() - skipped.len(),
+            targets.len(),
+            skipped.join(", ")
+        );
+    }
+    Ok(())
+}
+
+async fn remove_skill(dir: &Path, name: &str, keep_node: bool) -> Result<()> {
+    let (path, skill) =
+        find_skill(dir, name)?.ok_or_else(|| SkillCommandError::NotFound(name.into()))?;
+
+    if !keep_node {
+        match DaemonClient::connect().await {
+            Ok(client) => {
+                // Each skill runs as a node instance named after it.
+                for command in ["stop", "uninstall"] {
+                    if let Err(e) = client.send_node_command(&skill.name, command).await {
+                        log::debug!("{} {}: {}", command, skill.name, e);
+                    }
+                }
+                match client.remove_node(&skill.name).await {
+                    Ok(_) => println!("Removed node instance '{}'", skill.name),
+                    Err(DaemonClientError::DaemonError(msg))
+                        if msg.to_lowercase().contains("not found") => {}
+                    Err(e) => eprintln!("Warning: could not remove node '{}': {}", skill.name, e),
+                }
+            }
+            Err(e) => eprintln!(
+                "Warning: daemon not reachable ({}); node '{}' left registered",
+                e, skill.name
+            ),
+        }
+    }
+
+    std::fs::remove_file(&path)?;
+    let config_dir = get_bubbaloop_home().join("skills-config").join(&skill.name);
+    if config_dir.exists() {
+        std::fs::remove_dir_all(&config_dir)?;
+    }
+    println!("Removed skill '{}' ({})", skill.name, path.display());
+    Ok(())
+}
+
+/// The file and config of the skill called `name`. File names need not
+/// match skill names, so every file is read; invalid ones are skipped.
+fn find_skill(dir: &Path, name: &str) -> Result<Option<(PathBuf, SkillConfig)>> {
+    Ok(skills::skill_files(dir)?.into_iter().find_map(|path| {
+        skills::load_skill_file(&path)
+            .ok()
+            .filter(|skill| skill.name == name)
+            .map(|skill| (path, skill))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skills_are_found_by_name_not_file_name() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("front-door.yaml"),
+            "name: entrance-cam\ndriver: rtsp\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("broken.yaml"), ": : :").unwrap();
+
+        let (path, skill) = find_skill(dir.path(), "entrance-cam").unwrap().unwrap();
+        assert!(path.ends_with("front-door.yaml"));
+        assert_eq!(skill.driver, "rtsp");
+        assert!(find_skill(dir.path(), "front-door").unwrap().is_none());
+
+        let entries: Vec<SkillEntry> = skills::skill_files(dir.path())
+            .unwrap()
+            .iter()
+            .map(|p| skill_entry(p, skills::load_skill_file(p)))
+            .collect();
+        assert!(entries[0].error.is_some());
+        assert_eq!(entries[1].node.as_deref(), Some("rtsp-camera"));
+    }
+}
@@ -6,14 +6,23 @@
 //! - Bridge (running/stopped)
 //! - Node summary (running/stopped/not-installed counts)
 //!
-//! Supports --json for machine-readable output.
+//! Supports --json for machine-readable output, and `--watch` for a live
+//! view: a compact table of the daemon and its nodes, re-rendered on every
+//! daemon event and at a fixed interval, with the age of each node's last
+//! health heartbeat. Plain text with a screen clear, so it works over ssh
+//! and in tmux panes without a TUI.
 
-use std::time::Duration;
+use std::collections::{HashMap, VecDeque};
+use std::io::{IsTerminal, Write};
+use std::time::{Duration, Instant};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 
+use crate::cli::daemon_client::DaemonClient;
 use crate::cli::system_utils::{check_systemd_service as raw_systemd_service, is_process_running};
+use crate::daemon::gateway::{self, DaemonEvent, DaemonEventType, DaemonManifest};
+use crate::mcp::platform::NodeInfo;
 
 #[derive(Debug, Serialize)]
 struct StatusOutput {
@@ -201,6 +210,167 @@ async fn collect_status() -> Result<StatusOutput> {
     })
 }
 
+/// What `status --watch` shows in one frame.
+struct WatchView {
+    machine: String,
+    daemon: Option<DaemonManifest>,
+    nodes: Vec<NodeInfo>,
+    /// Seconds since each node's last health heartbeat.
+    heartbeats: HashMap<String, u64>,
+    /// Latest daemon events, oldest first.
+    events: Vec<RecentEvent>,
+}
+
+/// Daemon events kept by `status --watch`.
+const RECENT_EVENTS: usize = 5;
+
+/// A daemon event as shown by `status --watch`.
+#[derive(Clone)]
+struct RecentEvent {
+    /// Local time the event arrived, `HH:MM:SS`.
+    clock: String,
+    text: String,
+    error: bool,
+}
+
+/// `bubbaloop status --watch`: re-render on daemon events and every `interval`.
+pub async fn watch(interval: Duration) -> Result<()> {
+    let session = crate::cli::zenoh_session::create_zenoh_session(None).await?;
+    let machine = crate::cli::target::get().machine_id();
+    let client = DaemonClient::for_machine(session.clone(), &machine);
+    let events = session
+        .declare_subscriber(gateway::events_topic(&machine))
+        .await
+        .map_err(|e| anyhow!("Failed to subscribe to daemon events: {}", e))?;
+    let health = session
+        .declare_subscriber(format!("bubbaloop/global/{}/*/health", machine))
+        .await
+        .map_err(|e| anyhow!("Failed to subscribe to node health: {}", e))?;
+
+    let clear = std::io::stdout().is_terminal();
+    let mut heartbeats: HashMap<String, Instant> = HashMap::new();
+    let mut events_seen: VecDeque<RecentEvent> = VecDeque::with_capacity(RECENT_EVENTS);
+    let mut ticker = tokio::time::interval(interval);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            Ok(sample) = events.recv_async() => {
+                if let Ok(DaemonEvent { event_type, text: Some(text), .. }) =
+                    gateway::from_cbor::<DaemonEvent>(&sample.payload().to_bytes())
+                {
+                    if events_seen.len() == RECENT_EVENTS {
+                        events_seen.pop_front();
+                    }
+                    events_seen.push_back(RecentEvent {
+                        clock: chrono::Local::now().format("%H:%M:%S").to_string(),
+                        text,
+                        error: event_type == DaemonEventType::Error,
+                    });
+                }
+            }
+            Ok(sample) = health.recv_async() => {
+                // bubbaloop/global/{machine}/{node}/health; no re-render per heartbeat.
+                if let Some(node) = sample.key_expr().as_str().split('/').nth(3) {
+                    heartbeats.insert(node.to_string(), Instant::now());
+                }
+                continue;
+            }
+            _ = &mut ctrl_c => break,
+        }
+
+        let view = WatchView {
+            machine: machine.clone(),
+            daemon: client.health().await.ok(),
+            nodes: match client.list_nodes().await {
+                Ok(json) => serde_json::from_str(&json).unwrap_or_default(),
+                Err(_) => Vec::new(),
+            },
+            heartbeats: heartbeats
+                .iter()
+                .map(|(name, at)| (name.clone(), at.elapsed().as_secs()))
+                .collect(),
+            events: events_seen.iter().cloned().collect(),
+        };
+        let clock = chrono::Local::now().format("%H:%M:%S").to_string();
+        let mut out = std::io::stdout().lock();
+        if clear {
+            write!(out, "\x1b[H\x1b[2J")?;
+        } else {
+            writeln!(out)?;
+        }
+        write!(out, "{}", render_watch(&view, &clock, interval))?;
+        out.flush()?;
+    }
+    Ok(())
+}
+
+fn render_watch(view: &WatchView, clock: &str, interval: Duration) -> String {
+    let mut out = format!(
+        "bubbaloop status: {}  {}  (every {}s, Ctrl-C to quit)\n",
+        view.machine,
+        clock,
+        interval.as_secs()
+    );
+    match &view.daemon {
+        Some(d) => out.push_str(&format!(
+            "Daemon: ✓ v{}, up {}, {} nodes\n",
+            d.version,
+            crate::cli::launch::format_uptime(d.uptime_secs),
+            d.node_count
+        )),
+        None => out.push_str("Daemon: ✗ not reachable\n"),
+    }
+    if !view.nodes.is_empty() {
+        let width = view
+            .nodes
+            .iter()
+            .map(|n| n.name.len())
+            .max()
+            .unwrap_or(0)
+            .max(4);
+        out.push_str(&format!(
+            "\n{:<width$}  {:<10} {:<10} BEAT\n",
+            "NAME",
+            "STATUS",
+            "HEALTH",
+            width = width
+        ));
+        for node in &view.nodes {
+            let beat = view
+                .heartbeats
+                .get(&node.name)
+                .map(|secs| format!("{}s ago", secs))
+                .unwrap_or_else(|| "-".to_string());
+            out.push_str(&format!(
+                "{:<width$}  {:<10} {:<10} {}\n",
+                node.name,
+                node.status,
+                node.health,
+                beat,
+                width = width
+            ));
+        }
+    } else if view.daemon.is_some() {
+        out.push_str("\nNo nodes registered\n");
+    }
+    if !view.events.is_empty() {
+        out.push_str("\nRecent events:\n");
+        for event in &view.events {
+            let text = event.text.lines().next().unwrap_or_default();
+            out.push_str(&format!(
+                "  {} {} {}\n",
+                event.clock,
+                if event.error { "✗" } else { "·" },
+                super::node::truncate(text, 66)
+            ));
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -279,4 +449,65 @@ mod tests {
         assert_eq!(resp.nodes_total, 5);
         assert_eq!(resp.nodes_running, 3);
     }
+
+    #[test]
+    fn test_render_watch() {
+        let node = |name: &str, status: &str| NodeInfo {
+            name: name.to_string(),
+            status: status.to_string(),
+            health: "healthy".to_string(),
+            node_type: "rust".to_string(),
+            installed: true,
+            is_built: true,
+        };
+        let view = WatchView {
+            machine: "jetson_orin01".to_string(),
+            daemon: Some(DaemonManifest {
+                version: "0.0.9".to_string(),
+                machine_id: "jetson_orin01".to_string(),
+                uptime_secs: 3725,
+                node_count: 2,
+                agent_count: 0,
+                mcp_port: 8088,
+                timestamp_ms: 0,
+            }),
+            nodes: vec![node("rtsp-camera", "running"), node("openmeteo", "stopped")],
+            heartbeats: HashMap::from([("rtsp-camera".to_string(), 1)]),
+            events: vec![
+                RecentEvent {
+                    clock: "14:01:50".to_string(),
+                    text: "Node started\nextra".to_string(),
+                    error: false,
+                },
+                RecentEvent {
+                    clock: "14:02:03".to_string(),
+                    text: "Build failed: openmeteo".to_string(),
+                    error: true,
+                },
+            ],
+        };
+        let out = render_watch(&view, "14:02:11", Duration::from_secs(2));
+        assert_eq!(
+            out,
+            "bubbaloop status: jetson_orin01  14:02:11  (every 2s, Ctrl-C to quit)\n\
+             Daemon: ✓ v0.0.9, up 1h2m, 2 nodes\n\
+             \n\
+             NAME         STATUS     HEALTH     BEAT\n\
+             rtsp-camera  running    healthy    1s ago\n\
+             openmeteo    stopped    healthy    -\n\
+             \n\
+             Recent events:\n  \
+             14:01:50 · Node started\n  \
+             14:02:03 ✗ Build failed: openmeteo\n"
+        );
+
+        let down = WatchView {
+            daemon: None,
+            nodes: Vec::new(),
+            events: Vec::new(),
+            ..view
+        };
+        assert!(render_watch(&down, "14:02:11", Duration::from_secs(2))
+            .ends_with("Daemon: ✗ not reachable\n"));
+    }
 }
@@ -0,0 +1,147 @@
+//! Which machine and key space CLI commands address.
+//!
+//! Set once from the global `--machine` / `--scope` options before the
+//! subcommand runs. Commands that talk to a daemon or build node key
+//! expressions read it instead of assuming this machine, so a laptop can
+//! administer any daemon on the Zenoh network:
+//!
+//!   bubbaloop --machine jetson_orin01 node list
+//!   bubbaloop --machine jetson_orin01 node restart rtsp-camera-terrace
+
+use std::sync::OnceLock;
+
+use thiserror::Error;
+
+static TARGET: OnceLock<Target> = OnceLock::new();
+
+#[derive(Debug, Error, PartialEq)]
+pub enum TargetError {
+    #[error("Invalid scope '{0}' (expected global or local)")]
+    InvalidScope(String),
+    #[error("Invalid machine id '{0}'")]
+    InvalidMachine(String),
+    #[error("The local key space never leaves its machine; use --scope global with --machine {0}")]
+    LocalScopeRemote(String),
+}
+
+/// Key space of `bubbaloop/{scope}/{machine}/...` key expressions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Scope {
+    /// Network-visible topics (daemons, dashboard, other machines).
+    #[default]
+    Global,
+    /// Shared-memory topics that stay on the publishing machine.
+    Local,
+}
+
+impl Scope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Scope::Global => "global",
+            Scope::Local => "local",
+        }
+    }
+}
+
+impl std::str::FromStr for Scope {
+    type Err = TargetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "global" => Ok(Scope::Global),
+            "local" => Ok(Scope::Local),
+            other => Err(TargetError::InvalidScope(other.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Target {
+    /// Remote machine id; `None` is this machine.
+    pub machine: Option<String>,
+    pub scope: Scope,
+}
+
+impl Target {
+    pub fn new(machine: Option<String>, scope: &str) -> Result<Self, TargetError> {
+        let scope: Scope = scope.parse()?;
+        let local = crate::daemon::util::get_machine_id();
+        // Machine ids use underscores (see `get_machine_id`), and naming our
+        // own machine is the same as not naming one.
+        let machine = machine.map(|m| m.replace('-', "_")).filter(|m| *m != local);
+        if let Some(machine) = &machine {
+            if machine.is_empty()
+                || !machine
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                return Err(TargetError::InvalidMachine(machine.clone()));
+            }
+            if scope == Scope::Local {
+                return Err(TargetError::LocalScopeRemote(machine.clone()));
+            }
+        }
+        Ok(Self { machine, scope })
+    }
+
+    /// The machine id to put in key expressions.
+    pub fn machine_id(&self) -> String {
+        self.machine
+            .clone()
+            .unwrap_or_else(crate::daemon::util::get_machine_id)
+    }
+
+    /// `bubbaloop/{scope}/{machine}`
+    pub fn key_prefix(&self) -> String {
+        format!("bubbaloop/{}/{}", self.scope.as_str(), self.machine_id())
+    }
+}
+
+/// Set the target for the rest of the process. Later calls are ignored.
+pub fn set(target: Target) {
+    let _ = TARGET.set(target);
+}
+
+/// The target set from the command line, or this machine in global scope.
+pub fn get() -> Target {
+    explicit().unwrap_or_default()
+}
+
+/// The target, only when `--machine` or `--scope` was given.
+pub fn explicit() -> Option<Target> {
+    TARGET.get().cloned()
+}
+
+/// Whether commands address another machine's daemon.
+pub fn is_remote() -> bool {
+    TARGET.get().is_some_and(|t| t.machine.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_validates_machine_and_scope() {
+        let target = Target::new(Some("jetson_orin01".into()), "global").unwrap();
+        assert_eq!(target.machine_id(), "jetson_orin01");
+        assert_eq!(target.key_prefix(), "bubbaloop/global/jetson_orin01");
+
+        let target = Target::new(Some("jetson-orin01".into()), "global").unwrap();
+        assert_eq!(target.machine.as_deref(), Some("jetson_orin01"));
+        let target = Target::new(None, "local").unwrap();
+        assert!(target.key_prefix().starts_with("bubbaloop/local/"));
+
+        assert_eq!(
+            Target::new(None, "prod"),
+            Err(TargetError::InvalidScope("prod".into()))
+        );
+        assert_eq!(
+            Target::new(Some("jetson_orin01".into()), "local"),
+            Err(TargetError::LocalScopeRemote("jetson_orin01".into()))
+        );
+        assert!(Target::new(Some("a/b".into()), "global").is_err());
+        assert!(Target::new(Some(String::new()), "global").is_err());
+        assert!(Target::new(Some("*".into()), "global").is_err());
+    }
+}
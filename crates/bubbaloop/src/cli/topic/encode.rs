@@ -0,0 +1,168 @@
+//! JSON to protobuf encoding for `topic pub`.
+//!
+//! The mapping mirrors what `topic echo` prints, so echoed output can be
+//! published back: fields by proto or JSON name, enums by name or number,
+//! 64-bit integers as numbers or strings, and bytes as hex.
+
+use std::collections::HashMap;
+
+use prost_reflect::{DynamicMessage, FieldDescriptor, Kind, MapKey, MessageDescriptor, Value};
+use serde_json::Value as Json;
+
+pub(super) fn json_to_message(
+    desc: &MessageDescriptor,
+    json: &Json,
+) -> std::result::Result<DynamicMessage, String> {
+    let object = json
+        .as_object()
+        .ok_or_else(|| format!("{} must be a JSON object", desc.full_name()))?;
+    let mut msg = DynamicMessage::new(desc.clone());
+    for (name, value) in object {
+        let field = desc
+            .get_field_by_name(name)
+            .or_else(|| desc.get_field_by_json_name(name))
+            .ok_or_else(|| format!("{} has no field '{}'", desc.full_name(), name))?;
+        if value.is_null() {
+            continue;
+        }
+        let value = field_value(&field, value).map_err(|e| format!("{}: {}", name, e))?;
+        msg.set_field(&field, value);
+    }
+    Ok(msg)
+}
+
+fn field_value(field: &FieldDescriptor, json: &Json) -> std::result::Result<Value, String> {
+    let kind = field.kind();
+    if field.is_map() {
+        let entry = kind
+            .as_message()
+            .expect("map fields are map entry messages");
+        let key_kind = entry.map_entry_key_field().kind();
+        let value_kind = entry.map_entry_value_field().kind();
+        let object = json.as_object().ok_or("expected a JSON object")?;
+        let mut map = HashMap::new();
+        for (key, value) in object {
+            map.insert(map_key(key, &key_kind)?, single(value, &value_kind)?);
+        }
+        return Ok(Value::Map(map));
+    }
+    if field.is_list() {
+        let items = json.as_array().ok_or("expected a JSON array")?;
+        return items
+            .iter()
+            .map(|item| single(item, &kind))
+            .collect::<std::result::Result<_, _>>()
+            .map(Value::List);
+    }
+    single(json, &kind)
+}
+
+fn single(json: &Json, kind: &Kind) -> std::result::Result<Value, String> {
+    Ok(match kind {
+        Kind::Double => Value::F64(float(json)?),
+        Kind::Float => Value::F32(float(json)? as f32),
+        Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 => Value::I32(integer(json)?),
+        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 => Value::I64(integer(json)?),
+        Kind::Uint32 | Kind::Fixed32 => Value::U32(integer(json)?),
+        Kind::Uint64 | Kind::Fixed64 => Value::U64(integer(json)?),
+        Kind::Bool => Value::Bool(json.as_bool().ok_or("expected true or false")?),
+        Kind::String => Value::String(json.as_str().ok_or("expected a string")?.to_string()),
+        Kind::Bytes => {
+            let hex_str = json.as_str().ok_or("expected a hex string")?;
+            let bytes = hex::decode(hex_str).map_err(|e| format!("invalid hex: {}", e))?;
+            Value::Bytes(bytes.into())
+        }
+        Kind::Message(message) => Value::Message(json_to_message(message, json)?),
+        Kind::Enum(enumeration) => match json {
+            Json::String(name) => enumeration
+                .get_value_by_name(name)
+                .map(|v| Value::EnumNumber(v.number()))
+                .ok_or_else(|| format!("{} has no value '{}'", enumeration.full_name(), name))?,
+            _ => Value::EnumNumber(integer(json)?),
+        },
+    })
+}
+
+fn map_key(key: &str, kind: &Kind) -> std::result::Result<MapKey, String> {
+    let json = Json::String(key.to_string());
+    Ok(match kind {
+        Kind::Bool => MapKey::Bool(key.parse().map_err(|_| "expected a true/false key")?),
+        Kind::Int32 | Kind::Sint32 | Kind::Sfixed32 => MapKey::I32(integer(&json)?),
+        Kind::Int64 | Kind::Sint64 | Kind::Sfixed64 => MapKey::I64(integer(&json)?),
+        Kind::Uint32 | Kind::Fixed32 => MapKey::U32(integer(&json)?),
+        Kind::Uint64 | Kind::Fixed64 => MapKey::U64(integer(&json)?),
+        _ => MapKey::String(key.to_string()),
+    })
+}
+
+fn float(json: &Json) -> std::result::Result<f64, String> {
+    match json {
+        Json::Number(n) => n.as_f64(),
+        Json::String(s) => s.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| format!("expected a number, got {}", json))
+}
+
+/// Integers come as JSON numbers or, for 64-bit values that JSON
+/// numbers cannot hold exactly, as strings.
+fn integer<T>(json: &Json) -> std::result::Result<T, String>
+where
+    T: TryFrom<i64> + TryFrom<u64> + std::str::FromStr,
+{
+    match json {
+        Json::Number(n) => n
+            .as_u64()
+            .and_then(|n| T::try_from(n).ok())
+            .or_else(|| n.as_i64().and_then(|n| T::try_from(n).ok())),
+        Json::String(s) => s.parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| format!("expected an integer in range, got {}", json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::decode::decode_protobuf;
+    use crate::schemas::{NodeList, NodeStatus};
+    use prost::Message;
+    use serde_json::json;
+
+    fn message(name: &str) -> MessageDescriptor {
+        crate::get_descriptor_pool()
+            .get_message_by_name(name)
+            .unwrap()
+    }
+
+    #[test]
+    fn encodes_nested_messages_enums_and_string_integers() {
+        let desc = message("bubbaloop.daemon.v1.NodeList");
+        let input = json!({
+            "nodes": [{"name": "cam", "status": "NODE_STATUS_RUNNING", "lastUpdatedMs": "7"}],
+            "timestamp_ms": 42,
+        });
+        let bytes = json_to_message(&desc, &input).unwrap().encode_to_vec();
+
+        let list = NodeList::decode(bytes.as_slice()).unwrap();
+        assert_eq!(list.timestamp_ms, 42);
+        assert_eq!(list.nodes[0].name, "cam");
+        assert_eq!(list.nodes[0].status, NodeStatus::Running as i32);
+        assert_eq!(list.nodes[0].last_updated_ms, 7);
+
+        // What echo prints can be published back unchanged.
+        let echoed = decode_protobuf(&desc, &bytes).value;
+        let again = json_to_message(&desc, &echoed).unwrap().encode_to_vec();
+        assert_eq!(again, bytes);
+    }
+
+    #[test]
+    fn reports_the_offending_field() {
+        let desc = message("bubbaloop.header.v1.Header");
+        let err = json_to_message(&desc, &json!({"sequence": -1})).unwrap_err();
+        assert!(err.starts_with("sequence: expected an integer"), "{}", err);
+        let err = json_to_message(&desc, &json!({"frameid": "x"})).unwrap_err();
+        assert_eq!(err, "bubbaloop.header.v1.Header has no field 'frameid'");
+        assert!(json_to_message(&desc, &json!([1])).is_err());
+    }
+}
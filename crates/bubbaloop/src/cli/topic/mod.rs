@@ -0,0 +1,451 @@
+//! Topic inspection commands
+//!
+//! Usage:
+//!   bubbaloop topic echo 'bubbaloop/global/*/tapo_terrace/**'
+//!   bubbaloop topic echo bubbaloop/global/orin/cam/compressed -n 5
+//!   bubbaloop topic echo bubbaloop/global/orin/cam/meta --type camera.v1.Meta
+//!   bubbaloop topic echo bubbaloop/global/orin/cam/compressed --json | jq .value
+//!   bubbaloop topic pub bubbaloop/global/orin/cam/meta --json '{"fps": 30}'
+//!   bubbaloop topic pub bubbaloop/global/orin/cam/meta --json '{...}' \
+//!       --proto camera.v1.Meta --rate 10
+//!   bubbaloop topic hz 'bubbaloop/global/*/cam/compressed' -w 30
+//!   bubbaloop topic bw 'bubbaloop/global/orin/**' --json
+//!
+//! Protobuf samples are decoded with the descriptors served by the
+//! publishing node (`bubbaloop/{scope}/{machine}/{node}/schema`), so `echo`
+//! needs no compiled-in knowledge of node message types. `pub --proto`
+//! encodes with the same descriptors, from the node owning the key.
+
+mod encode;
+pub(crate) mod stats;
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use argh::FromArgs;
+use serde_json::json;
+use thiserror::Error;
+use zenoh::bytes::Encoding;
+
+use crate::daemon::decode::{self, Decoder};
+use stats::{format_size, Report, Stats, Window};
+
+#[derive(Debug, Error)]
+pub enum TopicError {
+    #[error("Zenoh error: {0}")]
+    Zenoh(String),
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Encode error: {0}")]
+    Encode(String),
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+}
+
+pub type Result<T> = std::result::Result<T, TopicError>;
+
+/// Inspect data flowing on Zenoh topics
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "topic")]
+pub struct TopicCommand {
+    #[argh(subcommand)]
+    action: TopicSubcommand,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand)]
+enum TopicSubcommand {
+    Echo(EchoArgs),
+    Pub(PubArgs),
+    Hz(HzArgs),
+    Bw(BwArgs),
+}
+
+/// Print decoded messages with rate and size
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "echo")]
+struct EchoArgs {
+    /// key expression to subscribe to (e.g. "bubbaloop/global/*/cam/**")
+    #[argh(positional)]
+    key: String,
+
+    /// decode as this protobuf message type, whatever the encoding says
+    #[argh(option, long = "type")]
+    type_name: Option<String>,
+
+    /// exit after this many messages
+    #[argh(option, short = 'n')]
+    count: Option<u64>,
+
+    /// print one JSON object per message instead of a header and body
+    #[argh(switch)]
+    json: bool,
+}
+
+/// Publish a test message, once or at a fixed rate
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "pub")]
+struct PubArgs {
+    /// key to publish on (e.g. "bubbaloop/global/orin/cam/meta")
+    #[argh(positional)]
+    key: String,
+
+    /// message body as JSON
+    #[argh(option)]
+    json: String,
+
+    /// encode the JSON as this protobuf message type instead of sending it as-is
+    #[argh(option)]
+    proto: Option<String>,
+
+    /// publish at this many messages per second until Ctrl-C
+    #[argh(option)]
+    rate: Option<f64>,
+
+    /// with --rate, stop after this many messages
+    #[argh(option, short = 'n')]
+    count: Option<u64>,
+}
+
+/// Measure message rate and jitter over a window
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "hz")]
+struct HzArgs {
+    /// key expression to measure (e.g. "bubbaloop/global/*/cam/compressed")
+    #[argh(positional)]
+    key: String,
+
+    /// measurement window in seconds (default: 10)
+    #[argh(option, short = 'w', default = "10")]
+    window: u64,
+
+    /// output the full report as JSON
+    #[argh(switch)]
+    json: bool,
+}
+
+/// Measure bandwidth and payload sizes over a window
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "bw")]
+struct BwArgs {
+    /// key expression to measure (e.g. "bubbaloop/global/*/cam/compressed")
+    #[argh(positional)]
+    key: String,
+
+    /// measurement window in seconds (default: 10)
+    #[argh(option, short = 'w', default = "10")]
+    window: u64,
+
+    /// output the full report as JSON
+    #[argh(switch)]
+    json: bool,
+}
+
+impl TopicCommand {
+    pub async fn run(self) -> Result<()> {
+        match self.action {
+            TopicSubcommand::Echo(args) => echo(args).await,
+            TopicSubcommand::Pub(args) => publish(args).await,
+            TopicSubcommand::Hz(args) => {
+                let reports = measure(&args.key, args.window, args.json).await?;
+                print_reports(&reports, args.json, print_hz)
+            }
+            TopicSubcommand::Bw(args) => {
+                let reports = measure(&args.key, args.window, args.json).await?;
+                print_reports(&reports, args.json, print_bw)
+            }
+        }
+    }
+}
+
+async fn echo(args: EchoArgs) -> Result<()> {
+    let session = crate::cli::zenoh_session::create_zenoh_session(None)
+        .await
+        .map_err(|e| TopicError::Zenoh(e.to_string()))?;
+    let subscriber = session
+        .declare_subscriber(&args.key)
+        .await
+        .map_err(|e| TopicError::Zenoh(e.to_string()))?;
+    let decoder = Decoder::new(session.clone(), args.type_name.clone());
+    let mut stats: BTreeMap<String, Stats> = BTreeMap::new();
+    let mut received = 0u64;
+
+    if !args.json {
+        eprintln!("Echoing {} (Ctrl-C to stop)", args.key);
+    }
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    while args.count.is_none_or(|count| received < count) {
+        let sample = tokio::select! {
+            sample = subscriber.recv_async() => {
+                sample.map_err(|e| TopicError::Zenoh(e.to_string()))?
+            }
+            _ = &mut ctrl_c => break,
+        };
+        let key = sample.key_expr().to_string();
+        let payload = sample.payload().to_bytes();
+        let topic = stats.entry(key.clone()).or_default();
+        topic.record(Instant::now(), payload.len());
+        let rate = topic.rate();
+        received += 1;
+
+        let decoded = decoder
+            .decode(&key, &sample.encoding().to_string(), &payload)
+            .await;
+        if args.json {
+            println!(
+                "{}",
+                serde_json::to_string(&json!({
+                    "key": key,
+                    "format": decoded.format,
+                    "size": payload.len(),
+                    "rate_hz": rate,
+                    "value": decoded.value,
+                }))?
+            );
+        } else {
+            let rate = rate
+                .map(|hz| format!("{:.1} Hz", hz))
+                .unwrap_or_else(|| "- Hz".to_string());
+            println!(
+                "--- {} [{}] {} | {}",
+                key,
+                decoded.format,
+                format_size(payload.len() as f64),
+                rate
+            );
+            println!("{}", serde_json::to_string_pretty(&decoded.value)?);
+        }
+    }
+
+    if !args.json {
+        print_summary(&stats);
+    }
+    Ok(())
+}
+
+async fn publish(args: PubArgs) -> Result<()> {
+    let period = match args.rate {
+        Some(rate) if !(rate > 0.0 && rate.is_finite()) => {
+            return Err(TopicError::InvalidArgument(format!(
+                "--rate must be a positive number, got {}",
+                rate
+            )));
+        }
+        Some(rate) => Some(Duration::from_secs_f64(1.0 / rate)),
+        None => None,
+    };
+    let json: serde_json::Value = serde_json::from_str(&args.json)?;
+    let session = crate::cli::zenoh_session::create_zenoh_session(None)
+        .await
+        .map_err(|e| TopicError::Zenoh(e.to_string()))?;
+
+    let (payload, encoding) = match &args.proto {
+        Some(type_name) => {
+            let pool = match decode::schema_key(&args.key) {
+                Some(schema_key) => decode::fetch_pool(&session, &schema_key).await,
+                None => None,
+            };
+            let message = decode::find_message(pool.as_ref(), type_name).ok_or_else(|| {
+                TopicError::Encode(format!("unknown protobuf message type '{}'", type_name))
+            })?;
+            let msg = encode::json_to_message(&message, &json).map_err(TopicError::Encode)?;
+            (
+                prost::Message::encode_to_vec(&msg),
+                Encoding::APPLICATION_PROTOBUF.with_schema(type_name.as_str()),
+            )
+        }
+        None => (serde_json::to_vec(&json)?, Encoding::APPLICATION_JSON),
+    };
+
+    let publisher = session
+        .declare_publisher(args.key.clone())
+        .encoding(encoding)
+        .await
+        .map_err(|e| TopicError::Zenoh(e.to_string()))?;
+    let Some(period) = period else {
+        publisher
+            .put(payload)
+            .await
+            .map_err(|e| TopicError::Zenoh(e.to_string()))?;
+        println!("Published 1 message to {}", args.key);
+        return Ok(());
+    };
+
+    println!(
+        "Publishing to {} every {:.3}s (Ctrl-C to stop)",
+        args.key,
+        period.as_secs_f64()
+    );
+    let mut ticker = tokio::time::interval(period);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let mut sent = 0u64;
+    while args.count.is_none_or(|count| sent < count) {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = &mut ctrl_c => break,
+        }
+        publisher
+            .put(payload.clone())
+            .await
+            .map_err(|e| TopicError::Zenoh(e.to_string()))?;
+        sent += 1;
+    }
+    println!("Published {} message(s) to {}", sent, args.key);
+    Ok(())
+}
+
+/// Record every arrival on `key` for `window` seconds, or until Ctrl-C.
+async fn measure(key: &str, window: u64, quiet: bool) -> Result<BTreeMap<String, Report>> {
+    if window == 0 {
+        return Err(TopicError::InvalidArgument(
+            "--window must be at least 1 second".to_string(),
+        ));
+    }
+    let session = crate::cli::zenoh_session::create_zenoh_session(None)
+        .await
+        .map_err(|e| TopicError::Zenoh(e.to_string()))?;
+    let subscriber = session
+        .declare_subscriber(key)
+        .await
+        .map_err(|e| TopicError::Zenoh(e.to_string()))?;
+
+    if !quiet {
+        eprintln!("Measuring {} for {}s (Ctrl-C to stop early)", key, window);
+    }
+    let start = Instant::now();
+    let deadline = tokio::time::sleep(Duration::from_secs(window));
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(deadline, ctrl_c);
+    let mut windows: BTreeMap<String, Window> = BTreeMap::new();
+    loop {
+        tokio::select! {
+            sample = subscriber.recv_async() => {
+                let sample = sample.map_err(|e| TopicError::Zenoh(e.to_string()))?;
+                windows
+                    .entry(sample.key_expr().to_string())
+                    .or_default()
+                    .record(Instant::now(), sample.payload().len());
+            }
+            _ = &mut deadline => break,
+            _ = &mut ctrl_c => break,
+        }
+    }
+    let elapsed = start.elapsed();
+    Ok(windows
+        .into_iter()
+        .map(|(key, window)| (key, window.report(elapsed)))
+        .collect())
+}
+
+fn print_reports(reports: &BTreeMap<String, Report>, json: bool, print: fn(&Report)) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(reports)?);
+        return Ok(());
+    }
+    if reports.is_empty() {
+        println!("No messages received.");
+    }
+    for (key, report) in reports {
+        println!("{}", key);
+        print(report);
+    }
+    Ok(())
+}
+
+fn print_hz(report: &Report) {
+    match report.rate_hz {
+        Some(rate) => println!("  rate      {:.2} Hz ({} messages)", rate, report.messages),
+        None => println!("  rate      - ({} message)", report.messages),
+    }
+    if let Some(interval) = &report.interval_ms {
+        println!(
+            "  interval  mean {:.1} ms  min {:.1} ms  max {:.1} ms  p99 {:.1} ms",
+            interval.mean, interval.min, interval.max, interval.p99
+        );
+        println!("  jitter    {:.2} ms (std dev)", interval.std_dev);
+    }
+}
+
+fn print_bw(report: &Report) {
+    println!(
+        "  bandwidth {}/s ({} in {} messages)",
+        format_size(report.bandwidth_bps),
+        format_size(report.total_bytes as f64),
+        report.messages
+    );
+    if let Some(size) = &report.size_bytes {
+        println!(
+            "  size      mean {}  min {}  p50 {}  p90 {}  p99 {}  max {}",
+            format_size(size.mean),
+            format_size(size.min),
+            format_size(size.p50),
+            format_size(size.p90),
+            format_size(size.p99),
+            format_size(size.max)
+        );
+    }
+}
+
+fn print_summary(stats: &BTreeMap<String, Stats>) {
+    if stats.is_empty() {
+        eprintln!("\nNo messages received.");
+        return;
+    }
+    eprintln!();
+    for (key, topic) in stats {
+        let rate = topic
+            .average_rate()
+            .map(|hz| format!("{:.1} Hz", hz))
+            .unwrap_or_else(|| "-".to_string());
+        eprintln!(
+            "{}: {} message(s), {} avg, {} avg",
+            key,
+            topic.count(),
+            rate,
+            format_size(topic.average_size())
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_subcommands_parse() {
+        let cmd = TopicCommand::from_args(
+            &["topic"],
+            &["echo", "bubbaloop/**", "--type", "a.B", "-n", "3", "--json"],
+        )
+        .unwrap();
+        let TopicSubcommand::Echo(args) = cmd.action else {
+            panic!("expected echo");
+        };
+        assert_eq!(args.key, "bubbaloop/**");
+        assert_eq!(args.type_name.as_deref(), Some("a.B"));
+        assert_eq!(args.count, Some(3));
+        assert!(args.json);
+
+        let cmd = TopicCommand::from_args(
+            &["topic"],
+            &[
+                "pub", "a/b", "--json", "{}", "--proto", "a.B", "--rate", "2.5",
+            ],
+        )
+        .unwrap();
+        let TopicSubcommand::Pub(args) = cmd.action else {
+            panic!("expected pub");
+        };
+        assert_eq!(args.key, "a/b");
+        assert_eq!(args.proto.as_deref(), Some("a.B"));
+        assert_eq!(args.rate, Some(2.5));
+        assert_eq!(args.count, None);
+
+        let cmd = TopicCommand::from_args(&["topic"], &["hz", "a/**", "-w", "3"]).unwrap();
+        assert!(matches!(cmd.action, TopicSubcommand::Hz(ref a) if a.window == 3 && !a.json));
+        let cmd = TopicCommand::from_args(&["topic"], &["bw", "a/**", "--json"]).unwrap();
+        assert!(matches!(cmd.action, TopicSubcommand::Bw(ref a) if a.window == 10 && a.json));
+    }
+}
@@ -0,0 +1,231 @@
+//! Message rate and size statistics for a single topic.
+//!
+//! [`Stats`] keeps a rolling window for `echo`, which runs indefinitely.
+//! [`Window`] keeps every arrival of a bounded `hz` / `bw` measurement,
+//! so intervals and sizes can be summarized exactly.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Arrivals kept for the current rate.
+const WINDOW: usize = 50;
+
+#[derive(Debug, Default)]
+pub(super) struct Stats {
+    window: VecDeque<Instant>,
+    first: Option<Instant>,
+    last: Option<Instant>,
+    count: u64,
+    bytes: u64,
+}
+
+impl Stats {
+    pub(super) fn record(&mut self, at: Instant, size: usize) {
+        if self.window.len() == WINDOW {
+            self.window.pop_front();
+        }
+        self.window.push_back(at);
+        self.first.get_or_insert(at);
+        self.last = Some(at);
+        self.count += 1;
+        self.bytes += size as u64;
+    }
+
+    pub(super) fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Rate over the last [`WINDOW`] messages, once two have arrived.
+    pub(super) fn rate(&self) -> Option<f64> {
+        rate(
+            self.window.len(),
+            *self.window.back()? - *self.window.front()?,
+        )
+    }
+
+    /// Rate over every message seen.
+    pub(super) fn average_rate(&self) -> Option<f64> {
+        rate(self.count as usize, self.last? - self.first?)
+    }
+
+    pub(super) fn average_size(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            self.bytes as f64 / self.count as f64
+        }
+    }
+}
+
+fn rate(messages: usize, span: Duration) -> Option<f64> {
+    (messages > 1 && !span.is_zero()).then(|| (messages - 1) as f64 / span.as_secs_f64())
+}
+
+/// Every arrival on one topic during a measurement.
+#[derive(Debug, Default)]
+pub(super) struct Window {
+    arrivals: Vec<(Instant, usize)>,
+}
+
+/// What `hz` and `bw` report for one topic.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(super) struct Report {
+    pub messages: usize,
+    /// Publish rate from first to last arrival.
+    pub rate_hz: Option<f64>,
+    /// Inter-arrival times in milliseconds; `std_dev` is the jitter.
+    pub interval_ms: Option<Summary>,
+    /// Bytes per second over the whole measurement.
+    pub bandwidth_bps: f64,
+    pub total_bytes: u64,
+    /// Payload sizes in bytes.
+    pub size_bytes: Option<Summary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct Summary {
+    pub mean: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+impl Window {
+    pub(super) fn record(&mut self, at: Instant, size: usize) {
+        self.arrivals.push((at, size));
+    }
+
+    /// Summarize the arrivals of a measurement that lasted `elapsed`.
+    pub(super) fn report(&self, elapsed: Duration) -> Report {
+        let intervals: Vec<f64> = self
+            .arrivals
+            .windows(2)
+            .map(|pair| (pair[1].0 - pair[0].0).as_secs_f64() * 1000.0)
+            .collect();
+        let sizes: Vec<f64> = self.arrivals.iter().map(|&(_, size)| size as f64).collect();
+        let total_bytes = self.arrivals.iter().map(|&(_, size)| size as u64).sum();
+        let rate_hz = match (self.arrivals.first(), self.arrivals.last()) {
+            (Some(first), Some(last)) => rate(self.arrivals.len(), last.0 - first.0),
+            _ => None,
+        };
+        Report {
+            messages: self.arrivals.len(),
+            rate_hz,
+            interval_ms: Summary::of(intervals),
+            bandwidth_bps: if elapsed.is_zero() {
+                0.0
+            } else {
+                total_bytes as f64 / elapsed.as_secs_f64()
+            },
+            total_bytes,
+            size_bytes: Summary::of(sizes),
+        }
+    }
+}
+
+impl Summary {
+    pub(crate) fn of(mut values: Vec<f64>) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+        Some(Self {
+            mean,
+            std_dev: variance.sqrt(),
+            min: values[0],
+            p50: percentile(&values, 50.0),
+            p90: percentile(&values, 90.0),
+            p99: percentile(&values, 99.0),
+            max: values[values.len() - 1],
+        })
+    }
+}
+
+/// Nearest-rank percentile of sorted, non-empty `values`.
+fn percentile(values: &[f64], p: f64) -> f64 {
+    let rank = (p / 100.0 * values.len() as f64).ceil() as usize;
+    values[rank.clamp(1, values.len()) - 1]
+}
+
+/// `532 B`, `12.3 KiB`, `4.0 MiB`.
+pub(crate) fn format_size(bytes: f64) -> String {
+    if bytes < 1024.0 {
+        format!("{:.0} B", bytes)
+    } else if bytes < 1024.0 * 1024.0 {
+        format!("{:.1} KiB", bytes / 1024.0)
+    } else {
+        format!("{:.1} MiB", bytes / (1024.0 * 1024.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_uses_the_recent_window() {
+        let start = Instant::now();
+        let mut stats = Stats::default();
+        assert_eq!(stats.rate(), None);
+
+        // 10 Hz for 100 messages, then 20 Hz for 50: the window only sees 20 Hz.
+        let mut at = start;
+        for _ in 0..100 {
+            stats.record(at, 100);
+            at += Duration::from_millis(100);
+        }
+        for _ in 0..WINDOW {
+            at += Duration::from_millis(50);
+            stats.record(at, 300);
+        }
+
+        assert!((stats.rate().unwrap() - 20.0).abs() < 1e-6);
+        let average = stats.average_rate().unwrap();
+        assert!(average > 10.0 && average < 20.0);
+        assert_eq!(stats.count(), 150);
+        assert!((stats.average_size() - 500.0 / 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn window_reports_jitter_and_size_percentiles() {
+        let start = Instant::now();
+        let mut window = Window::default();
+        assert_eq!(window.report(Duration::from_secs(1)).rate_hz, None);
+
+        // Alternating 90 ms / 110 ms gaps: 10 Hz with 10 ms of jitter.
+        let mut at = start;
+        for i in 0..101u64 {
+            window.record(at, (i as usize + 1) * 10);
+            at += Duration::from_millis(if i % 2 == 0 { 90 } else { 110 });
+        }
+        let report = window.report(Duration::from_secs(10));
+
+        assert_eq!(report.messages, 101);
+        assert!((report.rate_hz.unwrap() - 10.0).abs() < 1e-6);
+        let interval = report.interval_ms.unwrap();
+        assert!((interval.mean - 100.0).abs() < 1e-6);
+        assert!((interval.std_dev - 10.0).abs() < 1e-6);
+        assert_eq!((interval.min, interval.max), (90.0, 110.0));
+
+        let size = report.size_bytes.unwrap();
+        assert_eq!((size.min, size.p50, size.p90), (10.0, 510.0, 910.0));
+        assert_eq!((size.p99, size.max), (1000.0, 1010.0));
+        assert_eq!(report.total_bytes, 51510);
+        assert!((report.bandwidth_bps - 5151.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn sizes_are_human_readable() {
+        assert_eq!(format_size(532.0), "532 B");
+        assert_eq!(format_size(12.3 * 1024.0), "12.3 KiB");
+        assert_eq!(format_size(4.0 * 1024.0 * 1024.0), "4.0 MiB");
+    }
+}
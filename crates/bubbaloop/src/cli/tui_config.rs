@@ -0,0 +1,354 @@
+//! `~/.bubbaloop/tui.yaml` — colours and key bindings of the `agent chat`
+//! REPL.
+//!
+//! ```yaml
+//! colors:
+//!   agent: lightgreen
+//!   tool_result: "#a0a0a0"
+//!   border: "8"                 # indexed colour
+//! keys:
+//!   scroll_up: [up, ctrl-k]
+//!   scroll_down: [down, ctrl-j]
+//!   page_up: [pageup, ctrl-b]
+//!   page_down: [pagedown, ctrl-f]
+//! ```
+//!
+//! Colours are ratatui colour names, `#rrggbb` or a 0-255 index. A key list
+//! replaces the default keys of that action. Plain characters cannot be
+//! bound because they are typed into the prompt, and Ctrl-C always exits.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use ratatui::style::Color;
+use serde::Deserialize;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum TuiConfigError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{path}: {message}")]
+    Invalid { path: String, message: String },
+}
+
+pub type Result<T> = std::result::Result<T, TuiConfigError>;
+
+/// Colours of the REPL, one per kind of output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// Your messages and the input line.
+    pub user: Color,
+    /// Agent name header and panel titles.
+    pub agent_name: Color,
+    /// Agent response text.
+    pub agent: Color,
+    pub tool_call: Color,
+    pub tool_result: Color,
+    pub error: Color,
+    /// Banner and other client messages.
+    pub info: Color,
+    /// Daemon lifecycle events (shown dimmed).
+    pub system: Color,
+    /// Borders, separators and hints.
+    pub border: Color,
+    /// Prompt and input border while ready for a message.
+    pub prompt: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            user: Color::White,
+            agent_name: Color::Cyan,
+            agent: Color::Green,
+            tool_call: Color::Yellow,
+            tool_result: Color::Gray,
+            error: Color::Red,
+            info: Color::Blue,
+            system: Color::Cyan,
+            border: Color::DarkGray,
+            prompt: Color::Green,
+        }
+    }
+}
+
+/// What a bound key does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    ScrollUp,
+    ScrollDown,
+    PageUp,
+    PageDown,
+}
+
+/// A key with the modifiers that must be held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyBindings {
+    bindings: Vec<(Key, Action)>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let plain = |code| Key {
+            code,
+            modifiers: KeyModifiers::NONE,
+        };
+        Self {
+            bindings: vec![
+                (plain(KeyCode::Up), Action::ScrollUp),
+                (plain(KeyCode::Down), Action::ScrollDown),
+                (plain(KeyCode::PageUp), Action::PageUp),
+                (plain(KeyCode::PageDown), Action::PageDown),
+            ],
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Action bound to a key press. Shift is ignored, since terminals
+    /// report it inconsistently.
+    pub fn action(&self, code: KeyCode, modifiers: KeyModifiers) -> Option<Action> {
+        let code = match code {
+            KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+            other => other,
+        };
+        let modifiers = modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        self.bindings
+            .iter()
+            .find(|(key, _)| key.code == code && key.modifiers == modifiers)
+            .map(|(_, action)| *action)
+    }
+
+    fn set(&mut self, action: Action, keys: Vec<Key>) {
+        self.bindings.retain(|(_, a)| *a != action);
+        self.bindings
+            .extend(keys.into_iter().map(|key| (key, action)));
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TuiConfig {
+    pub theme: Theme,
+    pub keys: KeyBindings,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TuiFile {
+    #[serde(default)]
+    colors: ColorsFile,
+    #[serde(default)]
+    keys: KeysFile,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ColorsFile {
+    user: Option<String>,
+    agent_name: Option<String>,
+    agent: Option<String>,
+    tool_call: Option<String>,
+    tool_result: Option<String>,
+    error: Option<String>,
+    info: Option<String>,
+    system: Option<String>,
+    border: Option<String>,
+    prompt: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeysFile {
+    scroll_up: Option<Vec<String>>,
+    scroll_down: Option<Vec<String>>,
+    page_up: Option<Vec<String>>,
+    page_down: Option<Vec<String>>,
+}
+
+/// `~/.bubbaloop/tui.yaml`
+pub fn config_path() -> PathBuf {
+    crate::daemon::registry::get_bubbaloop_home().join("tui.yaml")
+}
+
+/// Load the REPL settings, falling back to the defaults when the file
+/// does not exist.
+pub fn load() -> Result<TuiConfig> {
+    load_from(&config_path())
+}
+
+fn load_from(path: &Path) -> Result<TuiConfig> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => parse(&text).map_err(|message| TuiConfigError::Invalid {
+            path: path.display().to_string(),
+            message,
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(TuiConfig::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn parse(text: &str) -> std::result::Result<TuiConfig, String> {
+    let file: TuiFile = if text.trim().is_empty() {
+        TuiFile::default()
+    } else {
+        serde_yaml::from_str(text).map_err(|e| e.to_string())?
+    };
+    let mut config = TuiConfig::default();
+
+    let theme = &mut config.theme;
+    let colors = file.colors;
+    for (slot, value, name) in [
+        (&mut theme.user, colors.user, "user"),
+        (&mut theme.agent_name, colors.agent_name, "agent_name"),
+        (&mut theme.agent, colors.agent, "agent"),
+        (&mut theme.tool_call, colors.tool_call, "tool_call"),
+        (&mut theme.tool_result, colors.tool_result, "tool_result"),
+        (&mut theme.error, colors.error, "error"),
+        (&mut theme.info, colors.info, "info"),
+        (&mut theme.system, colors.system, "system"),
+        (&mut theme.border, colors.border, "border"),
+        (&mut theme.prompt, colors.prompt, "prompt"),
+    ] {
+        if let Some(value) = value {
+            *slot = Color::from_str(&value)
+                .map_err(|_| format!("colors.{}: unknown colour '{}'", name, value))?;
+        }
+    }
+
+    let keys = file.keys;
+    for (action, specs, name) in [
+        (Action::ScrollUp, keys.scroll_up, "scroll_up"),
+        (Action::ScrollDown, keys.scroll_down, "scroll_down"),
+        (Action::PageUp, keys.page_up, "page_up"),
+        (Action::PageDown, keys.page_down, "page_down"),
+    ] {
+        if let Some(specs) = specs {
+            let keys = specs
+                .iter()
+                .map(|spec| parse_key(spec).map_err(|e| format!("keys.{}: {}", name, e)))
+                .collect::<std::result::Result<Vec<_>, _>>()?;
+            config.keys.set(action, keys);
+        }
+    }
+    Ok(config)
+}
+
+/// Parse `ctrl-k`, `alt-down`, `pageup`, `f5`, ...
+fn parse_key(spec: &str) -> std::result::Result<Key, String> {
+    let lower = spec.trim().to_lowercase();
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = lower.as_str();
+    loop {
+        if let Some(r) = rest.strip_prefix("ctrl-") {
+            modifiers |= KeyModifiers::CONTROL;
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix("alt-") {
+            modifiers |= KeyModifiers::ALT;
+            rest = r;
+        } else {
+            break;
+        }
+    }
+    let function_key = rest
+        .strip_prefix('f')
+        .and_then(|n| n.parse::<u8>().ok())
+        .filter(|n| (1..=12).contains(n));
+    let code = match rest {
+        _ if function_key.is_some() => KeyCode::F(function_key.unwrap_or(1)),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "tab" => KeyCode::Tab,
+        "esc" => KeyCode::Esc,
+        c if c.chars().count() == 1 => {
+            if modifiers.is_empty() {
+                return Err(format!(
+                    "'{}' is typed into the prompt; use ctrl-{} or alt-{}",
+                    spec, c, c
+                ));
+            }
+            KeyCode::Char(c.chars().next().unwrap_or_default())
+        }
+        _ => return Err(format!("unknown key '{}'", spec)),
+    };
+    if code == KeyCode::Char('c') && modifiers == KeyModifiers::CONTROL {
+        return Err("ctrl-c always exits and cannot be rebound".to_string());
+    }
+    Ok(Key { code, modifiers })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_or_empty_file_gives_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tui.yaml");
+        assert_eq!(load_from(&path).unwrap(), TuiConfig::default());
+        std::fs::write(&path, "\n").unwrap();
+        assert_eq!(load_from(&path).unwrap(), TuiConfig::default());
+    }
+
+    #[test]
+    fn colours_and_keys_override_defaults() {
+        let config = parse(
+            "colors:\n  agent: lightgreen\n  border: \"#101010\"\n  info: \"8\"\n\
+             keys:\n  scroll_up: [up, ctrl-k]\n  page_down: [ALT-F]\n",
+        )
+        .unwrap();
+        assert_eq!(config.theme.agent, Color::LightGreen);
+        assert_eq!(config.theme.border, Color::Rgb(16, 16, 16));
+        assert_eq!(config.theme.info, Color::Indexed(8));
+        assert_eq!(config.theme.error, Color::Red);
+
+        let keys = &config.keys;
+        assert_eq!(
+            keys.action(KeyCode::Char('k'), KeyModifiers::CONTROL),
+            Some(Action::ScrollUp)
+        );
+        assert_eq!(
+            keys.action(KeyCode::Up, KeyModifiers::NONE),
+            Some(Action::ScrollUp)
+        );
+        assert_eq!(
+            keys.action(KeyCode::Char('F'), KeyModifiers::ALT | KeyModifiers::SHIFT),
+            Some(Action::PageDown)
+        );
+        // The list replaces the action's default keys.
+        assert_eq!(keys.action(KeyCode::PageDown, KeyModifiers::NONE), None);
+        assert_eq!(
+            keys.action(KeyCode::Down, KeyModifiers::NONE),
+            Some(Action::ScrollDown)
+        );
+        assert_eq!(keys.action(KeyCode::Char('k'), KeyModifiers::NONE), None);
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        assert!(parse("colors:\n  agent: notacolour\n")
+            .unwrap_err()
+            .contains("colors.agent"));
+        assert!(parse("colours:\n  agent: red\n").is_err());
+        assert!(parse("keys:\n  scroll_up: [k]\n")
+            .unwrap_err()
+            .contains("typed into the prompt"));
+        assert!(parse("keys:\n  quit: [ctrl-q]\n").is_err());
+        assert!(parse_key("ctrl-c").is_err());
+        assert!(parse_key("hyper-x").is_err());
+        assert_eq!(parse_key("f5").unwrap().code, KeyCode::F(5));
+    }
+}
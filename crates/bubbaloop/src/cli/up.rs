@@ -3,6 +3,9 @@
 //! Reads all YAML skill files from `~/.bubbaloop/skills/`, resolves the
 //! corresponding marketplace nodes, installs missing nodes, injects
 //! per-skill config, and prints a summary of what was done.
+//!
+//! With `--profile <name>`, brings up a named stack from
+//! `~/.bubbaloop/profiles.yaml` instead (see [`super::up_profile`]).
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -27,6 +30,10 @@ pub enum UpError {
     Registry(String),
     #[error("Daemon error: {0}")]
     Daemon(String),
+    #[error("Profile error: {0}")]
+    Profile(String),
+    #[error("{0}")]
+    Unhealthy(String),
 }
 
 pub type Result<T> = std::result::Result<T, UpError>;
@@ -42,10 +49,19 @@ pub struct UpCommand {
     /// dry run — show what would be done without doing it
     #[argh(switch)]
     pub dry_run: bool,
+
+    /// bring up this profile from ~/.bubbaloop/profiles.yaml (zenohd, the
+    /// daemon, nodes and launch files) and wait until it is healthy
+    #[argh(option, short = 'p')]
+    pub profile: Option<String>,
 }
 
 impl UpCommand {
     pub async fn run(&self) -> Result<()> {
+        if let Some(profile) = &self.profile {
+            return super::up_profile::run_profile(profile, self.dry_run).await;
+        }
+
         let skills_dir = match &self.skills_dir {
             Some(p) => PathBuf::from(p),
             None => get_bubbaloop_home().join("skills"),
@@ -101,110 +117,11 @@ impl UpCommand {
         let mut skipped_count: usize = 0;
 
         for skill in &active_skills {
-            println!("\n  skill:  {}", skill.name);
-            println!("  driver: {}", skill.driver);
-
-            let driver_entry = match skills::resolve_driver(&skill.driver) {
-                Some(d) => d,
-                None => {
-                    println!("  [skip] Unknown driver '{}'", skill.driver);
-                    skipped_count += 1;
-                    continue;
-                }
-            };
-
-            let marketplace_node = driver_entry.marketplace_node;
-            println!("  node:   {}", marketplace_node);
-
-            let registry_node = match registry::find_by_name(&registry_nodes, marketplace_node) {
-                Some(n) => n,
-                None => {
-                    println!("  [skip] Node '{}' not in registry", marketplace_node);
-                    skipped_count += 1;
-                    continue;
-                }
-            };
-
-            // Step 1: Download if not installed locally
-            let node_dir = if is_node_installed(marketplace_node) {
-                resolve_node_dir(&registry_node)
-            } else if self.dry_run {
-                println!("  [dry-run] Would install {}", marketplace_node);
-                skipped_count += 1;
-                continue;
-            } else {
-                println!("  Installing {}...", marketplace_node);
-                let dir = marketplace::download_precompiled(&registry_node)?;
-                println!("  Downloaded to {}", dir);
-                PathBuf::from(&dir)
-            };
-
-            // Step 2: Write per-skill config (each skill gets its own file)
-            let config_path = if !skill.config.is_empty() && !self.dry_run {
-                let cfg_dir = get_bubbaloop_home().join("skills-config").join(&skill.name);
-                match write_node_config(&cfg_dir, &skill.config) {
-                    Ok(()) => {
-                        let p = cfg_dir.join("config.yaml");
-                        println!("  Config written to {}", p.display());
-                        Some(p.display().to_string())
-                    }
-                    Err(e) => {
-                        println!("  [warn] Config write failed: {}", e);
-                        None
-                    }
-                }
-            } else {
-                None
-            };
-
-            if self.dry_run {
-                println!("  [dry-run] Would register + start {}", skill.name);
-                continue;
-            }
-
-            // Step 3: Register with daemon as a named instance
-            // Each skill becomes its own node instance (e.g. "entrance-cam" backed by rtsp-camera binary)
-            let node_path = node_dir.display().to_string();
-            let instance_name = &skill.name;
-            match client
-                .add_node(&node_path, Some(instance_name), config_path.as_deref())
-                .await
-            {
-                Ok(msg) => {
-                    log::debug!("Registered {}: {}", instance_name, msg);
-                }
-                Err(e) => {
-                    println!("  [warn] Could not register with daemon: {}", e);
-                    skipped_count += 1;
-                    continue;
-                }
-            }
-
-            // Step 4: Install systemd service
-            match client.send_node_command(instance_name, "install").await {
-                Ok(msg) => {
-                    log::debug!("Installed service for {}: {}", instance_name, msg);
-                }
-                Err(_) => {
-                    log::debug!("Service install for {} (may already exist)", instance_name);
-                }
-            }
-
-            // Step 5: Start the node
-            match client.send_node_command(instance_name, "start").await {
-                Ok(msg) => {
-                    if msg.contains("already") || msg.contains("Running") {
-                        println!("  [ok] Already running");
-                        already_running += 1;
-                    } else {
-                        println!("  [ok] Started");
-                        started_count += 1;
-                    }
-                }
-                Err(e) => {
-                    println!("  [err] Failed to start: {}", e);
-                    skipped_count += 1;
-                }
+            match apply_skill(&client, &registry_nodes, skill, self.dry_run).await? {
+                SkillOutcome::Started => started_count += 1,
+                SkillOutcome::AlreadyRunning => already_running += 1,
+                SkillOutcome::Skipped => skipped_count += 1,
+                SkillOutcome::DryRun => {}
             }
         }
 
@@ -270,6 +187,126 @@ impl UpCommand {
     }
 }
 
+/// What applying one skill did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SkillOutcome {
+    Started,
+    AlreadyRunning,
+    Skipped,
+    DryRun,
+}
+
+/// Install the skill's driver node if needed, write its config, and
+/// register, install and start it as an instance named after the skill.
+pub(crate) async fn apply_skill(
+    client: &crate::cli::daemon_client::DaemonClient,
+    registry_nodes: &[registry::RegistryNode],
+    skill: &skills::SkillConfig,
+    dry_run: bool,
+) -> Result<SkillOutcome> {
+    println!("\n  skill:  {}", skill.name);
+    println!("  driver: {}", skill.driver);
+
+    let driver_entry = match skills::resolve_driver(&skill.driver) {
+        Some(d) => d,
+        None => {
+            println!("  [skip] Unknown driver '{}'", skill.driver);
+            return Ok(SkillOutcome::Skipped);
+        }
+    };
+
+    let marketplace_node = driver_entry.marketplace_node;
+    println!("  node:   {}", marketplace_node);
+
+    let registry_node = match registry::find_by_name(registry_nodes, marketplace_node) {
+        Some(n) => n,
+        None => {
+            println!("  [skip] Node '{}' not in registry", marketplace_node);
+            return Ok(SkillOutcome::Skipped);
+        }
+    };
+
+    // Step 1: Download if not installed locally
+    let node_dir = if is_node_installed(marketplace_node) {
+        resolve_node_dir(&registry_node)
+    } else if dry_run {
+        println!("  [dry-run] Would install {}", marketplace_node);
+        return Ok(SkillOutcome::Skipped);
+    } else {
+        println!("  Installing {}...", marketplace_node);
+        let dir = marketplace::download_precompiled(&registry_node)?;
+        println!("  Downloaded to {}", dir);
+        PathBuf::from(&dir)
+    };
+
+    // Step 2: Write per-skill config (each skill gets its own file)
+    let config_path = if !skill.config.is_empty() && !dry_run {
+        let cfg_dir = get_bubbaloop_home().join("skills-config").join(&skill.name);
+        match write_node_config(&cfg_dir, &skill.config) {
+            Ok(()) => {
+                let p = cfg_dir.join("config.yaml");
+                println!("  Config written to {}", p.display());
+                Some(p.display().to_string())
+            }
+            Err(e) => {
+                println!("  [warn] Config write failed: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    if dry_run {
+        println!("  [dry-run] Would register + start {}", skill.name);
+        return Ok(SkillOutcome::DryRun);
+    }
+
+    // Step 3: Register with daemon as a named instance
+    // Each skill becomes its own node instance (e.g. "entrance-cam" backed by rtsp-camera binary)
+    let node_path = node_dir.display().to_string();
+    let instance_name = &skill.name;
+    match client
+        .add_node(&node_path, Some(instance_name), config_path.as_deref())
+        .await
+    {
+        Ok(msg) => {
+            log::debug!("Registered {}: {}", instance_name, msg);
+        }
+        Err(e) => {
+            println!("  [warn] Could not register with daemon: {}", e);
+            return Ok(SkillOutcome::Skipped);
+        }
+    }
+
+    // Step 4: Install systemd service
+    match client.send_node_command(instance_name, "install").await {
+        Ok(msg) => {
+            log::debug!("Installed service for {}: {}", instance_name, msg);
+        }
+        Err(_) => {
+            log::debug!("Service install for {} (may already exist)", instance_name);
+        }
+    }
+
+    // Step 5: Start the node
+    match client.send_node_command(instance_name, "start").await {
+        Ok(msg) => {
+            if msg.contains("already") || msg.contains("Running") {
+                println!("  [ok] Already running");
+                Ok(SkillOutcome::AlreadyRunning)
+            } else {
+                println!("  [ok] Started");
+                Ok(SkillOutcome::Started)
+            }
+        }
+        Err(e) => {
+            println!("  [err] Failed to start: {}", e);
+            Ok(SkillOutcome::Skipped)
+        }
+    }
+}
+
 /// Return true if a node directory for `node_name` exists under `~/.bubbaloop/nodes/`.
 ///
 /// The layout is `~/.bubbaloop/nodes/<repo>/<subdir>` so we search two levels deep.
@@ -338,6 +375,7 @@ mod tests {
         let cmd = UpCommand {
             skills_dir: None,
             dry_run: false,
+            profile: None,
         };
         assert!(cmd.skills_dir.is_none());
         assert!(!cmd.dry_run);
@@ -348,6 +386,7 @@ mod tests {
         let cmd = UpCommand {
             skills_dir: Some("/tmp/skills".to_string()),
             dry_run: true,
+            profile: None,
         };
         assert_eq!(cmd.skills_dir.as_deref(), Some("/tmp/skills"));
         assert!(cmd.dry_run);
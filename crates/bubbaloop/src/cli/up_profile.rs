@@ -0,0 +1,595 @@
+//! `bubbaloop up --profile` — bring up a named stack with one command.
+//!
+//! Profiles live in `~/.bubbaloop/profiles.yaml`:
+//!
+//! ```yaml
+//! profiles:
+//!   cameras:
+//!     description: Terrace and entrance cameras
+//!     nodes: [tapo_terrace, tapo_entrance]
+//!     launch:
+//!       - ~/stacks/detection.launch.yaml
+//!       - file: stacks/recording.launch.yaml   # relative to profiles.yaml
+//!         args: ["fps:=10"]
+//!     timeout_secs: 90
+//! ```
+//!
+//! Bringing a profile up starts zenohd and the daemon when they are not
+//! running (through their systemd units when `bubbaloop setup` installed
+//! them), starts the listed nodes through the daemon, and runs each launch
+//! file as a detached `bubbaloop launch start`. It then waits until every
+//! node reports healthy and every autostart launch node is running.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use super::up::{Result, UpError};
+use crate::cli::daemon_client::DaemonClient;
+use crate::cli::doctor::fixes::FixAction;
+use crate::cli::launch::{self, LaunchNodeState, LaunchStatus};
+use crate::daemon::registry::get_bubbaloop_home;
+use crate::daemon::systemd::{get_systemd_user_dir, SystemdClient};
+use crate::schemas::{HealthStatus, NodeStatus};
+
+/// How often health is polled while waiting.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a launch status query waits for the launch to answer.
+const STATUS_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// `~/.bubbaloop/profiles.yaml`
+#[derive(Debug, Default, Deserialize)]
+pub struct ProfilesFile {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// One named stack.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Start zenohd when it is not running (default: true).
+    #[serde(default = "default_true")]
+    pub zenohd: bool,
+    /// Registered nodes to start through the daemon.
+    #[serde(default)]
+    pub nodes: Vec<String>,
+    /// Launch files to run.
+    #[serde(default)]
+    pub launch: Vec<LaunchEntry>,
+    /// How long to wait for everything to become healthy.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+/// A launch file, either as a bare path or with arguments.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum LaunchEntry {
+    Path(String),
+    Full {
+        file: String,
+        /// `name:=value` overrides, as for `launch start --arg`.
+        #[serde(default)]
+        args: Vec<String>,
+        /// Groups to start, as for `launch start --group`.
+        #[serde(default)]
+        groups: Vec<String>,
+    },
+}
+
+impl LaunchEntry {
+    fn file(&self) -> &str {
+        match self {
+            LaunchEntry::Path(file) | LaunchEntry::Full { file, .. } => file,
+        }
+    }
+
+    fn args(&self) -> &[String] {
+        match self {
+            LaunchEntry::Path(_) => &[],
+            LaunchEntry::Full { args, .. } => args,
+        }
+    }
+
+    fn groups(&self) -> &[String] {
+        match self {
+            LaunchEntry::Path(_) => &[],
+            LaunchEntry::Full { groups, .. } => groups,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_timeout_secs() -> u64 {
+    60
+}
+
+pub fn profiles_path() -> PathBuf {
+    get_bubbaloop_home().join("profiles.yaml")
+}
+
+/// Read `name` from the profiles file at `path`.
+pub fn load_profile(path: &Path, name: &str) -> Result<Profile> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        UpError::Profile(format!(
+            "cannot read {}: {} (see: bubbaloop up --help)",
+            path.display(),
+            e
+        ))
+    })?;
+    let file: ProfilesFile = serde_yaml::from_str(&content)
+        .map_err(|e| UpError::Profile(format!("{}: {}", path.display(), e)))?;
+    let names: Vec<&str> = file.profiles.keys().map(String::as_str).collect();
+    file.profiles.get(name).cloned().ok_or_else(|| {
+        UpError::Profile(format!(
+            "no profile '{}' in {} (available: {})",
+            name,
+            path.display(),
+            if names.is_empty() {
+                "none".to_string()
+            } else {
+                names.join(", ")
+            }
+        ))
+    })
+}
+
+/// Resolve a launch path from the profiles file: `~/` is the home
+/// directory and relative paths are relative to the file's directory.
+fn resolve_path(path: &str, base_dir: &Path) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/") {
+        return dirs::home_dir().unwrap_or_default().join(rest);
+    }
+    let path = Path::new(path);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        base_dir.join(path)
+    }
+}
+
+/// A launch file started (or found running) by the profile.
+struct PlannedLaunch {
+    name: String,
+    /// Names of the nodes that start without a `start:` action.
+    autostart: Vec<String>,
+}
+
+/// Bring up profile `name` and wait for it to become healthy.
+pub async fn run_profile(name: &str, dry_run: bool) -> Result<()> {
+    let path = profiles_path();
+    let profile = load_profile(&path, name)?;
+    let base_dir = path.parent().unwrap_or(Path::new(".")).to_path_buf();
+    match &profile.description {
+        Some(description) => println!("Bringing up profile '{}': {}", name, description),
+        None => println!("Bringing up profile '{}'", name),
+    }
+
+    // Resolve launch files first so a broken file fails before anything starts.
+    let mut launches = Vec::new();
+    for entry in &profile.launch {
+        let file = resolve_path(entry.file(), &base_dir);
+        let file_str = file.display().to_string();
+        let (_, _, plan) = launch::resolve_plan(&file_str, entry.args(), entry.groups())
+            .map_err(|e| UpError::Profile(format!("{}: {}", file_str, e)))?;
+        let planned = PlannedLaunch {
+            name: plan.name.clone(),
+            autostart: plan
+                .nodes
+                .iter()
+                .filter(|n| n.autostart)
+                .map(|n| n.name.clone())
+                .collect(),
+        };
+        launches.push((file, entry, planned));
+    }
+
+    if dry_run {
+        println!("[dry-run] No changes will be made.");
+        if profile.zenohd {
+            println!("  [dry-run] Would start zenohd if it is not running");
+        }
+        println!("  [dry-run] Would start the daemon if it is not running");
+        for node in &profile.nodes {
+            println!("  [dry-run] Would start node {}", node);
+        }
+        for (file, _, planned) in &launches {
+            println!(
+                "  [dry-run] Would launch '{}' from {} ({} node(s))",
+                planned.name,
+                file.display(),
+                planned.autostart.len()
+            );
+        }
+        return Ok(());
+    }
+
+    if profile.zenohd {
+        ensure_zenohd().await?;
+    }
+    let client = ensure_daemon().await?;
+
+    for node in &profile.nodes {
+        match client.send_node_command(node, "start").await {
+            Ok(_) => println!("  [ok] Started node {}", node),
+            Err(e) => println!("  [err] Failed to start node {}: {}", node, e),
+        }
+    }
+
+    let machine_id = crate::daemon::util::get_machine_id();
+    let session = crate::cli::zenoh_session::create_zenoh_session(None)
+        .await
+        .map_err(|e| UpError::Daemon(e.to_string()))?;
+    for (file, entry, planned) in &launches {
+        if launch_status(&session, &machine_id, &planned.name)
+            .await
+            .is_some()
+        {
+            println!("  [ok] Launch '{}' already running", planned.name);
+            continue;
+        }
+        let pid = spawn_launch(file, entry, &planned.name)?;
+        println!("  [ok] Launched '{}' (pid={})", planned.name, pid);
+    }
+
+    let timeout = Duration::from_secs(profile.timeout_secs);
+    println!(
+        "Waiting up to {}s for the profile to become healthy...",
+        timeout.as_secs()
+    );
+    let problems = wait_healthy(
+        &client,
+        &session,
+        &machine_id,
+        &profile.nodes,
+        &launches,
+        timeout,
+    )
+    .await;
+    if problems.is_empty() {
+        println!("Profile '{}' is up.", name);
+        Ok(())
+    } else {
+        for problem in &problems {
+            println!("  [err] {}", problem);
+        }
+        Err(UpError::Unhealthy(format!(
+            "profile '{}': {} component(s) not healthy",
+            name,
+            problems.len()
+        )))
+    }
+}
+
+/// Start zenohd, through its systemd unit when `bubbaloop setup` installed one.
+async fn ensure_zenohd() -> Result<()> {
+    if crate::cli::system_utils::is_process_running("zenohd").await {
+        println!("  [ok] zenohd running");
+        return Ok(());
+    }
+    if get_systemd_user_dir()
+        .join(crate::cli::setup::ZENOHD_UNIT)
+        .exists()
+    {
+        start_unit(crate::cli::setup::ZENOHD_UNIT).await?;
+        println!("  [ok] Started {}", crate::cli::setup::ZENOHD_UNIT);
+        return Ok(());
+    }
+    let message = FixAction::StartZenohd
+        .execute()
+        .await
+        .map_err(|e| UpError::Daemon(e.to_string()))?;
+    println!("  [ok] {}", message);
+    Ok(())
+}
+
+/// Start the daemon, through its systemd unit when one is installed, and
+/// wait for it to answer.
+async fn ensure_daemon() -> Result<DaemonClient> {
+    let client = DaemonClient::connect()
+        .await
+        .map_err(|e| UpError::Daemon(e.to_string()))?;
+    if client.is_running().await {
+        println!("  [ok] Daemon running");
+        return Ok(client);
+    }
+    if get_systemd_user_dir()
+        .join(crate::cli::setup::DAEMON_UNIT)
+        .exists()
+    {
+        start_unit(crate::cli::setup::DAEMON_UNIT).await?;
+        let started = Instant::now();
+        while !client.is_running().await {
+            if started.elapsed() > crate::cli::setup::DAEMON_READY_TIMEOUT {
+                return Err(UpError::Daemon(format!(
+                    "{} started but the daemon does not answer. Check: journalctl --user -u bubbaloop-daemon",
+                    crate::cli::setup::DAEMON_UNIT
+                )));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        println!("  [ok] Started {}", crate::cli::setup::DAEMON_UNIT);
+    } else {
+        client
+            .ensure_running()
+            .await
+            .map_err(|e| UpError::Daemon(e.to_string()))?;
+        println!("  [ok] Daemon started");
+    }
+    Ok(client)
+}
+
+async fn start_unit(unit: &str) -> Result<()> {
+    let systemd = SystemdClient::new()
+        .await
+        .map_err(|e| UpError::Daemon(format!("D-Bus connect failed: {}", e)))?;
+    systemd
+        .start_unit(unit)
+        .await
+        .map_err(|e| UpError::Daemon(e.to_string()))
+}
+
+/// Run `bubbaloop launch start` for `file` in the background, logging to
+/// `~/.bubbaloop/logs/up-{launch}.log`.
+fn spawn_launch(file: &Path, entry: &LaunchEntry, name: &str) -> Result<u32> {
+    let exe = std::env::current_exe()?;
+    let log_dir = get_bubbaloop_home().join("logs");
+    std::fs::create_dir_all(&log_dir)?;
+    let log_path = log_dir.join(format!("up-{}.log", name));
+    let log = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)?;
+    let mut command = std::process::Command::new(exe);
+    command.args(launch_args(file, entry));
+    // std::process rather than tokio, so the launch outlives this process.
+    let child = command
+        .stdout(log.try_clone()?)
+        .stderr(log)
+        .stdin(std::process::Stdio::null())
+        .spawn()?;
+    Ok(child.id())
+}
+
+fn launch_args(file: &Path, entry: &LaunchEntry) -> Vec<String> {
+    let mut args = vec![
+        "launch".to_string(),
+        "start".to_string(),
+        file.display().to_string(),
+    ];
+    for arg in entry.args() {
+        args.push("--arg".to_string());
+        args.push(arg.clone());
+    }
+    for group in entry.groups() {
+        args.push("--group".to_string());
+        args.push(group.clone());
+    }
+    args
+}
+
+async fn launch_status(
+    session: &zenoh::Session,
+    machine_id: &str,
+    name: &str,
+) -> Option<LaunchStatus> {
+    let replies = session
+        .get(launch::status_topic(machine_id, name))
+        .timeout(STATUS_TIMEOUT)
+        .await
+        .ok()?;
+    let reply = replies.recv_async().await.ok()?;
+    let sample = reply.into_result().ok()?;
+    serde_json::from_slice(&sample.payload().to_bytes()).ok()
+}
+
+/// Poll until every node and launch is healthy, something fails for good,
+/// or `timeout` expires. Returns what is not healthy.
+async fn wait_healthy(
+    client: &DaemonClient,
+    session: &Arc<zenoh::Session>,
+    machine_id: &str,
+    nodes: &[String],
+    launches: &[(PathBuf, &LaunchEntry, PlannedLaunch)],
+    timeout: Duration,
+) -> Vec<String> {
+    let started = Instant::now();
+    loop {
+        let mut pending = Vec::new();
+        let mut failed = Vec::new();
+
+        match client.node_states().await {
+            Ok(list) => {
+                for name in nodes {
+                    let state = list.nodes.iter().find(|n| &n.name == name);
+                    match node_health(state.map(|n| (n.status, n.health_status))) {
+                        Health::Healthy => {}
+                        Health::Pending(why) => pending.push(format!("node {}: {}", name, why)),
+                        Health::Failed(why) => failed.push(format!("node {}: {}", name, why)),
+                    }
+                }
+            }
+            Err(e) => pending.push(format!("daemon: {}", e)),
+        }
+        for (_, _, planned) in launches {
+            let status = launch_status(session, machine_id, &planned.name).await;
+            match launch_health(status.as_ref(), &planned.autostart) {
+                Health::Healthy => {}
+                Health::Pending(why) => pending.push(format!("launch {}: {}", planned.name, why)),
+                Health::Failed(why) => failed.push(format!("launch {}: {}", planned.name, why)),
+            }
+        }
+
+        if pending.is_empty() || !failed.is_empty() || started.elapsed() >= timeout {
+            failed.extend(pending);
+            return failed;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Health {
+    Healthy,
+    Pending(String),
+    Failed(String),
+}
+
+/// Health of a daemon-managed node from its `(status, health_status)`.
+fn node_health(state: Option<(i32, i32)>) -> Health {
+    let Some((status, health)) = state else {
+        return Health::Failed("not registered (see: bubbaloop node list)".to_string());
+    };
+    if status == NodeStatus::Failed as i32 {
+        return Health::Failed("failed (see: bubbaloop node logs)".to_string());
+    }
+    if status != NodeStatus::Running as i32 {
+        return Health::Pending("not running yet".to_string());
+    }
+    if health == HealthStatus::Healthy as i32 {
+        Health::Healthy
+    } else {
+        Health::Pending("running, no healthy heartbeat yet".to_string())
+    }
+}
+
+/// Health of a launch: every autostart node running.
+fn launch_health(status: Option<&LaunchStatus>, autostart: &[String]) -> Health {
+    let Some(status) = status else {
+        return Health::Pending("not answering yet".to_string());
+    };
+    let mut waiting = Vec::new();
+    for name in autostart {
+        match status
+            .nodes
+            .iter()
+            .find(|n| &n.name == name)
+            .map(|n| n.state)
+        {
+            Some(LaunchNodeState::Running) => {}
+            Some(LaunchNodeState::Failed) | Some(LaunchNodeState::Exited) => {
+                return Health::Failed(format!(
+                    "{} stopped (see: bubbaloop launch status {})",
+                    name, status.name
+                ));
+            }
+            _ => waiting.push(name.as_str()),
+        }
+    }
+    if waiting.is_empty() {
+        Health::Healthy
+    } else {
+        Health::Pending(format!("waiting for {}", waiting.join(", ")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILES: &str = r#"
+profiles:
+  cameras:
+    description: Terrace cameras
+    nodes: [tapo_terrace]
+    launch:
+      - stacks/detect.launch.yaml
+      - file: ~/rec.launch.yaml
+        args: ["fps:=10"]
+        groups: [rec]
+  minimal:
+    zenohd: false
+"#;
+
+    #[test]
+    fn loads_profiles_with_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.yaml");
+        std::fs::write(&path, PROFILES).unwrap();
+
+        let cameras = load_profile(&path, "cameras").unwrap();
+        assert!(cameras.zenohd);
+        assert_eq!(cameras.timeout_secs, 60);
+        assert_eq!(cameras.nodes, vec!["tapo_terrace"]);
+        assert_eq!(cameras.launch[0].file(), "stacks/detect.launch.yaml");
+        assert_eq!(
+            launch_args(Path::new("/r.yaml"), &cameras.launch[1]),
+            vec!["launch", "start", "/r.yaml", "--arg", "fps:=10", "--group", "rec"]
+        );
+
+        assert!(!load_profile(&path, "minimal").unwrap().zenohd);
+        let err = load_profile(&path, "lidar").unwrap_err().to_string();
+        assert!(err.contains("available: cameras, minimal"), "{}", err);
+    }
+
+    #[test]
+    fn launch_paths_resolve_against_profiles_dir() {
+        let base = Path::new("/home/u/.bubbaloop");
+        assert_eq!(
+            resolve_path("stacks/a.yaml", base),
+            Path::new("/home/u/.bubbaloop/stacks/a.yaml")
+        );
+        assert_eq!(resolve_path("/srv/a.yaml", base), Path::new("/srv/a.yaml"));
+        assert!(!resolve_path("~/a.yaml", base).starts_with(base));
+    }
+
+    #[test]
+    fn node_health_needs_running_and_heartbeat() {
+        let running = NodeStatus::Running as i32;
+        let healthy = HealthStatus::Healthy as i32;
+        assert_eq!(node_health(Some((running, healthy))), Health::Healthy);
+        assert!(matches!(
+            node_health(Some((running, 0))),
+            Health::Pending(_)
+        ));
+        assert!(matches!(
+            node_health(Some((NodeStatus::Failed as i32, 0))),
+            Health::Failed(_)
+        ));
+        assert!(matches!(node_health(None), Health::Failed(_)));
+    }
+
+    #[test]
+    fn launch_health_checks_autostart_nodes() {
+        let node = |name: &str, state: &str| {
+            serde_json::json!({
+                "name": name, "node": "n", "state": state, "pid": null,
+                "uptime_secs": null, "restarts": 0, "exit_code": null,
+            })
+        };
+        let status = |cam: &str| -> LaunchStatus {
+            serde_json::from_value(serde_json::json!({
+                "name": "stack", "machine_id": "m", "pid": 1, "uptime_secs": 3,
+                "nodes": [node("cam", cam), node("manual", "waiting")],
+            }))
+            .unwrap()
+        };
+        let autostart = vec!["cam".to_string()];
+        assert_eq!(
+            launch_health(Some(&status("running")), &autostart),
+            Health::Healthy
+        );
+        assert!(matches!(
+            launch_health(Some(&status("starting")), &autostart),
+            Health::Pending(_)
+        ));
+        assert!(matches!(
+            launch_health(Some(&status("failed")), &autostart),
+            Health::Failed(_)
+        ));
+        assert!(matches!(
+            launch_health(None, &autostart),
+            Health::Pending(_)
+        ));
+    }
+}
@@ -5,7 +5,12 @@
 //! No LLM involvement. Pure data pipeline.
 
 use rusqlite::{params, Connection};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use tokio::sync::mpsc;
 
 /// Configuration for a single context provider.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -205,55 +210,277 @@ pub fn parse_clause(clause: &str) -> Option<(&str, &str, &str)> {
     Some((field, op, rhs))
 }
 
-/// Minimal filter evaluator for "field=value AND field2>number" expressions.
-/// Supports =, !=, >, <, >=, <= operators. AND conjunction only.
-pub fn apply_filter(filter: &str, sample: &serde_json::Value) -> bool {
-    for clause in filter.split(" AND ") {
-        // Unparseable clause — skip (fail open for robustness).
-        let Some((field, op, expected)) = parse_clause(clause) else {
-            continue;
-        };
-        let actual = match sample.get(field) {
-            Some(v) => v,
-            None => return false,
-        };
+/// A parsed filter expression.
+///
+/// Clauses combine with `AND`, `OR` and `NOT` (upper case, surrounded by
+/// whitespace) and group with parentheses. `NOT` binds tightest, then
+/// `AND`, then `OR`, so `a = 1 OR b = 2 AND NOT c = 3` reads as
+/// `a = 1 OR (b = 2 AND (NOT c = 3))`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FilterExpr<'a> {
+    /// `(field, op, rhs)` from [`parse_clause`]; `None` for a clause with
+    /// no operator, which is skipped.
+    Clause(Option<(&'a str, &'a str, &'a str)>),
+    And(Vec<FilterExpr<'a>>),
+    Or(Vec<FilterExpr<'a>>),
+    Not(Box<FilterExpr<'a>>),
+}
 
-        // Try numeric comparison first
-        let expected_num = expected.parse::<f64>();
-        let actual_num = actual
-            .as_f64()
-            .or_else(|| actual.as_str().and_then(|s| s.parse::<f64>().ok()));
-
-        if let (Ok(exp), Some(act)) = (expected_num, actual_num) {
-            let pass = match op {
-                "=" => (act - exp).abs() < f64::EPSILON,
-                "!=" => (act - exp).abs() >= f64::EPSILON,
-                ">" => act > exp,
-                "<" => act < exp,
-                ">=" => act >= exp,
-                "<=" => act <= exp,
-                _ => true,
-            };
-            if !pass {
-                return false;
+impl<'a> FilterExpr<'a> {
+    /// Evaluate against a sample. `None` when every clause was skipped.
+    fn eval(&self, sample: &serde_json::Value) -> Option<bool> {
+        match self {
+            FilterExpr::Clause(clause) => {
+                clause.map(|(field, op, expected)| eval_clause(field, op, expected, sample))
             }
-        } else {
-            // String comparison
-            let actual_str = actual
-                .as_str()
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| actual.to_string());
-            let pass = match op {
-                "=" => actual_str == expected,
-                "!=" => actual_str != expected,
-                _ => false, // >, <, >=, <= not meaningful for strings
-            };
-            if !pass {
-                return false;
+            FilterExpr::And(items) => items
+                .iter()
+                .filter_map(|e| e.eval(sample))
+                .fold(None, |acc, v| Some(acc.unwrap_or(true) && v)),
+            FilterExpr::Or(items) => items
+                .iter()
+                .filter_map(|e| e.eval(sample))
+                .fold(None, |acc, v| Some(acc.unwrap_or(false) || v)),
+            FilterExpr::Not(inner) => inner.eval(sample).map(|v| !v),
+        }
+    }
+
+    /// Every well-formed clause, left to right.
+    pub fn clauses(&self) -> Vec<(&'a str, &'a str, &'a str)> {
+        let mut out = Vec::new();
+        self.collect_clauses(&mut out);
+        out
+    }
+
+    fn collect_clauses(&self, out: &mut Vec<(&'a str, &'a str, &'a str)>) {
+        match self {
+            FilterExpr::Clause(clause) => out.extend(*clause),
+            FilterExpr::And(items) | FilterExpr::Or(items) => {
+                items.iter().for_each(|e| e.collect_clauses(out))
+            }
+            FilterExpr::Not(inner) => inner.collect_clauses(out),
+        }
+    }
+}
+
+/// Parse a filter like `"label=dog AND (confidence>0.85 OR NOT zone=yard)"`.
+///
+/// Fails only on unbalanced parentheses or a `NOT` with nothing after it;
+/// clauses without an operator parse as skipped [`FilterExpr::Clause`]s.
+pub fn parse_filter(filter: &str) -> Result<FilterExpr<'_>, String> {
+    let mut depth = 0i32;
+    for c in filter.chars() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        if depth < 0 {
+            return Err("unbalanced parentheses: unexpected ')'".to_string());
+        }
+    }
+    if depth != 0 {
+        return Err("unbalanced parentheses: missing ')'".to_string());
+    }
+    parse_or(filter)
+}
+
+fn parse_or(filter: &str) -> Result<FilterExpr<'_>, String> {
+    let mut items = split_top_level(filter, " OR ")
+        .into_iter()
+        .map(parse_and)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(if items.len() == 1 {
+        items.remove(0)
+    } else {
+        FilterExpr::Or(items)
+    })
+}
+
+fn parse_and(filter: &str) -> Result<FilterExpr<'_>, String> {
+    let mut items = split_top_level(filter, " AND ")
+        .into_iter()
+        .map(parse_unary)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(if items.len() == 1 {
+        items.remove(0)
+    } else {
+        FilterExpr::And(items)
+    })
+}
+
+fn parse_unary(filter: &str) -> Result<FilterExpr<'_>, String> {
+    let filter = filter.trim();
+    if let Some(rest) = filter.strip_prefix("NOT") {
+        if rest.starts_with(|c: char| c.is_whitespace() || c == '(') {
+            if rest.trim().is_empty() {
+                return Err("NOT needs a condition after it".to_string());
+            }
+            return Ok(FilterExpr::Not(Box::new(parse_unary(rest)?)));
+        }
+        if rest.is_empty() {
+            return Err("NOT needs a condition after it".to_string());
+        }
+    }
+    if let Some(inner) = filter
+        .strip_prefix('(')
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        // Only a group if the first '(' closes at the very end: "(a) = (b)" is a clause.
+        if split_top_level(inner, ")").len() == 1 {
+            return parse_or(inner);
+        }
+    }
+    Ok(FilterExpr::Clause(parse_clause(filter)))
+}
+
+/// Split on `sep` outside parentheses. Parentheses must be balanced.
+fn split_top_level<'a>(filter: &'a str, sep: &str) -> Vec<&'a str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    let mut i = 0;
+    while i < filter.len() {
+        let rest = &filter[i..];
+        if depth == 0 && rest.starts_with(sep) {
+            parts.push(&filter[start..i]);
+            i += sep.len();
+            start = i;
+            continue;
+        }
+        match rest.as_bytes()[0] {
+            b'(' => depth += 1,
+            b')' => depth -= 1,
+            _ => {}
+        }
+        i += rest.chars().next().map_or(1, char::len_utf8);
+    }
+    parts.push(&filter[start..]);
+    parts
+}
+
+/// Filter evaluator for expressions like
+/// `"label=dog AND (confidence>0.85 OR NOT zone=yard)"`.
+/// Supports =, !=, >, <, >=, <= operators combined with AND, OR, NOT and
+/// parentheses (see [`FilterExpr`]). A filter with no usable clause
+/// matches; one with unbalanced parentheses never does.
+pub fn apply_filter(filter: &str, sample: &serde_json::Value) -> bool {
+    match parse_filter(filter) {
+        // Unparseable clauses are skipped (fail open for robustness).
+        Ok(expr) => expr.eval(sample).unwrap_or(true),
+        Err(_) => false,
+    }
+}
+
+fn eval_clause(field: &str, op: &str, expected: &str, sample: &serde_json::Value) -> bool {
+    let actual = match lookup_field(sample, field) {
+        Some(v) => v,
+        None => return false,
+    };
+
+    // Try numeric comparison first
+    let expected_num = expected.parse::<f64>();
+    let actual_num = actual
+        .as_f64()
+        .or_else(|| actual.as_str().and_then(|s| s.parse::<f64>().ok()));
+
+    if let (Ok(exp), Some(act)) = (expected_num, actual_num) {
+        match op {
+            "=" => (act - exp).abs() < f64::EPSILON,
+            "!=" => (act - exp).abs() >= f64::EPSILON,
+            ">" => act > exp,
+            "<" => act < exp,
+            ">=" => act >= exp,
+            "<=" => act <= exp,
+            _ => true,
+        }
+    } else {
+        // String comparison
+        let actual_str = actual
+            .as_str()
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| actual.to_string());
+        match op {
+            "=" => actual_str == expected,
+            "!=" => actual_str != expected,
+            _ => false, // >, <, >=, <= not meaningful for strings
+        }
+    }
+}
+
+/// One step of a field path: an object key or an array index.
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+/// Split `metrics.cpu.temp` or `entries[0].temperature_2m` into segments.
+/// `None` when a bracket is not a closed array index.
+fn path_segments(path: &str) -> Option<Vec<PathSegment<'_>>> {
+    let mut segments = Vec::new();
+    for part in path.split('.') {
+        let (key, mut rest) = part.split_at(part.find('[').unwrap_or(part.len()));
+        if !key.is_empty() {
+            segments.push(PathSegment::Key(key));
+        }
+        while !rest.is_empty() {
+            let (index, tail) = rest.strip_prefix('[')?.split_once(']')?;
+            segments.push(PathSegment::Index(index.trim().parse().ok()?));
+            rest = tail;
+        }
+    }
+    Some(segments)
+}
+
+/// Look a field up in a sample. A field naming a top-level key, dots and
+/// all, is that key. Otherwise it is a path: dots step into objects,
+/// `[n]` into arrays, and a string holding JSON is stepped into as well,
+/// so `weather.entries[0].temperature_2m` reads inside the JSON text of a
+/// `weather` world-state entry. Keys that contain dots match before
+/// shorter ones, so `metrics.cpu.temp` finds a `metrics.cpu` key.
+pub fn lookup_field<'a>(
+    sample: &'a serde_json::Value,
+    field: &str,
+) -> Option<Cow<'a, serde_json::Value>> {
+    if let Some(value) = sample.get(field) {
+        return Some(Cow::Borrowed(value));
+    }
+    walk_path(sample, &path_segments(field)?)
+}
+
+fn walk_path<'a>(
+    value: &'a serde_json::Value,
+    segments: &[PathSegment<'_>],
+) -> Option<Cow<'a, serde_json::Value>> {
+    use serde_json::Value;
+    let Some(first) = segments.first() else {
+        return Some(Cow::Borrowed(value));
+    };
+    match (value, first) {
+        (Value::Array(items), PathSegment::Index(i)) => walk_path(items.get(*i)?, &segments[1..]),
+        (Value::Object(map), PathSegment::Key(_)) => {
+            let keys: Vec<&str> = segments
+                .iter()
+                .map_while(|s| match s {
+                    PathSegment::Key(key) => Some(*key),
+                    PathSegment::Index(_) => None,
+                })
+                .collect();
+            (1..=keys.len()).rev().find_map(|n| {
+                let child = map.get(&keys[..n].join("."))?;
+                walk_path(child, &segments[n..])
+            })
+        }
+        (Value::String(text), _) => {
+            let parsed: Value = serde_json::from_str(text).ok()?;
+            if !(parsed.is_object() || parsed.is_array()) {
+                return None;
             }
+            walk_path(&parsed, segments).map(|v| Cow::Owned(v.into_owned()))
         }
+        _ => None,
     }
-    true
 }
 
 /// Replace {field} placeholders in template with values from sample.
@@ -263,15 +490,7 @@ pub fn resolve_key_template(template: &str, sample: &serde_json::Value) -> Strin
     while let Some(start) = result.find('{') {
         if let Some(end) = result[start..].find('}') {
             let field = &result[start + 1..start + end];
-            let replacement = sample
-                .get(field)
-                .and_then(|v| v.as_str().map(|s| s.to_string()))
-                .unwrap_or_else(|| {
-                    sample
-                        .get(field)
-                        .map(|v| v.to_string().trim_matches('"').to_string())
-                        .unwrap_or_else(|| field.to_string())
-                });
+            let replacement = extract_field(field, sample).unwrap_or_else(|| field.to_string());
             result = format!(
                 "{}{}{}",
                 &result[..start],
@@ -285,9 +504,10 @@ pub fn resolve_key_template(template: &str, sample: &serde_json::Value) -> Strin
     result
 }
 
-/// Extract a field value from a JSON sample as a string.
+/// Extract a field value from a JSON sample as a string. `field` may be a
+/// path, see [`lookup_field`].
 pub fn extract_field(field: &str, sample: &serde_json::Value) -> Option<String> {
-    sample.get(field).map(|v| {
+    lookup_field(sample, field).map(|v| {
         if let Some(s) = v.as_str() {
             s.to_string()
         } else {
@@ -296,8 +516,138 @@ pub fn extract_field(field: &str, sample: &serde_json::Value) -> Option<String>
     })
 }
 
+/// The numeric value at the `confidence_field` path, or 1.0 when there is
+/// no such field or it is not a number.
+pub fn extract_confidence(field: Option<&str>, sample: &serde_json::Value) -> f64 {
+    field
+        .and_then(|f| lookup_field(sample, f))
+        .and_then(|v| v.as_f64())
+        .unwrap_or(1.0)
+}
+
+/// Samples a provider buffers between its subscriber and its workers.
+/// Samples arriving while the buffer is full are dropped and counted.
+pub const PROVIDER_QUEUE_CAPACITY: usize = 256;
+
+/// Workers that decode and filter one provider's samples.
+pub const PROVIDER_WORKERS: usize = 2;
+
+/// How often a provider that dropped samples says so.
+const DROP_REPORT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Sample counters of a running provider.
+#[derive(Debug, Default)]
+pub struct ProviderStats {
+    id: String,
+    topic_pattern: String,
+    received: AtomicU64,
+    dropped: AtomicU64,
+    skipped: AtomicU64,
+    filtered: AtomicU64,
+    rate_limited: AtomicU64,
+    written: AtomicU64,
+    failed: AtomicU64,
+}
+
+/// The counters of a provider at one moment, as reported by
+/// `get_system_status`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ProviderStatsSnapshot {
+    pub id: String,
+    pub topic_pattern: String,
+    /// Samples the subscriber received.
+    pub received: u64,
+    /// Samples dropped because the provider's queue was full.
+    pub dropped: u64,
+    /// Samples that could not be decoded or lacked the value field.
+    pub skipped: u64,
+    /// Samples the filter rejected.
+    pub filtered: u64,
+    /// Values not written because the key was written too recently.
+    pub rate_limited: u64,
+    pub written: u64,
+    /// Values the world state store failed to write.
+    pub failed: u64,
+}
+
+impl ProviderStats {
+    fn new(cfg: &ProviderConfig) -> Self {
+        Self {
+            id: cfg.id.clone(),
+            topic_pattern: cfg.topic_pattern.clone(),
+            ..Self::default()
+        }
+    }
+
+    fn count(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> ProviderStatsSnapshot {
+        ProviderStatsSnapshot {
+            id: self.id.clone(),
+            topic_pattern: self.topic_pattern.clone(),
+            received: self.received.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            skipped: self.skipped.load(Ordering::Relaxed),
+            filtered: self.filtered.load(Ordering::Relaxed),
+            rate_limited: self.rate_limited.load(Ordering::Relaxed),
+            written: self.written.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Counters of the providers running in this process, by provider ID.
+fn stats_registry() -> &'static Mutex<BTreeMap<String, Arc<ProviderStats>>> {
+    static REGISTRY: OnceLock<Mutex<BTreeMap<String, Arc<ProviderStats>>>> = OnceLock::new();
+    REGISTRY.get_or_init(Default::default)
+}
+
+/// Counters of every running provider.
+pub fn provider_stats() -> Vec<ProviderStatsSnapshot> {
+    let registry = stats_registry().lock().unwrap_or_else(|e| e.into_inner());
+    registry.values().map(|s| s.snapshot()).collect()
+}
+
+/// Queue `item` for the worker that handles `key`, so samples of one topic
+/// stay in order. Returns `false` once the workers are gone.
+fn enqueue<T>(queues: &[mpsc::Sender<T>], key: &str, item: T, stats: &ProviderStats) -> bool {
+    use std::hash::{Hash, Hasher};
+
+    ProviderStats::count(&stats.received);
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    key.hash(&mut hasher);
+    let queue = &queues[hasher.finish() as usize % queues.len()];
+    match queue.try_send(item) {
+        Ok(()) => true,
+        Err(mpsc::error::TrySendError::Full(_)) => {
+            ProviderStats::count(&stats.dropped);
+            true
+        }
+        Err(mpsc::error::TrySendError::Closed(_)) => false,
+    }
+}
+
+/// A world state value a worker extracted from a sample.
+struct WorldStateWrite {
+    key: String,
+    value: String,
+    confidence: f64,
+    source_topic: String,
+}
+
 /// Spawn a background task that subscribes to a Zenoh topic and writes to world state.
 ///
+/// Samples may be JSON, CBOR or protobuf (`application/protobuf;<type>`);
+/// all are turned into JSON before the filter and fields are applied.
+///
+/// The subscriber only queues samples: [`PROVIDER_WORKERS`] workers decode
+/// and filter them, and one blocking thread writes the values, so a
+/// high-rate topic or a slow disk never stalls the async runtime. When the
+/// queue is full, new samples are dropped and counted (see
+/// [`provider_stats`]) instead of backing up into Zenoh.
+///
 /// Each provider opens its own `SemanticStore` connection to the same database file.
 /// WAL mode supports concurrent writers serialized by SQLite.
 pub fn spawn_provider(
@@ -341,90 +691,61 @@ pub fn spawn_provider(
             }
         };
 
-        // Rate-limiting: track last write time per resolved key
-        let mut last_write: std::collections::HashMap<String, u64> =
-            std::collections::HashMap::new();
-        let min_interval = u64::from(cfg.min_interval_secs);
-        let max_age = i64::from(cfg.max_age_secs);
-        let default_confidence = 1.0f64;
+        let cfg = Arc::new(cfg);
+        let stats = Arc::new(ProviderStats::new(&cfg));
+        stats_registry()
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(cfg.id.clone(), stats.clone());
+
+        let (write_tx, write_rx) = mpsc::channel(PROVIDER_QUEUE_CAPACITY);
+        let writer = tokio::task::spawn_blocking({
+            let (cfg, stats) = (cfg.clone(), stats.clone());
+            move || write_world_state(&cfg, store, write_rx, &stats)
+        });
+        // Protobuf samples are decoded with the publishing node's
+        // descriptors, fetched once per node from its schema queryable, so
+        // filters and value fields work on camera, telemetry and weather
+        // topics as well as on JSON ones. The workers share one schema
+        // cache through clones of this decoder.
+        let decoder = crate::daemon::decode::Decoder::new(session.clone(), None);
+        let mut queues = Vec::with_capacity(PROVIDER_WORKERS);
+        let mut workers = Vec::with_capacity(PROVIDER_WORKERS);
+        for _ in 0..PROVIDER_WORKERS {
+            let (tx, rx) = mpsc::channel(PROVIDER_QUEUE_CAPACITY / PROVIDER_WORKERS);
+            queues.push(tx);
+            workers.push(tokio::spawn(evaluate_samples(
+                cfg.clone(),
+                decoder.clone(),
+                rx,
+                write_tx.clone(),
+                stats.clone(),
+            )));
+        }
+        drop(write_tx);
 
+        let mut drop_report = tokio::time::interval(DROP_REPORT_INTERVAL);
+        drop_report.tick().await;
+        let mut reported_drops = 0;
         loop {
             tokio::select! {
                 result = subscriber.recv_async() => {
-                    let sample = match result {
-                        Ok(s) => s,
-                        Err(_) => break,
-                    };
-
-                    // Parse UTF-8 payload
-                    let bytes = sample.payload().to_bytes();
-                    let text = match String::from_utf8(bytes.to_vec()) {
-                        Ok(t) => t,
-                        Err(_) => continue,
-                    };
-
-                    // Parse JSON
-                    let json: serde_json::Value = match serde_json::from_str(&text) {
-                        Ok(v) => v,
-                        Err(_) => continue,
-                    };
-
-                    // Apply filter
-                    if let Some(ref filter_expr) = cfg.filter {
-                        if !apply_filter(filter_expr, &json) {
-                            continue;
-                        }
+                    let Ok(sample) = result else { break };
+                    let key = sample.key_expr().to_string();
+                    if !enqueue(&queues, &key, sample, &stats) {
+                        break;
                     }
-
-                    // Resolve the world state key
-                    let resolved_key = resolve_key_template(&cfg.world_state_key_template, &json);
-
-                    // Rate-limit by resolved key
-                    let now_secs = crate::agent::memory::now_epoch_secs();
-                    if let Some(&last) = last_write.get(&resolved_key) {
-                        if now_secs.saturating_sub(last) < min_interval {
-                            continue;
-                        }
-                    }
-
-                    // Extract value
-                    let value = match extract_field(&cfg.value_field, &json) {
-                        Some(v) => v,
-                        None => continue,
-                    };
-
-                    // Extract confidence
-                    let confidence = cfg
-                        .confidence_field
-                        .as_ref()
-                        .and_then(|f| {
-                            json.get(f.as_str()).and_then(|v| v.as_f64())
-                        })
-                        .unwrap_or(default_confidence);
-
-                    let source_topic = Some(sample.key_expr().to_string());
-                    let source_node: Option<&str> = None;
-
-                    // Write to world state (blocking rusqlite call)
-                    let write_result = tokio::task::block_in_place(|| {
-                        store.upsert_world_state(
-                            &resolved_key,
-                            &value,
-                            confidence,
-                            source_topic.as_deref(),
-                            source_node,
-                            max_age,
-                        )
-                    });
-
-                    if let Err(e) = write_result {
+                }
+                _ = drop_report.tick() => {
+                    let dropped = stats.dropped.load(Ordering::Relaxed);
+                    if dropped > reported_drops {
                         log::warn!(
-                            "[ContextProvider] Failed to write world state for key '{}': {}",
-                            resolved_key,
-                            e
+                            "[ContextProvider] Provider '{}' dropped {} sample(s) in the last {}s: queue full",
+                            cfg.id,
+                            dropped - reported_drops,
+                            DROP_REPORT_INTERVAL.as_secs()
                         );
-                    } else {
-                        last_write.insert(resolved_key, now_secs);
+                        reported_drops = dropped;
                     }
                 }
                 _ = shutdown.changed() => {
@@ -433,10 +754,125 @@ pub fn spawn_provider(
             }
         }
 
+        // Closing the queues stops the workers, and the writer once they
+        // are done.
+        drop(queues);
+        for worker in workers {
+            let _ = worker.await;
+        }
+        let _ = writer.await;
+        {
+            let mut registry = stats_registry().lock().unwrap_or_else(|e| e.into_inner());
+            if registry
+                .get(&cfg.id)
+                .is_some_and(|s| Arc::ptr_eq(s, &stats))
+            {
+                registry.remove(&cfg.id);
+            }
+        }
+
         log::info!("[ContextProvider] Provider '{}' shutting down", cfg.id);
     })
 }
 
+/// Worker: decode and filter queued samples, and hand the values to the
+/// writer.
+async fn evaluate_samples(
+    cfg: Arc<ProviderConfig>,
+    decoder: crate::daemon::decode::Decoder,
+    mut samples: mpsc::Receiver<zenoh::sample::Sample>,
+    writes: mpsc::Sender<WorldStateWrite>,
+    stats: Arc<ProviderStats>,
+) {
+    while let Some(sample) = samples.recv().await {
+        // Decode to JSON; text and undecodable bytes are skipped.
+        let bytes = sample.payload().to_bytes();
+        let decoded = decoder
+            .decode(
+                sample.key_expr().as_str(),
+                &sample.encoding().to_string(),
+                &bytes,
+            )
+            .await;
+        if !decoded.structured {
+            ProviderStats::count(&stats.skipped);
+            continue;
+        }
+        let json = decoded.value;
+
+        // Apply filter
+        if let Some(ref filter_expr) = cfg.filter {
+            if !apply_filter(filter_expr, &json) {
+                ProviderStats::count(&stats.filtered);
+                continue;
+            }
+        }
+
+        // Extract value
+        let Some(value) = extract_field(&cfg.value_field, &json) else {
+            ProviderStats::count(&stats.skipped);
+            continue;
+        };
+
+        let confidence = extract_confidence(cfg.confidence_field.as_deref(), &json);
+
+        let write = WorldStateWrite {
+            key: resolve_key_template(&cfg.world_state_key_template, &json),
+            value,
+            confidence,
+            source_topic: sample.key_expr().to_string(),
+        };
+        if writes.send(write).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Writer: runs on a blocking thread and owns the provider's world state
+/// connection, rate-limiting writes per resolved key.
+fn write_world_state(
+    cfg: &ProviderConfig,
+    store: crate::agent::memory::semantic::SemanticStore,
+    mut writes: mpsc::Receiver<WorldStateWrite>,
+    stats: &ProviderStats,
+) {
+    let mut last_write: HashMap<String, u64> = HashMap::new();
+    let min_interval = u64::from(cfg.min_interval_secs);
+    let max_age = i64::from(cfg.max_age_secs);
+
+    while let Some(write) = writes.blocking_recv() {
+        let now_secs = crate::agent::memory::now_epoch_secs();
+        if let Some(&last) = last_write.get(&write.key) {
+            if now_secs.saturating_sub(last) < min_interval {
+                ProviderStats::count(&stats.rate_limited);
+                continue;
+            }
+        }
+        let result = store.upsert_world_state(
+            &write.key,
+            &write.value,
+            write.confidence,
+            Some(&write.source_topic),
+            None,
+            max_age,
+        );
+        match result {
+            Ok(()) => {
+                ProviderStats::count(&stats.written);
+                last_write.insert(write.key, now_secs);
+            }
+            Err(e) => {
+                ProviderStats::count(&stats.failed);
+                log::warn!(
+                    "[ContextProvider] Failed to write world state for key '{}': {}",
+                    write.key,
+                    e
+                );
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -558,6 +994,67 @@ mod tests {
         assert!(!apply_filter("missing_field=hello", &sample));
     }
 
+    #[test]
+    fn filter_or_not_and_groups() {
+        let sample = serde_json::json!({
+            "cpu_temp": 85,
+            "fan_rpm": 900,
+            "zone": "rack",
+        });
+
+        assert!(apply_filter("cpu_temp > 80 OR fan_rpm < 500", &sample));
+        assert!(!apply_filter("cpu_temp > 80 AND fan_rpm < 500", &sample));
+        assert!(apply_filter("NOT fan_rpm < 500", &sample));
+        assert!(!apply_filter(
+            "NOT (cpu_temp > 80 OR fan_rpm < 500)",
+            &sample
+        ));
+        // AND binds tighter than OR.
+        assert!(apply_filter(
+            "zone = yard AND fan_rpm < 500 OR cpu_temp > 80",
+            &sample
+        ));
+        assert!(!apply_filter(
+            "zone = yard AND (fan_rpm < 500 OR cpu_temp > 80)",
+            &sample
+        ));
+        // A missing field fails its clause, so NOT of it matches.
+        assert!(apply_filter("NOT missing = 1", &sample));
+        // Skipped clauses don't decide an OR.
+        assert!(!apply_filter("bogus OR zone = yard", &sample));
+        // Parentheses inside a value are part of the value.
+        let sample = serde_json::json!({"label": "person (adult)"});
+        assert!(apply_filter("label = person (adult)", &sample));
+        assert!(!apply_filter("(label = person", &sample));
+    }
+
+    #[test]
+    fn parse_filter_structure() {
+        let expr = parse_filter("a = 1 OR NOT (b > 2 AND c != x)").unwrap();
+        assert_eq!(
+            expr,
+            FilterExpr::Or(vec![
+                FilterExpr::Clause(Some(("a", "=", "1"))),
+                FilterExpr::Not(Box::new(FilterExpr::And(vec![
+                    FilterExpr::Clause(Some(("b", ">", "2"))),
+                    FilterExpr::Clause(Some(("c", "!=", "x"))),
+                ]))),
+            ])
+        );
+        assert_eq!(
+            expr.clauses(),
+            vec![("a", "=", "1"), ("b", ">", "2"), ("c", "!=", "x")]
+        );
+        assert!(parse_filter("a = 1 AND NOT").is_err());
+        assert!(parse_filter("a = 1)").is_err());
+        assert!(parse_filter("(a = 1").is_err());
+        // "NOTE = x" is a clause, not a negation.
+        assert_eq!(
+            parse_filter("NOTE = x").unwrap(),
+            FilterExpr::Clause(Some(("NOTE", "=", "x")))
+        );
+    }
+
     #[test]
     fn key_template_substitution() {
         let sample = serde_json::json!({"label": "dog"});
@@ -583,6 +1080,46 @@ mod tests {
         assert_eq!(extract_field("missing", &sample), None);
     }
 
+    #[test]
+    fn fields_are_paths_into_nested_samples() {
+        let sample = serde_json::json!({
+            "entries": [{"temperature_2m": 21.5}, {"temperature_2m": 19.0}],
+            "metrics": {"cpu": {"temp": 64}},
+            "metrics.gpu": {"temp": 80},
+            "matrix": [[1, 2], [3, 4]],
+            "raw": "{\"status\": {\"code\": 3}}",
+            "a.b": "flat",
+            "detections": [{"label": "person", "score": 0.87}],
+        });
+        let field = |path| extract_field(path, &sample);
+        assert_eq!(field("entries[1].temperature_2m").as_deref(), Some("19.0"));
+        assert_eq!(field("metrics.cpu.temp").as_deref(), Some("64"));
+        assert_eq!(field("metrics.gpu.temp").as_deref(), Some("80"));
+        assert_eq!(field("matrix[1][0]").as_deref(), Some("3"));
+        assert_eq!(field("raw.status.code").as_deref(), Some("3"));
+        assert_eq!(field("a.b").as_deref(), Some("flat"));
+        assert_eq!(field("entries[2].temperature_2m"), None);
+        assert_eq!(field("entries[x]"), None);
+        assert_eq!(field("entries[0"), None);
+        assert_eq!(field("metrics.cpu.temp.max"), None);
+
+        let confidence = |path| extract_confidence(path, &sample);
+        assert_eq!(confidence(Some("detections[0].score")), 0.87);
+        assert_eq!(confidence(Some("detections[0].label")), 1.0);
+        assert_eq!(confidence(Some("detections[1].score")), 1.0);
+        assert_eq!(confidence(None), 1.0);
+
+        assert!(apply_filter("entries[0].temperature_2m > 20", &sample));
+        assert!(apply_filter(
+            "metrics.cpu.temp < 70 AND raw.status.code = 3",
+            &sample
+        ));
+        assert_eq!(
+            resolve_key_template("cpu.{metrics.cpu.temp}", &sample),
+            "cpu.64"
+        );
+    }
+
     #[test]
     fn filter_not_equal() {
         let sample = serde_json::json!({"label": "dog"});
@@ -604,4 +1141,75 @@ mod tests {
         assert!(apply_filter("count<=10", &sample));
         assert!(!apply_filter("count<=4", &sample));
     }
+
+    fn test_provider() -> ProviderConfig {
+        ProviderConfig {
+            id: "cp-queue".to_string(),
+            mission_id: "mission-1".to_string(),
+            topic_pattern: "bubbaloop/**/status".to_string(),
+            world_state_key_template: "{name}.status".to_string(),
+            value_field: "status".to_string(),
+            filter: None,
+            min_interval_secs: 60,
+            max_age_secs: 300,
+            confidence_field: None,
+            token_budget: 50,
+        }
+    }
+
+    #[test]
+    fn enqueue_drops_samples_when_the_queue_is_full() {
+        let stats = ProviderStats::new(&test_provider());
+        let (tx_a, mut rx_a) = mpsc::channel(1);
+        let (tx_b, mut rx_b) = mpsc::channel(1);
+        let queues = [tx_a, tx_b];
+
+        // One topic always goes to the same worker, so its samples stay in
+        // order and the second one finds that worker's queue full.
+        for n in 0..3 {
+            assert!(enqueue(&queues, "bubbaloop/a/status", n, &stats));
+        }
+        let snapshot = stats.snapshot();
+        assert_eq!((snapshot.received, snapshot.dropped), (3, 2));
+        let queued: Vec<i32> = [rx_a.try_recv(), rx_b.try_recv()]
+            .into_iter()
+            .filter_map(Result::ok)
+            .collect();
+        assert_eq!(queued, vec![0]);
+
+        drop((rx_a, rx_b));
+        assert!(!enqueue(&queues, "bubbaloop/a/status", 3, &stats));
+    }
+
+    #[test]
+    fn writer_rate_limits_each_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("semantic.db");
+        let store = crate::agent::memory::semantic::SemanticStore::open(&db_path).unwrap();
+        let cfg = test_provider();
+        let stats = ProviderStats::new(&cfg);
+        let (tx, rx) = mpsc::channel(8);
+        for (key, value) in [
+            ("cam.status", "ok"),
+            ("cam.status", "down"),
+            ("fan.status", "ok"),
+        ] {
+            tx.blocking_send(WorldStateWrite {
+                key: key.to_string(),
+                value: value.to_string(),
+                confidence: 1.0,
+                source_topic: "bubbaloop/a/status".to_string(),
+            })
+            .unwrap();
+        }
+        drop(tx);
+
+        write_world_state(&cfg, store, rx, &stats);
+        let snapshot = stats.snapshot();
+        assert_eq!((snapshot.written, snapshot.rate_limited), (2, 1));
+        let store = crate::agent::memory::semantic::SemanticStore::open(&db_path).unwrap();
+        let entries = store.world_state_snapshot().unwrap();
+        let cam = entries.iter().find(|e| e.key == "cam.status").unwrap();
+        assert_eq!(cam.value, "ok");
+    }
 }
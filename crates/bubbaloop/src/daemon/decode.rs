@@ -0,0 +1,465 @@
+//! Sample payload decoding, shared by `topic echo` and context providers.
+//!
+//! The sample's encoding picks the decoder:
+//!
+//! - `application/protobuf;<type>`: decoded with the publishing node's
+//!   descriptors, fetched once per node from its `.../schema` queryable
+//!   into a [`SchemaCache`] shared by clones of the decoder, falling back
+//!   to the descriptors built into this binary
+//! - `application/json`: parsed as-is
+//! - `application/cbor`: converted to JSON
+//! - anything else: JSON if it parses, otherwise text, otherwise hex
+//!
+//! `--type` forces protobuf decoding as the given message type, for
+//! publishers that do not set an encoding.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use prost_reflect::{
+    DescriptorPool, DynamicMessage, Kind, MapKey, MessageDescriptor, ReflectMessage,
+};
+use serde_json::{json, Map, Value};
+use zenoh::query::QueryTarget;
+
+/// How long to wait for a node's schema queryable.
+const SCHEMA_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long to wait before asking a node that did not serve a schema again.
+const SCHEMA_RETRY: Duration = Duration::from_secs(60);
+
+/// Byte fields up to this length are shown as hex; longer ones by size.
+const INLINE_BYTES: usize = 32;
+
+/// A decoded payload and the format it was decoded as.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Decoded {
+    pub format: String,
+    pub value: Value,
+    /// Decoded into fields (JSON, CBOR or protobuf with a schema), as
+    /// opposed to text or raw bytes.
+    pub structured: bool,
+}
+
+impl Decoded {
+    fn new(format: impl Into<String>, value: Value) -> Self {
+        Self {
+            format: format.into(),
+            value,
+            structured: true,
+        }
+    }
+
+    fn raw(format: impl Into<String>, value: Value) -> Self {
+        Self {
+            structured: false,
+            ..Self::new(format, value)
+        }
+    }
+}
+
+/// A fetched pool (`None` when the node served none) and when it was
+/// fetched.
+type CachedPool = (Option<DescriptorPool>, Instant);
+
+/// One key's cache slot, locked while its schema is being fetched.
+type Slot = Arc<tokio::sync::Mutex<Option<CachedPool>>>;
+
+/// Descriptor pools of publishing nodes, by schema key. Clones share the
+/// cache, so several decoders fetch each node's schema once.
+#[derive(Clone, Default)]
+pub(crate) struct SchemaCache {
+    entries: Arc<Mutex<HashMap<String, Slot>>>,
+}
+
+impl SchemaCache {
+    /// The pool for `schema_key`, calling `fetch` when it is not cached
+    /// yet. A node that did not answer may simply not have started yet,
+    /// so a missing pool is fetched again after [`SCHEMA_RETRY`].
+    /// Concurrent callers for one key wait for a single fetch.
+    async fn get_or_fetch<F>(
+        &self,
+        schema_key: &str,
+        fetch: impl FnOnce() -> F,
+    ) -> Option<DescriptorPool>
+    where
+        F: Future<Output = Option<DescriptorPool>>,
+    {
+        let entry = self
+            .entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(schema_key.to_string())
+            .or_default()
+            .clone();
+        let mut entry = entry.lock().await;
+        let stale = match &*entry {
+            None => true,
+            Some((pool, fetched)) => pool.is_none() && fetched.elapsed() >= SCHEMA_RETRY,
+        };
+        if stale {
+            let pool = fetch().await;
+            if pool.is_none() {
+                log::debug!("No schema from {}", schema_key);
+            }
+            *entry = Some((pool, Instant::now()));
+        }
+        entry.as_ref().and_then(|(pool, _)| pool.clone())
+    }
+}
+
+/// Decodes samples with the descriptor pool of each publishing node.
+/// Clones share one [`SchemaCache`].
+#[derive(Clone)]
+pub(crate) struct Decoder {
+    session: Arc<zenoh::Session>,
+    type_override: Option<String>,
+    schemas: SchemaCache,
+}
+
+impl Decoder {
+    pub(crate) fn new(session: Arc<zenoh::Session>, type_override: Option<String>) -> Self {
+        Self {
+            session,
+            type_override,
+            schemas: SchemaCache::default(),
+        }
+    }
+
+    pub(crate) async fn decode(&self, key: &str, encoding: &str, payload: &[u8]) -> Decoded {
+        let type_name = self
+            .type_override
+            .clone()
+            .or_else(|| protobuf_type(encoding).map(str::to_string));
+        match type_name {
+            Some(type_name) => {
+                let pool = match schema_key(key) {
+                    Some(schema_key) => self.pool(&schema_key).await,
+                    None => None,
+                };
+                match find_message(pool.as_ref(), &type_name) {
+                    Some(message) => decode_protobuf(&message, payload),
+                    None => Decoded::raw(
+                        format!("protobuf {} (no schema)", type_name),
+                        bytes_value(payload),
+                    ),
+                }
+            }
+            None => decode_untyped(encoding, payload),
+        }
+    }
+
+    async fn pool(&self, schema_key: &str) -> Option<DescriptorPool> {
+        self.schemas
+            .get_or_fetch(schema_key, || fetch_pool(&self.session, schema_key))
+            .await
+    }
+}
+
+/// `type_name` from a node's descriptors, or else from the descriptors
+/// built into this binary.
+pub(crate) fn find_message(
+    pool: Option<&DescriptorPool>,
+    type_name: &str,
+) -> Option<MessageDescriptor> {
+    pool.and_then(|pool| pool.get_message_by_name(type_name))
+        .or_else(|| crate::get_descriptor_pool().get_message_by_name(type_name))
+}
+
+/// Fetch the FileDescriptorSet served on `schema_key`.
+pub(crate) async fn fetch_pool(
+    session: &zenoh::Session,
+    schema_key: &str,
+) -> Option<DescriptorPool> {
+    let replies = session
+        .get(schema_key)
+        .target(QueryTarget::BestMatching)
+        .timeout(SCHEMA_TIMEOUT)
+        .await
+        .ok()?;
+    while let Ok(reply) = replies.recv_async().await {
+        if let Ok(sample) = reply.into_result() {
+            if let Ok(pool) = DescriptorPool::decode(sample.payload().to_bytes().as_ref()) {
+                return Some(pool);
+            }
+        }
+    }
+    None
+}
+
+/// The message type named by an `application/protobuf;<type>` encoding.
+fn protobuf_type(encoding: &str) -> Option<&str> {
+    let (mime, schema) = encoding.split_once(';')?;
+    (mime == "application/protobuf" && !schema.is_empty()).then_some(schema)
+}
+
+/// The schema queryable of the node publishing on `key`:
+/// `bubbaloop/{scope}/{machine}/{node}/schema`.
+pub(crate) fn schema_key(key: &str) -> Option<String> {
+    let segments: Vec<&str> = key.split('/').collect();
+    if segments.len() < 5 || segments[0] != "bubbaloop" {
+        return None;
+    }
+    Some(format!("{}/schema", segments[..4].join("/")))
+}
+
+pub(crate) fn decode_protobuf(message: &MessageDescriptor, payload: &[u8]) -> Decoded {
+    match DynamicMessage::decode(message.clone(), payload) {
+        Ok(msg) => Decoded::new(
+            format!("protobuf {}", message.full_name()),
+            message_to_json(&msg),
+        ),
+        Err(e) => Decoded::raw(
+            format!("protobuf {} (decode failed: {})", message.full_name(), e),
+            bytes_value(payload),
+        ),
+    }
+}
+
+pub(crate) fn decode_untyped(encoding: &str, payload: &[u8]) -> Decoded {
+    let mime = encoding.split(';').next().unwrap_or_default();
+    if mime == "application/cbor" {
+        if let Ok(value) = ciborium::from_reader::<ciborium::Value, _>(payload) {
+            return Decoded::new("cbor", cbor_to_json(&value));
+        }
+    }
+    if let Ok(value) = serde_json::from_slice::<Value>(payload) {
+        return Decoded::new("json", value);
+    }
+    match std::str::from_utf8(payload) {
+        Ok(text) => Decoded::raw("text", Value::String(text.to_string())),
+        Err(_) => Decoded::raw("binary", bytes_value(payload)),
+    }
+}
+
+/// Every field of `msg`, including proto3 defaults; unset fields with
+/// presence (messages, `optional`, oneof members) are left out.
+fn message_to_json(msg: &DynamicMessage) -> Value {
+    let mut object = Map::new();
+    for field in msg.descriptor().fields() {
+        if field.supports_presence() && !msg.has_field(&field) {
+            continue;
+        }
+        let value = msg.get_field(&field);
+        object.insert(
+            field.name().to_string(),
+            value_to_json(&value, &field.kind()),
+        );
+    }
+    Value::Object(object)
+}
+
+fn value_to_json(value: &prost_reflect::Value, kind: &Kind) -> Value {
+    use prost_reflect::Value as V;
+    match value {
+        V::Bool(b) => json!(b),
+        V::I32(n) => json!(n),
+        V::I64(n) => json!(n),
+        V::U32(n) => json!(n),
+        V::U64(n) => json!(n),
+        V::F32(f) => float(*f as f64),
+        V::F64(f) => float(*f),
+        V::String(s) => json!(s),
+        V::Bytes(b) => bytes_value(b),
+        V::EnumNumber(n) => kind
+            .as_enum()
+            .and_then(|e| e.get_value(*n))
+            .map(|v| json!(v.name()))
+            .unwrap_or_else(|| json!(n)),
+        V::Message(m) => message_to_json(m),
+        V::List(items) => Value::Array(items.iter().map(|v| value_to_json(v, kind)).collect()),
+        V::Map(entries) => {
+            let value_kind = kind
+                .as_message()
+                .map(|entry| entry.map_entry_value_field().kind())
+                .unwrap_or_else(|| kind.clone());
+            let mut entries: Vec<_> = entries
+                .iter()
+                .map(|(k, v)| (map_key(k), value_to_json(v, &value_kind)))
+                .collect();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(entries.into_iter().collect())
+        }
+    }
+}
+
+fn map_key(key: &MapKey) -> String {
+    match key {
+        MapKey::Bool(b) => b.to_string(),
+        MapKey::I32(n) => n.to_string(),
+        MapKey::I64(n) => n.to_string(),
+        MapKey::U32(n) => n.to_string(),
+        MapKey::U64(n) => n.to_string(),
+        MapKey::String(s) => s.clone(),
+    }
+}
+
+fn cbor_to_json(value: &ciborium::Value) -> Value {
+    use ciborium::Value as C;
+    match value {
+        C::Null => Value::Null,
+        C::Bool(b) => json!(b),
+        C::Integer(n) => {
+            let n = i128::from(*n);
+            i64::try_from(n)
+                .map(|n| json!(n))
+                .or_else(|_| u64::try_from(n).map(|n| json!(n)))
+                .unwrap_or_else(|_| json!(n.to_string()))
+        }
+        C::Float(f) => float(*f),
+        C::Text(s) => json!(s),
+        C::Bytes(b) => bytes_value(b),
+        C::Array(items) => Value::Array(items.iter().map(cbor_to_json).collect()),
+        C::Map(entries) => Value::Object(
+            entries
+                .iter()
+                .map(|(k, v)| {
+                    let key = match k {
+                        C::Text(s) => s.clone(),
+                        other => cbor_to_json(other).to_string(),
+                    };
+                    (key, cbor_to_json(v))
+                })
+                .collect(),
+        ),
+        C::Tag(_, inner) => cbor_to_json(inner),
+        _ => Value::Null,
+    }
+}
+
+/// JSON has no NaN or infinity; those are shown as strings.
+fn float(f: f64) -> Value {
+    serde_json::Number::from_f64(f)
+        .map(Value::Number)
+        .unwrap_or_else(|| json!(f.to_string()))
+}
+
+fn bytes_value(bytes: &[u8]) -> Value {
+    if bytes.len() <= INLINE_BYTES {
+        json!(hex::encode(bytes))
+    } else {
+        json!(format!("<{} bytes>", bytes.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::{DaemonNodeState, NodeList, NodeStatus};
+    use prost::Message;
+
+    fn message(name: &str) -> MessageDescriptor {
+        crate::get_descriptor_pool()
+            .get_message_by_name(name)
+            .unwrap()
+    }
+
+    #[test]
+    fn protobuf_type_and_schema_key() {
+        assert_eq!(
+            protobuf_type("application/protobuf;bubbaloop.header.v1.Header"),
+            Some("bubbaloop.header.v1.Header")
+        );
+        assert_eq!(protobuf_type("application/protobuf"), None);
+        assert_eq!(protobuf_type("application/json"), None);
+
+        assert_eq!(
+            schema_key("bubbaloop/global/orin/cam/compressed").as_deref(),
+            Some("bubbaloop/global/orin/cam/schema")
+        );
+        assert_eq!(schema_key("bubbaloop/global/orin/cam"), None);
+        assert_eq!(schema_key("other/global/orin/cam/compressed"), None);
+    }
+
+    #[test]
+    fn decodes_nested_protobuf_with_enum_names_and_defaults() {
+        let list = NodeList {
+            nodes: vec![DaemonNodeState {
+                name: "cam".to_string(),
+                status: NodeStatus::Running as i32,
+                build_output: vec!["ok".to_string()],
+                ..Default::default()
+            }],
+            timestamp_ms: 42,
+            machine_id: String::new(),
+        };
+        let decoded = decode_protobuf(
+            &message("bubbaloop.daemon.v1.NodeList"),
+            &list.encode_to_vec(),
+        );
+
+        assert_eq!(decoded.format, "protobuf bubbaloop.daemon.v1.NodeList");
+        assert!(decoded.structured);
+        assert_eq!(decoded.value["timestamp_ms"], 42);
+        assert_eq!(decoded.value["machine_id"], "");
+        let node = &decoded.value["nodes"][0];
+        assert_eq!(node["name"], "cam");
+        assert_eq!(node["status"], "NODE_STATUS_RUNNING");
+        assert_eq!(node["build_output"], json!(["ok"]));
+    }
+
+    #[test]
+    fn undecodable_protobuf_falls_back_to_bytes() {
+        let decoded = decode_protobuf(&message("bubbaloop.header.v1.Header"), &[0xff; 3]);
+        assert!(decoded.format.contains("decode failed"));
+        assert!(!decoded.structured);
+        assert_eq!(decoded.value, "ffffff");
+    }
+
+    #[test]
+    fn untyped_payloads_are_sniffed() {
+        let mut cbor = Vec::new();
+        ciborium::into_writer(
+            &ciborium::Value::Map(vec![
+                ("n".into(), 3.into()),
+                ("raw".into(), ciborium::Value::Bytes(vec![0u8; 64])),
+            ]),
+            &mut cbor,
+        )
+        .unwrap();
+        let decoded = decode_untyped("application/cbor", &cbor);
+        assert_eq!(decoded.format, "cbor");
+        assert_eq!(decoded.value, json!({"n": 3, "raw": "<64 bytes>"}));
+
+        assert_eq!(
+            decode_untyped("application/json", br#"{"a":1}"#).value,
+            json!({"a": 1})
+        );
+        assert_eq!(decode_untyped("", b"ok").format, "text");
+        assert!(!decode_untyped("", b"ok").structured);
+        assert_eq!(decode_untyped("", &[0xc3, 0x28]).value, "c328");
+    }
+
+    #[tokio::test]
+    async fn schema_cache_fetches_each_schema_once_across_clones() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let cache = SchemaCache::default();
+        let shared = cache.clone();
+        let fetches = AtomicUsize::new(0);
+        let fetch = || async {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            Some(crate::get_descriptor_pool().clone())
+        };
+        let key = "bubbaloop/global/orin/cam/schema";
+        let (a, b) = tokio::join!(
+            cache.get_or_fetch(key, fetch),
+            shared.get_or_fetch(key, fetch)
+        );
+        assert!(a.is_some() && b.is_some());
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        // A node that served no schema is not asked again right away.
+        let missing = || async {
+            fetches.fetch_add(1, Ordering::SeqCst);
+            None
+        };
+        let key = "bubbaloop/global/orin/other/schema";
+        assert!(cache.get_or_fetch(key, missing).await.is_none());
+        assert!(shared.get_or_fetch(key, missing).await.is_none());
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+    }
+}
@@ -60,6 +60,13 @@ pub enum DaemonCommandType {
         name: Option<String>,
         config: Option<String>,
     },
+    /// Register an instance of an already-installed node, with its config
+    /// sent inline (used by launch files dispatching to other machines).
+    AddInstance {
+        node: String,
+        name: String,
+        config_yaml: Option<String>,
+    },
     /// Remove a node by name.
     RemoveNode { name: String },
     /// Build a node by name.
@@ -74,6 +81,8 @@ pub enum DaemonCommandType {
     EnableAutostart { name: String },
     /// Disable autostart for a node.
     DisableAutostart { name: String },
+    /// Pull, rebuild and restart a node from its git checkout.
+    UpdateNode { name: String },
     /// Query daemon health.
     Health,
     /// Graceful daemon shutdown.
@@ -164,6 +173,10 @@ pub struct DaemonManifest {
     pub agent_count: usize,
     /// MCP server port.
     pub mcp_port: u16,
+    /// Wall clock of the daemon's machine when it answered (Unix ms), used
+    /// by `doctor` to detect clock skew. 0 from daemons that predate it.
+    #[serde(default)]
+    pub timestamp_ms: i64,
 }
 
 // ── JSON mirror types (for JSON queryable responses) ────────────
@@ -311,6 +324,149 @@ pub struct CommandResultJson {
     pub timestamp_ms: i64,
 }
 
+// ── Node logs ───────────────────────────────────────────────────
+
+/// Severity of a log line, from least to most severe.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        }
+    }
+}
+
+impl std::str::FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "debug" | "trace" => Ok(LogLevel::Debug),
+            "info" => Ok(LogLevel::Info),
+            "warn" | "warning" => Ok(LogLevel::Warn),
+            "error" | "err" => Ok(LogLevel::Error),
+            other => Err(format!(
+                "invalid log level '{}' (expected debug, info, warn or error)",
+                other
+            )),
+        }
+    }
+}
+
+/// Request sent as the CBOR payload of a query on [`logs_topic`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LogQuery {
+    /// Most recent lines to return when `after` is not set.
+    pub lines: usize,
+    /// Only lines logged at or after this time (milliseconds since epoch).
+    #[serde(default)]
+    pub since_ms: Option<i64>,
+    /// Only lines at this level or more severe.
+    #[serde(default)]
+    pub level: Option<LogLevel>,
+    /// Only lines matching this regex.
+    #[serde(default)]
+    pub grep: Option<String>,
+    /// Cursor of a previous [`LogChunk`]; only lines logged after it.
+    #[serde(default)]
+    pub after: Option<String>,
+}
+
+/// One line of node output.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LogLine {
+    /// When the line was logged (milliseconds since epoch, 0 if unknown).
+    pub timestamp_ms: i64,
+    pub level: LogLevel,
+    pub text: String,
+}
+
+/// Reply of the logs queryable.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LogChunk {
+    pub lines: Vec<LogLine>,
+    /// Pass back as [`LogQuery::after`] to get only newer lines.
+    pub cursor: String,
+}
+
+/// Request sent as the CBOR payload of a query on [`schemas_topic`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SchemaQuery {
+    /// Only this message type, with the descriptors of its versions.
+    /// Without it, every type is listed without descriptors.
+    #[serde(default)]
+    pub type_name: Option<String>,
+}
+
+/// A message type in the schema registry, with every definition seen.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RegisteredSchema {
+    pub type_name: String,
+    /// Oldest first; the last one is the most recently introduced.
+    pub versions: Vec<SchemaVersion>,
+}
+
+/// One definition of a message type.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SchemaVersion {
+    /// Starts at 1 and grows by one per new definition.
+    pub version: u32,
+    /// Hash of the type's definition and of every type it uses, in hex.
+    pub fingerprint: String,
+    pub first_seen_ms: i64,
+    pub last_seen_ms: i64,
+    /// `machine/node` of every node that served this definition.
+    pub sources: Vec<String>,
+    /// FileDescriptorSet defining the type. Only filled in when the query
+    /// names the type.
+    #[serde(default)]
+    pub descriptor: Vec<u8>,
+}
+
+/// Published on [`schema_changes_topic`] when a type first appears or its
+/// definition changes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SchemaChange {
+    pub type_name: String,
+    pub version: u32,
+    /// `None` when the type is new.
+    pub previous_version: Option<u32>,
+    pub fingerprint: String,
+    /// `machine/node` that served the new definition.
+    pub source: String,
+    pub timestamp_ms: i64,
+}
+
+/// One change of a node's status or health, as seen by the daemon.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HealthTransition {
+    /// When the change was observed (milliseconds since epoch).
+    pub timestamp_ms: i64,
+    /// Proto `NodeStatus` after the change.
+    pub status: i32,
+    /// Proto `HealthStatus` after the change.
+    pub health_status: i32,
+}
+
+/// Reply of the history queryable: the node's transitions, oldest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct HealthHistory {
+    /// Start of the recording (daemon start); nothing is known before it.
+    pub since_ms: i64,
+    pub transitions: Vec<HealthTransition>,
+}
+
 // ── Topic builders ──────────────────────────────────────────────
 
 /// Build the daemon command topic (CLI → Daemon).
@@ -348,6 +504,37 @@ pub fn nodes_topic(machine_id: &str) -> String {
     format!("bubbaloop/global/{}/daemon/nodes", machine_id)
 }
 
+/// Build the node logs topic (queryable — takes a CBOR [`LogQuery`],
+/// returns a CBOR [`LogChunk`]).
+///
+/// Format: `bubbaloop/global/{machine}/daemon/logs/{node}`
+pub fn logs_topic(machine_id: &str, node: &str) -> String {
+    format!("bubbaloop/global/{}/daemon/logs/{}", machine_id, node)
+}
+
+/// Build the node health history topic (queryable — returns a CBOR
+/// [`HealthHistory`]).
+///
+/// Format: `bubbaloop/global/{machine}/daemon/history/{node}`
+pub fn history_topic(machine_id: &str, node: &str) -> String {
+    format!("bubbaloop/global/{}/daemon/history/{}", machine_id, node)
+}
+
+/// Build the schema registry topic (queryable — takes an optional CBOR
+/// [`SchemaQuery`], returns a CBOR list of [`RegisteredSchema`]).
+///
+/// Format: `bubbaloop/global/{machine}/daemon/schemas`
+pub fn schemas_topic(machine_id: &str) -> String {
+    format!("bubbaloop/global/{}/daemon/schemas", machine_id)
+}
+
+/// Build the schema change notification topic (CBOR [`SchemaChange`]).
+///
+/// Format: `bubbaloop/global/{machine}/daemon/schema_changes`
+pub fn schema_changes_topic(machine_id: &str) -> String {
+    format!("bubbaloop/global/{}/daemon/schema_changes", machine_id)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -415,6 +602,11 @@ mod tests {
                 name: None,
                 config: None,
             },
+            DaemonCommandType::AddInstance {
+                node: "rtsp-camera".to_string(),
+                name: "entrance".to_string(),
+                config_yaml: Some("url: rtsp://x\n".to_string()),
+            },
             DaemonCommandType::RemoveNode {
                 name: "cam".to_string(),
             },
@@ -436,6 +628,9 @@ mod tests {
             DaemonCommandType::DisableAutostart {
                 name: "cam".to_string(),
             },
+            DaemonCommandType::UpdateNode {
+                name: "cam".to_string(),
+            },
             DaemonCommandType::Health,
             DaemonCommandType::Shutdown,
         ];
@@ -499,6 +694,7 @@ mod tests {
             node_count: 5,
             agent_count: 2,
             mcp_port: 8088,
+            timestamp_ms: 1_700_000_000_000,
         };
         let json = serde_json::to_string(&manifest).unwrap();
         let parsed: DaemonManifest = serde_json::from_str(&json).unwrap();
@@ -533,4 +729,33 @@ mod tests {
     fn manifest_wildcard_format() {
         assert_eq!(manifest_wildcard(), "bubbaloop/global/*/daemon/manifest");
     }
+
+    #[test]
+    fn logs_topic_format() {
+        assert_eq!(
+            logs_topic("jetson_orin", "camera"),
+            "bubbaloop/global/jetson_orin/daemon/logs/camera"
+        );
+        assert_eq!(
+            history_topic("jetson_orin", "camera"),
+            "bubbaloop/global/jetson_orin/daemon/history/camera"
+        );
+    }
+
+    #[test]
+    fn log_query_cbor_roundtrip_and_levels() {
+        let query = LogQuery {
+            lines: 50,
+            since_ms: Some(1_700_000_000_000),
+            level: Some(LogLevel::Warn),
+            grep: Some("frame;drop=\\d+".to_string()),
+            after: None,
+        };
+        let buf = to_cbor(&query).unwrap();
+        assert_eq!(from_cbor::<LogQuery>(&buf).unwrap(), query);
+
+        assert_eq!("WARNING".parse::<LogLevel>(), Ok(LogLevel::Warn));
+        assert!("loud".parse::<LogLevel>().is_err());
+        assert!(LogLevel::Error > LogLevel::Warn && LogLevel::Info > LogLevel::Debug);
+    }
 }
@@ -15,13 +15,22 @@
 pub mod belief_updater;
 pub mod constraints;
 pub mod context_provider;
+pub mod decode;
 pub mod federated;
 pub mod gateway;
 pub mod mission;
 pub mod native_supervisor;
 pub mod node_manager;
+pub mod notifications;
+pub mod overrides;
 pub mod reactive;
 pub mod registry;
+pub mod rule_actions;
+pub mod rule_events;
+pub mod rule_templates;
+pub mod rule_windows;
+pub mod rules_file;
+pub mod schema_registry;
 pub mod supervisor;
 pub mod systemd;
 pub mod telemetry;
@@ -179,6 +188,7 @@ async fn run_daemon_gateway(
                                     node_count: node_list.nodes.len(),
                                     agent_count: 0, // TODO: get from agent runtime
                                     mcp_port: manifest_port,
+                                    timestamp_ms: util::now_ms(),
                                 };
                                 let payload = gateway::to_cbor(&manifest).unwrap_or_default();
                                 let _ = query
@@ -246,7 +256,98 @@ async fn run_daemon_gateway(
         }
     });
 
-    // 3. Register command queryable (for dashboard / Zenoh GET clients)
+    // 3. Register logs queryable (CBOR LogQuery in, CBOR LogChunk out).
+    //    Lets `node logs` read and follow node output from any machine.
+    let logs_key = gateway::logs_topic(&machine_id, "*");
+    let logs_session = session.clone();
+    let logs_nm = node_manager.clone();
+    let mut logs_shutdown = shutdown_rx.clone();
+    tokio::spawn(async move {
+        match logs_session.declare_queryable(&logs_key).await {
+            Ok(queryable) => {
+                log::info!("[Gateway] Logs queryable registered: {}", logs_key);
+                loop {
+                    tokio::select! {
+                        result = queryable.recv_async() => {
+                            let Ok(query) = result else { break };
+                            let nm = logs_nm.clone();
+                            // journalctl can take a while; don't hold up other clients.
+                            tokio::spawn(async move {
+                                let key = query.key_expr().clone();
+                                let node = key.as_str().rsplit('/').next().unwrap_or_default().to_string();
+                                let request = match query.payload() {
+                                    Some(p) => gateway::from_cbor::<gateway::LogQuery>(&p.to_bytes()),
+                                    None => Ok(gateway::LogQuery { lines: 50, ..Default::default() }),
+                                };
+                                let result = match request {
+                                    Ok(request) => nm.read_logs(&node, &request).await.map_err(|e| e.to_string()),
+                                    Err(e) => Err(format!("Invalid logs query: {}", e)),
+                                };
+                                let sent = match result.and_then(|chunk| gateway::to_cbor(&chunk).map_err(|e| e.to_string())) {
+                                    Ok(buf) => query
+                                        .reply(&key, buf)
+                                        .encoding(zenoh::bytes::Encoding::APPLICATION_CBOR)
+                                        .await,
+                                    Err(msg) => query.reply_err(msg).await,
+                                };
+                                if let Err(e) = sent {
+                                    log::debug!("[Gateway] Logs reply failed: {}", e);
+                                }
+                            });
+                        }
+                        _ = logs_shutdown.changed() => break,
+                    }
+                }
+            }
+            Err(e) => {
+                log::warn!("[Gateway] Failed to register logs queryable: {}", e);
+            }
+        }
+    });
+
+    // 3b. Register history queryable (empty query in, CBOR HealthHistory out).
+    //     Lets `node history` show a node's health timeline.
+    let history_key = gateway::history_topic(&machine_id, "*");
+    let history_session = session.clone();
+    let history_nm = node_manager.clone();
+    let mut history_shutdown = shutdown_rx.clone();
+    tokio::spawn(async move {
+        match history_session.declare_queryable(&history_key).await {
+            Ok(queryable) => {
+                log::info!("[Gateway] History queryable registered: {}", history_key);
+                loop {
+                    tokio::select! {
+                        result = queryable.recv_async() => {
+                            let Ok(query) = result else { break };
+                            let key = query.key_expr().clone();
+                            let node = key.as_str().rsplit('/').next().unwrap_or_default();
+                            let result = history_nm
+                                .health_history(node)
+                                .await
+                                .map_err(|e| e.to_string())
+                                .and_then(|history| gateway::to_cbor(&history).map_err(|e| e.to_string()));
+                            let sent = match result {
+                                Ok(buf) => query
+                                    .reply(&key, buf)
+                                    .encoding(zenoh::bytes::Encoding::APPLICATION_CBOR)
+                                    .await,
+                                Err(msg) => query.reply_err(msg).await,
+                            };
+                            if let Err(e) = sent {
+                                log::debug!("[Gateway] History reply failed: {}", e);
+                            }
+                        }
+                        _ = history_shutdown.changed() => break,
+                    }
+                }
+            }
+            Err(e) => {
+                log::warn!("[Gateway] Failed to register history queryable: {}", e);
+            }
+        }
+    });
+
+    // 4. Register command queryable (for dashboard / Zenoh GET clients)
     //    Accepts JSON NodeCommandJson, returns JSON CommandResultJson.
     let cmd_queryable_key = gateway::command_topic(&machine_id);
     let cmd_queryable_session = session.clone();
@@ -381,7 +482,7 @@ async fn run_daemon_gateway(
         }
     });
 
-    // 4. Subscribe to command topic and dispatch (legacy JSON pub/sub for CLI clients).
+    // 5. Subscribe to command topic and dispatch (legacy JSON pub/sub for CLI clients).
     //    The JSON queryable above handles the dashboard's request/reply API.
     let cmd_topic = gateway::command_topic(&machine_id);
     let evt_topic = gateway::events_topic(&machine_id);
@@ -610,6 +711,16 @@ async fn dispatch_daemon_command(
                 Err(e) => events.push(gateway::DaemonEvent::error(id, &e.to_string())),
             }
         }
+        gateway::DaemonCommandType::UpdateNode { name } => {
+            validate_name!(name);
+            match platform
+                .execute_command(name, crate::mcp::platform::NodeCommand::Update)
+                .await
+            {
+                Ok(msg) => events.push(gateway::DaemonEvent::result(id, &msg)),
+                Err(e) => events.push(gateway::DaemonEvent::error(id, &e.to_string())),
+            }
+        }
         gateway::DaemonCommandType::InstallNode {
             source,
             name,
@@ -623,6 +734,18 @@ async fn dispatch_daemon_command(
                 Err(e) => events.push(gateway::DaemonEvent::error(id, &e.to_string())),
             }
         }
+        gateway::DaemonCommandType::AddInstance {
+            node,
+            name,
+            config_yaml,
+        } => {
+            validate_name!(node);
+            validate_name!(name);
+            match add_instance(platform, node, name, config_yaml.as_deref()).await {
+                Ok(msg) => events.push(gateway::DaemonEvent::result(id, &msg)),
+                Err(e) => events.push(gateway::DaemonEvent::error(id, &e)),
+            }
+        }
         gateway::DaemonCommandType::RemoveNode { name } => {
             validate_name!(name);
             match platform.remove_node(name).await {
@@ -639,6 +762,7 @@ async fn dispatch_daemon_command(
                 node_count: node_list.len(),
                 agent_count: 0,
                 mcp_port,
+                timestamp_ms: util::now_ms(),
             };
             let text = serde_json::to_string(&manifest).unwrap_or_default();
             events.push(gateway::DaemonEvent::result(id, &text));
@@ -654,6 +778,55 @@ async fn dispatch_daemon_command(
     events
 }
 
+/// Register `name` as an instance of the installed node `node`.
+///
+/// The inline config is written to `~/.bubbaloop/configs/{name}.yaml` on
+/// this machine. An instance that is already registered only gets its
+/// config rewritten, so re-running a launch is idempotent.
+/// Start of the `AddInstance` reply when the instance was already
+/// registered, so callers can tell an update from a new registration.
+pub const INSTANCE_UPDATED: &str = "Updated instance";
+
+async fn add_instance(
+    platform: &crate::mcp::platform::DaemonPlatform,
+    node: &str,
+    name: &str,
+    config_yaml: Option<&str>,
+) -> Result<String, String> {
+    use crate::mcp::platform::PlatformOperations;
+
+    let home = registry::get_bubbaloop_home();
+    let node_dir = registry::find_installed_node(&home.join("nodes"), node)
+        .ok_or_else(|| format!("node '{}' is not installed on this machine", node))?;
+
+    let config_path = match config_yaml {
+        Some(yaml) => {
+            serde_yaml::from_str::<serde_yaml::Value>(yaml)
+                .map_err(|e| format!("invalid config for '{}': {}", name, e))?;
+            let configs_dir = home.join("configs");
+            std::fs::create_dir_all(&configs_dir).map_err(|e| e.to_string())?;
+            let path = configs_dir.join(format!("{}.yaml", name));
+            std::fs::write(&path, yaml).map_err(|e| e.to_string())?;
+            Some(path.to_string_lossy().to_string())
+        }
+        None => None,
+    };
+
+    let registered = platform.list_nodes().await.unwrap_or_default();
+    if registered.iter().any(|n| n.name == name) {
+        return Ok(format!("{}: {}", INSTANCE_UPDATED, name));
+    }
+    let name_override = (name != node).then_some(name);
+    platform
+        .add_node(
+            &node_dir.to_string_lossy(),
+            name_override,
+            config_path.as_deref(),
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Run the daemon with the given configuration.
 ///
 /// This is the main entry point called by `bubbaloop daemon`.
@@ -789,6 +962,19 @@ pub async fn run(zenoh_endpoint: Option<String>) -> Result<(), Box<dyn std::erro
         })
     };
 
+    // Start schema registry (collects every node's schema queryable)
+    let schema_registry_task = {
+        let sr_session = session.clone();
+        let sr_shutdown = shutdown_rx.clone();
+        tokio::spawn(async move {
+            if let Err(e) =
+                schema_registry::run(sr_session, util::get_machine_id(), sr_shutdown).await
+            {
+                log::error!("Schema registry error: {}", e);
+            }
+        })
+    };
+
     log::info!("Bubbaloop skill runtime started.");
     log::info!("  MCP server: http://127.0.0.1:{}/mcp", mcp_port);
     log::info!("  Agent runtime: active");
@@ -796,6 +982,7 @@ pub async fn run(zenoh_endpoint: Option<String>) -> Result<(), Box<dyn std::erro
     log::info!("  Nodes: {} registered", initial_list.nodes.len());
     log::info!("  Health monitor: active (Zenoh heartbeats)");
     log::info!("  Telemetry watchdog: active");
+    log::info!("  Schema registry: active");
 
     // Wait for shutdown signal
     let mut shutdown_wait = shutdown_rx.clone();
@@ -804,7 +991,7 @@ pub async fn run(zenoh_endpoint: Option<String>) -> Result<(), Box<dyn std::erro
     log::info!("Shutdown signal received, waiting for tasks to gracefully finish...");
 
     // Wait for all tasks to complete gracefully (they all listen to shutdown_rx)
-    let _ = tokio::join!(mcp_task, agent_task, gateway_task);
+    let _ = tokio::join!(mcp_task, agent_task, gateway_task, schema_registry_task);
 
     log::info!("Bubbaloop daemon stopped.");
 
@@ -34,6 +34,30 @@ struct ProcConfig {
     depends_on: Vec<String>,
 }
 
+/// Default command for a node without an explicit `command:` in node.yaml.
+///
+/// - `rust`   → `<node_path>/target/release/<name>` (or debug if release absent)
+/// - `python` → `<node_path>/venv/bin/python main.py`
+/// - other    → `./<name>`
+pub(crate) fn default_command(node_path: &str, name: &str, node_type: &str) -> String {
+    match node_type {
+        "rust" => {
+            let release = Path::new(node_path).join("target/release").join(name);
+            let debug = Path::new(node_path).join("target/debug").join(name);
+            if release.exists() {
+                release.to_string_lossy().to_string()
+            } else {
+                debug.to_string_lossy().to_string()
+            }
+        }
+        "python" => {
+            let venv_python = Path::new(node_path).join("venv/bin/python");
+            format!("{} main.py", venv_python.display())
+        }
+        _ => format!("./{name}"),
+    }
+}
+
 /// Native process supervisor — manages processes directly without systemd.
 pub struct NativeSupervisor {
     /// Broadcast channel for lifecycle events (started, stopped, failed).
@@ -148,10 +172,8 @@ impl NativeSupervisor {
 
     /// Write process configuration. Equivalent to systemd `install_service`.
     ///
-    /// When `command` is `None`, a default is derived from `node_type`:
-    /// - `rust`   → `<node_path>/target/release/<name>` (or debug if release absent)
-    /// - `python` → `<node_path>/venv/bin/python main.py`
-    /// - other    → `./<name>`
+    /// When `command` is `None`, a default is derived from `node_type`
+    /// (see [`default_command`]).
     ///
     /// `depends_on` is persisted for informational purposes. The native backend
     /// does not enforce ordering — a warning is emitted at start time if non-empty.
@@ -165,22 +187,7 @@ impl NativeSupervisor {
     ) -> Result<()> {
         let cmd = match command {
             Some(c) => c.to_string(),
-            None => match node_type {
-                "rust" => {
-                    let release = Path::new(node_path).join("target/release").join(name);
-                    let debug = Path::new(node_path).join("target/debug").join(name);
-                    if release.exists() {
-                        release.to_string_lossy().to_string()
-                    } else {
-                        debug.to_string_lossy().to_string()
-                    }
-                }
-                "python" => {
-                    let venv_python = Path::new(node_path).join("venv/bin/python");
-                    format!("{} main.py", venv_python.display())
-                }
-                _ => format!("./{name}"),
-            },
+            None => default_command(node_path, name, node_type),
         };
 
         let config = ProcConfig {
@@ -6,7 +6,6 @@
 use super::{NodeManager, NodeManagerError, Result};
 use crate::daemon::systemd::ActiveState;
 use crate::schemas::daemon::v1::NodeStatus;
-use std::collections::VecDeque;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, BufReader};
@@ -15,6 +14,9 @@ use tokio::process::Command;
 /// Build timeout in seconds (10 minutes)
 const BUILD_TIMEOUT_SECS: u64 = 600;
 
+/// Timeout for the `git pull` of an update in seconds
+const GIT_PULL_TIMEOUT_SECS: u64 = 120;
+
 /// Maximum number of build output lines to retain
 const MAX_BUILD_OUTPUT_LINES: usize = 100;
 
@@ -85,6 +87,7 @@ impl NodeManager {
             node.build_state.status = status;
             node.build_state.output.clear();
             node.status = NodeStatus::Building;
+            self.record_history(node);
         }
         drop(nodes);
 
@@ -102,19 +105,7 @@ impl NodeManager {
             .begin_build_activity(name, BuildStatus::Building, "building")
             .await?;
 
-        // Get build command
-        let build_cmd = {
-            let nodes = self.nodes.read().await;
-            let node = nodes
-                .get(name)
-                .ok_or_else(|| NodeManagerError::NodeNotFound(name.to_string()))?;
-            node.manifest
-                .as_ref()
-                .and_then(|m| m.build.clone())
-                .ok_or_else(|| {
-                    NodeManagerError::BuildError("No build command defined".to_string())
-                })?
-        };
+        let build_cmd = self.build_command(name).await?;
 
         let name_clone = name.to_string();
         let path_clone = path.clone();
@@ -146,6 +137,98 @@ impl NodeManager {
         Ok(format!("Building {} (background)", name))
     }
 
+    /// Update a node from its git checkout: pull, rebuild, then restart the
+    /// nodes sharing its directory (base node and instances) that were running.
+    pub(crate) async fn update_node(
+        self: &Arc<Self>,
+        manager: Arc<Self>,
+        name: &str,
+    ) -> Result<String> {
+        let path = self.find_node_path(name).await?;
+        if !is_git_checkout(&path).await {
+            return Err(NodeManagerError::BuildError(format!(
+                "{} is not a git checkout; re-add the node to update it",
+                path
+            )));
+        }
+        let build_cmd = self.build_command(name).await?;
+        let (running, was_built) = {
+            let nodes = self.nodes.read().await;
+            let running: Vec<String> = nodes
+                .values()
+                .filter(|n| n.path == path && n.status == NodeStatus::Running)
+                .map(|n| n.effective_name().to_string())
+                .collect();
+            (running, nodes.get(name).is_some_and(|n| n.is_built))
+        };
+
+        let path = self
+            .begin_build_activity(name, BuildStatus::Building, "updating")
+            .await?;
+        let name_clone = name.to_string();
+
+        tokio::spawn(async move {
+            let result = async {
+                let head = git_head(&path).await;
+                let mut pull = Command::new("git");
+                pull.args(["pull", "--ff-only"])
+                    .current_dir(&path)
+                    .env("GIT_TERMINAL_PROMPT", "0")
+                    .kill_on_drop(true);
+                tokio::time::timeout(
+                    Duration::from_secs(GIT_PULL_TIMEOUT_SECS),
+                    run_streamed(&manager, pull, &name_clone),
+                )
+                .await
+                .map_err(|_| NodeManagerError::BuildError("git pull timed out".to_string()))??;
+                if was_built && head.is_some() && git_head(&path).await == head {
+                    push_output(&manager, &name_clone, "Already up to date, skipping build").await;
+                    return Ok(());
+                }
+                run_with_timeout(&manager, &path, &build_cmd, &name_clone).await
+            }
+            .await;
+
+            finish_build_activity(&manager, &name_clone, &result, "Update").await;
+
+            if result.is_ok() {
+                let mut nodes = manager.nodes.write().await;
+                if let Some(node) = nodes.get_mut(&name_clone) {
+                    node.is_built = true;
+                }
+                drop(nodes);
+            }
+
+            // Bring back what was running, on the old binary if the update failed.
+            for node in &running {
+                if let Err(e) = manager.supervisor.restart_unit(node).await {
+                    log::warn!("Failed to restart {} after update: {}", node, e);
+                }
+            }
+
+            let _ = manager.refresh_all().await;
+
+            match result {
+                Ok(_) => manager.emit_event("update_complete", &name_clone).await,
+                Err(_) => manager.emit_event("update_failed", &name_clone).await,
+            }
+        });
+
+        Ok(format!("Updating {} (background)", name))
+    }
+
+    /// The build command from a node's manifest.
+    async fn build_command(&self, name: &str) -> Result<String> {
+        let nodes = self.nodes.read().await;
+        let node = nodes
+            .get(name)
+            .ok_or_else(|| NodeManagerError::NodeNotFound(name.to_string()))?;
+        node.manifest
+            .as_ref()
+            .and_then(|m| m.build.clone())
+            .ok_or_else(|| NodeManagerError::BuildError("No build command defined".to_string()))
+    }
+
     /// Clean a node
     pub(crate) async fn clean_node(
         self: &Arc<Self>,
@@ -278,10 +361,18 @@ async fn run_build_command(
         home.join(".pixi/bin").display(),
     );
 
-    let mut child = Command::new("sh")
+    let mut command = Command::new("sh");
+    command
         .args(["-c", cmd])
         .current_dir(path)
-        .env("PATH", &build_path)
+        .env("PATH", &build_path);
+    run_streamed(manager, command, name).await
+}
+
+/// Run a command, appending its stdout and stderr lines to the node's
+/// build output as they arrive.
+async fn run_streamed(manager: &Arc<NodeManager>, mut command: Command, name: &str) -> Result<()> {
+    let mut child = command
         .stdout(std::process::Stdio::piped())
         .stderr(std::process::Stdio::piped())
         .kill_on_drop(true) // Kill child process if future is dropped (e.g., on timeout)
@@ -290,46 +381,21 @@ async fn run_build_command(
     let stdout = child.stdout.take();
     let stderr = child.stderr.take();
 
-    // Read stdout — uses VecDeque for efficient front removal
-    if let Some(stdout) = stdout {
-        let reader = BufReader::new(stdout);
-        let mut lines = reader.lines();
-        let manager = manager.clone();
-        let name = name.to_string();
-
-        tokio::spawn(async move {
-            let mut ring = VecDeque::with_capacity(MAX_BUILD_OUTPUT_LINES + 1);
-            while let Ok(Some(line)) = lines.next_line().await {
-                ring.push_back(line);
-                if ring.len() > MAX_BUILD_OUTPUT_LINES {
-                    ring.pop_front();
-                }
-                // Flush to shared state periodically
-                let mut nodes = manager.nodes.write().await;
-                if let Some(node) = nodes.get_mut(&name) {
-                    node.build_state.output = ring.iter().cloned().collect();
-                }
-            }
-        });
-    }
-
-    // Read stderr — uses VecDeque for efficient front removal
-    if let Some(stderr) = stderr {
-        let reader = BufReader::new(stderr);
-        let mut lines = reader.lines();
+    // Read stdout and stderr concurrently into the same output buffer
+    for stream in [
+        stdout.map(|s| Box::new(s) as Box<dyn tokio::io::AsyncRead + Send + Unpin>),
+        stderr.map(|s| Box::new(s) as Box<dyn tokio::io::AsyncRead + Send + Unpin>),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        let mut lines = BufReader::new(stream).lines();
         let manager = manager.clone();
         let name = name.to_string();
 
         tokio::spawn(async move {
             while let Ok(Some(line)) = lines.next_line().await {
-                let mut nodes = manager.nodes.write().await;
-                if let Some(node) = nodes.get_mut(&name) {
-                    node.build_state.output.push(line);
-                    // Keep only last MAX_BUILD_OUTPUT_LINES lines
-                    if node.build_state.output.len() > MAX_BUILD_OUTPUT_LINES {
-                        node.build_state.output.remove(0);
-                    }
-                }
+                push_output(&manager, &name, &line).await;
             }
         });
     }
@@ -347,6 +413,41 @@ async fn run_build_command(
     }
 }
 
+/// Append a line to the node's build output, keeping the last
+/// MAX_BUILD_OUTPUT_LINES lines.
+async fn push_output(manager: &Arc<NodeManager>, name: &str, line: &str) {
+    let mut nodes = manager.nodes.write().await;
+    if let Some(node) = nodes.get_mut(name) {
+        node.build_state.output.push(line.to_string());
+        if node.build_state.output.len() > MAX_BUILD_OUTPUT_LINES {
+            node.build_state.output.remove(0);
+        }
+    }
+}
+
+async fn is_git_checkout(path: &str) -> bool {
+    Command::new("git")
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .current_dir(path)
+        .output()
+        .await
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+async fn git_head(path: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(path)
+        .output()
+        .await
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,6 +109,7 @@ impl NodeManager {
                                 log::debug!("Received health heartbeat from node: {}", name);
                                 node.health_status = HealthStatus::Healthy;
                                 node.last_health_check_ms = now;
+                                manager_heartbeat.record_history(node);
                             }
                             found = true;
                             break;
@@ -156,12 +157,14 @@ impl NodeManager {
                                     age
                                 );
                                 node.health_status = HealthStatus::Unhealthy;
+                                manager.record_history(node);
                             }
                         }
                     } else {
                         // Reset health for non-running nodes
                         node.health_status = HealthStatus::Unknown;
                         node.last_health_check_ms = 0;
+                        manager.record_history(node);
                     }
                 }
             }
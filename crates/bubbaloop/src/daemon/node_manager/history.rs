@@ -0,0 +1,166 @@
+//! Rolling history of node status and health transitions.
+//!
+//! Every place that changes a cached node's status or health records the
+//! result here; only actual changes are kept, up to [`HISTORY_LEN`] per
+//! node. The history lives in memory and starts over when the daemon
+//! restarts. `bubbaloop node history` reads it through the history
+//! queryable.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::{CachedNode, NodeManager, NodeManagerError, Result};
+use crate::daemon::gateway::{self, HealthTransition};
+
+/// Transitions kept per node; a node flapping every minute still covers
+/// about four hours.
+pub const HISTORY_LEN: usize = 256;
+
+#[derive(Debug)]
+pub struct HealthHistory {
+    since_ms: i64,
+    nodes: HashMap<String, VecDeque<HealthTransition>>,
+}
+
+impl HealthHistory {
+    pub fn new(since_ms: i64) -> Self {
+        Self {
+            since_ms,
+            nodes: HashMap::new(),
+        }
+    }
+
+    /// Record the node's current state if it differs from the last one.
+    pub fn record(&mut self, node: &CachedNode, at_ms: i64) {
+        let status = node.effective_status() as i32;
+        let health_status = node.health_status as i32;
+        let transitions = self.nodes.entry(node.effective_name()).or_default();
+        if transitions
+            .back()
+            .is_some_and(|t| t.status == status && t.health_status == health_status)
+        {
+            return;
+        }
+        if transitions.len() == HISTORY_LEN {
+            transitions.pop_front();
+        }
+        transitions.push_back(HealthTransition {
+            timestamp_ms: at_ms,
+            status,
+            health_status,
+        });
+    }
+
+    /// Forget nodes that are no longer registered.
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+        self.nodes.retain(|name, _| keep(name));
+    }
+
+    pub fn get(&self, name: &str) -> gateway::HealthHistory {
+        gateway::HealthHistory {
+            since_ms: self.since_ms,
+            transitions: self
+                .nodes
+                .get(name)
+                .map(|t| t.iter().cloned().collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl NodeManager {
+    /// Record a node's state after it was changed.
+    pub(crate) fn record_history(&self, node: &CachedNode) {
+        self.history
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .record(node, Self::now_ms());
+    }
+
+    /// Status and health transitions of a registered node.
+    pub async fn health_history(&self, name: &str) -> Result<gateway::HealthHistory> {
+        if !self
+            .nodes
+            .read()
+            .await
+            .values()
+            .any(|n| n.effective_name() == name)
+        {
+            return Err(NodeManagerError::NodeNotFound(name.to_string()));
+        }
+        Ok(self
+            .history
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::node_manager::BuildState;
+    use crate::schemas::daemon::v1::{HealthStatus, NodeStatus};
+
+    fn node(status: NodeStatus, health: HealthStatus) -> CachedNode {
+        CachedNode {
+            path: "/nodes/camera".to_string(),
+            manifest: None,
+            status,
+            installed: true,
+            autostart_enabled: false,
+            is_built: true,
+            build_state: BuildState::default(),
+            last_updated_ms: 0,
+            health_status: health,
+            last_health_check_ms: 0,
+            name_override: Some("camera".to_string()),
+            config_override: None,
+        }
+    }
+
+    #[test]
+    fn records_only_changes() {
+        let mut history = HealthHistory::new(100);
+        history.record(&node(NodeStatus::Running, HealthStatus::Unknown), 1_000);
+        history.record(&node(NodeStatus::Running, HealthStatus::Unknown), 2_000);
+        history.record(&node(NodeStatus::Running, HealthStatus::Healthy), 3_000);
+        history.record(&node(NodeStatus::Failed, HealthStatus::Healthy), 4_000);
+
+        let got = history.get("camera");
+        assert_eq!(got.since_ms, 100);
+        let times: Vec<_> = got.transitions.iter().map(|t| t.timestamp_ms).collect();
+        assert_eq!(times, [1_000, 3_000, 4_000]);
+        assert_eq!(got.transitions[2].status, NodeStatus::Failed as i32);
+        assert!(history.get("other").transitions.is_empty());
+
+        history.retain(|name| name != "camera");
+        assert!(history.get("camera").transitions.is_empty());
+    }
+
+    #[test]
+    fn keeps_the_latest_transitions() {
+        let mut history = HealthHistory::new(0);
+        for i in 0..(HISTORY_LEN as i64 + 10) {
+            let status = if i % 2 == 0 {
+                NodeStatus::Running
+            } else {
+                NodeStatus::Failed
+            };
+            history.record(&node(status, HealthStatus::Unknown), i);
+        }
+        let got = history.get("camera").transitions;
+        assert_eq!(got.len(), HISTORY_LEN);
+        assert_eq!(got[0].timestamp_ms, 10);
+        assert_eq!(got.last().unwrap().timestamp_ms, HISTORY_LEN as i64 + 9);
+    }
+
+    #[test]
+    fn running_outside_systemd_counts_as_running() {
+        let mut history = HealthHistory::new(0);
+        history.record(&node(NodeStatus::Stopped, HealthStatus::Healthy), 1);
+        assert_eq!(
+            history.get("camera").transitions[0].status,
+            NodeStatus::Running as i32
+        );
+    }
+}
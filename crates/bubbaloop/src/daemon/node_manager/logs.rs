@@ -0,0 +1,287 @@
+//! Node log retrieval for the logs queryable.
+//!
+//! With the systemd backend lines come from the journal (`journalctl -o json`)
+//! and the cursor is the journal cursor of the last line read. With the native
+//! supervisor they come from `{procs_dir}/{name}.stdout` and `.stderr`, and the
+//! cursor holds the byte offsets read so far in both files; those lines carry
+//! no timestamp, so `since_ms` does not apply to them. Level and regex filters
+//! run here so remote clients only receive the lines they asked for.
+
+use std::io::SeekFrom;
+use std::path::Path;
+
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use super::{NodeManager, NodeManagerError, Result, JOURNALCTL_PATH};
+use crate::daemon::gateway::{LogChunk, LogLevel, LogLine, LogQuery};
+use crate::daemon::systemd;
+
+/// Upper bound of journal entries read per query, before filtering.
+const MAX_SCAN_LINES: usize = 5000;
+
+/// Bytes read from the end of a native log file on the first query.
+const NATIVE_TAIL_BYTES: u64 = 512 * 1024;
+
+impl NodeManager {
+    /// Read the output of node `name` as requested by `query`.
+    pub async fn read_logs(&self, name: &str, query: &LogQuery) -> Result<LogChunk> {
+        self.find_node_path(name).await?;
+        let grep = query
+            .grep
+            .as_deref()
+            .map(regex::Regex::new)
+            .transpose()
+            .map_err(|e| NodeManagerError::InvalidLogQuery(format!("grep: {}", e)))?;
+
+        let mut chunk = match self.supervisor.native_procs_dir() {
+            Some(dir) => read_native(dir, name, query.after.as_deref()).await?,
+            None => read_journal(name, query).await?,
+        };
+        filter_lines(&mut chunk.lines, query, grep.as_ref());
+        Ok(chunk)
+    }
+}
+
+async fn read_journal(name: &str, query: &LogQuery) -> Result<LogChunk> {
+    // Same unit filter as `get_logs`: user services log to the system journal.
+    let unit_filter = format!("_SYSTEMD_USER_UNIT={}", systemd::get_service_name(name));
+    let mut cmd = tokio::process::Command::new(JOURNALCTL_PATH);
+    cmd.args([unit_filter.as_str(), "--no-pager", "-o", "json"]);
+    match &query.after {
+        Some(cursor) => cmd.arg(format!("--after-cursor={}", cursor)),
+        None => cmd.args(["-n", &MAX_SCAN_LINES.to_string()]),
+    };
+    if let Some(since_ms) = query.since_ms {
+        cmd.arg(format!("--since=@{}", since_ms / 1000));
+    }
+    let output = cmd.output().await?;
+    Ok(parse_journal(
+        &String::from_utf8_lossy(&output.stdout),
+        query.after.as_deref(),
+    ))
+}
+
+/// Parse `journalctl -o json` output. The cursor stays at `after` when
+/// there are no new entries.
+fn parse_journal(output: &str, after: Option<&str>) -> LogChunk {
+    let mut chunk = LogChunk {
+        lines: Vec::new(),
+        cursor: after.unwrap_or_default().to_string(),
+    };
+    for entry in output.lines() {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(entry) else {
+            continue;
+        };
+        if let Some(cursor) = entry["__CURSOR"].as_str() {
+            chunk.cursor = cursor.to_string();
+        }
+        // MESSAGE is a byte array when it is not valid UTF-8.
+        let text = match &entry["MESSAGE"] {
+            serde_json::Value::String(s) => s.clone(),
+            serde_json::Value::Array(bytes) => {
+                let bytes: Vec<u8> = bytes
+                    .iter()
+                    .filter_map(|b| b.as_u64().map(|b| b as u8))
+                    .collect();
+                String::from_utf8_lossy(&bytes).into_owned()
+            }
+            _ => continue,
+        };
+        let timestamp_ms = entry["__REALTIME_TIMESTAMP"]
+            .as_str()
+            .and_then(|us| us.parse::<i64>().ok())
+            .map_or(0, |us| us / 1000);
+        let priority = entry["PRIORITY"].as_str().and_then(|p| p.parse().ok());
+        chunk.lines.push(LogLine {
+            timestamp_ms,
+            level: line_level(priority, &text),
+            text,
+        });
+    }
+    chunk
+}
+
+async fn read_native(dir: &Path, name: &str, after: Option<&str>) -> Result<LogChunk> {
+    let offsets = after.and_then(parse_native_cursor);
+    let mut lines = Vec::new();
+    let mut ends = [0u64; 2];
+    for (i, ext) in ["stdout", "stderr"].into_iter().enumerate() {
+        let path = dir.join(format!("{}.{}", name, ext));
+        let Ok(mut file) = tokio::fs::File::open(&path).await else {
+            continue;
+        };
+        let len = file.metadata().await?.len();
+        let start = match offsets {
+            // Restarts truncate the files; start over when one shrank.
+            Some(offsets) if offsets[i] <= len => offsets[i],
+            Some(_) => 0,
+            None => len.saturating_sub(NATIVE_TAIL_BYTES),
+        };
+        file.seek(SeekFrom::Start(start)).await?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf).await?;
+        let text = String::from_utf8_lossy(&buf);
+        let (complete, consumed) = complete_lines(&text, offsets.is_none() && start > 0);
+        ends[i] = start + consumed as u64;
+        lines.extend(complete.into_iter().map(|text| LogLine {
+            timestamp_ms: 0,
+            level: line_level(None, text),
+            text: text.to_string(),
+        }));
+    }
+    Ok(LogChunk {
+        lines,
+        cursor: format!("native:{}:{}", ends[0], ends[1]),
+    })
+}
+
+/// The complete lines of `text` and the bytes they span. A trailing partial
+/// line is left for the next read; with `skip_first` the first line is
+/// dropped too because the read started inside it.
+fn complete_lines(text: &str, skip_first: bool) -> (Vec<&str>, usize) {
+    let Some(end) = text.rfind('\n') else {
+        return (Vec::new(), 0);
+    };
+    let mut lines: Vec<&str> = text[..end].split('\n').collect();
+    if skip_first {
+        lines.remove(0);
+    }
+    (lines, end + 1)
+}
+
+fn parse_native_cursor(cursor: &str) -> Option<[u64; 2]> {
+    let mut parts = cursor.strip_prefix("native:")?.split(':');
+    let stdout = parts.next()?.parse().ok()?;
+    let stderr = parts.next()?.parse().ok()?;
+    Some([stdout, stderr])
+}
+
+/// Level of a line: the level word written by the node's logger when there
+/// is one, else the journal priority, else info.
+fn line_level(priority: Option<u8>, text: &str) -> LogLevel {
+    for word in text.split_whitespace().take(6) {
+        // Lowercase level words only count in logfmt, not in prose.
+        let word = match word.strip_prefix("level=") {
+            Some(level) => level.to_ascii_uppercase(),
+            None => word.to_string(),
+        };
+        let level = match word.trim_matches(|c: char| !c.is_ascii_alphabetic()) {
+            "ERROR" | "FATAL" | "CRITICAL" => LogLevel::Error,
+            "WARN" | "WARNING" => LogLevel::Warn,
+            "INFO" => LogLevel::Info,
+            "DEBUG" | "TRACE" => LogLevel::Debug,
+            _ => continue,
+        };
+        return level;
+    }
+    match priority {
+        Some(0..=3) => LogLevel::Error,
+        Some(4) => LogLevel::Warn,
+        Some(7) => LogLevel::Debug,
+        _ => LogLevel::Info,
+    }
+}
+
+/// Apply the level, time and regex filters, then keep the last
+/// `query.lines` lines unless the query continues from a cursor.
+fn filter_lines(lines: &mut Vec<LogLine>, query: &LogQuery, grep: Option<&regex::Regex>) {
+    lines.retain(|line| {
+        query.level.is_none_or(|level| line.level >= level)
+            && query
+                .since_ms
+                .is_none_or(|since| line.timestamp_ms == 0 || line.timestamp_ms >= since)
+            && grep.is_none_or(|re| re.is_match(&line.text))
+    });
+    if query.after.is_none() && query.lines > 0 && lines.len() > query.lines {
+        lines.drain(..lines.len() - query.lines);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn journal_entries_are_parsed_with_cursor() {
+        let output = concat!(
+            r#"{"__CURSOR":"s=1;i=10","__REALTIME_TIMESTAMP":"1700000000123456","PRIORITY":"6","MESSAGE":"camera started"}"#,
+            "\n",
+            r#"{"__CURSOR":"s=1;i=11","__REALTIME_TIMESTAMP":"1700000001000000","PRIORITY":"3","MESSAGE":[104,105]}"#,
+            "\n",
+        );
+        let chunk = parse_journal(output, None);
+        assert_eq!(chunk.cursor, "s=1;i=11");
+        assert_eq!(chunk.lines.len(), 2);
+        assert_eq!(chunk.lines[0].timestamp_ms, 1_700_000_000_123);
+        assert_eq!(chunk.lines[0].level, LogLevel::Info);
+        assert_eq!(chunk.lines[1].text, "hi");
+        assert_eq!(chunk.lines[1].level, LogLevel::Error);
+
+        assert_eq!(parse_journal("", Some("s=1;i=11")).cursor, "s=1;i=11");
+    }
+
+    #[test]
+    fn levels_come_from_the_text_before_the_priority() {
+        assert_eq!(
+            line_level(Some(6), "[2026-01-01T00:00:00Z WARN  camera] frame dropped"),
+            LogLevel::Warn
+        );
+        assert_eq!(
+            line_level(Some(6), "2026-01-01 00:00:00 - telemetry - ERROR - boom"),
+            LogLevel::Error
+        );
+        assert_eq!(
+            line_level(Some(6), "ts=1 level=debug msg=x"),
+            LogLevel::Debug
+        );
+        assert_eq!(line_level(Some(4), "no level word here"), LogLevel::Warn);
+        assert_eq!(
+            line_level(None, "an error happened later on"),
+            LogLevel::Info
+        );
+    }
+
+    #[test]
+    fn native_reads_keep_partial_lines_for_later() {
+        assert_eq!(complete_lines("a\nb\npart", false), (vec!["a", "b"], 4));
+        assert_eq!(complete_lines("tail of a\nb\n", true), (vec!["b"], 12));
+        assert_eq!(complete_lines("partial", false), (vec![], 0));
+        assert_eq!(parse_native_cursor("native:12:0"), Some([12, 0]));
+        assert_eq!(parse_native_cursor("s=1;i=11"), None);
+    }
+
+    #[test]
+    fn filters_then_keeps_the_last_lines() {
+        let line = |ts, level, text: &str| LogLine {
+            timestamp_ms: ts,
+            level,
+            text: text.to_string(),
+        };
+        let all = vec![
+            line(1000, LogLevel::Info, "frame 1"),
+            line(2000, LogLevel::Warn, "frame 2 dropped"),
+            line(3000, LogLevel::Error, "frame 3 dropped"),
+            line(4000, LogLevel::Warn, "reconnecting"),
+        ];
+
+        let mut lines = all.clone();
+        let query = LogQuery {
+            lines: 1,
+            level: Some(LogLevel::Warn),
+            ..Default::default()
+        };
+        let grep = regex::Regex::new("dropped").unwrap();
+        filter_lines(&mut lines, &query, Some(&grep));
+        assert_eq!(lines, [all[2].clone()]);
+
+        let mut lines = all.clone();
+        let query = LogQuery {
+            lines: 1,
+            since_ms: Some(2500),
+            after: Some("s=1".into()),
+            ..Default::default()
+        };
+        filter_lines(&mut lines, &query, None);
+        assert_eq!(lines, all[2..]);
+    }
+}
@@ -1,11 +1,13 @@
 //! Node manager with state caching
 //!
 //! Maintains authoritative state for all nodes and handles commands.
-//! Split into submodules: build, health, lifecycle.
+//! Split into submodules: build, health, history, lifecycle, logs.
 
 pub mod build;
 pub mod health;
+pub mod history;
 pub mod lifecycle;
+pub mod logs;
 
 use crate::daemon::registry::{self, NodeManifest};
 use crate::daemon::supervisor::Supervisor;
@@ -45,6 +47,9 @@ pub enum NodeManagerError {
     #[error("Build timed out for: {0}")]
     BuildTimeout(String),
 
+    #[error("Invalid log query: {0}")]
+    InvalidLogQuery(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -121,6 +126,18 @@ impl CachedNode {
             .unwrap_or_else(|| "unknown".to_string())
     }
 
+    /// Status as reported to clients.
+    ///
+    /// If health heartbeat says Healthy but systemd says Stopped,
+    /// the node is running outside systemd (e.g. local dev). Show Running.
+    pub fn effective_status(&self) -> NodeStatus {
+        if self.health_status == HealthStatus::Healthy && self.status == NodeStatus::Stopped {
+            NodeStatus::Running
+        } else {
+            self.status
+        }
+    }
+
     /// Convert to protobuf NodeState (requires machine info from caller)
     pub fn to_proto(
         &self,
@@ -130,18 +147,10 @@ impl CachedNode {
     ) -> NodeState {
         let manifest = self.manifest.as_ref();
         let base_name = manifest.map(|m| m.name.clone()).unwrap_or_default();
-        // If health heartbeat says Healthy but systemd says Stopped,
-        // the node is running outside systemd (e.g. local dev). Show Running.
-        let effective_status =
-            if self.health_status == HealthStatus::Healthy && self.status == NodeStatus::Stopped {
-                NodeStatus::Running
-            } else {
-                self.status
-            };
         NodeState {
             name: self.effective_name(),
             path: self.path.clone(),
-            status: effective_status as i32,
+            status: self.effective_status() as i32,
             installed: self.installed,
             autostart_enabled: self.autostart_enabled,
             version: manifest
@@ -185,6 +194,8 @@ pub struct NodeManager {
     pub(crate) machine_hostname: String,
     /// Machine IP addresses
     pub(crate) machine_ips: Vec<String>,
+    /// Status and health transitions per node
+    pub(crate) history: std::sync::Mutex<history::HealthHistory>,
 }
 
 impl NodeManager {
@@ -217,6 +228,7 @@ impl NodeManager {
             machine_id,
             machine_hostname,
             machine_ips,
+            history: std::sync::Mutex::new(history::HealthHistory::new(Self::now_ms())),
         });
 
         // Initial load
@@ -361,6 +373,7 @@ impl NodeManager {
                 node.installed = installed;
                 node.autostart_enabled = autostart_enabled;
                 node.last_updated_ms = Self::now_ms();
+                self.record_history(node);
                 break;
             }
         }
@@ -451,11 +464,16 @@ impl NodeManager {
                 config_override: entry.config_override.clone(),
             };
 
+            self.record_history(&cached);
             nodes.insert(key, cached);
         }
 
         // Remove nodes that are no longer registered
         nodes.retain(|key, _| seen.contains(key));
+        self.history
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .retain(|name| seen.contains(name));
 
         Ok(())
     }
@@ -537,6 +555,7 @@ impl NodeManager {
             CommandType::Uninstall => self.uninstall_node(&cmd.node_name).await,
             CommandType::Build => self.build_node(self.clone(), &cmd.node_name).await,
             CommandType::Clean => self.clean_node(self.clone(), &cmd.node_name).await,
+            CommandType::Update => self.update_node(self.clone(), &cmd.node_name).await,
             CommandType::EnableAutostart => self.enable_autostart(&cmd.node_name).await,
             CommandType::DisableAutostart => self.disable_autostart(&cmd.node_name).await,
             CommandType::AddNode => {
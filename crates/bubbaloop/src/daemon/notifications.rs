@@ -0,0 +1,592 @@
+//! `~/.bubbaloop/notifications.yaml` — channels that `notify` rule actions
+//! send messages to.
+//!
+//! ```yaml
+//! channels:
+//!   phone:
+//!     type: ntfy
+//!     url: https://ntfy.sh/my-rack-alerts
+//!     token: env:NTFY_TOKEN          # optional
+//!     priority: high                 # optional: min, low, default, high, max
+//!   ops:
+//!     type: slack
+//!     webhook_url: env:SLACK_WEBHOOK_URL
+//!     max_per_hour: 5
+//!   me:
+//!     type: email
+//!     to: [me@example.com]
+//!     from: bubbaloop@rack.local     # optional
+//! ```
+//!
+//! Every channel takes `max_per_hour` (default 20); messages over the limit
+//! are dropped and logged, so a flapping rule cannot flood a phone. Tokens
+//! and webhook URLs accept `env:NAME`. Email is handed to the local
+//! `sendmail` (`sendmail: /path/to/sendmail` overrides it).
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::rule_actions::{resolve_secret, send_with_retries, NotifyAction};
+
+/// Messages per channel and hour when `max_per_hour` is not set.
+pub const DEFAULT_MAX_PER_HOUR: u32 = 20;
+
+/// Longest message or title template a rule may carry.
+pub const MAX_TEMPLATE_LEN: usize = 1024;
+
+/// Seconds before one ntfy or Slack request gives up, and retries after it.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+const HTTP_RETRIES: u32 = 2;
+
+/// How long `sendmail` may take to accept a message.
+const SENDMAIL_TIMEOUT: Duration = Duration::from_secs(30);
+
+const DEFAULT_SENDMAIL: &str = "/usr/sbin/sendmail";
+
+const NTFY_PRIORITIES: &[&str] = &[
+    "min", "low", "default", "high", "max", "1", "2", "3", "4", "5",
+];
+
+#[derive(Debug, Error)]
+pub enum NotificationsError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{path}: {message}")]
+    Invalid { path: String, message: String },
+}
+
+pub type Result<T> = std::result::Result<T, NotificationsError>;
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NotificationsConfig {
+    #[serde(default)]
+    pub channels: BTreeMap<String, Channel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Channel {
+    /// Push notification through an ntfy server.
+    Ntfy(NtfyChannel),
+    /// Slack incoming webhook.
+    Slack(SlackChannel),
+    /// Email through the local `sendmail`.
+    Email(EmailChannel),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NtfyChannel {
+    /// Topic URL, e.g. `https://ntfy.sh/my-topic`.
+    pub url: String,
+    /// Access token, or `env:NAME`.
+    #[serde(default)]
+    pub token: Option<String>,
+    #[serde(default)]
+    pub priority: Option<String>,
+    #[serde(default = "default_max_per_hour")]
+    pub max_per_hour: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SlackChannel {
+    /// Incoming webhook URL, or `env:NAME`.
+    pub webhook_url: String,
+    #[serde(default = "default_max_per_hour")]
+    pub max_per_hour: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EmailChannel {
+    pub to: Vec<String>,
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub sendmail: Option<PathBuf>,
+    #[serde(default = "default_max_per_hour")]
+    pub max_per_hour: u32,
+}
+
+fn default_max_per_hour() -> u32 {
+    DEFAULT_MAX_PER_HOUR
+}
+
+impl Channel {
+    pub fn max_per_hour(&self) -> u32 {
+        match self {
+            Self::Ntfy(c) => c.max_per_hour,
+            Self::Slack(c) => c.max_per_hour,
+            Self::Email(c) => c.max_per_hour,
+        }
+    }
+
+    fn validate(&self) -> std::result::Result<(), String> {
+        if self.max_per_hour() == 0 {
+            return Err("max_per_hour must be at least 1".to_string());
+        }
+        match self {
+            Self::Ntfy(c) => {
+                validate_url(&c.url)?;
+                if let Some(p) = &c.priority {
+                    if !NTFY_PRIORITIES.contains(&p.as_str()) {
+                        return Err(format!(
+                            "unknown priority '{}' (use min, low, default, high, max)",
+                            p
+                        ));
+                    }
+                }
+            }
+            Self::Slack(c) => {
+                if !c.webhook_url.starts_with("env:") {
+                    validate_url(&c.webhook_url)?;
+                }
+            }
+            Self::Email(c) => {
+                if c.to.is_empty() {
+                    return Err("to must list at least one address".to_string());
+                }
+                for address in c.to.iter().chain(&c.from) {
+                    if !address.contains('@') || address.contains(['\r', '\n', ',']) {
+                        return Err(format!("'{}' is not an email address", address));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn validate_url(url: &str) -> std::result::Result<(), String> {
+    match reqwest::Url::parse(url) {
+        Ok(u) if matches!(u.scheme(), "http" | "https") => Ok(()),
+        _ => Err(format!("'{}' is not an http:// or https:// URL", url)),
+    }
+}
+
+/// Channel names are referenced from rules: letters, digits, `-` and `_`.
+pub fn validate_channel_name(name: &str) -> std::result::Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "channel name '{}' must be 1-64 letters, digits, '-' or '_'",
+            name
+        ))
+    }
+}
+
+/// `~/.bubbaloop/notifications.yaml`
+pub fn config_path() -> PathBuf {
+    crate::daemon::registry::get_bubbaloop_home().join("notifications.yaml")
+}
+
+/// Load the channels, or none when the file does not exist.
+pub fn load() -> Result<NotificationsConfig> {
+    load_from(&config_path())
+}
+
+fn load_from(path: &Path) -> Result<NotificationsConfig> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => parse(&text).map_err(|message| NotificationsError::Invalid {
+            path: path.display().to_string(),
+            message,
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(NotificationsConfig::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn parse(text: &str) -> std::result::Result<NotificationsConfig, String> {
+    if text.trim().is_empty() {
+        return Ok(NotificationsConfig::default());
+    }
+    let config: NotificationsConfig = serde_yaml::from_str(text).map_err(|e| e.to_string())?;
+    for (name, channel) in &config.channels {
+        validate_channel_name(name)?;
+        channel
+            .validate()
+            .map_err(|e| format!("channels.{}: {}", name, e))?;
+    }
+    Ok(config)
+}
+
+/// Messages sent per channel within the last hour.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    sent: HashMap<String, VecDeque<Instant>>,
+}
+
+impl RateLimiter {
+    /// Whether another message may go to `channel`; counts it if so.
+    pub fn allow(&mut self, channel: &str, max_per_hour: u32, now: Instant) -> bool {
+        const HOUR: Duration = Duration::from_secs(3600);
+        let sent = self.sent.entry(channel.to_string()).or_default();
+        while sent
+            .front()
+            .is_some_and(|t| now.saturating_duration_since(*t) >= HOUR)
+        {
+            sent.pop_front();
+        }
+        if sent.len() >= max_per_hour as usize {
+            return false;
+        }
+        sent.push_back(now);
+        true
+    }
+}
+
+/// A rendered notification.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub title: String,
+    pub body: String,
+}
+
+impl Message {
+    /// Fill the action's templates from a rule action payload (see
+    /// [`super::rule_actions::payload`]).
+    pub fn render(notify: &NotifyAction, payload: &serde_json::Value) -> Self {
+        let body = match &notify.message {
+            Some(template) => render(template, payload),
+            None if payload["description"]
+                .as_str()
+                .is_some_and(|d| !d.is_empty()) =>
+            {
+                render("{description}", payload)
+            }
+            None if payload["predicate"].as_str().is_some_and(|p| !p.is_empty()) => {
+                render("Rule {rule} fired: {predicate}", payload)
+            }
+            None => render("Rule {rule} fired on schedule {schedule}", payload),
+        };
+        let title = render(
+            notify.title.as_deref().unwrap_or("bubbaloop: {mission_id}"),
+            payload,
+        );
+        Self { title, body }
+    }
+}
+
+/// Replace `{name}` with the payload field of that name and
+/// `{fields.<key>}` with a world-state value. Unknown placeholders are
+/// left as they are; missing values and fields become empty.
+pub fn render(template: &str, payload: &serde_json::Value) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let Some(close) = after.find('}') else {
+            rest = &rest[open..];
+            break;
+        };
+        let name = &after[..close];
+        let value = match name.strip_prefix("fields.") {
+            Some(field) => Some(
+                payload["fields"]
+                    .get(field)
+                    .unwrap_or(&serde_json::Value::Null),
+            ),
+            None => payload.get(name).filter(|_| name != "fields"),
+        };
+        match value {
+            Some(serde_json::Value::String(s)) => out.push_str(s),
+            Some(serde_json::Value::Null) => {}
+            Some(other) => out.push_str(&other.to_string()),
+            None => out.push_str(&rest[open..open + close + 2]),
+        }
+        rest = &after[close + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Deliver a message to a channel.
+pub async fn send(
+    client: &reqwest::Client,
+    channel: &Channel,
+    message: &Message,
+    backoff: Duration,
+) -> std::result::Result<(), String> {
+    match channel {
+        Channel::Ntfy(c) => {
+            let token = c.token.as_deref().map(resolve_secret).transpose()?;
+            // ntfy reads the title from a header, which must be one line.
+            let title = one_line(&message.title);
+            let build = || {
+                let mut request = client
+                    .post(&c.url)
+                    .timeout(HTTP_TIMEOUT)
+                    .header("Title", title.as_str())
+                    .body(message.body.clone());
+                if let Some(priority) = &c.priority {
+                    request = request.header("Priority", priority);
+                }
+                if let Some(token) = &token {
+                    request = request.bearer_auth(token);
+                }
+                request
+            };
+            send_with_retries(build, HTTP_RETRIES, backoff).await?;
+        }
+        Channel::Slack(c) => {
+            let url = resolve_secret(&c.webhook_url)?;
+            let body = serde_json::json!({
+                "text": format!("*{}*\n{}", message.title, message.body),
+            });
+            let build = || client.post(&url).timeout(HTTP_TIMEOUT).json(&body);
+            send_with_retries(build, HTTP_RETRIES, backoff).await?;
+        }
+        Channel::Email(c) => sendmail(c, message).await?,
+    }
+    Ok(())
+}
+
+fn one_line(s: &str) -> String {
+    s.replace(['\r', '\n'], " ")
+}
+
+async fn sendmail(channel: &EmailChannel, message: &Message) -> std::result::Result<(), String> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut mail = format!("To: {}\n", channel.to.join(", "));
+    if let Some(from) = &channel.from {
+        mail.push_str(&format!("From: {}\n", from));
+    }
+    mail.push_str(&format!(
+        "Subject: {}\nContent-Type: text/plain; charset=utf-8\n\n{}\n",
+        one_line(&message.title),
+        message.body
+    ));
+
+    let program = channel
+        .sendmail
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_SENDMAIL));
+    let mut child = tokio::process::Command::new(&program)
+        .args(["-t", "-i"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("cannot run {}: {}", program.display(), e))?;
+    let mut stdin = child.stdin.take().ok_or("sendmail has no stdin")?;
+    let run = async {
+        stdin.write_all(mail.as_bytes()).await?;
+        drop(stdin);
+        child.wait_with_output().await
+    };
+    let output = tokio::time::timeout(SENDMAIL_TIMEOUT, run)
+        .await
+        .map_err(|_| format!("{} timed out", program.display()))?
+        .map_err(|e| format!("{}: {}", program.display(), e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} failed: {}",
+            program.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload() -> serde_json::Value {
+        serde_json::json!({
+            "rule": "alert-1",
+            "mission_id": "rack",
+            "description": "",
+            "predicate": "cpu_temp > 80",
+            "schedule": null,
+            "fields": {"cpu_temp": "91"},
+            "fired_at": "2026-10-17T12:00:00Z",
+        })
+    }
+
+    #[test]
+    fn parses_channels_with_defaults() {
+        let config = parse(
+            "channels:\n\
+             \x20 phone: {type: ntfy, url: 'https://ntfy.sh/rack', priority: high}\n\
+             \x20 ops: {type: slack, webhook_url: 'env:SLACK_URL', max_per_hour: 5}\n\
+             \x20 me: {type: email, to: [me@example.com]}\n",
+        )
+        .unwrap();
+        assert_eq!(config.channels.len(), 3);
+        assert_eq!(
+            config.channels["phone"].max_per_hour(),
+            DEFAULT_MAX_PER_HOUR
+        );
+        assert_eq!(config.channels["ops"].max_per_hour(), 5);
+        assert!(matches!(config.channels["me"], Channel::Email(_)));
+        assert_eq!(parse("\n").unwrap(), NotificationsConfig::default());
+
+        let dir = tempfile::tempdir().unwrap();
+        assert!(load_from(&dir.path().join("missing.yaml"))
+            .unwrap()
+            .channels
+            .is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_channels() {
+        let err = |yaml: &str| parse(yaml).unwrap_err();
+        assert!(err("channels:\n  p: {type: pager}\n").contains("unknown variant"));
+        assert!(err("channels:\n  p: {type: ntfy, url: 'ftp://x'}\n").contains("channels.p"));
+        assert!(
+            err("channels:\n  p: {type: ntfy, url: 'https://x', priority: loud}\n")
+                .contains("priority")
+        );
+        assert!(err("channels:\n  p: {type: email, to: []}\n").contains("address"));
+        assert!(err("channels:\n  p: {type: email, to: [nobody]}\n").contains("nobody"));
+        assert!(
+            err("channels:\n  p: {type: slack, webhook_url: 'env:X', max_per_hour: 0}\n")
+                .contains("max_per_hour")
+        );
+        assert!(
+            err("channels:\n  'my phone': {type: ntfy, url: 'https://x'}\n")
+                .contains("channel name")
+        );
+        assert!(
+            err("channels:\n  p: {type: ntfy, url: 'https://x', colour: red}\n")
+                .contains("unknown field")
+        );
+    }
+
+    #[test]
+    fn renders_templates() {
+        let p = payload();
+        assert_eq!(
+            render("{rule} on {mission_id}: cpu at {fields.cpu_temp}C", &p),
+            "alert-1 on rack: cpu at 91C"
+        );
+        assert_eq!(
+            render("{schedule}{fields.fan}|{nope}|{fields}|{", &p),
+            "|{nope}|{fields}|{"
+        );
+
+        let message = Message::render(&NotifyAction::new("phone"), &p);
+        assert_eq!(message.title, "bubbaloop: rack");
+        assert_eq!(message.body, "Rule alert-1 fired: cpu_temp > 80");
+        let mut notify = NotifyAction::new("phone");
+        notify.message = Some("Hot: {fields.cpu_temp}".into());
+        assert_eq!(Message::render(&notify, &p).body, "Hot: 91");
+    }
+
+    #[test]
+    fn rate_limiter_allows_max_per_hour() {
+        let mut limiter = RateLimiter::default();
+        let start = Instant::now();
+        assert!(limiter.allow("phone", 2, start));
+        assert!(limiter.allow("phone", 2, start + Duration::from_secs(60)));
+        assert!(!limiter.allow("phone", 2, start + Duration::from_secs(120)));
+        assert!(limiter.allow("ops", 2, start + Duration::from_secs(120)));
+        // The first message leaves the window after an hour.
+        assert!(limiter.allow("phone", 2, start + Duration::from_secs(3600)));
+        assert!(!limiter.allow("phone", 2, start + Duration::from_secs(3601)));
+    }
+
+    #[tokio::test]
+    async fn ntfy_and_slack_post_the_message() {
+        use axum::http::HeaderMap;
+        use std::sync::{Arc, Mutex};
+
+        let seen: Arc<Mutex<Vec<(String, String)>>> = Arc::default();
+        let s = seen.clone();
+        let app = axum::Router::new().route(
+            "/{path}",
+            axum::routing::post(move |headers: HeaderMap, body: String| {
+                let title = headers
+                    .get("title")
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string();
+                s.lock().unwrap().push((title, body));
+                async { "ok" }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = super::super::rule_actions::http_client();
+        let message = Message {
+            title: "bubbaloop: rack\nsecond line".into(),
+            body: "CPU is hot".into(),
+        };
+        let ntfy = Channel::Ntfy(NtfyChannel {
+            url: format!("http://{}/rack", addr),
+            token: None,
+            priority: Some("high".into()),
+            max_per_hour: 1,
+        });
+        send(&client, &ntfy, &message, Duration::from_millis(1))
+            .await
+            .unwrap();
+        let slack = Channel::Slack(SlackChannel {
+            webhook_url: format!("http://{}/slack", addr),
+            max_per_hour: 1,
+        });
+        send(&client, &slack, &message, Duration::from_millis(1))
+            .await
+            .unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(
+            seen[0],
+            ("bubbaloop: rack second line".into(), "CPU is hot".into())
+        );
+        let slack_body: serde_json::Value = serde_json::from_str(&seen[1].1).unwrap();
+        assert_eq!(
+            slack_body["text"],
+            "*bubbaloop: rack\nsecond line*\nCPU is hot"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn email_goes_through_sendmail() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("mail.txt");
+        let script = dir.path().join("sendmail");
+        std::fs::write(&script, format!("#!/bin/sh\ncat > '{}'\n", out.display())).unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let channel = Channel::Email(EmailChannel {
+            to: vec!["me@example.com".into()],
+            from: Some("rack@example.com".into()),
+            sendmail: Some(script),
+            max_per_hour: 1,
+        });
+        let message = Message {
+            title: "bubbaloop: rack".into(),
+            body: "CPU is hot".into(),
+        };
+        let client = super::super::rule_actions::http_client();
+        send(&client, &channel, &message, Duration::from_millis(1))
+            .await
+            .unwrap();
+        let mail = std::fs::read_to_string(out).unwrap();
+        assert!(mail.starts_with("To: me@example.com\nFrom: rack@example.com\n"));
+        assert!(mail.contains("Subject: bubbaloop: rack\n"));
+        assert!(mail.ends_with("\n\nCPU is hot\n"));
+    }
+}
@@ -0,0 +1,386 @@
+//! Human overrides of reactive rule automation.
+//!
+//! An operator can pause every rule of every agent on the machine, or one
+//! rule of one agent, until they resume it or, optionally, until a set
+//! time. A paused rule still fires and its firings are still recorded,
+//! but its actions and escalation steps do not run and it neither boosts
+//! arousal nor wakes the LLM.
+//!
+//! Overrides live in `overrides.db` next to the agent directories, so one
+//! pause covers all agents of the machine. Every change — set, cleared,
+//! expired — is appended to the `override_audit` table with who made it
+//! and why. Only the rules queryable (`agent rules pause|resume`) changes
+//! overrides: they are not MCP tools, so an agent cannot lift a pause a
+//! human put on it.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{params, Connection, OptionalExtension, Transaction, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+
+/// Audit entries returned when a query does not set a limit.
+pub const DEFAULT_AUDIT_LIMIT: u32 = 100;
+
+/// Most audit entries one query returns.
+pub const MAX_AUDIT_LIMIT: u32 = 10_000;
+
+/// Longest override reason, in bytes.
+pub const MAX_REASON_LEN: usize = 500;
+
+/// A pause of rule automation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Override {
+    /// The agent whose rule is paused; `None` with `rule` for the global
+    /// pause.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    /// The paused rule; `None` pauses every rule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    pub set_by: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub reason: String,
+    /// Epoch seconds.
+    pub set_at: i64,
+    /// When the override lapses by itself (epoch seconds).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+impl Override {
+    /// Whether this override pauses rule `rule` of agent `agent`.
+    pub fn pauses(&self, agent: &str, rule: &str) -> bool {
+        match (&self.agent, &self.rule) {
+            (None, None) => true,
+            (Some(a), None) => a == agent,
+            (Some(a), Some(r)) => a == agent && r == rule,
+            (None, Some(r)) => r == rule,
+        }
+    }
+
+    /// "alice (maintenance)" — who paused, and why when they said.
+    pub fn by(&self) -> String {
+        if self.reason.is_empty() {
+            self.set_by.clone()
+        } else {
+            format!("{} ({})", self.set_by, self.reason)
+        }
+    }
+}
+
+/// The override among `overrides` that pauses rule `rule` of agent
+/// `agent`, the global pause first.
+pub fn paused_by<'a>(overrides: &'a [Override], agent: &str, rule: &str) -> Option<&'a Override> {
+    overrides
+        .iter()
+        .filter(|o| o.pauses(agent, rule))
+        .min_by_key(|o| (o.agent.is_some(), o.rule.is_some()))
+}
+
+/// What happened to an override.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverrideChange {
+    Set,
+    Clear,
+    Expire,
+}
+
+impl OverrideChange {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Set => "set",
+            Self::Clear => "clear",
+            Self::Expire => "expire",
+        }
+    }
+
+    fn parse(s: &str) -> Self {
+        match s {
+            "set" => Self::Set,
+            "clear" => Self::Clear,
+            _ => Self::Expire,
+        }
+    }
+}
+
+/// One entry of the override audit trail.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverrideAudit {
+    pub id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    pub change: OverrideChange,
+    /// Who set or cleared the override; for an expiry, who had set it.
+    pub actor: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub reason: String,
+    /// Epoch seconds.
+    pub at: i64,
+    /// For `set`, when the override lapses by itself (epoch seconds).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+/// Where the overrides of the agents in `agent_dir`'s parent live.
+pub fn path(agent_dir: &Path) -> PathBuf {
+    agent_dir.parent().unwrap_or(agent_dir).join("overrides.db")
+}
+
+// The global pause is stored with empty agent and rule, so that the
+// primary key keeps one row per target.
+fn column(value: Option<&str>) -> &str {
+    value.unwrap_or("")
+}
+
+fn from_column(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
+
+/// SQLite-backed overrides and their audit trail.
+pub struct OverrideStore {
+    conn: Connection,
+}
+
+impl OverrideStore {
+    /// Open (or create) the overrides at `path`.
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let conn = crate::daemon::util::open_sqlite(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS overrides (
+                agent      TEXT NOT NULL DEFAULT '',
+                rule       TEXT NOT NULL DEFAULT '',
+                set_by     TEXT NOT NULL,
+                reason     TEXT NOT NULL DEFAULT '',
+                set_at     INTEGER NOT NULL,
+                expires_at INTEGER,
+                PRIMARY KEY (agent, rule)
+            );
+            CREATE TABLE IF NOT EXISTS override_audit (
+                id         INTEGER PRIMARY KEY AUTOINCREMENT,
+                agent      TEXT NOT NULL DEFAULT '',
+                rule       TEXT NOT NULL DEFAULT '',
+                change     TEXT NOT NULL,
+                actor      TEXT NOT NULL,
+                reason     TEXT NOT NULL DEFAULT '',
+                at         INTEGER NOT NULL,
+                expires_at INTEGER
+            );",
+        )?;
+        Ok(Self { conn })
+    }
+
+    /// Set `ov`, replacing an override of the same target, and audit it.
+    pub fn set(&self, ov: &Override) -> anyhow::Result<()> {
+        if ov.set_by.trim().is_empty() {
+            anyhow::bail!("an override needs to say who set it");
+        }
+        if ov.reason.len() > MAX_REASON_LEN {
+            anyhow::bail!("reason is longer than {} bytes", MAX_REASON_LEN);
+        }
+        if ov.expires_at.is_some_and(|at| at <= ov.set_at) {
+            anyhow::bail!("override would expire before it is set");
+        }
+        let tx = self.conn.unchecked_transaction()?;
+        let (agent, rule) = (column(ov.agent.as_deref()), column(ov.rule.as_deref()));
+        tx.execute(
+            "INSERT OR REPLACE INTO overrides (agent, rule, set_by, reason, set_at, expires_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![agent, rule, ov.set_by, ov.reason, ov.set_at, ov.expires_at],
+        )?;
+        tx.execute(
+            "INSERT INTO override_audit (agent, rule, change, actor, reason, at, expires_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                agent,
+                rule,
+                OverrideChange::Set.as_str(),
+                ov.set_by,
+                ov.reason,
+                ov.set_at,
+                ov.expires_at,
+            ],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Clear the override of a target and audit it; false when there was
+    /// none.
+    pub fn clear(
+        &self,
+        agent: Option<&str>,
+        rule: Option<&str>,
+        actor: &str,
+        now: i64,
+    ) -> anyhow::Result<bool> {
+        let tx = self.conn.unchecked_transaction()?;
+        let (agent, rule) = (column(agent), column(rule));
+        let removed = tx.execute(
+            "DELETE FROM overrides WHERE agent = ?1 AND rule = ?2",
+            params![agent, rule],
+        )? > 0;
+        if removed {
+            tx.execute(
+                "INSERT INTO override_audit (agent, rule, change, actor, at) \
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![agent, rule, OverrideChange::Clear.as_str(), actor, now],
+            )?;
+        }
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// Overrides in force at `now`, global first. Those that lapsed are
+    /// removed and audited as expired.
+    pub fn active(&self, now: i64) -> anyhow::Result<Vec<Override>> {
+        self.expire(now)?;
+        let mut stmt = self.conn.prepare(
+            "SELECT agent, rule, set_by, reason, set_at, expires_at FROM overrides \
+             ORDER BY agent != '', rule != '', agent, rule",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(Override {
+                agent: from_column(row.get(0)?),
+                rule: from_column(row.get(1)?),
+                set_by: row.get(2)?,
+                reason: row.get(3)?,
+                set_at: row.get(4)?,
+                expires_at: row.get(5)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    // Every agent reads overrides on its own; taking the write lock up
+    // front makes sure only one of them audits a given expiry.
+    fn expire(&self, now: i64) -> anyhow::Result<()> {
+        let lapsed: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT 1 FROM overrides WHERE expires_at <= ?1 LIMIT 1",
+                params![now],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if lapsed.is_none() {
+            return Ok(());
+        }
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        tx.execute(
+            "INSERT INTO override_audit (agent, rule, change, actor, reason, at) \
+             SELECT agent, rule, ?1, set_by, reason, expires_at FROM overrides \
+             WHERE expires_at <= ?2",
+            params![OverrideChange::Expire.as_str(), now],
+        )?;
+        tx.execute("DELETE FROM overrides WHERE expires_at <= ?1", params![now])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The latest `limit` audit entries (default 100), newest first.
+    pub fn audit(&self, limit: Option<u32>) -> anyhow::Result<Vec<OverrideAudit>> {
+        let limit = limit.unwrap_or(DEFAULT_AUDIT_LIMIT).min(MAX_AUDIT_LIMIT);
+        let mut stmt = self.conn.prepare(
+            "SELECT id, agent, rule, change, actor, reason, at, expires_at \
+             FROM override_audit ORDER BY id DESC LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit], |row| {
+            let change: String = row.get(3)?;
+            Ok(OverrideAudit {
+                id: row.get(0)?,
+                agent: from_column(row.get(1)?),
+                rule: from_column(row.get(2)?),
+                change: OverrideChange::parse(&change),
+                actor: row.get(4)?,
+                reason: row.get(5)?,
+                at: row.get(6)?,
+                expires_at: row.get(7)?,
+            })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pause(agent: Option<&str>, rule: Option<&str>, expires_at: Option<i64>) -> Override {
+        Override {
+            agent: agent.map(str::to_string),
+            rule: rule.map(str::to_string),
+            set_by: "alice".to_string(),
+            reason: "maintenance".to_string(),
+            set_at: 100,
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn set_clear_and_expire_are_audited() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = OverrideStore::open(&dir.path().join("overrides.db")).unwrap();
+        store
+            .set(&pause(Some("rack"), Some("alert-hot"), None))
+            .unwrap();
+        store.set(&pause(None, None, Some(200))).unwrap();
+
+        let active = store.active(150).unwrap();
+        assert_eq!(active.len(), 2);
+        assert_eq!(active[0].agent, None, "global pause comes first");
+        assert_eq!(
+            paused_by(&active, "rack", "alert-cold").map(|o| o.rule.clone()),
+            Some(None)
+        );
+
+        // The global pause lapses; the rule override stays.
+        let active = store.active(200).unwrap();
+        assert_eq!(active.len(), 1);
+        assert!(paused_by(&active, "rack", "alert-hot").is_some());
+        assert!(paused_by(&active, "rack", "alert-cold").is_none());
+        assert!(paused_by(&active, "garden", "alert-hot").is_none());
+        // Reading again does not audit the expiry twice.
+        store.active(300).unwrap();
+
+        assert!(store
+            .clear(Some("rack"), Some("alert-hot"), "bob", 400)
+            .unwrap());
+        assert!(!store.clear(None, None, "bob", 400).unwrap());
+        assert!(store.active(400).unwrap().is_empty());
+
+        let audit = store.audit(None).unwrap();
+        let changes: Vec<(OverrideChange, &str, i64)> = audit
+            .iter()
+            .map(|a| (a.change, a.actor.as_str(), a.at))
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (OverrideChange::Clear, "bob", 400),
+                (OverrideChange::Expire, "alice", 200),
+                (OverrideChange::Set, "alice", 100),
+                (OverrideChange::Set, "alice", 100),
+            ]
+        );
+        assert_eq!(audit[0].rule.as_deref(), Some("alert-hot"));
+        assert_eq!(audit[1].agent, None);
+        assert_eq!(store.audit(Some(1)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn set_rejects_bad_overrides() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = OverrideStore::open(&dir.path().join("overrides.db")).unwrap();
+        let mut anonymous = pause(None, None, None);
+        anonymous.set_by = " ".to_string();
+        assert!(store.set(&anonymous).is_err());
+        assert!(store.set(&pause(None, None, Some(100))).is_err());
+        let mut chatty = pause(None, None, None);
+        chatty.reason = "x".repeat(MAX_REASON_LEN + 1);
+        assert!(store.set(&chatty).is_err());
+        assert!(store.audit(None).unwrap().is_empty());
+    }
+}
@@ -4,10 +4,12 @@
 //! The evaluator reuses `apply_filter` from `context_provider` for predicate parsing.
 
 use crate::daemon::context_provider::apply_filter;
+use crate::daemon::rule_actions::{EscalationStep, RuleAction};
 use rusqlite::{params, Connection};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::Path;
-use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU32, Ordering};
+use std::sync::Mutex;
 use std::time::Duration;
 use tokio::time::Instant;
 
@@ -26,25 +28,151 @@ pub const REACTIVE_BREAKER_COOL_OFF: Duration = Duration::from_secs(300);
 pub struct ReactiveRule {
     pub id: String,
     pub mission_id: String,
-    /// Predicate expression using apply_filter syntax (e.g. "dog.near_stairs = 'true'").
+    /// Predicate expression using apply_filter syntax (e.g. "dog.near_stairs = 'true'"),
+    /// with AND, OR, NOT and parentheses.
     pub predicate: String,
+    /// Minimum seconds between firings (a cooldown).
     pub debounce_secs: u32,
+    /// Seconds the predicate must hold continuously before the rule fires.
+    pub hold_secs: u32,
+    /// Cron expression (UTC) that triggers the rule. The predicate, if
+    /// any, is then checked only when the schedule comes due.
+    pub schedule: Option<String>,
+    /// Predicate that re-arms the rule after it fired (hysteresis). While
+    /// set, the rule fires once when `predicate` starts to hold and not
+    /// again until this one has held, e.g. above 85°C, then below 75°C.
+    pub clear_predicate: Option<String>,
+    /// Decides between rules that fire together and give one node
+    /// different commands; higher wins.
+    pub priority: i32,
+    /// Log the actions the rule would run instead of running them, and
+    /// leave the agent's arousal alone.
+    pub dry_run: bool,
+    /// At most this many firings within an hour run their actions and
+    /// boost arousal; the others are recorded as over budget.
+    pub max_triggers_per_hour: Option<u32>,
+    /// Actions run while the predicate keeps holding after a firing, each
+    /// step once its `after_secs` have passed.
+    pub escalation: Vec<EscalationStep>,
     pub arousal_boost: f64,
     pub description: String,
+    /// Run by the agent loop each time the rule fires.
+    pub actions: Vec<RuleAction>,
     /// Last time this rule fired (epoch secs). Atomic for concurrent reads.
     pub last_fired_at: AtomicI64,
+    /// Since when the predicate has held without a break (epoch secs),
+    /// 0 while it does not match.
+    pub holding_since: AtomicI64,
+    /// Next time the schedule comes due (epoch secs), 0 until the first
+    /// evaluation.
+    pub next_due: AtomicI64,
+    /// Set when a rule with a clear predicate fires, reset once the clear
+    /// predicate holds.
+    pub latched: AtomicBool,
+    /// When the firings within the last hour that counted against
+    /// `max_triggers_per_hour` happened (epoch secs), oldest first.
+    pub recent_triggers: Mutex<VecDeque<i64>>,
+    /// When the firing that started the current escalation happened
+    /// (epoch secs), 0 while the rule is not escalating.
+    pub escalating_since: AtomicI64,
+    /// Escalation steps already run since `escalating_since`.
+    pub escalation_level: AtomicU32,
 }
 
 impl ReactiveRule {
     /// Check whether this rule should fire given the current world state.
     /// Respects debounce: will not fire if less than `debounce_secs` have passed.
+    /// With `hold_secs`, the predicate must also have matched on every
+    /// evaluation for that long, so a single noisy sample does not fire it.
+    /// A scheduled rule is only considered once per cron occurrence, and a
+    /// rule with a clear predicate is not considered again until it cleared.
     pub fn should_fire(&self, world_state: &HashMap<&str, &str>) -> bool {
         let now = crate::agent::memory::now_epoch_secs() as i64;
+        self.should_fire_at(world_state, now)
+    }
+
+    fn should_fire_at(&self, world_state: &HashMap<&str, &str>, now: i64) -> bool {
+        let armed = self.armed(world_state);
+        if let Some(schedule) = &self.schedule {
+            return self.schedule_due(schedule, now)
+                && armed
+                && (self.predicate.trim().is_empty()
+                    || eval_predicate(&self.predicate, world_state));
+        }
+        if !armed || !eval_predicate(&self.predicate, world_state) {
+            self.holding_since.store(0, Ordering::Relaxed);
+            return false;
+        }
+        let since = match self.holding_since.load(Ordering::Relaxed) {
+            0 => {
+                self.holding_since.store(now, Ordering::Relaxed);
+                now
+            }
+            since => since,
+        };
+        if now - since < self.hold_secs as i64 {
+            return false;
+        }
         let last = self.last_fired_at.load(Ordering::Relaxed);
-        if now - last < self.debounce_secs as i64 {
+        now - last >= self.debounce_secs as i64
+    }
+
+    /// Whether the rule may fire: always without a clear predicate, and
+    /// otherwise only once the clear predicate held since the last firing.
+    /// The evaluation that sees it clear does not fire yet.
+    fn armed(&self, world_state: &HashMap<&str, &str>) -> bool {
+        let Some(clear) = &self.clear_predicate else {
+            return true;
+        };
+        if !self.latched.load(Ordering::Relaxed) {
+            return true;
+        }
+        if eval_predicate(clear, world_state) {
+            self.latched.store(false, Ordering::Relaxed);
+        }
+        false
+    }
+
+    /// Whether the schedule came due since the last evaluation. The first
+    /// evaluation only arms it, so occurrences missed while the agent was
+    /// down are skipped rather than replayed.
+    fn schedule_due(&self, schedule: &str, now: i64) -> bool {
+        let next = |after: i64| {
+            crate::agent::scheduler::next_run_after(schedule, after.max(0) as u64)
+                .map(|t| t as i64)
+                .unwrap_or(i64::MAX)
+        };
+        let due = self.next_due.load(Ordering::Relaxed);
+        if due == 0 {
+            self.next_due.store(next(now), Ordering::Relaxed);
             return false;
         }
-        eval_predicate(&self.predicate, world_state)
+        if now < due {
+            return false;
+        }
+        self.next_due.store(next(now), Ordering::Relaxed);
+        true
+    }
+
+    /// Count a firing at `now` against `max_triggers_per_hour`. Returns
+    /// `false`, without counting it, when the budget of the last hour is
+    /// spent.
+    fn within_budget(&self, now: i64) -> bool {
+        let Some(max) = self.max_triggers_per_hour else {
+            return true;
+        };
+        let mut recent = self
+            .recent_triggers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        while recent.front().is_some_and(|t| now - t >= 3600) {
+            recent.pop_front();
+        }
+        if recent.len() >= max as usize {
+            return false;
+        }
+        recent.push_back(now);
+        true
     }
 
     /// Mark this rule as fired and return its arousal boost.
@@ -53,21 +181,39 @@ impl ReactiveRule {
             crate::agent::memory::now_epoch_secs() as i64,
             Ordering::Relaxed,
         );
+        if self.clear_predicate.is_some() {
+            self.latched.store(true, Ordering::Relaxed);
+        }
         self.arousal_boost
     }
 }
 
 /// Evaluate a predicate against a world state HashMap.
 ///
-/// Converts the HashMap into a JSON object and delegates to `apply_filter`.
+/// Converts the HashMap into a JSON object and delegates to `apply_filter`,
+/// so a field may also be a path into a JSON value, e.g.
+/// `weather.entries[0].temperature_2m` (see
+/// [`crate::daemon::context_provider::lookup_field`]).
 pub fn eval_predicate(predicate: &str, world_state: &HashMap<&str, &str>) -> bool {
-    let json = serde_json::Value::Object(
+    apply_filter(predicate, &world_state_json(world_state))
+}
+
+/// The value a predicate field reads from world state, following a path
+/// into JSON values like [`eval_predicate`] does.
+pub fn field_value(world_state: &HashMap<&str, &str>, field: &str) -> Option<String> {
+    if let Some(value) = world_state.get(field) {
+        return Some(value.to_string());
+    }
+    crate::daemon::context_provider::extract_field(field, &world_state_json(world_state))
+}
+
+fn world_state_json(world_state: &HashMap<&str, &str>) -> serde_json::Value {
+    serde_json::Value::Object(
         world_state
             .iter()
             .map(|(k, v)| (k.to_string(), serde_json::Value::String(v.to_string())))
             .collect(),
-    );
-    apply_filter(predicate, &json)
+    )
 }
 
 /// A rule that just fired during evaluation.
@@ -78,8 +224,22 @@ pub struct FiredRule {
     pub id: String,
     pub mission_id: String,
     pub predicate: String,
+    pub schedule: Option<String>,
     pub description: String,
+    pub priority: i32,
+    /// Actions are logged, not run; `boost` is 0.
+    pub dry_run: bool,
+    /// Over its `max_triggers_per_hour`: actions are skipped and `boost`
+    /// is 0.
+    pub throttled: bool,
+    /// 0 for a firing; otherwise the escalation step (from 1) whose
+    /// actions these are, and `boost` is 0.
+    pub escalation: u32,
+    /// Who paused the rule (see [`crate::daemon::overrides`]): actions are
+    /// skipped and `boost` is 0. Set by the agent loop, not by evaluation.
+    pub paused_by: Option<String>,
     pub boost: f64,
+    pub actions: Vec<RuleAction>,
 }
 
 /// Evaluate all rules against world state, fire matching ones, return the list of fired rules.
@@ -92,17 +252,26 @@ pub fn evaluate_rules_fired(
     rules: &[ReactiveRule],
     world_state: &HashMap<&str, &str>,
 ) -> Vec<FiredRule> {
+    let now = crate::agent::memory::now_epoch_secs() as i64;
     rules
         .iter()
         .filter_map(|r| {
-            if r.should_fire(world_state) {
+            if r.should_fire_at(world_state, now) {
                 let boost = r.fire();
+                let throttled = !r.within_budget(now);
                 Some(FiredRule {
                     id: r.id.clone(),
                     mission_id: r.mission_id.clone(),
                     predicate: r.predicate.clone(),
+                    schedule: r.schedule.clone(),
                     description: r.description.clone(),
-                    boost,
+                    priority: r.priority,
+                    dry_run: r.dry_run,
+                    throttled,
+                    escalation: 0,
+                    paused_by: None,
+                    boost: if r.dry_run || throttled { 0.0 } else { boost },
+                    actions: r.actions.clone(),
                 })
             } else {
                 None
@@ -111,6 +280,92 @@ pub fn evaluate_rules_fired(
         .collect()
 }
 
+/// A change in a rule's escalation, from [`evaluate_escalations`].
+#[derive(Debug, Clone)]
+pub enum EscalationEvent {
+    /// A firing started an escalation.
+    Started { id: String, since: i64 },
+    /// The predicate held for the next step's `after_secs`; `rule` carries
+    /// that step's actions.
+    Escalated { rule: FiredRule, since: i64 },
+    /// The predicate stopped holding, ending the escalation.
+    Resolved { id: String, level: u32 },
+}
+
+/// Advance the escalation of every rule that has one: start it when `fired`
+/// holds a firing of the rule that ran its actions, run the next step once
+/// its time came while the predicate still holds, and end it once the
+/// predicate no longer does. After the last step the escalation stays on
+/// until then, so it does not start over while the condition persists.
+pub fn evaluate_escalations(
+    rules: &[ReactiveRule],
+    fired: &[FiredRule],
+    world_state: &HashMap<&str, &str>,
+) -> Vec<EscalationEvent> {
+    let now = crate::agent::memory::now_epoch_secs() as i64;
+    evaluate_escalations_at(rules, fired, world_state, now)
+}
+
+fn evaluate_escalations_at(
+    rules: &[ReactiveRule],
+    fired: &[FiredRule],
+    world_state: &HashMap<&str, &str>,
+    now: i64,
+) -> Vec<EscalationEvent> {
+    let mut events = Vec::new();
+    for rule in rules.iter().filter(|r| !r.escalation.is_empty()) {
+        let since = rule.escalating_since.load(Ordering::Relaxed);
+        if since == 0 {
+            if fired.iter().any(|f| {
+                f.id == rule.id && f.escalation == 0 && !f.throttled && f.paused_by.is_none()
+            }) {
+                rule.escalating_since.store(now, Ordering::Relaxed);
+                rule.escalation_level.store(0, Ordering::Relaxed);
+                events.push(EscalationEvent::Started {
+                    id: rule.id.clone(),
+                    since: now,
+                });
+            }
+            continue;
+        }
+        let level = rule.escalation_level.load(Ordering::Relaxed);
+        if !eval_predicate(&rule.predicate, world_state) {
+            rule.escalating_since.store(0, Ordering::Relaxed);
+            rule.escalation_level.store(0, Ordering::Relaxed);
+            events.push(EscalationEvent::Resolved {
+                id: rule.id.clone(),
+                level,
+            });
+            continue;
+        }
+        let Some(step) = rule.escalation.get(level as usize) else {
+            continue;
+        };
+        if now - since < step.after_secs as i64 {
+            continue;
+        }
+        rule.escalation_level.store(level + 1, Ordering::Relaxed);
+        events.push(EscalationEvent::Escalated {
+            rule: FiredRule {
+                id: rule.id.clone(),
+                mission_id: rule.mission_id.clone(),
+                predicate: rule.predicate.clone(),
+                schedule: rule.schedule.clone(),
+                description: rule.description.clone(),
+                priority: rule.priority,
+                dry_run: rule.dry_run,
+                throttled: false,
+                escalation: level + 1,
+                paused_by: None,
+                boost: 0.0,
+                actions: step.actions.clone(),
+            },
+            since,
+        });
+    }
+    events
+}
+
 /// Sum of arousal boosts from a set of fired rules.
 pub fn total_boost(fired: &[FiredRule]) -> f64 {
     fired.iter().map(|r| r.boost).sum()
@@ -136,13 +391,47 @@ pub fn total_boost(fired: &[FiredRule]) -> f64 {
 /// Matching is by `id` only, not `(mission_id, id)`, because the SQLite
 /// primary key is `id` — rule ids are already globally unique.
 pub fn merge_rule_state(old: &[ReactiveRule], new: Vec<ReactiveRule>) -> Vec<ReactiveRule> {
-    let preserved: HashMap<&str, i64> = old
-        .iter()
-        .map(|r| (r.id.as_str(), r.last_fired_at.load(Ordering::Relaxed)))
-        .collect();
+    let preserved: HashMap<&str, &ReactiveRule> = old.iter().map(|r| (r.id.as_str(), r)).collect();
     for rule in &new {
-        if let Some(&ts) = preserved.get(rule.id.as_str()) {
-            rule.last_fired_at.store(ts, Ordering::Relaxed);
+        if let Some(prev) = preserved.get(rule.id.as_str()) {
+            rule.last_fired_at.store(
+                prev.last_fired_at.load(Ordering::Relaxed),
+                Ordering::Relaxed,
+            );
+            // A reload must not restart a hold that is under way either.
+            rule.holding_since.store(
+                prev.holding_since.load(Ordering::Relaxed),
+                Ordering::Relaxed,
+            );
+            // Nor re-arm a schedule, unless the schedule itself changed.
+            if prev.schedule == rule.schedule {
+                rule.next_due
+                    .store(prev.next_due.load(Ordering::Relaxed), Ordering::Relaxed);
+            }
+            // Nor forget that a rule is waiting to clear.
+            rule.latched
+                .store(prev.latched.load(Ordering::Relaxed), Ordering::Relaxed);
+            // Nor refill a trigger budget that is spent.
+            let recent = prev
+                .recent_triggers
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone();
+            *rule
+                .recent_triggers
+                .lock()
+                .unwrap_or_else(|e| e.into_inner()) = recent;
+            // Nor restart an escalation, unless its steps changed.
+            if prev.escalation == rule.escalation {
+                rule.escalating_since.store(
+                    prev.escalating_since.load(Ordering::Relaxed),
+                    Ordering::Relaxed,
+                );
+                rule.escalation_level.store(
+                    prev.escalation_level.load(Ordering::Relaxed),
+                    Ordering::Relaxed,
+                );
+            }
         }
     }
     new
@@ -269,18 +558,143 @@ pub const MAX_AROUSAL_BOOST: f64 = 100.0;
 /// MCP tool handler so all validation paths see the same value.
 pub const DEFAULT_DEBOUNCE_SECS: u32 = 60;
 
+/// Sanity ceiling on `hold_secs`, same as for debounce.
+pub const MAX_HOLD_SECS: u32 = 86_400;
+
+/// Bounds of `priority`; rules default to 0.
+pub const MIN_PRIORITY: i32 = -100;
+pub const MAX_PRIORITY: i32 = 100;
+
+/// Upper bound of `max_triggers_per_hour`.
+pub const MAX_TRIGGERS_PER_HOUR: u32 = 3600;
+
 /// Default `arousal_boost` used when the operator does not specify one.
 pub const DEFAULT_AROUSAL_BOOST: f64 = 2.0;
 
+/// Most machine or node patterns in a rule's scope.
+pub const MAX_SCOPE_PATTERNS: usize = 32;
+
+/// Where a rule is active, so one rule can be shared across a fleet.
+/// Patterns are names or globs where `*` matches any run of characters;
+/// an empty list matches everything.
+#[derive(
+    Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+)]
+pub struct RuleScope {
+    /// Machine IDs whose agents evaluate the rule.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub machines: Vec<String>,
+    /// Nodes the rule is about. The rule is active only on machines that
+    /// have one of them, and its node actions may only target them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nodes: Vec<String>,
+}
+
+impl RuleScope {
+    pub fn is_empty(&self) -> bool {
+        self.machines.is_empty() && self.nodes.is_empty()
+    }
+
+    /// Whether the agent on `machine_id`, which has `local_nodes`, should
+    /// evaluate the rule.
+    pub fn applies_to(&self, machine_id: &str, local_nodes: &[String]) -> bool {
+        let machine_ok =
+            self.machines.is_empty() || self.machines.iter().any(|p| glob_match(p, machine_id));
+        let nodes_ok = self.nodes.is_empty() || local_nodes.iter().any(|n| self.allows_node(n));
+        machine_ok && nodes_ok
+    }
+
+    /// Whether the rule's actions may target `node`.
+    pub fn allows_node(&self, node: &str) -> bool {
+        self.nodes.is_empty() || self.nodes.iter().any(|p| glob_match(p, node))
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        for (what, patterns) in [("machines", &self.machines), ("nodes", &self.nodes)] {
+            if patterns.len() > MAX_SCOPE_PATTERNS {
+                anyhow::bail!(
+                    "scope.{} has more than {} patterns",
+                    what,
+                    MAX_SCOPE_PATTERNS
+                );
+            }
+            for pattern in patterns {
+                let valid = !pattern.is_empty()
+                    && pattern.len() <= 64
+                    && pattern
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '*'));
+                if !valid {
+                    anyhow::bail!(
+                        "scope.{} pattern '{}' must be 1-64 letters, digits, '-', '_', '.' or '*'",
+                        what,
+                        pattern
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Match `name` against `pattern`, where `*` matches any run of characters.
+pub fn glob_match(pattern: &str, name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        // No `*`: the whole name must match.
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
 /// Serializable configuration for a reactive rule (no AtomicI64).
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct ReactiveRuleConfig {
     pub id: String,
     pub mission_id: String,
     pub predicate: String,
     pub debounce_secs: u32,
+    /// Seconds the predicate must hold before firing; 0 fires at once.
+    #[serde(default)]
+    pub hold_secs: u32,
+    /// Cron expression (UTC) that triggers the rule; the predicate may
+    /// then be empty.
+    #[serde(default)]
+    pub schedule: Option<String>,
+    /// Predicate that must hold before the rule can fire again.
+    #[serde(default)]
+    pub clear_predicate: Option<String>,
+    /// Higher-priority rules win conflicting node commands.
+    #[serde(default)]
+    pub priority: i32,
+    /// Evaluate and log without running actions or boosting arousal.
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Most firings an hour that run actions; unlimited when `None`.
+    #[serde(default)]
+    pub max_triggers_per_hour: Option<u32>,
+    /// Actions for when the predicate still holds a while after a firing.
+    #[serde(default)]
+    pub escalation: Vec<EscalationStep>,
+    /// Machines and nodes the rule is addressed to; everywhere when empty.
+    #[serde(default)]
+    pub scope: RuleScope,
     pub arousal_boost: f64,
     pub description: String,
+    /// Actions run when the rule fires, besides the arousal boost.
+    #[serde(default)]
+    pub actions: Vec<RuleAction>,
 }
 
 impl ReactiveRuleConfig {
@@ -316,8 +730,17 @@ impl ReactiveRuleConfig {
             bail!("mission_id must be non-empty");
         }
 
+        if let Some(schedule) = &self.schedule {
+            let now = crate::agent::memory::now_epoch_secs();
+            if let Err(e) = crate::agent::scheduler::next_run_after(schedule, now) {
+                bail!("schedule is invalid: {}", e);
+            }
+        }
+
+        // A scheduled rule fires at most once per occurrence, so it needs
+        // no predicate; one that has a predicate must still be well formed.
         let predicate = self.predicate.trim();
-        if predicate.is_empty() {
+        if predicate.is_empty() && self.schedule.is_none() {
             bail!(
                 "predicate must be non-empty (empty predicates match every tick and cause reactive storms)"
             );
@@ -329,13 +752,66 @@ impl ReactiveRuleConfig {
                 MAX_PREDICATE_LEN
             );
         }
-        if extract_predicate_fields(predicate).is_empty() {
-            bail!(
-                "predicate must contain at least one well-formed \
-                 `field <op> value` clause (ops: =, !=, >, <, >=, <=); \
-                 got {:?}",
-                predicate
-            );
+        if !predicate.is_empty() {
+            if let Err(e) = crate::daemon::context_provider::parse_filter(predicate) {
+                bail!("predicate {:?} does not parse: {}", predicate, e);
+            }
+            if extract_predicate_fields(predicate).is_empty() {
+                bail!(
+                    "predicate must contain at least one well-formed \
+                     `field <op> value` clause (ops: =, !=, >, <, >=, <=); \
+                     got {:?}",
+                    predicate
+                );
+            }
+            validate_windows(predicate)?;
+        }
+
+        if let Some(clear) = &self.clear_predicate {
+            let clear = clear.trim();
+            if predicate.is_empty() {
+                bail!("clear_predicate needs a predicate to clear");
+            }
+            if clear.len() > MAX_PREDICATE_LEN {
+                bail!(
+                    "clear_predicate exceeds maximum length ({} > {})",
+                    clear.len(),
+                    MAX_PREDICATE_LEN
+                );
+            }
+            if let Err(e) = crate::daemon::context_provider::parse_filter(clear) {
+                bail!("clear_predicate {:?} does not parse: {}", clear, e);
+            }
+            if extract_predicate_fields(clear).is_empty() {
+                bail!(
+                    "clear_predicate must contain at least one `field <op> value` clause; got {:?}",
+                    clear
+                );
+            }
+            validate_windows(clear)?;
+        }
+
+        crate::daemon::rule_actions::validate_actions(&self.actions)?;
+
+        if !self.escalation.is_empty() && predicate.is_empty() {
+            bail!("escalation needs a predicate that tells whether the condition persists");
+        }
+        crate::daemon::rule_actions::validate_escalation(&self.escalation)?;
+
+        self.scope.validate()?;
+        let escalation_actions = self.escalation.iter().flat_map(|s| &s.actions);
+        for action in self.actions.iter().chain(escalation_actions) {
+            if let Some(node) = action
+                .nodes()
+                .into_iter()
+                .find(|n| !self.scope.allows_node(n))
+            {
+                bail!(
+                    "action {} targets node '{}', which is outside scope.nodes",
+                    action.summary(),
+                    node
+                );
+            }
         }
 
         if self.description.len() > MAX_DESCRIPTION_LEN {
@@ -362,6 +838,33 @@ impl ReactiveRuleConfig {
             );
         }
 
+        if self.hold_secs > MAX_HOLD_SECS {
+            bail!(
+                "hold_secs must be at most {} (got {})",
+                MAX_HOLD_SECS,
+                self.hold_secs
+            );
+        }
+
+        if !(MIN_PRIORITY..=MAX_PRIORITY).contains(&self.priority) {
+            bail!(
+                "priority must be in [{}, {}] (got {})",
+                MIN_PRIORITY,
+                MAX_PRIORITY,
+                self.priority
+            );
+        }
+
+        if let Some(max) = self.max_triggers_per_hour {
+            if max == 0 || max > MAX_TRIGGERS_PER_HOUR {
+                bail!(
+                    "max_triggers_per_hour must be in [1, {}] (got {})",
+                    MAX_TRIGGERS_PER_HOUR,
+                    max
+                );
+            }
+        }
+
         if !self.arousal_boost.is_finite() {
             bail!("arousal_boost must be finite (got {})", self.arousal_boost);
         }
@@ -378,12 +881,24 @@ impl ReactiveRuleConfig {
     }
 }
 
+/// Reject malformed window aggregates such as `avg(cpu_load)`, which would
+/// otherwise read as a world-state key that never exists.
+fn validate_windows(predicate: &str) -> anyhow::Result<()> {
+    for field in extract_predicate_fields(predicate) {
+        if let Some(Err(e)) = crate::daemon::rule_windows::parse_window(&field) {
+            anyhow::bail!("{}", e);
+        }
+    }
+    Ok(())
+}
+
 /// Extract the field name (LHS) from each clause of a predicate.
 ///
 /// Returns a deduplicated list in first-appearance order for
-/// diagnostics. Uses [`crate::daemon::context_provider::parse_clause`]
+/// diagnostics. Uses [`crate::daemon::context_provider::parse_filter`]
 /// so the static analyser stays in lockstep with the runtime evaluator
-/// by construction — no comment-enforced parallelism.
+/// by construction — no comment-enforced parallelism. A predicate that
+/// does not parse has no fields.
 ///
 /// Used by the dangling-reference check for the 2026-04-10 incident:
 /// a rule referenced `motion.level`, no provider populated it, and a
@@ -391,12 +906,13 @@ impl ReactiveRuleConfig {
 /// forever on a "ghost" value.
 pub fn extract_predicate_fields(predicate: &str) -> Vec<String> {
     let mut fields: Vec<String> = Vec::new();
-    for clause in predicate.split(" AND ") {
-        if let Some((field, _, _)) = crate::daemon::context_provider::parse_clause(clause) {
-            let field = field.to_string();
-            if !fields.contains(&field) {
-                fields.push(field);
-            }
+    let Ok(expr) = crate::daemon::context_provider::parse_filter(predicate) else {
+        return fields;
+    };
+    for (field, _, _) in expr.clauses() {
+        let field = field.to_string();
+        if !fields.contains(&field) {
+            fields.push(field);
         }
     }
     fields
@@ -417,6 +933,10 @@ pub fn extract_predicate_fields(predicate: &str) -> Vec<String> {
 ///     coverage: we'd rather miss a real dangling ref than bother
 ///     the operator with a wrong warning.
 ///
+/// A window aggregate such as `avg(cpu_load, 5m)` is covered when its key
+/// is, and a path such as `weather.entries[0].temp` when a literal covers
+/// a leading part of it (`weather`).
+///
 /// Returns an empty vec if every field is covered.
 pub fn find_dangling_fields(
     predicate_fields: &[String],
@@ -438,8 +958,11 @@ pub fn find_dangling_fields(
     predicate_fields
         .iter()
         .filter(|f| {
-            let f = f.as_str();
-            let literal_hit = literals.contains(&f);
+            let f = crate::daemon::rule_windows::source_key(f);
+            let literal_hit = literals.iter().any(|l| {
+                f.strip_prefix(l)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '[']))
+            });
             let prefix_hit = prefixes
                 .iter()
                 .any(|p| !p.is_empty() && f.starts_with(p.as_str()));
@@ -456,9 +979,23 @@ impl From<ReactiveRuleConfig> for ReactiveRule {
             mission_id: c.mission_id,
             predicate: c.predicate,
             debounce_secs: c.debounce_secs,
+            hold_secs: c.hold_secs,
+            schedule: c.schedule,
+            clear_predicate: c.clear_predicate,
+            priority: c.priority,
+            dry_run: c.dry_run,
+            max_triggers_per_hour: c.max_triggers_per_hour,
+            escalation: c.escalation,
             arousal_boost: c.arousal_boost,
             description: c.description,
+            actions: c.actions,
             last_fired_at: AtomicI64::new(0),
+            holding_since: AtomicI64::new(0),
+            next_due: AtomicI64::new(0),
+            latched: AtomicBool::new(false),
+            recent_triggers: Mutex::new(VecDeque::new()),
+            escalating_since: AtomicI64::new(0),
+            escalation_level: AtomicU32::new(0),
         }
     }
 }
@@ -468,6 +1005,21 @@ pub struct ReactiveRuleStore {
     conn: Connection,
 }
 
+/// What [`ReactiveRuleStore::sync_file_rules`] changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileRuleSync {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+}
+
+impl FileRuleSync {
+    /// Whether the rule set changed.
+    pub fn changed(&self) -> bool {
+        self.added + self.updated + self.removed > 0
+    }
+}
+
 impl ReactiveRuleStore {
     /// Open (or create) the reactive rule store at the given path.
     pub fn open(path: &Path) -> anyhow::Result<Self> {
@@ -481,14 +1033,148 @@ impl ReactiveRuleStore {
                 debounce_secs INTEGER NOT NULL DEFAULT 30,
                 arousal_boost REAL NOT NULL DEFAULT 1.0,
                 description   TEXT NOT NULL DEFAULT '',
-                created_at    INTEGER NOT NULL DEFAULT (strftime('%s','now'))
+                created_at    INTEGER NOT NULL DEFAULT (strftime('%s','now')),
+                enabled       INTEGER NOT NULL DEFAULT 1,
+                hold_secs     INTEGER NOT NULL DEFAULT 0,
+                schedule      TEXT,
+                actions       TEXT NOT NULL DEFAULT '[]',
+                clear_predicate TEXT,
+                priority      INTEGER NOT NULL DEFAULT 0,
+                dry_run       INTEGER NOT NULL DEFAULT 0,
+                max_triggers_per_hour INTEGER,
+                escalation    TEXT NOT NULL DEFAULT '[]',
+                from_file     INTEGER NOT NULL DEFAULT 0,
+                scope         TEXT NOT NULL DEFAULT '{}'
             );",
         )?;
 
+        // Stores created before rules could be disabled lack the column.
+        let has_enabled: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('reactive_rules') WHERE name = 'enabled'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_enabled {
+            conn.execute_batch(
+                "ALTER TABLE reactive_rules ADD COLUMN enabled INTEGER NOT NULL DEFAULT 1;",
+            )?;
+        }
+        // ... and before rules could require the predicate to hold.
+        let has_hold: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('reactive_rules') WHERE name = 'hold_secs'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_hold {
+            conn.execute_batch(
+                "ALTER TABLE reactive_rules ADD COLUMN hold_secs INTEGER NOT NULL DEFAULT 0;",
+            )?;
+        }
+        // ... and before rules could run on a schedule.
+        let has_schedule: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('reactive_rules') WHERE name = 'schedule'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_schedule {
+            conn.execute_batch("ALTER TABLE reactive_rules ADD COLUMN schedule TEXT;")?;
+        }
+        // ... and before rules had actions.
+        let has_actions: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('reactive_rules') WHERE name = 'actions'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_actions {
+            conn.execute_batch(
+                "ALTER TABLE reactive_rules ADD COLUMN actions TEXT NOT NULL DEFAULT '[]';",
+            )?;
+        }
+        // ... and before rules could have a clear predicate.
+        let has_clear: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('reactive_rules') \
+             WHERE name = 'clear_predicate'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_clear {
+            conn.execute_batch("ALTER TABLE reactive_rules ADD COLUMN clear_predicate TEXT;")?;
+        }
+        // ... and before rules had priorities.
+        let has_priority: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('reactive_rules') WHERE name = 'priority'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_priority {
+            conn.execute_batch(
+                "ALTER TABLE reactive_rules ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;",
+            )?;
+        }
+        // ... and before rules could run dry.
+        let has_dry_run: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('reactive_rules') WHERE name = 'dry_run'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_dry_run {
+            conn.execute_batch(
+                "ALTER TABLE reactive_rules ADD COLUMN dry_run INTEGER NOT NULL DEFAULT 0;",
+            )?;
+        }
+        // ... and before rules had a trigger budget.
+        let has_budget: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('reactive_rules') \
+             WHERE name = 'max_triggers_per_hour'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_budget {
+            conn.execute_batch(
+                "ALTER TABLE reactive_rules ADD COLUMN max_triggers_per_hour INTEGER;",
+            )?;
+        }
+        // ... and before rules could escalate.
+        let has_escalation: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('reactive_rules') \
+             WHERE name = 'escalation'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_escalation {
+            conn.execute_batch(
+                "ALTER TABLE reactive_rules ADD COLUMN escalation TEXT NOT NULL DEFAULT '[]';",
+            )?;
+        }
+        // ... and before rules could come from `rules.yaml`.
+        let has_from_file: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('reactive_rules') \
+             WHERE name = 'from_file'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_from_file {
+            conn.execute_batch(
+                "ALTER TABLE reactive_rules ADD COLUMN from_file INTEGER NOT NULL DEFAULT 0;",
+            )?;
+        }
+        // ... and before rules could be scoped to machines and nodes.
+        let has_scope: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('reactive_rules') WHERE name = 'scope'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_scope {
+            conn.execute_batch(
+                "ALTER TABLE reactive_rules ADD COLUMN scope TEXT NOT NULL DEFAULT '{}';",
+            )?;
+        }
+
         Ok(Self { conn })
     }
 
-    /// Save (insert or replace) a reactive rule configuration.
+    /// Save (insert or replace) a reactive rule configuration. Replacing a
+    /// rule enables it again.
     ///
     /// Validates the rule before touching the database — any invariant
     /// violation (see [`ReactiveRuleConfig::validate`]) is an error and
@@ -499,8 +1185,10 @@ impl ReactiveRuleStore {
         rule.validate()?;
         self.conn.execute(
             "INSERT OR REPLACE INTO reactive_rules \
-             (id, mission_id, predicate, debounce_secs, arousal_boost, description) \
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+             (id, mission_id, predicate, debounce_secs, arousal_boost, description, hold_secs, \
+              schedule, actions, clear_predicate, priority, dry_run, max_triggers_per_hour, \
+              escalation, scope) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
             params![
                 rule.id,
                 rule.mission_id,
@@ -508,6 +1196,15 @@ impl ReactiveRuleStore {
                 rule.debounce_secs,
                 rule.arousal_boost,
                 rule.description,
+                rule.hold_secs,
+                rule.schedule,
+                serde_json::to_string(&rule.actions)?,
+                rule.clear_predicate,
+                rule.priority,
+                rule.dry_run,
+                rule.max_triggers_per_hour,
+                serde_json::to_string(&rule.escalation)?,
+                serde_json::to_string(&rule.scope)?,
             ],
         )?;
         Ok(())
@@ -516,8 +1213,9 @@ impl ReactiveRuleStore {
     /// List all reactive rule configurations.
     pub fn list_rules(&self) -> anyhow::Result<Vec<ReactiveRuleConfig>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, mission_id, predicate, debounce_secs, arousal_boost, description \
-             FROM reactive_rules ORDER BY id ASC",
+            "SELECT id, mission_id, predicate, debounce_secs, arousal_boost, description, hold_secs, \
+             schedule, actions, clear_predicate, priority, dry_run, max_triggers_per_hour, \
+             escalation, scope FROM reactive_rules ORDER BY id ASC",
         )?;
         let rows = stmt.query_map([], |row| {
             Ok(ReactiveRuleConfig {
@@ -525,13 +1223,138 @@ impl ReactiveRuleStore {
                 mission_id: row.get(1)?,
                 predicate: row.get(2)?,
                 debounce_secs: row.get(3)?,
+                hold_secs: row.get(6)?,
+                schedule: row.get(7)?,
+                clear_predicate: row.get(9)?,
+                priority: row.get(10)?,
+                dry_run: row.get(11)?,
+                max_triggers_per_hour: row.get(12)?,
+                escalation: parse_escalation(row.get(13)?),
+                scope: parse_scope(row.get(14)?),
                 arousal_boost: row.get(4)?,
                 description: row.get(5)?,
+                actions: parse_actions(row.get(8)?),
             })
         })?;
         rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
     }
 
+    /// List the rules the agent evaluates, leaving out disabled ones.
+    pub fn list_enabled_rules(&self) -> anyhow::Result<Vec<ReactiveRuleConfig>> {
+        let disabled = self.disabled_rule_ids()?;
+        let mut rules = self.list_rules()?;
+        rules.retain(|r| !disabled.contains(&r.id));
+        Ok(rules)
+    }
+
+    /// IDs of the rules that are disabled.
+    pub fn disabled_rule_ids(&self) -> anyhow::Result<HashSet<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id FROM reactive_rules WHERE enabled = 0")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect::<Result<HashSet<_>, _>>().map_err(Into::into)
+    }
+
+    /// Enable or disable a rule without deleting it. Returns `false` when
+    /// no rule has this ID.
+    pub fn set_enabled(&self, id: &str, enabled: bool) -> anyhow::Result<bool> {
+        let changed = self.conn.execute(
+            "UPDATE reactive_rules SET enabled = ?1 WHERE id = ?2",
+            params![enabled, id],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// Switch a rule's dry-run mode. Returns `false` when no rule has
+    /// this ID.
+    pub fn set_dry_run(&self, id: &str, dry_run: bool) -> anyhow::Result<bool> {
+        let changed = self.conn.execute(
+            "UPDATE reactive_rules SET dry_run = ?1 WHERE id = ?2",
+            params![dry_run, id],
+        )?;
+        Ok(changed > 0)
+    }
+
+    /// IDs of the rules that came from `rules.yaml`.
+    pub fn file_rule_ids(&self) -> anyhow::Result<HashSet<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id FROM reactive_rules WHERE from_file = 1")?;
+        let rows = stmt.query_map([], |row| row.get(0))?;
+        rows.collect::<Result<HashSet<_>, _>>().map_err(Into::into)
+    }
+
+    /// Make the rules from `rules.yaml` exactly `rules`: add new ones,
+    /// update changed ones and delete the ones no longer listed, all in one
+    /// transaction. Updating a rule keeps it disabled if it was. A rule
+    /// registered some other way is never touched; listing its ID is an
+    /// error and nothing is written.
+    pub fn sync_file_rules(&self, rules: &[ReactiveRuleConfig]) -> anyhow::Result<FileRuleSync> {
+        for rule in rules {
+            rule.validate()
+                .map_err(|e| anyhow::anyhow!("rule '{}': {}", rule.id, e))?;
+        }
+        let tx = self.conn.unchecked_transaction()?;
+        let file_ids = self.file_rule_ids()?;
+        let existing: HashMap<String, ReactiveRuleConfig> = self
+            .list_rules()?
+            .into_iter()
+            .map(|r| (r.id.clone(), r))
+            .collect();
+        let mut sync = FileRuleSync::default();
+        for rule in rules {
+            match existing.get(&rule.id) {
+                Some(_) if !file_ids.contains(&rule.id) => anyhow::bail!(
+                    "rule '{}' already exists and was not registered from rules.yaml",
+                    rule.id
+                ),
+                Some(current) if current == rule => continue,
+                Some(_) => sync.updated += 1,
+                None => sync.added += 1,
+            }
+            tx.execute(
+                "INSERT INTO reactive_rules \
+                 (id, mission_id, predicate, debounce_secs, arousal_boost, description, hold_secs, \
+                  schedule, actions, clear_predicate, priority, dry_run, max_triggers_per_hour, \
+                  escalation, scope, from_file) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, 1) \
+                 ON CONFLICT(id) DO UPDATE SET mission_id = excluded.mission_id, \
+                  predicate = excluded.predicate, debounce_secs = excluded.debounce_secs, \
+                  arousal_boost = excluded.arousal_boost, description = excluded.description, \
+                  hold_secs = excluded.hold_secs, schedule = excluded.schedule, \
+                  actions = excluded.actions, clear_predicate = excluded.clear_predicate, \
+                  priority = excluded.priority, dry_run = excluded.dry_run, \
+                  max_triggers_per_hour = excluded.max_triggers_per_hour, \
+                  escalation = excluded.escalation, scope = excluded.scope",
+                params![
+                    rule.id,
+                    rule.mission_id,
+                    rule.predicate,
+                    rule.debounce_secs,
+                    rule.arousal_boost,
+                    rule.description,
+                    rule.hold_secs,
+                    rule.schedule,
+                    serde_json::to_string(&rule.actions)?,
+                    rule.clear_predicate,
+                    rule.priority,
+                    rule.dry_run,
+                    rule.max_triggers_per_hour,
+                    serde_json::to_string(&rule.escalation)?,
+                    serde_json::to_string(&rule.scope)?,
+                ],
+            )?;
+        }
+        let listed: HashSet<&str> = rules.iter().map(|r| r.id.as_str()).collect();
+        for id in file_ids.iter().filter(|id| !listed.contains(id.as_str())) {
+            tx.execute("DELETE FROM reactive_rules WHERE id = ?1", params![id])?;
+            sync.removed += 1;
+        }
+        tx.commit()?;
+        Ok(sync)
+    }
+
     /// Delete a reactive rule by ID.
     pub fn delete_rule(&self, id: &str) -> anyhow::Result<()> {
         self.conn
@@ -542,8 +1365,9 @@ impl ReactiveRuleStore {
     /// List rules for a specific mission.
     pub fn rules_for_mission(&self, mission_id: &str) -> anyhow::Result<Vec<ReactiveRuleConfig>> {
         let mut stmt = self.conn.prepare(
-            "SELECT id, mission_id, predicate, debounce_secs, arousal_boost, description \
-             FROM reactive_rules WHERE mission_id = ?1 ORDER BY id ASC",
+            "SELECT id, mission_id, predicate, debounce_secs, arousal_boost, description, hold_secs, \
+             schedule, actions, clear_predicate, priority, dry_run, max_triggers_per_hour, \
+             escalation, scope FROM reactive_rules WHERE mission_id = ?1 ORDER BY id ASC",
         )?;
         let rows = stmt.query_map(params![mission_id], |row| {
             Ok(ReactiveRuleConfig {
@@ -551,14 +1375,57 @@ impl ReactiveRuleStore {
                 mission_id: row.get(1)?,
                 predicate: row.get(2)?,
                 debounce_secs: row.get(3)?,
+                hold_secs: row.get(6)?,
+                schedule: row.get(7)?,
+                clear_predicate: row.get(9)?,
+                priority: row.get(10)?,
+                dry_run: row.get(11)?,
+                max_triggers_per_hour: row.get(12)?,
+                escalation: parse_escalation(row.get(13)?),
+                scope: parse_scope(row.get(14)?),
                 arousal_boost: row.get(4)?,
                 description: row.get(5)?,
+                actions: parse_actions(row.get(8)?),
             })
         })?;
         rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
     }
 }
 
+/// Decode the stored action list. A row written by a newer version with an
+/// action this one does not know runs no actions rather than failing the
+/// whole rule load.
+fn parse_actions(json: String) -> Vec<RuleAction> {
+    serde_json::from_str(&json).unwrap_or_else(|e| {
+        log::warn!("[Rules] ignoring unreadable rule actions {:?}: {}", json, e);
+        Vec::new()
+    })
+}
+
+/// Decode the stored escalation steps, leniently like [`parse_actions`].
+fn parse_escalation(json: String) -> Vec<EscalationStep> {
+    serde_json::from_str(&json).unwrap_or_else(|e| {
+        log::warn!(
+            "[Rules] ignoring unreadable rule escalation {:?}: {}",
+            json,
+            e
+        );
+        Vec::new()
+    })
+}
+
+/// Decode the stored scope. An unreadable scope leaves the rule off rather
+/// than letting it run on every machine.
+fn parse_scope(json: String) -> RuleScope {
+    serde_json::from_str(&json).unwrap_or_else(|e| {
+        log::warn!("[Rules] unreadable rule scope {:?}: {}", json, e);
+        RuleScope {
+            machines: vec![String::new()],
+            nodes: Vec::new(),
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -578,6 +1445,34 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn predicate_reads_paths_into_json_values() {
+        let mut ws = HashMap::new();
+        ws.insert(
+            "weather",
+            r#"{"entries": [{"temperature_2m": 31.5}, {"temperature_2m": 12.0}]}"#,
+        );
+        ws.insert("metrics.cpu", r#"{"temp": 71}"#);
+        assert!(eval_predicate(
+            "weather.entries[0].temperature_2m > 30",
+            &ws
+        ));
+        assert!(!eval_predicate(
+            "weather.entries[1].temperature_2m > 30",
+            &ws
+        ));
+        assert!(!eval_predicate(
+            "weather.entries[2].temperature_2m > 30",
+            &ws
+        ));
+        assert!(eval_predicate("metrics.cpu.temp >= 71", &ws));
+        assert_eq!(
+            field_value(&ws, "weather.entries[1].temperature_2m").as_deref(),
+            Some("12.0")
+        );
+        assert_eq!(field_value(&ws, "metrics.cpu.load"), None);
+    }
+
     #[test]
     fn rule_respects_debounce() {
         let now = crate::agent::memory::now_epoch_secs() as i64;
@@ -586,9 +1481,23 @@ mod tests {
             mission_id: "m1".to_string(),
             predicate: "x = 1".to_string(),
             debounce_secs: 60,
+            hold_secs: 0,
+            schedule: None,
+            clear_predicate: None,
+            priority: 0,
+            dry_run: false,
+            max_triggers_per_hour: None,
+            escalation: Vec::new(),
+            actions: Vec::new(),
             arousal_boost: 2.0,
             description: "test rule".to_string(),
             last_fired_at: AtomicI64::new(now - 10), // fired 10s ago
+            holding_since: AtomicI64::new(0),
+            next_due: AtomicI64::new(0),
+            latched: AtomicBool::new(false),
+            recent_triggers: Mutex::new(VecDeque::new()),
+            escalating_since: AtomicI64::new(0),
+            escalation_level: AtomicU32::new(0),
         };
         let mut ws = HashMap::new();
         ws.insert("x", "1");
@@ -604,9 +1513,23 @@ mod tests {
             mission_id: "m1".to_string(),
             predicate: "x = 1".to_string(),
             debounce_secs: 60,
+            hold_secs: 0,
+            schedule: None,
+            clear_predicate: None,
+            priority: 0,
+            dry_run: false,
+            max_triggers_per_hour: None,
+            escalation: Vec::new(),
+            actions: Vec::new(),
             arousal_boost: 1.5,
             description: "test rule".to_string(),
             last_fired_at: AtomicI64::new(now - 70), // fired 70s ago
+            holding_since: AtomicI64::new(0),
+            next_due: AtomicI64::new(0),
+            latched: AtomicBool::new(false),
+            recent_triggers: Mutex::new(VecDeque::new()),
+            escalating_since: AtomicI64::new(0),
+            escalation_level: AtomicU32::new(0),
         };
         let mut ws = HashMap::new();
         ws.insert("x", "1");
@@ -614,6 +1537,123 @@ mod tests {
         assert!(rule.should_fire(&ws));
     }
 
+    #[test]
+    fn rule_fires_only_after_predicate_holds() {
+        let rule = ReactiveRule {
+            hold_secs: 30,
+            debounce_secs: 60,
+            ..mk_rule("held", 0)
+        };
+        let on: HashMap<&str, &str> = [("x", "1")].into_iter().collect();
+        let off: HashMap<&str, &str> = [("x", "0")].into_iter().collect();
+
+        assert!(!rule.should_fire_at(&on, 1_000));
+        assert!(!rule.should_fire_at(&on, 1_020));
+        // A single non-matching sample restarts the hold.
+        assert!(!rule.should_fire_at(&off, 1_025));
+        assert!(!rule.should_fire_at(&on, 1_030));
+        assert!(!rule.should_fire_at(&on, 1_050));
+        assert!(rule.should_fire_at(&on, 1_060));
+        rule.last_fired_at.store(1_060, Ordering::Relaxed);
+        // Still holding, but the cooldown applies.
+        assert!(!rule.should_fire_at(&on, 1_100));
+        assert!(rule.should_fire_at(&on, 1_120));
+    }
+
+    #[test]
+    fn rule_with_clear_predicate_fires_once_per_excursion() {
+        let rule = ReactiveRule {
+            predicate: "cpu_temp > 85".to_string(),
+            clear_predicate: Some("cpu_temp < 75".to_string()),
+            priority: 0,
+            dry_run: false,
+            max_triggers_per_hour: None,
+            debounce_secs: 1,
+            ..mk_rule("hot", 0)
+        };
+        let temp =
+            |t: &'static str| -> HashMap<&str, &str> { [("cpu_temp", t)].into_iter().collect() };
+
+        assert!(rule.should_fire_at(&temp("86"), 1_000));
+        rule.fire();
+        rule.last_fired_at.store(1_000, Ordering::Relaxed);
+        assert!(rule.latched.load(Ordering::Relaxed));
+        // Hovering around the threshold does not fire again...
+        assert!(!rule.should_fire_at(&temp("84"), 1_010));
+        assert!(!rule.should_fire_at(&temp("87"), 1_020));
+        assert!(!rule.should_fire_at(&temp("80"), 1_030));
+        // ... until the value went below the clear threshold.
+        assert!(!rule.should_fire_at(&temp("74"), 1_040));
+        assert!(!rule.latched.load(Ordering::Relaxed));
+        assert!(rule.should_fire_at(&temp("90"), 1_050));
+    }
+
+    #[test]
+    fn scheduled_rule_fires_once_per_occurrence() {
+        // 22:00 UTC on 1970-01-01 is epoch 79_200.
+        let rule = ReactiveRule {
+            predicate: String::new(),
+            schedule: Some("0 22 * * *".to_string()),
+            ..mk_rule("nightly", 0)
+        };
+        let ws = HashMap::new();
+
+        // The first evaluation arms the schedule without firing.
+        assert!(!rule.should_fire_at(&ws, 1_000));
+        assert_eq!(rule.next_due.load(Ordering::Relaxed), 79_200);
+        assert!(!rule.should_fire_at(&ws, 79_199));
+        assert!(rule.should_fire_at(&ws, 79_210));
+        assert!(!rule.should_fire_at(&ws, 79_260));
+        assert_eq!(rule.next_due.load(Ordering::Relaxed), 79_200 + 86_400);
+    }
+
+    #[test]
+    fn scheduled_rule_checks_its_predicate_when_due() {
+        let rule = ReactiveRule {
+            schedule: Some("0 22 * * *".to_string()),
+            ..mk_rule("nightly", 0)
+        };
+        let off: HashMap<&str, &str> = [("x", "0")].into_iter().collect();
+        let on: HashMap<&str, &str> = [("x", "1")].into_iter().collect();
+
+        assert!(!rule.should_fire_at(&on, 1_000));
+        // Not matching at 22:00 skips this occurrence...
+        assert!(!rule.should_fire_at(&off, 79_200));
+        assert!(!rule.should_fire_at(&on, 79_260));
+        // ... and the rule waits for the next one.
+        assert!(rule.should_fire_at(&on, 79_200 + 86_400));
+    }
+
+    #[test]
+    fn merge_rule_state_keeps_schedule_armed_unless_it_changed() {
+        let scheduled = |cron: &str| ReactiveRule {
+            schedule: Some(cron.to_string()),
+            ..mk_rule("a", 0)
+        };
+        let old = vec![scheduled("0 22 * * *")];
+        old[0].next_due.store(79_200, Ordering::Relaxed);
+        let merged = merge_rule_state(&old, vec![scheduled("0 22 * * *")]);
+        assert_eq!(merged[0].next_due.load(Ordering::Relaxed), 79_200);
+        let merged = merge_rule_state(&old, vec![scheduled("0 6 * * *")]);
+        assert_eq!(merged[0].next_due.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn merge_rule_state_keeps_hold_in_progress() {
+        let old = vec![mk_rule("a", 0)];
+        old[0].holding_since.store(1_234, Ordering::Relaxed);
+        let merged = merge_rule_state(&old, vec![mk_rule("a", 0)]);
+        assert_eq!(merged[0].holding_since.load(Ordering::Relaxed), 1_234);
+    }
+
+    #[test]
+    fn merge_rule_state_keeps_rules_latched() {
+        let old = vec![mk_rule("a", 0)];
+        old[0].latched.store(true, Ordering::Relaxed);
+        let merged = merge_rule_state(&old, vec![mk_rule("a", 0)]);
+        assert!(merged[0].latched.load(Ordering::Relaxed));
+    }
+
     #[test]
     fn evaluate_rules_sums_boosts() {
         let now = crate::agent::memory::now_epoch_secs() as i64;
@@ -623,18 +1663,46 @@ mod tests {
                 mission_id: "m1".to_string(),
                 predicate: "x = 1".to_string(),
                 debounce_secs: 0,
+                hold_secs: 0,
+                schedule: None,
+                clear_predicate: None,
+                priority: 0,
+                dry_run: false,
+                max_triggers_per_hour: None,
+                escalation: Vec::new(),
+                actions: Vec::new(),
                 arousal_boost: 1.0,
                 description: String::new(),
                 last_fired_at: AtomicI64::new(now - 100),
+                holding_since: AtomicI64::new(0),
+                next_due: AtomicI64::new(0),
+                latched: AtomicBool::new(false),
+                recent_triggers: Mutex::new(VecDeque::new()),
+                escalating_since: AtomicI64::new(0),
+                escalation_level: AtomicU32::new(0),
             },
             ReactiveRule {
                 id: "r2".to_string(),
                 mission_id: "m1".to_string(),
                 predicate: "x = 1".to_string(),
                 debounce_secs: 0,
+                hold_secs: 0,
+                schedule: None,
+                clear_predicate: None,
+                priority: 0,
+                dry_run: false,
+                max_triggers_per_hour: None,
+                escalation: Vec::new(),
+                actions: Vec::new(),
                 arousal_boost: 2.0,
                 description: String::new(),
                 last_fired_at: AtomicI64::new(now - 100),
+                holding_since: AtomicI64::new(0),
+                next_due: AtomicI64::new(0),
+                latched: AtomicBool::new(false),
+                recent_triggers: Mutex::new(VecDeque::new()),
+                escalating_since: AtomicI64::new(0),
+                escalation_level: AtomicU32::new(0),
             },
         ];
         let mut ws = HashMap::new();
@@ -650,12 +1718,167 @@ mod tests {
             mission_id: "m1".to_string(),
             predicate: "x = 1".to_string(),
             debounce_secs: 60,
+            hold_secs: 0,
+            schedule: None,
+            clear_predicate: None,
+            priority: 0,
+            dry_run: false,
+            max_triggers_per_hour: None,
+            escalation: Vec::new(),
+            actions: Vec::new(),
             arousal_boost: 1.0,
             description: String::new(),
             last_fired_at: AtomicI64::new(last),
+            holding_since: AtomicI64::new(0),
+            next_due: AtomicI64::new(0),
+            latched: AtomicBool::new(false),
+            recent_triggers: Mutex::new(VecDeque::new()),
+            escalating_since: AtomicI64::new(0),
+            escalation_level: AtomicU32::new(0),
         }
     }
 
+    #[test]
+    fn escalation_runs_steps_while_the_predicate_holds() {
+        let step = |after_secs, channel: &str| EscalationStep {
+            after_secs,
+            actions: vec![RuleAction::Notify(
+                crate::daemon::rule_actions::NotifyAction::new(channel),
+            )],
+        };
+        let rules = vec![ReactiveRule {
+            escalation: vec![step(600, "phone"), step(1800, "oncall")],
+            ..mk_rule("hot", 0)
+        }];
+        let on = HashMap::from([("x", "1")]);
+        let off = HashMap::from([("x", "0")]);
+        let fired = |id: &str, throttled| FiredRule {
+            id: id.to_string(),
+            mission_id: "m1".to_string(),
+            predicate: "x = 1".to_string(),
+            schedule: None,
+            description: String::new(),
+            priority: 0,
+            dry_run: false,
+            throttled,
+            escalation: 0,
+            paused_by: None,
+            boost: 1.0,
+            actions: Vec::new(),
+        };
+        let escalate = |fired: &[FiredRule], ws: &HashMap<&str, &str>, now| {
+            evaluate_escalations_at(&rules, fired, ws, now)
+        };
+
+        // Nothing escalates before a firing that ran its actions.
+        assert!(escalate(&[], &on, 1_000).is_empty());
+        assert!(escalate(&[fired("hot", true)], &on, 1_000).is_empty());
+        let events = escalate(&[fired("hot", false)], &on, 1_000);
+        assert!(matches!(
+            &events[..],
+            [EscalationEvent::Started { since: 1_000, .. }]
+        ));
+
+        assert!(escalate(&[], &on, 1_599).is_empty());
+        let events = escalate(&[], &on, 1_600);
+        let [EscalationEvent::Escalated { rule, since: 1_000 }] = &events[..] else {
+            panic!("expected the first step, got {:?}", events);
+        };
+        assert_eq!(rule.escalation, 1);
+        assert_eq!(rule.boost, 0.0);
+        assert_eq!(rule.actions[0].summary(), "notify phone");
+        assert!(escalate(&[], &on, 1_700).is_empty());
+
+        // A firing while escalating does not start over.
+        assert!(escalate(&[fired("hot", false)], &on, 2_000).is_empty());
+        let events = escalate(&[], &on, 2_800);
+        assert!(
+            matches!(&events[..], [EscalationEvent::Escalated { rule, .. }] if rule.escalation == 2)
+        );
+        // After the last step the escalation stays on until the condition clears.
+        assert!(escalate(&[], &on, 9_000).is_empty());
+        let events = escalate(&[], &off, 9_100);
+        assert!(matches!(
+            &events[..],
+            [EscalationEvent::Resolved { level: 2, .. }]
+        ));
+        assert_eq!(rules[0].escalating_since.load(Ordering::Relaxed), 0);
+
+        // A reload keeps the escalation, unless its steps changed.
+        escalate(&[fired("hot", false)], &on, 10_000);
+        let same = vec![ReactiveRule {
+            escalation: vec![step(600, "phone"), step(1800, "oncall")],
+            ..mk_rule("hot", 0)
+        }];
+        let same = merge_rule_state(&rules, same);
+        assert_eq!(same[0].escalating_since.load(Ordering::Relaxed), 10_000);
+        let changed = vec![ReactiveRule {
+            escalation: vec![step(300, "phone")],
+            ..mk_rule("hot", 0)
+        }];
+        let changed = merge_rule_state(&rules, changed);
+        assert_eq!(changed[0].escalating_since.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn trigger_budget_throttles_firings_within_an_hour() {
+        let rule = ReactiveRule {
+            max_triggers_per_hour: Some(2),
+            ..mk_rule("flappy", 0)
+        };
+        assert!(rule.within_budget(100));
+        assert!(rule.within_budget(200));
+        assert!(!rule.within_budget(300));
+        // Throttled firings do not count: one hour after the first
+        // trigger, one slot is free again.
+        assert!(rule.within_budget(3700));
+        assert!(!rule.within_budget(3750));
+        assert!(mk_rule("unlimited", 0).within_budget(100));
+
+        // A spent budget survives a reload.
+        let old = vec![rule];
+        let new = merge_rule_state(
+            &old,
+            vec![ReactiveRule {
+                max_triggers_per_hour: Some(2),
+                ..mk_rule("flappy", 0)
+            }],
+        );
+        assert!(!new[0].within_budget(3790));
+
+        // Over budget, a rule still fires but neither boosts nor acts.
+        let ws: HashMap<&str, &str> = [("x", "1")].into_iter().collect();
+        let now = crate::agent::memory::now_epoch_secs() as i64;
+        new[0]
+            .recent_triggers
+            .lock()
+            .unwrap()
+            .extend([now - 10, now - 5]);
+        let fired = evaluate_rules_fired(&new, &ws);
+        assert_eq!(fired.len(), 1);
+        assert!(fired[0].throttled);
+        assert_eq!(fired[0].boost, 0.0);
+    }
+
+    #[test]
+    fn dry_run_rules_fire_without_boost() {
+        let rules = vec![
+            mk_rule("live", 0),
+            ReactiveRule {
+                dry_run: true,
+                ..mk_rule("trial", 0)
+            },
+        ];
+        let ws: HashMap<&str, &str> = [("x", "1")].into_iter().collect();
+        let fired = evaluate_rules_fired(&rules, &ws);
+        assert_eq!(fired.len(), 2);
+        assert!(fired[1].dry_run);
+        assert_eq!(fired[1].boost, 0.0);
+        assert!((total_boost(&fired) - 1.0).abs() < f64::EPSILON);
+        // Debounce applies as if the rule were armed.
+        assert!(evaluate_rules_fired(&rules, &ws).is_empty());
+    }
+
     #[test]
     fn merge_rule_state_preserves_last_fired_for_matching_ids() {
         // The whole point of the helper: after a reload, a rule that fired
@@ -735,15 +1958,38 @@ mod tests {
             mission_id: "m1".to_string(),
             predicate: "x = 1".to_string(),
             debounce_secs: 60,
+            hold_secs: 0,
+            schedule: None,
+            clear_predicate: None,
+            priority: 0,
+            dry_run: false,
+            max_triggers_per_hour: None,
+            escalation: Vec::new(),
+            actions: Vec::new(),
             arousal_boost: 1.0,
             description: String::new(),
             last_fired_at: AtomicI64::new(now - 10),
+            holding_since: AtomicI64::new(0),
+            next_due: AtomicI64::new(0),
+            latched: AtomicBool::new(false),
+            recent_triggers: Mutex::new(VecDeque::new()),
+            escalating_since: AtomicI64::new(0),
+            escalation_level: AtomicU32::new(0),
         }];
         let reloaded_without_merge: Vec<ReactiveRule> = vec![ReactiveRuleConfig {
             id: "r".to_string(),
             mission_id: "m1".to_string(),
             predicate: "x = 1".to_string(),
             debounce_secs: 60,
+            hold_secs: 0,
+            schedule: None,
+            clear_predicate: None,
+            priority: 0,
+            dry_run: false,
+            max_triggers_per_hour: None,
+            escalation: Vec::new(),
+            scope: RuleScope::default(),
+            actions: Vec::new(),
             arousal_boost: 1.0,
             description: String::new(),
         }]
@@ -772,6 +2018,23 @@ mod tests {
             mission_id: "mission-dog".to_string(),
             predicate: "dog.near_stairs = true AND dog.confidence > 0.85".to_string(),
             debounce_secs: 30,
+            hold_secs: 15,
+            schedule: Some("*/5 * * * *".to_string()),
+            clear_predicate: Some("dog.near_stairs = false".to_string()),
+            priority: 0,
+            dry_run: true,
+            max_triggers_per_hour: Some(6),
+            escalation: vec![EscalationStep {
+                after_secs: 600,
+                actions: vec![RuleAction::Notify(
+                    crate::daemon::rule_actions::NotifyAction::new("phone"),
+                )],
+            }],
+            scope: RuleScope {
+                machines: vec!["jetson-*".to_string()],
+                nodes: Vec::new(),
+            },
+            actions: Vec::new(),
             arousal_boost: 2.5,
             description: "Dog near stairs alert".to_string(),
         };
@@ -785,6 +2048,20 @@ mod tests {
         assert_eq!(rules[0].predicate, rule.predicate);
         assert!((rules[0].arousal_boost - 2.5).abs() < f64::EPSILON);
         assert_eq!(rules[0].debounce_secs, 30);
+        assert_eq!(rules[0].hold_secs, 15);
+        assert_eq!(rules[0].schedule.as_deref(), Some("*/5 * * * *"));
+        assert_eq!(
+            rules[0].clear_predicate.as_deref(),
+            Some("dog.near_stairs = false")
+        );
+        assert!(rules[0].dry_run);
+        assert_eq!(rules[0].max_triggers_per_hour, Some(6));
+        assert_eq!(rules[0].escalation, rule.escalation);
+        assert_eq!(rules[0].scope, rule.scope);
+
+        assert!(store.set_dry_run("alert-1", false).unwrap());
+        assert!(!store.list_rules().unwrap()[0].dry_run);
+        assert!(!store.set_dry_run("alert-missing", false).unwrap());
     }
 
     #[test]
@@ -797,6 +2074,15 @@ mod tests {
             mission_id: "m1".to_string(),
             predicate: "temp > 100".to_string(),
             debounce_secs: 60,
+            hold_secs: 0,
+            schedule: None,
+            clear_predicate: None,
+            priority: 0,
+            dry_run: false,
+            max_triggers_per_hour: None,
+            escalation: Vec::new(),
+            scope: RuleScope::default(),
+            actions: Vec::new(),
             arousal_boost: 1.0,
             description: "High temp".to_string(),
         };
@@ -808,6 +2094,71 @@ mod tests {
         assert!(store.list_rules().unwrap().is_empty());
     }
 
+    #[test]
+    fn reactive_rule_store_disables_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ReactiveRuleStore::open(&dir.path().join("alerts.db")).unwrap();
+        let mut rule = ReactiveRuleConfig {
+            id: "a1".to_string(),
+            mission_id: "m1".to_string(),
+            predicate: "x = 1".to_string(),
+            debounce_secs: 30,
+            hold_secs: 0,
+            schedule: None,
+            clear_predicate: None,
+            priority: 0,
+            dry_run: false,
+            max_triggers_per_hour: None,
+            escalation: Vec::new(),
+            scope: RuleScope::default(),
+            actions: Vec::new(),
+            arousal_boost: 1.0,
+            description: String::new(),
+        };
+        store.save_rule(&rule).unwrap();
+        rule.id = "a2".to_string();
+        store.save_rule(&rule).unwrap();
+
+        assert!(store.set_enabled("a1", false).unwrap());
+        assert!(!store.set_enabled("missing", false).unwrap());
+        assert_eq!(store.list_rules().unwrap().len(), 2);
+        let enabled = store.list_enabled_rules().unwrap();
+        assert_eq!(enabled.len(), 1);
+        assert_eq!(enabled[0].id, "a2");
+        assert!(store.disabled_rule_ids().unwrap().contains("a1"));
+
+        // The flag survives reopening; re-saving a rule enables it again.
+        let store = ReactiveRuleStore::open(&dir.path().join("alerts.db")).unwrap();
+        assert_eq!(store.list_enabled_rules().unwrap().len(), 1);
+        rule.id = "a1".to_string();
+        store.save_rule(&rule).unwrap();
+        assert_eq!(store.list_enabled_rules().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn reactive_rule_store_adds_enabled_column_to_old_stores() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("alerts.db");
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE reactive_rules (
+                    id TEXT PRIMARY KEY, mission_id TEXT NOT NULL, predicate TEXT NOT NULL,
+                    debounce_secs INTEGER NOT NULL DEFAULT 30, arousal_boost REAL NOT NULL DEFAULT 1.0,
+                    description TEXT NOT NULL DEFAULT '', created_at INTEGER NOT NULL DEFAULT 0);
+                 INSERT INTO reactive_rules (id, mission_id, predicate) VALUES ('old', 'm', 'x = 1');",
+            )
+            .unwrap();
+
+        let store = ReactiveRuleStore::open(&path).unwrap();
+        assert_eq!(store.list_enabled_rules().unwrap().len(), 1);
+        assert!(store.list_rules().unwrap()[0].schedule.is_none());
+        assert!(store.list_rules().unwrap()[0].clear_predicate.is_none());
+        assert!(!store.list_rules().unwrap()[0].dry_run);
+        assert!(store.set_enabled("old", false).unwrap());
+        assert!(store.list_enabled_rules().unwrap().is_empty());
+    }
+
     #[test]
     fn reactive_rule_store_rules_for_mission() {
         let dir = tempfile::tempdir().unwrap();
@@ -819,6 +2170,15 @@ mod tests {
                 mission_id: "m1".to_string(),
                 predicate: "x = 1".to_string(),
                 debounce_secs: 30,
+                hold_secs: 0,
+                schedule: None,
+                clear_predicate: None,
+                priority: 0,
+                dry_run: false,
+                max_triggers_per_hour: None,
+                escalation: Vec::new(),
+                scope: RuleScope::default(),
+                actions: Vec::new(),
                 arousal_boost: 1.0,
                 description: String::new(),
             })
@@ -829,6 +2189,15 @@ mod tests {
                 mission_id: "m2".to_string(),
                 predicate: "y = 2".to_string(),
                 debounce_secs: 30,
+                hold_secs: 0,
+                schedule: None,
+                clear_predicate: None,
+                priority: 0,
+                dry_run: false,
+                max_triggers_per_hour: None,
+                escalation: Vec::new(),
+                scope: RuleScope::default(),
+                actions: Vec::new(),
                 arousal_boost: 1.0,
                 description: String::new(),
             })
@@ -846,6 +2215,15 @@ mod tests {
             mission_id: "m1".to_string(),
             predicate: "x > 5".to_string(),
             debounce_secs: 45,
+            hold_secs: 0,
+            schedule: None,
+            clear_predicate: None,
+            priority: 0,
+            dry_run: false,
+            max_triggers_per_hour: None,
+            escalation: Vec::new(),
+            scope: RuleScope::default(),
+            actions: Vec::new(),
             arousal_boost: 3.0,
             description: "test".to_string(),
         };
@@ -1018,6 +2396,15 @@ mod tests {
             mission_id: "m1".to_string(),
             predicate: "motion.level > 0.05".to_string(),
             debounce_secs: 60,
+            hold_secs: 0,
+            schedule: None,
+            clear_predicate: None,
+            priority: 0,
+            dry_run: false,
+            max_triggers_per_hour: None,
+            escalation: Vec::new(),
+            scope: RuleScope::default(),
+            actions: Vec::new(),
             arousal_boost: 2.0,
             description: "motion detected".to_string(),
         }
@@ -1079,6 +2466,70 @@ mod tests {
         }
     }
 
+    #[test]
+    fn validate_accepts_compound_predicates_and_rejects_unbalanced_ones() {
+        let mut c = valid_cfg();
+        c.predicate = "cpu_temp > 80 AND (fan_rpm < 500 OR NOT fan.ok = true)".to_string();
+        c.validate().expect("compound predicate must validate");
+
+        c.predicate = "cpu_temp > 80 AND (fan_rpm < 500".to_string();
+        let err = c.validate().unwrap_err().to_string();
+        assert!(err.contains("does not parse"), "{err}");
+    }
+
+    #[test]
+    fn validate_bounds_hold_secs() {
+        let mut c = valid_cfg();
+        c.hold_secs = MAX_HOLD_SECS;
+        c.validate().expect("maximum hold must validate");
+        c.hold_secs = MAX_HOLD_SECS + 1;
+        let err = c.validate().unwrap_err().to_string();
+        assert!(err.contains("hold_secs"), "{err}");
+    }
+
+    #[test]
+    fn validate_allows_scheduled_rules_without_predicate() {
+        let mut c = valid_cfg();
+        c.predicate = String::new();
+        c.schedule = Some("0 22 * * *".to_string());
+        assert!(c.validate().is_ok());
+        c.predicate = "x = 1 AND (".to_string();
+        assert!(c.validate().is_err());
+        c.predicate = String::new();
+        c.schedule = Some("at ten".to_string());
+        let err = c.validate().unwrap_err().to_string();
+        assert!(err.contains("schedule"), "{err}");
+    }
+
+    #[test]
+    fn validate_checks_window_aggregates() {
+        let mut c = valid_cfg();
+        c.predicate = "avg(cpu_load, 5m) > 3.0".to_string();
+        assert!(c.validate().is_ok());
+        c.predicate = "avg(cpu_load) > 3.0".to_string();
+        let err = c.validate().unwrap_err().to_string();
+        assert!(err.contains("needs a key and a window"), "{err}");
+        c.predicate = "x = 1".to_string();
+        c.clear_predicate = Some("median(cpu_load, 5m) < 1".to_string());
+        let err = c.validate().unwrap_err().to_string();
+        assert!(err.contains("unknown aggregate"), "{err}");
+    }
+
+    #[test]
+    fn validate_checks_clear_predicate() {
+        let mut c = valid_cfg();
+        c.clear_predicate = Some("x < 1".to_string());
+        assert!(c.validate().is_ok());
+        c.clear_predicate = Some("x < 1 AND (".to_string());
+        let err = c.validate().unwrap_err().to_string();
+        assert!(err.contains("clear_predicate"), "{err}");
+        c.clear_predicate = Some("x < 1".to_string());
+        c.predicate = String::new();
+        c.schedule = Some("0 22 * * *".to_string());
+        let err = c.validate().unwrap_err().to_string();
+        assert!(err.contains("needs a predicate"), "{err}");
+    }
+
     #[test]
     fn validate_rejects_overlong_predicate() {
         let mut c = valid_cfg();
@@ -1115,6 +2566,105 @@ mod tests {
         assert!(err.contains("at most"), "{err}");
     }
 
+    #[test]
+    fn validate_bounds_trigger_budget() {
+        let mut c = valid_cfg();
+        c.max_triggers_per_hour = Some(0);
+        let err = c.validate().unwrap_err().to_string();
+        assert!(err.contains("max_triggers_per_hour"), "{err}");
+        c.max_triggers_per_hour = Some(MAX_TRIGGERS_PER_HOUR + 1);
+        assert!(c.validate().is_err());
+        c.max_triggers_per_hour = Some(6);
+        assert!(c.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_checks_escalation_steps() {
+        let step = |after_secs| EscalationStep {
+            after_secs,
+            actions: vec![RuleAction::Node(
+                crate::daemon::rule_actions::NodeAction::new(
+                    "camera",
+                    crate::daemon::rule_actions::NodeActionCommand::Stop,
+                ),
+            )],
+        };
+        let mut c = valid_cfg();
+        c.escalation = vec![step(600), step(1800)];
+        assert!(c.validate().is_ok());
+
+        c.escalation = vec![step(1800), step(600)];
+        let err = c.validate().unwrap_err().to_string();
+        assert!(err.contains("increase"), "{err}");
+        c.escalation = vec![step(0)];
+        assert!(c.validate().is_err());
+        c.escalation = vec![EscalationStep {
+            after_secs: 60,
+            actions: Vec::new(),
+        }];
+        assert!(c.validate().is_err());
+
+        // Without a predicate nothing tells whether the condition persists.
+        c.escalation = vec![step(600)];
+        c.predicate = String::new();
+        c.schedule = Some("0 * * * *".to_string());
+        let err = c.validate().unwrap_err().to_string();
+        assert!(err.contains("escalation needs a predicate"), "{err}");
+    }
+
+    #[test]
+    fn scope_selects_machines_and_nodes() {
+        assert!(glob_match("jetson-*", "jetson-01"));
+        assert!(glob_match("*-cam*", "front-camera"));
+        assert!(glob_match("rack", "rack"));
+        assert!(!glob_match("rack", "rack-2"));
+        assert!(!glob_match("jetson-*", "pi-01"));
+        assert!(!glob_match("a*a", "a"));
+
+        let everywhere = RuleScope::default();
+        assert!(everywhere.applies_to("pi-01", &[]));
+
+        let scope = RuleScope {
+            machines: vec!["jetson-*".to_string()],
+            nodes: vec!["camera-*".to_string()],
+        };
+        let nodes = vec!["camera-front".to_string(), "lidar".to_string()];
+        assert!(scope.applies_to("jetson-01", &nodes));
+        assert!(!scope.applies_to("pi-01", &nodes));
+        assert!(!scope.applies_to("jetson-01", &["lidar".to_string()]));
+        assert!(scope.allows_node("camera-rear"));
+        assert!(!scope.allows_node("lidar"));
+    }
+
+    #[test]
+    fn validate_checks_scope() {
+        let stop = |node: &str| {
+            RuleAction::Node(crate::daemon::rule_actions::NodeAction::new(
+                node,
+                crate::daemon::rule_actions::NodeActionCommand::Stop,
+            ))
+        };
+        let mut c = valid_cfg();
+        c.scope.nodes = vec!["camera-*".to_string()];
+        c.actions = vec![stop("camera-front")];
+        assert!(c.validate().is_ok());
+
+        c.actions = vec![stop("lidar")];
+        let err = c.validate().unwrap_err().to_string();
+        assert!(err.contains("outside scope.nodes"), "{err}");
+        c.actions.clear();
+        c.escalation = vec![EscalationStep {
+            after_secs: 600,
+            actions: vec![stop("lidar")],
+        }];
+        assert!(c.validate().is_err());
+
+        c.escalation.clear();
+        c.scope.machines = vec!["bad name".to_string()];
+        let err = c.validate().unwrap_err().to_string();
+        assert!(err.contains("scope.machines"), "{err}");
+    }
+
     #[test]
     fn validate_rejects_nan_arousal_boost() {
         let mut c = valid_cfg();
@@ -1223,6 +2773,13 @@ mod tests {
         assert_eq!(f, vec!["a", "b", "c", "d", "e", "f"]);
     }
 
+    #[test]
+    fn extract_fields_walks_or_not_and_groups() {
+        let f = extract_predicate_fields("cpu_temp > 80 AND NOT (fan_rpm < 500 OR zone = rack)");
+        assert_eq!(f, vec!["cpu_temp", "fan_rpm", "zone"]);
+        assert!(extract_predicate_fields("(x = 1").is_empty());
+    }
+
     #[test]
     fn extract_fields_dedupes_repeated_references() {
         let f = extract_predicate_fields("x > 0 AND x < 10 AND y = 1");
@@ -1252,6 +2809,16 @@ mod tests {
         assert!(find_dangling_fields(&fields, &tpls).is_empty());
     }
 
+    #[test]
+    fn dangling_checks_the_key_of_window_aggregates() {
+        let fields = vec!["avg(motion.level, 5m)".to_string()];
+        assert!(find_dangling_fields(&fields, &["motion.level".to_string()]).is_empty());
+        assert_eq!(
+            find_dangling_fields(&fields, &["temperature.value".to_string()]),
+            fields
+        );
+    }
+
     #[test]
     fn dangling_reports_missing_literal() {
         let fields = vec!["motion.level".to_string()];
@@ -1262,6 +2829,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dangling_treats_paths_into_a_literal_as_covered() {
+        let fields = vec![
+            "weather.entries[0].temperature_2m".to_string(),
+            "weatherstation.ok".to_string(),
+        ];
+        let tpls = vec!["weather".to_string()];
+        assert_eq!(
+            find_dangling_fields(&fields, &tpls),
+            vec!["weatherstation.ok".to_string()]
+        );
+    }
+
     #[test]
     fn dangling_empty_when_prefix_template_covers_field() {
         // Template "object.{label}" → prefix "object." → covers any
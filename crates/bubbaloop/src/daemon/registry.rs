@@ -472,6 +472,43 @@ pub fn check_is_built(node_path: &str, manifest: &NodeManifest) -> bool {
     true
 }
 
+/// Return `dir` if `dir/node.yaml` exists and its `name` field matches
+/// `node_name`, otherwise `None`.
+fn node_name_matches(dir: &Path, node_name: &str) -> Option<PathBuf> {
+    let node_yaml = dir.join("node.yaml");
+    if !node_yaml.exists() {
+        return None;
+    }
+    let content = std::fs::read_to_string(&node_yaml).ok()?;
+    let manifest: serde_yaml::Value = serde_yaml::from_str(&content).ok()?;
+    if manifest.get("name").and_then(|v| v.as_str()) == Some(node_name) {
+        Some(dir.to_path_buf())
+    } else {
+        None
+    }
+}
+
+/// Search `nodes_dir` for the directory of `node_name` (top-level and one
+/// level deep, for multi-node repos).
+pub fn find_installed_node(nodes_dir: &Path, node_name: &str) -> Option<PathBuf> {
+    for entry in std::fs::read_dir(nodes_dir).ok()?.flatten() {
+        if let Some(path) = node_name_matches(&entry.path(), node_name) {
+            return Some(path);
+        }
+        if !entry.path().is_dir() {
+            continue;
+        }
+        if let Ok(sub_entries) = std::fs::read_dir(entry.path()) {
+            for sub_entry in sub_entries.flatten() {
+                if let Some(path) = node_name_matches(&sub_entry.path(), node_name) {
+                    return Some(path);
+                }
+            }
+        }
+    }
+    None
+}
+
 /// Validate a config_override path to prevent systemd specifier injection and path traversal.
 ///
 /// Rejects:
@@ -961,4 +998,22 @@ mod tests {
         let m = manifest_with("python", Some("pixi run run"));
         assert!(check_is_built(dir.path().to_str().unwrap(), &m));
     }
+
+    #[test]
+    fn find_installed_node_top_level_and_nested() {
+        let dir = tempfile::tempdir().unwrap();
+        let top = dir.path().join("telemetry");
+        let nested = dir.path().join("official").join("rtsp-camera");
+        std::fs::create_dir_all(&top).unwrap();
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(top.join("node.yaml"), "name: system-telemetry\n").unwrap();
+        std::fs::write(nested.join("node.yaml"), "name: rtsp-camera\n").unwrap();
+
+        assert_eq!(
+            find_installed_node(dir.path(), "system-telemetry"),
+            Some(top)
+        );
+        assert_eq!(find_installed_node(dir.path(), "rtsp-camera"), Some(nested));
+        assert_eq!(find_installed_node(dir.path(), "missing"), None);
+    }
 }
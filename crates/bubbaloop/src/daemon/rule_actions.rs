@@ -0,0 +1,1513 @@
+//! Actions a reactive rule runs when it fires, besides boosting arousal.
+//!
+//! Actions are stored with the rule as a JSON list and run by the agent
+//! loop in the background, so a slow endpoint never delays the heartbeat:
+//!
+//! ```json
+//! [{"type": "http", "url": "https://hooks.example.com/bubbaloop",
+//!   "headers": {"X-Source": "rack"}, "secret": "env:HOOK_TOKEN",
+//!   "timeout_secs": 5, "retries": 2}]
+//! ```
+//!
+//! An `http` action POSTs [`payload`] as JSON. `secret` is sent as
+//! `Authorization: Bearer <secret>`; `env:NAME` reads it from the agent's
+//! environment so the token does not have to be stored in `alerts.db`.
+//!
+//! A `notify` action sends a message to a channel (ntfy, Slack or email)
+//! configured in `~/.bubbaloop/notifications.yaml`, see
+//! [`super::notifications`].
+//!
+//! A `node` action starts, stops or restarts a node. When rules that fire
+//! together give one node different commands, only the command of the
+//! rule with the highest `priority` runs, see [`resolve_conflicts`].
+//!
+//! A `command` action sends a command to a running node's `command`
+//! queryable, the same request the `send_command` tool makes, e.g. to have
+//! a camera save a clip of the event:
+//!
+//! ```json
+//! [{"type": "command", "node": "entrance", "command": "save_clip",
+//!   "params": {"pre_secs": 10, "post_secs": 20}}]
+//! ```
+//!
+//! A `workflow` action is a small runbook: its steps run in order, and
+//! besides the actions above a step can `wait`, `check` a node's status or
+//! `publish` to a Zenoh topic:
+//!
+//! ```json
+//! {"type": "workflow", "steps": [
+//!   {"type": "node", "node": "camera", "command": "restart"},
+//!   {"type": "wait", "secs": 30},
+//!   {"type": "check", "node": "camera", "status": "running", "health": "healthy"},
+//!   {"type": "publish", "topic": "bubbaloop/local/rack/camera/recovered",
+//!    "on_failure": "continue"}]}
+//! ```
+//!
+//! A step that fails stops the workflow unless its `on_failure` is
+//! `continue`. Node steps run as written: only top-level `node` actions
+//! take part in [`resolve_conflicts`].
+//!
+//! A rule's `escalation` lists further actions for when its predicate still
+//! holds some time after it fired, e.g. restart a node, then notify a human
+//! ten minutes later, then stop the node after half an hour:
+//!
+//! ```json
+//! [{"after_secs": 600, "actions": [{"type": "notify", "channel": "phone"}]},
+//!  {"after_secs": 1800, "actions": [{"type": "node", "node": "camera", "command": "stop"}]}]
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::notifications::{self, RateLimiter};
+use super::reactive::FiredRule;
+use super::rule_events::ActionResult;
+use crate::mcp::platform::{NodeCommand, PlatformOperations};
+
+/// Most actions a single rule may carry.
+pub const MAX_ACTIONS: usize = 8;
+
+/// Default and ceiling of `timeout_secs`.
+pub const DEFAULT_HTTP_TIMEOUT_SECS: u32 = 10;
+pub const MAX_HTTP_TIMEOUT_SECS: u32 = 60;
+
+/// Default and ceiling of `retries`.
+pub const DEFAULT_HTTP_RETRIES: u32 = 2;
+pub const MAX_HTTP_RETRIES: u32 = 5;
+
+/// Wait before the first retry; doubled for each further one.
+const RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Most steps a workflow may have.
+pub const MAX_WORKFLOW_STEPS: usize = 16;
+
+/// Longest total time the `wait` steps of a workflow may add up to.
+pub const MAX_WORKFLOW_WAIT_SECS: u32 = 3600;
+
+/// Most escalation steps a rule may have.
+pub const MAX_ESCALATION_STEPS: usize = 4;
+
+/// Latest an escalation step may run after the rule fired.
+pub const MAX_ESCALATION_AFTER_SECS: u32 = 86_400;
+
+/// Largest the JSON `params` of a `command` action may be.
+pub const MAX_COMMAND_PARAMS_LEN: usize = 4096;
+
+/// Shown instead of a secret when rules are listed.
+const REDACTED: &str = "***";
+
+/// What a rule does when it fires.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleAction {
+    /// POST the firing as JSON to a URL (Home Assistant, PagerDuty, ...).
+    Http(HttpAction),
+    /// Send a message to a channel in `notifications.yaml`.
+    Notify(NotifyAction),
+    /// Start, stop or restart a node.
+    Node(NodeAction),
+    /// Send a command to a running node.
+    Command(CommandAction),
+    /// Run steps in order, stopping at a failed one.
+    Workflow(WorkflowAction),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct WorkflowAction {
+    pub steps: Vec<WorkflowStep>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct WorkflowStep {
+    #[serde(flatten)]
+    pub kind: StepKind,
+    /// What to do when the step fails (default: abort).
+    #[serde(default, skip_serializing_if = "OnFailure::is_abort")]
+    pub on_failure: OnFailure,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StepKind {
+    Http(HttpAction),
+    Notify(NotifyAction),
+    Node(NodeAction),
+    Command(CommandAction),
+    /// Sleep before the next step.
+    Wait {
+        secs: u32,
+    },
+    /// Fail unless a node has the given status and health.
+    Check(CheckStep),
+    /// Put a message on a Zenoh topic.
+    Publish(PublishStep),
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum OnFailure {
+    /// Skip the remaining steps.
+    #[default]
+    Abort,
+    /// Go on with the next step.
+    Continue,
+}
+
+impl OnFailure {
+    fn is_abort(&self) -> bool {
+        *self == Self::Abort
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct CheckStep {
+    pub node: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<CheckStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<CheckHealth>,
+}
+
+/// Node status a `check` step expects; names match `bubbaloop node list`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Stopped,
+    Running,
+    Failed,
+    Installing,
+    Building,
+    NotInstalled,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckHealth {
+    Healthy,
+    Unhealthy,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PublishStep {
+    /// Topic under `bubbaloop/`, without wildcards.
+    pub topic: String,
+    /// Message to put; defaults to the firing payload as JSON.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct NodeAction {
+    /// Node name as shown by `bubbaloop node list`.
+    pub node: String,
+    pub command: NodeActionCommand,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeActionCommand {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl NodeActionCommand {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Stop => "stop",
+            Self::Restart => "restart",
+        }
+    }
+}
+
+impl std::str::FromStr for NodeActionCommand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "start" => Ok(Self::Start),
+            "stop" => Ok(Self::Stop),
+            "restart" => Ok(Self::Restart),
+            _ => Err(format!(
+                "unknown node command '{}' (expected start, stop or restart)",
+                s
+            )),
+        }
+    }
+}
+
+impl From<NodeActionCommand> for NodeCommand {
+    fn from(command: NodeActionCommand) -> Self {
+        match command {
+            NodeActionCommand::Start => NodeCommand::Start,
+            NodeActionCommand::Stop => NodeCommand::Stop,
+            NodeActionCommand::Restart => NodeCommand::Restart,
+        }
+    }
+}
+
+impl NodeAction {
+    pub fn new(node: impl Into<String>, command: NodeActionCommand) -> Self {
+        Self {
+            node: node.into(),
+            command,
+        }
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if let Err(e) = crate::validation::validate_node_name(&self.node) {
+            anyhow::bail!("node action: {}", e);
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct CommandAction {
+    /// Node name as shown by `bubbaloop node list`.
+    pub node: String,
+    /// A command the node lists in its manifest, e.g. "save_clip".
+    pub command: String,
+    /// Command parameters; defaults to the firing payload.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<serde_json::Value>,
+}
+
+impl CommandAction {
+    pub fn new(node: impl Into<String>, command: impl Into<String>) -> Self {
+        Self {
+            node: node.into(),
+            command: command.into(),
+            params: None,
+        }
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if let Err(e) = crate::validation::validate_node_name(&self.node) {
+            anyhow::bail!("command action: {}", e);
+        }
+        if self.command.is_empty()
+            || self.command.len() > 64
+            || !self
+                .command
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            anyhow::bail!(
+                "command action: command {:?} must be 1-64 characters of [a-zA-Z0-9_-]",
+                self.command
+            );
+        }
+        if let Some(params) = &self.params {
+            let len = params.to_string().len();
+            if len > MAX_COMMAND_PARAMS_LEN {
+                anyhow::bail!(
+                    "command action params exceed maximum length ({} > {})",
+                    len,
+                    MAX_COMMAND_PARAMS_LEN
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct HttpAction {
+    /// http:// or https:// URL to POST to.
+    pub url: String,
+    /// Extra request headers.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// Bearer token, or `env:NAME` to read it from the agent's environment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Seconds before one attempt gives up (default: 10).
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u32,
+    /// Further attempts after a failed one (default: 2).
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct NotifyAction {
+    /// Channel name in `~/.bubbaloop/notifications.yaml`.
+    pub channel: String,
+    /// Message template; `{description}`, `{rule}`, `{mission_id}`,
+    /// `{predicate}`, `{schedule}`, `{fired_at}` and `{fields.<name>}` are
+    /// replaced. Defaults to the rule description.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Title template (default: "bubbaloop: {mission_id}").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl NotifyAction {
+    pub fn new(channel: impl Into<String>) -> Self {
+        Self {
+            channel: channel.into(),
+            message: None,
+            title: None,
+        }
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        if let Err(e) = notifications::validate_channel_name(&self.channel) {
+            anyhow::bail!("notify action: {}", e);
+        }
+        for template in [&self.message, &self.title].into_iter().flatten() {
+            if template.len() > notifications::MAX_TEMPLATE_LEN {
+                anyhow::bail!(
+                    "notify action template exceeds maximum length ({} > {})",
+                    template.len(),
+                    notifications::MAX_TEMPLATE_LEN
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+fn default_timeout_secs() -> u32 {
+    DEFAULT_HTTP_TIMEOUT_SECS
+}
+
+fn default_retries() -> u32 {
+    DEFAULT_HTTP_RETRIES
+}
+
+impl HttpAction {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            headers: BTreeMap::new(),
+            secret: None,
+            timeout_secs: DEFAULT_HTTP_TIMEOUT_SECS,
+            retries: DEFAULT_HTTP_RETRIES,
+        }
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        use anyhow::bail;
+
+        let url = reqwest::Url::parse(&self.url)
+            .map_err(|e| anyhow::anyhow!("http action url {:?} is invalid: {}", self.url, e))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            bail!(
+                "http action url must be http:// or https:// (got {:?})",
+                self.url
+            );
+        }
+        for (name, value) in &self.headers {
+            if reqwest::header::HeaderName::from_bytes(name.as_bytes()).is_err() {
+                bail!("http action header name {:?} is invalid", name);
+            }
+            if reqwest::header::HeaderValue::from_str(value).is_err() {
+                bail!("http action header {:?} has an invalid value", name);
+            }
+        }
+        if matches!(&self.secret, Some(s) if s.trim().is_empty() || s.trim() == "env:") {
+            bail!("http action secret must be non-empty");
+        }
+        if self.timeout_secs == 0 || self.timeout_secs > MAX_HTTP_TIMEOUT_SECS {
+            bail!(
+                "http action timeout_secs must be in [1, {}] (got {})",
+                MAX_HTTP_TIMEOUT_SECS,
+                self.timeout_secs
+            );
+        }
+        if self.retries > MAX_HTTP_RETRIES {
+            bail!(
+                "http action retries must be at most {} (got {})",
+                MAX_HTTP_RETRIES,
+                self.retries
+            );
+        }
+        Ok(())
+    }
+}
+
+impl CheckStep {
+    fn validate(&self) -> anyhow::Result<()> {
+        if let Err(e) = crate::validation::validate_node_name(&self.node) {
+            anyhow::bail!("check step: {}", e);
+        }
+        if self.status.is_none() && self.health.is_none() {
+            anyhow::bail!("check step for {} needs a status or a health", self.node);
+        }
+        Ok(())
+    }
+
+    /// Compare with a node's detail, whose status and health are the
+    /// daemon's names for them ("Running", "NotInstalled", "Healthy").
+    fn matches(&self, detail: &serde_json::Value) -> Result<String, String> {
+        let names = |value: &serde_json::Value| value.as_str().unwrap_or("Unknown").to_string();
+        let status = names(&detail["status"]);
+        let health = names(detail.get("health_status").unwrap_or(&detail["health"]));
+        if let Some(expected) = self.status {
+            if format!("{:?}", expected) != status {
+                return Err(format!("{} is {}", self.node, status));
+            }
+        }
+        if let Some(expected) = self.health {
+            if format!("{:?}", expected) != health {
+                return Err(format!("{} is {}", self.node, health));
+            }
+        }
+        Ok(format!("{} is {} and {}", self.node, status, health))
+    }
+}
+
+impl WorkflowAction {
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.steps.is_empty() || self.steps.len() > MAX_WORKFLOW_STEPS {
+            anyhow::bail!(
+                "a workflow must have 1 to {} steps (got {})",
+                MAX_WORKFLOW_STEPS,
+                self.steps.len()
+            );
+        }
+        let mut waited: u32 = 0;
+        for step in &self.steps {
+            match &step.kind {
+                StepKind::Http(http) => http.validate()?,
+                StepKind::Notify(notify) => notify.validate()?,
+                StepKind::Node(node) => node.validate()?,
+                StepKind::Command(command) => command.validate()?,
+                StepKind::Wait { secs } => {
+                    if *secs == 0 {
+                        anyhow::bail!("wait step must wait at least 1 second");
+                    }
+                    waited = waited.saturating_add(*secs);
+                }
+                StepKind::Check(check) => check.validate()?,
+                StepKind::Publish(publish) => {
+                    if let Err(e) = crate::validation::validate_publish_topic(&publish.topic) {
+                        anyhow::bail!("publish step: {}", e);
+                    }
+                    if publish
+                        .message
+                        .as_ref()
+                        .is_some_and(|m| m.len() > notifications::MAX_TEMPLATE_LEN)
+                    {
+                        anyhow::bail!(
+                            "publish step message exceeds maximum length ({})",
+                            notifications::MAX_TEMPLATE_LEN
+                        );
+                    }
+                }
+            }
+        }
+        if waited > MAX_WORKFLOW_WAIT_SECS {
+            anyhow::bail!(
+                "workflow waits {}s in total, more than {}s",
+                waited,
+                MAX_WORKFLOW_WAIT_SECS
+            );
+        }
+        Ok(())
+    }
+}
+
+impl StepKind {
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Http(http) => format!("POST {}", http.url),
+            Self::Notify(notify) => format!("notify {}", notify.channel),
+            Self::Node(node) => format!("{} {}", node.command.as_str(), node.node),
+            Self::Command(command) => format!("command {} {}", command.node, command.command),
+            Self::Wait { secs } => format!("wait {}s", secs),
+            Self::Check(check) => {
+                let expected: Vec<String> = [
+                    check.status.map(|s| serde_name(&s)),
+                    check.health.map(|h| serde_name(&h)),
+                ]
+                .into_iter()
+                .flatten()
+                .collect();
+                format!("check {} {}", check.node, expected.join(" "))
+            }
+            Self::Publish(publish) => format!("publish {}", publish.topic),
+        }
+    }
+}
+
+/// The name a unit enum variant has in rule JSON, e.g. "not_installed".
+fn serde_name<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+impl RuleAction {
+    /// A short description for logs and `agent rules list`.
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Http(http) => format!("POST {}", http.url),
+            Self::Notify(notify) => format!("notify {}", notify.channel),
+            Self::Node(node) => format!("{} {}", node.command.as_str(), node.node),
+            Self::Command(command) => format!("command {} {}", command.node, command.command),
+            Self::Workflow(workflow) => {
+                let steps: Vec<String> = workflow.steps.iter().map(|s| s.kind.summary()).collect();
+                format!("workflow: {}", steps.join(" -> "))
+            }
+        }
+    }
+
+    /// A copy safe to show: literal secrets are replaced, `env:` references
+    /// are kept since they name a variable rather than hold the token.
+    pub fn redacted(&self) -> Self {
+        match self {
+            Self::Http(http) => Self::Http(http.redacted()),
+            Self::Workflow(workflow) => {
+                let mut workflow = workflow.clone();
+                for step in &mut workflow.steps {
+                    if let StepKind::Http(http) = &step.kind {
+                        step.kind = StepKind::Http(http.redacted());
+                    }
+                }
+                Self::Workflow(workflow)
+            }
+            Self::Notify(_) | Self::Node(_) | Self::Command(_) => self.clone(),
+        }
+    }
+
+    /// Nodes the action starts, stops or restarts.
+    pub fn nodes(&self) -> Vec<&str> {
+        match self {
+            Self::Node(node) => vec![node.node.as_str()],
+            Self::Workflow(workflow) => workflow
+                .steps
+                .iter()
+                .filter_map(|s| match &s.kind {
+                    StepKind::Node(node) => Some(node.node.as_str()),
+                    _ => None,
+                })
+                .collect(),
+            Self::Http(_) | Self::Notify(_) | Self::Command(_) => Vec::new(),
+        }
+    }
+}
+
+impl HttpAction {
+    fn redacted(&self) -> Self {
+        let mut http = self.clone();
+        if let Some(secret) = &http.secret {
+            if !secret.starts_with("env:") {
+                http.secret = Some(REDACTED.to_string());
+            }
+        }
+        http
+    }
+}
+
+/// Validate a rule's action list.
+pub fn validate_actions(actions: &[RuleAction]) -> anyhow::Result<()> {
+    if actions.len() > MAX_ACTIONS {
+        anyhow::bail!(
+            "a rule may have at most {} actions (got {})",
+            MAX_ACTIONS,
+            actions.len()
+        );
+    }
+    for action in actions {
+        match action {
+            RuleAction::Http(http) => http.validate()?,
+            RuleAction::Notify(notify) => notify.validate()?,
+            RuleAction::Node(node) => node.validate()?,
+            RuleAction::Command(command) => command.validate()?,
+            RuleAction::Workflow(workflow) => workflow.validate()?,
+        }
+    }
+    Ok(())
+}
+
+/// Actions a rule runs when its predicate still holds `after_secs` after
+/// it fired.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct EscalationStep {
+    /// Seconds after the firing; later steps must come later.
+    pub after_secs: u32,
+    pub actions: Vec<RuleAction>,
+}
+
+impl EscalationStep {
+    /// A copy safe to show, see [`RuleAction::redacted`].
+    pub fn redacted(&self) -> Self {
+        Self {
+            after_secs: self.after_secs,
+            actions: self.actions.iter().map(RuleAction::redacted).collect(),
+        }
+    }
+}
+
+/// Validate a rule's escalation steps.
+pub fn validate_escalation(steps: &[EscalationStep]) -> anyhow::Result<()> {
+    if steps.len() > MAX_ESCALATION_STEPS {
+        anyhow::bail!(
+            "a rule may have at most {} escalation steps (got {})",
+            MAX_ESCALATION_STEPS,
+            steps.len()
+        );
+    }
+    let mut previous = 0;
+    for step in steps {
+        if step.after_secs <= previous || step.after_secs > MAX_ESCALATION_AFTER_SECS {
+            anyhow::bail!(
+                "escalation after_secs must increase from step to step and be in [1, {}] (got {})",
+                MAX_ESCALATION_AFTER_SECS,
+                step.after_secs
+            );
+        }
+        if step.actions.is_empty() {
+            anyhow::bail!("escalation step after {}s has no actions", step.after_secs);
+        }
+        validate_actions(&step.actions)?;
+        previous = step.after_secs;
+    }
+    Ok(())
+}
+
+/// A node action left out by [`resolve_conflicts`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suppressed {
+    pub rule: String,
+    pub action: NodeAction,
+    /// The rule whose command for the node runs instead.
+    pub winner: String,
+}
+
+/// Settle node commands among rules that fired in the same evaluation.
+///
+/// For each node, only the command of the highest-priority rule runs; on
+/// a tie, the rule with the smaller id wins. Node actions of other rules
+/// that give the node a different command are removed from them and
+/// returned, and the same command from several rules runs only once.
+/// Other actions, and rules in dry-run mode, over their trigger budget or
+/// paused, are left alone.
+pub fn resolve_conflicts(fired: &mut [FiredRule]) -> Vec<Suppressed> {
+    let mut order: Vec<usize> = (0..fired.len())
+        .filter(|&i| !fired[i].dry_run && !fired[i].throttled && fired[i].paused_by.is_none())
+        .collect();
+    order.sort_by(|&a, &b| {
+        fired[b]
+            .priority
+            .cmp(&fired[a].priority)
+            .then_with(|| fired[a].id.cmp(&fired[b].id))
+    });
+    let mut winners: HashMap<String, (NodeActionCommand, String)> = HashMap::new();
+    let mut suppressed = Vec::new();
+    for i in order {
+        let rule = &mut fired[i];
+        rule.actions.retain(|action| {
+            let RuleAction::Node(node) = action else {
+                return true;
+            };
+            match winners.get(&node.node) {
+                None => {
+                    winners.insert(node.node.clone(), (node.command, rule.id.clone()));
+                    true
+                }
+                Some((command, _)) if *command == node.command => false,
+                Some((_, winner)) => {
+                    suppressed.push(Suppressed {
+                        rule: rule.id.clone(),
+                        action: node.clone(),
+                        winner: winner.clone(),
+                    });
+                    false
+                }
+            }
+        });
+    }
+    suppressed
+}
+
+/// What a rule in dry-run mode would have done: logs each of its actions
+/// and reports it as not run.
+pub fn dry_run(rule: &FiredRule) -> Vec<ActionResult> {
+    rule.actions
+        .iter()
+        .map(|action| {
+            log::info!(
+                "[Rules] rule {} (dry run) would have executed action {}",
+                rule.id,
+                action.summary()
+            );
+            ActionResult {
+                action: action.summary(),
+                ok: true,
+                outcome: "dry run, not executed".to_string(),
+            }
+        })
+        .collect()
+}
+
+/// What a rule over its `max_triggers_per_hour` skipped: every action,
+/// reported as not run.
+pub fn over_budget(rule: &FiredRule) -> Vec<ActionResult> {
+    log::warn!(
+        "[Rules] rule {} is over its trigger budget, skipping {} action(s)",
+        rule.id,
+        rule.actions.len()
+    );
+    rule.actions
+        .iter()
+        .map(|action| ActionResult {
+            action: action.summary(),
+            ok: false,
+            outcome: "skipped: rule over its trigger budget".to_string(),
+        })
+        .collect()
+}
+
+/// What a paused rule skipped: every action, reported as not run.
+pub fn paused(rule: &FiredRule) -> Vec<ActionResult> {
+    let by = rule.paused_by.as_deref().unwrap_or_default();
+    log::info!(
+        "[Rules] rule {} is paused by {}, skipping {} action(s)",
+        rule.id,
+        by,
+        rule.actions.len()
+    );
+    rule.actions
+        .iter()
+        .map(|action| ActionResult {
+            action: action.summary(),
+            ok: false,
+            outcome: format!("skipped: paused by {}", by),
+        })
+        .collect()
+}
+
+/// The JSON body an `http` action posts: which rule fired, what triggered
+/// it, and the current value of every world-state field its predicate
+/// reads.
+pub fn payload(rule: &FiredRule, world_state: &HashMap<&str, &str>) -> serde_json::Value {
+    let fields: serde_json::Map<String, serde_json::Value> =
+        super::reactive::extract_predicate_fields(&rule.predicate)
+            .into_iter()
+            .map(|field| {
+                let value = super::reactive::field_value(world_state, &field)
+                    .map(serde_json::Value::String)
+                    .unwrap_or(serde_json::Value::Null);
+                (field, value)
+            })
+            .collect();
+    serde_json::json!({
+        "rule": rule.id,
+        "mission_id": rule.mission_id,
+        "description": rule.description,
+        "predicate": rule.predicate,
+        "schedule": rule.schedule,
+        "fields": fields,
+        "fired_at": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+    })
+}
+
+/// Client for action requests. Each request sets its own timeout; the
+/// short idle timeout keeps webhook connections from lingering.
+pub fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .pool_idle_timeout(crate::agent::provider::HTTP_POOL_IDLE)
+        .build()
+        .expect("reqwest client builder with static config must succeed")
+}
+
+/// Runs rule actions for one agent. Shared by the background tasks the
+/// agent loop spawns, so notification rate limits hold across firings.
+pub struct ActionRunner {
+    client: reqwest::Client,
+    machine_id: String,
+    limiter: std::sync::Mutex<RateLimiter>,
+}
+
+impl Default for ActionRunner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActionRunner {
+    pub fn new() -> Self {
+        Self {
+            client: http_client(),
+            machine_id: super::util::get_machine_id(),
+            limiter: std::sync::Mutex::new(RateLimiter::default()),
+        }
+    }
+
+    /// Run every action of a fired rule, logging and returning the outcome
+    /// of each. Node commands go through `platform`.
+    pub async fn run<P: PlatformOperations>(
+        &self,
+        rule: &FiredRule,
+        payload: &serde_json::Value,
+        platform: &P,
+    ) -> Vec<ActionResult> {
+        let mut results = Vec::with_capacity(rule.actions.len());
+        for action in &rule.actions {
+            let result = match action {
+                RuleAction::Http(http) => self.http(http, payload).await,
+                RuleAction::Notify(notify) => self.notify(notify, payload).await,
+                RuleAction::Node(node) => self.node(node, platform).await,
+                RuleAction::Command(command) => self.command(command, payload, platform).await,
+                RuleAction::Workflow(workflow) => {
+                    self.workflow(
+                        &rule.id,
+                        workflow,
+                        payload,
+                        platform,
+                        Duration::from_secs(1),
+                    )
+                    .await
+                }
+            };
+            match &result {
+                Ok(outcome) => log::info!(
+                    "[Rules] rule {} action {}: {}",
+                    rule.id,
+                    action.summary(),
+                    outcome
+                ),
+                Err(e) => log::warn!(
+                    "[Rules] rule {} action {} failed: {}",
+                    rule.id,
+                    action.summary(),
+                    e
+                ),
+            }
+            results.push(ActionResult {
+                action: action.summary(),
+                ok: result.is_ok(),
+                outcome: result.unwrap_or_else(|e| e),
+            });
+        }
+        results
+    }
+
+    async fn http(&self, http: &HttpAction, payload: &serde_json::Value) -> Result<String, String> {
+        post(&self.client, http, payload, RETRY_BACKOFF)
+            .await
+            .map(|status| format!("HTTP {}", status))
+    }
+
+    async fn node<P: PlatformOperations>(
+        &self,
+        node: &NodeAction,
+        platform: &P,
+    ) -> Result<String, String> {
+        platform
+            .execute_command(&node.node, node.command.into())
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Query the node's `command` queryable on this machine. Fails when
+    /// no node answers.
+    async fn command<P: PlatformOperations>(
+        &self,
+        command: &CommandAction,
+        payload: &serde_json::Value,
+        platform: &P,
+    ) -> Result<String, String> {
+        let key_expr = format!(
+            "bubbaloop/global/{}/{}/command",
+            self.machine_id, command.node
+        );
+        let request = serde_json::json!({
+            "command": command.command,
+            "params": command.params.as_ref().unwrap_or(payload),
+        });
+        let replies = platform
+            .send_zenoh_query(&key_expr, request.to_string().into_bytes())
+            .await
+            .map_err(|e| e.to_string())?;
+        if replies.is_empty() {
+            return Err(format!("no reply from {} (is it running?)", command.node));
+        }
+        Ok(replies.join("; "))
+    }
+
+    /// Run the steps of a workflow in order. `second` is the length of a
+    /// `wait` second, shortened by tests. The outcome lists every step run;
+    /// the workflow fails if any step did.
+    async fn workflow<P: PlatformOperations>(
+        &self,
+        rule_id: &str,
+        workflow: &WorkflowAction,
+        payload: &serde_json::Value,
+        platform: &P,
+        second: Duration,
+    ) -> Result<String, String> {
+        let mut outcomes = Vec::with_capacity(workflow.steps.len());
+        let mut failed = false;
+        for (i, step) in workflow.steps.iter().enumerate() {
+            let result = match &step.kind {
+                StepKind::Http(http) => self.http(http, payload).await,
+                StepKind::Notify(notify) => self.notify(notify, payload).await,
+                StepKind::Node(node) => self.node(node, platform).await,
+                StepKind::Command(command) => self.command(command, payload, platform).await,
+                StepKind::Wait { secs } => {
+                    tokio::time::sleep(second * *secs).await;
+                    Ok("done".to_string())
+                }
+                StepKind::Check(check) => match platform.get_node_detail(&check.node).await {
+                    Ok(detail) => check.matches(&detail),
+                    Err(e) => Err(e.to_string()),
+                },
+                StepKind::Publish(publish) => {
+                    let message = publish
+                        .message
+                        .clone()
+                        .unwrap_or_else(|| payload.to_string());
+                    platform
+                        .publish_to_topic(&publish.topic, &message)
+                        .await
+                        .map(|()| "published".to_string())
+                        .map_err(|e| e.to_string())
+                }
+            };
+            let summary = step.kind.summary();
+            match result {
+                Ok(outcome) => {
+                    log::debug!(
+                        "[Rules] rule {} workflow step {} ({}): {}",
+                        rule_id,
+                        i + 1,
+                        summary,
+                        outcome
+                    );
+                    outcomes.push(format!("{}. {}: {}", i + 1, summary, outcome));
+                }
+                Err(e) => {
+                    failed = true;
+                    outcomes.push(format!("{}. {} failed: {}", i + 1, summary, e));
+                    if step.on_failure == OnFailure::Abort {
+                        if i + 1 < workflow.steps.len() {
+                            outcomes.push(format!(
+                                "aborted, {} step(s) skipped",
+                                workflow.steps.len() - i - 1
+                            ));
+                        }
+                        break;
+                    }
+                }
+            }
+        }
+        let outcome = outcomes.join("; ");
+        if failed {
+            Err(outcome)
+        } else {
+            Ok(outcome)
+        }
+    }
+
+    async fn notify(
+        &self,
+        notify: &NotifyAction,
+        payload: &serde_json::Value,
+    ) -> Result<String, String> {
+        // Read on every use, so edits apply without restarting the agent.
+        let config = notifications::load().map_err(|e| e.to_string())?;
+        let channel = config
+            .channels
+            .get(&notify.channel)
+            .ok_or_else(|| format!("no channel '{}' in notifications.yaml", notify.channel))?;
+        let allowed = self
+            .limiter
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .allow(
+                &notify.channel,
+                channel.max_per_hour(),
+                std::time::Instant::now(),
+            );
+        if !allowed {
+            return Ok(format!(
+                "skipped, channel over {} messages per hour",
+                channel.max_per_hour()
+            ));
+        }
+        let message = notifications::Message::render(notify, payload);
+        notifications::send(&self.client, channel, &message, RETRY_BACKOFF).await?;
+        Ok("sent".to_string())
+    }
+}
+
+/// Resolve `env:NAME` to the variable's value; anything else is literal.
+pub(crate) fn resolve_secret(secret: &str) -> Result<String, String> {
+    match secret.strip_prefix("env:") {
+        Some(name) => {
+            std::env::var(name).map_err(|_| format!("secret variable {} is not set", name))
+        }
+        None => Ok(secret.to_string()),
+    }
+}
+
+/// Send a request built by `build`, retrying on connection errors,
+/// timeouts, 429 and 5xx with doubling `backoff`. Returns the final
+/// success status.
+pub(crate) async fn send_with_retries(
+    build: impl Fn() -> reqwest::RequestBuilder,
+    retries: u32,
+    backoff: Duration,
+) -> Result<u16, String> {
+    let mut delay = backoff;
+    let mut attempt = 0;
+    loop {
+        let (error, retryable) = match build().send().await {
+            Ok(response) if response.status().is_success() => {
+                return Ok(response.status().as_u16());
+            }
+            Ok(response) => {
+                let status = response.status();
+                (
+                    format!("HTTP {}", status.as_u16()),
+                    status.is_server_error() || status.as_u16() == 429,
+                )
+            }
+            Err(e) => (e.to_string(), true),
+        };
+        if !retryable || attempt >= retries {
+            return Err(format!("{} after {} attempt(s)", error, attempt + 1));
+        }
+        attempt += 1;
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+    }
+}
+
+/// POST the payload as JSON.
+async fn post(
+    client: &reqwest::Client,
+    http: &HttpAction,
+    payload: &serde_json::Value,
+    backoff: Duration,
+) -> Result<u16, String> {
+    let secret = http.secret.as_deref().map(resolve_secret).transpose()?;
+    let build = || {
+        let mut request = client
+            .post(&http.url)
+            .timeout(Duration::from_secs(http.timeout_secs as u64))
+            .json(payload);
+        for (name, value) in &http.headers {
+            request = request.header(name, value);
+        }
+        if let Some(secret) = &secret {
+            request = request.bearer_auth(secret);
+        }
+        request
+    };
+    send_with_retries(build, http.retries, backoff).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    fn fired(predicate: &str) -> FiredRule {
+        FiredRule {
+            id: "alert-1".to_string(),
+            mission_id: "rack".to_string(),
+            predicate: predicate.to_string(),
+            schedule: None,
+            description: "Rack is hot".to_string(),
+            priority: 0,
+            dry_run: false,
+            throttled: false,
+            escalation: 0,
+            paused_by: None,
+            boost: 2.0,
+            actions: Vec::new(),
+        }
+    }
+
+    fn fired_with(id: &str, priority: i32, actions: Vec<RuleAction>) -> FiredRule {
+        FiredRule {
+            id: id.to_string(),
+            priority,
+            actions,
+            ..fired("x = 1")
+        }
+    }
+
+    fn node(name: &str, command: NodeActionCommand) -> RuleAction {
+        RuleAction::Node(NodeAction::new(name, command))
+    }
+
+    #[test]
+    fn actions_deserialize_with_defaults() {
+        let actions: Vec<RuleAction> =
+            serde_json::from_str(r#"[{"type": "http", "url": "https://example.com/hook"}]"#)
+                .unwrap();
+        assert_eq!(
+            actions,
+            vec![RuleAction::Http(HttpAction::new(
+                "https://example.com/hook"
+            ))]
+        );
+        assert!(validate_actions(&actions).is_ok());
+
+        let actions: Vec<RuleAction> =
+            serde_json::from_str(r#"[{"type": "notify", "channel": "phone"}]"#).unwrap();
+        assert_eq!(
+            actions,
+            vec![RuleAction::Notify(NotifyAction::new("phone"))]
+        );
+        assert!(validate_actions(&actions).is_ok());
+        assert!(validate_actions(&[RuleAction::Notify(NotifyAction::new("my phone"))]).is_err());
+
+        let actions: Vec<RuleAction> =
+            serde_json::from_str(r#"[{"type": "node", "node": "camera", "command": "restart"}]"#)
+                .unwrap();
+        assert_eq!(actions, vec![node("camera", NodeActionCommand::Restart)]);
+        assert_eq!(actions[0].summary(), "restart camera");
+        assert!(validate_actions(&actions).is_ok());
+        assert!(validate_actions(&[node("../camera", NodeActionCommand::Stop)]).is_err());
+    }
+
+    #[test]
+    fn highest_priority_command_wins_per_node() {
+        use NodeActionCommand::*;
+        let mut fired = vec![
+            fired_with(
+                "cool-down",
+                1,
+                vec![node("fan", Start), node("heater", Stop)],
+            ),
+            fired_with(
+                "night-mode",
+                5,
+                vec![
+                    node("fan", Stop),
+                    RuleAction::Notify(NotifyAction::new("phone")),
+                ],
+            ),
+            fired_with("also-cool", 1, vec![node("heater", Stop)]),
+            fired_with("tie", 5, vec![node("heater", Start)]),
+        ];
+        let suppressed = resolve_conflicts(&mut fired);
+
+        let kept: Vec<Vec<String>> = fired
+            .iter()
+            .map(|r| r.actions.iter().map(RuleAction::summary).collect())
+            .collect();
+        assert_eq!(
+            kept,
+            vec![
+                Vec::<String>::new(),
+                vec!["stop fan".to_string(), "notify phone".to_string()],
+                Vec::new(),
+                vec!["start heater".to_string()],
+            ]
+        );
+        let lost: Vec<_> = suppressed
+            .iter()
+            .map(|s| (s.rule.as_str(), s.action.node.as_str(), s.winner.as_str()))
+            .collect();
+        assert_eq!(
+            lost,
+            [
+                ("also-cool", "heater", "tie"),
+                ("cool-down", "fan", "night-mode"),
+                ("cool-down", "heater", "tie"),
+            ]
+        );
+    }
+
+    #[test]
+    fn dry_run_rules_neither_claim_nor_lose_nodes() {
+        use NodeActionCommand::*;
+        let mut fired = vec![
+            FiredRule {
+                dry_run: true,
+                ..fired_with("trial", 10, vec![node("fan", Stop)])
+            },
+            fired_with("live", 0, vec![node("fan", Start)]),
+        ];
+        assert!(resolve_conflicts(&mut fired).is_empty());
+        assert_eq!(fired[0].actions.len(), 1);
+        assert_eq!(fired[1].actions.len(), 1);
+
+        let results = dry_run(&fired[0]);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].action, "stop fan");
+        assert!(results[0].outcome.contains("dry run"));
+    }
+
+    #[test]
+    fn paused_rules_neither_claim_nor_run_nodes() {
+        use NodeActionCommand::*;
+        let mut fired = vec![
+            FiredRule {
+                paused_by: Some("alice (maintenance)".to_string()),
+                ..fired_with("paused", 10, vec![node("fan", Stop)])
+            },
+            fired_with("live", 0, vec![node("fan", Start)]),
+        ];
+        assert!(resolve_conflicts(&mut fired).is_empty());
+        assert_eq!(fired[1].actions.len(), 1);
+
+        let results = paused(&fired[0]);
+        assert_eq!(results.len(), 1);
+        assert!(!results[0].ok);
+        assert_eq!(results[0].outcome, "skipped: paused by alice (maintenance)");
+    }
+
+    #[test]
+    fn validate_rejects_bad_http_actions() {
+        let check = |f: fn(&mut HttpAction)| {
+            let mut http = HttpAction::new("https://example.com/hook");
+            f(&mut http);
+            validate_actions(&[RuleAction::Http(http)])
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(check(|h| h.url = "ftp://example.com".into()).contains("http://"));
+        assert!(check(|h| h.url = "not a url".into()).contains("invalid"));
+        assert!(check(|h| {
+            h.headers.insert("bad header".into(), "x".into());
+        })
+        .contains("header"));
+        assert!(check(|h| h.secret = Some("env:".into())).contains("secret"));
+        assert!(check(|h| h.timeout_secs = 0).contains("timeout_secs"));
+        assert!(check(|h| h.retries = MAX_HTTP_RETRIES + 1).contains("retries"));
+
+        let many = vec![RuleAction::Http(HttpAction::new("http://a")); MAX_ACTIONS + 1];
+        assert!(validate_actions(&many).is_err());
+    }
+
+    #[test]
+    fn redacted_hides_literal_secrets_only() {
+        let mut http = HttpAction::new("https://example.com/hook");
+        http.secret = Some("s3cret".into());
+        let RuleAction::Http(shown) = RuleAction::Http(http.clone()).redacted() else {
+            panic!("expected an http action");
+        };
+        assert_eq!(shown.secret.as_deref(), Some(REDACTED));
+
+        http.secret = Some("env:HOOK_TOKEN".into());
+        let RuleAction::Http(shown) = RuleAction::Http(http).redacted() else {
+            panic!("expected an http action");
+        };
+        assert_eq!(shown.secret.as_deref(), Some("env:HOOK_TOKEN"));
+    }
+
+    #[test]
+    fn payload_carries_rule_and_predicate_fields() {
+        let ws: HashMap<&str, &str> = [("cpu_temp", "91"), ("other", "x")].into_iter().collect();
+        let body = payload(&fired("cpu_temp > 80 OR fan.ok = false"), &ws);
+        assert_eq!(body["rule"], "alert-1");
+        assert_eq!(body["mission_id"], "rack");
+        assert_eq!(body["fields"]["cpu_temp"], "91");
+        assert!(body["fields"]["fan.ok"].is_null());
+        assert!(body["fields"].get("other").is_none());
+        assert!(body["fired_at"].is_string());
+    }
+
+    /// Serve `statuses` in turn, counting requests and recording the
+    /// Authorization header of the last one.
+    async fn serve(statuses: Vec<u16>) -> (String, Arc<AtomicU32>, Arc<std::sync::Mutex<String>>) {
+        use axum::http::{HeaderMap, StatusCode};
+        let hits = Arc::new(AtomicU32::new(0));
+        let auth = Arc::new(std::sync::Mutex::new(String::new()));
+        let (h, a) = (hits.clone(), auth.clone());
+        let app = axum::Router::new().route(
+            "/hook",
+            axum::routing::post(move |headers: HeaderMap, _body: String| {
+                let n = h.fetch_add(1, Ordering::SeqCst) as usize;
+                *a.lock().unwrap() = headers
+                    .get("authorization")
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string();
+                let status = statuses[n.min(statuses.len() - 1)];
+                async move { StatusCode::from_u16(status).unwrap() }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (format!("http://{}/hook", addr), hits, auth)
+    }
+
+    #[tokio::test]
+    async fn post_retries_server_errors() {
+        let (url, hits, auth) = serve(vec![500, 503, 200]).await;
+        let mut http = HttpAction::new(url);
+        http.secret = Some("s3cret".into());
+        let client = http_client();
+        let body = serde_json::json!({});
+
+        let status = post(&client, &http, &body, Duration::from_millis(1)).await;
+        assert_eq!(status, Ok(200));
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+        assert_eq!(auth.lock().unwrap().as_str(), "Bearer s3cret");
+    }
+
+    #[tokio::test]
+    async fn post_gives_up_on_client_errors_and_after_retries() {
+        let (url, hits, _) = serve(vec![404]).await;
+        let client = http_client();
+        let body = serde_json::json!({});
+        let err = post(
+            &client,
+            &HttpAction::new(url),
+            &body,
+            Duration::from_millis(1),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("404"), "{err}");
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let (url, hits, _) = serve(vec![502]).await;
+        let mut http = HttpAction::new(url);
+        http.retries = 1;
+        assert!(post(&client, &http, &body, Duration::from_millis(1))
+            .await
+            .is_err());
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    fn workflow(steps: &str) -> WorkflowAction {
+        serde_json::from_str(&format!(r#"{{"steps": {}}}"#, steps)).unwrap()
+    }
+
+    #[test]
+    fn workflows_deserialize_and_validate() {
+        let action: RuleAction = serde_json::from_str(
+            r#"{"type": "workflow", "steps": [
+                {"type": "node", "node": "camera", "command": "restart"},
+                {"type": "wait", "secs": 30},
+                {"type": "check", "node": "camera", "status": "not_installed", "on_failure": "continue"},
+                {"type": "publish", "topic": "bubbaloop/local/rack/recovered"}]}"#,
+        )
+        .unwrap();
+        let RuleAction::Workflow(ref flow) = action else {
+            panic!("expected a workflow");
+        };
+        assert_eq!(flow.steps[0].on_failure, OnFailure::Abort);
+        assert_eq!(flow.steps[2].on_failure, OnFailure::Continue);
+        assert_eq!(
+            action.summary(),
+            "workflow: restart camera -> wait 30s -> check camera not_installed -> \
+             publish bubbaloop/local/rack/recovered"
+        );
+        assert!(validate_actions(&[action]).is_ok());
+
+        let invalid = |steps: &str| {
+            validate_actions(&[RuleAction::Workflow(workflow(steps))])
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(invalid("[]").contains("steps"));
+        assert!(invalid(r#"[{"type": "wait", "secs": 0}]"#).contains("1 second"));
+        assert!(
+            invalid(r#"[{"type": "wait", "secs": 3000}, {"type": "wait", "secs": 3000}]"#)
+                .contains("in total")
+        );
+        assert!(invalid(r#"[{"type": "check", "node": "camera"}]"#).contains("status"));
+        assert!(invalid(r#"[{"type": "publish", "topic": "other/topic"}]"#).contains("bubbaloop/"));
+        assert!(invalid(r#"[{"type": "http", "url": "ftp://example.com"}]"#).contains("http://"));
+    }
+
+    #[test]
+    fn workflow_secrets_are_redacted() {
+        let flow = workflow(r#"[{"type": "http", "url": "http://a", "secret": "s3cret"}]"#);
+        let RuleAction::Workflow(flow) = RuleAction::Workflow(flow).redacted() else {
+            panic!("expected a workflow");
+        };
+        let StepKind::Http(http) = &flow.steps[0].kind else {
+            panic!("expected an http step");
+        };
+        assert_eq!(http.secret.as_deref(), Some(REDACTED));
+    }
+
+    #[tokio::test]
+    async fn workflows_run_steps_in_order_and_abort_on_failure() {
+        let platform = crate::mcp::mock_platform::MockPlatform::new();
+        let runner = ActionRunner::new();
+        let body = serde_json::json!({"rule": "alert-1"});
+        let run = |steps: &'static str| {
+            let flow = workflow(steps);
+            let runner = &runner;
+            let platform = &platform;
+            let body = &body;
+            async move {
+                runner
+                    .workflow("alert-1", &flow, body, platform, Duration::from_millis(1))
+                    .await
+            }
+        };
+
+        let outcome = run(r#"[
+            {"type": "node", "node": "test-node", "command": "restart"},
+            {"type": "wait", "secs": 2},
+            {"type": "check", "node": "test-node", "status": "running", "health": "healthy"},
+            {"type": "publish", "topic": "bubbaloop/local/rack/recovered"}]"#)
+        .await
+        .unwrap();
+        assert!(
+            outcome.starts_with("1. restart test-node: mock"),
+            "{outcome}"
+        );
+        assert!(outcome.contains("4. publish bubbaloop/local/rack/recovered: published"));
+
+        let outcome = run(r#"[
+            {"type": "check", "node": "test-node", "status": "stopped", "on_failure": "continue"},
+            {"type": "node", "node": "missing", "command": "start"},
+            {"type": "wait", "secs": 1}]"#)
+        .await
+        .unwrap_err();
+        assert!(outcome.contains("1. check test-node stopped failed: test-node is Running"));
+        assert!(outcome.contains("2. start missing failed"), "{outcome}");
+        assert!(outcome.ends_with("aborted, 1 step(s) skipped"), "{outcome}");
+    }
+
+    #[tokio::test]
+    async fn command_actions_validate_and_query_the_node() {
+        let action: RuleAction = serde_json::from_str(
+            r#"{"type": "command", "node": "entrance", "command": "save_clip",
+                "params": {"pre_secs": 10, "post_secs": 20}}"#,
+        )
+        .unwrap();
+        assert_eq!(action.summary(), "command entrance save_clip");
+        assert!(action.nodes().is_empty());
+        assert!(validate_actions(std::slice::from_ref(&action)).is_ok());
+
+        let invalid = |command: CommandAction| {
+            validate_actions(&[RuleAction::Command(command)])
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(invalid(CommandAction::new("entrance", "")).contains("command"));
+        assert!(invalid(CommandAction::new("entrance", "save clip")).contains("[a-zA-Z0-9_-]"));
+        assert!(invalid(CommandAction::new("../etc", "save_clip")).contains("command action"));
+        let mut big = CommandAction::new("entrance", "save_clip");
+        big.params = Some(serde_json::json!("x".repeat(MAX_COMMAND_PARAMS_LEN)));
+        assert!(invalid(big).contains("maximum length"));
+
+        let platform = crate::mcp::mock_platform::MockPlatform::new();
+        let runner = ActionRunner::new();
+        let RuleAction::Command(command) = &action else {
+            panic!("expected a command action");
+        };
+        let outcome = runner
+            .command(command, &serde_json::json!({}), &platform)
+            .await
+            .unwrap();
+        assert!(outcome.ends_with("/entrance/command"), "{outcome}");
+    }
+}
@@ -0,0 +1,442 @@
+//! Persistent history of reactive rule firings.
+//!
+//! Every time a rule fires, the agent loop appends an event to the
+//! `rule_events` table of the agent's `alerts.db`: when it fired, the
+//! payload its actions received (trigger, predicate field values) and,
+//! once the actions have run, the outcome of each. The history is
+//! append-only and survives agent restarts; `agent rules events` and the
+//! `get_events` MCP tool read it back with rule and time-range filters.
+//! Firings over a rule's `max_triggers_per_hour` are recorded as
+//! throttled, and `agent rules status` sums them up per rule.
+//!
+//! Escalation steps are recorded like firings, with their level. While a
+//! rule escalates, the `rule_escalations` table holds how far it got, so
+//! `agent rules status` can show it and a restarted agent picks it up.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+/// Events returned when a query does not set a limit.
+pub const DEFAULT_EVENT_LIMIT: u32 = 100;
+
+/// Most events one query returns.
+pub const MAX_EVENT_LIMIT: u32 = 10_000;
+
+/// Outcome of one action of a fired rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ActionResult {
+    /// Short description of the action, e.g. "POST https://..." or "restart camera".
+    pub action: String,
+    pub ok: bool,
+    /// "HTTP 200", "sent", the error, or why the action did not run.
+    pub outcome: String,
+}
+
+/// One firing of a rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct RuleEvent {
+    pub id: i64,
+    pub rule_id: String,
+    pub mission_id: String,
+    /// Epoch seconds.
+    pub fired_at: i64,
+    /// The JSON body actions received, with the predicate field values at
+    /// the time the rule fired.
+    pub payload: serde_json::Value,
+    /// Empty until the actions have run, and for rules without actions.
+    #[serde(default)]
+    pub results: Vec<ActionResult>,
+    /// The rule was over its trigger budget, so its actions did not run.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub throttled: bool,
+    /// The escalation step (from 1) this event ran; 0 for a firing.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub escalation: u32,
+}
+
+fn is_zero(level: &u32) -> bool {
+    *level == 0
+}
+
+/// A rule whose predicate still holds after it fired, from
+/// [`RuleEventStore::escalations`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleEscalation {
+    pub rule_id: String,
+    /// When the firing that started it happened (epoch seconds).
+    pub since: i64,
+    /// Escalation steps run so far.
+    pub level: u32,
+    /// When the latest step ran (epoch seconds).
+    pub escalated_at: Option<i64>,
+}
+
+/// Firings of one rule since some time, from [`RuleEventStore::usage`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RuleUsage {
+    /// Firings that counted against the trigger budget.
+    pub triggers: u32,
+    /// Firings over the budget.
+    pub throttled: u32,
+    /// Latest throttled firing (epoch seconds).
+    pub last_throttled_at: Option<i64>,
+}
+
+/// Filters for [`RuleEventStore::query`]; every field is optional.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct RuleEventQuery {
+    /// Only events of this rule.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule_id: Option<String>,
+    /// Only events at or after this time (epoch seconds).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<i64>,
+    /// Only events at or before this time (epoch seconds).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<i64>,
+    /// Most events to return, newest first (default: 100).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+/// SQLite-backed rule event history, in the same file as the rules.
+pub struct RuleEventStore {
+    conn: Connection,
+}
+
+impl RuleEventStore {
+    /// Open (or create) the history in the agent's `alerts.db`.
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let conn = crate::daemon::util::open_sqlite(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS rule_events (
+                id         INTEGER PRIMARY KEY AUTOINCREMENT,
+                rule_id    TEXT NOT NULL,
+                mission_id TEXT NOT NULL,
+                fired_at   INTEGER NOT NULL,
+                payload    TEXT NOT NULL,
+                results    TEXT NOT NULL DEFAULT '[]',
+                throttled  INTEGER NOT NULL DEFAULT 0,
+                escalation INTEGER NOT NULL DEFAULT 0
+            );
+            CREATE TABLE IF NOT EXISTS rule_escalations (
+                rule_id      TEXT PRIMARY KEY,
+                since        INTEGER NOT NULL,
+                level        INTEGER NOT NULL DEFAULT 0,
+                escalated_at INTEGER
+            );
+            CREATE INDEX IF NOT EXISTS idx_rule_events_fired_at ON rule_events(fired_at);
+            CREATE INDEX IF NOT EXISTS idx_rule_events_rule ON rule_events(rule_id, fired_at);",
+        )?;
+        // Histories written before rules had a trigger budget.
+        let has_throttled: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('rule_events') WHERE name = 'throttled'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_throttled {
+            conn.execute_batch(
+                "ALTER TABLE rule_events ADD COLUMN throttled INTEGER NOT NULL DEFAULT 0;",
+            )?;
+        }
+        // ... and before rules could escalate.
+        let has_escalation: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('rule_events') WHERE name = 'escalation'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_escalation {
+            conn.execute_batch(
+                "ALTER TABLE rule_events ADD COLUMN escalation INTEGER NOT NULL DEFAULT 0;",
+            )?;
+        }
+        Ok(Self { conn })
+    }
+
+    /// Append a firing and return its event ID.
+    pub fn record(
+        &self,
+        rule_id: &str,
+        mission_id: &str,
+        fired_at: i64,
+        payload: &serde_json::Value,
+        results: &[ActionResult],
+        throttled: bool,
+    ) -> anyhow::Result<i64> {
+        self.conn.execute(
+            "INSERT INTO rule_events (rule_id, mission_id, fired_at, payload, results, throttled) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                rule_id,
+                mission_id,
+                fired_at,
+                payload.to_string(),
+                serde_json::to_string(results)?,
+                throttled,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Append the run of escalation step `level` and return its event ID.
+    pub fn record_escalation(
+        &self,
+        rule_id: &str,
+        mission_id: &str,
+        fired_at: i64,
+        payload: &serde_json::Value,
+        results: &[ActionResult],
+        level: u32,
+    ) -> anyhow::Result<i64> {
+        self.conn.execute(
+            "INSERT INTO rule_events (rule_id, mission_id, fired_at, payload, results, escalation) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                rule_id,
+                mission_id,
+                fired_at,
+                payload.to_string(),
+                serde_json::to_string(results)?,
+                level,
+            ],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Store the action outcomes of an event once they are known.
+    pub fn set_results(&self, id: i64, results: &[ActionResult]) -> anyhow::Result<()> {
+        self.conn.execute(
+            "UPDATE rule_events SET results = ?1 WHERE id = ?2",
+            params![serde_json::to_string(results)?, id],
+        )?;
+        Ok(())
+    }
+
+    /// Events matching `query`, newest first.
+    pub fn query(&self, query: &RuleEventQuery) -> anyhow::Result<Vec<RuleEvent>> {
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_EVENT_LIMIT)
+            .min(MAX_EVENT_LIMIT);
+        let mut stmt = self.conn.prepare(
+            "SELECT id, rule_id, mission_id, fired_at, payload, results, throttled, escalation \
+             FROM rule_events \
+             WHERE (?1 IS NULL OR rule_id = ?1) \
+               AND (?2 IS NULL OR fired_at >= ?2) \
+               AND (?3 IS NULL OR fired_at <= ?3) \
+             ORDER BY fired_at DESC, id DESC LIMIT ?4",
+        )?;
+        let rows = stmt.query_map(
+            params![query.rule_id, query.since, query.until, limit],
+            |row| {
+                let payload: String = row.get(4)?;
+                let results: String = row.get(5)?;
+                Ok(RuleEvent {
+                    id: row.get(0)?,
+                    rule_id: row.get(1)?,
+                    mission_id: row.get(2)?,
+                    fired_at: row.get(3)?,
+                    payload: serde_json::from_str(&payload).unwrap_or(serde_json::Value::Null),
+                    results: serde_json::from_str(&results).unwrap_or_default(),
+                    throttled: row.get(6)?,
+                    escalation: row.get(7)?,
+                })
+            },
+        )?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(Into::into)
+    }
+
+    /// Firings and throttled firings of each rule at or after `since`;
+    /// escalation steps are not firings.
+    pub fn usage(&self, since: i64) -> anyhow::Result<HashMap<String, RuleUsage>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT rule_id, SUM(throttled = 0), SUM(throttled), \
+                    MAX(CASE WHEN throttled THEN fired_at END) \
+             FROM rule_events WHERE fired_at >= ?1 AND escalation = 0 GROUP BY rule_id",
+        )?;
+        let rows = stmt.query_map(params![since], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                RuleUsage {
+                    triggers: row.get(1)?,
+                    throttled: row.get(2)?,
+                    last_throttled_at: row.get(3)?,
+                },
+            ))
+        })?;
+        rows.collect::<Result<HashMap<_, _>, _>>()
+            .map_err(Into::into)
+    }
+
+    /// Record how far a rule escalated: `level` steps since `since`, the
+    /// latest at `escalated_at`.
+    pub fn set_escalation(
+        &self,
+        rule_id: &str,
+        since: i64,
+        level: u32,
+        escalated_at: Option<i64>,
+    ) -> anyhow::Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO rule_escalations (rule_id, since, level, escalated_at) \
+             VALUES (?1, ?2, ?3, ?4)",
+            params![rule_id, since, level, escalated_at],
+        )?;
+        Ok(())
+    }
+
+    /// Forget a rule's escalation once its condition cleared.
+    pub fn clear_escalation(&self, rule_id: &str) -> anyhow::Result<()> {
+        self.conn.execute(
+            "DELETE FROM rule_escalations WHERE rule_id = ?1",
+            params![rule_id],
+        )?;
+        Ok(())
+    }
+
+    /// Rules escalating right now, by rule ID.
+    pub fn escalations(&self) -> anyhow::Result<HashMap<String, RuleEscalation>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT rule_id, since, level, escalated_at FROM rule_escalations")?;
+        let rows = stmt.query_map([], |row| {
+            let escalation = RuleEscalation {
+                rule_id: row.get(0)?,
+                since: row.get(1)?,
+                level: row.get(2)?,
+                escalated_at: row.get(3)?,
+            };
+            Ok((escalation.rule_id.clone(), escalation))
+        })?;
+        rows.collect::<Result<HashMap<_, _>, _>>()
+            .map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(action: &str, ok: bool) -> ActionResult {
+        ActionResult {
+            action: action.to_string(),
+            ok,
+            outcome: if ok { "sent" } else { "HTTP 500" }.to_string(),
+        }
+    }
+
+    #[test]
+    fn records_and_queries_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("alerts.db");
+        let store = RuleEventStore::open(&path).unwrap();
+        let payload = serde_json::json!({"rule": "hot", "fields": {"cpu_temp": "91"}});
+        for (rule, at) in [("hot", 100), ("cold", 200), ("hot", 300)] {
+            store
+                .record(rule, "rack", at, &payload, &[], rule == "cold")
+                .unwrap();
+        }
+        let id = store
+            .record(
+                "hot",
+                "rack",
+                400,
+                &payload,
+                &[result("stop fan", false)],
+                false,
+            )
+            .unwrap();
+        store
+            .set_results(
+                id,
+                &[result("stop fan", false), result("notify phone", true)],
+            )
+            .unwrap();
+
+        // The history lives on disk, next to the rules.
+        drop(store);
+        let store = RuleEventStore::open(&path).unwrap();
+
+        let all = store.query(&RuleEventQuery::default()).unwrap();
+        let times: Vec<_> = all.iter().map(|e| e.fired_at).collect();
+        assert_eq!(times, [400, 300, 200, 100]);
+        assert_eq!(all[0].results.len(), 2);
+        assert!(all[0].results[1].ok);
+        assert_eq!(all[0].payload["fields"]["cpu_temp"], "91");
+
+        let hot = store
+            .query(&RuleEventQuery {
+                rule_id: Some("hot".to_string()),
+                since: Some(200),
+                until: Some(350),
+                limit: None,
+            })
+            .unwrap();
+        assert_eq!(hot.len(), 1);
+        assert_eq!(hot[0].fired_at, 300);
+
+        let latest = store
+            .query(&RuleEventQuery {
+                limit: Some(2),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(latest.len(), 2);
+
+        assert!(all[2].throttled);
+        let usage = store.usage(150).unwrap();
+        assert_eq!(
+            usage["hot"],
+            RuleUsage {
+                triggers: 2,
+                throttled: 0,
+                last_throttled_at: None
+            }
+        );
+        assert_eq!(usage["cold"].throttled, 1);
+        assert_eq!(usage["cold"].last_throttled_at, Some(200));
+    }
+
+    #[test]
+    fn records_escalations() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = RuleEventStore::open(&dir.path().join("alerts.db")).unwrap();
+        let payload = serde_json::json!({"rule": "hot"});
+        store
+            .record("hot", "rack", 100, &payload, &[], false)
+            .unwrap();
+        store
+            .record_escalation(
+                "hot",
+                "rack",
+                700,
+                &payload,
+                &[result("notify phone", true)],
+                1,
+            )
+            .unwrap();
+
+        let events = store.query(&RuleEventQuery::default()).unwrap();
+        assert_eq!(events[0].escalation, 1);
+        assert_eq!(events[1].escalation, 0);
+        // Escalation steps do not count against the trigger budget.
+        assert_eq!(store.usage(0).unwrap()["hot"].triggers, 1);
+
+        store.set_escalation("hot", 100, 0, None).unwrap();
+        store.set_escalation("hot", 100, 1, Some(700)).unwrap();
+        assert_eq!(
+            store.escalations().unwrap()["hot"],
+            RuleEscalation {
+                rule_id: "hot".to_string(),
+                since: 100,
+                level: 1,
+                escalated_at: Some(700),
+            }
+        );
+        store.clear_escalation("hot").unwrap();
+        assert!(store.escalations().unwrap().is_empty());
+    }
+}
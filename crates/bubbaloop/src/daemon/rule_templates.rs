@@ -0,0 +1,573 @@
+//! Rule templates and rule packs.
+//!
+//! A rule pack is a YAML file of parameterized rule templates that can be
+//! shared like a node and installed from the marketplace registry:
+//!
+//! ```yaml
+//! name: rack-safety
+//! version: "0.1.0"
+//! description: Keep a server rack from overheating
+//! templates:
+//!   - name: high-temperature-shutdown
+//!     description: Stop a node while a temperature stays too high
+//!     params:
+//!       node: { description: node to stop }
+//!       threshold: { description: temperature that stops the node, default: 85 }
+//!       field: { description: world-state key with the temperature, default: cpu_temp }
+//!     rule:
+//!       predicate: "${field} > ${threshold}"
+//!       hold_secs: 30
+//!       actions:
+//!         - { type: node, node: "${node}", command: stop }
+//! ```
+//!
+//! `rule` takes the fields of the `register_alert` MCP tool except
+//! `mission_id`, which is given when a template is instantiated. Every
+//! `${param}` in its strings is replaced by the parameter's value; a
+//! string that is exactly one placeholder takes the value's type, so
+//! `hold_secs: "${hold}"` becomes a number. Parameter values cannot contain
+//! whitespace, quotes or parentheses, so they cannot change the shape of a
+//! predicate.
+//!
+//! Each agent has the built-in `core` pack and the packs installed in its
+//! `rule-packs/` directory (`~/.bubbaloop/agents/{id}/rule-packs/`).
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::mcp::platform::RegisterAlertParams;
+
+/// Name of the pack built into bubbaloop.
+pub const BUILTIN_PACK: &str = "core";
+
+/// Longest parameter value a template accepts.
+pub const MAX_PARAM_LEN: usize = 256;
+
+/// Largest pack file accepted.
+pub const MAX_PACK_BYTES: usize = 256 * 1024;
+
+const CORE_PACK: &str = r#"
+name: core
+version: "0.1.0"
+description: Rule templates that ship with bubbaloop
+templates:
+  - name: high-temperature-shutdown
+    description: Stop a node while a temperature stays above a threshold
+    params:
+      node: { description: node to stop }
+      threshold: { description: temperature that stops the node, default: 85 }
+      clear: { description: temperature below which the rule re-arms, default: 75 }
+      field: { description: world-state key with the temperature, default: cpu_temp }
+      hold: { description: seconds the temperature must stay high, default: 30 }
+    rule:
+      predicate: "${field} > ${threshold}"
+      clear_predicate: "${field} < ${clear}"
+      hold_secs: "${hold}"
+      priority: 50
+      description: "Stop ${node} while ${field} is above ${threshold}"
+      actions:
+        - { type: node, node: "${node}", command: stop }
+  - name: threshold-notify
+    description: Send a notification when a value crosses a threshold
+    params:
+      field: { description: world-state key to watch }
+      op: { description: "comparison: >, <, >=, <=, = or !=", default: ">" }
+      threshold: { description: value to compare with }
+      channel: { description: channel from notifications.yaml }
+      debounce: { description: minimum seconds between two messages, default: 600 }
+    rule:
+      predicate: "${field} ${op} ${threshold}"
+      debounce_secs: "${debounce}"
+      description: "${field} ${op} ${threshold}"
+      actions:
+        - type: notify
+          channel: "${channel}"
+          message: "${field} is {fields.${field}} (${op} ${threshold})"
+"#;
+
+#[derive(Debug, Error)]
+pub enum RuleTemplateError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid rule pack: {0}")]
+    InvalidPack(String),
+    #[error("no rule template '{0}' (see `bubbaloop agent rules templates`)")]
+    NotFound(String),
+    #[error("template '{0}' is in several packs; use pack/template")]
+    Ambiguous(String),
+    #[error("{0}")]
+    InvalidParams(String),
+}
+
+pub type Result<T> = std::result::Result<T, RuleTemplateError>;
+
+/// A pack of rule templates.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RulePack {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    pub templates: Vec<RuleTemplate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub params: BTreeMap<String, TemplateParam>,
+    /// `register_alert` fields with `${param}` placeholders.
+    pub rule: serde_json::Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateParam {
+    #[serde(default)]
+    pub description: String,
+    /// Value used when none is given; the parameter is required without one.
+    #[serde(default)]
+    pub default: Option<serde_json::Value>,
+}
+
+/// A template as listed by `agent rules templates` and `list_rule_templates`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TemplateInfo {
+    pub pack: String,
+    pub name: String,
+    pub description: String,
+    pub params: Vec<ParamInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ParamInfo {
+    pub name: String,
+    pub description: String,
+    /// `None` when the parameter is required.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+impl RulePack {
+    /// Parse and check a pack: names are well-formed and unique, and every
+    /// placeholder of a template names one of its parameters.
+    pub fn parse(yaml: &str) -> Result<Self> {
+        if yaml.len() > MAX_PACK_BYTES {
+            return Err(RuleTemplateError::InvalidPack(format!(
+                "larger than {} bytes",
+                MAX_PACK_BYTES
+            )));
+        }
+        let pack: RulePack = serde_yaml::from_str(yaml)
+            .map_err(|e| RuleTemplateError::InvalidPack(e.to_string()))?;
+        validate_name("pack", &pack.name)?;
+        let mut names = std::collections::HashSet::new();
+        for template in &pack.templates {
+            validate_name("template", &template.name)?;
+            if !names.insert(template.name.as_str()) {
+                return Err(RuleTemplateError::InvalidPack(format!(
+                    "template '{}' is defined twice",
+                    template.name
+                )));
+            }
+            template.check().map_err(|e| {
+                RuleTemplateError::InvalidPack(format!("template '{}': {}", template.name, e))
+            })?;
+        }
+        Ok(pack)
+    }
+}
+
+impl RuleTemplate {
+    fn check(&self) -> std::result::Result<(), String> {
+        let Some(rule) = self.rule.as_object() else {
+            return Err("rule must be a mapping of register_alert fields".to_string());
+        };
+        if rule.contains_key("mission_id") {
+            return Err("rule cannot set mission_id; it is given on instantiation".to_string());
+        }
+        for (name, param) in &self.params {
+            validate_name("parameter", name).map_err(|e| e.to_string())?;
+            if let Some(default) = &param.default {
+                param_text(default)
+                    .ok_or_else(|| format!("default of '{}' must be a scalar", name))?;
+            }
+        }
+        let mut placeholders = Vec::new();
+        collect_placeholders(&self.rule, &mut placeholders)?;
+        match placeholders
+            .iter()
+            .find(|p| !self.params.contains_key(p.as_str()))
+        {
+            Some(unknown) => Err(format!("${{{}}} is not a parameter", unknown)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn info(&self, pack: &str) -> TemplateInfo {
+        TemplateInfo {
+            pack: pack.to_string(),
+            name: self.name.clone(),
+            description: self.description.clone(),
+            params: self
+                .params
+                .iter()
+                .map(|(name, param)| ParamInfo {
+                    name: name.clone(),
+                    description: param.description.clone(),
+                    default: param.default.as_ref().and_then(param_text),
+                })
+                .collect(),
+        }
+    }
+
+    /// Fill in the template for `mission_id`. Parameters missing from
+    /// `values` take their default; unknown ones are an error. The result
+    /// still goes through rule validation when it is registered.
+    pub fn instantiate(
+        &self,
+        mission_id: &str,
+        values: &HashMap<String, String>,
+    ) -> Result<RegisterAlertParams> {
+        let invalid = RuleTemplateError::InvalidParams;
+        if let Some(unknown) = values
+            .keys()
+            .find(|k| !self.params.contains_key(k.as_str()))
+        {
+            return Err(invalid(format!(
+                "template '{}' has no parameter '{}'",
+                self.name, unknown
+            )));
+        }
+        let mut resolved = HashMap::new();
+        for (name, param) in &self.params {
+            let value = match values.get(name) {
+                Some(value) => value.clone(),
+                None => param
+                    .default
+                    .as_ref()
+                    .and_then(param_text)
+                    .ok_or_else(|| invalid(format!("parameter '{}' is required", name)))?,
+            };
+            validate_value(name, &value).map_err(invalid)?;
+            resolved.insert(name.as_str(), value);
+        }
+        let mut rule = substitute(&self.rule, &resolved).map_err(invalid)?;
+        let fields = rule
+            .as_object_mut()
+            .expect("checked when the pack was parsed");
+        fields.insert("mission_id".to_string(), mission_id.into());
+        if !fields.contains_key("description") {
+            fields.insert("description".to_string(), self.description.clone().into());
+        }
+        serde_json::from_value(rule)
+            .map_err(|e| invalid(format!("template '{}': {}", self.name, e)))
+    }
+}
+
+/// `[A-Za-z0-9_-]{1,64}`
+fn validate_name(kind: &str, name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(RuleTemplateError::InvalidPack(format!(
+            "{} name '{}' must be 1-64 letters, digits, '-' or '_'",
+            kind, name
+        )))
+    }
+}
+
+fn validate_value(name: &str, value: &str) -> std::result::Result<(), String> {
+    if value.is_empty() || value.len() > MAX_PARAM_LEN {
+        return Err(format!(
+            "parameter '{}' must be 1-{} characters",
+            name, MAX_PARAM_LEN
+        ));
+    }
+    if value
+        .chars()
+        .any(|c| c.is_whitespace() || matches!(c, '(' | ')' | '"' | '\'' | '$'))
+    {
+        return Err(format!(
+            "parameter '{}' cannot contain whitespace, quotes, parentheses or '$' (got {:?})",
+            name, value
+        ));
+    }
+    Ok(())
+}
+
+/// A scalar default as text.
+fn param_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Every `${name}` in the strings of `value`.
+fn collect_placeholders(
+    value: &serde_json::Value,
+    out: &mut Vec<String>,
+) -> std::result::Result<(), String> {
+    match value {
+        serde_json::Value::String(s) => {
+            let mut rest = s.as_str();
+            while let Some(start) = rest.find("${") {
+                let Some(len) = rest[start + 2..].find('}') else {
+                    return Err(format!("unclosed placeholder in {:?}", s));
+                };
+                out.push(rest[start + 2..start + 2 + len].to_string());
+                rest = &rest[start + 3 + len..];
+            }
+            Ok(())
+        }
+        serde_json::Value::Array(items) => {
+            items.iter().try_for_each(|v| collect_placeholders(v, out))
+        }
+        serde_json::Value::Object(fields) => fields
+            .values()
+            .try_for_each(|v| collect_placeholders(v, out)),
+        _ => Ok(()),
+    }
+}
+
+fn substitute(
+    value: &serde_json::Value,
+    values: &HashMap<&str, String>,
+) -> std::result::Result<serde_json::Value, String> {
+    Ok(match value {
+        serde_json::Value::String(s) => {
+            // A lone placeholder takes the value's type, so numeric
+            // fields can be parameters.
+            if let Some(name) = s.strip_prefix("${").and_then(|s| s.strip_suffix('}')) {
+                if let Some(value) = values.get(name) {
+                    return Ok(serde_yaml::from_str::<serde_json::Value>(value)
+                        .ok()
+                        .filter(|v| v.is_number() || v.is_boolean())
+                        .unwrap_or_else(|| value.clone().into()));
+                }
+            }
+            let mut text = s.clone();
+            for (name, value) in values {
+                text = text.replace(&format!("${{{}}}", name), value);
+            }
+            serde_json::Value::String(text)
+        }
+        serde_json::Value::Array(items) => serde_json::Value::Array(
+            items
+                .iter()
+                .map(|v| substitute(v, values))
+                .collect::<std::result::Result<_, _>>()?,
+        ),
+        serde_json::Value::Object(fields) => serde_json::Value::Object(
+            fields
+                .iter()
+                .map(|(k, v)| Ok((k.clone(), substitute(v, values)?)))
+                .collect::<std::result::Result<_, String>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+/// The pack built into bubbaloop.
+pub fn builtin_pack() -> RulePack {
+    RulePack::parse(CORE_PACK).expect("built-in rule pack must parse")
+}
+
+/// Where an agent's rule packs are installed.
+pub fn packs_dir(agent_dir: &Path) -> PathBuf {
+    agent_dir.join("rule-packs")
+}
+
+/// The built-in pack and every pack installed in `dir`. Files that do not
+/// parse are logged and skipped.
+pub fn load_packs(dir: &Path) -> Vec<RulePack> {
+    let mut packs = vec![builtin_pack()];
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return packs;
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "yaml"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        match std::fs::read_to_string(&path)
+            .map_err(RuleTemplateError::from)
+            .and_then(|yaml| RulePack::parse(&yaml))
+        {
+            Ok(pack) => packs.push(pack),
+            Err(e) => log::warn!("[Rules] skipping rule pack {}: {}", path.display(), e),
+        }
+    }
+    packs
+}
+
+/// Every template of `packs`.
+pub fn list_templates(packs: &[RulePack]) -> Vec<TemplateInfo> {
+    packs
+        .iter()
+        .flat_map(|pack| pack.templates.iter().map(|t| t.info(&pack.name)))
+        .collect()
+}
+
+/// Find a template by `name` or `pack/name`.
+pub fn find_template<'a>(packs: &'a [RulePack], name: &str) -> Result<&'a RuleTemplate> {
+    let (pack, template) = match name.split_once('/') {
+        Some((pack, template)) => (Some(pack), template),
+        None => (None, name),
+    };
+    let mut found = packs
+        .iter()
+        .filter(|p| pack.is_none_or(|name| p.name == name))
+        .flat_map(|p| p.templates.iter())
+        .filter(|t| t.name == template);
+    match (found.next(), found.next()) {
+        (Some(t), None) => Ok(t),
+        (Some(_), Some(_)) => Err(RuleTemplateError::Ambiguous(name.to_string())),
+        (None, _) => Err(RuleTemplateError::NotFound(name.to_string())),
+    }
+}
+
+/// Check a pack and save it in `dir` as `<name>.yaml`, replacing an
+/// installed pack of the same name. Returns the pack.
+pub fn install_pack(dir: &Path, yaml: &str) -> Result<RulePack> {
+    let pack = RulePack::parse(yaml)?;
+    if pack.name == BUILTIN_PACK {
+        return Err(RuleTemplateError::InvalidPack(format!(
+            "'{}' is the name of the built-in pack",
+            BUILTIN_PACK
+        )));
+    }
+    std::fs::create_dir_all(dir)?;
+    std::fs::write(dir.join(format!("{}.yaml", pack.name)), yaml)?;
+    Ok(pack)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn builtin_templates_instantiate_into_valid_rules() {
+        let packs = vec![builtin_pack()];
+        let shutdown = find_template(&packs, "high-temperature-shutdown").unwrap();
+        let params = shutdown
+            .instantiate("rack", &values(&[("node", "trainer"), ("threshold", "90")]))
+            .unwrap();
+        assert_eq!(params.mission_id, "rack");
+        assert_eq!(params.predicate, "cpu_temp > 90");
+        assert_eq!(params.clear_predicate.as_deref(), Some("cpu_temp < 75"));
+        assert_eq!(params.hold_secs, Some(30));
+        assert_eq!(
+            params.description,
+            "Stop trainer while cpu_temp is above 90"
+        );
+        let config = params.into_config("alert-1".to_string());
+        config.validate().unwrap();
+        assert_eq!(config.actions[0].summary(), "stop trainer");
+
+        let notify = find_template(&packs, "core/threshold-notify").unwrap();
+        let config = notify
+            .instantiate(
+                "rack",
+                &values(&[
+                    ("field", "fan_rpm"),
+                    ("op", "<"),
+                    ("threshold", "500"),
+                    ("channel", "phone"),
+                ]),
+            )
+            .unwrap()
+            .into_config("alert-2".to_string());
+        config.validate().unwrap();
+        assert_eq!(config.predicate, "fan_rpm < 500");
+        assert_eq!(config.debounce_secs, 600);
+        let crate::daemon::rule_actions::RuleAction::Notify(action) = &config.actions[0] else {
+            panic!("expected a notify action");
+        };
+        assert_eq!(
+            action.message.as_deref(),
+            Some("fan_rpm is {fields.fan_rpm} (< 500)")
+        );
+    }
+
+    #[test]
+    fn instantiate_checks_parameters() {
+        let packs = vec![builtin_pack()];
+        let shutdown = find_template(&packs, "high-temperature-shutdown").unwrap();
+        let err = |pairs: &[(&str, &str)]| {
+            shutdown
+                .instantiate("rack", &values(pairs))
+                .unwrap_err()
+                .to_string()
+        };
+        assert!(err(&[]).contains("'node' is required"));
+        assert!(err(&[("node", "fan"), ("speed", "1")]).contains("no parameter 'speed'"));
+        assert!(err(&[("node", "fan"), ("threshold", "1 OR x")]).contains("whitespace"));
+        assert!(err(&[("node", "fan"), ("hold", "soon")]).contains("invalid type"));
+        assert!(matches!(
+            find_template(&packs, "missing"),
+            Err(RuleTemplateError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn parse_rejects_broken_packs() {
+        let pack = |templates: &str| {
+            RulePack::parse(&format!("name: p\nversion: '1'\ntemplates:\n{}", templates))
+        };
+        assert!(pack("  - name: t\n    rule: { predicate: \"x > ${y}\" }").is_err());
+        assert!(pack("  - name: t\n    rule: { mission_id: m, predicate: x > 1 }").is_err());
+        assert!(pack("  - name: t\n    rule: [1]").is_err());
+        assert!(pack("  - name: bad name\n    rule: { predicate: x > 1 }").is_err());
+        assert!(
+            pack("  - name: t\n    rule: { predicate: x > 1 }\n  - name: t\n    rule: {}").is_err()
+        );
+        assert!(
+            pack("  - name: t\n    params: { y: {} }\n    rule: { predicate: \"x > ${y}\" }")
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn installed_packs_load_next_to_the_builtin_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let yaml = "name: rack\nversion: '1'\ntemplates:\n  - name: high-temperature-shutdown\n    rule: { predicate: x > 1 }\n";
+        install_pack(dir.path(), yaml).unwrap();
+        std::fs::write(dir.path().join("broken.yaml"), "name: [").unwrap();
+        assert!(install_pack(dir.path(), &yaml.replace("rack", "core")).is_err());
+
+        let packs = load_packs(dir.path());
+        assert_eq!(packs.len(), 2);
+        assert!(matches!(
+            find_template(&packs, "high-temperature-shutdown"),
+            Err(RuleTemplateError::Ambiguous(_))
+        ));
+        let template = find_template(&packs, "rack/high-temperature-shutdown").unwrap();
+        let params = template.instantiate("m", &HashMap::new()).unwrap();
+        assert_eq!(params.predicate, "x > 1");
+        assert_eq!(list_templates(&packs).len(), 3);
+    }
+}
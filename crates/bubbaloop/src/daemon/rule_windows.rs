@@ -0,0 +1,354 @@
+//! Sliding-window aggregates in rule predicates.
+//!
+//! Besides the current value of a world-state key, a predicate clause may
+//! compare an aggregate of its recent values:
+//!
+//! ```text
+//! avg(cpu_load, 5m) > 3.0 AND max(cpu_temp, 10m) < 90
+//! count(door.opened, 1h) >= 10
+//! ```
+//!
+//! `avg`, `min` and `max` cover the numeric values a key took within the
+//! window; `count` is the number of times the key was written, whatever its
+//! value. The agent loop keeps a bounded ring buffer of samples for each key
+//! its rules aggregate, recording a sample whenever the key's `last_seen_at`
+//! moves on, and adds the aggregates to the world state a rule is evaluated
+//! against under the clause's own text. An aggregate with no numeric sample
+//! in its window is absent, so its clause does not match.
+
+use std::collections::{HashMap, VecDeque};
+
+use super::context_provider::parse_filter;
+use super::reactive::ReactiveRule;
+
+/// Longest window a clause may ask for.
+pub const MAX_WINDOW_SECS: i64 = 86_400;
+
+/// Samples kept per key; the oldest are dropped first.
+pub const MAX_WINDOW_SAMPLES: usize = 4_096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregate {
+    Avg,
+    Min,
+    Max,
+    Count,
+}
+
+/// An aggregate clause field such as `avg(cpu_load, 5m)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowRef<'a> {
+    /// The field as written in the predicate, used as its world-state key.
+    pub field: &'a str,
+    pub aggregate: Aggregate,
+    /// The world-state key being aggregated.
+    pub key: &'a str,
+    pub window_secs: i64,
+}
+
+/// Parse a clause field as an aggregate. `None` when the field is a plain
+/// world-state key, an error when it is a malformed aggregate.
+pub fn parse_window(field: &str) -> Option<Result<WindowRef<'_>, String>> {
+    let (name, args) = field.strip_suffix(')')?.split_once('(')?;
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    Some(parse_call(field, name, args))
+}
+
+fn parse_call<'a>(field: &'a str, name: &str, args: &'a str) -> Result<WindowRef<'a>, String> {
+    let aggregate = match name {
+        "avg" => Aggregate::Avg,
+        "min" => Aggregate::Min,
+        "max" => Aggregate::Max,
+        "count" => Aggregate::Count,
+        _ => {
+            return Err(format!(
+                "unknown aggregate '{}' in {:?} (expected avg, min, max or count)",
+                name, field
+            ))
+        }
+    };
+    let Some((key, window)) = args.split_once(',') else {
+        return Err(format!(
+            "{:?} needs a key and a window, e.g. {}(cpu_load, 5m)",
+            field, name
+        ));
+    };
+    let key = key.trim();
+    if key.is_empty() {
+        return Err(format!("{:?} has no key", field));
+    }
+    let window_secs = parse_window_secs(window.trim()).ok_or_else(|| {
+        format!(
+            "{:?} has an invalid window (expected e.g. 90s, 5m, 1h)",
+            field
+        )
+    })?;
+    if window_secs > MAX_WINDOW_SECS {
+        return Err(format!(
+            "{:?} exceeds the longest window ({}s)",
+            field, MAX_WINDOW_SECS
+        ));
+    }
+    Ok(WindowRef {
+        field,
+        aggregate,
+        key,
+        window_secs,
+    })
+}
+
+/// `90`, `90s`, `5m`, `1h` to seconds.
+fn parse_window_secs(s: &str) -> Option<i64> {
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(at) => s.split_at(at),
+        None => (s, "s"),
+    };
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        _ => return None,
+    };
+    match number.parse::<i64>() {
+        Ok(n) if n > 0 => n.checked_mul(multiplier),
+        _ => None,
+    }
+}
+
+/// The world-state key a clause field reads: the aggregated key for an
+/// aggregate, the field itself otherwise.
+pub fn source_key(field: &str) -> &str {
+    match parse_window(field) {
+        Some(Ok(window)) => window.key,
+        _ => field,
+    }
+}
+
+/// Every well-formed aggregate in a predicate.
+pub fn windows_in(predicate: &str) -> Vec<WindowRef<'_>> {
+    let Ok(expr) = parse_filter(predicate) else {
+        return Vec::new();
+    };
+    expr.clauses()
+        .into_iter()
+        .filter_map(|(field, _, _)| parse_window(field)?.ok())
+        .collect()
+}
+
+fn rule_windows(rule: &ReactiveRule) -> impl Iterator<Item = WindowRef<'_>> {
+    windows_in(&rule.predicate).into_iter().chain(
+        rule.clear_predicate
+            .as_deref()
+            .map(windows_in)
+            .unwrap_or_default(),
+    )
+}
+
+#[derive(Debug, Default)]
+struct Samples {
+    /// Longest window any rule aggregates this key over.
+    span_secs: i64,
+    last_seen_at: i64,
+    /// `(last_seen_at, numeric value)`, oldest first.
+    samples: VecDeque<(i64, Option<f64>)>,
+}
+
+/// Recent samples of the world-state keys the agent's rules aggregate.
+#[derive(Debug, Default)]
+pub struct SampleWindows {
+    keys: HashMap<String, Samples>,
+}
+
+impl SampleWindows {
+    /// Track exactly the keys `rules` aggregate, each for the longest
+    /// window asked for; keys no rule uses any more are dropped.
+    pub fn track(&mut self, rules: &[ReactiveRule]) {
+        let mut spans: HashMap<&str, i64> = HashMap::new();
+        for window in rules.iter().flat_map(rule_windows) {
+            let span = spans.entry(window.key).or_default();
+            *span = (*span).max(window.window_secs);
+        }
+        self.keys.retain(|key, _| spans.contains_key(key.as_str()));
+        for (key, span) in spans {
+            self.keys.entry(key.to_string()).or_default().span_secs = span;
+        }
+    }
+
+    /// Record `(key, value, last_seen_at)` world-state entries. An entry is
+    /// a new sample only when its `last_seen_at` moved on since the last
+    /// one recorded for the key.
+    pub fn record<'a>(&mut self, entries: impl IntoIterator<Item = (&'a str, &'a str, i64)>) {
+        for (key, value, seen_at) in entries {
+            let Some(samples) = self.keys.get_mut(key) else {
+                continue;
+            };
+            if seen_at <= samples.last_seen_at {
+                continue;
+            }
+            samples.last_seen_at = seen_at;
+            if samples.samples.len() == MAX_WINDOW_SAMPLES {
+                samples.samples.pop_front();
+            }
+            samples
+                .samples
+                .push_back((seen_at, value.trim().parse::<f64>().ok()));
+        }
+    }
+
+    /// Drop samples older than any window still needs.
+    fn prune(&mut self, now: i64) {
+        for samples in self.keys.values_mut() {
+            while samples
+                .samples
+                .front()
+                .is_some_and(|(t, _)| now - t > samples.span_secs)
+            {
+                samples.samples.pop_front();
+            }
+        }
+    }
+
+    /// The current value of an aggregate, if it has one.
+    pub fn value(&self, window: &WindowRef<'_>, now: i64) -> Option<f64> {
+        let samples = self.keys.get(window.key)?;
+        let in_window = samples
+            .samples
+            .iter()
+            .filter(|(t, _)| now - t <= window.window_secs);
+        if window.aggregate == Aggregate::Count {
+            return Some(in_window.count() as f64);
+        }
+        let values: Vec<f64> = in_window.filter_map(|(_, v)| *v).collect();
+        if values.is_empty() {
+            return None;
+        }
+        Some(match window.aggregate {
+            Aggregate::Avg => values.iter().sum::<f64>() / values.len() as f64,
+            Aggregate::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            Aggregate::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Aggregate::Count => unreachable!(),
+        })
+    }
+
+    /// Every aggregate the rules use that has a value, keyed by its field
+    /// text, ready to be added to the world state they are evaluated against.
+    pub fn values(&mut self, rules: &[ReactiveRule], now: i64) -> Vec<(String, String)> {
+        self.prune(now);
+        let mut values: Vec<(String, String)> = Vec::new();
+        for window in rules.iter().flat_map(rule_windows) {
+            if values.iter().any(|(field, _)| field == window.field) {
+                continue;
+            }
+            if let Some(value) = self.value(&window, now) {
+                values.push((window.field.to_string(), value.to_string()));
+            }
+        }
+        values
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::reactive::ReactiveRuleConfig;
+
+    fn rule(predicate: &str) -> ReactiveRule {
+        ReactiveRuleConfig {
+            id: "r".to_string(),
+            mission_id: "m".to_string(),
+            predicate: predicate.to_string(),
+            debounce_secs: 60,
+            hold_secs: 0,
+            schedule: None,
+            clear_predicate: None,
+            priority: 0,
+            dry_run: false,
+            max_triggers_per_hour: None,
+            escalation: Vec::new(),
+            scope: Default::default(),
+            arousal_boost: 1.0,
+            description: String::new(),
+            actions: Vec::new(),
+        }
+        .into()
+    }
+
+    #[test]
+    fn parses_aggregate_fields() {
+        let window = parse_window("avg(cpu_load, 5m)").unwrap().unwrap();
+        assert_eq!(window.aggregate, Aggregate::Avg);
+        assert_eq!(window.key, "cpu_load");
+        assert_eq!(window.window_secs, 300);
+        assert_eq!(
+            parse_window("count(door.opened,90)")
+                .unwrap()
+                .unwrap()
+                .window_secs,
+            90
+        );
+        assert!(parse_window("cpu_load").is_none());
+        assert!(parse_window("sum(cpu_load, 5m)").unwrap().is_err());
+        assert!(parse_window("avg(cpu_load)").unwrap().is_err());
+        assert!(parse_window("avg(cpu_load, 5 minutes)").unwrap().is_err());
+        assert!(parse_window("avg(cpu_load, 2d)").unwrap().is_err());
+        assert!(parse_window("max(cpu_load, 25h)").unwrap().is_err());
+        assert_eq!(source_key("max(cpu_temp, 1h)"), "cpu_temp");
+        assert_eq!(source_key("cpu_temp"), "cpu_temp");
+    }
+
+    #[test]
+    fn finds_aggregates_in_predicates() {
+        let windows = windows_in("avg(cpu_load, 5m) > 3 AND (x = 1 OR NOT count(door, 1h) > 2)");
+        let fields: Vec<_> = windows.iter().map(|w| w.field).collect();
+        assert_eq!(fields, ["avg(cpu_load, 5m)", "count(door, 1h)"]);
+    }
+
+    #[test]
+    fn aggregates_samples_within_the_window() {
+        let rules = vec![rule(
+            "avg(load, 60s) > 2 AND max(load, 2m) > 0 AND min(load, 60) > 0 AND count(load, 2m) > 0",
+        )];
+        let mut windows = SampleWindows::default();
+        windows.track(&rules);
+        for (at, value) in [
+            (1_000, "9"),
+            (1_070, "1"),
+            (1_100, "2"),
+            (1_100, "5"),
+            (1_110, "oops"),
+        ] {
+            windows.record([("load", value, at), ("other", "1", at)]);
+        }
+
+        let values: HashMap<String, String> = windows.values(&rules, 1_120).into_iter().collect();
+        assert_eq!(values["avg(load, 60s)"], "1.5");
+        assert_eq!(values["min(load, 60)"], "1");
+        assert_eq!(values["max(load, 2m)"], "9");
+        // The duplicate write at 1_100 is not a new sample.
+        assert_eq!(values["count(load, 2m)"], "4");
+        assert!(!windows.keys.contains_key("other"));
+
+        // Once the numeric samples leave the window, only count has a value.
+        let values: HashMap<String, String> = windows.values(&rules, 1_300).into_iter().collect();
+        assert_eq!(values.len(), 1);
+        assert_eq!(values["count(load, 2m)"], "0");
+    }
+
+    #[test]
+    fn ring_buffers_are_bounded() {
+        let rules = vec![rule("count(load, 1h) > 0")];
+        let mut windows = SampleWindows::default();
+        windows.track(&rules);
+        for at in 1..=(MAX_WINDOW_SAMPLES as i64 + 10) {
+            windows.record([("load", "1", at)]);
+        }
+        assert_eq!(windows.keys["load"].samples.len(), MAX_WINDOW_SAMPLES);
+        assert_eq!(windows.keys["load"].samples[0].0, 11);
+
+        windows.track(&[rule("load > 1")]);
+        assert!(windows.keys.is_empty());
+    }
+}
@@ -0,0 +1,341 @@
+//! `~/.bubbaloop/rules.yaml` — reactive rules kept in a file, e.g. under
+//! version control, instead of registered one by one.
+//!
+//! ```yaml
+//! rules:
+//!   - id: rack-hot
+//!     agent: jean-clawd              # optional: the default agent
+//!     mission_id: rack
+//!     predicate: "cpu_temp > 85"
+//!     hold_secs: 30
+//!     description: Rack running hot
+//!     actions:
+//!       - { type: notify, channel: phone }
+//! ```
+//!
+//! Each rule takes the fields of the `register_alert` MCP tool plus its
+//! `id`. The daemon watches the file and applies every edit as a whole:
+//! new rules are added, changed ones updated and rules no longer listed
+//! deleted, without a restart. A file that does not parse or validate
+//! changes nothing. Rules registered another way are never touched, and a
+//! file rule may not reuse one of their IDs.
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::reactive::{FileRuleSync, ReactiveRuleConfig, ReactiveRuleStore};
+use crate::mcp::platform::RegisterAlertParams;
+
+/// Largest rules file accepted.
+pub const MAX_RULES_FILE_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum RulesFileError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{path}: {message}")]
+    Invalid { path: String, message: String },
+    #[error("rule store: {0}")]
+    Store(#[from] anyhow::Error),
+}
+
+pub type Result<T> = std::result::Result<T, RulesFileError>;
+
+/// The parsed `rules.yaml`.
+#[derive(Debug, Default, Deserialize)]
+pub struct RulesFile {
+    #[serde(default)]
+    pub rules: Vec<FileRule>,
+}
+
+/// One rule in `rules.yaml`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FileRule {
+    pub id: String,
+    /// Agent that evaluates the rule; the default agent when unset.
+    #[serde(default)]
+    pub agent: Option<String>,
+    #[serde(flatten)]
+    pub params: RegisterAlertParams,
+}
+
+impl FileRule {
+    /// The rule as stored, with defaults filled in.
+    pub fn config(&self) -> ReactiveRuleConfig {
+        self.params.clone().into_config(self.id.clone())
+    }
+}
+
+/// `~/.bubbaloop/rules.yaml`
+pub fn path() -> PathBuf {
+    crate::daemon::registry::get_bubbaloop_home().join("rules.yaml")
+}
+
+/// Load the rules, or none when the file does not exist.
+pub fn load_from(path: &Path) -> Result<RulesFile> {
+    let invalid = |message: String| RulesFileError::Invalid {
+        path: path.display().to_string(),
+        message,
+    };
+    match std::fs::metadata(path) {
+        Ok(meta) if meta.len() > MAX_RULES_FILE_BYTES => {
+            return Err(invalid(format!(
+                "file is larger than {} bytes",
+                MAX_RULES_FILE_BYTES
+            )))
+        }
+        Ok(_) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(RulesFile::default()),
+        Err(e) => return Err(e.into()),
+    }
+    match std::fs::read_to_string(path) {
+        Ok(text) => parse(&text).map_err(invalid),
+        // Deleted between the two calls.
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(RulesFile::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn parse(text: &str) -> std::result::Result<RulesFile, String> {
+    if text.trim().is_empty() {
+        return Ok(RulesFile::default());
+    }
+    let file: RulesFile = serde_yaml::from_str(text).map_err(|e| e.to_string())?;
+    let mut seen = HashSet::new();
+    for rule in &file.rules {
+        validate_rule_id(&rule.id)?;
+        if !seen.insert(rule.id.as_str()) {
+            return Err(format!("rule '{}' is listed twice", rule.id));
+        }
+        rule.config()
+            .validate()
+            .map_err(|e| format!("rule '{}': {}", rule.id, e))?;
+    }
+    Ok(file)
+}
+
+/// Rule IDs in the file: letters, digits, `-` and `_`.
+fn validate_rule_id(id: &str) -> std::result::Result<(), String> {
+    let valid = !id.is_empty()
+        && id.len() <= 64
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "rule id '{}' must be 1-64 letters, digits, '-' or '_'",
+            id
+        ))
+    }
+}
+
+/// Split the rules by agent. Every agent in `agents` gets an entry, so
+/// agents whose rules were all removed from the file lose them.
+fn assign(
+    file: &RulesFile,
+    agents: &BTreeMap<String, PathBuf>,
+    default_agent: Option<&str>,
+) -> std::result::Result<BTreeMap<String, Vec<ReactiveRuleConfig>>, String> {
+    let mut assigned: BTreeMap<String, Vec<ReactiveRuleConfig>> = agents
+        .keys()
+        .map(|agent| (agent.clone(), Vec::new()))
+        .collect();
+    for rule in &file.rules {
+        let agent = match rule.agent.as_deref().or(default_agent) {
+            Some(agent) => agent,
+            None => {
+                return Err(format!(
+                    "rule '{}' names no agent and there is no default agent",
+                    rule.id
+                ))
+            }
+        };
+        match assigned.get_mut(agent) {
+            Some(rules) => rules.push(rule.config()),
+            None => return Err(format!("rule '{}': no running agent '{}'", rule.id, agent)),
+        }
+    }
+    Ok(assigned)
+}
+
+/// Make each agent's file rules those in `file`. `agents` maps agent IDs
+/// to their rule stores (`alerts.db`). Every store is checked before any
+/// is written, so a rule that clashes with one registered another way
+/// leaves all agents unchanged. Returns the total change and the agents
+/// whose rules changed.
+pub fn apply(
+    file: &RulesFile,
+    agents: &BTreeMap<String, PathBuf>,
+    default_agent: Option<&str>,
+) -> Result<(FileRuleSync, Vec<String>)> {
+    let invalid = |message: String| RulesFileError::Invalid {
+        path: path().display().to_string(),
+        message,
+    };
+    let assigned = assign(file, agents, default_agent).map_err(invalid)?;
+
+    let mut stores = Vec::with_capacity(assigned.len());
+    for (agent, rules) in &assigned {
+        let store = ReactiveRuleStore::open(&agents[agent])?;
+        let file_ids = store.file_rule_ids()?;
+        let other_ids: HashSet<String> = store
+            .list_rules()?
+            .into_iter()
+            .map(|r| r.id)
+            .filter(|id| !file_ids.contains(id))
+            .collect();
+        if let Some(rule) = rules.iter().find(|r| other_ids.contains(&r.id)) {
+            return Err(invalid(format!(
+                "rule '{}' already exists on agent '{}' and was not registered from rules.yaml",
+                rule.id, agent
+            )));
+        }
+        stores.push((agent, store, rules));
+    }
+
+    let mut total = FileRuleSync::default();
+    let mut changed = Vec::new();
+    for (agent, store, rules) in stores {
+        let sync = store.sync_file_rules(rules)?;
+        if sync.changed() {
+            changed.push(agent.clone());
+        }
+        total.added += sync.added;
+        total.updated += sync.updated;
+        total.removed += sync.removed;
+    }
+    Ok((total, changed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = "rules:\n\
+         \x20 - id: rack-hot\n\
+         \x20   mission_id: rack\n\
+         \x20   predicate: cpu_temp > 85\n\
+         \x20   hold_secs: 30\n\
+         \x20   description: Rack running hot\n\
+         \x20 - id: fan-dead\n\
+         \x20   agent: ops\n\
+         \x20   mission_id: rack\n\
+         \x20   predicate: fan_rpm < 100\n\
+         \x20   description: Fan stopped\n";
+
+    fn agents(dir: &Path) -> BTreeMap<String, PathBuf> {
+        ["main", "ops"]
+            .iter()
+            .map(|id| (id.to_string(), dir.join(format!("{}.db", id))))
+            .collect()
+    }
+
+    #[test]
+    fn parses_rules_with_defaults() {
+        let file = parse(RULES).unwrap();
+        assert_eq!(file.rules.len(), 2);
+        let config = file.rules[0].config();
+        assert_eq!(config.id, "rack-hot");
+        assert_eq!(config.hold_secs, 30);
+        assert_eq!(
+            config.debounce_secs,
+            crate::daemon::reactive::DEFAULT_DEBOUNCE_SECS
+        );
+        assert_eq!(file.rules[1].agent.as_deref(), Some("ops"));
+        assert!(parse("").unwrap().rules.is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_files() {
+        let twice = format!(
+            "{}  - {{id: rack-hot, mission_id: rack, predicate: x > 1, description: ''}}\n",
+            RULES
+        );
+        assert!(parse(&twice).unwrap_err().contains("listed twice"));
+        assert!(
+            parse("rules:\n  - {id: 'a b', mission_id: m, predicate: x > 1, description: ''}")
+                .unwrap_err()
+                .contains("letters, digits")
+        );
+        let err = parse("rules:\n  - {id: a, mission_id: m, predicate: '', description: ''}")
+            .unwrap_err();
+        assert!(err.starts_with("rule 'a'"), "{}", err);
+        assert!(parse("rules: [").is_err());
+    }
+
+    #[test]
+    fn missing_file_has_no_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = load_from(&dir.path().join("rules.yaml")).unwrap();
+        assert!(file.rules.is_empty());
+    }
+
+    #[test]
+    fn apply_adds_updates_and_removes_file_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let agents = agents(dir.path());
+
+        let (sync, changed) = apply(&parse(RULES).unwrap(), &agents, Some("main")).unwrap();
+        assert_eq!((sync.added, sync.updated, sync.removed), (2, 0, 0));
+        assert_eq!(changed, vec!["main", "ops"]);
+        let main = ReactiveRuleStore::open(&agents["main"]).unwrap();
+        assert_eq!(main.list_rules().unwrap()[0].id, "rack-hot");
+
+        // Applying the same file again changes nothing and keeps a
+        // disabled rule disabled.
+        main.set_enabled("rack-hot", false).unwrap();
+        let (sync, changed) = apply(&parse(RULES).unwrap(), &agents, Some("main")).unwrap();
+        assert!(!sync.changed());
+        assert!(changed.is_empty());
+
+        let edited = RULES.replace("cpu_temp > 85", "cpu_temp > 90");
+        let (sync, _) = apply(&parse(&edited).unwrap(), &agents, Some("main")).unwrap();
+        assert_eq!((sync.added, sync.updated, sync.removed), (0, 1, 0));
+        assert_eq!(main.list_rules().unwrap()[0].predicate, "cpu_temp > 90");
+        assert!(main.list_enabled_rules().unwrap().is_empty());
+
+        let (sync, changed) = apply(&RulesFile::default(), &agents, Some("main")).unwrap();
+        assert_eq!(sync.removed, 2);
+        assert_eq!(changed, vec!["main", "ops"]);
+        assert!(main.list_rules().unwrap().is_empty());
+    }
+
+    #[test]
+    fn apply_leaves_other_rules_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let agents = agents(dir.path());
+        let ops = ReactiveRuleStore::open(&agents["ops"]).unwrap();
+        let mut manual = parse(RULES).unwrap().rules[1].config();
+        ops.save_rule(&manual).unwrap();
+
+        // `fan-dead` was registered by hand: the file may not take it over,
+        // and nothing is applied, not even to the other agent.
+        let err = apply(&parse(RULES).unwrap(), &agents, Some("main")).unwrap_err();
+        assert!(err.to_string().contains("not registered from rules.yaml"));
+        let main = ReactiveRuleStore::open(&agents["main"]).unwrap();
+        assert!(main.list_rules().unwrap().is_empty());
+
+        // Nor does removing every file rule delete it.
+        manual.id = "other".into();
+        ops.save_rule(&manual).unwrap();
+        ops.delete_rule("fan-dead").unwrap();
+        apply(&RulesFile::default(), &agents, Some("main")).unwrap();
+        assert_eq!(ops.list_rules().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn apply_rejects_unknown_agents() {
+        let dir = tempfile::tempdir().unwrap();
+        let agents = agents(dir.path());
+        let file = parse(&RULES.replace("agent: ops", "agent: nobody")).unwrap();
+        let err = apply(&file, &agents, Some("main")).unwrap_err();
+        assert!(err.to_string().contains("no running agent 'nobody'"));
+        let err = apply(&parse(RULES).unwrap(), &agents, None).unwrap_err();
+        assert!(err.to_string().contains("no default agent"));
+    }
+}
@@ -0,0 +1,490 @@
+//! Fleet-wide schema registry.
+//!
+//! Every node serves the FileDescriptorSet of its messages on
+//! `bubbaloop/global/{machine}/{node}/schema`. The daemon collects them all
+//! every [`COLLECT_INTERVAL`] and keeps one entry per message type, with a
+//! version for each distinct definition it has seen. Two definitions are the
+//! same when their fingerprints match: a hash of the type and of every type
+//! it uses, so a changed `Header` is a new version of every message that
+//! embeds it, while an edited comment is not.
+//!
+//! The registry is served on [`gateway::schemas_topic`], and new types and
+//! definitions are announced on [`gateway::schema_changes_topic`].
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use prost::Message;
+use prost_reflect::{DescriptorPool, EnumDescriptor, FileDescriptor, Kind, MessageDescriptor};
+use tokio::sync::watch;
+use zenoh::Session;
+
+use super::gateway::{self, RegisteredSchema, SchemaChange, SchemaQuery, SchemaVersion};
+use super::util;
+
+/// How often schema queryables are collected.
+pub const COLLECT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long one collection waits for replies.
+const COLLECT_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Definitions kept per type; older ones are dropped first.
+const MAX_VERSIONS: usize = 16;
+
+/// Schema queryables of every node on every machine.
+const SCHEMA_PATTERN: &str = "bubbaloop/global/*/*/schema";
+
+/// Message types by full name, each with its known definitions.
+#[derive(Debug, Default)]
+pub struct SchemaRegistry {
+    types: BTreeMap<String, Vec<SchemaVersion>>,
+}
+
+impl SchemaRegistry {
+    /// Record every message type in `pool` as served by `source`
+    /// (`machine/node`). Returns the types that are new or changed.
+    pub fn observe(
+        &mut self,
+        source: &str,
+        pool: &DescriptorPool,
+        now_ms: i64,
+    ) -> Vec<SchemaChange> {
+        let mut changes = Vec::new();
+        for message in pool.all_messages() {
+            if message.is_map_entry() || message.full_name().starts_with("google.protobuf.") {
+                continue;
+            }
+            let fingerprint = fingerprint(&message);
+            let versions = self
+                .types
+                .entry(message.full_name().to_string())
+                .or_default();
+            if let Some(known) = versions.iter_mut().find(|v| v.fingerprint == fingerprint) {
+                known.last_seen_ms = known.last_seen_ms.max(now_ms);
+                if !known.sources.iter().any(|s| s == source) {
+                    known.sources.push(source.to_string());
+                    known.sources.sort();
+                }
+                continue;
+            }
+
+            let previous_version = versions.last().map(|v| v.version);
+            let version = previous_version.map_or(1, |v| v + 1);
+            versions.push(SchemaVersion {
+                version,
+                fingerprint: fingerprint.clone(),
+                first_seen_ms: now_ms,
+                last_seen_ms: now_ms,
+                sources: vec![source.to_string()],
+                descriptor: file_descriptor_set(&message),
+            });
+            if versions.len() > MAX_VERSIONS {
+                versions.remove(0);
+            }
+            changes.push(SchemaChange {
+                type_name: message.full_name().to_string(),
+                version,
+                previous_version,
+                fingerprint,
+                source: source.to_string(),
+                timestamp_ms: now_ms,
+            });
+        }
+        changes
+    }
+
+    /// Answer a [`SchemaQuery`]. Descriptors are only included for a named type.
+    pub fn query(&self, query: &SchemaQuery) -> Vec<RegisteredSchema> {
+        match &query.type_name {
+            Some(name) => self
+                .types
+                .get(name)
+                .map(|versions| RegisteredSchema {
+                    type_name: name.clone(),
+                    versions: versions.clone(),
+                })
+                .into_iter()
+                .collect(),
+            None => self
+                .types
+                .iter()
+                .map(|(name, versions)| RegisteredSchema {
+                    type_name: name.clone(),
+                    versions: versions
+                        .iter()
+                        .map(|v| SchemaVersion {
+                            descriptor: Vec::new(),
+                            ..v.clone()
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// `machine/node` from `bubbaloop/global/{machine}/{node}/schema`.
+fn source_of(key: &str) -> Option<String> {
+    match key.split('/').collect::<Vec<_>>().as_slice() {
+        ["bubbaloop", _, machine, node, "schema"] => Some(format!("{}/{}", machine, node)),
+        _ => None,
+    }
+}
+
+/// 64-bit FNV-1a over the definitions of `message` and every type it uses,
+/// in name order. Spelled out rather than using `DefaultHasher` so
+/// fingerprints agree across builds and machines.
+fn fingerprint(message: &MessageDescriptor) -> String {
+    let mut definitions = BTreeMap::new();
+    let mut seen = HashSet::new();
+    let mut pending = vec![message.clone()];
+    while let Some(m) = pending.pop() {
+        if !seen.insert(m.full_name().to_string()) {
+            continue;
+        }
+        definitions.insert(
+            m.full_name().to_string(),
+            m.descriptor_proto().encode_to_vec(),
+        );
+        for field in m.fields() {
+            match field.kind() {
+                Kind::Message(used) => pending.push(used),
+                Kind::Enum(used) => {
+                    definitions.insert(used.full_name().to_string(), enum_definition(&used));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for (name, definition) in &definitions {
+        for byte in name.bytes().chain([0]).chain(definition.iter().copied()) {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    format!("{:016x}", hash)
+}
+
+fn enum_definition(e: &EnumDescriptor) -> Vec<u8> {
+    e.enum_descriptor_proto().encode_to_vec()
+}
+
+/// FileDescriptorSet of the file defining `message` and the files it imports.
+fn file_descriptor_set(message: &MessageDescriptor) -> Vec<u8> {
+    fn collect(file: FileDescriptor, seen: &mut BTreeSet<String>, out: &mut Vec<FileDescriptor>) {
+        if !seen.insert(file.name().to_string()) {
+            return;
+        }
+        for dep in file.dependencies() {
+            collect(dep, seen, out);
+        }
+        out.push(file);
+    }
+
+    let mut files = Vec::new();
+    collect(message.parent_file(), &mut BTreeSet::new(), &mut files);
+    prost_types::FileDescriptorSet {
+        file: files
+            .iter()
+            .map(|f| f.file_descriptor_proto().clone())
+            .collect(),
+    }
+    .encode_to_vec()
+}
+
+/// Every node's schema, keyed by `machine/node`.
+async fn collect(session: &Session) -> Vec<(String, DescriptorPool)> {
+    let replies = match session.get(SCHEMA_PATTERN).timeout(COLLECT_TIMEOUT).await {
+        Ok(replies) => replies,
+        Err(e) => {
+            log::warn!("[SchemaRegistry] Query failed: {}", e);
+            return Vec::new();
+        }
+    };
+    let mut pools = Vec::new();
+    while let Ok(reply) = replies.recv_async().await {
+        let Ok(sample) = reply.into_result() else {
+            continue;
+        };
+        let key = sample.key_expr().as_str();
+        let Some(source) = source_of(key) else {
+            continue;
+        };
+        match DescriptorPool::decode(sample.payload().to_bytes().as_ref()) {
+            Ok(pool) => pools.push((source, pool)),
+            Err(e) => log::debug!("[SchemaRegistry] Invalid schema on {}: {}", key, e),
+        }
+    }
+    pools
+}
+
+/// Collect schemas until shutdown, serving the registry and publishing changes.
+pub async fn run(
+    session: Arc<Session>,
+    machine_id: String,
+    mut shutdown_rx: watch::Receiver<()>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let registry = Arc::new(Mutex::new(SchemaRegistry::default()));
+    let schemas_key = gateway::schemas_topic(&machine_id);
+    let changes_key = gateway::schema_changes_topic(&machine_id);
+
+    let publisher = session
+        .declare_publisher(&changes_key)
+        .encoding(zenoh::bytes::Encoding::APPLICATION_CBOR)
+        .await?;
+    let queryable = session.declare_queryable(&schemas_key).await?;
+    log::info!(
+        "[SchemaRegistry] Serving {} (changes on {})",
+        schemas_key,
+        changes_key
+    );
+
+    let query_registry = registry.clone();
+    let mut query_shutdown = shutdown_rx.clone();
+    let query_task = tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                result = queryable.recv_async() => {
+                    let Ok(query) = result else { break };
+                    let request = match query.payload() {
+                        Some(p) => gateway::from_cbor::<SchemaQuery>(&p.to_bytes()),
+                        None => Ok(SchemaQuery::default()),
+                    };
+                    let result = match request {
+                        Ok(request) => {
+                            let schemas = query_registry
+                                .lock()
+                                .unwrap_or_else(|e| e.into_inner())
+                                .query(&request);
+                            gateway::to_cbor(&schemas).map_err(|e| e.to_string())
+                        }
+                        Err(e) => Err(format!("Invalid schema query: {}", e)),
+                    };
+                    let sent = match result {
+                        Ok(buf) => query
+                            .reply(query.key_expr(), buf)
+                            .encoding(zenoh::bytes::Encoding::APPLICATION_CBOR)
+                            .await,
+                        Err(msg) => query.reply_err(msg).await,
+                    };
+                    if let Err(e) = sent {
+                        log::debug!("[SchemaRegistry] Reply failed: {}", e);
+                    }
+                }
+                _ = query_shutdown.changed() => break,
+            }
+        }
+    });
+
+    let mut interval = tokio::time::interval(COLLECT_INTERVAL);
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                let pools = collect(&session).await;
+                let now_ms = util::now_ms();
+                let changes: Vec<SchemaChange> = {
+                    let mut registry = registry.lock().unwrap_or_else(|e| e.into_inner());
+                    pools
+                        .iter()
+                        .flat_map(|(source, pool)| registry.observe(source, pool, now_ms))
+                        .collect()
+                };
+                for change in &changes {
+                    match change.previous_version {
+                        Some(previous) => log::info!(
+                            "[SchemaRegistry] {} changed: v{} -> v{} (from {})",
+                            change.type_name, previous, change.version, change.source
+                        ),
+                        None => log::debug!(
+                            "[SchemaRegistry] New type {} (from {})",
+                            change.type_name, change.source
+                        ),
+                    }
+                    match gateway::to_cbor(change) {
+                        Ok(buf) => {
+                            if let Err(e) = publisher.put(buf).await {
+                                log::warn!("[SchemaRegistry] Publish failed: {}", e);
+                            }
+                        }
+                        Err(e) => log::warn!("[SchemaRegistry] Encode failed: {}", e),
+                    }
+                }
+            }
+            _ = shutdown_rx.changed() => break,
+        }
+    }
+
+    let _ = query_task.await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost_types::field_descriptor_proto::{Label, Type};
+    use prost_types::{
+        DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+    };
+
+    fn field(name: &str, number: i32, ty: Type) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_string()),
+            number: Some(number),
+            r#type: Some(ty as i32),
+            label: Some(Label::Optional as i32),
+            json_name: Some(name.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// `test.v1.Reading { Stamp stamp = 1; <value field> }`, where `Stamp`
+    /// has the given fields.
+    fn pool(
+        stamp_fields: Vec<FieldDescriptorProto>,
+        value: FieldDescriptorProto,
+    ) -> DescriptorPool {
+        let mut stamp = field("stamp", 1, Type::Message);
+        stamp.type_name = Some(".test.v1.Stamp".to_string());
+        let file = FileDescriptorProto {
+            name: Some("test/reading.proto".to_string()),
+            package: Some("test.v1".to_string()),
+            syntax: Some("proto3".to_string()),
+            message_type: vec![
+                DescriptorProto {
+                    name: Some("Stamp".to_string()),
+                    field: stamp_fields,
+                    ..Default::default()
+                },
+                DescriptorProto {
+                    name: Some("Reading".to_string()),
+                    field: vec![stamp, value],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        DescriptorPool::from_file_descriptor_set(FileDescriptorSet { file: vec![file] }).unwrap()
+    }
+
+    fn v1() -> DescriptorPool {
+        pool(
+            vec![field("time", 1, Type::Uint64)],
+            field("value", 2, Type::Double),
+        )
+    }
+
+    fn versions(registry: &SchemaRegistry, name: &str) -> Vec<SchemaVersion> {
+        registry
+            .query(&SchemaQuery {
+                type_name: Some(name.to_string()),
+            })
+            .pop()
+            .map(|s| s.versions)
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn same_definition_from_many_nodes_is_one_version() {
+        let mut registry = SchemaRegistry::default();
+        let changes = registry.observe("orin/thermo_a", &v1(), 1_000);
+        let mut names: Vec<_> = changes.iter().map(|c| c.type_name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, ["test.v1.Reading", "test.v1.Stamp"]);
+        assert!(changes
+            .iter()
+            .all(|c| c.version == 1 && c.previous_version.is_none()));
+
+        assert!(registry.observe("pi/thermo_b", &v1(), 2_000).is_empty());
+        assert!(registry.observe("orin/thermo_a", &v1(), 3_000).is_empty());
+        let reading = versions(&registry, "test.v1.Reading");
+        assert_eq!(reading.len(), 1);
+        assert_eq!(reading[0].sources, ["orin/thermo_a", "pi/thermo_b"]);
+        assert_eq!(reading[0].first_seen_ms, 1_000);
+        assert_eq!(reading[0].last_seen_ms, 3_000);
+
+        let decoded = DescriptorPool::decode(reading[0].descriptor.as_slice()).unwrap();
+        assert!(decoded.get_message_by_name("test.v1.Reading").is_some());
+    }
+
+    #[test]
+    fn changed_dependency_is_a_new_version_of_its_users() {
+        let mut registry = SchemaRegistry::default();
+        registry.observe("orin/thermo_a", &v1(), 1_000);
+
+        let v2 = pool(
+            vec![
+                field("time", 1, Type::Uint64),
+                field("frame_id", 2, Type::String),
+            ],
+            field("value", 2, Type::Double),
+        );
+        let changes = registry.observe("orin/thermo_a", &v2, 2_000);
+        let reading = changes
+            .iter()
+            .find(|c| c.type_name == "test.v1.Reading")
+            .unwrap();
+        assert_eq!((reading.previous_version, reading.version), (Some(1), 2));
+        assert_eq!(changes.len(), 2);
+
+        // A node still on the old definition matches version 1 again.
+        assert!(registry.observe("pi/thermo_b", &v1(), 3_000).is_empty());
+        let reading = versions(&registry, "test.v1.Reading");
+        assert_eq!(reading[0].sources, ["orin/thermo_a", "pi/thermo_b"]);
+        assert_eq!(reading[1].sources, ["orin/thermo_a"]);
+    }
+
+    #[test]
+    fn listing_omits_descriptors_and_keeps_bounded_history() {
+        let mut registry = SchemaRegistry::default();
+        for i in 0..(MAX_VERSIONS as i32 + 2) {
+            let p = pool(
+                vec![field("time", 1, Type::Uint64)],
+                field(&format!("value_{}", i), 2, Type::Double),
+            );
+            registry.observe("orin/thermo_a", &p, i as i64);
+        }
+        let listing = registry.query(&SchemaQuery::default());
+        let reading = listing
+            .iter()
+            .find(|s| s.type_name == "test.v1.Reading")
+            .unwrap();
+        assert_eq!(reading.versions.len(), MAX_VERSIONS);
+        assert_eq!(reading.versions[0].version, 3);
+        assert_eq!(
+            reading.versions.last().unwrap().version,
+            MAX_VERSIONS as u32 + 2
+        );
+        assert!(reading.versions.iter().all(|v| v.descriptor.is_empty()));
+        assert!(registry
+            .query(&SchemaQuery {
+                type_name: Some("test.v1.Missing".to_string()),
+            })
+            .is_empty());
+    }
+
+    #[test]
+    fn builtin_types_are_registered_without_well_known_types() {
+        let mut registry = SchemaRegistry::default();
+        registry.observe("orin/daemon", crate::get_descriptor_pool(), 0);
+        let names: Vec<_> = registry
+            .query(&SchemaQuery::default())
+            .into_iter()
+            .map(|s| s.type_name)
+            .collect();
+        assert!(names.iter().any(|n| n == "bubbaloop.header.v1.Header"));
+        assert!(!names.iter().any(|n| n.starts_with("google.protobuf.")));
+    }
+
+    #[test]
+    fn source_of_schema_keys() {
+        assert_eq!(
+            source_of("bubbaloop/global/orin/cam/schema").as_deref(),
+            Some("orin/cam")
+        );
+        assert_eq!(source_of("bubbaloop/global/orin/daemon/schemas"), None);
+        assert_eq!(source_of("bubbaloop/global/orin/agent/a/schema"), None);
+    }
+}
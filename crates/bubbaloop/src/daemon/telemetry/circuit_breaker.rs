@@ -260,7 +260,7 @@ async fn find_kill_candidate(
         .collect();
 
     // Sort descending by RSS and take the top candidate.
-    candidates.sort_by(|a, b| b.1.cmp(&a.1));
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.1));
     candidates.into_iter().next()
 }
 
@@ -380,7 +380,7 @@ fn top_processes_by_rss(
     n: usize,
 ) -> Vec<&crate::daemon::telemetry::types::ProcessSnapshot> {
     let mut procs: Vec<_> = snapshot.processes.iter().collect();
-    procs.sort_by(|a, b| b.rss_bytes.cmp(&a.rss_bytes));
+    procs.sort_by_key(|p| std::cmp::Reverse(p.rss_bytes));
     procs.truncate(n);
     procs
 }
@@ -133,7 +133,7 @@ pub fn take_snapshot(sys: &mut System, disks: &mut Disks, disk_path: &str) -> Te
         })
         .collect();
 
-    processes.sort_unstable_by(|a, b| b.rss_bytes.cmp(&a.rss_bytes));
+    processes.sort_unstable_by_key(|p| std::cmp::Reverse(p.rss_bytes));
     processes.truncate(20);
 
     let system = SystemSnapshot {
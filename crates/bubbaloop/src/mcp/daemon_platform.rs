@@ -6,6 +6,7 @@ use crate::schemas::daemon::v1::{
     CommandType, HealthStatus, NodeCommand as ProtoNodeCommand, NodeStatus,
 };
 use serde_json::Value;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use zenoh::Session;
@@ -138,6 +139,7 @@ impl PlatformOperations for DaemonPlatform {
             NodeCommand::Clean => CommandType::Clean,
             NodeCommand::EnableAutostart => CommandType::EnableAutostart,
             NodeCommand::DisableAutostart => CommandType::DisableAutostart,
+            NodeCommand::Update => CommandType::Update,
         };
 
         let proto_cmd = build_node_command(cmd_type, name);
@@ -573,18 +575,7 @@ impl PlatformOperations for DaemonPlatform {
         let store = crate::daemon::reactive::ReactiveRuleStore::open(&alerts_db_path)
             .map_err(|e| PlatformError::Internal(e.to_string()))?;
         let rule_id = format!("alert-{}", uuid::Uuid::new_v4());
-        let rule = crate::daemon::reactive::ReactiveRuleConfig {
-            id: rule_id.clone(),
-            mission_id: params.mission_id,
-            predicate: params.predicate,
-            debounce_secs: params
-                .debounce_secs
-                .unwrap_or(crate::daemon::reactive::DEFAULT_DEBOUNCE_SECS),
-            arousal_boost: params
-                .arousal_boost
-                .unwrap_or(crate::daemon::reactive::DEFAULT_AROUSAL_BOOST),
-            description: params.description,
-        };
+        let rule = params.into_config(rule_id.clone());
         store
             .save_rule(&rule)
             .map_err(|e| PlatformError::Internal(e.to_string()))?;
@@ -648,6 +639,54 @@ impl PlatformOperations for DaemonPlatform {
             .collect())
     }
 
+    async fn list_rule_templates(
+        &self,
+    ) -> PlatformResult<Vec<crate::daemon::rule_templates::TemplateInfo>> {
+        use crate::daemon::rule_templates::{list_templates, load_packs, packs_dir};
+
+        let agent_dir = self
+            .agent_db_path
+            .parent()
+            .unwrap_or(std::path::Path::new("."));
+        Ok(list_templates(&load_packs(&packs_dir(agent_dir))))
+    }
+
+    async fn render_rule_template(
+        &self,
+        template: String,
+        mission_id: String,
+        params: HashMap<String, String>,
+    ) -> PlatformResult<super::platform::RegisterAlertParams> {
+        use crate::daemon::rule_templates::{find_template, load_packs, packs_dir};
+
+        let agent_dir = self
+            .agent_db_path
+            .parent()
+            .unwrap_or(std::path::Path::new("."));
+        let packs = load_packs(&packs_dir(agent_dir));
+        find_template(&packs, &template)
+            .and_then(|t| t.instantiate(&mission_id, &params))
+            .map_err(|e| PlatformError::InvalidInput(e.to_string()))
+    }
+
+    async fn get_events(
+        &self,
+        query: crate::daemon::rule_events::RuleEventQuery,
+    ) -> PlatformResult<Vec<crate::daemon::rule_events::RuleEvent>> {
+        let alerts_db_path = self
+            .agent_db_path
+            .parent()
+            .unwrap_or(std::path::Path::new("."))
+            .join("alerts.db");
+        // Missing DB file is treated as "no events".
+        if !alerts_db_path.exists() {
+            return Ok(Vec::new());
+        }
+        crate::daemon::rule_events::RuleEventStore::open(&alerts_db_path)
+            .and_then(|store| store.query(&query))
+            .map_err(|e| PlatformError::Internal(e.to_string()))
+    }
+
     async fn register_constraint(
         &self,
         params: super::platform::RegisterConstraintParams,
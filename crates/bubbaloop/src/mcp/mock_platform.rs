@@ -13,6 +13,7 @@ pub struct MockPlatform {
     pub manifests: Mutex<Vec<(String, Value)>>,
     pub missions: Mutex<Vec<crate::daemon::mission::Mission>>,
     pub alerts: Mutex<Vec<AlertInfo>>,
+    pub rule_events: Mutex<Vec<crate::daemon::rule_events::RuleEvent>>,
     pub constraints: Mutex<Vec<(String, String, crate::daemon::constraints::Constraint)>>, // (id, mission_id, constraint)
     pub beliefs: Mutex<Vec<crate::agent::memory::semantic::Belief>>,
     pub world_state: Mutex<Vec<crate::agent::memory::WorldStateEntry>>,
@@ -40,6 +41,7 @@ impl MockPlatform {
             configs: Mutex::new(HashMap::new()),
             missions: Mutex::new(Vec::new()),
             alerts: Mutex::new(Vec::new()),
+            rule_events: Mutex::new(Vec::new()),
             constraints: Mutex::new(Vec::new()),
             beliefs: Mutex::new(Vec::new()),
             world_state: Mutex::new(Vec::new()),
@@ -279,13 +281,33 @@ impl PlatformOperations for MockPlatform {
         let arousal_boost = params
             .arousal_boost
             .unwrap_or(crate::daemon::reactive::DEFAULT_AROUSAL_BOOST);
+        let hold_secs = params.hold_secs.unwrap_or(0);
         self.alerts.lock().unwrap().push(AlertInfo {
             id: alert_id.clone(),
             mission_id: params.mission_id,
             predicate: params.predicate,
             debounce_secs,
+            hold_secs,
+            schedule: params.schedule,
+            clear_predicate: params.clear_predicate,
+            priority: params.priority.unwrap_or(0),
+            dry_run: params.dry_run.unwrap_or(false),
+            max_triggers_per_hour: params.max_triggers_per_hour,
             arousal_boost,
             description: params.description,
+            actions: params
+                .actions
+                .unwrap_or_default()
+                .iter()
+                .map(|a| a.redacted())
+                .collect(),
+            escalation: params
+                .escalation
+                .unwrap_or_default()
+                .iter()
+                .map(|s| s.redacted())
+                .collect(),
+            scope: params.scope.unwrap_or_default(),
             // The mock doesn't track provider state, so we never
             // report dangling fields — that analysis lives in the
             // daemon implementation.
@@ -321,6 +343,49 @@ impl PlatformOperations for MockPlatform {
         Ok(out)
     }
 
+    async fn list_rule_templates(
+        &self,
+    ) -> PlatformResult<Vec<crate::daemon::rule_templates::TemplateInfo>> {
+        // The mock only knows the built-in pack.
+        let packs = vec![crate::daemon::rule_templates::builtin_pack()];
+        Ok(crate::daemon::rule_templates::list_templates(&packs))
+    }
+
+    async fn render_rule_template(
+        &self,
+        template: String,
+        mission_id: String,
+        params: HashMap<String, String>,
+    ) -> PlatformResult<super::platform::RegisterAlertParams> {
+        let packs = vec![crate::daemon::rule_templates::builtin_pack()];
+        crate::daemon::rule_templates::find_template(&packs, &template)
+            .and_then(|t| t.instantiate(&mission_id, &params))
+            .map_err(|e| PlatformError::InvalidInput(e.to_string()))
+    }
+
+    async fn get_events(
+        &self,
+        query: crate::daemon::rule_events::RuleEventQuery,
+    ) -> PlatformResult<Vec<crate::daemon::rule_events::RuleEvent>> {
+        let mut events: Vec<_> = self
+            .rule_events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| query.rule_id.as_ref().is_none_or(|id| &e.rule_id == id))
+            .filter(|e| query.since.is_none_or(|t| e.fired_at >= t))
+            .filter(|e| query.until.is_none_or(|t| e.fired_at <= t))
+            .cloned()
+            .collect();
+        events.sort_by_key(|e| std::cmp::Reverse(e.fired_at));
+        events.truncate(
+            query
+                .limit
+                .unwrap_or(crate::daemon::rule_events::DEFAULT_EVENT_LIMIT) as usize,
+        );
+        Ok(events)
+    }
+
     async fn register_constraint(
         &self,
         params: super::platform::RegisterConstraintParams,
@@ -467,6 +532,7 @@ mod tests {
             configs: Mutex::new(HashMap::new()),
             missions: Mutex::new(Vec::new()),
             alerts: Mutex::new(Vec::new()),
+            rule_events: Mutex::new(Vec::new()),
             constraints: Mutex::new(Vec::new()),
             beliefs: Mutex::new(Vec::new()),
             world_state: Mutex::new(Vec::new()),
@@ -1100,7 +1166,7 @@ mod tests {
 
     #[test]
     fn node_command_all_variants_exist() {
-        // Ensure all ten command variants are constructible and debug-printable
+        // Ensure all eleven command variants are constructible and debug-printable
         let cmds = vec![
             NodeCommand::Start,
             NodeCommand::Stop,
@@ -1112,8 +1178,9 @@ mod tests {
             NodeCommand::Clean,
             NodeCommand::EnableAutostart,
             NodeCommand::DisableAutostart,
+            NodeCommand::Update,
         ];
-        assert_eq!(cmds.len(), 10);
+        assert_eq!(cmds.len(), 11);
         for cmd in &cmds {
             let debug = format!("{:?}", cmd);
             assert!(!debug.is_empty());
@@ -1273,6 +1340,15 @@ mod tests {
             mission_id: "m1".to_string(),
             predicate: "toddler.near_stairs = true".to_string(),
             debounce_secs: Some(30),
+            hold_secs: None,
+            schedule: None,
+            clear_predicate: None,
+            priority: None,
+            dry_run: None,
+            max_triggers_per_hour: None,
+            escalation: None,
+            scope: None,
+            actions: None,
             arousal_boost: Some(3.0),
             description: "Toddler near stairs".to_string(),
         };
@@ -1288,6 +1364,15 @@ mod tests {
             mission_id: "m1".to_string(),
             predicate: "temp > 100".to_string(),
             debounce_secs: None,
+            hold_secs: None,
+            schedule: None,
+            clear_predicate: None,
+            priority: None,
+            dry_run: None,
+            max_triggers_per_hour: None,
+            escalation: None,
+            scope: None,
+            actions: None,
             arousal_boost: None,
             description: "High temp".to_string(),
         };
@@ -1313,6 +1398,15 @@ mod tests {
             mission_id: "m1".to_string(),
             predicate: "temp > 100".to_string(),
             debounce_secs: Some(45),
+            hold_secs: None,
+            schedule: None,
+            clear_predicate: None,
+            priority: None,
+            dry_run: None,
+            max_triggers_per_hour: None,
+            escalation: None,
+            scope: None,
+            actions: None,
             arousal_boost: Some(3.5),
             description: "hot".to_string(),
         };
@@ -1330,6 +1424,37 @@ mod tests {
         assert!(a.dangling_fields.is_empty());
     }
 
+    #[tokio::test]
+    async fn get_events_filters_and_orders_newest_first() {
+        use crate::daemon::rule_events::{RuleEvent, RuleEventQuery};
+
+        let mock = MockPlatform::new();
+        let event = |rule_id: &str, fired_at: i64| RuleEvent {
+            id: fired_at,
+            rule_id: rule_id.to_string(),
+            mission_id: "m1".to_string(),
+            fired_at,
+            payload: serde_json::Value::Null,
+            results: Vec::new(),
+            throttled: false,
+            escalation: 0,
+        };
+        *mock.rule_events.lock().unwrap() = vec![event("a", 10), event("b", 20), event("a", 30)];
+
+        let all = mock.get_events(RuleEventQuery::default()).await.unwrap();
+        let times: Vec<_> = all.iter().map(|e| e.fired_at).collect();
+        assert_eq!(times, [30, 20, 10]);
+
+        let query = RuleEventQuery {
+            rule_id: Some("a".to_string()),
+            until: Some(20),
+            ..Default::default()
+        };
+        let only_a = mock.get_events(query).await.unwrap();
+        assert_eq!(only_a.len(), 1);
+        assert_eq!(only_a[0].fired_at, 10);
+    }
+
     #[tokio::test]
     async fn list_alerts_filters_by_mission() {
         let mock = MockPlatform::new();
@@ -1338,6 +1463,15 @@ mod tests {
                 mission_id: mid.to_string(),
                 predicate: pred.to_string(),
                 debounce_secs: None,
+                hold_secs: None,
+                schedule: None,
+                clear_predicate: None,
+                priority: None,
+                dry_run: None,
+                max_triggers_per_hour: None,
+                escalation: None,
+                scope: None,
+                actions: None,
                 arousal_boost: None,
                 description: String::new(),
             })
@@ -1363,6 +1497,15 @@ mod tests {
             mission_id: "m1".to_string(),
             predicate: "x = 1".to_string(),
             debounce_secs: None,
+            hold_secs: None,
+            schedule: None,
+            clear_predicate: None,
+            priority: None,
+            dry_run: None,
+            max_triggers_per_hour: None,
+            escalation: None,
+            scope: None,
+            actions: None,
             arousal_boost: None,
             description: String::new(),
         })
@@ -4,6 +4,7 @@
 //! making the MCP server testable with mock implementations.
 
 use serde_json::Value;
+use std::collections::HashMap;
 
 /// Result type for platform operations.
 pub type PlatformResult<T> = Result<T, PlatformError>;
@@ -45,6 +46,7 @@ pub enum NodeCommand {
     Clean,
     EnableAutostart,
     DisableAutostart,
+    Update,
 }
 
 /// Abstraction over daemon internals.
@@ -229,6 +231,30 @@ pub trait PlatformOperations: Send + Sync + 'static {
         mission_id: Option<String>,
     ) -> impl std::future::Future<Output = PlatformResult<Vec<AlertInfo>>> + Send;
 
+    /// List the rule templates of the built-in and installed rule packs.
+    fn list_rule_templates(
+        &self,
+    ) -> impl std::future::Future<
+        Output = PlatformResult<Vec<crate::daemon::rule_templates::TemplateInfo>>,
+    > + Send;
+
+    /// Fill in a rule template (`name` or `pack/name`) for a mission. The
+    /// result is registered like any alert.
+    fn render_rule_template(
+        &self,
+        template: String,
+        mission_id: String,
+        params: HashMap<String, String>,
+    ) -> impl std::future::Future<Output = PlatformResult<RegisterAlertParams>> + Send;
+
+    /// Read the rule event history: when alert rules fired, the payload
+    /// they fired with and the outcome of their actions, newest first.
+    fn get_events(
+        &self,
+        query: crate::daemon::rule_events::RuleEventQuery,
+    ) -> impl std::future::Future<Output = PlatformResult<Vec<crate::daemon::rule_events::RuleEvent>>>
+           + Send;
+
     // ── Constraints ───────────────────────────────────────────────────
 
     /// Register a safety constraint for a mission.
@@ -325,8 +351,31 @@ pub struct AlertInfo {
     pub mission_id: String,
     pub predicate: String,
     pub debounce_secs: u32,
+    #[serde(default)]
+    pub hold_secs: u32,
+    #[serde(default)]
+    pub schedule: Option<String>,
+    #[serde(default)]
+    pub clear_predicate: Option<String>,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_triggers_per_hour: Option<u32>,
     pub arousal_boost: f64,
     pub description: String,
+    /// Actions with literal secrets redacted.
+    #[serde(default)]
+    pub actions: Vec<crate::daemon::rule_actions::RuleAction>,
+    /// Escalation steps, redacted like `actions`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub escalation: Vec<crate::daemon::rule_actions::EscalationStep>,
+    #[serde(
+        default,
+        skip_serializing_if = "crate::daemon::reactive::RuleScope::is_empty"
+    )]
+    pub scope: crate::daemon::reactive::RuleScope,
     pub dangling_fields: Vec<String>,
 }
 
@@ -346,8 +395,17 @@ impl AlertInfo {
             mission_id: rule.mission_id,
             predicate: rule.predicate,
             debounce_secs: rule.debounce_secs,
+            hold_secs: rule.hold_secs,
+            schedule: rule.schedule,
+            clear_predicate: rule.clear_predicate,
+            priority: rule.priority,
+            dry_run: rule.dry_run,
+            max_triggers_per_hour: rule.max_triggers_per_hour,
             arousal_boost: rule.arousal_boost,
             description: rule.description,
+            actions: rule.actions.iter().map(|a| a.redacted()).collect(),
+            escalation: rule.escalation.iter().map(|s| s.redacted()).collect(),
+            scope: rule.scope,
             dangling_fields,
         }
     }
@@ -358,16 +416,68 @@ impl AlertInfo {
 pub struct RegisterAlertParams {
     /// Mission this alert is attached to.
     pub mission_id: String,
-    /// World state predicate expression (e.g. "toddler.near_stairs = 'true'").
+    /// World state predicate expression (e.g. "toddler.near_stairs = 'true'" or
+    /// "cpu_temp > 80 AND (fan_rpm < 500 OR NOT fan.ok = true)").
+    /// May be empty when `schedule` is set.
+    #[serde(default)]
     pub predicate: String,
     /// Minimum seconds between consecutive firings (default: 60).
     #[serde(default)]
     pub debounce_secs: Option<u32>,
+    /// Seconds the predicate must hold continuously before firing (default: 0).
+    #[serde(default)]
+    pub hold_secs: Option<u32>,
+    /// Cron expression in UTC (e.g. "0 22 * * *") that triggers the alert.
+    /// The predicate, if any, is checked only when the schedule comes due.
+    #[serde(default)]
+    pub schedule: Option<String>,
+    /// Predicate that must hold before the alert can fire again, e.g.
+    /// "cpu_temp < 75" for a "cpu_temp > 85" alert, so it fires once per
+    /// excursion instead of on every crossing of a single threshold.
+    #[serde(default)]
+    pub clear_predicate: Option<String>,
+    /// When alerts that fire together give one node different commands,
+    /// only the highest-priority one runs (default: 0, range -100..=100).
+    #[serde(default)]
+    pub priority: Option<i32>,
+    /// Log "would have executed" for each action instead of running it,
+    /// without boosting arousal, to try the alert on live data (default: false).
+    #[serde(default)]
+    pub dry_run: Option<bool>,
+    /// Most firings within an hour that run actions and boost arousal;
+    /// further ones are recorded as throttled (default: unlimited).
+    #[serde(default)]
+    pub max_triggers_per_hour: Option<u32>,
     /// Arousal boost when rule fires (default: 2.0).
     #[serde(default)]
     pub arousal_boost: Option<f64>,
     /// Human-readable description of this alert.
     pub description: String,
+    /// Actions run when the alert fires, e.g.
+    /// `[{"type": "http", "url": "https://...", "secret": "env:HOOK_TOKEN"}]` or
+    /// `[{"type": "notify", "channel": "phone", "message": "CPU at {fields.cpu_temp}C"}]`
+    /// (channels are configured in `~/.bubbaloop/notifications.yaml`) or
+    /// `[{"type": "node", "node": "camera", "command": "restart"}]`. A
+    /// `workflow` action runs `steps` in order: the above plus
+    /// `{"type": "wait", "secs": 30}`, `{"type": "check", "node": "camera",
+    /// "status": "running"}` and `{"type": "publish", "topic": "bubbaloop/..."}`,
+    /// each with `"on_failure": "abort"` (default) or `"continue"`.
+    #[serde(default)]
+    pub actions: Option<Vec<crate::daemon::rule_actions::RuleAction>>,
+    /// Actions for when the predicate still holds a while after the alert
+    /// fired, e.g. `[{"after_secs": 600, "actions": [{"type": "notify",
+    /// "channel": "phone"}]}, {"after_secs": 1800, "actions": [{"type":
+    /// "node", "node": "camera", "command": "stop"}]}]`. Each step runs once
+    /// its `after_secs` passed, and the escalation ends when the predicate
+    /// stops holding.
+    #[serde(default)]
+    pub escalation: Option<Vec<crate::daemon::rule_actions::EscalationStep>>,
+    /// Machines and nodes the alert is addressed to, e.g.
+    /// `{"machines": ["jetson-*"], "nodes": ["camera-*"]}`. Only agents on a
+    /// matching machine that has a matching node evaluate it, and its node
+    /// actions may only target matching nodes (default: everywhere).
+    #[serde(default)]
+    pub scope: Option<crate::daemon::reactive::RuleScope>,
 }
 
 impl RegisterAlertParams {
@@ -384,8 +494,17 @@ impl RegisterAlertParams {
             mission_id: self.mission_id,
             predicate: self.predicate,
             debounce_secs: self.debounce_secs.unwrap_or(DEFAULT_DEBOUNCE_SECS),
+            hold_secs: self.hold_secs.unwrap_or(0),
+            schedule: self.schedule,
+            clear_predicate: self.clear_predicate,
+            priority: self.priority.unwrap_or(0),
+            dry_run: self.dry_run.unwrap_or(false),
+            max_triggers_per_hour: self.max_triggers_per_hour,
             arousal_boost: self.arousal_boost.unwrap_or(DEFAULT_AROUSAL_BOOST),
             description: self.description,
+            actions: self.actions.unwrap_or_default(),
+            escalation: self.escalation.unwrap_or_default(),
+            scope: self.scope.unwrap_or_default(),
         }
     }
 }
@@ -68,6 +68,8 @@ pub fn required_tier(tool_name: &str) -> Tier {
         | "list_constraints"
         | "get_belief"
         | "list_alerts"
+        | "get_events"
+        | "list_rule_templates"
         | "list_world_state"
         | "dataflow" => Tier::Viewer,
 
@@ -89,6 +91,7 @@ pub fn required_tier(tool_name: &str) -> Tier {
         | "configure_context"
         | "register_alert"
         | "unregister_alert"
+        | "instantiate_rule_template"
         | "register_constraint" => Tier::Admin,
 
         // Unknown tools default to admin (principle of least privilege)
@@ -129,6 +132,8 @@ mod tests {
         assert_eq!(required_tier("list_jobs"), Tier::Viewer);
         assert_eq!(required_tier("get_belief"), Tier::Viewer);
         assert_eq!(required_tier("list_alerts"), Tier::Viewer);
+        assert_eq!(required_tier("get_events"), Tier::Viewer);
+        assert_eq!(required_tier("list_rule_templates"), Tier::Viewer);
         assert_eq!(required_tier("list_world_state"), Tier::Viewer);
     }
 
@@ -147,6 +152,7 @@ mod tests {
         assert_eq!(required_tier("query_zenoh"), Tier::Admin);
         assert_eq!(required_tier("install_node"), Tier::Admin);
         assert_eq!(required_tier("clear_episodic_memory"), Tier::Admin);
+        assert_eq!(required_tier("instantiate_rule_template"), Tier::Admin);
     }
 
     #[test]
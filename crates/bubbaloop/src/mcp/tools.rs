@@ -107,16 +107,68 @@ pub(crate) struct MissionIdRequest {
 pub(crate) struct RegisterAlertRequest {
     /// Mission this alert is attached to.
     mission_id: String,
-    /// World state predicate expression (e.g. "toddler.near_stairs = 'true'").
+    /// World state predicate expression (e.g. "toddler.near_stairs = 'true'" or
+    /// "cpu_temp > 80 AND (fan_rpm < 500 OR NOT fan.ok = true)").
+    /// May be empty when `schedule` is set.
+    #[serde(default)]
     predicate: String,
     /// Minimum seconds between consecutive firings (default: 60).
     #[serde(default)]
     debounce_secs: Option<u32>,
+    /// Seconds the predicate must hold continuously before firing (default: 0).
+    #[serde(default)]
+    hold_secs: Option<u32>,
+    /// Cron expression in UTC (e.g. "0 22 * * *") that triggers the alert.
+    /// The predicate, if any, is checked only when the schedule comes due.
+    #[serde(default)]
+    schedule: Option<String>,
+    /// Predicate that must hold before the alert can fire again, e.g.
+    /// "cpu_temp < 75" for a "cpu_temp > 85" alert, so it fires once per
+    /// excursion instead of on every crossing of a single threshold.
+    #[serde(default)]
+    clear_predicate: Option<String>,
+    /// When alerts that fire together give one node different commands,
+    /// only the highest-priority one runs (default: 0, range -100..=100).
+    #[serde(default)]
+    priority: Option<i32>,
+    /// Log "would have executed" for each action instead of running it,
+    /// without boosting arousal, to try the alert on live data (default: false).
+    #[serde(default)]
+    dry_run: Option<bool>,
+    /// Most firings within an hour that run actions and boost arousal;
+    /// further ones are recorded as throttled (default: unlimited).
+    #[serde(default)]
+    max_triggers_per_hour: Option<u32>,
     /// Arousal boost when rule fires (default: 2.0).
     #[serde(default)]
     arousal_boost: Option<f64>,
     /// Human-readable description of this alert.
     description: String,
+    /// Actions run when the alert fires, e.g.
+    /// `[{"type": "http", "url": "https://...", "secret": "env:HOOK_TOKEN"}]` or
+    /// `[{"type": "notify", "channel": "phone", "message": "CPU at {fields.cpu_temp}C"}]`
+    /// (channels are configured in `~/.bubbaloop/notifications.yaml`) or
+    /// `[{"type": "node", "node": "camera", "command": "restart"}]`. A
+    /// `workflow` action runs `steps` in order: the above plus
+    /// `{"type": "wait", "secs": 30}`, `{"type": "check", "node": "camera",
+    /// "status": "running"}` and `{"type": "publish", "topic": "bubbaloop/..."}`,
+    /// each with `"on_failure": "abort"` (default) or `"continue"`.
+    #[serde(default)]
+    actions: Option<Vec<crate::daemon::rule_actions::RuleAction>>,
+    /// Actions for when the predicate still holds a while after the alert
+    /// fired, e.g. `[{"after_secs": 600, "actions": [{"type": "notify",
+    /// "channel": "phone"}]}, {"after_secs": 1800, "actions": [{"type":
+    /// "node", "node": "camera", "command": "stop"}]}]`. Each step runs once
+    /// its `after_secs` passed, and the escalation ends when the predicate
+    /// stops holding.
+    #[serde(default)]
+    escalation: Option<Vec<crate::daemon::rule_actions::EscalationStep>>,
+    /// Machines and nodes the alert is addressed to, e.g.
+    /// `{"machines": ["jetson-*"], "nodes": ["camera-*"]}`. Only agents on a
+    /// matching machine that has a matching node evaluate it, and its node
+    /// actions may only target matching nodes (default: everywhere).
+    #[serde(default)]
+    scope: Option<crate::daemon::reactive::RuleScope>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -132,6 +184,36 @@ pub(crate) struct ListAlertsRequest {
     mission_id: Option<String>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct InstantiateRuleTemplateRequest {
+    /// Template name, or pack/template (see list_rule_templates).
+    template: String,
+    /// Mission the new alert belongs to.
+    mission_id: String,
+    /// Template parameters by name; omitted ones take their default.
+    #[serde(default)]
+    params: std::collections::HashMap<String, String>,
+    /// Log the alert's actions instead of running them (default: false).
+    #[serde(default)]
+    dry_run: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub(crate) struct GetEventsRequest {
+    /// Only return firings of this alert rule.
+    #[serde(default)]
+    rule_id: Option<String>,
+    /// Only return firings at or after this time (epoch seconds).
+    #[serde(default)]
+    since: Option<i64>,
+    /// Only return firings at or before this time (epoch seconds).
+    #[serde(default)]
+    until: Option<i64>,
+    /// Maximum number of events, newest first (default: 100).
+    #[serde(default)]
+    limit: Option<u32>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub(crate) struct ConfigureContextRequest {
     /// Mission this provider is attached to.
@@ -678,7 +760,7 @@ impl<P: PlatformOperations> BubbaLoopMcpServer<P> {
     }
 
     #[tool(
-        description = "Get overall system status including daemon health, node count, and Zenoh connection state."
+        description = "Get overall system status including daemon health, node count, Zenoh connection state, and the sample counters of each context provider (received, dropped when its queue was full, filtered, written)."
     )]
     async fn get_system_status(&self) -> Result<CallToolResult, rmcp::ErrorData> {
         log::info!("[MCP] tool=get_system_status");
@@ -699,6 +781,7 @@ impl<P: PlatformOperations> BubbaLoopMcpServer<P> {
             "nodes_running": running,
             "nodes_healthy": healthy,
             "mcp_server": "running",
+            "context_providers": crate::daemon::context_provider::provider_stats(),
         });
         Ok(CallToolResult::success(vec![Content::text(
             serde_json::to_string_pretty(&status).unwrap_or_default(),
@@ -1178,7 +1261,7 @@ impl<P: PlatformOperations> BubbaLoopMcpServer<P> {
     // ── Reactive alert tools ────────────────────────────────────────
 
     #[tool(
-        description = "Register a reactive alert rule. When the world state matches the predicate, the agent's arousal spikes without an LLM call. Admin only."
+        description = "Register a reactive alert rule. When the world state matches the predicate, or a cron schedule comes due, the agent's arousal spikes without an LLM call. Admin only."
     )]
     async fn register_alert(
         &self,
@@ -1190,8 +1273,17 @@ impl<P: PlatformOperations> BubbaLoopMcpServer<P> {
             mission_id: req.mission_id,
             predicate: req.predicate,
             debounce_secs: req.debounce_secs,
+            hold_secs: req.hold_secs,
+            schedule: req.schedule,
+            clear_predicate: req.clear_predicate,
+            priority: req.priority,
+            dry_run: req.dry_run,
+            max_triggers_per_hour: req.max_triggers_per_hour,
             arousal_boost: req.arousal_boost,
             description: req.description,
+            actions: req.actions,
+            escalation: req.escalation,
+            scope: req.scope,
         };
 
         // Validate at the MCP boundary so mock and daemon backends reject
@@ -1233,7 +1325,7 @@ impl<P: PlatformOperations> BubbaLoopMcpServer<P> {
     }
 
     #[tool(description = "List reactive alert rules with full introspection. \
-            Each entry includes predicate, debounce_secs, arousal_boost, description, \
+            Each entry includes predicate, debounce_secs, hold_secs, schedule, clear_predicate, priority, dry_run, max_triggers_per_hour, arousal_boost, description, actions, escalation, scope, \
             and `dangling_fields` — world-state keys the predicate references \
             that no registered context provider appears to produce. A non-empty \
             dangling_fields list is a red flag: the rule may never fire, or may \
@@ -1257,6 +1349,102 @@ impl<P: PlatformOperations> BubbaLoopMcpServer<P> {
         }
     }
 
+    #[tool(
+        description = "List parameterized alert rule templates from the built-in and installed \
+            rule packs, e.g. core/high-temperature-shutdown. Each entry has the pack, name, description \
+            and parameters (with their defaults; parameters without one are required)."
+    )]
+    async fn list_rule_templates(&self) -> Result<CallToolResult, rmcp::ErrorData> {
+        log::info!("[MCP] tool=list_rule_templates");
+        match self.platform.list_rule_templates().await {
+            Ok(templates) => {
+                let json = serde_json::to_string_pretty(&templates)
+                    .unwrap_or_else(|e| format!("Error serializing: {}", e));
+                Ok(CallToolResult::success(vec![Content::text(json)]))
+            }
+            Err(e) => Ok(CallToolResult::success(vec![Content::text(format!(
+                "Error: {}",
+                e
+            ))])),
+        }
+    }
+
+    #[tool(
+        description = "Register a reactive alert rule from a rule template (see list_rule_templates), filling in its parameters. Admin only."
+    )]
+    async fn instantiate_rule_template(
+        &self,
+        Parameters(req): Parameters<InstantiateRuleTemplateRequest>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        log::info!(
+            "[MCP] tool=instantiate_rule_template template={} mission_id={}",
+            req.template,
+            req.mission_id
+        );
+        let mut params = match self
+            .platform
+            .render_rule_template(req.template, req.mission_id, req.params)
+            .await
+        {
+            Ok(params) => params,
+            Err(e) => {
+                return Ok(CallToolResult::success(vec![Content::text(format!(
+                    "Error: {}",
+                    e
+                ))]))
+            }
+        };
+        params.dry_run = req.dry_run.or(params.dry_run);
+        // Same boundary check as register_alert.
+        if let Err(e) = params.clone().into_config("preview".to_string()).validate() {
+            log::warn!("[MCP] instantiate_rule_template rejected: {}", e);
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Error: {}",
+                e
+            ))]));
+        }
+        match self.platform.register_alert(params).await {
+            Ok(msg) => Ok(CallToolResult::success(vec![Content::text(msg)])),
+            Err(e) => Ok(CallToolResult::success(vec![Content::text(format!(
+                "Error: {}",
+                e
+            ))])),
+        }
+    }
+
+    #[tool(description = "Read the history of alert rule firings, newest first. \
+            Each event has the rule and mission IDs, fired_at (epoch seconds), the payload \
+            the rule fired with (including predicate field values) and the outcome of \
+            each of its actions. Optional rule_id, since/until (epoch seconds) and limit filters.")]
+    async fn get_events(
+        &self,
+        Parameters(req): Parameters<GetEventsRequest>,
+    ) -> Result<CallToolResult, rmcp::ErrorData> {
+        log::info!(
+            "[MCP] tool=get_events rule_id={:?} since={:?} until={:?}",
+            req.rule_id,
+            req.since,
+            req.until
+        );
+        let query = crate::daemon::rule_events::RuleEventQuery {
+            rule_id: req.rule_id,
+            since: req.since,
+            until: req.until,
+            limit: req.limit,
+        };
+        match self.platform.get_events(query).await {
+            Ok(events) => {
+                let json = serde_json::to_string_pretty(&events)
+                    .unwrap_or_else(|e| format!("Error serializing: {}", e));
+                Ok(CallToolResult::success(vec![Content::text(json)]))
+            }
+            Err(e) => Ok(CallToolResult::success(vec![Content::text(format!(
+                "Error: {}",
+                e
+            ))])),
+        }
+    }
+
     // ── Constraint tools ────────────────────────────────────────────
 
     #[tool(
@@ -2,10 +2,15 @@
 //!
 //! Provides lookup, search, and caching of the official nodes registry
 //! (fetched from GitHub). Used by both CLI and TUI.
+//!
+//! Devices without internet can run marketplace operations offline
+//! (`--offline` or `BUBBALOOP_OFFLINE=1`): [`load_registry`] then reads the
+//! cache without refreshing it, and callers warn about its age.
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 /// Raw URL for the official nodes registry on GitHub.
 pub const OFFICIAL_NODES_URL: &str =
@@ -14,8 +19,14 @@ pub const OFFICIAL_NODES_URL: &str =
 /// Local cache filename inside ~/.bubbaloop/cache/
 pub const OFFICIAL_NODES_CACHE: &str = "official_nodes.yaml";
 
+/// Environment variable that makes marketplace operations work offline.
+pub const OFFLINE_ENV: &str = "BUBBALOOP_OFFLINE";
+
+/// Registry caches older than this get a staleness warning.
+pub const STALE_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
 /// A node entry from the official registry.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegistryNode {
     pub name: String,
     pub version: String,
@@ -33,11 +44,32 @@ pub struct RegistryNode {
     pub binary: Option<String>,
 }
 
+/// A rule pack entry from the official registry: a YAML file of rule
+/// templates (see [`crate::daemon::rule_templates`]) in a GitHub repo.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegistryRulePack {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub repo: String,
+    /// Path of the pack file inside the repo.
+    pub path: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct OfficialNodesYaml {
     nodes: Vec<RegistryNode>,
 }
 
+#[derive(Debug, Deserialize)]
+struct OfficialRulePacksYaml {
+    #[serde(default)]
+    rule_packs: Vec<RegistryRulePack>,
+}
+
 /// Parse a nodes.yaml string into a list of `RegistryNode`.
 pub fn parse_nodes_yaml(yaml: &str) -> Vec<RegistryNode> {
     let registry: OfficialNodesYaml = match serde_yaml::from_str(yaml) {
@@ -47,6 +79,13 @@ pub fn parse_nodes_yaml(yaml: &str) -> Vec<RegistryNode> {
     registry.nodes
 }
 
+/// Parse the `rule_packs` section of a nodes.yaml string.
+pub fn parse_rule_packs_yaml(yaml: &str) -> Vec<RegistryRulePack> {
+    serde_yaml::from_str::<OfficialRulePacksYaml>(yaml)
+        .map(|registry| registry.rule_packs)
+        .unwrap_or_default()
+}
+
 /// Return the cache directory path (~/.bubbaloop/cache).
 fn cache_dir() -> PathBuf {
     dirs::home_dir()
@@ -65,6 +104,99 @@ pub fn load_cached_registry() -> Vec<RegistryNode> {
     }
 }
 
+/// Load registry rule packs from the local cache file.
+/// Returns an empty list if the cache doesn't exist or can't be parsed.
+pub fn load_cached_rule_packs() -> Vec<RegistryRulePack> {
+    let cache_path = cache_dir().join(OFFICIAL_NODES_CACHE);
+    match fs::read_to_string(&cache_path) {
+        Ok(yaml) => parse_rule_packs_yaml(&yaml),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Age of the registry cache file, or `None` when there is no cache.
+pub fn cache_age() -> Option<Duration> {
+    let modified = fs::metadata(cache_dir().join(OFFICIAL_NODES_CACHE))
+        .and_then(|m| m.modified())
+        .ok()?;
+    Some(
+        SystemTime::now()
+            .duration_since(modified)
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Whether `BUBBALOOP_OFFLINE` asks for offline marketplace operations.
+pub fn offline_from_env() -> bool {
+    std::env::var(OFFLINE_ENV)
+        .is_ok_and(|v| !v.is_empty() && v != "0" && !v.eq_ignore_ascii_case("false"))
+}
+
+/// Registry nodes and where they came from.
+#[derive(Debug, Clone)]
+pub struct LoadedRegistry {
+    pub nodes: Vec<RegistryNode>,
+    /// Whether the cache was refreshed from GitHub just now.
+    pub refreshed: bool,
+    /// Why the cache was not refreshed, when a refresh was attempted.
+    pub refresh_error: Option<String>,
+    /// Age of the cache the nodes were read from.
+    pub age: Option<Duration>,
+}
+
+/// Load the registry, refreshing the cache first unless `offline`.
+///
+/// A failed refresh falls back to the cache; [`LoadedRegistry::warning`]
+/// says how old it is.
+pub fn load_registry(offline: bool) -> LoadedRegistry {
+    let refresh_error = if offline { None } else { refresh_cache().err() };
+    if let Some(e) = &refresh_error {
+        log::warn!("registry refresh failed: {}", e);
+    }
+    LoadedRegistry {
+        nodes: load_cached_registry(),
+        refreshed: !offline && refresh_error.is_none(),
+        refresh_error,
+        age: cache_age(),
+    }
+}
+
+impl LoadedRegistry {
+    /// A warning for the user when the nodes may be out of date.
+    pub fn warning(&self) -> Option<String> {
+        if self.refreshed {
+            return None;
+        }
+        let why = match &self.refresh_error {
+            Some(e) => format!("could not refresh registry: {}", e.trim()),
+            None => "offline".to_string(),
+        };
+        let Some(age) = self.age.filter(|_| !self.nodes.is_empty()) else {
+            return Some(format!(
+                "{}, and there is no cached registry. Run `bubbaloop node search` once while online.",
+                why
+            ));
+        };
+        let mut warning = format!("{}; using registry cache from {} ago", why, format_age(age));
+        if age > STALE_AFTER {
+            warning.push_str(" (stale: versions and nodes may be out of date)");
+        }
+        Some(warning)
+    }
+}
+
+/// `45 seconds`, `12 minutes`, `3 hours`, `9 days`.
+pub fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    let (n, unit) = match secs {
+        0..60 => (secs, "second"),
+        60..3600 => (secs / 60, "minute"),
+        3600..86400 => (secs / 3600, "hour"),
+        _ => (secs / 86400, "day"),
+    };
+    format!("{} {}{}", n, unit, if n == 1 { "" } else { "s" })
+}
+
 /// Refresh the cache by fetching from GitHub (blocking, uses curl).
 /// Returns Ok(()) on success, Err with message on failure.
 pub fn refresh_cache() -> Result<(), String> {
@@ -180,6 +312,18 @@ pub fn find_by_name(nodes: &[RegistryNode], name: &str) -> Option<RegistryNode>
     nodes.iter().find(|n| n.name == name).cloned()
 }
 
+/// Raw URL of a rule pack file on the repo's main branch.
+pub fn rule_pack_url(pack: &RegistryRulePack) -> Result<String, String> {
+    validate_repo(&pack.repo)?;
+    if pack.path.is_empty() || pack.path.starts_with('/') || pack.path.contains("..") {
+        return Err(format!("invalid rule pack path: {}", pack.path));
+    }
+    Ok(format!(
+        "https://raw.githubusercontent.com/{}/main/{}",
+        pack.repo, pack.path
+    ))
+}
+
 /// Validate that a repo string looks like a valid GitHub "owner/repo" shorthand.
 /// Rejects path traversal, shell metacharacters, and malformed values.
 pub fn validate_repo(repo: &str) -> Result<(), String> {
@@ -260,6 +404,62 @@ nodes:
     subdir: network-monitor
 "#;
 
+    #[test]
+    fn test_parse_rule_packs_yaml() {
+        let yaml = format!(
+            "{}\nrule_packs:\n  - name: rack-safety\n    version: \"0.1.0\"\n    \
+             repo: kornia/bubbaloop-nodes-official\n    path: rules/rack-safety.yaml\n",
+            TEST_NODES_YAML
+        );
+        assert_eq!(parse_nodes_yaml(&yaml).len(), 5);
+        let packs = parse_rule_packs_yaml(&yaml);
+        assert_eq!(packs.len(), 1);
+        assert_eq!(
+            rule_pack_url(&packs[0]).unwrap(),
+            "https://raw.githubusercontent.com/kornia/bubbaloop-nodes-official/main/rules/rack-safety.yaml"
+        );
+        assert!(parse_rule_packs_yaml(TEST_NODES_YAML).is_empty());
+
+        let mut bad = packs[0].clone();
+        bad.path = "../secrets".to_string();
+        assert!(rule_pack_url(&bad).is_err());
+    }
+
+    fn loaded(refreshed: bool, error: Option<&str>, age_days: Option<u64>) -> LoadedRegistry {
+        LoadedRegistry {
+            nodes: parse_nodes_yaml(TEST_NODES_YAML),
+            refreshed,
+            refresh_error: error.map(str::to_string),
+            age: age_days.map(|d| Duration::from_secs(d * 86400)),
+        }
+    }
+
+    #[test]
+    fn test_registry_warnings() {
+        assert_eq!(loaded(true, None, Some(0)).warning(), None);
+
+        let offline = loaded(false, None, Some(2)).warning().unwrap();
+        assert_eq!(offline, "offline; using registry cache from 2 days ago");
+
+        let stale = loaded(false, Some("curl failed: timeout\n"), Some(30))
+            .warning()
+            .unwrap();
+        assert!(stale.starts_with("could not refresh registry: curl failed: timeout;"));
+        assert!(stale.contains("30 days ago (stale"));
+
+        let mut empty = loaded(false, None, None);
+        empty.nodes.clear();
+        assert!(empty.warning().unwrap().contains("no cached registry"));
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::from_secs(1)), "1 second");
+        assert_eq!(format_age(Duration::from_secs(150)), "2 minutes");
+        assert_eq!(format_age(Duration::from_secs(3 * 3600)), "3 hours");
+        assert_eq!(format_age(Duration::from_secs(86400)), "1 day");
+    }
+
     #[test]
     fn test_parse_nodes_yaml() {
         let nodes = parse_nodes_yaml(TEST_NODES_YAML);
@@ -13,7 +13,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Errors from skill loading and validation operations.
 #[derive(Debug, thiserror::Error)]
@@ -129,6 +129,31 @@ pub fn validate_skill(skill: &SkillConfig) -> Result<()> {
     Ok(())
 }
 
+/// Parse and validate a single skill file.
+pub fn load_skill_file(path: &Path) -> Result<SkillConfig> {
+    let raw = std::fs::read_to_string(path)?;
+    let skill: SkillConfig =
+        serde_yaml::from_str(&raw).map_err(|source| SkillError::ParseError {
+            path: path.display().to_string(),
+            source,
+        })?;
+    validate_skill(&skill)?;
+    Ok(skill)
+}
+
+/// The `*.yaml` files in `skills_dir`, sorted. A missing directory has none.
+pub fn skill_files(skills_dir: &Path) -> Result<Vec<PathBuf>> {
+    if !skills_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files: Vec<PathBuf> = std::fs::read_dir(skills_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("yaml"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
 /// Load all `*.yaml` skill files from the given directory.
 ///
 /// Files that fail to parse are logged and skipped. An empty or missing
@@ -415,4 +440,24 @@ actions:
         let skills = load_skills(dir.path()).unwrap();
         assert_eq!(skills.len(), 3);
     }
+
+    // ── load_skill_file / skill_files ────────────────────────────────────────
+
+    #[test]
+    fn load_skill_file_parses_and_validates() {
+        let dir = tempdir().unwrap();
+        let good = dir.path().join("b.yaml");
+        std::fs::write(&good, "name: cam1\ndriver: rtsp\n").unwrap();
+        let unknown = dir.path().join("a.yaml");
+        std::fs::write(&unknown, "name: cam2\ndriver: lidar\n").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "x").unwrap();
+
+        assert_eq!(load_skill_file(&good).unwrap().name, "cam1");
+        assert!(matches!(
+            load_skill_file(&unknown),
+            Err(SkillError::UnknownDriver(_))
+        ));
+        assert_eq!(skill_files(dir.path()).unwrap(), vec![unknown, good]);
+        assert!(skill_files(&dir.path().join("missing")).unwrap().is_empty());
+    }
 }
@@ -140,6 +140,7 @@ fn mock_with_nodes(nodes: Vec<NodeInfo>) -> MockPlatform {
         manifests: Mutex::new(Vec::new()),
         missions: Mutex::new(Vec::new()),
         alerts: Mutex::new(Vec::new()),
+        rule_events: Mutex::new(Vec::new()),
         constraints: Mutex::new(Vec::new()),
         beliefs: Mutex::new(Vec::new()),
         world_state: Mutex::new(Vec::new()),
@@ -5,7 +5,7 @@ import { decode as cborDecode } from 'cbor-x';
 import { useZenohSubscription } from '../hooks/useZenohSubscription';
 import { useFleetContext } from '../contexts/FleetContext';
 import { MachineBadge } from './MachineBadge';
-import { H264Decoder } from '../lib/h264-decoder';
+import { H264Decoder, type VideoCodec } from '../lib/h264-decoder';
 import { normalizeTopicPattern } from '../lib/subscription-manager';
 
 // Local interface for camera metadata display
@@ -32,6 +32,21 @@ function toBigInt(value: unknown): bigint {
   return 0n;
 }
 
+// Map a CompressedImage format to a decoder codec.
+// An empty format is treated as H264, the camera node's default.
+function codecForFormat(format: string): VideoCodec | null {
+  switch (format.toLowerCase()) {
+    case '':
+    case 'h264':
+      return 'h264';
+    case 'h265':
+    case 'hevc':
+      return 'h265';
+    default:
+      return null;
+  }
+}
+
 interface DragHandleProps {
   [key: string]: unknown;
 }
@@ -169,11 +184,13 @@ export function CameraView({
       // Store latest metadata in ref (no re-render)
       lastMetaRef.current = { header, format, dataSize: data.length };
 
-      // Skip non-h264 formats (but allow empty format in case field is missing)
-      if (format && format !== 'h264') {
+      // Skip formats the decoder can't handle (empty format means h264)
+      const codec = codecForFormat(format);
+      if (!codec) {
         console.warn(`[CameraView] Unexpected format: ${format}`);
         return;
       }
+      decoder.setCodec(codec);
 
       // Use pub_time as timestamp (convert from nanoseconds to microseconds)
       const timestamp = header
@@ -24,6 +24,8 @@ vi.mock('../../lib/h264-decoder', () => {
 
     close() {}
 
+    setCodec() {}
+
     decode() {
       return Promise.resolve();
     }
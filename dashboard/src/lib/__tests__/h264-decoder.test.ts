@@ -0,0 +1,30 @@
+import { describe, it, expect } from 'vitest';
+import { hevcCodecString } from '../h264-decoder';
+
+describe('hevcCodecString', () => {
+  it('reads Main profile, main tier, level 3.1', () => {
+    // NAL header, VPS id / sub-layers, then profile_tier_level with
+    // emulation prevention bytes (0x03) that must be skipped.
+    const sps = Uint8Array.from([
+      0x42, 0x01, 0x01,
+      0x01, 0x60, 0x00, 0x00, 0x03, 0x00,
+      0xb0, 0x00, 0x00, 0x03, 0x00, 0x00, 0x03, 0x00,
+      0x5d, 0xa0, 0x02,
+    ]);
+    expect(hevcCodecString(sps)).toBe('hev1.1.6.L93.B0');
+  });
+
+  it('reads Main 10 profile, high tier', () => {
+    const sps = Uint8Array.from([
+      0x42, 0x01, 0x01,
+      0x22, 0x20, 0x00, 0x00, 0x03, 0x00,
+      0xb0, 0x00, 0x00, 0x03, 0x00, 0x00, 0x03, 0x00,
+      0x99,
+    ]);
+    expect(hevcCodecString(sps)).toBe('hev1.2.4.H153.B0');
+  });
+
+  it('returns null for a truncated SPS', () => {
+    expect(hevcCodecString(Uint8Array.from([0x42, 0x01, 0x01]))).toBeNull();
+  });
+});
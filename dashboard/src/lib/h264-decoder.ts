@@ -1,29 +1,88 @@
 /**
- * H264 video decoder using WebCodecs API
+ * H264 / H265 video decoder using WebCodecs API
  *
  * WebCodecs provides hardware-accelerated video decoding directly in the browser.
- * Decodes H264 NAL units frame-by-frame and renders to canvas.
+ * Decodes H264 (default) or H265/HEVC NAL units frame-by-frame and renders to canvas.
  *
  * Supports both Annex B format (start codes, from GStreamer) and
- * AVCC format (length-prefixed, from MP4/RTSP extractors).
+ * AVCC/HVCC format (length-prefixed, from MP4/RTSP extractors).
  * Format is auto-detected from the first keyframe.
  */
 
 export type FrameCallback = (frame: VideoFrame) => void;
 
+export type VideoCodec = 'h264' | 'h265';
+
 export interface H264DecoderOptions {
   onFrame: FrameCallback;
   onError?: (error: Error) => void;
 }
 
 interface SPSInfo {
-  profileIdc: number;
-  constraintFlags: number;
-  levelIdc: number;
+  /** WebCodecs codec string, e.g. "avc1.42e01f" or "hev1.1.6.L93.B0" */
+  codec: string;
   spsData: Uint8Array;
   ppsData?: Uint8Array;
 }
 
+// H265 NAL unit types
+const HEVC_NAL_IRAP_FIRST = 16; // BLA_W_LP
+const HEVC_NAL_IRAP_LAST = 23; // RSV_IRAP_VCL23
+const HEVC_NAL_SPS = 33;
+const HEVC_NAL_PPS = 34;
+
+/**
+ * Strip emulation prevention bytes (0x00 0x00 0x03 -> 0x00 0x00).
+ */
+function unescapeRbsp(nal: Uint8Array): Uint8Array {
+  const out: number[] = [];
+  let zeros = 0;
+  for (const byte of nal) {
+    if (zeros >= 2 && byte === 3) {
+      zeros = 0;
+      continue;
+    }
+    zeros = byte === 0 ? zeros + 1 : 0;
+    out.push(byte);
+  }
+  return new Uint8Array(out);
+}
+
+/**
+ * WebCodecs codec string for an H265 SPS NAL unit (ISO/IEC 14496-15 E.3),
+ * read from its general profile_tier_level. Returns null if the SPS is too short.
+ */
+export function hevcCodecString(sps: Uint8Array): string | null {
+  const rbsp = unescapeRbsp(sps);
+  // 2-byte NAL header, 1 byte of VPS id / sub-layers, then 12 bytes of
+  // general profile_tier_level.
+  if (rbsp.length < 15) return null;
+
+  const profileSpace = rbsp[3] >> 6;
+  const tier = (rbsp[3] >> 5) & 1;
+  const profileIdc = rbsp[3] & 0x1f;
+  const compat = ((rbsp[4] << 24) | (rbsp[5] << 16) | (rbsp[6] << 8) | rbsp[7]) >>> 0;
+  // Compatibility flags are written in reverse bit order
+  let reversed = 0;
+  for (let i = 0; i < 32; i++) {
+    reversed = ((reversed << 1) | ((compat >>> i) & 1)) >>> 0;
+  }
+  const constraints = Array.from(rbsp.slice(8, 14));
+  while (constraints.length > 0 && constraints[constraints.length - 1] === 0) {
+    constraints.pop();
+  }
+  const levelIdc = rbsp[14];
+
+  const space = profileSpace === 0 ? '' : String.fromCharCode(64 + profileSpace);
+  return [
+    'hev1',
+    `${space}${profileIdc}`,
+    reversed.toString(16),
+    `${tier ? 'H' : 'L'}${levelIdc}`,
+    ...constraints.map(b => b.toString(16).toUpperCase()),
+  ].join('.');
+}
+
 type NalFormat = 'unknown' | 'annexb' | 'avcc';
 
 interface NalUnit {
@@ -35,6 +94,7 @@ interface NalUnit {
 export class H264Decoder {
   private decoder: VideoDecoder | null = null;
   private options: H264DecoderOptions;
+  private codec: VideoCodec = 'h264';
   private initialized = false;
   private waitingForKeyframe = true;
   private spsInfo: SPSInfo | null = null;
@@ -55,6 +115,29 @@ export class H264Decoder {
     return typeof VideoDecoder !== 'undefined';
   }
 
+  /**
+   * Switch codec. Resets the decoder and waits for the next keyframe
+   * when the codec changes.
+   */
+  setCodec(codec: VideoCodec): void {
+    if (codec === this.codec) return;
+    console.log(`[H264Decoder] Switching codec to ${codec}`);
+    this.codec = codec;
+    this.spsInfo = null;
+    this.nalFormat = 'unknown';
+    this.waitingForKeyframe = true;
+    if (this.decoder?.state === 'configured') {
+      this.decoder.reset();
+    }
+  }
+
+  /**
+   * NAL unit type from the first NAL header byte.
+   */
+  private nalType(header: number): number {
+    return this.codec === 'h265' ? (header >> 1) & 0x3f : header & 0x1f;
+  }
+
   /**
    * Detect whether data is Annex B (start codes) or AVCC (length-prefixed).
    */
@@ -74,8 +157,9 @@ export class H264Decoder {
       // Verify the NAL header byte after the length looks valid
       const nalHeader = data[4];
       const forbiddenBit = (nalHeader >> 7) & 1;
-      const nalType = nalHeader & 0x1f;
-      if (forbiddenBit === 0 && nalType > 0 && nalType <= 23) {
+      const nalType = this.nalType(nalHeader);
+      const maxType = this.codec === 'h265' ? 40 : 23;
+      if (forbiddenBit === 0 && (nalType > 0 || this.codec === 'h265') && nalType <= maxType) {
         return 'avcc';
       }
     }
@@ -111,7 +195,7 @@ export class H264Decoder {
             }
           }
 
-          const nalType = data[nalStart] & 0x1f;
+          const nalType = this.nalType(data[nalStart]);
           nalUnits.push({
             type: nalType,
             data: data.slice(nalStart, nalEnd),
@@ -145,7 +229,7 @@ export class H264Decoder {
 
       const nalStart = i + 4;
       const nalEnd = nalStart + nalLen;
-      const nalType = data[nalStart] & 0x1f;
+      const nalType = this.nalType(data[nalStart]);
 
       nalUnits.push({
         type: nalType,
@@ -248,28 +332,31 @@ export class H264Decoder {
   private extractParameterSets(data: Uint8Array): SPSInfo | null {
     const nalUnits = this.parseNalUnits(data);
 
+    const spsType = this.codec === 'h265' ? HEVC_NAL_SPS : 7;
+    const ppsType = this.codec === 'h265' ? HEVC_NAL_PPS : 8;
+
     let sps: Uint8Array | null = null;
     let pps: Uint8Array | null = null;
 
     for (const nal of nalUnits) {
-      if (nal.type === 7 && !sps) { // SPS
+      if (nal.type === spsType && !sps) {
         sps = nal.data;
-      } else if (nal.type === 8 && !pps) { // PPS
+      } else if (nal.type === ppsType && !pps) {
         pps = nal.data;
       }
     }
 
-    if (sps && sps.length >= 4) {
-      return {
-        profileIdc: sps[1],
-        constraintFlags: sps[2],
-        levelIdc: sps[3],
-        spsData: sps,
-        ppsData: pps || undefined,
-      };
+    if (!sps) return null;
+
+    let codec: string | null = null;
+    if (this.codec === 'h265') {
+      codec = hevcCodecString(sps);
+    } else if (sps.length >= 4) {
+      // profile_idc, constraint flags, level_idc
+      codec = `avc1.${[sps[1], sps[2], sps[3]].map(b => b.toString(16).padStart(2, '0')).join('')}`;
     }
 
-    return null;
+    return codec ? { codec, spsData: sps, ppsData: pps || undefined } : null;
   }
 
   /**
@@ -325,7 +412,7 @@ export class H264Decoder {
       return false;
     }
 
-    const codec = `avc1.${spsInfo.profileIdc.toString(16).padStart(2, '0')}${spsInfo.constraintFlags.toString(16).padStart(2, '0')}${spsInfo.levelIdc.toString(16).padStart(2, '0')}`;
+    const codec = spsInfo.codec;
     console.log(`[H264Decoder] Configuring with codec: ${codec}`);
 
     // Configure WITHOUT description for Annex B format data
@@ -361,6 +448,13 @@ export class H264Decoder {
    */
   private containsKeyframe(data: Uint8Array): boolean {
     const nalUnits = this.parseNalUnits(data);
+    if (this.codec === 'h265') {
+      // IRAP pictures (BLA, IDR, CRA) are random access points; the SPS
+      // is accepted too, as for H264 below.
+      return nalUnits.some(nal =>
+        nal.type === HEVC_NAL_SPS ||
+        (nal.type >= HEVC_NAL_IRAP_FIRST && nal.type <= HEVC_NAL_IRAP_LAST));
+    }
     // IDR slice (type 5) is the standard keyframe indicator.
     // Some cameras (e.g., Tapo) send SPS+PPS with non-IDR (type 1) — treat
     // the presence of SPS (7) as a sync point since the decoder can initialize from it.
@@ -394,8 +488,8 @@ export class H264Decoder {
   }
 
   /**
-   * Decode H264 data
-   * @param data - H264 NAL units in Annex B or AVCC format (auto-detected)
+   * Decode H264 or H265 data (see setCodec)
+   * @param data - NAL units in Annex B or AVCC format (auto-detected)
    * @param timestamp - Timestamp in microseconds
    */
   async decode(data: Uint8Array, timestamp: number): Promise<void> {
@@ -431,7 +525,10 @@ export class H264Decoder {
         // Log NAL types for debugging
         const nalUnits = this.parseNalUnits(data);
         const nalTypes = nalUnits.map(n => n.type);
-        console.log(`[H264Decoder] NAL types in keyframe: ${nalTypes.join(', ')} (7=SPS, 8=PPS, 5=IDR, 9=AUD, 6=SEI)`);
+        const legend = this.codec === 'h265'
+          ? '32=VPS, 33=SPS, 34=PPS, 19/20=IDR, 21=CRA, 39=SEI'
+          : '7=SPS, 8=PPS, 5=IDR, 9=AUD, 6=SEI';
+        console.log(`[H264Decoder] NAL types in keyframe: ${nalTypes.join(', ')} (${legend})`);
 
         const configured = await this.configureDecoder(spsInfo);
         if (!configured) {
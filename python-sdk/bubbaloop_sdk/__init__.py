@@ -1,12 +1,13 @@
 """Bubbaloop Node SDK for Python.
 
 Synchronous SDK — no asyncio required. Wraps zenoh-python with health
-heartbeat, config loading, and shutdown handling.
+heartbeat, diagnostics, config loading, and shutdown handling.
 
     pip install git+https://github.com/kornia/bubbaloop.git#subdirectory=python-sdk
 """
 
 from .context import NodeContext
+from .diagnostics import DIAGNOSTICS_SCHEMA_URI, DiagnosticLevel, DiagnosticStatus
 from .discover import NodeInfo, discover_nodes
 from .get_sample import GetSampleTimeout, get_sample
 from .manifest import (
@@ -22,6 +23,9 @@ from .node import run_node
 __all__ = [
     "CborPublisher",
     "CborSubscriber",
+    "DIAGNOSTICS_SCHEMA_URI",
+    "DiagnosticLevel",
+    "DiagnosticStatus",
     "Envelope",
     "GetSampleTimeout",
     "JsonPublisher",
@@ -102,6 +102,8 @@ class NodeContext:
         self._io_lock = threading.Lock()
         self._outputs: dict[str, _Liveness] = {}
         self._inputs: dict[str, _Liveness] = {}
+        # Statuses reported via report_diagnostic(), keyed by name.
+        self._diagnostics: dict = {}
         for sig in (signal.SIGINT, signal.SIGTERM):
             signal.signal(sig, lambda s, f: self._shutdown.set())
 
@@ -216,6 +218,8 @@ class NodeContext:
             self._outputs = {}
         if not hasattr(self, "_inputs") or isinstance(self._inputs, list):
             self._inputs = {}
+        if not hasattr(self, "_diagnostics"):
+            self._diagnostics = {}
 
     def _declare_output(self, key: str) -> str | None:
         """Register ``key`` as an output. Returns the stripped suffix, or
@@ -299,6 +303,32 @@ class NodeContext:
         with self._io_lock:
             return [v.to_dict(k) for k, v in self._inputs.items()]
 
+    # ------------------------------------------------------------------
+    # Diagnostics
+    # ------------------------------------------------------------------
+
+    def report_diagnostic(self, status) -> None:
+        """Report the state of one component of this node.
+
+        Replaces any earlier status with the same ``name``; it is republished
+        on every diagnostics tick until cleared.
+        """
+        self._ensure_io_state()
+        with self._io_lock:
+            self._diagnostics[status.name] = status
+
+    def clear_diagnostic(self, name: str) -> None:
+        """Stop reporting the status called ``name``."""
+        self._ensure_io_state()
+        with self._io_lock:
+            self._diagnostics.pop(name, None)
+
+    def diagnostics_snapshot(self) -> list:
+        """Node-reported :class:`DiagnosticStatus` entries in name order."""
+        self._ensure_io_state()
+        with self._io_lock:
+            return [self._diagnostics[k] for k in sorted(self._diagnostics)]
+
     # ------------------------------------------------------------------
     # Shutdown
     # ------------------------------------------------------------------
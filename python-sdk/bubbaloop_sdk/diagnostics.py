@@ -0,0 +1,117 @@
+"""Node self-diagnostics, mirroring ROS ``diagnostic_msgs``.
+
+Every node publishes a ``DiagnosticArray`` every 5 seconds on
+``bubbaloop/global/{machine_id}/{instance_name}/diagnostics`` as a CBOR
+envelope with schema URI :data:`DIAGNOSTICS_SCHEMA_URI`. The body carries the
+fields of ``bubbaloop.diagnostics.v1.DiagnosticArray``::
+
+    {"status": [{"level": 0, "name": "cam: sdk", "message": "running",
+                 "hardware_id": "", "values": [{"key": ..., "value": ...}]}]}
+
+The SDK always reports a ``"{instance_name}: sdk"`` status; nodes add their
+own with :meth:`NodeContext.report_diagnostic`. Same wire format as the Rust
+SDK's ``bubbaloop_node::diagnostics``.
+"""
+
+import enum
+import logging
+import threading
+import time
+from dataclasses import dataclass, field
+
+log = logging.getLogger(__name__)
+
+DIAGNOSTICS_SCHEMA_URI = "bubbaloop.diagnostics.v1.DiagnosticArray"
+DIAGNOSTICS_INTERVAL_SECS = 5.0
+
+
+class DiagnosticLevel(enum.IntEnum):
+    """Severity of a :class:`DiagnosticStatus`. Values match ROS."""
+
+    OK = 0
+    WARN = 1
+    ERROR = 2
+    STALE = 3
+
+
+@dataclass
+class DiagnosticStatus:
+    """State of one component of a node."""
+
+    level: DiagnosticLevel
+    name: str
+    message: str
+    hardware_id: str = ""
+    values: dict[str, str] = field(default_factory=dict)
+
+    def with_value(self, key: str, value) -> "DiagnosticStatus":
+        self.values[key] = str(value)
+        return self
+
+    def to_dict(self) -> dict:
+        return {
+            "level": int(self.level),
+            "name": self.name,
+            "message": self.message,
+            "hardware_id": self.hardware_id,
+            "values": [{"key": k, "value": v} for k, v in self.values.items()],
+        }
+
+
+def sdk_status(
+    instance_name: str,
+    uptime_secs: float,
+    outputs: list[dict],
+    inputs: list[dict],
+) -> DiagnosticStatus:
+    """The status the SDK reports on behalf of every node.
+
+    ``outputs`` / ``inputs`` are :meth:`NodeContext.outputs_snapshot` entries.
+    """
+    def live(entries):
+        return sum(1 for e in entries if e["still_live"])
+
+    def idle(entries):
+        return sum(1 for e in entries if e["still_live"] and not e["ever_fired"])
+
+    return (
+        DiagnosticStatus(DiagnosticLevel.OK, f"{instance_name}: sdk", "running")
+        .with_value("uptime_secs", int(uptime_secs))
+        .with_value("outputs", live(outputs))
+        .with_value("outputs_idle", idle(outputs))
+        .with_value("inputs", live(inputs))
+        .with_value("inputs_idle", idle(inputs))
+    )
+
+
+def build_diagnostics(ctx, started_at: float) -> dict:
+    """SDK status first, then node-reported statuses in name order."""
+    sdk = sdk_status(
+        ctx.instance_name or "",
+        time.monotonic() - started_at,
+        ctx.outputs_snapshot(),
+        ctx.inputs_snapshot(),
+    )
+    return {"status": [sdk.to_dict()] + [s.to_dict() for s in ctx.diagnostics_snapshot()]}
+
+
+def start_diagnostics(ctx, interval_secs: float = DIAGNOSTICS_INTERVAL_SECS) -> threading.Thread:
+    """Publish ``build_diagnostics(ctx)`` on ``ctx.topic("diagnostics")`` every ``interval_secs``.
+
+    Returns the daemon thread (already started). Stops when the context shuts down.
+    """
+    pub = ctx.publisher_cbor("diagnostics", schema_uri=DIAGNOSTICS_SCHEMA_URI)
+    started_at = time.monotonic()
+
+    def _loop():
+        while True:
+            try:
+                pub.put(build_diagnostics(ctx, started_at))
+            except Exception as e:
+                log.warning("Diagnostics publish failed: %s", e)
+            if ctx._shutdown.wait(timeout=interval_secs):
+                break
+
+    t = threading.Thread(target=_loop, daemon=True, name=f"diagnostics-{ctx.instance_name}")
+    t.start()
+    return t
@@ -1,7 +1,8 @@
 """run_node() — SDK entry point for Python nodes.
 
 Mirrors the Rust SDK's run_node::<N>() function. Handles CLI args,
-config loading, Zenoh connection, health heartbeat, and shutdown.
+config loading, Zenoh connection, health heartbeat, diagnostics, and
+shutdown.
 
 Usage::
 
@@ -21,6 +22,7 @@ import time
 import yaml
 
 from .context import NodeContext
+from .diagnostics import start_diagnostics
 from .health import start_health_heartbeat
 from .manifest import start_manifest_queryable
 
@@ -66,6 +68,9 @@ def run_node(node_class) -> None:
         ctx, role=role, started_at_ns=started_at_ns, node_kind="python"
     )
 
+    start_diagnostics(ctx)
+    log.info("Diagnostics: %s", ctx.topic("diagnostics"))
+
     node = node_class(ctx, config)
     log.info("Initialized. Running…")
     try:
@@ -581,6 +581,49 @@ def test_import_get_sample():
     assert GetSampleTimeout is not None
 
 
+# ---------------------------------------------------------------------------
+# Diagnostics
+# ---------------------------------------------------------------------------
+
+def test_report_diagnostic_replaces_by_name_and_clears():
+    from bubbaloop_sdk import DiagnosticLevel, DiagnosticStatus
+    ctx = _make_context("bot", instance_name="cam")
+    ctx.report_diagnostic(DiagnosticStatus(DiagnosticLevel.WARN, "cam: rtsp", "slow"))
+    ctx.report_diagnostic(DiagnosticStatus(DiagnosticLevel.ERROR, "cam: rtsp", "refused"))
+    ctx.report_diagnostic(DiagnosticStatus(DiagnosticLevel.OK, "cam: encoder", "ok"))
+    assert [s.name for s in ctx.diagnostics_snapshot()] == ["cam: encoder", "cam: rtsp"]
+    assert ctx.diagnostics_snapshot()[1].message == "refused"
+    ctx.clear_diagnostic("cam: rtsp")
+    ctx.clear_diagnostic("missing")
+    assert [s.name for s in ctx.diagnostics_snapshot()] == ["cam: encoder"]
+
+
+def test_build_diagnostics_matches_wire_format():
+    from bubbaloop_sdk import DiagnosticLevel, DiagnosticStatus
+    from bubbaloop_sdk.diagnostics import build_diagnostics
+    ctx = _make_context("bot", instance_name="cam")
+    ctx._declare_output("bubbaloop/global/bot/cam/raw")
+    ctx.report_diagnostic(
+        DiagnosticStatus(DiagnosticLevel.ERROR, "cam: rtsp", "refused", hardware_id="tapo")
+        .with_value("retries", 3)
+    )
+    status = build_diagnostics(ctx, started_at=0.0)["status"]
+    assert status[0]["name"] == "cam: sdk"
+    assert status[0]["level"] == 0
+    values = {kv["key"]: kv["value"] for kv in status[0]["values"]}
+    assert values["outputs"] == "1"
+    assert values["outputs_idle"] == "1"
+    assert status[1] == {
+        "level": 2,
+        "name": "cam: rtsp",
+        "message": "refused",
+        "hardware_id": "tapo",
+        "values": [{"key": "retries", "value": "3"}],
+    }
+    # Survives the CBOR round trip with plain ints, like the Rust SDK's u8 level.
+    assert cbor2.loads(cbor2.dumps(status))[1]["level"] == 2
+
+
 # ---------------------------------------------------------------------------
 # Helper
 # ---------------------------------------------------------------------------